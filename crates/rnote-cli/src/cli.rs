@@ -1,5 +1,5 @@
 // Imports
-use crate::{export, import, test};
+use crate::{export, import, test, validators};
 use anyhow::Context;
 use clap::Parser;
 use rnote_compose::SplitOrder;
@@ -33,7 +33,7 @@ pub(crate) enum Command {
         rnote_files: Vec<PathBuf>,
     },
     /// Imports the specified input file and saves it as a rnote save file.{n}
-    /// Currently only `.xopp` files can be imported.
+    /// Currently `.xopp` and Evernote `.enex` files can be imported.
     Import {
         /// The rnote save file.
         rnote_file: PathBuf,
@@ -52,6 +52,10 @@ pub(crate) enum Command {
         /// The rnote save file.
         #[arg(global = true)]
         rnote_files: Vec<PathBuf>,
+        /// Export all rnote files in the directory, in addition to the specified rnote files.{n}
+        /// Requires "--output-format" (or the "doc-pages" sub-command).
+        #[arg(long, global = true)]
+        input_dir: Option<PathBuf>,
         /// The action that will be performed if the to be exported file(s) already exist(s).
         #[arg(long, default_value = "ask", global = true)]
         on_conflict: OnConflict,
@@ -221,7 +225,8 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             println!("Import finished!");
         }
         Command::Export {
-            rnote_files,
+            mut rnote_files,
+            input_dir,
             no_background,
             no_pattern,
             optimize_printing,
@@ -229,6 +234,9 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             open,
             export_command,
         } => {
+            if let Some(input_dir) = input_dir {
+                rnote_files.extend(rnote_files_in_dir(&input_dir)?);
+            }
             println!("Exporting..");
             export::run_export(
                 rnote_files,
@@ -247,6 +255,13 @@ pub(crate) async fn run() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// All rnote files in the directory (not recursing into sub-directories), sorted by path.
+pub(crate) fn rnote_files_in_dir(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    validators::path_is_dir(dir)?;
+    rnote_engine::utils::rnote_files_in_dir(dir)
+        .with_context(|| format!("Reading directory \"{}\" failed.", dir.display()))
+}
+
 pub(crate) fn new_progressbar(message: String) -> indicatif::ProgressBar {
     let pb = indicatif::ProgressBar::new_spinner().with_message(message);
     pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
@@ -5,10 +5,10 @@ use anyhow::Context;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::SplitOrder;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, PageHeaderFooter,
     SelectionExportFormat, SelectionExportPrefs,
 };
-use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::engine::{EngineSnapshot, Progress};
 use rnote_engine::{Engine, SelectionCollision};
 use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
@@ -120,8 +120,13 @@ pub(crate) async fn run_export(
                 })
                 .collect::<Vec<PathBuf>>();
 
+            let mut n_failed = 0;
             for (rnote_file, output_file) in rnote_files.iter().zip(output_files.iter()) {
-                validators::file_has_ext(rnote_file, "rnote")?;
+                if let Err(e) = validators::file_has_ext(rnote_file, "rnote") {
+                    println!("Skipping \"{}\", Err: {e:?}", rnote_file.display());
+                    n_failed += 1;
+                    continue;
+                }
                 let output_file = match get_output_file_path(
                     output_file,
                     on_conflict,
@@ -163,7 +168,8 @@ pub(crate) async fn run_export(
                         println!("{abandon_msg}")
                     }
                     progressbar.abandon_with_message(abandon_msg);
-                    return Err(e);
+                    // Continue with the remaining files, the failures are reported at the end.
+                    n_failed += 1;
                 } else {
                     let finish_msg = match exporting_doc_pages {
                         false => format!(
@@ -177,6 +183,13 @@ pub(crate) async fn run_export(
                     progressbar.finish_with_message(finish_msg);
                 }
             }
+
+            if n_failed > 0 {
+                return Err(anyhow::anyhow!(
+                    "Export of {n_failed} of {} rnote files failed.",
+                    rnote_files.len()
+                ));
+            }
         }
     }
 
@@ -304,6 +317,7 @@ pub(crate) fn create_doc_export_prefs_from_args(
         with_pattern: !no_pattern,
         optimize_printing,
         page_order,
+        header_footer: PageHeaderFooter::default(),
     };
 
     Ok(prefs)
@@ -314,6 +328,7 @@ fn doc_export_format_from_ext_str(format: &str) -> anyhow::Result<DocExportForma
         "svg" => Ok(DocExportFormat::Svg),
         "xopp" => Ok(DocExportFormat::Xopp),
         "pdf" => Ok(DocExportFormat::Pdf),
+        "html" => Ok(DocExportFormat::Html),
         ext => Err(anyhow::anyhow!(
             "Exporting document to format with extension \"{ext}\" is not supported."
         )),
@@ -393,6 +408,8 @@ fn get_selection_export_format(format: &str) -> anyhow::Result<SelectionExportFo
         "svg" => Ok(SelectionExportFormat::Svg),
         "png" => Ok(SelectionExportFormat::Png),
         "jpg" | "jpeg" => Ok(SelectionExportFormat::Jpeg),
+        "html" => Ok(SelectionExportFormat::Html),
+        "eps" => Ok(SelectionExportFormat::Eps),
         ext => Err(anyhow::anyhow!(
             "Exporting selection to format with extension \"{ext}\" is not supported."
         )),
@@ -530,6 +547,11 @@ pub(crate) async fn export_to_file(
     let rnote_bytes = cli::read_bytes_from_file(&rnote_file).await?;
     let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await?;
     let _ = engine.load_snapshot(engine_snapshot);
+    // The document is exported on request, so its linked images are included
+    let (n_failed, _) = engine.load_linked_images();
+    if n_failed > 0 {
+        println!("{n_failed} linked images could not be loaded and are exported as broken links.");
+    }
 
     match export_command {
         cli::ExportCommand::Selection {
@@ -558,7 +580,9 @@ pub(crate) async fn export_to_file(
                     output_file.as_ref().display()
                 ));
             };
-            let export_bytes = engine.export_doc(export_file_name, None).await??;
+            let export_bytes = engine
+                .export_doc(export_file_name, None, None, Progress::default())
+                .await??;
             cli::create_overwrite_file_w_bytes(&output_file, &export_bytes).await?;
             if open {
                 cli::open_file_default_app(output_file)?;
@@ -574,7 +598,9 @@ pub(crate) async fn export_to_file(
             // The output file cannot be set with this subcommand
             drop(output_file);
 
-            let pages_export_bytes = engine.export_doc_pages(None).await??;
+            let pages_export_bytes = engine
+                .export_doc_pages(None, None, Progress::default())
+                .await??;
             let out_ext = output_format.file_ext();
             let output_file_stem = match output_file_stem {
                 Some(o) => o.clone(),
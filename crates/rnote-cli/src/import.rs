@@ -10,7 +10,7 @@ pub(crate) async fn run_import(
     xopp_dpi: f64,
 ) -> anyhow::Result<()> {
     validators::file_has_ext(rnote_file, "rnote")?;
-    // Xopp files don't require file extensions
+    // Xopp files don't require file extensions, Enex files are recognized by theirs
     validators::path_is_file(input_file)?;
 
     let mut engine = Engine::default();
@@ -59,9 +59,15 @@ pub(crate) async fn import_file(
         return Err(anyhow::anyhow!("Failed to get filename from rnote_file"));
     };
     let input_bytes = cli::read_bytes_from_file(&input_file).await?;
-    let snapshot =
+    let is_enex = input_file
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("enex"));
+    let snapshot = if is_enex {
+        EngineSnapshot::load_from_enex_bytes(input_bytes).await?
+    } else {
         EngineSnapshot::load_from_xopp_bytes(input_bytes, engine.import_prefs.xopp_import_prefs)
-            .await?;
+            .await?
+    };
     let _ = engine.load_snapshot(snapshot);
     let rnote_bytes = engine.save_as_rnote_bytes(rnote_file_name).await??;
     cli::create_overwrite_file_w_bytes(&rnote_file, &rnote_bytes).await?;
@@ -30,7 +30,14 @@ pub(crate) async fn run_test(rnote_files: &[PathBuf]) -> anyhow::Result<()> {
 
 pub(crate) async fn test_file(rnote_file: impl AsRef<Path>) -> anyhow::Result<()> {
     let rnote_bytes = cli::read_bytes_from_file(&rnote_file).await?;
-    let _ = EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await?;
+    let (_, dropped) = EngineSnapshot::load_from_rnote_bytes_w_recovery(rnote_bytes).await?;
+    // A file is only valid if it could be read without dropping anything.
+    if !dropped.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Parts of the file could not be read:\n{}",
+            dropped.join("\n")
+        ));
+    }
     // Loading a valid snapshot into the engine can't fail, so we skip it.
     Ok(())
 }
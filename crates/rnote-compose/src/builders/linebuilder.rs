@@ -45,7 +45,7 @@ impl Buildable for LineBuilder {
 
         let progress = match event {
             PenEvent::Down { element, .. } => {
-                self.current = constraints.constrain(element.pos - self.start) + self.start;
+                self.current = constraints.constrain_line(self.start, element.pos);
                 BuilderProgress::InProgress
             }
             PenEvent::Up { .. } => {
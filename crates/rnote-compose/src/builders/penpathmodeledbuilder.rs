@@ -173,10 +173,11 @@ impl PenPathModeledBuilder {
         };
 
         match self.stroke_modeler.update(modeler_input) {
+            // The modeler does not know about the tilt, so it is taken over from the input element
             Ok(results) => self.buffer.extend(results.into_iter().map(|r| {
                 let pos = r.pos;
                 let pressure = r.pressure;
-                Element::new(na::vector![pos.0, pos.1], pressure)
+                Element::new(na::vector![pos.0, pos.1], pressure).with_tilt(element.tilt)
             })),
             Err(e) => {
                 match e {
@@ -226,7 +227,7 @@ impl PenPathModeledBuilder {
                     .map(|r| {
                         let pos = r.pos;
                         let pressure = r.pressure;
-                        Element::new(na::vector![pos.0, pos.1], pressure)
+                        Element::new(na::vector![pos.0, pos.1], pressure).with_tilt(element.tilt)
                     })
                     .collect::<Vec<Element>>(),
                 Err(e) => {
@@ -258,7 +259,7 @@ impl PenPathModeledBuilder {
                 self.buffer.extend(results.into_iter().map(|r| {
                     let pos = r.pos;
                     let pressure = r.pressure;
-                    Element::new(na::vector![pos.0, pos.1], pressure)
+                    Element::new(na::vector![pos.0, pos.1], pressure).with_tilt(element.tilt)
                 }));
             }
             Err(e) => {
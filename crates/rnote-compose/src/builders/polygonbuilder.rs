@@ -150,6 +150,7 @@ impl PolygonBuilder {
         Polygon {
             start: self.start,
             path: self.path.clone(),
+            ..Default::default()
         }
     }
 
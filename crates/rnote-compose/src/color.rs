@@ -130,6 +130,28 @@ impl Color {
         0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
     }
 
+    /// The relative luminance as defined by WCAG, ranging [0.0 - 1.0]. Alpha is ignored.
+    ///
+    /// see: <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+    pub fn relative_luminance(&self) -> f64 {
+        let linearize = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linearize(self.r) + 0.7152 * linearize(self.g) + 0.0722 * linearize(self.b)
+    }
+
+    /// The contrast ratio to the other color as defined by WCAG, ranging [1.0 - 21.0]. Alpha is ignored.
+    ///
+    /// see: <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>
+    pub fn contrast_ratio(&self, other: Self) -> f64 {
+        let (l1, l2) = (self.relative_luminance(), other.relative_luminance());
+        (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+    }
+
     /// Invert the perceived brightness of the color.
     pub fn to_inverted_brightness_color(self) -> Self {
         let mut hwba_color: palette::Okhwba<f64> = self.into_color();
@@ -354,3 +376,26 @@ pub const GNOME_DARKS: [piet::Color; 5] = [
     piet::Color::rgb8(0x24, 0x1f, 0x31),
     piet::Color::rgb8(0x00, 0x00, 0x00),
 ];
+
+/// The Okabe-Ito palette, distinguishable with all common color vision deficiencies.
+pub const OKABE_ITO: [piet::Color; 8] = [
+    piet::Color::rgb8(0xe6, 0x9f, 0x00),
+    piet::Color::rgb8(0x56, 0xb4, 0xe9),
+    piet::Color::rgb8(0x00, 0x9e, 0x73),
+    piet::Color::rgb8(0xf0, 0xe4, 0x42),
+    piet::Color::rgb8(0x00, 0x72, 0xb2),
+    piet::Color::rgb8(0xd5, 0x5e, 0x00),
+    piet::Color::rgb8(0xcc, 0x79, 0xa7),
+    piet::Color::rgb8(0x00, 0x00, 0x00),
+];
+
+/// Paul Tol's bright palette, distinguishable with all common color vision deficiencies.
+pub const TOL_BRIGHT: [piet::Color; 7] = [
+    piet::Color::rgb8(0x44, 0x77, 0xaa),
+    piet::Color::rgb8(0xee, 0x66, 0x77),
+    piet::Color::rgb8(0x22, 0x88, 0x33),
+    piet::Color::rgb8(0xcc, 0xbb, 0x44),
+    piet::Color::rgb8(0x66, 0xcc, 0xee),
+    piet::Color::rgb8(0xaa, 0x33, 0x77),
+    piet::Color::rgb8(0xbb, 0xbb, 0xbb),
+];
@@ -12,6 +12,11 @@ pub struct Constraints {
     /// stores the constraint ratios
     #[serde(rename = "ratios")]
     pub ratios: HashSet<ConstraintRatio>,
+    /// Vanishing points in absolute coordinates. Lines are constrained toward the nearest one.
+    ///
+    /// Applied independently of `enabled`, as they are only set while perspective guides are active.
+    #[serde(skip)]
+    pub vanishing_points: Vec<na::Vector2<f64>>,
 }
 
 impl Constraints {
@@ -33,6 +38,27 @@ impl Constraints {
             .map(|(_d, p)| p)
             .unwrap_or(pos)
     }
+
+    /// Constrain the absolute position of the end of a line starting at `start`.
+    ///
+    /// Additionally to the constraint ratios, the line can point toward one of the vanishing points.
+    pub fn constrain_line(
+        &self,
+        start: na::Vector2<f64>,
+        pos: na::Vector2<f64>,
+    ) -> na::Vector2<f64> {
+        let constrained = self.constrain(pos - start) + start;
+        let vanishing_point_candidates = self.vanishing_points.iter().filter_map(|vp| {
+            let direction = (vp - start).try_normalize(f64::EPSILON)?;
+            Some(start + direction * (pos - start).dot(&direction))
+        });
+
+        std::iter::once(constrained)
+            .filter(|_| self.enabled && !self.ratios.is_empty())
+            .chain(vanishing_point_candidates)
+            .min_by(|a, b| (a - pos).norm().total_cmp(&(b - pos).norm()))
+            .unwrap_or(constrained)
+    }
 }
 
 /// A constraint ratio.
@@ -13,6 +13,15 @@ pub struct Element {
     #[serde(rename = "pressure", with = "crate::serialize::f64_dp3")]
     /// The pen pressure. The valid range is [0.0, 1.0].
     pub pressure: f64,
+    #[serde(
+        rename = "tilt",
+        with = "crate::serialize::na_vector2_f64_dp3",
+        skip_serializing_if = "Element::tilt_is_upright"
+    )]
+    /// The pen tilt along the x- and y-axis. The valid range for each axis is [-1.0, 1.0].
+    ///
+    /// Is zero when the pen is upright or when the tilt could not be retrieved from the input.
+    pub tilt: na::Vector2<f64>,
 }
 
 impl Default for Element {
@@ -30,6 +39,7 @@ impl Transformable for Element {
         let mut isometry = na::Isometry2::identity();
         isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
         self.pos = isometry.transform_point(&self.pos.into()).coords;
+        self.tilt = na::Rotation2::new(angle) * self.tilt;
     }
 
     fn scale(&mut self, scale: na::Vector2<f64>) {
@@ -46,14 +56,42 @@ impl Element {
         Self {
             pos,
             pressure: pressure.clamp(0.0, 1.0),
+            tilt: na::Vector2::zeros(),
         }
     }
 
+    /// Returns the element with the given tilt, each axis clamped to the range [-1.0, 1.0].
+    pub fn with_tilt(mut self, tilt: na::Vector2<f64>) -> Self {
+        self.set_tilt_clamped(tilt);
+        self
+    }
+
     /// Sets the pressure, clamped to the range [0.0 - 1.0].
     pub fn set_pressure_clamped(&mut self, pressure: f64) {
         self.pressure = pressure.clamp(0.0, 1.0);
     }
 
+    /// Sets the tilt, each axis clamped to the range [-1.0, 1.0].
+    pub fn set_tilt_clamped(&mut self, tilt: na::Vector2<f64>) {
+        self.tilt = na::vector![tilt[0].clamp(-1.0, 1.0), tilt[1].clamp(-1.0, 1.0)];
+    }
+
+    /// How far the pen is tilted away from being upright. The range is [0.0, 1.0].
+    pub fn tilt_amount(&self) -> f64 {
+        self.tilt.magnitude().min(1.0)
+    }
+
+    /// The direction the pen is tilted towards, as angle in radians measured from the positive x-axis.
+    ///
+    /// Is zero when the pen is upright.
+    pub fn tilt_azimuth(&self) -> f64 {
+        self.tilt[1].atan2(self.tilt[0])
+    }
+
+    fn tilt_is_upright(tilt: &na::Vector2<f64>) -> bool {
+        *tilt == na::Vector2::zeros()
+    }
+
     /// Indicates if a element is out of valid bounds and should be filtered out.
     ///
     /// Returns true if element pos is not inside the bounds.
@@ -25,11 +25,11 @@ pub use arrow::Arrow;
 pub use cubbez::CubicBezier;
 pub use ellipse::Ellipse;
 pub use line::Line;
-pub use polygon::Polygon;
+pub use polygon::{FillRule, Polygon};
 pub use polyline::Polyline;
 pub use quadbez::QuadraticBezier;
 pub use rectangle::Rectangle;
-pub use shape::Shape;
+pub use shape::{Shape, ShapeNode, ShapeNodeKind};
 pub use shapeable::Shapeable;
 
 /// Calculate the number hitbox elems for the given length ( e.g. length of a line, curve, etc.).
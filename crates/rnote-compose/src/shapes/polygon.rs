@@ -5,6 +5,19 @@ use crate::transform::Transformable;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "fill_rule")]
+/// Decides which areas enclosed by the sub-paths of a polygon are filled.
+pub enum FillRule {
+    /// Areas that the sub-paths wind around a non-zero number of times are filled.
+    #[default]
+    #[serde(rename = "nonzero")]
+    NonZero,
+    /// Areas that are enclosed by an odd number of sub-paths are filled.
+    #[serde(rename = "evenodd")]
+    EvenOdd,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default, rename = "polygon")]
 /// A Polygon.
@@ -15,12 +28,17 @@ pub struct Polygon {
     /// The polygon path
     #[serde(rename = "path")]
     pub path: Vec<na::Vector2<f64>>,
+    /// Further closed sub-paths of a compound polygon, for example its holes.
+    #[serde(rename = "subpaths")]
+    pub subpaths: Vec<Vec<na::Vector2<f64>>>,
+    /// The fill rule for compound polygons.
+    #[serde(rename = "fill_rule")]
+    pub fill_rule: FillRule,
 }
 
 impl Transformable for Polygon {
     fn translate(&mut self, offset: na::Vector2<f64>) {
-        self.start += offset;
-        for p in &mut self.path {
+        for p in self.points_mut() {
             *p += offset;
         }
     }
@@ -29,15 +47,13 @@ impl Transformable for Polygon {
         let mut isometry = na::Isometry2::identity();
         isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
 
-        self.start = isometry.transform_point(&self.start.into()).coords;
-        for p in &mut self.path {
+        for p in self.points_mut() {
             *p = isometry.transform_point(&(*p).into()).coords;
         }
     }
 
     fn scale(&mut self, scale: na::Vector2<f64>) {
-        self.start = self.start.component_mul(&scale);
-        for p in &mut self.path {
+        for p in self.points_mut() {
             *p = p.component_mul(&scale);
         }
     }
@@ -46,7 +62,7 @@ impl Transformable for Polygon {
 impl Shapeable for Polygon {
     fn bounds(&self) -> Aabb {
         let mut bounds = Aabb::new(self.start.into(), self.start.into());
-        for p in &self.path {
+        for p in self.path.iter().chain(self.subpaths.iter().flatten()) {
             bounds.take_point((*p).into());
         }
         bounds
@@ -67,6 +83,20 @@ impl Shapeable for Polygon {
         }
         hitboxes.push(Aabb::new_positive(prev.into(), self.start.into()));
 
+        for subpath in self.subpaths.iter().filter(|subpath| !subpath.is_empty()) {
+            let n_points = subpath.len();
+            for (i, p) in subpath.iter().enumerate() {
+                let next = subpath[(i + 1) % n_points];
+                let n_splits = super::hitbox_elems_for_shape_len((next - p).magnitude());
+                hitboxes.extend(
+                    Line::new(*p, next)
+                        .split(n_splits)
+                        .into_iter()
+                        .map(|line| line.bounds()),
+                );
+            }
+        }
+
         hitboxes
     }
 
@@ -78,6 +108,16 @@ impl Shapeable for Polygon {
         );
         let mut path = kurbo::BezPath::from_iter(iter);
         path.close_path();
+        for subpath in self.subpaths.iter() {
+            let Some((first, rest)) = subpath.split_first() else {
+                continue;
+            };
+            path.move_to(first.to_kurbo_point());
+            for p in rest {
+                path.line_to(p.to_kurbo_point());
+            }
+            path.close_path();
+        }
         path
     }
 }
@@ -87,9 +127,15 @@ impl Polygon {
     pub fn new(start: na::Vector2<f64>) -> Self {
         Self {
             start,
-            path: Vec::new(),
+            ..Default::default()
         }
     }
+
+    fn points_mut(&mut self) -> impl Iterator<Item = &mut na::Vector2<f64>> {
+        std::iter::once(&mut self.start)
+            .chain(self.path.iter_mut())
+            .chain(self.subpaths.iter_mut().flatten())
+    }
 }
 
 impl Extend<na::Vector2<f64>> for Polygon {
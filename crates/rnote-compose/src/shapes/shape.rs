@@ -1,7 +1,10 @@
 // Imports
+use super::cubbez::cubbez_calc;
+use super::quadbez::quadbez_calc;
 use super::{
     Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
 };
+use crate::ext::Vector2Ext;
 use crate::transform::Transformable;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
@@ -171,3 +174,364 @@ impl Shapeable for Shape {
         }
     }
 }
+
+/// The kind of a shape node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeNodeKind {
+    /// A node the shape passes through.
+    Anchor,
+    /// A control point of a bezier curve.
+    Control,
+}
+
+/// An editable node of a shape.
+#[derive(Debug, Clone, Copy)]
+pub struct ShapeNode {
+    /// The position of the node.
+    pub pos: na::Vector2<f64>,
+    /// The kind of the node.
+    pub kind: ShapeNodeKind,
+}
+
+impl ShapeNode {
+    fn anchor(pos: na::Vector2<f64>) -> Self {
+        Self {
+            pos,
+            kind: ShapeNodeKind::Anchor,
+        }
+    }
+
+    fn control(pos: na::Vector2<f64>) -> Self {
+        Self {
+            pos,
+            kind: ShapeNodeKind::Control,
+        }
+    }
+}
+
+impl Shape {
+    /// The number of lines each half of a cubic bezier curve is flattened into, when a node is inserted into it.
+    const CURVE_FLATTEN_SPLITS: i32 = 4;
+
+    /// The editable nodes of the shape.
+    ///
+    /// Ellipses don't have any.
+    pub fn nodes(&self) -> Vec<ShapeNode> {
+        match self {
+            Self::Line(line) => vec![ShapeNode::anchor(line.start), ShapeNode::anchor(line.end)],
+            Self::Arrow(arrow) => {
+                vec![ShapeNode::anchor(arrow.start), ShapeNode::anchor(arrow.tip)]
+            }
+            Self::Rectangle(rectangle) => rectangle_corners(rectangle)
+                .into_iter()
+                .map(ShapeNode::anchor)
+                .collect(),
+            Self::Ellipse(_) => vec![],
+            Self::QuadraticBezier(quadbez) => vec![
+                ShapeNode::anchor(quadbez.start),
+                ShapeNode::control(quadbez.cp),
+                ShapeNode::anchor(quadbez.end),
+            ],
+            Self::CubicBezier(cubbez) => vec![
+                ShapeNode::anchor(cubbez.start),
+                ShapeNode::control(cubbez.cp1),
+                ShapeNode::control(cubbez.cp2),
+                ShapeNode::anchor(cubbez.end),
+            ],
+            Self::Polyline(polyline) => std::iter::once(polyline.start)
+                .chain(polyline.path.iter().copied())
+                .map(ShapeNode::anchor)
+                .collect(),
+            Self::Polygon(polygon) => std::iter::once(polygon.start)
+                .chain(polygon.path.iter().copied())
+                .map(ShapeNode::anchor)
+                .collect(),
+        }
+    }
+
+    /// Move the node at the given index to a new position.
+    ///
+    /// Rectangles are converted to polygons, because their corners can't be moved independently.
+    pub fn move_node(&mut self, index: usize, pos: na::Vector2<f64>) {
+        if let Self::Rectangle(rectangle) = self {
+            *self = Self::Polygon(rectangle_to_polygon(rectangle));
+        }
+        let node = match self {
+            Self::Line(line) => match index {
+                0 => Some(&mut line.start),
+                1 => Some(&mut line.end),
+                _ => None,
+            },
+            Self::Arrow(arrow) => match index {
+                0 => Some(&mut arrow.start),
+                1 => Some(&mut arrow.tip),
+                _ => None,
+            },
+            Self::Rectangle(_) | Self::Ellipse(_) => None,
+            Self::QuadraticBezier(quadbez) => match index {
+                0 => Some(&mut quadbez.start),
+                1 => Some(&mut quadbez.cp),
+                2 => Some(&mut quadbez.end),
+                _ => None,
+            },
+            Self::CubicBezier(cubbez) => match index {
+                0 => Some(&mut cubbez.start),
+                1 => Some(&mut cubbez.cp1),
+                2 => Some(&mut cubbez.cp2),
+                3 => Some(&mut cubbez.end),
+                _ => None,
+            },
+            Self::Polyline(Polyline { start, path })
+            | Self::Polygon(Polygon { start, path, .. }) => match index {
+                0 => Some(start),
+                i => path.get_mut(i - 1),
+            },
+        };
+        if let Some(node) = node {
+            *node = pos;
+        }
+    }
+
+    /// Insert a new node on the segment closest to the given position,
+    /// if it is not further away than the tolerance.
+    ///
+    /// Lines and arrows are converted to polylines and rectangles to polygons.
+    /// Quadratic bezier curves are raised to the identical cubic bezier curve, which has a second control point.
+    /// Cubic bezier curves can't have more nodes, so they are flattened into a polyline
+    /// with the new anchor node where they were split.
+    /// Returns the index of the new node.
+    pub fn insert_node(&mut self, pos: na::Vector2<f64>, tolerance: f64) -> Option<usize> {
+        let closed = matches!(self, Self::Polygon(_));
+        match self {
+            Self::Line(Line { start, end }) | Self::Arrow(Arrow { start, tip: end }) => {
+                let (closest, dist) = closest_on_segment(*start, *end, pos);
+                if dist > tolerance {
+                    return None;
+                }
+                *self = Self::Polyline(Polyline {
+                    start: *start,
+                    path: vec![closest, *end],
+                });
+                Some(1)
+            }
+            Self::QuadraticBezier(quadbez) => {
+                let (t, dist) = closest_on_curve(
+                    |t| quadbez_calc(quadbez.start, quadbez.cp, quadbez.end, t),
+                    pos,
+                );
+                if dist > tolerance {
+                    return None;
+                }
+                *self = Self::CubicBezier(quadbez.to_cubic_bezier());
+                // the control point on the side of the curve that was clicked
+                Some(if t < 0.5 { 1 } else { 2 })
+            }
+            Self::CubicBezier(cubbez) => {
+                let (t, dist) = closest_on_curve(
+                    |t| cubbez_calc(cubbez.start, cubbez.cp1, cubbez.cp2, cubbez.end, t),
+                    pos,
+                );
+                if dist > tolerance {
+                    return None;
+                }
+                let (first, second) = cubbez.split(t);
+                *self = Self::Polyline(Polyline {
+                    start: cubbez.start,
+                    path: first
+                        .approx_with_lines(Self::CURVE_FLATTEN_SPLITS)
+                        .into_iter()
+                        .chain(second.approx_with_lines(Self::CURVE_FLATTEN_SPLITS))
+                        .map(|line| line.end)
+                        .collect(),
+                });
+                Some(Self::CURVE_FLATTEN_SPLITS as usize)
+            }
+            Self::Rectangle(rectangle) => {
+                let mut polygon = Self::Polygon(rectangle_to_polygon(rectangle));
+                let index = polygon.insert_node(pos, tolerance)?;
+                *self = polygon;
+                Some(index)
+            }
+            Self::Polyline(Polyline { start, path })
+            | Self::Polygon(Polygon { start, path, .. }) => {
+                let points = std::iter::once(*start)
+                    .chain(path.iter().copied())
+                    .collect::<Vec<na::Vector2<f64>>>();
+                let n_segments = if closed {
+                    points.len()
+                } else {
+                    points.len().saturating_sub(1)
+                };
+                let (segment, closest, dist) = (0..n_segments)
+                    .map(|i| {
+                        let (closest, dist) =
+                            closest_on_segment(points[i], points[(i + 1) % points.len()], pos);
+                        (i, closest, dist)
+                    })
+                    .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))?;
+                if dist > tolerance {
+                    return None;
+                }
+                // the path does not contain the start, so the index into it is the segment index
+                path.insert(segment, closest);
+                Some(segment + 1)
+            }
+            Self::Ellipse(_) => None,
+        }
+    }
+
+    /// Remove the node at the given index.
+    ///
+    /// Rectangles are converted to polygons.
+    /// Bezier curves are lowered by one order, with the remaining nodes taking the places of the removed one:
+    /// cubic bezier curves become quadratic ones, and quadratic bezier curves become lines.
+    /// Returns false when the node can't be removed, for example because the shape would become degenerate.
+    /// This is always the case for lines and arrows, which only consist of their two end nodes.
+    pub fn remove_node(&mut self, index: usize) -> bool {
+        match self {
+            Self::QuadraticBezier(QuadraticBezier { start, cp, end }) => {
+                let (start, end) = match index {
+                    0 => (*cp, *end),
+                    1 => (*start, *end),
+                    2 => (*start, *cp),
+                    _ => return false,
+                };
+                *self = Self::Line(Line { start, end });
+                return true;
+            }
+            Self::CubicBezier(CubicBezier {
+                start,
+                cp1,
+                cp2,
+                end,
+            }) => {
+                let (start, cp, end) = match index {
+                    0 => (*cp1, *cp2, *end),
+                    1 => (*start, *cp2, *end),
+                    2 => (*start, *cp1, *end),
+                    3 => (*start, *cp1, *cp2),
+                    _ => return false,
+                };
+                *self = Self::QuadraticBezier(QuadraticBezier { start, cp, end });
+                return true;
+            }
+            _ => {}
+        }
+        if let Self::Rectangle(rectangle) = self {
+            if index >= 4 {
+                return false;
+            }
+            *self = Self::Polygon(rectangle_to_polygon(rectangle));
+        }
+        let (start, path, min_nodes) = match self {
+            Self::Polyline(Polyline { start, path }) => (start, path, 2),
+            Self::Polygon(Polygon { start, path, .. }) => (start, path, 3),
+            _ => return false,
+        };
+        if path.len() < min_nodes || index > path.len() {
+            return false;
+        }
+        if index == 0 {
+            *start = path.remove(0);
+        } else {
+            path.remove(index - 1);
+        }
+        true
+    }
+
+    /// Toggle the anchor node at the given index between a sharp corner and a smooth curve.
+    ///
+    /// A corner has its control point collapsed onto the anchor, a smooth node has it extended.
+    /// Lines and quadratic bezier curves are converted to cubic bezier curves.
+    /// Returns false when the node can't be toggled.
+    pub fn toggle_node_smooth(&mut self, index: usize) -> bool {
+        let (mut cubbez, index) = match self {
+            Self::Line(line) => match index {
+                0 | 1 => (
+                    CubicBezier {
+                        start: line.start,
+                        cp1: line.start,
+                        cp2: line.end,
+                        end: line.end,
+                    },
+                    index * 3,
+                ),
+                _ => return false,
+            },
+            Self::QuadraticBezier(quadbez) => match index {
+                0 | 2 => (quadbez.to_cubic_bezier(), if index == 0 { 0 } else { 3 }),
+                _ => return false,
+            },
+            Self::CubicBezier(cubbez) => match index {
+                0 | 3 => (*cubbez, index),
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let (anchor, cp, other_anchor) = if index == 0 {
+            (cubbez.start, &mut cubbez.cp1, cubbez.end)
+        } else {
+            (cubbez.end, &mut cubbez.cp2, cubbez.start)
+        };
+        *cp = if cp.approx_eq(&anchor) {
+            anchor + (other_anchor - anchor) / 3.0
+        } else {
+            anchor
+        };
+        *self = Self::CubicBezier(cubbez);
+        true
+    }
+}
+
+/// The corners of the rectangle, in clockwise order starting from the upper left.
+fn rectangle_corners(rectangle: &Rectangle) -> [na::Vector2<f64>; 4] {
+    let half_extents = rectangle.cuboid.half_extents;
+    [
+        na::point![-half_extents[0], -half_extents[1]],
+        na::point![half_extents[0], -half_extents[1]],
+        na::point![half_extents[0], half_extents[1]],
+        na::point![-half_extents[0], half_extents[1]],
+    ]
+    .map(|corner| rectangle.transform.transform_point(corner).coords)
+}
+
+fn rectangle_to_polygon(rectangle: &Rectangle) -> Polygon {
+    let [first, rest @ ..] = rectangle_corners(rectangle);
+    Polygon {
+        start: first,
+        path: rest.to_vec(),
+        ..Default::default()
+    }
+}
+
+/// The closest point on the segment to the position and the distance to it.
+/// The parameter of the point on the curve closest to the given position, and the distance to it.
+///
+/// The curve is sampled at regular intervals, which is precise enough to pick a position on it.
+fn closest_on_curve(curve: impl Fn(f64) -> na::Vector2<f64>, pos: na::Vector2<f64>) -> (f64, f64) {
+    const N_SAMPLES: u32 = 100;
+
+    (0..=N_SAMPLES)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(N_SAMPLES);
+            (t, (curve(t) - pos).norm())
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
+}
+
+fn closest_on_segment(
+    start: na::Vector2<f64>,
+    end: na::Vector2<f64>,
+    pos: na::Vector2<f64>,
+) -> (na::Vector2<f64>, f64) {
+    let segment = end - start;
+    let len_squared = segment.norm_squared();
+    let t = if len_squared > 0.0 {
+        ((pos - start).dot(&segment) / len_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let closest = start + segment * t;
+    (closest, (pos - closest).norm())
+}
@@ -0,0 +1,157 @@
+// Imports
+use crate::style::PressureCurve;
+use serde::{Deserialize, Serialize};
+
+/// The shape of the dabs a custom brush is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "brush_tip")]
+pub enum BrushTip {
+    /// A circle.
+    #[serde(rename = "circle")]
+    Circle,
+    /// A square.
+    #[serde(rename = "square")]
+    Square,
+    /// An ellipse.
+    #[serde(rename = "ellipse")]
+    Ellipse {
+        /// The ratio of the minor to the major axis, between 0.0 and 1.0.
+        #[serde(rename = "aspect_ratio", with = "crate::serialize::f64_dp3")]
+        aspect_ratio: f64,
+        /// The angle of the major axis in degrees.
+        #[serde(rename = "angle", with = "crate::serialize::f64_dp3")]
+        angle: f64,
+    },
+}
+
+impl Default for BrushTip {
+    fn default() -> Self {
+        Self::Circle
+    }
+}
+
+/// Random variation applied to every dab of a custom brush.
+///
+/// A value of zero disables the variation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "brush_jitter")]
+pub struct BrushJitter {
+    /// The maximum offset of the dab position, relative to the stroke width.
+    #[serde(rename = "position", with = "crate::serialize::f64_dp3")]
+    pub position: f64,
+    /// The maximum relative change of the dab size, between 0.0 and 1.0.
+    #[serde(rename = "size", with = "crate::serialize::f64_dp3")]
+    pub size: f64,
+    /// The maximum rotation of the dab in degrees.
+    #[serde(rename = "angle", with = "crate::serialize::f64_dp3")]
+    pub angle: f64,
+    /// The maximum reduction of the dab opacity, between 0.0 and 1.0.
+    #[serde(rename = "opacity", with = "crate::serialize::f64_dp3")]
+    pub opacity: f64,
+}
+
+/// How the pen pressure maps to the size and the opacity of the dabs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "brush_pressure_mapping")]
+pub struct BrushPressureMapping {
+    /// The pressure curve for the dab size.
+    #[serde(rename = "size_curve")]
+    pub size_curve: PressureCurve,
+    /// The dab size at zero pressure, relative to the stroke width.
+    #[serde(rename = "size_min", with = "crate::serialize::f64_dp3")]
+    pub size_min: f64,
+    /// The pressure curve for the dab opacity.
+    #[serde(rename = "opacity_curve")]
+    pub opacity_curve: PressureCurve,
+    /// The dab opacity at zero pressure.
+    #[serde(rename = "opacity_min", with = "crate::serialize::f64_dp3")]
+    pub opacity_min: f64,
+}
+
+impl Default for BrushPressureMapping {
+    fn default() -> Self {
+        Self {
+            size_curve: PressureCurve::Linear,
+            size_min: 0.1,
+            opacity_curve: PressureCurve::Const,
+            opacity_min: 1.0,
+        }
+    }
+}
+
+impl BrushPressureMapping {
+    /// The factor the stroke width is multiplied with for the given pressure.
+    pub fn size_factor(&self, pressure: f64) -> f64 {
+        self.size_min + (1.0 - self.size_min) * self.size_curve.apply(1.0, pressure)
+    }
+
+    /// The dab opacity for the given pressure.
+    pub fn opacity(&self, pressure: f64) -> f64 {
+        self.opacity_min + (1.0 - self.opacity_min) * self.opacity_curve.apply(1.0, pressure)
+    }
+}
+
+/// The description of a custom brush.
+///
+/// A custom brush stroke is drawn by placing dabs of the tip shape along the path.
+/// Definitions are meant to be shared as files, so all fields have defaults and
+/// out-of-range values are clamped by [BrushDefinition::sanitized].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "brush_definition")]
+pub struct BrushDefinition {
+    /// The name of the brush.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The tip shape.
+    #[serde(rename = "tip")]
+    pub tip: BrushTip,
+    /// Rotate the tip along with the stroke direction.
+    #[serde(rename = "follow_direction")]
+    pub follow_direction: bool,
+    /// The distance between two dabs, relative to the stroke width.
+    #[serde(rename = "spacing", with = "crate::serialize::f64_dp3")]
+    pub spacing: f64,
+    /// The random variation of the dabs.
+    #[serde(rename = "jitter")]
+    pub jitter: BrushJitter,
+    /// The pressure mapping.
+    #[serde(rename = "pressure_mapping")]
+    pub pressure_mapping: BrushPressureMapping,
+}
+
+impl Default for BrushDefinition {
+    fn default() -> Self {
+        Self {
+            name: String::from("Round"),
+            tip: BrushTip::default(),
+            follow_direction: false,
+            spacing: 0.1,
+            jitter: BrushJitter::default(),
+            pressure_mapping: BrushPressureMapping::default(),
+        }
+    }
+}
+
+impl BrushDefinition {
+    /// The minimum spacing.
+    pub const SPACING_MIN: f64 = 0.02;
+    /// The maximum spacing.
+    pub const SPACING_MAX: f64 = 10.0;
+    /// The maximum position jitter.
+    pub const JITTER_POSITION_MAX: f64 = 10.0;
+
+    /// The definition with all values clamped to their valid ranges.
+    pub fn sanitized(mut self) -> Self {
+        if let BrushTip::Ellipse { aspect_ratio, .. } = &mut self.tip {
+            *aspect_ratio = aspect_ratio.clamp(0.01, 1.0);
+        }
+        self.spacing = self.spacing.clamp(Self::SPACING_MIN, Self::SPACING_MAX);
+        self.jitter.position = self.jitter.position.clamp(0.0, Self::JITTER_POSITION_MAX);
+        self.jitter.size = self.jitter.size.clamp(0.0, 1.0);
+        self.jitter.angle = self.jitter.angle.clamp(0.0, 180.0);
+        self.jitter.opacity = self.jitter.opacity.clamp(0.0, 1.0);
+        self.pressure_mapping.size_min = self.pressure_mapping.size_min.clamp(0.0, 1.0);
+        self.pressure_mapping.opacity_min = self.pressure_mapping.opacity_min.clamp(0.0, 1.0);
+        self
+    }
+}
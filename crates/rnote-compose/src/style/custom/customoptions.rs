@@ -0,0 +1,73 @@
+// Imports
+use super::{BrushDefinition, BrushTip};
+use crate::style::smooth::SmoothOptions;
+use crate::Color;
+use serde::{Deserialize, Serialize};
+
+/// Options for pen paths that are drawn with a custom brush.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "custom_options")]
+pub struct CustomOptions {
+    /// An optional seed to generate reproducible strokes.
+    #[serde(rename = "seed")]
+    pub seed: Option<u64>,
+    /// Stroke width.
+    #[serde(rename = "stroke_width", with = "crate::serialize::f64_dp3")]
+    pub stroke_width: f64,
+    /// Stroke color. When set to None, the stroke is not drawn.
+    #[serde(rename = "stroke_color")]
+    pub stroke_color: Option<Color>,
+    /// The brush definition.
+    #[serde(rename = "definition")]
+    pub definition: BrushDefinition,
+}
+
+impl Default for CustomOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            stroke_width: 6.0,
+            stroke_color: Some(Color::BLACK),
+            definition: BrushDefinition::default(),
+        }
+    }
+}
+
+impl CustomOptions {
+    /// Advances the seed.
+    pub fn advance_seed(&mut self) {
+        self.seed = self.seed.map(crate::utils::seed_advance)
+    }
+
+    /// The maximum distance a dab can extend from the path.
+    pub fn max_dab_extent(&self) -> f64 {
+        let definition = &self.definition;
+        // Squares extend the furthest when rotated
+        let tip_factor = match definition.tip {
+            BrushTip::Square => std::f64::consts::SQRT_2,
+            BrushTip::Circle | BrushTip::Ellipse { .. } => 1.0,
+        };
+
+        // The size curves are monotonic, so the largest dab is at either end of the pressure range
+        let size_factor = definition
+            .pressure_mapping
+            .size_factor(0.0)
+            .max(definition.pressure_mapping.size_factor(1.0));
+
+        self.stroke_width
+            * (0.5 * tip_factor * size_factor * (1.0 + definition.jitter.size)
+                + definition.jitter.position)
+    }
+
+    /// Smooth options with the same stroke width and color.
+    ///
+    /// Shapes are drawn with them, since custom brushes are only applied along pen paths.
+    pub fn smooth_fallback(&self) -> SmoothOptions {
+        SmoothOptions {
+            stroke_width: self.stroke_width,
+            stroke_color: self.stroke_color,
+            fill_color: None,
+            ..SmoothOptions::default()
+        }
+    }
+}
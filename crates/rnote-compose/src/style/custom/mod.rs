@@ -0,0 +1,177 @@
+// Modules
+mod brushdefinition;
+mod customoptions;
+
+// Re-exports
+pub use brushdefinition::{BrushDefinition, BrushJitter, BrushPressureMapping, BrushTip};
+pub use customoptions::CustomOptions;
+
+// Imports
+use super::Composer;
+use crate::ext::Vector2Ext;
+use crate::penpath::{Element, Segment};
+use crate::shapes::{Line, Shapeable};
+use crate::PenPath;
+use kurbo::{ParamCurve, ParamCurveArclen, ParamCurveDeriv, Shape};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rand_distr::{Distribution, Uniform};
+
+impl Composer<CustomOptions> for Line {
+    fn composed_bounds(&self, options: &CustomOptions) -> Aabb {
+        self.bounds().loosened(options.max_dab_extent())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &CustomOptions) {
+        PenPath::new_w_segments(
+            Element::new(self.start, 1.0),
+            [Segment::LineTo {
+                end: Element::new(self.end, 1.0),
+            }],
+        )
+        .draw_composed(cx, options);
+    }
+}
+
+impl Composer<CustomOptions> for PenPath {
+    fn composed_bounds(&self, options: &CustomOptions) -> Aabb {
+        self.bounds().loosened(options.max_dab_extent())
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &CustomOptions) {
+        let Some(color) = options.stroke_color else {
+            return;
+        };
+        let mut options = options.clone();
+        let mut prev = self.start;
+        // The spacing continues across segments, so the dabs don't depend on how the path is split up
+        let mut dab_offset = 0.0;
+
+        cx.save().unwrap();
+
+        for seg in self.segments.iter() {
+            let end = seg.end();
+            let path_seg = match seg {
+                Segment::LineTo { end } => kurbo::PathSeg::Line(kurbo::Line::new(
+                    prev.pos.to_kurbo_point(),
+                    end.pos.to_kurbo_point(),
+                )),
+                Segment::QuadBezTo { cp, end } => kurbo::PathSeg::Quad(kurbo::QuadBez::new(
+                    prev.pos.to_kurbo_point(),
+                    cp.to_kurbo_point(),
+                    end.pos.to_kurbo_point(),
+                )),
+                Segment::CubBezTo { cp1, cp2, end } => kurbo::PathSeg::Cubic(kurbo::CubicBez::new(
+                    prev.pos.to_kurbo_point(),
+                    cp1.to_kurbo_point(),
+                    cp2.to_kurbo_point(),
+                    end.pos.to_kurbo_point(),
+                )),
+            };
+
+            dab_offset = draw_dabs(
+                cx,
+                &path_seg,
+                (prev.pressure, end.pressure),
+                dab_offset,
+                color,
+                &options,
+            );
+            options.advance_seed();
+            prev = end;
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
+/// Places the dabs along a single path segment, the first one at the given arc length offset from its start.
+///
+/// The pressure is interpolated linearly between the segment start and end.
+/// Returns the offset of the next dab from the start of the following segment.
+fn draw_dabs(
+    cx: &mut impl piet::RenderContext,
+    path_seg: &kurbo::PathSeg,
+    pressures: (f64, f64),
+    dab_offset: f64,
+    color: crate::Color,
+    options: &CustomOptions,
+) -> f64 {
+    const ARCLEN_ACCURACY: f64 = 0.1;
+    // Upper bound to protect against degenerate definitions and huge segments
+    const N_DABS_MAX: usize = 10_000;
+
+    let definition = &options.definition;
+    let step = (definition.spacing * options.stroke_width).max(0.1);
+    let arclen = path_seg.arclen(ARCLEN_ACCURACY);
+
+    let mut rng = crate::utils::new_rng_default_pcg64(options.seed);
+    let distr = Uniform::from(-1.0..1.0);
+    let jitter = &definition.jitter;
+    let mut dab_arclen = dab_offset;
+
+    for _ in 0..N_DABS_MAX {
+        if dab_arclen > arclen {
+            break;
+        }
+        let t = if arclen > 0.0 {
+            path_seg.inv_arclen(dab_arclen, ARCLEN_ACCURACY)
+        } else {
+            0.0
+        };
+        dab_arclen += step;
+        let pos = path_seg.eval(t);
+        let pressure = pressures.0 + (pressures.1 - pressures.0) * t;
+
+        let size = (options.stroke_width
+            * definition.pressure_mapping.size_factor(pressure)
+            * (1.0 + jitter.size * distr.sample(&mut rng)))
+        .max(0.0);
+        let opacity = definition.pressure_mapping.opacity(pressure)
+            * (1.0 - jitter.opacity * distr.sample(&mut rng).abs());
+        let offset = kurbo::Vec2::new(distr.sample(&mut rng), distr.sample(&mut rng))
+            * jitter.position
+            * options.stroke_width;
+        let mut angle = (jitter.angle * distr.sample(&mut rng)).to_radians();
+        if definition.follow_direction {
+            angle += direction_angle(path_seg, t);
+        }
+        if size <= 0.0 || opacity <= 0.0 {
+            continue;
+        }
+
+        let center = pos + offset;
+        let dab = match definition.tip {
+            BrushTip::Circle => kurbo::Circle::new(center, size * 0.5).to_path(0.1),
+            BrushTip::Square => {
+                kurbo::Affine::rotate_about(angle, center)
+                    * kurbo::Rect::from_center_size(center, (size, size)).to_path(0.1)
+            }
+            BrushTip::Ellipse {
+                aspect_ratio,
+                angle: tip_angle,
+            } => kurbo::Ellipse::new(
+                center,
+                (size * 0.5, size * 0.5 * aspect_ratio),
+                angle + tip_angle.to_radians(),
+            )
+            .to_path(0.1),
+        };
+
+        let dab_color = crate::Color {
+            a: color.a * opacity.min(1.0),
+            ..color
+        };
+        cx.fill(dab, &piet::Color::from(dab_color));
+    }
+
+    (dab_arclen - arclen).max(0.0)
+}
+
+fn direction_angle(path_seg: &kurbo::PathSeg, t: f64) -> f64 {
+    let deriv = match path_seg {
+        kurbo::PathSeg::Line(line) => line.p1 - line.p0,
+        kurbo::PathSeg::Quad(quad) => quad.deriv().eval(t).to_vec2(),
+        kurbo::PathSeg::Cubic(cubic) => cubic.deriv().eval(t).to_vec2(),
+    };
+    deriv.y.atan2(deriv.x)
+}
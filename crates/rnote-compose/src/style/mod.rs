@@ -1,6 +1,8 @@
 // Modules
 /// Composer
 mod composer;
+/// The custom module for styles described by brush definitions
+pub mod custom;
 /// Draw indicators
 pub mod indicators;
 /// The rough module for rough styles
@@ -11,6 +13,7 @@ pub mod smooth;
 pub mod textured;
 
 // Re-exports
+use self::custom::CustomOptions;
 use self::rough::RoughOptions;
 use self::smooth::SmoothOptions;
 use self::textured::TexturedOptions;
@@ -37,6 +40,9 @@ pub enum Style {
     /// A textured style
     #[serde(rename = "textured")]
     Textured(TexturedOptions),
+    /// A custom style, described by a brush definition
+    #[serde(rename = "custom")]
+    Custom(CustomOptions),
 }
 
 impl Default for Style {
@@ -52,6 +58,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width,
             Style::Rough(options) => options.stroke_width,
             Style::Textured(options) => options.stroke_width,
+            Style::Custom(options) => options.stroke_width,
         }
     }
 
@@ -61,6 +68,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width = stroke_width,
             Style::Rough(options) => options.stroke_width = stroke_width,
             Style::Textured(options) => options.stroke_width = stroke_width,
+            Style::Custom(options) => options.stroke_width = stroke_width,
         }
     }
 
@@ -70,6 +78,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width,
             Style::Rough(options) => options.stroke_width + RoughOptions::ROUGH_BOUNDS_MARGIN,
             Style::Textured(options) => options.stroke_width,
+            Style::Custom(options) => options.max_dab_extent() * 2.0,
         }
     }
 
@@ -79,6 +88,7 @@ impl Style {
             Style::Smooth(_) => {}
             Style::Rough(options) => options.advance_seed(),
             Style::Textured(options) => options.advance_seed(),
+            Style::Custom(options) => options.advance_seed(),
         }
     }
 
@@ -88,6 +98,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_color,
             Style::Rough(options) => options.stroke_color,
             Style::Textured(options) => options.stroke_color,
+            Style::Custom(options) => options.stroke_color,
         }
     }
 
@@ -96,7 +107,7 @@ impl Style {
         match self {
             Style::Smooth(options) => options.fill_color,
             Style::Rough(options) => options.fill_color,
-            Style::Textured(_) => None,
+            Style::Textured(_) | Style::Custom(_) => None,
         }
     }
 
@@ -106,6 +117,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_color = Some(color),
             Style::Rough(options) => options.stroke_color = Some(color),
             Style::Textured(options) => options.stroke_color = Some(color),
+            Style::Custom(options) => options.stroke_color = Some(color),
         };
     }
 
@@ -114,7 +126,7 @@ impl Style {
         match self {
             Style::Smooth(options) => options.fill_color = Some(color),
             Style::Rough(options) => options.fill_color = Some(color),
-            Style::Textured(_) => {}
+            Style::Textured(_) | Style::Custom(_) => {}
         };
     }
 }
@@ -125,6 +137,7 @@ impl Composer<Style> for Line {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(options) => self.composed_bounds(options),
+            Style::Custom(options) => self.composed_bounds(options),
         }
     }
 
@@ -133,6 +146,7 @@ impl Composer<Style> for Line {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(options) => self.draw_composed(cx, options),
+            Style::Custom(options) => self.draw_composed(cx, options),
         }
     }
 }
@@ -143,6 +157,7 @@ impl Composer<Style> for Arrow {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -151,6 +166,7 @@ impl Composer<Style> for Arrow {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -161,6 +177,7 @@ impl Composer<Style> for Rectangle {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -169,6 +186,7 @@ impl Composer<Style> for Rectangle {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -179,6 +197,7 @@ impl Composer<Style> for Ellipse {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -187,6 +206,7 @@ impl Composer<Style> for Ellipse {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -197,6 +217,7 @@ impl Composer<Style> for QuadraticBezier {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -205,6 +226,7 @@ impl Composer<Style> for QuadraticBezier {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -215,6 +237,7 @@ impl Composer<Style> for CubicBezier {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -223,6 +246,7 @@ impl Composer<Style> for CubicBezier {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -233,6 +257,7 @@ impl Composer<Style> for Polyline {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -241,6 +266,7 @@ impl Composer<Style> for Polyline {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -251,6 +277,7 @@ impl Composer<Style> for Polygon {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.composed_bounds(&options.smooth_fallback()),
         }
     }
 
@@ -259,6 +286,7 @@ impl Composer<Style> for Polygon {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Custom(options) => self.draw_composed(cx, &options.smooth_fallback()),
         }
     }
 }
@@ -269,6 +297,7 @@ impl Composer<Style> for PenPath {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(_) => unimplemented!(),
             Style::Textured(options) => self.composed_bounds(options),
+            Style::Custom(options) => self.composed_bounds(options),
         }
     }
 
@@ -277,6 +306,7 @@ impl Composer<Style> for PenPath {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(_) => unimplemented!(),
             Style::Textured(options) => self.draw_composed(cx, options),
+            Style::Custom(options) => self.draw_composed(cx, options),
         }
     }
 }
@@ -311,7 +341,15 @@ impl Composer<Style> for Shape {
 
 /// The pressure curve used by some styles.
 #[derive(
-    Debug, Clone, Copy, Serialize, Deserialize, num_derive::FromPrimitive, num_derive::ToPrimitive,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
 )]
 #[serde(rename = "pressure_curve")]
 pub enum PressureCurve {
@@ -233,21 +233,20 @@ impl Composer<RoughOptions> for Polygon {
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
-        let points: Vec<roughr::Point2D<_, _>> = std::iter::once(roughr::Point2D::new(
-            self.start[0] as f32,
-            self.start[1] as f32,
-        ))
-        .chain(
-            self.path
-                .iter()
-                .map(|p| roughr::Point2D::new(p[0] as f32, p[1] as f32)),
-        )
-        .collect();
-
-        let drawable =
-            rough_piet::KurboGenerator::new(generate_roughr_options(options)).polygon(&points);
-
-        drawable.draw(cx);
+        let generator = rough_piet::KurboGenerator::new(generate_roughr_options(options));
+        let to_point = |p: &na::Vector2<f64>| roughr::Point2D::new(p[0] as f32, p[1] as f32);
+        let points: Vec<roughr::Point2D<_, _>> = std::iter::once(&self.start)
+            .chain(self.path.iter())
+            .map(to_point)
+            .collect();
+
+        generator.polygon(&points).draw(cx);
+
+        // The rough fill can't cut out holes, so the sub-paths of compound polygons are drawn on their own
+        for subpath in self.subpaths.iter().filter(|subpath| !subpath.is_empty()) {
+            let points: Vec<roughr::Point2D<_, _>> = subpath.iter().map(to_point).collect();
+            generator.polygon(&points).draw(cx);
+        }
     }
 }
 
@@ -9,7 +9,8 @@ use super::Composer;
 use crate::ext::Vector2Ext;
 use crate::penpath::{self, Segment};
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arrow, CubicBezier, Ellipse, FillRule, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    Shapeable,
 };
 use crate::PenPath;
 use kurbo::Shape;
@@ -179,14 +180,14 @@ impl Composer<SmoothOptions> for Polygon {
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
-        let Some(color) = options.stroke_color else {
-            return;
-        };
         let n_points = self.path.len();
         let single_pos = self.path.iter().all(|p| *p == self.start);
 
         // Single element/position polylines need special treatment to be rendered
         if n_points == 0 || single_pos {
+            let Some(color) = options.stroke_color else {
+                return;
+            };
             cx.fill(
                 kurbo::Circle::new(self.start.to_kurbo_point(), options.stroke_width),
                 &Into::<piet::Color>::into(color),
@@ -194,24 +195,31 @@ impl Composer<SmoothOptions> for Polygon {
         } else {
             let outline_path = self.outline_path();
             if let Some(fill_color) = options.fill_color {
-                cx.fill(&outline_path, &Into::<piet::Color>::into(fill_color));
+                let fill_color = Into::<piet::Color>::into(fill_color);
+                match self.fill_rule {
+                    FillRule::NonZero => cx.fill(&outline_path, &fill_color),
+                    FillRule::EvenOdd => cx.fill_even_odd(&outline_path, &fill_color),
+                }
             }
 
-            cx.stroke_styled(
-                &outline_path,
-                &Into::<piet::Color>::into(color),
-                options.stroke_width,
-                &piet::StrokeStyle::default()
-                    .line_cap(piet::LineCap::Butt)
-                    .line_join(piet::LineJoin::Bevel),
-            );
+            // Polygons without a stroke color are only filled
+            if let Some(color) = options.stroke_color {
+                cx.stroke_styled(
+                    &outline_path,
+                    &Into::<piet::Color>::into(color),
+                    options.stroke_width,
+                    &piet::StrokeStyle::default()
+                        .line_cap(piet::LineCap::Butt)
+                        .line_join(piet::LineJoin::Bevel),
+                );
+            }
         }
     }
 }
 
 impl Composer<SmoothOptions> for PenPath {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
-        self.bounds().loosened(options.stroke_width * 0.5)
+        self.bounds().loosened(options.max_width() * 0.5)
     }
 
     fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
@@ -234,12 +242,8 @@ impl Composer<SmoothOptions> for PenPath {
                 match seg {
                     Segment::LineTo { end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.width_for_element(&prev),
+                            options.width_for_element(end),
                         );
 
                         let bez_path = compose_lines_variable_width(
@@ -257,12 +261,8 @@ impl Composer<SmoothOptions> for PenPath {
                     }
                     Segment::QuadBezTo { cp, end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.width_for_element(&prev),
+                            options.width_for_element(end),
                         );
 
                         let quadbez = QuadraticBezier {
@@ -283,12 +283,8 @@ impl Composer<SmoothOptions> for PenPath {
                     }
                     Segment::CubBezTo { cp1, cp2, end } => {
                         let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
+                            options.width_for_element(&prev),
+                            options.width_for_element(end),
                         );
 
                         let cubbez = CubicBezier {
@@ -320,9 +316,7 @@ impl Composer<SmoothOptions> for PenPath {
 
         // Single element/position strokes need special treatment to be rendered
         if single_pos {
-            let start_width = options
-                .pressure_curve
-                .apply(options.stroke_width, self.start.pressure);
+            let start_width = options.width_for_element(&self.start);
             cx.fill(
                 kurbo::Circle::new(self.start.pos.to_kurbo_point(), start_width * 0.5),
                 &Into::<piet::Color>::into(color),
@@ -361,6 +355,167 @@ impl Composer<SmoothOptions> for crate::Shape {
     }
 }
 
+/// The number of points that approximate a round cap of a composed outline.
+const OUTLINE_CAP_POINTS: u32 = 8;
+
+/// Composes the envelope of the pen path drawn with the options as a single polygon, including round caps and joins.
+///
+/// Filled with the stroke color and the nonzero fill rule it covers the same area as the drawn pen path.
+pub fn compose_penpath_outline(penpath: &PenPath, options: &SmoothOptions) -> Polygon {
+    // Positions along the path with the stroke width at each of them
+    let mut samples = vec![(penpath.start.pos, options.width_for_element(&penpath.start))];
+    let mut prev = penpath.start;
+
+    for seg in penpath.segments.iter() {
+        let end = seg.end();
+        let (width_start, width_end) = (
+            options.width_for_element(&prev),
+            options.width_for_element(&end),
+        );
+        let lines = match seg {
+            Segment::LineTo { end } => vec![Line {
+                start: prev.pos,
+                end: end.pos,
+            }],
+            Segment::QuadBezTo { cp, end } => {
+                let quadbez = QuadraticBezier {
+                    start: prev.pos,
+                    cp: *cp,
+                    end: end.pos,
+                };
+                let n_splits =
+                    penpath::no_subsegments_for_segment_len(quadbez.outline_path().perimeter(0.25))
+                        .max(2);
+                quadbez.approx_with_lines(n_splits)
+            }
+            Segment::CubBezTo { cp1, cp2, end } => {
+                let cubbez = CubicBezier {
+                    start: prev.pos,
+                    cp1: *cp1,
+                    cp2: *cp2,
+                    end: end.pos,
+                };
+                let n_splits =
+                    penpath::no_subsegments_for_segment_len(cubbez.outline_path().perimeter(0.25))
+                        .max(2);
+                cubbez.approx_with_lines(n_splits)
+            }
+        };
+
+        let n_lines = lines.len();
+        for (i, line) in lines.into_iter().enumerate() {
+            let width = width_start
+                + (width_end - width_start) * (f64::from(i as u32 + 1) / f64::from(n_lines as u32));
+            if samples
+                .last()
+                .map(|(pos, _)| *pos != line.end)
+                .unwrap_or(true)
+            {
+                samples.push((line.end, width));
+            }
+        }
+        prev = end;
+    }
+
+    let (start_pos, start_width) = samples[0];
+    if samples.len() < 2 {
+        // A single position is drawn as a circle
+        let radius = start_width * 0.5;
+        let n_points = OUTLINE_CAP_POINTS * 2;
+        let mut circle = (0..n_points).map(|i| {
+            let angle = std::f64::consts::TAU * f64::from(i) / f64::from(n_points);
+            start_pos + na::vector![angle.cos(), angle.sin()] * radius
+        });
+        return Polygon {
+            start: circle.next().unwrap(),
+            path: circle.collect(),
+            ..Default::default()
+        };
+    }
+
+    let n_samples = samples.len();
+    let dirs = samples
+        .windows(2)
+        .map(|w| w[1].0 - w[0].0)
+        .collect::<Vec<na::Vector2<f64>>>();
+
+    // The offset coordinates on one side of the path, with joins between the segments.
+    //
+    // On the outer side of a turn the join is round, on the inner side the offset goes through the position itself.
+    // This keeps the polygon covering the entire stroke when filled with the nonzero fill rule,
+    // also at sharp turns where the offsets on the inner side overlap.
+    let offset_coords = |side: f64| {
+        let mut coords = vec![samples[0].0 + dirs[0].orth_unit() * side * samples[0].1 * 0.5];
+        for (i, (pos, width)) in samples.iter().enumerate().take(n_samples - 1).skip(1) {
+            let (dir_prev, dir_next) = (dirs[i - 1], dirs[i]);
+            let offset_prev = dir_prev.orth_unit() * side * width * 0.5;
+            let offset_next = dir_next.orth_unit() * side * width * 0.5;
+            let turn = dir_prev.perp(&dir_next);
+            // When the path reverses exactly, the round join is on the positive side
+            let reversal = turn == 0.0 && dir_prev.dot(&dir_next) < 0.0;
+
+            if turn == 0.0 && !reversal {
+                // Straight continuation
+                coords.push(pos + offset_prev);
+            } else if (reversal && side > 0.0) || (!reversal && offset_prev.dot(&dir_next) < 0.0) {
+                // Round join on the outer side
+                let angle = if reversal {
+                    -std::f64::consts::PI
+                } else {
+                    offset_prev
+                        .perp(&offset_next)
+                        .atan2(offset_prev.dot(&offset_next))
+                };
+                let n_steps = ((angle.abs() / std::f64::consts::PI) * f64::from(OUTLINE_CAP_POINTS))
+                    .ceil()
+                    .max(1.0) as u32;
+                coords.extend((0..=n_steps).map(|step| {
+                    pos + na::Rotation2::new(angle * f64::from(step) / f64::from(n_steps))
+                        * offset_prev
+                }));
+            } else {
+                // Inner side
+                coords.extend([pos + offset_prev, *pos, pos + offset_next]);
+            }
+        }
+        coords.push(
+            samples[n_samples - 1].0
+                + dirs[n_samples - 2].orth_unit() * side * samples[n_samples - 1].1 * 0.5,
+        );
+        coords
+    };
+    let pos_offset_coords = offset_coords(1.0);
+    let neg_offset_coords = offset_coords(-1.0);
+
+    let cap = |center: na::Vector2<f64>, dir: na::Vector2<f64>, width: f64| {
+        let dir_unit = if dir.magnitude() > 0.0 {
+            dir.normalize()
+        } else {
+            dir
+        };
+        let orth_unit = dir.orth_unit();
+        (1..OUTLINE_CAP_POINTS).map(move |i| {
+            let angle = std::f64::consts::PI * f64::from(i) / f64::from(OUTLINE_CAP_POINTS);
+            center + (orth_unit * angle.cos() + dir_unit * angle.sin()) * width * 0.5
+        })
+    };
+    let (end_pos, end_width) = samples[n_samples - 1];
+    let end_dir = end_pos - samples[n_samples - 2].0;
+    let start_dir = start_pos - samples[1].0;
+
+    let mut path = pos_offset_coords[1..].to_vec();
+    path.extend(cap(end_pos, end_dir, end_width));
+    path.extend(neg_offset_coords.into_iter().rev());
+    path.extend(cap(start_pos, start_dir, start_width));
+
+    Polygon {
+        start: pos_offset_coords[0],
+        path,
+        fill_rule: FillRule::NonZero,
+        ..Default::default()
+    }
+}
+
 /// Composes lines with variable width. Must be drawn with only a fill.
 fn compose_lines_variable_width(
     lines: &[Line],
@@ -1,4 +1,5 @@
 // Imports
+use crate::penpath::Element;
 use crate::style::PressureCurve;
 use crate::Color;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,11 @@ pub struct SmoothOptions {
     /// Pressure curve.
     #[serde(rename = "pressure_curve")]
     pub pressure_curve: PressureCurve,
+    /// How much the stroke width increases when the pen is tilted.
+    ///
+    /// A fully tilted pen results in a width of `(1.0 + tilt_sensitivity)` times the width of an upright pen.
+    #[serde(rename = "tilt_sensitivity", with = "crate::serialize::f64_dp3")]
+    pub tilt_sensitivity: f64,
 }
 
 impl Default for SmoothOptions {
@@ -28,6 +34,26 @@ impl Default for SmoothOptions {
             stroke_color: Some(Color::BLACK),
             fill_color: None,
             pressure_curve: PressureCurve::default(),
+            tilt_sensitivity: 0.0,
         }
     }
 }
+
+impl SmoothOptions {
+    /// The minimum tilt sensitivity.
+    pub const TILT_SENSITIVITY_MIN: f64 = 0.0;
+    /// The maximum tilt sensitivity.
+    pub const TILT_SENSITIVITY_MAX: f64 = 5.0;
+
+    /// The stroke width at the given element, with the pressure curve and the tilt sensitivity applied.
+    pub fn width_for_element(&self, element: &Element) -> f64 {
+        self.pressure_curve
+            .apply(self.stroke_width, element.pressure)
+            * (1.0 + self.tilt_sensitivity * element.tilt_amount())
+    }
+
+    /// The maximum width that can be reached at any element.
+    pub fn max_width(&self) -> f64 {
+        self.stroke_width * (1.0 + self.tilt_sensitivity.max(0.0))
+    }
+}
@@ -0,0 +1,129 @@
+//! Property based tests for the geometry helpers and the transformation invariants of the shapes.
+
+// Imports
+use nalgebra as na;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use parry2d_f64 as p2d;
+use proptest::prelude::*;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
+use rnote_compose::shapes::{
+    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+};
+use rnote_compose::transform::Transformable;
+use rnote_compose::Shape;
+
+const COORD_RANGE: std::ops::Range<f64> = -1000.0..1000.0;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * (1.0 + a.abs().max(b.abs()))
+}
+
+fn approx_vec(a: na::Vector2<f64>, b: na::Vector2<f64>) -> bool {
+    approx(a[0], b[0]) && approx(a[1], b[1])
+}
+
+fn approx_aabb(a: &Aabb, b: &Aabb) -> bool {
+    approx_vec(a.mins.coords, b.mins.coords) && approx_vec(a.maxs.coords, b.maxs.coords)
+}
+
+fn vec2() -> impl Strategy<Value = na::Vector2<f64>> {
+    (COORD_RANGE, COORD_RANGE).prop_map(|(x, y)| na::vector![x, y])
+}
+
+fn scale_factor() -> impl Strategy<Value = na::Vector2<f64>> {
+    (0.1..10.0f64, 0.1..10.0f64).prop_map(|(x, y)| na::vector![x, y])
+}
+
+fn aabb() -> impl Strategy<Value = Aabb> {
+    (vec2(), vec2()).prop_map(|(a, b)| Aabb::new_positive(a.into(), b.into()))
+}
+
+/// Generates every variant of [Shape].
+fn shape() -> impl Strategy<Value = Shape> {
+    prop_oneof![
+        (vec2(), vec2()).prop_map(|(start, end)| Shape::Line(Line::new(start, end))),
+        (vec2(), vec2()).prop_map(|(start, tip)| Shape::Arrow(Arrow::new(start, tip))),
+        (vec2(), vec2())
+            .prop_map(|(first, second)| Shape::Rectangle(Rectangle::from_corners(first, second))),
+        (vec2(), vec2(), vec2()).prop_map(|(f1, f2, point)| Shape::Ellipse(
+            Ellipse::from_foci_and_point([f1, f2], point)
+        )),
+        (vec2(), vec2(), vec2()).prop_map(|(start, cp, end)| Shape::QuadraticBezier(
+            QuadraticBezier { start, cp, end }
+        )),
+        (vec2(), vec2(), vec2(), vec2()).prop_map(|(start, cp1, cp2, end)| Shape::CubicBezier(
+            CubicBezier {
+                start,
+                cp1,
+                cp2,
+                end
+            }
+        )),
+        (vec2(), prop::collection::vec(vec2(), 1..8)).prop_map(|(start, path)| {
+            let mut polyline = Polyline::new(start);
+            polyline.path = path;
+            Shape::Polyline(polyline)
+        }),
+        (vec2(), prop::collection::vec(vec2(), 2..8)).prop_map(|(start, path)| {
+            let mut polygon = Polygon::new(start);
+            polygon.path = path;
+            Shape::Polygon(polygon)
+        }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn aabb_new_positive_is_valid(a in vec2(), b in vec2()) {
+        let bounds = Aabb::new_positive(a.into(), b.into());
+        prop_assert!(bounds.assert_valid().is_ok());
+        prop_assert!(bounds.contains_local_point(&a.into()));
+        prop_assert!(bounds.contains_local_point(&b.into()));
+    }
+
+    #[test]
+    fn aabb_merged_contains_both(a in aabb(), b in aabb()) {
+        let merged = a.merged(&b);
+        prop_assert!(merged.contains(&a));
+        prop_assert!(merged.contains(&b));
+        prop_assert!(approx_aabb(&merged, &b.merged(&a)));
+    }
+
+    #[test]
+    fn aabb_translate_roundtrip(bounds in aabb(), offset in vec2()) {
+        let translated = bounds.translate(offset);
+        prop_assert!(approx_vec(translated.extents(), bounds.extents()));
+        prop_assert!(approx_aabb(&translated.translate(-offset), &bounds));
+    }
+
+    #[test]
+    fn vector_orth_unit(v in vec2()) {
+        prop_assume!(v.magnitude() > 1e-6);
+        let orth = v.orth_unit();
+        prop_assert!(approx(orth.magnitude(), 1.0));
+        prop_assert!(approx(orth.dot(&v.normalize()), 0.0));
+    }
+
+    #[test]
+    fn shape_translate_moves_bounds(mut shape in shape(), offset in vec2()) {
+        let bounds = shape.bounds();
+        shape.translate(offset);
+        prop_assert!(approx_aabb(&shape.bounds(), &bounds.translate(offset)));
+    }
+
+    #[test]
+    fn shape_set_pos(mut shape in shape(), pos in vec2()) {
+        let extents = shape.bounds().extents();
+        shape.set_pos(pos);
+        prop_assert!(approx_vec(shape.pos(), pos));
+        prop_assert!(approx_vec(shape.bounds().extents(), extents));
+    }
+
+    #[test]
+    fn shape_scale_roundtrip(mut shape in shape(), scale in scale_factor()) {
+        let bounds = shape.bounds();
+        shape.scale(scale);
+        shape.scale(na::vector![1.0 / scale[0], 1.0 / scale[1]]);
+        prop_assert!(approx_aabb(&shape.bounds(), &bounds));
+    }
+}
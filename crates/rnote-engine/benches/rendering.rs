@@ -0,0 +1,204 @@
+//! Benchmarks for the rendering and the bounds computation of the stroke types.
+//!
+//! Run with `cargo bench -p rnote-engine --bench rendering`.
+
+// Imports
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra as na;
+use parry2d_f64::bounding_volume::Aabb;
+use rnote_compose::penpath::Element;
+use rnote_compose::shapes::{Ellipse, Rectangle, Shapeable};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::style::PressureCurve;
+use rnote_compose::{Color, PenPath, Shape, Style};
+use rnote_engine::strokes::resize::ImageSizeOption;
+use rnote_engine::strokes::textstroke::TextStyle;
+use rnote_engine::strokes::{
+    BitmapImage, BrushStroke, CheckboxStroke, Content, ShapeStroke, StickyNoteStroke, Stroke,
+    TableStroke, TextStroke, VectorImage,
+};
+use std::io::Cursor;
+
+const IMAGE_SCALE: f64 = 1.0;
+
+fn style() -> Style {
+    Style::Smooth(SmoothOptions {
+        stroke_width: 3.0,
+        pressure_curve: PressureCurve::Linear,
+        ..Default::default()
+    })
+}
+
+/// A brush stroke with the given number of elements, zig-zagging across the page.
+fn brushstroke(n_elements: usize) -> Stroke {
+    let elements = (0..n_elements).map(|i| {
+        let t = i as f64 / n_elements as f64;
+        Element::new(
+            na::vector![t * 600.0, (t * 40.0 * std::f64::consts::TAU).sin() * 30.0],
+            0.2 + t * 0.8,
+        )
+    });
+    Stroke::BrushStroke(BrushStroke::from_penpath(
+        PenPath::try_from_elements(elements).unwrap(),
+        style(),
+    ))
+}
+
+fn vectorimage() -> Stroke {
+    let svg_data = r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200">
+  <rect x="10" y="10" width="180" height="180" fill="none" stroke="black" stroke-width="4"/>
+  <circle cx="100" cy="100" r="60" fill="red"/>
+  <path d="M 20 180 C 60 20, 140 20, 180 180" fill="none" stroke="blue" stroke-width="3"/>
+</svg>"#;
+    Stroke::VectorImage(
+        VectorImage::from_svg_str(
+            svg_data,
+            na::vector![0.0, 0.0],
+            ImageSizeOption::RespectOriginalSize,
+        )
+        .unwrap(),
+    )
+}
+
+fn bitmapimage() -> Stroke {
+    let image = image::RgbaImage::from_fn(512, 512, |x, y| {
+        image::Rgba([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8, 255])
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .unwrap();
+    Stroke::BitmapImage(
+        BitmapImage::from_image_bytes(
+            &bytes,
+            na::vector![0.0, 0.0],
+            ImageSizeOption::RespectOriginalSize,
+        )
+        .unwrap(),
+    )
+}
+
+/// One stroke of every type, with a name.
+fn strokes() -> Vec<(&'static str, Stroke)> {
+    let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(8);
+    vec![
+        ("brushstroke", brushstroke(1000)),
+        (
+            "shapestroke_rectangle",
+            Stroke::ShapeStroke(ShapeStroke::new(
+                Shape::Rectangle(Rectangle::from_corners(
+                    na::vector![0.0, 0.0],
+                    na::vector![300.0, 200.0],
+                )),
+                style(),
+            )),
+        ),
+        (
+            "shapestroke_ellipse",
+            Stroke::ShapeStroke(ShapeStroke::new(
+                Shape::Ellipse(Ellipse {
+                    radii: na::vector![150.0, 100.0],
+                    ..Default::default()
+                }),
+                style(),
+            )),
+        ),
+        (
+            "textstroke",
+            Stroke::TextStroke(TextStroke::new(
+                text.clone(),
+                na::vector![0.0, 0.0],
+                TextStyle::default(),
+            )),
+        ),
+        ("vectorimage", vectorimage()),
+        ("bitmapimage", bitmapimage()),
+        (
+            "tablestroke",
+            Stroke::TableStroke(TableStroke::new(
+                8,
+                6,
+                na::vector![0.0, 0.0],
+                TextStyle::default(),
+            )),
+        ),
+        (
+            "checkboxstroke",
+            Stroke::CheckboxStroke(CheckboxStroke::new(
+                String::from("A checkbox"),
+                na::vector![0.0, 0.0],
+                TextStyle::default(),
+            )),
+        ),
+        (
+            "stickynotestroke",
+            Stroke::StickyNoteStroke(StickyNoteStroke::new(
+                text,
+                na::vector![0.0, 0.0],
+                Color::GREEN,
+                TextStyle::default(),
+            )),
+        ),
+    ]
+}
+
+fn gen_svg(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gen_svg");
+    for (name, stroke) in strokes() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &stroke, |b, stroke| {
+            b.iter(|| black_box(stroke.gen_svg().unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn gen_images(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gen_images");
+    for (name, stroke) in strokes() {
+        let viewport = stroke.bounds();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &stroke, |b, stroke| {
+            b.iter(|| black_box(stroke.gen_images(viewport, IMAGE_SCALE).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn gen_images_partial_viewport(c: &mut Criterion) {
+    let stroke = brushstroke(10_000);
+    let bounds = stroke.bounds();
+    // Only a quarter of the stroke is inside the viewport
+    let viewport = Aabb::new(
+        bounds.mins,
+        bounds.mins + bounds.extents().component_mul(&na::vector![0.25, 1.0]),
+    );
+    c.bench_function("gen_images_partial_viewport", |b| {
+        b.iter(|| black_box(stroke.gen_images(viewport, IMAGE_SCALE).unwrap()))
+    });
+}
+
+fn bounds(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bounds");
+    for n_elements in [100, 1_000, 10_000] {
+        let stroke = brushstroke(n_elements);
+        group.bench_with_input(
+            BenchmarkId::new("brushstroke", n_elements),
+            &stroke,
+            |b, stroke| b.iter(|| black_box(stroke.bounds())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("brushstroke_hitboxes", n_elements),
+            &stroke,
+            |b, stroke| b.iter(|| black_box(stroke.hitboxes())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    gen_svg,
+    gen_images,
+    gen_images_partial_viewport,
+    bounds
+);
+criterion_main!(benches);
@@ -0,0 +1,113 @@
+//! Benchmarks for saving and loading large documents.
+//!
+//! Run with `cargo bench -p rnote-engine --bench serialization`.
+
+// Imports
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra as na;
+use rnote_compose::penpath::Element;
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::{PenPath, Style};
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::strokes::{BrushStroke, Stroke};
+use rnote_engine::Engine;
+
+/// The number of elements of each brush stroke, which is about the size of a handwritten word.
+const STROKE_ELEMENTS: usize = 200;
+
+/// An engine holding the given number of brush strokes, laid out in lines like handwriting.
+fn engine_w_strokes(n_strokes: usize) -> Engine {
+    let mut engine = Engine::default();
+    let strokes = (0..n_strokes)
+        .map(|i| {
+            let offset = na::vector![(i % 10) as f64 * 70.0, (i / 10) as f64 * 40.0];
+            let elements = (0..STROKE_ELEMENTS).map(|j| {
+                let t = j as f64 / STROKE_ELEMENTS as f64;
+                Element::new(
+                    offset + na::vector![t * 60.0, (t * 6.0 * std::f64::consts::TAU).sin() * 10.0],
+                    0.5,
+                )
+            });
+            let stroke = Stroke::BrushStroke(BrushStroke::from_penpath(
+                PenPath::try_from_elements(elements).unwrap(),
+                Style::Smooth(SmoothOptions::default()),
+            ));
+            (stroke, None)
+        })
+        .collect();
+    let _ = engine.import_generated_content(strokes, false);
+    engine
+}
+
+fn save(c: &mut Criterion) {
+    let mut group = c.benchmark_group("save_as_rnote_bytes");
+    group.sample_size(10);
+    for n_strokes in [1_000, 10_000] {
+        let engine = engine_w_strokes(n_strokes);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_strokes),
+            &engine,
+            |b, engine| {
+                b.iter(|| {
+                    black_box(
+                        futures::executor::block_on(
+                            engine.save_as_rnote_bytes(String::from("bench.rnote")),
+                        )
+                        .unwrap()
+                        .unwrap(),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_from_rnote_bytes");
+    group.sample_size(10);
+    for n_strokes in [1_000, 10_000] {
+        let bytes = futures::executor::block_on(
+            engine_w_strokes(n_strokes).save_as_rnote_bytes(String::from("bench.rnote")),
+        )
+        .unwrap()
+        .unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_strokes),
+            &bytes,
+            |b, bytes| {
+                b.iter(|| {
+                    black_box(
+                        futures::executor::block_on(EngineSnapshot::load_from_rnote_bytes(
+                            bytes.clone(),
+                        ))
+                        .unwrap(),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn load_snapshot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_snapshot");
+    group.sample_size(10);
+    for n_strokes in [1_000, 10_000] {
+        let snapshot = engine_w_strokes(n_strokes).take_snapshot();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_strokes),
+            &snapshot,
+            |b, snapshot| {
+                b.iter(|| {
+                    let mut engine = Engine::default();
+                    black_box(engine.load_snapshot(snapshot.clone()));
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, save, load, load_snapshot);
+criterion_main!(benches);
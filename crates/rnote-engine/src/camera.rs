@@ -36,6 +36,11 @@ pub struct Camera {
     /// The temporary zoom. Is used to overlay the "permanent" zoom.
     #[serde(skip)]
     temporary_zoom: f64,
+    /// The rotation of the view in radians around the center of the viewport, clockwise.
+    ///
+    /// Only changes how the document is displayed, document coordinates are not affected.
+    #[serde(rename = "rotation")]
+    rotation: f64,
 
     /// The scale factor of the surface, usually 1.0 or 2.0 for high-dpi screens.
     ///
@@ -54,6 +59,7 @@ impl Default for Camera {
             size: na::vector![800.0, 600.0],
             zoom: 1.0,
             temporary_zoom: 1.0,
+            rotation: 0.0,
             scale_factor: 1.0,
             zoom_task_handle: None,
         }
@@ -66,6 +72,7 @@ impl CloneConfig for Camera {
             offset: self.offset,
             size: self.size,
             zoom: self.zoom,
+            rotation: self.rotation,
             ..Default::default()
         }
     }
@@ -81,6 +88,8 @@ impl Camera {
     pub const DRAG_ZOOM_MAGN_ZOOM_FACTOR: f64 = 0.005;
     pub const OVERSHOOT_HORIZONTAL: f64 = 96.0;
     pub const OVERSHOOT_VERTICAL: f64 = 96.0;
+    /// The rotation step in radians for rotating the view with actions.
+    pub const ROTATION_STEP: f64 = std::f64::consts::PI / 12.0;
 
     pub fn with_zoom(mut self, zoom: f64) -> Self {
         self.zoom = zoom.clamp(Self::ZOOM_MIN, Self::ZOOM_MAX);
@@ -96,6 +105,11 @@ impl Camera {
         self
     }
 
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = normalize_angle(rotation);
+        self
+    }
+
     /// The current viewport offset in surface coordinate space.
     pub fn offset(&self) -> na::Vector2<f64> {
         self.offset
@@ -226,6 +240,37 @@ impl Camera {
         self.zoom * self.temporary_zoom
     }
 
+    /// The rotation of the view in radians, clockwise. In the range (-PI, PI].
+    pub fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Set the rotation of the view in radians, clockwise.
+    ///
+    /// The view is rotated around the center of the viewport.
+    pub fn set_rotation(&mut self, rotation: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.rotation = normalize_angle(rotation);
+        widget_flags.view_modified = true;
+        widget_flags.resize = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Rotate a vector in surface coordinates into the coordinate space of the offset, which is not rotated.
+    ///
+    /// Needed to translate the offset by the distance that the pointer moved on the surface.
+    pub fn unrotate_surface_vec(&self, vec: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.rotation) * vec
+    }
+
+    /// Rotate a position in surface coordinates back around the center of the surface,
+    /// into the coordinate space of the offset, which is not rotated.
+    pub fn unrotate_surface_pos(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        let center = self.size * 0.5;
+        center + self.unrotate_surface_vec(pos - center)
+    }
+
     /// The scaling factor for generating bitmap images with the current permanent zoom.
     ///
     /// Takes the scale factor in account
@@ -248,13 +293,18 @@ impl Camera {
     }
 
     /// The viewport in document coordinate space.
+    ///
+    /// When the view is rotated, this is the bounding box of the visible area.
     pub fn viewport(&self) -> Aabb {
-        let total_zoom = self.total_zoom();
+        if self.rotation == 0.0 {
+            let total_zoom = self.total_zoom();
 
-        Aabb::new_positive(
-            (self.offset / total_zoom).into(),
-            ((self.offset + self.size) / total_zoom).into(),
-        )
+            return Aabb::new_positive(
+                (self.offset / total_zoom).into(),
+                ((self.offset + self.size) / total_zoom).into(),
+            );
+        }
+        self.transform_inv_bounds(Aabb::new(na::point![0.0, 0.0], self.size.into()))
     }
 
     /// The current viewport center in document coordinate space.
@@ -274,13 +324,23 @@ impl Camera {
     }
 
     /// Transform Aabb from document coords to surface coords.
+    ///
+    /// When the view is rotated, this is the bounding box of the transformed bounds.
     pub fn transform_bounds(&self, bounds: Aabb) -> Aabb {
-        bounds.scale(self.total_zoom()).translate(-self.offset)
+        if self.rotation == 0.0 {
+            return bounds.scale(self.total_zoom()).translate(-self.offset);
+        }
+        transform_aabb(&self.transform(), bounds)
     }
 
     /// Transform Aabb from surface coords to document coords.
+    ///
+    /// When the view is rotated, this is the bounding box of the transformed bounds.
     pub fn transform_inv_bounds(&self, bounds: Aabb) -> Aabb {
-        bounds.translate(self.offset).scale(1.0 / self.total_zoom())
+        if self.rotation == 0.0 {
+            return bounds.translate(self.offset).scale(1.0 / self.total_zoom());
+        }
+        transform_aabb(&self.transform().inverse(), bounds)
     }
 
     /// The transform from document coords to surface coords.
@@ -288,10 +348,14 @@ impl Camera {
     /// To get the inverse, call `.inverse()`.
     pub fn transform(&self) -> na::Affine2<f64> {
         let total_zoom = self.total_zoom();
+        let center = self.size * 0.5;
 
         na::try_convert(
             // LHS is applied onto RHS, so the order is scaling by zoom -> Translation by offset
-            na::Translation2::from(-self.offset).to_homogeneous()
+            // -> rotation around the center of the surface
+            na::Translation2::from(center).to_homogeneous()
+                * na::Rotation2::new(self.rotation).to_homogeneous()
+                * na::Translation2::from(-center - self.offset).to_homogeneous()
                 * na::Scale2::from(na::Vector2::from_element(total_zoom)).to_homogeneous(),
         )
         .unwrap()
@@ -305,11 +369,17 @@ impl Camera {
     #[cfg(feature = "ui")]
     pub fn transform_for_gtk_snapshot(&self) -> gtk4::gsk::Transform {
         let total_zoom = self.total_zoom();
+        let center = self.size * 0.5;
 
         gtk4::gsk::Transform::new()
             .translate(&gtk4::graphene::Point::new(
-                -self.offset[0] as f32,
-                -self.offset[1] as f32,
+                center[0] as f32,
+                center[1] as f32,
+            ))
+            .rotate(self.rotation.to_degrees() as f32)
+            .translate(&gtk4::graphene::Point::new(
+                (-center[0] - self.offset[0]) as f32,
+                (-center[1] - self.offset[1]) as f32,
             ))
             .scale(total_zoom as f32, total_zoom as f32)
     }
@@ -369,6 +439,29 @@ impl Camera {
     }
 }
 
+/// Normalize the angle in radians into the range (-PI, PI].
+fn normalize_angle(angle: f64) -> f64 {
+    use std::f64::consts::PI;
+    let angle = angle.rem_euclid(2.0 * PI);
+    if angle > PI {
+        angle - 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+/// The bounding box of the transformed corners of the bounds.
+fn transform_aabb(transform: &na::Affine2<f64>, bounds: Aabb) -> Aabb {
+    let corners = [
+        bounds.mins,
+        na::point![bounds.maxs[0], bounds.mins[1]],
+        bounds.maxs,
+        na::point![bounds.mins[0], bounds.maxs[1]],
+    ]
+    .map(|corner| transform * corner);
+    Aabb::from_points(&corners)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Camera;
@@ -408,4 +501,26 @@ mod tests {
         assert_relative_eq!(viewport.mins, mins);
         assert_relative_eq!(viewport.maxs, maxs);
     }
+
+    #[test]
+    fn rotation_keeps_viewport_center() {
+        let camera = Camera::default()
+            .with_zoom(1.5)
+            .with_offset(na::vector![10.0, -20.0])
+            .with_size(na::vector![200.0, 100.0]);
+        let rotated = camera.clone().with_rotation(0.3);
+
+        let center = camera.viewport_center();
+        assert_relative_eq!(rotated.viewport_center(), center);
+        assert_relative_eq!(
+            rotated.transform().transform_point(&center.into()).coords,
+            camera.size() * 0.5,
+            epsilon = 1e-9
+        );
+        // The bounding box of the rotated viewport contains the unrotated one
+        let viewport = camera.viewport();
+        let rotated_viewport = rotated.viewport();
+        assert!(rotated_viewport.mins[0] < viewport.mins[0]);
+        assert!(rotated_viewport.maxs[1] > viewport.maxs[1]);
+    }
 }
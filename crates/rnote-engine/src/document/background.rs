@@ -57,45 +57,69 @@ const HALF_SQRT_THREE: f64 = SQRT_THREE / 2_f64;
 /// 3_f64.sqrt() / 4_f64
 const QUARTER_SQRT_THREE: f64 = SQRT_THREE / 4_f64;
 
+/// Fill the bounds with the tile, repeated with the tile size and aligned to the origin.
+///
+/// The tile is defined once per region and placed by reference, instead of as a Svg pattern fill,
+/// because pattern fills get rasterized when the Svg is rendered to Pdf or Svg surfaces on export.
+fn fill_with_tiles(
+    bounds: Aabb,
+    tile_size: na::Vector2<f64>,
+    tile: element::Group,
+) -> svg::node::element::Element {
+    let id_prefix = rnote_compose::utils::svg_random_id_prefix();
+    let clip_id = id_prefix.clone() + "_bg_pattern_clip";
+    let tile_id = id_prefix + "_bg_pattern_tile";
+    let mut clip_rect = element::Rectangle::new();
+    clip_rect.assign("x", format!("{}px", bounds.mins[0]));
+    clip_rect.assign("y", format!("{}px", bounds.mins[1]));
+    clip_rect.assign("width", format!("{}px", bounds.extents()[0]));
+    clip_rect.assign("height", format!("{}px", bounds.extents()[1]));
+    let defs = element::Definitions::new()
+        .add(
+            element::ClipPath::new()
+                .set("id", clip_id.as_str())
+                .add(clip_rect),
+        )
+        .add(tile.set("id", tile_id.as_str()));
+
+    let mut tiles = element::Group::new().set("clip-path", format!("url(#{clip_id})"));
+    if tile_size[0] > 0.0 && tile_size[1] > 0.0 {
+        let start = bounds.mins.coords.component_div(&tile_size).map(f64::floor);
+        let end = bounds.maxs.coords.component_div(&tile_size).map(f64::ceil);
+        for row in (start[1] as i64)..(end[1] as i64) {
+            for column in (start[0] as i64)..(end[0] as i64) {
+                tiles = tiles.add(
+                    element::Use::new()
+                        .set("href", format!("#{tile_id}"))
+                        .set("x", column as f64 * tile_size[0])
+                        .set("y", row as f64 * tile_size[1]),
+                );
+            }
+        }
+    }
+
+    element::Group::new().add(defs).add(tiles).into()
+}
+
 fn gen_hline_pattern(
     bounds: Aabb,
     spacing: f64,
     color: Color,
     line_width: f64,
 ) -> svg::node::element::Element {
-    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_hline_pattern";
-
     let line_offset = line_width * 0.5;
 
-    let pattern = element::Definitions::new().add(
-        element::Pattern::new()
-            .set("id", pattern_id.as_str())
-            .set("x", 0_f64)
-            .set("y", 0_f64)
-            .set("width", bounds.extents()[0])
-            .set("height", spacing)
-            .set("patternUnits", "userSpaceOnUse")
-            .set("patternContentUnits", "userSpaceOnUse")
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", 0_f64)
-                    .set("y1", line_offset)
-                    .set("x2", bounds.extents()[0])
-                    .set("y2", line_offset),
-            ),
+    let tile = element::Group::new().add(
+        element::Line::new()
+            .set("stroke-width", line_width)
+            .set("stroke", color.to_css_color_attr())
+            .set("x1", 0_f64)
+            .set("y1", line_offset)
+            .set("x2", bounds.extents()[0])
+            .set("y2", line_offset),
     );
 
-    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
-
-    rect.assign("x", format!("{}px", bounds.mins[0]));
-    rect.assign("y", format!("{}px", bounds.mins[1]));
-    rect.assign("width", format!("{}px", bounds.extents()[0]));
-    rect.assign("height", format!("{}px", bounds.extents()[1]));
-
-    let group = element::Group::new().add(pattern).add(rect);
-    group.into()
+    fill_with_tiles(bounds, na::vector![bounds.extents()[0], spacing], tile)
 }
 
 fn gen_grid_pattern(
@@ -105,48 +129,29 @@ fn gen_grid_pattern(
     color: Color,
     line_width: f64,
 ) -> svg::node::element::Element {
-    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_grid_pattern";
-
     let line_offset = line_width * 0.5;
 
-    let pattern = element::Definitions::new().add(
-        element::Pattern::new()
-            .set("id", pattern_id.as_str())
-            .set("x", 0_f64)
-            .set("y", 0_f64)
-            .set("width", column_spacing)
-            .set("height", row_spacing)
-            .set("patternUnits", "userSpaceOnUse")
-            .set("patternContentUnits", "userSpaceOnUse")
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", 0_f64)
-                    .set("y1", line_offset)
-                    .set("x2", column_spacing)
-                    .set("y2", line_offset),
-            )
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", line_offset)
-                    .set("y1", 0_f64)
-                    .set("x2", line_offset)
-                    .set("y2", row_spacing),
-            ),
-    );
-
-    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
-
-    rect.assign("x", format!("{}px", bounds.mins[0]));
-    rect.assign("y", format!("{}px", bounds.mins[1]));
-    rect.assign("width", format!("{}px", bounds.extents()[0]));
-    rect.assign("height", format!("{}px", bounds.extents()[1]));
-
-    let group = element::Group::new().add(pattern).add(rect);
-    group.into()
+    let tile = element::Group::new()
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", 0_f64)
+                .set("y1", line_offset)
+                .set("x2", column_spacing)
+                .set("y2", line_offset),
+        )
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", line_offset)
+                .set("y1", 0_f64)
+                .set("x2", line_offset)
+                .set("y2", row_spacing),
+        );
+
+    fill_with_tiles(bounds, na::vector![column_spacing, row_spacing], tile)
 }
 
 fn gen_dots_pattern(
@@ -156,38 +161,19 @@ fn gen_dots_pattern(
     color: Color,
     dots_width: f64,
 ) -> svg::node::element::Element {
-    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_dots_pattern";
-
-    let pattern = element::Definitions::new().add(
-        element::Pattern::new()
-            .set("id", pattern_id.as_str())
+    let tile = element::Group::new().add(
+        element::Rectangle::new()
+            .set("stroke", "none")
+            .set("fill", color.to_css_color_attr())
             .set("x", 0_f64)
             .set("y", 0_f64)
-            .set("width", column_spacing)
-            .set("height", row_spacing)
-            .set("patternUnits", "userSpaceOnUse")
-            .set("patternContentUnits", "userSpaceOnUse")
-            .add(
-                element::Rectangle::new()
-                    .set("stroke", "none")
-                    .set("fill", color.to_css_color_attr())
-                    .set("x", 0_f64)
-                    .set("y", 0_f64)
-                    .set("width", dots_width)
-                    .set("height", dots_width)
-                    .set("rx", dots_width / 3.0)
-                    .set("ry", dots_width / 3.0),
-            ),
+            .set("width", dots_width)
+            .set("height", dots_width)
+            .set("rx", dots_width / 3.0)
+            .set("ry", dots_width / 3.0),
     );
 
-    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
-    rect.assign("x", format!("{}px", bounds.mins[0]));
-    rect.assign("y", format!("{}px", bounds.mins[1]));
-    rect.assign("width", format!("{}px", bounds.extents()[0]));
-    rect.assign("height", format!("{}px", bounds.extents()[1]));
-
-    let group = element::Group::new().add(pattern).add(rect);
-    group.into()
+    fill_with_tiles(bounds, na::vector![column_spacing, row_spacing], tile)
 }
 
 fn calc_width_iso_pattern(spacing: f64) -> f64 {
@@ -203,67 +189,49 @@ fn gen_iso_grid_pattern(
     // spacing: side length of the equilateral triangle
     // pattern_width: two times the height of the equilateral triangle
 
-    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_iso_grid_pattern";
     let pattern_width = calc_width_iso_pattern(spacing);
 
     let line_offset = line_width * 0.5;
 
-    let pattern = element::Definitions::new().add(
-        element::Pattern::new()
-            .set("id", pattern_id.as_str())
-            .set("x", 0_f64)
-            .set("y", 0_f64)
-            .set("width", pattern_width)
-            .set("height", spacing)
-            .set("patternUnits", "userSpaceOnUse")
-            .set("patternContentUnits", "userSpaceOnUse")
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", line_offset)
-                    .set("y1", 0_f64)
-                    .set("x2", line_offset + pattern_width)
-                    .set("y2", spacing),
-            )
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", line_offset)
-                    .set("y1", spacing)
-                    .set("x2", line_offset + pattern_width)
-                    .set("y2", 0_f64),
-            )
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", line_offset + pattern_width * 0.5)
-                    .set("y1", 0_f64)
-                    .set("x2", line_offset + pattern_width * 0.5)
-                    .set("y2", spacing),
-            )
-            .add(
-                element::Line::new()
-                    .set("stroke-width", line_width)
-                    .set("stroke", color.to_css_color_attr())
-                    .set("x1", line_offset)
-                    .set("y1", 0_f64)
-                    .set("x2", line_offset)
-                    .set("y2", spacing),
-            ),
-    );
-
-    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
-
-    rect.assign("x", format!("{}px", bounds.mins[0]));
-    rect.assign("y", format!("{}px", bounds.mins[1]));
-    rect.assign("width", format!("{}px", bounds.extents()[0]));
-    rect.assign("height", format!("{}px", bounds.extents()[1]));
-
-    let group = element::Group::new().add(pattern).add(rect);
-    group.into()
+    let tile = element::Group::new()
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", line_offset)
+                .set("y1", 0_f64)
+                .set("x2", line_offset + pattern_width)
+                .set("y2", spacing),
+        )
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", line_offset)
+                .set("y1", spacing)
+                .set("x2", line_offset + pattern_width)
+                .set("y2", 0_f64),
+        )
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", line_offset + pattern_width * 0.5)
+                .set("y1", 0_f64)
+                .set("x2", line_offset + pattern_width * 0.5)
+                .set("y2", spacing),
+        )
+        .add(
+            element::Line::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color_attr())
+                .set("x1", line_offset)
+                .set("y1", 0_f64)
+                .set("x2", line_offset)
+                .set("y2", spacing),
+        );
+
+    fill_with_tiles(bounds, na::vector![pattern_width, spacing], tile)
 }
 
 fn gen_iso_dots_pattern(
@@ -275,7 +243,6 @@ fn gen_iso_dots_pattern(
     // spacing: side length of the equilateral triangle
     // pattern_width: two times the height of the equilateral triangle
 
-    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_iso_dots_pattern";
     let pattern_width = calc_width_iso_pattern(spacing);
 
     let hexagon_path = |x_offset: f64, y_offset: f64| {
@@ -298,37 +265,21 @@ fn gen_iso_dots_pattern(
             .close()
     };
 
-    let pattern = element::Definitions::new().add(
-        element::Pattern::new()
-            .set("id", pattern_id.as_str())
-            .set("x", 0_f64)
-            .set("y", 0_f64)
-            .set("width", pattern_width)
-            .set("height", spacing)
-            .set("patternUnits", "userSpaceOnUse")
-            .set("patternContentUnits", "userSpaceOnUse")
-            .add(
-                element::Path::new()
-                    .set("stroke", "none")
-                    .set("fill", color.to_css_color_attr())
-                    .set("d", hexagon_path(0.0, 0.0)),
-            )
-            .add(
-                element::Path::new()
-                    .set("stroke", "none")
-                    .set("fill", color.to_css_color_attr())
-                    .set("d", hexagon_path(pattern_width * 0.5, spacing * 0.5)),
-            ),
-    );
-
-    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
-    rect.assign("x", format!("{}px", bounds.mins[0]));
-    rect.assign("y", format!("{}px", bounds.mins[1]));
-    rect.assign("width", format!("{}px", bounds.extents()[0]));
-    rect.assign("height", format!("{}px", bounds.extents()[1]));
-
-    let group = element::Group::new().add(pattern).add(rect);
-    group.into()
+    let tile = element::Group::new()
+        .add(
+            element::Path::new()
+                .set("stroke", "none")
+                .set("fill", color.to_css_color_attr())
+                .set("d", hexagon_path(0.0, 0.0)),
+        )
+        .add(
+            element::Path::new()
+                .set("stroke", "none")
+                .set("fill", color.to_css_color_attr())
+                .set("d", hexagon_path(pattern_width * 0.5, spacing * 0.5)),
+        );
+
+    fill_with_tiles(bounds, na::vector![pattern_width, spacing], tile)
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -512,3 +463,23 @@ impl Background {
         background_svg.draw_to_cairo(cx)
     }
 }
+
+/// A background that overrides the document background for a single page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "page_background")]
+pub struct PageBackground {
+    /// The column and row of the page, counted from the origin in multiples of the format size.
+    #[serde(rename = "page")]
+    pub page: [i64; 2],
+    #[serde(rename = "background")]
+    pub background: Background,
+}
+
+impl Default for PageBackground {
+    fn default() -> Self {
+        Self {
+            page: [0, 0],
+            background: Background::default(),
+        }
+    }
+}
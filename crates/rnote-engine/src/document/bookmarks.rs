@@ -0,0 +1,45 @@
+// Imports
+use serde::{Deserialize, Serialize};
+
+/// A named position in the document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "bookmark")]
+pub struct Bookmark {
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The upper left corner of the view when the bookmark was added, in document coordinates.
+    #[serde(rename = "pos", with = "rnote_compose::serialize::na_vector2_f64_dp3")]
+    pub pos: na::Vector2<f64>,
+}
+
+impl Default for Bookmark {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            pos: na::Vector2::zeros(),
+        }
+    }
+}
+
+impl Bookmark {
+    pub fn new(name: String, pos: na::Vector2<f64>) -> Self {
+        Self { name, pos }
+    }
+}
+
+/// The kind of an outline entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineEntryKind {
+    /// A text stroke that is marked as heading.
+    Heading,
+    Bookmark,
+}
+
+/// An entry of the document outline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+    pub kind: OutlineEntryKind,
+    pub title: String,
+    /// The position the entry points to, in document coordinates.
+    pub pos: na::Vector2<f64>,
+}
@@ -0,0 +1,139 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+/// The orientation of a guide line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "guide_orientation")]
+pub enum GuideOrientation {
+    /// A horizontal line, dragged out of the top ruler.
+    #[serde(rename = "horizontal")]
+    Horizontal,
+    /// A vertical line, dragged out of the left ruler.
+    #[serde(rename = "vertical")]
+    Vertical,
+}
+
+impl GuideOrientation {
+    /// The index of the coordinate that is fixed by a guide with this orientation.
+    pub fn axis(&self) -> usize {
+        match self {
+            GuideOrientation::Horizontal => 1,
+            GuideOrientation::Vertical => 0,
+        }
+    }
+}
+
+/// A guide line spanning the entire document, used as a snap target.
+///
+/// Guides are not part of the content, so they are not rendered on export.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "guide")]
+pub struct Guide {
+    #[serde(rename = "orientation")]
+    pub orientation: GuideOrientation,
+    /// The position on the perpendicular axis in document coordinates.
+    #[serde(rename = "position", with = "rnote_compose::serialize::f64_dp3")]
+    pub position: f64,
+}
+
+impl Guide {
+    pub fn new(orientation: GuideOrientation, position: f64) -> Self {
+        Self {
+            orientation,
+            position,
+        }
+    }
+
+    /// The distance of the guide to the given position.
+    pub fn dist_to(&self, pos: na::Vector2<f64>) -> f64 {
+        (pos[self.orientation.axis()] - self.position).abs()
+    }
+
+    /// The line of the guide, clipped to the given bounds.
+    pub fn line_in_bounds(&self, bounds: Aabb) -> (na::Vector2<f64>, na::Vector2<f64>) {
+        match self.orientation {
+            GuideOrientation::Horizontal => (
+                na::vector![bounds.mins[0], self.position],
+                na::vector![bounds.maxs[0], self.position],
+            ),
+            GuideOrientation::Vertical => (
+                na::vector![self.position, bounds.mins[1]],
+                na::vector![self.position, bounds.maxs[1]],
+            ),
+        }
+    }
+}
+
+/// The guides of a document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "guides")]
+pub struct Guides {
+    #[serde(rename = "guides")]
+    guides: Vec<Guide>,
+}
+
+impl Guides {
+    /// The distance in document coordinates where positions get snapped to a guide.
+    pub const SNAP_DIST: f64 = 8.0;
+
+    pub fn iter(&self) -> impl Iterator<Item = &Guide> {
+        self.guides.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guides.is_empty()
+    }
+
+    /// Add a guide and return its index.
+    pub fn push(&mut self, guide: Guide) -> usize {
+        self.guides.push(guide);
+        self.guides.len() - 1
+    }
+
+    pub fn get(&self, i: usize) -> Option<&Guide> {
+        self.guides.get(i)
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut Guide> {
+        self.guides.get_mut(i)
+    }
+
+    pub fn remove(&mut self, i: usize) -> Option<Guide> {
+        (i < self.guides.len()).then(|| self.guides.remove(i))
+    }
+
+    pub fn clear(&mut self) {
+        self.guides.clear();
+    }
+
+    /// The index of the guide with the given orientation that is nearest to the position, if it is within the distance.
+    pub fn nearest(
+        &self,
+        orientation: GuideOrientation,
+        pos: na::Vector2<f64>,
+        max_dist: f64,
+    ) -> Option<usize> {
+        self.guides
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.orientation == orientation)
+            .map(|(i, g)| (i, g.dist_to(pos)))
+            .filter(|(_, dist)| *dist <= max_dist)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Snap the position to the nearest horizontal and vertical guides that are within the snap distance.
+    pub fn snap_position(&self, mut pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        for orientation in [GuideOrientation::Horizontal, GuideOrientation::Vertical] {
+            if let Some(guide) = self
+                .nearest(orientation, pos, Self::SNAP_DIST)
+                .and_then(|i| self.get(i))
+            {
+                pos[orientation.axis()] = guide.position;
+            }
+        }
+        pos
+    }
+}
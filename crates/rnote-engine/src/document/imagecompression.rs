@@ -0,0 +1,37 @@
+// Imports
+use serde::{Deserialize, Serialize};
+
+/// Options for compressing the embedded bitmap images of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename = "image_compression")]
+pub struct ImageCompression {
+    /// Compress the embedded bitmap images when the document is saved.
+    #[serde(rename = "compress_on_save")]
+    pub compress_on_save: bool,
+    /// The Jpeg quality, from 1 to 100.
+    #[serde(rename = "jpeg_quality")]
+    pub jpeg_quality: u8,
+    /// Images with a larger width or height in pixels are downscaled to it.
+    #[serde(rename = "max_dimension")]
+    pub max_dimension: u32,
+}
+
+impl Default for ImageCompression {
+    fn default() -> Self {
+        Self {
+            compress_on_save: false,
+            jpeg_quality: Self::JPEG_QUALITY_DEFAULT,
+            max_dimension: Self::MAX_DIMENSION_DEFAULT,
+        }
+    }
+}
+
+impl ImageCompression {
+    pub const JPEG_QUALITY_MIN: u8 = 1;
+    pub const JPEG_QUALITY_MAX: u8 = 100;
+    pub const JPEG_QUALITY_DEFAULT: u8 = 85;
+
+    pub const MAX_DIMENSION_MIN: u32 = 64;
+    pub const MAX_DIMENSION_MAX: u32 = 16384;
+    pub const MAX_DIMENSION_DEFAULT: u32 = 2048;
+}
@@ -0,0 +1,133 @@
+// Imports
+use crate::store::{StrokeKey, StrokeStore};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where a link leads to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "link_target")]
+pub enum LinkTarget {
+    /// An external URI, opened in the browser.
+    #[serde(rename = "uri")]
+    Uri(String),
+    /// A position in the document, for example the upper left corner of a page.
+    #[serde(rename = "position")]
+    Position(#[serde(with = "rnote_compose::serialize::na_vector2_f64_dp3")] na::Vector2<f64>),
+}
+
+/// A clickable region of the document that leads to a link target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "link")]
+pub struct Link {
+    /// The region in document coordinates.
+    ///
+    /// Links that are anchored to strokes cover the bounds of the strokes instead.
+    #[serde(rename = "bounds")]
+    pub bounds: Aabb,
+    #[serde(rename = "target")]
+    pub target: LinkTarget,
+    /// The strokes the link is anchored to.
+    #[serde(rename = "keys", default)]
+    pub keys: Vec<StrokeKey>,
+}
+
+impl Link {
+    pub fn new(bounds: Aabb, target: LinkTarget) -> Self {
+        Self {
+            bounds,
+            target,
+            keys: vec![],
+        }
+    }
+
+    /// A new link anchored to the strokes, so that it moves with them.
+    pub fn new_anchored(bounds: Aabb, target: LinkTarget, keys: Vec<StrokeKey>) -> Self {
+        Self {
+            bounds,
+            target,
+            keys,
+        }
+    }
+
+    /// The region the link currently covers.
+    ///
+    /// `None` for anchored links while all of their strokes are trashed or removed.
+    pub(crate) fn current_bounds(&self, store: &StrokeStore) -> Option<Aabb> {
+        if self.keys.is_empty() {
+            return Some(self.bounds);
+        }
+        let keys = self
+            .keys
+            .iter()
+            .copied()
+            .filter(|&key| store.trashed(key) == Some(false))
+            .collect::<Vec<StrokeKey>>();
+        store.bounds_for_strokes(&keys)
+    }
+}
+
+/// The links of a document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "links")]
+pub struct Links {
+    #[serde(rename = "links")]
+    links: Vec<Link>,
+}
+
+impl Links {
+    pub fn iter(&self) -> impl Iterator<Item = &Link> {
+        self.links.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    pub fn push(&mut self, link: Link) {
+        self.links.push(link);
+    }
+
+    /// The topmost link containing the position.
+    pub(crate) fn link_at(&self, pos: na::Vector2<f64>, store: &StrokeStore) -> Option<&Link> {
+        self.links.iter().rev().find(|link| {
+            link.current_bounds(store)
+                .is_some_and(|bounds| bounds.contains_local_point(&pos.into()))
+        })
+    }
+
+    /// Remove all links intersecting the bounds. Returns whether any link was removed.
+    pub(crate) fn remove_intersecting(&mut self, bounds: Aabb, store: &StrokeStore) -> bool {
+        let prev_len = self.links.len();
+        self.links.retain(|link| {
+            link.current_bounds(store)
+                .map_or(true, |link_bounds| !link_bounds.intersects(&bounds))
+        });
+        self.links.len() != prev_len
+    }
+
+    /// Replace the keys the links are anchored to with the mapped ones. Returns whether any key was replaced.
+    pub(crate) fn remap_keys(&mut self, key_map: &HashMap<StrokeKey, StrokeKey>) -> bool {
+        let mut remapped = false;
+        for key in self.links.iter_mut().flat_map(|link| link.keys.iter_mut()) {
+            if let Some(&new_key) = key_map.get(key) {
+                *key = new_key;
+                remapped = true;
+            }
+        }
+        remapped
+    }
+
+    /// The links with the region they currently cover, without the ones whose strokes are all trashed.
+    pub(crate) fn resolved(&self, store: &StrokeStore) -> Links {
+        Links {
+            links: self
+                .links
+                .iter()
+                .filter_map(|link| {
+                    Some(Link::new(link.current_bounds(store)?, link.target.clone()))
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,10 +1,20 @@
 // Modules
 pub mod background;
+pub mod bookmarks;
 pub mod format;
+pub mod guides;
+pub mod imagecompression;
+pub mod links;
+pub mod perspective;
 
 // Re-exports
-pub use background::Background;
+pub use background::{Background, PageBackground};
+pub use bookmarks::{Bookmark, OutlineEntry, OutlineEntryKind};
 pub use format::Format;
+pub use guides::Guides;
+pub use imagecompression::ImageCompression;
+pub use links::Links;
+pub use perspective::Perspective;
 
 // Imports
 use crate::{Camera, CloneConfig, StrokeStore, WidgetFlags};
@@ -103,10 +113,38 @@ pub struct Document {
     pub format: Format,
     #[serde(rename = "background")]
     pub background: Background,
+    /// Backgrounds of single pages that differ from the document background.
+    #[serde(rename = "page_backgrounds")]
+    pub page_backgrounds: Vec<PageBackground>,
     #[serde(rename = "layout", alias = "expand_mode")]
     pub layout: Layout,
     #[serde(rename = "snap_positions")]
     pub snap_positions: bool,
+    #[serde(rename = "guides")]
+    pub guides: Guides,
+    #[serde(rename = "links")]
+    pub links: Links,
+    #[serde(rename = "bookmarks")]
+    pub bookmarks: Vec<Bookmark>,
+    #[serde(rename = "perspective")]
+    pub perspective: Perspective,
+    /// The language used for spellchecking text. Spellchecking is disabled when `None`.
+    #[serde(rename = "spellcheck_language")]
+    pub spellcheck_language: Option<String>,
+    #[serde(rename = "image_compression")]
+    pub image_compression: ImageCompression,
+    /// The most used colors of the document, offered as document palette.
+    #[serde(rename = "palette")]
+    pub palette: Vec<Color>,
+}
+
+/// The part of the document state that is kept in the history, so that it is restored on undo and redo.
+#[derive(Debug, Clone)]
+pub(crate) struct DocumentHistoryState {
+    pub(crate) height: f64,
+    pub(crate) page_backgrounds: Vec<PageBackground>,
+    pub(crate) bookmarks: Vec<Bookmark>,
+    pub(crate) links: Links,
 }
 
 impl Default for Document {
@@ -118,15 +156,32 @@ impl Default for Document {
             height: Format::default().height(),
             format: Format::default(),
             background: Background::default(),
+            page_backgrounds: vec![],
             layout: Layout::default(),
             snap_positions: false,
+            guides: Guides::default(),
+            links: Links::default(),
+            bookmarks: vec![],
+            perspective: Perspective::default(),
+            spellcheck_language: None,
+            image_compression: ImageCompression::default(),
+            palette: vec![],
         }
     }
 }
 
 impl CloneConfig for Document {
     fn clone_config(&self) -> Self {
-        self.clone()
+        // Page backgrounds, guides, links, bookmarks, the perspective and the palette belong to the specific document
+        Self {
+            page_backgrounds: vec![],
+            guides: Guides::default(),
+            links: Links::default(),
+            bookmarks: vec![],
+            perspective: Perspective::default(),
+            palette: vec![],
+            ..self.clone()
+        }
     }
 }
 
@@ -139,14 +194,78 @@ impl Document {
         b: 0.0,
         a: 0.35,
     };
+    /// The maximum number of colors in the document palette.
+    pub const PALETTE_SIZE: usize = 6;
+
+    /// The state that is kept in the history.
+    pub(crate) fn history_state(&self) -> DocumentHistoryState {
+        DocumentHistoryState {
+            height: self.height,
+            page_backgrounds: self.page_backgrounds.clone(),
+            bookmarks: self.bookmarks.clone(),
+            links: self.links.clone(),
+        }
+    }
+
+    /// Restore the state from the history.
+    pub(crate) fn import_history_state(&mut self, state: &DocumentHistoryState) {
+        self.height = state.height;
+        self.page_backgrounds.clone_from(&state.page_backgrounds);
+        self.bookmarks.clone_from(&state.bookmarks);
+        self.links = state.links.clone();
+    }
 
-    pub(crate) fn bounds(&self) -> Aabb {
+    pub fn bounds(&self) -> Aabb {
         Aabb::new(
             na::point![self.x, self.y],
             na::point![self.x + self.width, self.y + self.height],
         )
     }
 
+    /// The column and row of the page at the given position.
+    ///
+    /// Returns None when the format has no valid size.
+    pub fn page_at(&self, pos: na::Vector2<f64>) -> Option<[i64; 2]> {
+        let size = self.format.size();
+        if size[0] <= 0.0 || size[1] <= 0.0 {
+            return None;
+        }
+        Some([
+            (pos[0] / size[0]).floor() as i64,
+            (pos[1] / size[1]).floor() as i64,
+        ])
+    }
+
+    /// The bounds of the page with the given column and row.
+    pub fn page_bounds(&self, page: [i64; 2]) -> Aabb {
+        let size = self.format.size();
+        let mins = na::point![page[0] as f64 * size[0], page[1] as f64 * size[1]];
+        Aabb::new(mins, mins + size)
+    }
+
+    /// The background of the given page, either the page background override or the document background.
+    pub fn background_for_page(&self, page: [i64; 2]) -> Background {
+        self.page_backgrounds
+            .iter()
+            .find(|page_background| page_background.page == page)
+            .map(|page_background| page_background.background)
+            .unwrap_or(self.background)
+    }
+
+    /// The page background overrides that intersect the given bounds, with the bounds of their page.
+    pub(crate) fn page_backgrounds_intersecting(&self, bounds: Aabb) -> Vec<(Aabb, Background)> {
+        self.page_backgrounds
+            .iter()
+            .filter_map(|page_background| {
+                let page_bounds = self.page_bounds(page_background.page);
+                // Pages that only touch the bounds at the edges are excluded
+                let overlap = page_bounds.intersection(&bounds)?.extents();
+                (overlap[0] > 0.0 && overlap[1] > 0.0)
+                    .then_some((page_bounds, page_background.background))
+            })
+            .collect()
+    }
+
     /// Generate bounds for each page for the doc bounds, extended to fit the format.
     ///
     /// May contain many empty pages (in infinite mode)
@@ -404,8 +523,12 @@ impl Document {
 
     /// Snap the position to the document and pattern grid when `snap_positions` is enabled.
     ///
-    /// If not, the original coordinates are returned.
+    /// Positions near a guide are always snapped to it, taking precedence over the grid.
     pub(crate) fn snap_position(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        self.guides.snap_position(self.snap_position_to_grid(pos))
+    }
+
+    fn snap_position_to_grid(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
         const DOCUMENT_SNAP_DIST: f64 = 10.;
         let doc_format_size = self.format.size();
         let pattern_size = self.background.pattern_size;
@@ -0,0 +1,139 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+/// The number of vanishing points of the perspective guides.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "perspective_mode")]
+pub enum PerspectiveMode {
+    /// No perspective guides.
+    #[serde(rename = "off")]
+    Off = 0,
+    #[serde(rename = "one_point")]
+    OnePoint,
+    #[serde(rename = "two_point")]
+    TwoPoint,
+    #[serde(rename = "three_point")]
+    ThreePoint,
+}
+
+impl Default for PerspectiveMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl TryFrom<u32> for PerspectiveMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "PerspectiveMode try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl PerspectiveMode {
+    pub fn n_vanishing_points(&self) -> usize {
+        match self {
+            PerspectiveMode::Off => 0,
+            PerspectiveMode::OnePoint => 1,
+            PerspectiveMode::TwoPoint => 2,
+            PerspectiveMode::ThreePoint => 3,
+        }
+    }
+}
+
+/// Perspective guide lines converging in placeable vanishing points.
+///
+/// Like the guides, they are not part of the content and are not rendered on export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "perspective")]
+pub struct Perspective {
+    #[serde(rename = "mode")]
+    pub mode: PerspectiveMode,
+    /// The vanishing points in document coordinates.
+    ///
+    /// Points that are unused in the current mode are kept, so switching modes doesn't lose their position.
+    #[serde(rename = "vanishing_points")]
+    vanishing_points: Vec<na::Vector2<f64>>,
+    /// Constrain straight lines drawn with the shaper toward the nearest vanishing point.
+    #[serde(rename = "constrain_lines")]
+    pub constrain_lines: bool,
+}
+
+impl Perspective {
+    /// The distance in surface coordinates at which a vanishing point can be grabbed.
+    pub const GRAB_DIST: f64 = 10.0;
+    /// The angle between two guide lines from the same vanishing point, in degrees.
+    pub const LINES_ANGLE_STEP: f64 = 5.0;
+
+    /// The vanishing points that are used in the current mode.
+    pub fn vanishing_points(&self) -> &[na::Vector2<f64>] {
+        let n = self
+            .mode
+            .n_vanishing_points()
+            .min(self.vanishing_points.len());
+        &self.vanishing_points[..n]
+    }
+
+    /// Set the mode and place the missing vanishing points in a default layout inside the viewport.
+    pub fn set_mode(&mut self, mode: PerspectiveMode, viewport: Aabb) {
+        let center = viewport.center().coords;
+        let extents = viewport.extents();
+        let n = mode.n_vanishing_points();
+        // The horizon is placed in the middle of the viewport, the third point below it
+        let defaults = if n == 1 {
+            vec![center]
+        } else {
+            vec![
+                na::vector![viewport.maxs[0] - extents[0] * 0.1, center[1]],
+                na::vector![viewport.mins[0] + extents[0] * 0.1, center[1]],
+                na::vector![center[0], viewport.maxs[1] - extents[1] * 0.1],
+            ]
+        };
+
+        if self.vanishing_points.len() < n {
+            let n_placed = self.vanishing_points.len();
+            self.vanishing_points
+                .extend_from_slice(&defaults[n_placed..n]);
+        }
+        self.mode = mode;
+    }
+
+    /// Place all vanishing points of the current mode in the default layout inside the viewport.
+    pub fn reset_vanishing_points(&mut self, viewport: Aabb) {
+        self.vanishing_points.clear();
+        self.set_mode(self.mode, viewport);
+    }
+
+    /// The index of the vanishing point that is nearest to the position, if it is within the distance.
+    pub fn nearest_vanishing_point(&self, pos: na::Vector2<f64>, max_dist: f64) -> Option<usize> {
+        self.vanishing_points()
+            .iter()
+            .enumerate()
+            .map(|(i, vp)| (i, (vp - pos).norm()))
+            .filter(|(_, dist)| *dist <= max_dist)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    pub fn set_vanishing_point(&mut self, i: usize, pos: na::Vector2<f64>) {
+        if let Some(vp) = self.vanishing_points.get_mut(i) {
+            *vp = pos;
+        }
+    }
+}
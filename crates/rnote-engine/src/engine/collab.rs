@@ -0,0 +1,381 @@
+// Imports
+use super::Engine;
+use crate::store::chrono_comp::StrokeLayer;
+use crate::store::StrokeKey;
+use crate::strokes::Stroke;
+use crate::{StrokeStore, WidgetFlags};
+use rnote_compose::color;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// The number of operations per message when sending the history to peers that joined.
+const HISTORY_CHUNK_SIZE: usize = 64;
+
+/// Identifies an operation across all peers.
+///
+/// Ordered by the Lamport timestamp first, so that operations are ordered consistently on all peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename = "op_id")]
+pub struct OpId {
+    #[serde(rename = "lamport")]
+    pub lamport: u64,
+    #[serde(rename = "peer")]
+    pub peer: u32,
+}
+
+/// An operation on the shared strokes.
+///
+/// Strokes are never modified in place. A modification removes the old stroke and inserts the new one, so the
+/// operations can be applied in any order and every peer ends up with the same strokes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "collab_op")]
+pub enum CollabOp {
+    #[serde(rename = "insert")]
+    Insert {
+        #[serde(rename = "id")]
+        id: OpId,
+        #[serde(rename = "stroke")]
+        stroke: Arc<Stroke>,
+        #[serde(rename = "layer")]
+        layer: Option<StrokeLayer>,
+    },
+    /// Removes the stroke that was inserted by the target operation. Stays as tombstone once applied.
+    #[serde(rename = "remove")]
+    Remove {
+        #[serde(rename = "id")]
+        id: OpId,
+        #[serde(rename = "target")]
+        target: OpId,
+    },
+}
+
+impl CollabOp {
+    pub fn id(&self) -> OpId {
+        match self {
+            Self::Insert { id, .. } | Self::Remove { id, .. } => *id,
+        }
+    }
+}
+
+/// The messages that are exchanged between peers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "collab_message")]
+pub enum CollabMessage {
+    /// Operations on the shared strokes.
+    #[serde(rename = "ops")]
+    Ops {
+        #[serde(rename = "ops")]
+        ops: Vec<CollabOp>,
+    },
+    /// The cursor of a peer moved. `None` when it left the canvas.
+    #[serde(rename = "cursor")]
+    Cursor {
+        #[serde(rename = "peer")]
+        peer: u32,
+        #[serde(rename = "name")]
+        name: String,
+        #[serde(rename = "pos")]
+        pos: Option<na::Vector2<f64>>,
+    },
+    /// A peer left the session.
+    #[serde(rename = "leave")]
+    Leave {
+        #[serde(rename = "peer")]
+        peer: u32,
+    },
+}
+
+/// The cursor of a remote peer.
+#[derive(Debug, Clone)]
+pub struct RemoteCursor {
+    pub name: String,
+    pub pos: na::Vector2<f64>,
+    pub color: piet::Color,
+}
+
+/// A collaboration session, syncing the strokes of the store with remote peers.
+///
+/// Local changes are detected by comparing the strokes in the store with the ones that were last synced,
+/// so that every way of modifying the store (pens, undo, imports, ..) is covered.
+#[derive(Debug, Clone)]
+pub struct CollabSession {
+    peer: u32,
+    name: String,
+    lamport: u64,
+    /// All applied operations, for syncing peers that join later.
+    log: Vec<CollabOp>,
+    applied: HashSet<OpId>,
+    tombstones: HashSet<OpId>,
+    /// The synced strokes and the operations that inserted them.
+    ///
+    /// Strokes are identified by their Arc's, because the keys in the store change with undo and redo.
+    synced: HashMap<OpId, Arc<Stroke>>,
+    remote_cursors: HashMap<u32, RemoteCursor>,
+}
+
+impl CollabSession {
+    pub fn new(name: String) -> Self {
+        Self {
+            peer: rand::random(),
+            name,
+            lamport: 0,
+            log: vec![],
+            applied: HashSet::new(),
+            tombstones: HashSet::new(),
+            synced: HashMap::new(),
+            remote_cursors: HashMap::new(),
+        }
+    }
+
+    pub fn peer(&self) -> u32 {
+        self.peer
+    }
+
+    pub fn remote_cursors(&self) -> impl Iterator<Item = &RemoteCursor> {
+        self.remote_cursors.values()
+    }
+
+    fn next_op_id(&mut self) -> OpId {
+        self.lamport += 1;
+        OpId {
+            lamport: self.lamport,
+            peer: self.peer,
+        }
+    }
+
+    /// Creates the operations for the strokes that were inserted, modified or removed since the last sync.
+    fn collect_local_ops(&mut self, store: &StrokeStore) -> Vec<CollabOp> {
+        let mut ops = vec![];
+        // Modifying a stroke in the store creates a new Arc, because the synced one still references it
+        let current_strokes = store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| Some((key, store.get_stroke_arc(key)?)))
+            .collect::<Vec<(StrokeKey, Arc<Stroke>)>>();
+        let current_ptrs = current_strokes
+            .iter()
+            .map(|(_, stroke)| Arc::as_ptr(stroke))
+            .collect::<HashSet<*const Stroke>>();
+        let synced_ptrs = self
+            .synced
+            .values()
+            .map(Arc::as_ptr)
+            .collect::<HashSet<*const Stroke>>();
+
+        let removed = self
+            .synced
+            .iter()
+            .filter(|(_, stroke)| !current_ptrs.contains(&Arc::as_ptr(stroke)))
+            .map(|(target, _)| *target)
+            .collect::<Vec<OpId>>();
+        for target in removed {
+            self.synced.remove(&target);
+            let id = self.next_op_id();
+            ops.push(CollabOp::Remove { id, target });
+        }
+
+        for (key, stroke) in current_strokes {
+            if synced_ptrs.contains(&Arc::as_ptr(&stroke)) {
+                continue;
+            }
+            let id = self.next_op_id();
+            self.synced.insert(id, Arc::clone(&stroke));
+            ops.push(CollabOp::Insert {
+                id,
+                stroke,
+                layer: store.stroke_layer(key),
+            });
+        }
+
+        for op in ops.iter() {
+            self.applied.insert(op.id());
+            if let CollabOp::Remove { target, .. } = op {
+                self.tombstones.insert(*target);
+            }
+        }
+        self.log.extend(ops.iter().cloned());
+        ops
+    }
+
+    /// Applies the remote operations to the store. Operations that were already applied are skipped.
+    ///
+    /// Remote changes are not undoable locally, so they are applied outside of the history.
+    /// Returns the keys of the strokes that were inserted or trashed.
+    fn apply_remote_ops(&mut self, store: &mut StrokeStore, ops: Vec<CollabOp>) -> Vec<StrokeKey> {
+        let mut ops = ops
+            .into_iter()
+            .filter(|op| !self.applied.contains(&op.id()))
+            .collect::<Vec<CollabOp>>();
+        ops.sort_by_key(|op| op.id());
+
+        let mut inserted = vec![];
+        let mut removed = vec![];
+        for op in ops {
+            self.lamport = self.lamport.max(op.id().lamport);
+            self.applied.insert(op.id());
+            match &op {
+                CollabOp::Insert { id, stroke, layer } => {
+                    // The stroke might have already been removed by an operation that arrived earlier
+                    if !self.tombstones.contains(id) {
+                        let stroke = detach_remote_connector(stroke);
+                        self.synced.insert(*id, Arc::clone(&stroke));
+                        inserted.push((*id, stroke, *layer));
+                    }
+                }
+                CollabOp::Remove { target, .. } => {
+                    self.tombstones.insert(*target);
+                    if let Some(stroke) = self.synced.remove(target) {
+                        // Strokes that are inserted and removed in the same batch never reach the store
+                        if let Some(i) = inserted.iter().position(|(id, ..)| id == target) {
+                            inserted.remove(i);
+                        } else {
+                            removed.push(stroke);
+                        }
+                    }
+                }
+            }
+            self.log.push(op);
+        }
+
+        let mut keys = store.trash_strokes_unrecorded(&removed);
+        keys.extend(
+            store.insert_new_strokes_unrecorded(
+                inserted
+                    .into_iter()
+                    .map(|(_, stroke, layer)| (stroke, layer))
+                    .collect(),
+            ),
+        );
+        keys
+    }
+
+    fn update_remote_cursor(&mut self, peer: u32, name: String, pos: Option<na::Vector2<f64>>) {
+        let Some(pos) = pos else {
+            self.remote_cursors.remove(&peer);
+            return;
+        };
+        let palette = [
+            color::GNOME_BLUES[3],
+            color::GNOME_GREENS[4],
+            color::GNOME_ORANGES[3],
+            color::GNOME_PURPLES[3],
+            color::GNOME_REDS[3],
+        ];
+        self.remote_cursors.insert(
+            peer,
+            RemoteCursor {
+                name,
+                pos,
+                color: palette[peer as usize % palette.len()],
+            },
+        );
+    }
+}
+
+impl Engine {
+    /// Whether a collaboration session is active.
+    pub fn collab_active(&self) -> bool {
+        self.collab_session.is_some()
+    }
+
+    /// Starts a collaboration session. The current strokes are shared with the peers on the next sync,
+    /// so the remaining strokes of a lazily loaded document are loaded first.
+    ///
+    /// `name` is shown next to the cursor on the canvas of the peers.
+    pub fn start_collab_session(&mut self, name: String) -> WidgetFlags {
+        let widget_flags = self.load_pending_strokes();
+        self.collab_session = Some(CollabSession::new(name));
+        widget_flags
+    }
+
+    pub fn stop_collab_session(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.collab_session = None;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// The local changes since the last sync, to be sent to the peers.
+    pub fn collab_local_changes(&mut self) -> Option<CollabMessage> {
+        let session = self.collab_session.as_mut()?;
+        let ops = session.collect_local_ops(&self.store);
+        if ops.is_empty() {
+            return None;
+        }
+        Some(CollabMessage::Ops { ops })
+    }
+
+    /// All operations of the session, to be sent to peers that joined.
+    ///
+    /// Split into multiple messages, so that they stay below the message size limit of the transport.
+    pub fn collab_history(&mut self) -> Vec<CollabMessage> {
+        // Local changes that were not yet synced must be part of the history
+        let _ = self.collab_local_changes();
+        let Some(session) = self.collab_session.as_ref() else {
+            return vec![];
+        };
+        session
+            .log
+            .chunks(HISTORY_CHUNK_SIZE)
+            .map(|ops| CollabMessage::Ops { ops: ops.to_vec() })
+            .collect()
+    }
+
+    /// The current cursor position, to be sent to the peers.
+    pub fn collab_cursor(&self) -> Option<CollabMessage> {
+        let session = self.collab_session.as_ref()?;
+        Some(CollabMessage::Cursor {
+            peer: session.peer,
+            name: session.name.clone(),
+            pos: self.cursor_pos,
+        })
+    }
+
+    /// The message announcing that this peer leaves the session.
+    pub fn collab_leave(&self) -> Option<CollabMessage> {
+        let session = self.collab_session.as_ref()?;
+        Some(CollabMessage::Leave { peer: session.peer })
+    }
+
+    /// Handles a message received from a peer.
+    pub fn collab_handle_message(&mut self, message: CollabMessage) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(session) = self.collab_session.as_mut() else {
+            return widget_flags;
+        };
+        match message {
+            CollabMessage::Ops { ops } => {
+                let keys = session.apply_remote_ops(&mut self.store, ops);
+                if !keys.is_empty() {
+                    self.store.set_rendering_dirty_for_strokes(&keys);
+                    widget_flags |=
+                        self.doc_resize_autoexpand() | self.update_rendering_current_viewport();
+                    widget_flags.store_modified = true;
+                }
+            }
+            CollabMessage::Cursor { peer, name, pos } => {
+                session.update_remote_cursor(peer, name, pos);
+                widget_flags.redraw = true;
+            }
+            CollabMessage::Leave { peer } => {
+                session.remote_cursors.remove(&peer);
+                widget_flags.redraw = true;
+            }
+        }
+        widget_flags
+    }
+}
+
+/// Connectors of peers are inserted detached, the keys of the strokes they are attached to are only valid in the
+/// store of the peer.
+fn detach_remote_connector(stroke: &Arc<Stroke>) -> Arc<Stroke> {
+    match stroke.as_ref() {
+        Stroke::ConnectorStroke(connector) if connector.attachments().next().is_some() => {
+            let mut connector = connector.clone();
+            connector.detach(|_| true);
+            Arc::new(Stroke::ConnectorStroke(connector))
+        }
+        _ => Arc::clone(stroke),
+    }
+}
@@ -0,0 +1,194 @@
+// Imports
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A filter applied to the canvas that simulates a color vision deficiency.
+///
+/// Only used for previewing, it does not alter the colors of the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorVisionFilter {
+    #[default]
+    None,
+    /// Red-blindness.
+    Protanopia,
+    /// Green-blindness.
+    Deuteranopia,
+}
+
+impl std::str::FromStr for ColorVisionFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "protanopia" => Ok(Self::Protanopia),
+            "deuteranopia" => Ok(Self::Deuteranopia),
+            s => Err(anyhow::anyhow!(
+                "ColorVisionFilter from_string failed, invalid name: {s}"
+            )),
+        }
+    }
+}
+
+impl Display for ColorVisionFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorVisionFilter::None => write!(f, "none"),
+            ColorVisionFilter::Protanopia => write!(f, "protanopia"),
+            ColorVisionFilter::Deuteranopia => write!(f, "deuteranopia"),
+        }
+    }
+}
+
+impl ColorVisionFilter {
+    /// The simulation matrix that is applied to the rgb components, with full severity.
+    ///
+    /// From Machado, Oliveira and Fernandes: "A Physiologically-based Model for Simulation of Color Vision
+    /// Deficiency" (2009). The matrices are applied to the gamma encoded components, which is a commonly used
+    /// approximation that is good enough for previewing.
+    pub fn rgb_matrix(&self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            ColorVisionFilter::None => None,
+            ColorVisionFilter::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.097216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            ColorVisionFilter::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+        }
+    }
+
+    /// The color matrix and offset for pushing the filter onto a GTK snapshot.
+    #[cfg(feature = "ui")]
+    pub fn gtk_color_matrix(&self) -> Option<(gtk4::graphene::Matrix, gtk4::graphene::Vec4)> {
+        let [r, g, b] = self.rgb_matrix()?;
+        // Gsk applies the transposed color matrix to the pixels
+        let matrix = gtk4::graphene::Matrix::from_float([
+            r[0], r[1], r[2], 0.0, g[0], g[1], g[2], 0.0, b[0], b[1], b[2], 0.0, 0.0, 0.0, 0.0, 1.0,
+        ])
+        .transpose();
+        Some((matrix, gtk4::graphene::Vec4::zero()))
+    }
+}
+
+/// How the contrast of the strokes against the background is enforced when drawing the canvas.
+///
+/// Only applied to the view, it does not alter the colors of the document.
+#[derive(
+    Debug,
+    Eq,
+    PartialEq,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "contrast_mode")]
+pub enum ContrastMode {
+    #[serde(rename = "normal")]
+    Normal = 0,
+    /// Strokes that have less than the minimum contrast ratio are darkened on light and lightened on dark
+    /// backgrounds until they reach it.
+    #[serde(rename = "high_contrast")]
+    HighContrast,
+    /// Strokes are drawn in black on light and in white on dark backgrounds. Filled strokes keep their colors and
+    /// are adjusted like in high contrast mode.
+    #[serde(rename = "forced_colors")]
+    ForcedColors,
+}
+
+impl Default for ContrastMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl TryFrom<u32> for ContrastMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("ContrastMode try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+impl ContrastMode {
+    /// The minimum contrast ratio of the strokes in high contrast mode, the WCAG AA level for text.
+    pub const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+    /// The color matrix and offset for pushing the contrast adjustment of a stroke with the given colors onto a GTK
+    /// snapshot.
+    ///
+    /// In high contrast mode the color of the stroke with the highest contrast is considered, so that for example a
+    /// light fill is not darkened as long as the outline is readable. Filled strokes are adjusted the same way in
+    /// forced colors mode, because forcing a single color onto them would merge their fill with their outline and
+    /// text. None if nothing needs to be adjusted.
+    #[cfg(feature = "ui")]
+    pub fn gtk_color_matrix(
+        &self,
+        stroke_colors: &[rnote_compose::Color],
+        filled: bool,
+        background_color: rnote_compose::Color,
+    ) -> Option<(gtk4::graphene::Matrix, gtk4::graphene::Vec4)> {
+        use gtk4::graphene;
+        use rnote_compose::Color;
+
+        // Black or white, whichever has more contrast
+        let darken = Color::BLACK.contrast_ratio(background_color)
+            >= Color::WHITE.contrast_ratio(background_color);
+        // Scales the components towards black or white, keeping the alpha
+        let color_matrix = |factor: f64| {
+            let offset = if darken { 0.0 } else { 1.0 - factor as f32 };
+            (
+                graphene::Matrix::new_scale(factor as f32, factor as f32, factor as f32),
+                graphene::Vec4::new(offset, offset, offset, 0.0),
+            )
+        };
+
+        let high_contrast_matrix = || {
+            let stroke_color = stroke_colors.iter().copied().max_by(|a, b| {
+                a.contrast_ratio(background_color)
+                    .total_cmp(&b.contrast_ratio(background_color))
+            })?;
+            if stroke_color.contrast_ratio(background_color) >= Self::MIN_CONTRAST_RATIO {
+                return None;
+            }
+            let adjusted = |factor: f64| {
+                let offset = if darken { 0.0 } else { 1.0 - factor };
+                Color::new(
+                    stroke_color.r * factor + offset,
+                    stroke_color.g * factor + offset,
+                    stroke_color.b * factor + offset,
+                    stroke_color.a,
+                )
+            };
+            // Black and white always reach the minimum contrast ratio, so the factor is searched between them
+            // and the original color.
+            let (mut lower, mut upper) = (0.0, 1.0);
+            for _ in 0..16 {
+                let factor = (lower + upper) * 0.5;
+                if adjusted(factor).contrast_ratio(background_color) >= Self::MIN_CONTRAST_RATIO {
+                    lower = factor;
+                } else {
+                    upper = factor;
+                }
+            }
+            Some(color_matrix(lower))
+        };
+
+        match self {
+            ContrastMode::Normal => None,
+            ContrastMode::HighContrast => high_contrast_matrix(),
+            ContrastMode::ForcedColors if filled => high_contrast_matrix(),
+            // Strokes without colors are images, which would become silhouettes
+            ContrastMode::ForcedColors => (!stroke_colors.is_empty()).then(|| color_matrix(0.0)),
+        }
+    }
+}
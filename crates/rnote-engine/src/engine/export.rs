@@ -1,14 +1,23 @@
 // Imports
-use super::{Engine, EngineConfig, StrokeContent};
+use super::{Engine, EngineConfig, EngineSnapshot, Progress, StrokeContent};
+use crate::document::format::MeasureUnit;
+use crate::document::links::{LinkTarget, Links};
+use crate::document::OutlineEntry;
 use crate::fileformats::rnoteformat::RnoteFile;
 use crate::fileformats::{xoppformat, FileFormatSaver};
+use crate::strokes::Stroke;
 use crate::CloneConfig;
 use anyhow::Context;
 use futures::channel::oneshot;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
 use rayon::prelude::*;
+use rnote_compose::ext::AabbExt;
 use rnote_compose::transform::Transformable;
 use rnote_compose::SplitOrder;
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use std::sync::Arc;
 use tracing::error;
 
@@ -35,6 +44,8 @@ pub enum DocExportFormat {
     Pdf,
     #[serde(rename = "xopp")]
     Xopp,
+    #[serde(rename = "html")]
+    Html,
 }
 
 impl Default for DocExportFormat {
@@ -63,6 +74,7 @@ impl DocExportFormat {
             DocExportFormat::Svg => String::from("svg"),
             DocExportFormat::Pdf => String::from("pdf"),
             DocExportFormat::Xopp => String::from("xopp"),
+            DocExportFormat::Html => String::from("html"),
         }
     }
 }
@@ -86,6 +98,9 @@ pub struct DocExportPrefs {
     /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
     #[serde(rename = "page_order")]
     pub page_order: SplitOrder,
+    /// The header and footer that are added to the pages of Pdf exports and printed pages.
+    #[serde(rename = "header_footer")]
+    pub header_footer: PageHeaderFooter,
 }
 
 impl Default for DocExportPrefs {
@@ -96,6 +111,7 @@ impl Default for DocExportPrefs {
             optimize_printing: false,
             export_format: DocExportFormat::default(),
             page_order: SplitOrder::default(),
+            header_footer: PageHeaderFooter::default(),
         }
     }
 }
@@ -104,6 +120,93 @@ impl DocExportPrefs {
     const MARGIN: f64 = 0.0;
 }
 
+/// A header and footer line that is added on top of the exported or printed pages.
+///
+/// Only drawn into the output, the document content is not changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename = "page_header_footer")]
+pub struct PageHeaderFooter {
+    /// Whether the document title is shown on the left of the header.
+    #[serde(rename = "header_title")]
+    pub header_title: bool,
+    /// Whether the export date is shown on the right of the header.
+    #[serde(rename = "header_date")]
+    pub header_date: bool,
+    /// Whether the page number and the number of pages are shown in the center of the footer.
+    #[serde(rename = "footer_page_numbers")]
+    pub footer_page_numbers: bool,
+}
+
+impl PageHeaderFooter {
+    const FONT_SIZE: f64 = 9.0;
+    /// The distance of the text to the page edges.
+    const EDGE_DISTANCE: f64 = 20.0;
+    const TEXT_COLOR: piet::Color = piet::Color::rgba8(0x60, 0x60, 0x60, 0xff);
+
+    /// Whether nothing is shown.
+    pub fn is_empty(&self) -> bool {
+        !(self.header_title || self.header_date || self.footer_page_numbers)
+    }
+
+    /// The date that is shown in the header.
+    pub fn date_now() -> String {
+        chrono::Local::now().format("%Y-%m-%d").to_string()
+    }
+
+    /// Draw the header and footer of a page with the given size.
+    ///
+    /// Expects the origin of the context at the top left corner of the page. `page_no` starts at 1.
+    pub fn draw_to_cairo(
+        &self,
+        cairo_cx: &cairo::Context,
+        page_size: na::Vector2<f64>,
+        title: &str,
+        date: &str,
+        page_no: usize,
+        n_pages: usize,
+    ) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let mut piet_cx = piet_cairo::CairoRenderContext::new(cairo_cx);
+        let max_width = (page_size[0] - 2.0 * Self::EDGE_DISTANCE).max(0.0);
+        let mut draw_line =
+            |text: String, alignment: piet::TextAlignment, top: bool| -> anyhow::Result<()> {
+                let text_layout = piet_cx
+                    .text()
+                    .new_text_layout(text)
+                    .font(piet::FontFamily::SANS_SERIF, Self::FONT_SIZE)
+                    .text_color(Self::TEXT_COLOR)
+                    .max_width(max_width)
+                    .alignment(alignment)
+                    .build()
+                    .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                let y = if top {
+                    Self::EDGE_DISTANCE
+                } else {
+                    page_size[1] - Self::EDGE_DISTANCE - text_layout.size().height
+                };
+                piet_cx.draw_text(&text_layout, (Self::EDGE_DISTANCE, y));
+                Ok(())
+            };
+
+        if self.header_title {
+            draw_line(title.to_string(), piet::TextAlignment::Start, true)?;
+        }
+        if self.header_date {
+            draw_line(date.to_string(), piet::TextAlignment::End, true)?;
+        }
+        if self.footer_page_numbers {
+            draw_line(
+                format!("{page_no} / {n_pages}"),
+                piet::TextAlignment::Center,
+                false,
+            )?;
+        }
+        piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))
+    }
+}
+
 /// Document pages export format.
 #[derive(
     Debug,
@@ -127,6 +230,10 @@ pub enum DocPagesExportFormat {
     Png,
     #[serde(rename = "jpeg")]
     Jpeg,
+    #[serde(rename = "html")]
+    Html,
+    #[serde(rename = "eps")]
+    Eps,
 }
 
 impl Default for DocPagesExportFormat {
@@ -154,6 +261,8 @@ impl DocPagesExportFormat {
             Self::Svg => String::from("svg"),
             Self::Png => String::from("png"),
             Self::Jpeg => String::from("jpg"),
+            Self::Html => String::from("html"),
+            Self::Eps => String::from("eps"),
         }
     }
 }
@@ -203,6 +312,63 @@ impl Default for DocPagesExportPrefs {
     }
 }
 
+/// A selection of document pages, parsed from a comma separated list of page numbers and ranges like `1,3,5-7`.
+///
+/// Page numbers start at one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSelection {
+    ranges: Vec<RangeInclusive<usize>>,
+}
+
+impl FromStr for PageSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_number = |number: &str| {
+            number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid page number `{number}`."))
+        };
+        let ranges = s
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse_number(start)?, parse_number(end)?);
+                    if start > end {
+                        return Err(anyhow::anyhow!("Invalid page range `{part}`."));
+                    }
+                    Ok(start..=end)
+                }
+                None => {
+                    let number = parse_number(part)?;
+                    Ok(number..=number)
+                }
+            })
+            .collect::<anyhow::Result<Vec<RangeInclusive<usize>>>>()?;
+        if ranges.is_empty() {
+            return Err(anyhow::anyhow!("Page selection is empty."));
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl PageSelection {
+    /// Whether the page with the given index, starting at zero, is selected.
+    pub fn contains_index(&self, index: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(&(index + 1)))
+    }
+
+    /// The indices of the selected pages out of the given number of pages.
+    pub fn indices(&self, n_pages: usize) -> Vec<usize> {
+        (0..n_pages).filter(|i| self.contains_index(*i)).collect()
+    }
+}
+
 /// Selection export format.
 #[derive(
     Debug,
@@ -226,6 +392,10 @@ pub enum SelectionExportFormat {
     Png,
     #[serde(rename = "jpeg")]
     Jpeg,
+    #[serde(rename = "html")]
+    Html,
+    #[serde(rename = "eps")]
+    Eps,
 }
 
 impl Default for SelectionExportFormat {
@@ -240,6 +410,8 @@ impl SelectionExportFormat {
             SelectionExportFormat::Svg => String::from("svg"),
             SelectionExportFormat::Png => String::from("png"),
             SelectionExportFormat::Jpeg => String::from("jpg"),
+            SelectionExportFormat::Html => String::from("html"),
+            SelectionExportFormat::Eps => String::from("eps"),
         }
     }
 }
@@ -297,6 +469,173 @@ impl Default for SelectionExportPrefs {
     }
 }
 
+/// Figure export format.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "figure_export_format")]
+pub enum FigureExportFormat {
+    #[serde(rename = "pdf")]
+    Pdf,
+    #[serde(rename = "svg")]
+    Svg,
+}
+
+impl Default for FigureExportFormat {
+    fn default() -> Self {
+        Self::Pdf
+    }
+}
+
+impl TryFrom<u32> for FigureExportFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "FigureExportFormat try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl FigureExportFormat {
+    pub fn file_ext(self) -> String {
+        match self {
+            Self::Pdf => String::from("pdf"),
+            Self::Svg => String::from("svg"),
+        }
+    }
+}
+
+/// The markup language of the snippet that includes an exported figure.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "figure_snippet_markup")]
+pub enum FigureSnippetMarkup {
+    #[serde(rename = "latex")]
+    Latex,
+    #[serde(rename = "typst")]
+    Typst,
+}
+
+impl Default for FigureSnippetMarkup {
+    fn default() -> Self {
+        Self::Latex
+    }
+}
+
+impl TryFrom<u32> for FigureSnippetMarkup {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "FigureSnippetMarkup try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl FigureSnippetMarkup {
+    pub fn file_ext(self) -> String {
+        match self {
+            Self::Latex => String::from("tex"),
+            Self::Typst => String::from("typ"),
+        }
+    }
+
+    /// The snippet that includes the figure file with the given physical size in millimeters.
+    ///
+    /// In LaTeX the file name is wrapped in `\detokenize`, so that characters like `_` or `&` are taken literally.
+    /// Names containing characters that are interpreted even there are rejected.
+    pub fn snippet(
+        self,
+        figure_file_name: &str,
+        size_mm: na::Vector2<f64>,
+    ) -> anyhow::Result<String> {
+        match self {
+            Self::Latex => {
+                if figure_file_name.contains(['%', '#', '\\', '{', '}']) {
+                    return Err(anyhow::anyhow!(
+                        "The figure file name `{figure_file_name}` contains characters that can't be included in LaTeX."
+                    ));
+                }
+                Ok(format!(
+                    "\\includegraphics[width={:.2}mm,height={:.2}mm]{{\\detokenize{{{figure_file_name}}}}}\n",
+                    size_mm[0], size_mm[1]
+                ))
+            }
+            Self::Typst => Ok(format!(
+                "#image(\"{}\", width: {:.2}mm, height: {:.2}mm)\n",
+                figure_file_name.replace('\\', "\\\\").replace('"', "\\\""),
+                size_mm[0],
+                size_mm[1]
+            )),
+        }
+    }
+}
+
+/// Figure export preferences, for exporting the selection as figure for LaTeX or Typst documents.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "figure_export_prefs")]
+pub struct FigureExportPrefs {
+    /// Whether the background should be exported.
+    #[serde(rename = "with_background")]
+    pub with_background: bool,
+    /// Whether the background pattern should be exported.
+    #[serde(rename = "with_pattern")]
+    pub with_pattern: bool,
+    /// Whether the background and stroke colors should be optimized for printing.
+    #[serde(rename = "optimize_printing")]
+    pub optimize_printing: bool,
+    /// Export format.
+    #[serde(rename = "export_format")]
+    pub export_format: FigureExportFormat,
+    /// The markup language of the snippet.
+    #[serde(rename = "snippet_markup")]
+    pub snippet_markup: FigureSnippetMarkup,
+    /// The margins of the export extending the bounds of the selection.
+    #[serde(rename = "margin")]
+    pub margin: f64,
+}
+
+impl Default for FigureExportPrefs {
+    fn default() -> Self {
+        Self {
+            with_background: false,
+            with_pattern: false,
+            optimize_printing: false,
+            export_format: FigureExportFormat::default(),
+            snippet_markup: FigureSnippetMarkup::default(),
+            margin: 6.0,
+        }
+    }
+}
+
 /// Export preferences.
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(default, rename = "export_prefs")]
@@ -310,6 +649,9 @@ pub struct ExportPrefs {
     /// Selection export preferences.
     #[serde(rename = "selection_export_prefs")]
     pub selection_export_prefs: SelectionExportPrefs,
+    /// Figure export preferences.
+    #[serde(rename = "figure_export_prefs")]
+    pub figure_export_prefs: FigureExportPrefs,
 }
 
 impl CloneConfig for ExportPrefs {
@@ -323,26 +665,32 @@ impl Engine {
     pub const STROKE_EXPORT_IMAGE_SCALE: f64 = 1.8;
 
     /// Save the current document as a .rnote file.
+    ///
+    /// Only the current state of the store is captured here, taking the snapshot and serializing it happens on a
+    /// worker thread. Edits made in the meantime don't affect the saved document.
     pub fn save_as_rnote_bytes(
         &self,
         file_name: String,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
-        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
-        let engine_snapshot = self.take_snapshot();
-        rayon::spawn(move || {
-            let result = || -> anyhow::Result<Vec<u8>> {
-                let rnote_file = RnoteFile {
-                    engine_snapshot: ijson::to_value(&engine_snapshot)?,
-                };
-                rnote_file.save_as_bytes(&file_name)
-            };
-            if oneshot_sender.send(result()).is_err() {
-                error!(
-                    "Sending result to receiver failed while saving document as rnote bytes. Receiver already dropped."
-                );
-            }
-        });
-        oneshot_receiver
+        let document = self.document.clone();
+        let camera = self.camera.clone_config();
+        let history_entry = self.store.create_history_entry();
+        save_snapshot_as_rnote_bytes(
+            move || EngineSnapshot::from_history_entry(document, camera, history_entry),
+            file_name,
+        )
+    }
+
+    /// Save the pages with the given indices as a separate .rnote file.
+    ///
+    /// See [`Engine::take_pages_snapshot()`].
+    pub fn save_pages_as_rnote_bytes(
+        &self,
+        indices: &[usize],
+        file_name: String,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let snapshot = self.take_pages_snapshot(indices);
+        save_snapshot_as_rnote_bytes(move || snapshot, file_name)
     }
 
     /// Extract the current engine configuration.
@@ -355,26 +703,42 @@ impl Engine {
             export_prefs: self.export_prefs.clone_config(),
             pen_sounds: self.pen_sounds(),
             optimize_epd: self.optimize_epd(),
+            show_rulers: self.show_rulers(),
+            rulers_unit: self.rulers_unit(),
+            pen_hover_preview: self.pen_hover_preview(),
+            contrast_mode: self.contrast_mode(),
         }
     }
 
     pub fn extract_document_content(&self) -> StrokeContent {
+        let bounds = self
+            .bounds_w_content_extended()
+            .unwrap_or(self.document.bounds());
         StrokeContent::default()
             .with_strokes(
                 self.store
                     .get_strokes_arc(&self.store.stroke_keys_as_rendered()),
             )
-            .with_bounds(Some(
-                self.bounds_w_content_extended()
-                    .unwrap_or(self.document.bounds()),
-            ))
+            .with_bounds(Some(bounds))
             .with_background(Some(self.document.background))
+            .with_page_backgrounds(self.document.page_backgrounds_intersecting(bounds))
     }
 
     pub fn extract_pages_content(&self, page_order: SplitOrder) -> Vec<StrokeContent> {
+        self.extract_selected_pages_content(page_order, None)
+    }
+
+    /// Extract the content of the pages in the page selection, or of all pages when there is none.
+    pub fn extract_selected_pages_content(
+        &self,
+        page_order: SplitOrder,
+        page_selection: Option<&PageSelection>,
+    ) -> Vec<StrokeContent> {
         self.pages_bounds_w_content(page_order)
             .into_iter()
-            .map(|bounds| {
+            .enumerate()
+            .filter(|(i, _)| page_selection.map_or(true, |selection| selection.contains_index(*i)))
+            .map(|(_, bounds)| {
                 StrokeContent::default()
                     .with_strokes(
                         self.store.get_strokes_arc(
@@ -385,6 +749,7 @@ impl Engine {
                     )
                     .with_bounds(Some(bounds))
                     .with_background(Some(self.document.background))
+                    .with_page_backgrounds(self.document.page_backgrounds_intersecting(bounds))
             })
             .collect()
     }
@@ -401,6 +766,55 @@ impl Engine {
         )
     }
 
+    /// The content that is currently visible in the viewport.
+    pub fn extract_viewport_content(&self) -> StrokeContent {
+        let viewport = self.camera.viewport();
+        StrokeContent::default()
+            .with_strokes(
+                self.store.get_strokes_arc(
+                    &self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(viewport),
+                ),
+            )
+            .with_bounds(Some(viewport))
+            .with_background(Some(self.document.background))
+            .with_page_backgrounds(self.document.page_backgrounds_intersecting(viewport))
+    }
+
+    /// The content of each frame in the document together with the frame name.
+    ///
+    /// The frames themselves are not part of the content.
+    pub fn extract_frames_content(&self) -> Vec<(String, StrokeContent)> {
+        self.store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| match self.store.get_stroke_ref(key) {
+                Some(Stroke::FrameStroke(frame)) => Some((frame.name.clone(), frame.region())),
+                _ => None,
+            })
+            .map(|(name, region)| {
+                let keys = self
+                    .store
+                    .stroke_keys_as_rendered_intersecting_bounds(region)
+                    .into_iter()
+                    .filter(|key| {
+                        !matches!(
+                            self.store.get_stroke_ref(*key),
+                            Some(Stroke::FrameStroke(_))
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let content = StrokeContent::default()
+                    .with_strokes(self.store.get_strokes_arc(&keys))
+                    .with_bounds(Some(region))
+                    .with_background(Some(self.document.background))
+                    .with_page_backgrounds(self.document.page_backgrounds_intersecting(region));
+                (name, content)
+            })
+            .collect()
+    }
+
     /// Export the current engine config as Json string.
     pub fn export_engine_config_as_json(&self) -> anyhow::Result<String> {
         Ok(serde_json::to_string(&self.extract_engine_config())?)
@@ -414,23 +828,61 @@ impl Engine {
     }
 
     /// Export the document.
+    ///
+    /// Pdf exports report the exported pages to `progress` and can be cancelled through it.
+    /// The page selection applies to the formats that are split into pages.
     pub fn export_doc(
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
+        if let Err(e) =
+            self.check_page_selection(doc_export_prefs.page_order, page_selection.as_ref())
+        {
+            let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+            let _ = oneshot_sender.send(Err(e));
+            return oneshot_receiver;
+        }
 
         match doc_export_prefs.export_format {
             DocExportFormat::Svg => self.export_doc_as_svg_bytes(doc_export_prefs_override),
-            DocExportFormat::Pdf => self.export_doc_as_pdf_bytes(title, doc_export_prefs_override),
+            DocExportFormat::Pdf => self.export_doc_as_pdf_bytes(
+                title,
+                doc_export_prefs_override,
+                page_selection,
+                progress,
+            ),
             DocExportFormat::Xopp => {
-                self.export_doc_as_xopp_bytes(title, doc_export_prefs_override)
+                self.export_doc_as_xopp_bytes(title, doc_export_prefs_override, page_selection)
+            }
+            DocExportFormat::Html => {
+                self.export_doc_as_html_bytes(title, doc_export_prefs_override, page_selection)
             }
         }
     }
 
+    /// Returns an error when the page selection does not select any of the document pages.
+    fn check_page_selection(
+        &self,
+        page_order: SplitOrder,
+        page_selection: Option<&PageSelection>,
+    ) -> anyhow::Result<()> {
+        let Some(page_selection) = page_selection else {
+            return Ok(());
+        };
+        let n_pages = self.pages_bounds_w_content(page_order).len();
+        if page_selection.indices(n_pages).is_empty() {
+            return Err(anyhow::anyhow!(
+                "The page selection does not contain any of the {n_pages} document pages."
+            ));
+        }
+        Ok(())
+    }
+
     /// Export the doc with the strokes as Svg.
     fn export_doc_as_svg_bytes(
         &self,
@@ -471,17 +923,75 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Export the doc pages as a single Html document, with the Svg of every page inlined.
+    fn export_doc_as_html_bytes(
+        &self,
+        title: String,
+        doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<PageSelection>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let doc_export_prefs =
+            doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
+        let pages_content = self
+            .extract_selected_pages_content(doc_export_prefs.page_order, page_selection.as_ref());
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let svg_roots = pages_content
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(i, page_content)| {
+                        let page_svg = page_content
+                            .gen_svg(
+                                doc_export_prefs.with_background,
+                                doc_export_prefs.with_pattern,
+                                doc_export_prefs.optimize_printing,
+                                DocExportPrefs::MARGIN,
+                            )?
+                            .ok_or(anyhow::anyhow!(
+                                "Generating Svg for page {i} failed, returned None."
+                            ))?;
+                        Ok(rnote_compose::utils::wrap_svg_root(
+                            page_svg.svg_data.as_str(),
+                            Some(page_svg.bounds),
+                            Some(page_svg.bounds),
+                            false,
+                        ))
+                    })
+                    .collect::<anyhow::Result<Vec<String>>>()?;
+                Ok(wrap_svgs_in_html(Some(&title), &svg_roots).into_bytes())
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting document as Html bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the doc with the strokes as Pdf.
     fn export_doc_as_pdf_bytes(
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let pages_content = self
+            .extract_selected_pages_content(doc_export_prefs.page_order, page_selection.as_ref());
+        let pages_bounds = pages_content
+            .iter()
+            .filter_map(|page_content| page_content.bounds())
+            .collect::<Vec<Aabb>>();
+        let links = self.document.links.resolved(&self.store);
+        let outline = self.outline();
         let format_size = self.document.format.size();
+        let header_date = PageHeaderFooter::date_now();
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
@@ -504,10 +1014,15 @@ impl Engine {
                     let cairo_cx = cairo::Context::new(&target_surface)
                         .context("Creating new cairo context for pdf target surface failed.")?;
 
+                    let n_pages = pages_content.len();
+                    progress.set_total(n_pages);
                     for (i, page_content) in pages_content.into_iter().enumerate() {
+                        progress.check_cancelled()?;
                         let Some(page_bounds) = page_content.bounds() else {
+                            progress.advance();
                             continue;
                         };
+                        add_pdf_link_annotations(&cairo_cx, &links, page_bounds, &pages_bounds);
                         cairo_cx.save()?;
                         cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
                         page_content.draw_to_cairo(
@@ -518,14 +1033,25 @@ impl Engine {
                             DocExportPrefs::MARGIN,
                             Engine::STROKE_EXPORT_IMAGE_SCALE,
                         )?;
+                        cairo_cx.translate(page_bounds.mins[0], page_bounds.mins[1]);
+                        doc_export_prefs.header_footer.draw_to_cairo(
+                            &cairo_cx,
+                            page_bounds.extents(),
+                            &title,
+                            &header_date,
+                            i + 1,
+                            n_pages,
+                        )?;
                         cairo_cx.show_page().map_err(|e| {
                             anyhow::anyhow!(
                                 "Showing page failed while exporting page {i} as pdf, Err: {e:?}"
                             )
                         })?;
                         cairo_cx.restore()?;
+                        progress.advance();
                     }
                 }
+                add_pdf_outline(&target_surface, &outline, &pages_bounds)?;
                 let data = *target_surface
                     .finish_output_stream()
                     .map_err(|e| anyhow::anyhow!("Finishing outputstream failed, Err: {e:?}"))?
@@ -550,11 +1076,13 @@ impl Engine {
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let pages_content = self
+            .extract_selected_pages_content(doc_export_prefs.page_order, page_selection.as_ref());
         let document = self.document.clone();
 
         rayon::spawn(move || {
@@ -678,67 +1206,148 @@ impl Engine {
     }
 
     /// Export the document pages.
+    ///
+    /// The export reports the exported pages to `progress` and can be cancelled through it.
     pub fn export_doc_pages(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let doc_pages_export_prefs =
             doc_pages_export_prefs_override.unwrap_or(self.export_prefs.doc_pages_export_prefs);
+        if let Err(e) =
+            self.check_page_selection(doc_pages_export_prefs.page_order, page_selection.as_ref())
+        {
+            let (oneshot_sender, oneshot_receiver) =
+                oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
+            let _ = oneshot_sender.send(Err(e));
+            return oneshot_receiver;
+        }
 
         match doc_pages_export_prefs.export_format {
-            DocPagesExportFormat::Svg => {
-                self.export_doc_pages_as_svgs_bytes(doc_pages_export_prefs_override)
-            }
-            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg => {
-                self.export_doc_pages_as_bitmap_bytes(doc_pages_export_prefs_override)
-            }
+            DocPagesExportFormat::Svg | DocPagesExportFormat::Html => self
+                .export_doc_pages_as_svgs_bytes(
+                    doc_pages_export_prefs_override,
+                    page_selection,
+                    progress,
+                ),
+            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg => self
+                .export_doc_pages_as_bitmap_bytes(
+                    doc_pages_export_prefs_override,
+                    page_selection,
+                    progress,
+                ),
+            DocPagesExportFormat::Eps => self.export_doc_pages_as_eps_bytes(
+                doc_pages_export_prefs_override,
+                page_selection,
+                progress,
+            ),
         }
     }
 
-    /// Export the document as Svg.
+    /// Export the document pages as Svg, or as Html with the Svg inlined when the format pref is set to Html.
     fn export_doc_pages_as_svgs_bytes(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
+    ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
+        let doc_pages_export_prefs =
+            doc_pages_export_prefs_override.unwrap_or(self.export_prefs.doc_pages_export_prefs);
+        let pages_content = self.extract_selected_pages_content(
+            doc_pages_export_prefs.page_order,
+            page_selection.as_ref(),
+        );
+
+        rayon::spawn(move || {
+            let result =
+                || -> anyhow::Result<Vec<Vec<u8>>> {
+                    progress.set_total(pages_content.len());
+                    pages_content
+                        .into_par_iter()
+                        .enumerate()
+                        .map(|(i, page_content)| {
+                            progress.check_cancelled()?;
+                            let page_svg = page_content
+                                .gen_svg(
+                                    doc_pages_export_prefs.with_background,
+                                    doc_pages_export_prefs.with_pattern,
+                                    doc_pages_export_prefs.optimize_printing,
+                                    DocPagesExportPrefs::MARGIN,
+                                )?
+                                .ok_or(anyhow::anyhow!(
+                                    "Generating Svg for page {i} failed, returned None."
+                                ))?;
+                            progress.advance();
+                            let svg_root = rnote_compose::utils::wrap_svg_root(
+                                page_svg.svg_data.as_str(),
+                                Some(page_svg.bounds),
+                                Some(page_svg.bounds),
+                                false,
+                            );
+                            if doc_pages_export_prefs.export_format == DocPagesExportFormat::Html {
+                                Ok(wrap_svgs_in_html(None, std::slice::from_ref(&svg_root))
+                                    .into_bytes())
+                            } else {
+                                Ok(rnote_compose::utils::add_xml_header(svg_root.as_str())
+                                    .into_bytes())
+                            }
+                        })
+                        .collect()
+                };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document pages as Svg bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the document pages as Eps.
+    fn export_doc_pages_as_eps_bytes(
+        &self,
+        doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
         let doc_pages_export_prefs =
             doc_pages_export_prefs_override.unwrap_or(self.export_prefs.doc_pages_export_prefs);
-        let pages_content = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let pages_content = self.extract_selected_pages_content(
+            doc_pages_export_prefs.page_order,
+            page_selection.as_ref(),
+        );
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<Vec<u8>>> {
+                progress.set_total(pages_content.len());
                 pages_content
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
-                        let page_svg = page_content
-                            .gen_svg(
+                        progress.check_cancelled()?;
+                        let page_bytes = page_content
+                            .gen_eps(
                                 doc_pages_export_prefs.with_background,
                                 doc_pages_export_prefs.with_pattern,
                                 doc_pages_export_prefs.optimize_printing,
                                 DocPagesExportPrefs::MARGIN,
                             )?
                             .ok_or(anyhow::anyhow!(
-                                "Generating Svg for page {i} failed, returned None."
+                                "Generating Eps for page {i} failed, returned None."
                             ))?;
-                        Ok(rnote_compose::utils::add_xml_header(
-                            rnote_compose::utils::wrap_svg_root(
-                                page_svg.svg_data.as_str(),
-                                Some(page_svg.bounds),
-                                Some(page_svg.bounds),
-                                false,
-                            )
-                            .as_str(),
-                        )
-                        .into_bytes())
+                        progress.advance();
+                        Ok(page_bytes)
                     })
                     .collect()
             };
-
             if oneshot_sender.send(result()).is_err() {
-                error!(
-                    "Sending result to receiver failed while exporting document pages as Svg bytes. Receiver already dropped."
-                );
+                error!("Sending result to receiver failed while exporting document pages as Eps bytes. Receiver already dropped.");
             }
         });
 
@@ -751,24 +1360,31 @@ impl Engine {
     fn export_doc_pages_as_bitmap_bytes(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
         let doc_pages_export_prefs =
             doc_pages_export_prefs_override.unwrap_or(self.export_prefs.doc_pages_export_prefs);
-        let pages_contents = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let pages_contents = self.extract_selected_pages_content(
+            doc_pages_export_prefs.page_order,
+            page_selection.as_ref(),
+        );
 
         rayon::spawn(move || {
             let result = || -> Result<Vec<Vec<u8>>, anyhow::Error> {
                 let image_format = match doc_pages_export_prefs.export_format {
-                    DocPagesExportFormat::Svg => return Err(anyhow::anyhow!("Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format.")),
+                    DocPagesExportFormat::Svg | DocPagesExportFormat::Html | DocPagesExportFormat::Eps => return Err(anyhow::anyhow!("Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format.")),
                     DocPagesExportFormat::Png => image::ImageFormat::Png,
                     DocPagesExportFormat::Jpeg => image::ImageFormat::Jpeg,
                 };
+                progress.set_total(pages_contents.len());
                 pages_contents
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
-                        page_content
+                        progress.check_cancelled()?;
+                        let page_bytes = page_content
                             .gen_svg(
                                 doc_pages_export_prefs.with_background,
                                 doc_pages_export_prefs.with_pattern,
@@ -782,7 +1398,9 @@ impl Engine {
                             .into_encoded_bytes(
                                 image_format,
                                 Some(doc_pages_export_prefs.jpeg_quality),
-                            )
+                            )?;
+                        progress.advance();
+                        Ok(page_bytes)
                     })
                     .collect()
             };
@@ -803,16 +1421,239 @@ impl Engine {
             selection_export_prefs_override.unwrap_or(self.export_prefs.selection_export_prefs);
 
         match selection_export_prefs.export_format {
-            SelectionExportFormat::Svg => {
+            SelectionExportFormat::Svg | SelectionExportFormat::Html => {
                 self.export_selection_as_svg_bytes(selection_export_prefs_override)
             }
             SelectionExportFormat::Png | SelectionExportFormat::Jpeg => {
                 self.export_selection_as_bitmap_bytes(selection_export_prefs_override)
             }
+            SelectionExportFormat::Eps => {
+                self.export_selection_as_eps_bytes(selection_export_prefs_override)
+            }
         }
     }
 
-    /// Exports the selection as Svg.
+    /// Export the selection as a figure cropped to its bounds, together with a snippet that includes it
+    /// in a LaTeX or Typst document.
+    ///
+    /// `figure_file_name` is the name the snippet refers to the figure with. The snippet sets the physical size of the
+    /// figure, converted with the Dpi of the document.
+    ///
+    /// Returns Ok(None) if nothing is selected.
+    pub fn export_selection_as_figure(
+        &self,
+        figure_file_name: String,
+        figure_export_prefs_override: Option<FigureExportPrefs>,
+    ) -> oneshot::Receiver<anyhow::Result<Option<(Vec<u8>, String)>>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Option<(Vec<u8>, String)>>>();
+        let figure_export_prefs =
+            figure_export_prefs_override.unwrap_or(self.export_prefs.figure_export_prefs);
+        let content = self.extract_selection_content();
+        let dpi = self.document.format.dpi();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Option<(Vec<u8>, String)>> {
+                let Some(content) = content else {
+                    return Ok(None);
+                };
+                let Some(bounds) = content.bounds() else {
+                    return Ok(None);
+                };
+                let size_mm = bounds
+                    .loosened(figure_export_prefs.margin)
+                    .extents()
+                    .map(|v| {
+                        MeasureUnit::convert_measurement(
+                            v,
+                            MeasureUnit::Px,
+                            dpi,
+                            MeasureUnit::Mm,
+                            dpi,
+                        )
+                    });
+                let figure_bytes = match figure_export_prefs.export_format {
+                    FigureExportFormat::Pdf => content.gen_pdf(
+                        figure_export_prefs.with_background,
+                        figure_export_prefs.with_pattern,
+                        figure_export_prefs.optimize_printing,
+                        figure_export_prefs.margin,
+                    )?,
+                    FigureExportFormat::Svg => content
+                        .gen_svg(
+                            figure_export_prefs.with_background,
+                            figure_export_prefs.with_pattern,
+                            figure_export_prefs.optimize_printing,
+                            figure_export_prefs.margin,
+                        )?
+                        .map(|svg| {
+                            let svg_root = rnote_compose::utils::wrap_svg_root(
+                                svg.svg_data.as_str(),
+                                Some(svg.bounds),
+                                Some(svg.bounds),
+                                false,
+                            );
+                            rnote_compose::utils::add_xml_header(svg_root.as_str()).into_bytes()
+                        }),
+                };
+                let Some(figure_bytes) = figure_bytes else {
+                    return Ok(None);
+                };
+                let snippet = figure_export_prefs
+                    .snippet_markup
+                    .snippet(&figure_file_name, size_mm)?;
+                Ok(Some((figure_bytes, snippet)))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting selection as figure. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export each frame in the document as its own file in the format of the selection export prefs.
+    ///
+    /// Returns the file stem names, derived from the frame names, together with the file bytes.
+    /// The export margin is not applied, frames already define the exact region.
+    pub fn export_frames(
+        &self,
+        selection_export_prefs_override: Option<SelectionExportPrefs>,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<(String, Vec<u8>)>>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Vec<(String, Vec<u8>)>>>();
+        let selection_export_prefs =
+            selection_export_prefs_override.unwrap_or(self.export_prefs.selection_export_prefs);
+        let frames_content = self.extract_frames_content();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+                let stem_names = crate::utils::frames_files_stem_names(
+                    frames_content.iter().map(|(name, _)| name.as_str()),
+                );
+                stem_names
+                    .into_par_iter()
+                    .zip(frames_content.into_par_iter())
+                    .map(|(stem_name, (_, content))| {
+                        let gen_svg = || {
+                            content
+                                .gen_svg(
+                                    selection_export_prefs.with_background,
+                                    selection_export_prefs.with_pattern,
+                                    selection_export_prefs.optimize_printing,
+                                    0.0,
+                                )?
+                                .ok_or_else(|| anyhow::anyhow!("Frame content has no bounds."))
+                        };
+                        let bytes = match selection_export_prefs.export_format {
+                            SelectionExportFormat::Svg | SelectionExportFormat::Html => {
+                                let svg = gen_svg()?;
+                                let svg_root = rnote_compose::utils::wrap_svg_root(
+                                    svg.svg_data.as_str(),
+                                    Some(svg.bounds),
+                                    Some(svg.bounds),
+                                    false,
+                                );
+                                if selection_export_prefs.export_format
+                                    == SelectionExportFormat::Html
+                                {
+                                    wrap_svgs_in_html(None, std::slice::from_ref(&svg_root))
+                                        .into_bytes()
+                                } else {
+                                    rnote_compose::utils::add_xml_header(svg_root.as_str())
+                                        .into_bytes()
+                                }
+                            }
+                            SelectionExportFormat::Png | SelectionExportFormat::Jpeg => {
+                                let image_format = if selection_export_prefs.export_format
+                                    == SelectionExportFormat::Png
+                                {
+                                    image::ImageFormat::Png
+                                } else {
+                                    image::ImageFormat::Jpeg
+                                };
+                                gen_svg()?
+                                    .gen_image(selection_export_prefs.bitmap_scalefactor)?
+                                    .into_encoded_bytes(
+                                        image_format,
+                                        Some(selection_export_prefs.jpeg_quality),
+                                    )?
+                            }
+                            SelectionExportFormat::Eps => content
+                                .gen_eps(
+                                    selection_export_prefs.with_background,
+                                    selection_export_prefs.with_pattern,
+                                    selection_export_prefs.optimize_printing,
+                                    0.0,
+                                )?
+                                .ok_or_else(|| anyhow::anyhow!("Frame content has no bounds."))?,
+                        };
+                        Ok((stem_name, bytes))
+                    })
+                    .collect()
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting frames. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the content of the viewport as Svg bytes, including the background.
+    pub fn export_viewport_as_svg_bytes(&self) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let content = self.extract_viewport_content();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let Some(svg) = content.gen_svg(true, true, false, 0.0)? else {
+                    return Err(anyhow::anyhow!("Viewport content has no bounds."));
+                };
+                let svg_root = rnote_compose::utils::wrap_svg_root(
+                    svg.svg_data.as_str(),
+                    Some(svg.bounds),
+                    Some(svg.bounds),
+                    false,
+                );
+                Ok(rnote_compose::utils::add_xml_header(svg_root.as_str()).into_bytes())
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting viewport as Svg bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the content of the viewport as bitmap image bytes, including the background.
+    ///
+    /// Uses the bitmap scale factor of the selection export prefs.
+    pub fn export_viewport_as_bitmap_bytes(
+        &self,
+        image_format: image::ImageFormat,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let selection_export_prefs = self.export_prefs.selection_export_prefs;
+        let content = self.extract_viewport_content();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let Some(svg) = content.gen_svg(true, true, false, 0.0)? else {
+                    return Err(anyhow::anyhow!("Viewport content has no bounds."));
+                };
+                svg.gen_image(selection_export_prefs.bitmap_scalefactor)?
+                    .into_encoded_bytes(image_format, Some(selection_export_prefs.jpeg_quality))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting viewport as bitmap image bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Exports the selection as Svg, or as Html with the Svg inlined when the format pref is set to Html.
     fn export_selection_as_svg_bytes(
         &self,
         selection_export_prefs_override: Option<SelectionExportPrefs>,
@@ -837,19 +1678,22 @@ impl Engine {
                 else {
                     return Ok(None);
                 };
+                let svg_root = rnote_compose::utils::wrap_svg_root(
+                    svg.svg_data.as_str(),
+                    Some(svg.bounds),
+                    Some(svg.bounds),
+                    false,
+                );
 
-                Ok(Some(
-                    rnote_compose::utils::add_xml_header(
-                        rnote_compose::utils::wrap_svg_root(
-                            svg.svg_data.as_str(),
-                            Some(svg.bounds),
-                            Some(svg.bounds),
-                            false,
-                        )
-                        .as_str(),
-                    )
-                    .into_bytes(),
-                ))
+                if selection_export_prefs.export_format == SelectionExportFormat::Html {
+                    Ok(Some(
+                        wrap_svgs_in_html(None, std::slice::from_ref(&svg_root)).into_bytes(),
+                    ))
+                } else {
+                    Ok(Some(
+                        rnote_compose::utils::add_xml_header(svg_root.as_str()).into_bytes(),
+                    ))
+                }
             };
             if oneshot_sender.send(result()).is_err() {
                 error!("Sending result to receiver failed while exporting selection as Svg bytes. Receiver already dropped.");
@@ -859,6 +1703,37 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Export the selection as Eps bytes.
+    fn export_selection_as_eps_bytes(
+        &self,
+        selection_export_prefs_override: Option<SelectionExportPrefs>,
+    ) -> oneshot::Receiver<Result<Option<Vec<u8>>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Option<Vec<u8>>>>();
+        let selection_export_prefs =
+            selection_export_prefs_override.unwrap_or(self.export_prefs.selection_export_prefs);
+        let content = self.extract_selection_content();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Option<Vec<u8>>, anyhow::Error> {
+                let Some(content) = content else {
+                    return Ok(None);
+                };
+                content.gen_eps(
+                    selection_export_prefs.with_background,
+                    selection_export_prefs.with_pattern,
+                    selection_export_prefs.optimize_printing,
+                    selection_export_prefs.margin,
+                )
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting selection as Eps bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the selection a bitmap bytes.
     ///
     /// Returns an error if the format pref is not set to a bitmap format
@@ -887,7 +1762,7 @@ impl Engine {
                     return Ok(None);
                 };
                 let image_format = match selection_export_prefs.export_format {
-                    SelectionExportFormat::Svg => return Err(anyhow::anyhow!("Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format.")),
+                    SelectionExportFormat::Svg | SelectionExportFormat::Html | SelectionExportFormat::Eps => return Err(anyhow::anyhow!("Extracting bitmap image format from doc pages export prefs failed, not set to a bitmap format.")),
                     SelectionExportFormat::Png => image::ImageFormat::Png,
                     SelectionExportFormat::Jpeg => image::ImageFormat::Jpeg
                 };
@@ -908,3 +1783,133 @@ impl Engine {
         oneshot_receiver
     }
 }
+
+/// Wraps the Svg root elements into a standalone Html document, one after another.
+///
+/// The Svgs are inlined, so that their strokes stay vector graphics when the Html is pasted into wikis or emails.
+/// Images are already embedded in the Svgs as data urls.
+fn wrap_svgs_in_html(title: Option<&str>, svg_roots: &[String]) -> String {
+    let title = title
+        .map(|title| format!("<title>{}</title>\n", glib::markup_escape_text(title)))
+        .unwrap_or_default();
+    let body = svg_roots
+        .iter()
+        .map(|svg_root| format!("<div>\n{svg_root}\n</div>\n"))
+        .collect::<String>();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="generator" content="Rnote">
+{title}</head>
+<body>
+{body}</body>
+</html>
+"#
+    )
+}
+
+/// Add the links that intersect the page as link annotations to the current Pdf page.
+///
+/// Links to a position in the document point to the exported page containing it.
+fn add_pdf_link_annotations(
+    cairo_cx: &cairo::Context,
+    links: &Links,
+    page_bounds: Aabb,
+    pages_bounds: &[Aabb],
+) {
+    // Values in the tag attributes are enclosed in single quotes.
+    let escape = |value: &str| value.replace('\\', "\\\\").replace('\'', "\\'");
+
+    for link in links
+        .iter()
+        .filter(|link| link.bounds.intersects(&page_bounds))
+    {
+        let rect = link.bounds.translate(-page_bounds.mins.coords);
+        let rect = format!(
+            "rect=[{} {} {} {}]",
+            rect.mins[0],
+            rect.mins[1],
+            rect.extents()[0],
+            rect.extents()[1]
+        );
+        let attributes = match &link.target {
+            LinkTarget::Uri(uri) => format!("{rect} uri='{}'", escape(uri)),
+            LinkTarget::Position(pos) => {
+                let Some((i, target_page_bounds)) = pages_bounds
+                    .iter()
+                    .enumerate()
+                    .find(|(_, bounds)| bounds.contains_local_point(&(*pos).into()))
+                else {
+                    continue;
+                };
+                let target_pos = pos - target_page_bounds.mins.coords;
+                format!(
+                    "{rect} page={} pos=[{} {}]",
+                    i + 1,
+                    target_pos[0],
+                    target_pos[1]
+                )
+            }
+        };
+        // "Link" is the name of cairo's link tag.
+        cairo_cx.tag_begin("Link", &attributes);
+        cairo_cx.tag_end("Link");
+    }
+}
+
+/// Add the document outline as Pdf bookmarks.
+///
+/// Entries outside of the exported pages are skipped.
+fn add_pdf_outline(
+    target_surface: &cairo::PdfSurface,
+    outline: &[OutlineEntry],
+    pages_bounds: &[Aabb],
+) -> anyhow::Result<()> {
+    // The id of the outline root, see cairo's `CAIRO_PDF_OUTLINE_ROOT`.
+    const PDF_OUTLINE_ROOT: i32 = 0;
+
+    for entry in outline {
+        let Some((i, target_page_bounds)) = pages_bounds
+            .iter()
+            .enumerate()
+            .find(|(_, bounds)| bounds.contains_local_point(&entry.pos.into()))
+        else {
+            continue;
+        };
+        let target_pos = entry.pos - target_page_bounds.mins.coords;
+        target_surface
+            .add_outline(
+                PDF_OUTLINE_ROOT,
+                &entry.title,
+                &format!("page={} pos=[{} {}]", i + 1, target_pos[0], target_pos[1]),
+                cairo::PdfOutline::empty(),
+            )
+            .context("Adding entry to the pdf outline failed.")?;
+    }
+    Ok(())
+}
+
+fn save_snapshot_as_rnote_bytes(
+    take_snapshot: impl FnOnce() -> EngineSnapshot + Send + 'static,
+    file_name: String,
+) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+    let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+    rayon::spawn(move || {
+        let _span = tracing::info_span!("save_as_rnote_bytes").entered();
+        let result = || -> anyhow::Result<Vec<u8>> {
+            let engine_snapshot = take_snapshot();
+            let rnote_file = RnoteFile {
+                engine_snapshot: ijson::to_value(&engine_snapshot)?,
+            };
+            rnote_file.save_as_bytes(&file_name)
+        };
+        if oneshot_sender.send(result()).is_err() {
+            error!(
+                "Sending result to receiver failed while saving document as rnote bytes. Receiver already dropped."
+            );
+        }
+    });
+    oneshot_receiver
+}
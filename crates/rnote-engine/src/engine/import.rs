@@ -1,17 +1,26 @@
 // Imports
-use super::{EngineConfig, EngineViewMut, StrokeContent};
+use super::{EngineConfig, EngineSnapshot, EngineViewMut, Progress, StrokeContent};
+use crate::document::links::{Link, LinkTarget};
 use crate::document::Layout;
 use crate::pens::Pen;
 use crate::pens::PenStyle;
 use crate::store::chrono_comp::StrokeLayer;
 use crate::store::StrokeKey;
+use crate::strokes::mindmapstroke::{MindMapLayout, MindMapNode};
+use crate::strokes::textstroke::{parse_markdown, RangedTextAttribute};
 use crate::strokes::{resize::calculate_resize_ratio, resize::ImageSizeOption, Resize};
-use crate::strokes::{BitmapImage, Stroke, VectorImage};
+use crate::strokes::{
+    vectorimage, BitmapImage, BoardStroke, CheckboxStroke, MindMapStroke, StickyNoteStroke, Stroke,
+    TableStroke, VectorImage,
+};
 use crate::{CloneConfig, Engine, WidgetFlags};
 use futures::channel::oneshot;
-use rnote_compose::ext::Vector2Ext;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ops::Range;
 use std::path::PathBuf;
 use std::time::Instant;
@@ -129,6 +138,29 @@ impl Default for XoppImportPrefs {
     }
 }
 
+/// Bitmap image import preferences.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename = "bitmapimage_import_prefs")]
+pub struct BitmapImageImportPrefs {
+    /// Whether imported image files are linked instead of embedded.
+    ///
+    /// Linked images are re-loaded from their file when the document is opened.
+    #[serde(rename = "link_images")]
+    pub link_images: bool,
+    /// Whether the contrast of imported photos is enhanced after correcting their perspective.
+    #[serde(rename = "enhance_photo_contrast")]
+    pub enhance_photo_contrast: bool,
+}
+
+/// Vector image import preferences.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename = "vectorimage_import_prefs")]
+pub struct VectorImageImportPrefs {
+    /// Whether the paths of simple Svgs are converted into shape strokes, so that they can be edited individually.
+    #[serde(rename = "convert_paths")]
+    pub convert_paths: bool,
+}
+
 /// Import preferences.
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(default, rename = "import_prefs")]
@@ -139,6 +171,12 @@ pub struct ImportPrefs {
     /// Xournal++ `.xopp` file import preferences
     #[serde(rename = "xopp_import_prefs")]
     pub xopp_import_prefs: XoppImportPrefs,
+    /// Bitmap image import preferences
+    #[serde(rename = "bitmapimage_import_prefs")]
+    pub bitmapimage_import_prefs: BitmapImageImportPrefs,
+    /// Vector image import preferences
+    #[serde(rename = "vectorimage_import_prefs")]
+    pub vectorimage_import_prefs: VectorImageImportPrefs,
 }
 
 impl CloneConfig for ImportPrefs {
@@ -166,6 +204,10 @@ impl Engine {
         self.set_pen_sounds(engine_config.pen_sounds, data_dir);
 
         self.set_optimize_epd(engine_config.optimize_epd);
+        self.show_rulers = engine_config.show_rulers;
+        self.rulers_unit = engine_config.rulers_unit;
+        self.pen_hover_preview = engine_config.pen_hover_preview;
+        self.contrast_mode = engine_config.contrast_mode;
 
         widget_flags |= self
             .penholder
@@ -200,6 +242,10 @@ impl Engine {
         self.set_pen_sounds(engine_config.pen_sounds, data_dir);
 
         self.set_optimize_epd(engine_config.optimize_epd);
+        self.show_rulers = engine_config.show_rulers;
+        self.rulers_unit = engine_config.rulers_unit;
+        self.pen_hover_preview = engine_config.pen_hover_preview;
+        self.contrast_mode = engine_config.contrast_mode;
 
         widget_flags |= self
             .penholder
@@ -219,13 +265,14 @@ impl Engine {
 
     /// Import and replaces the engine config.
     ///
+    /// Sections of the config that can't be read fall back to their defaults.
     /// If pen sounds should be enabled the rnote data-dir must be provided.
     pub fn import_engine_config_from_json(
         &mut self,
         serialized_config: &str,
         data_dir: Option<PathBuf>,
     ) -> anyhow::Result<WidgetFlags> {
-        let engine_config = serde_json::from_str::<EngineConfig>(serialized_config)?;
+        let engine_config = EngineConfig::from_json_lenient(serialized_config)?;
         Ok(self.load_engine_config(engine_config, data_dir))
     }
 
@@ -269,6 +316,59 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Generate strokes for the bytes of a Svg.
+    ///
+    /// When the `convert_paths` import pref is set and the Svg is simple, its paths are converted into shape strokes.
+    /// Otherwise a single vectorimage is generated.
+    ///
+    /// The bytes are expected to be from a valid UTF-8 encoded Svg string.
+    pub fn generate_svg_strokes_from_bytes(
+        &self,
+        pos: na::Vector2<f64>,
+        bytes: Vec<u8>,
+        respect_borders: bool,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<Stroke>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Stroke>>>();
+
+        let convert_paths = self.import_prefs.vectorimage_import_prefs.convert_paths;
+        let resize_struct = Resize {
+            width: self.document.format.width(),
+            height: self.document.format.height(),
+            layout_fixed_width: self.document.layout.is_fixed_width(),
+            max_viewpoint: Some(self.camera.viewport().maxs),
+            restrain_to_viewport: true,
+            respect_borders,
+        };
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<Stroke>> {
+                let svg_str = String::from_utf8(bytes)?;
+
+                if convert_paths {
+                    if let Some(shapestrokes) = vectorimage::shapestrokes_from_svg_str(
+                        &svg_str,
+                        pos,
+                        ImageSizeOption::ResizeImage(resize_struct),
+                    )? {
+                        return Ok(shapestrokes.into_iter().map(Stroke::ShapeStroke).collect());
+                    }
+                }
+                Ok(vec![Stroke::VectorImage(VectorImage::from_svg_str(
+                    &svg_str,
+                    pos,
+                    ImageSizeOption::ResizeImage(resize_struct),
+                )?)])
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver while generating strokes from Svg bytes failed. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Generate a bitmapimage for the bytes.
     ///
     /// The bytes are expected to be from a valid bitmap image (Png/Jpeg).
@@ -312,12 +412,15 @@ impl Engine {
     /// The bytes are expected to be from a valid Pdf.
     ///
     /// Note: `insert_pos` does not have an effect when the `adjust_document` import pref is set true.
+    ///
+    /// The import reports the rendered pages to `progress` and can be cancelled through it.
     #[allow(clippy::type_complexity)]
     pub fn generate_pdf_pages_from_bytes(
         &self,
         bytes: Vec<u8>,
         insert_pos: na::Vector2<f64>,
         page_range: Option<Range<u32>>,
+        progress: Progress,
     ) -> oneshot::Receiver<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>> {
         let (oneshot_sender, oneshot_receiver) =
             oneshot::channel::<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>>();
@@ -339,6 +442,7 @@ impl Engine {
                             insert_pos,
                             page_range,
                             &format,
+                            &progress,
                         )?
                         .into_iter()
                         .map(|s| (Stroke::BitmapImage(s), Some(StrokeLayer::Document)))
@@ -352,6 +456,7 @@ impl Engine {
                             insert_pos,
                             page_range,
                             &format,
+                            &progress,
                         )?
                         .into_iter()
                         .map(|s| (Stroke::VectorImage(s), Some(StrokeLayer::Document)))
@@ -419,8 +524,123 @@ impl Engine {
         widget_flags
     }
 
+    /// Append the content of another document below the current document.
+    ///
+    /// The strokes, bookmarks, links and page background overrides are moved to their new position.
+    pub fn append_snapshot(&mut self, snapshot: EngineSnapshot) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let offset = na::vector![
+            self.document.x - snapshot.document.x,
+            self.document.y + self.document.height - snapshot.document.y
+        ];
+
+        let all_strokes = self.store.stroke_keys_as_rendered();
+        self.store.set_selected_keys(&all_strokes, false);
+
+        // Keep the chronological order of the appended strokes
+        let mut strokes = snapshot
+            .stroke_components
+            .iter()
+            .map(|(key, stroke)| (key, snapshot.chrono_components.get(key), stroke))
+            .collect::<Vec<_>>();
+        strokes.sort_by_key(|(_, chrono_comp, _)| chrono_comp.map(|c| **c));
+        let mut key_map = HashMap::new();
+        let inserted = strokes
+            .into_iter()
+            .map(|(snapshot_key, chrono_comp, stroke)| {
+                let mut stroke = Stroke::clone(stroke);
+                stroke.translate(offset);
+                let key = self
+                    .store
+                    .insert_stroke(stroke, chrono_comp.map(|c| c.layer));
+                key_map.insert(snapshot_key, key);
+                key
+            })
+            .collect::<Vec<StrokeKey>>();
+        self.store.remap_connector_attachments(&inserted, &key_map);
+        self.store.update_geometry_for_strokes(&inserted);
+
+        self.document
+            .bookmarks
+            .extend(snapshot.document.bookmarks.into_iter().map(|mut bookmark| {
+                bookmark.pos += offset;
+                bookmark
+            }));
+        for link in snapshot.document.links.iter() {
+            let target = match &link.target {
+                LinkTarget::Position(pos) => LinkTarget::Position(pos + offset),
+                target => target.clone(),
+            };
+            let keys = link
+                .keys
+                .iter()
+                .filter_map(|key| key_map.get(key).copied())
+                .collect();
+            self.document.links.push(Link::new_anchored(
+                link.bounds.translate(offset),
+                target,
+                keys,
+            ));
+        }
+        let format_height = self.document.format.height();
+        if self.document.layout.is_fixed_width()
+            && format_height > 0.0
+            && snapshot.document.format.height() == format_height
+        {
+            let page_offset = (offset[1] / format_height).round() as i64;
+            self.document.page_backgrounds.extend(
+                snapshot
+                    .document
+                    .page_backgrounds
+                    .into_iter()
+                    .map(|mut page_background| {
+                        page_background.page[1] += page_offset;
+                        page_background
+                    }),
+            );
+        }
+
+        if self.document.layout == Layout::FixedSize {
+            self.document.height += snapshot.document.height;
+        } else {
+            widget_flags |= self.doc_resize_autoexpand();
+        }
+        // the appended bookmarks, links, page backgrounds and height are undone together with the strokes
+        self.doc_record_history_state();
+        widget_flags |= self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.background_rendering_regenerate()
+            | self.update_rendering_current_viewport();
+        widget_flags.resize = true;
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
     /// Insert text.
     pub fn insert_text(&mut self, text: String, pos: Option<na::Vector2<f64>>) -> WidgetFlags {
+        self.insert_styled_text(text, vec![], pos)
+    }
+
+    /// Insert text containing basic markdown, which is converted to styled text.
+    pub fn insert_markdown_text(
+        &mut self,
+        markdown: String,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let (text, ranged_text_attributes) = parse_markdown(
+            &markdown,
+            self.pens_config.typewriter_config.text_style.font_size,
+        );
+        self.insert_styled_text(text, ranged_text_attributes, pos)
+    }
+
+    fn insert_styled_text(
+        &mut self,
+        text: String,
+        ranged_text_attributes: Vec<RangedTextAttribute>,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
         // we need to always deselect all strokes. Even tough changing the pen style deselects too, but only when the pen is actually changed.
@@ -432,6 +652,7 @@ impl Engine {
         if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
             widget_flags |= typewriter.insert_text(
                 text,
+                ranged_text_attributes,
                 pos,
                 &mut EngineViewMut {
                     tasks_tx: self.tasks_tx.clone(),
@@ -449,6 +670,105 @@ impl Engine {
         widget_flags
     }
 
+    /// Insert an empty table with the given number of rows and columns.
+    ///
+    /// The cell text takes the text style of the typewriter.
+    pub fn insert_table(
+        &mut self,
+        rows: usize,
+        columns: usize,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let pos = pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        text_style.set_max_width(None);
+        let table = TableStroke::new(rows, columns, pos, text_style);
+
+        self.import_generated_content(vec![(Stroke::TableStroke(table), None)], false)
+    }
+
+    /// Insert todo items, one below the other, with the given labels.
+    ///
+    /// The labels take the text style of the typewriter.
+    pub fn insert_checkboxes(
+        &mut self,
+        labels: Vec<String>,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let mut pos = pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        text_style.set_max_width(None);
+
+        let checkboxes = labels
+            .into_iter()
+            .map(|label| {
+                let checkbox = CheckboxStroke::new(label, pos, text_style.clone());
+                pos[1] = checkbox.bounds().maxs[1];
+                (Stroke::CheckboxStroke(checkbox), None)
+            })
+            .collect();
+        self.import_generated_content(checkboxes, false)
+    }
+
+    /// Insert a sticky note with the given text and color.
+    ///
+    /// The text takes the text style of the typewriter.
+    pub fn insert_sticky_note(
+        &mut self,
+        text: String,
+        color: Color,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let pos = pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        let note = StickyNoteStroke::new(text, pos, color, text_style);
+
+        self.import_generated_content(vec![(Stroke::StickyNoteStroke(note), None)], false)
+    }
+
+    /// Insert a board with columns with the given titles.
+    ///
+    /// The titles take the text style of the typewriter.
+    pub fn insert_board(
+        &mut self,
+        columns: Vec<String>,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let pos = pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        let color = text_style.color;
+        let board = BoardStroke::new(columns, pos, color, text_style);
+
+        self.import_generated_content(vec![(Stroke::BoardStroke(board), None)], false)
+    }
+
+    /// Insert a mind map with the given root node and layout.
+    ///
+    /// The node texts and the edges take the text style and color of the typewriter.
+    pub fn insert_mind_map(
+        &mut self,
+        root: MindMapNode,
+        layout: MindMapLayout,
+        pos: Option<na::Vector2<f64>>,
+    ) -> WidgetFlags {
+        let pos = pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        let color = text_style.color;
+        let mind_map = MindMapStroke::new(root, layout, pos, color, text_style);
+
+        self.import_generated_content(vec![(Stroke::MindMapStroke(mind_map), None)], false)
+    }
+
     /// Insert the stroke content.
     ///
     /// The data usually comes from the clipboard, drag-and-drop, ..
@@ -474,6 +794,8 @@ impl Engine {
             _ => 1.0f64,
         };
         let inserted_keys = self.store.insert_stroke_content(content, ratio, pos);
+        // The user pasted the content, so the files of linked images are loaded
+        let _ = self.load_linked_images_for_keys(&inserted_keys);
 
         // re generate view
         self.store.update_geometry_for_strokes(&inserted_keys);
@@ -1,36 +1,64 @@
 // Modules
+pub mod collab;
+pub mod colorvision;
 pub mod export;
 pub mod import;
+pub mod pages;
+pub mod progress;
 pub mod rendering;
 pub mod snapshot;
 pub mod strokecontent;
 pub mod visual_debug;
 
 // Re-exports
+pub use collab::CollabSession;
+pub use colorvision::{ColorVisionFilter, ContrastMode};
 pub use export::ExportPrefs;
 use futures::channel::mpsc::UnboundedReceiver;
 use futures::StreamExt;
 pub use import::ImportPrefs;
+pub use progress::Progress;
 pub use snapshot::EngineSnapshot;
 pub use strokecontent::StrokeContent;
+pub use visual_debug::RenderStats;
 
 // Imports
-use crate::document::Layout;
+use crate::document::format::MeasureUnit;
+use crate::document::guides::{Guide, GuideOrientation};
+use crate::document::links::{Link, LinkTarget};
+use crate::document::perspective::PerspectiveMode;
+use crate::document::{
+    Background, Bookmark, DocumentHistoryState, Layout, OutlineEntry, OutlineEntryKind,
+    PageBackground,
+};
+use crate::pens::deviceprofiles::InputDevice;
+use crate::pens::pensconfig::brushconfig::BrushStyle;
+use crate::pens::pensconfig::presets::{PenPreset, PenPresetOptions};
+use crate::pens::pensconfig::shaperconfig::ShaperStyle;
 use crate::pens::{Pen, PenStyle};
 use crate::pens::{PenMode, PensConfig};
 use crate::store::render_comp::{self, RenderCompState};
 use crate::store::StrokeKey;
 use crate::strokes::content::GeneratedContentImages;
+use crate::strokes::mindmapstroke::{MindMapLayout, MindMapNode};
+use crate::strokes::tablestroke::TableEdit;
 use crate::strokes::textstroke::{TextAttribute, TextStyle};
-use crate::{render, AudioPlayer, CloneConfig, SelectionCollision, WidgetFlags};
+use crate::strokes::{BitmapImage, BlendMode, FrameStroke, Stroke, TableStroke};
+use crate::utils::deserialize_field_or_default;
+use crate::{render, AudioPlayer, CloneConfig, SelectionCollision, Spellcheck, WidgetFlags};
 use crate::{Camera, Document, PenHolder, StrokeStore};
 use futures::channel::{mpsc, oneshot};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::eventresult::EventPropagation;
 use rnote_compose::ext::AabbExt;
-use rnote_compose::penevent::{PenEvent, ShortcutKey};
-use rnote_compose::{Color, SplitOrder};
+use rnote_compose::penevent::{ModifierKey, PenEvent, PenProgress, ShortcutKey};
+use rnote_compose::shapes::Rectangle;
+use rnote_compose::style::custom::CustomOptions;
+use rnote_compose::style::textured::TexturedOptions;
+use rnote_compose::{Color, SplitOrder, Style};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -97,6 +125,13 @@ pub enum EngineTask {
         /// The generated images
         images: GeneratedContentImages,
     },
+    /// Inserts strokes of a lazily loaded document that were deserialized in the background.
+    InsertLoadedStrokes {
+        /// Identifies the document the strokes belong to.
+        load_id: u32,
+        /// The indices of the pending strokes and the loaded strokes, `None` if they could not be read.
+        strokes: Vec<(usize, Option<Stroke>)>,
+    },
     /// Requests that the typewriter cursor should be blinked/toggled
     BlinkTypewriterCursor,
     /// Change the permanent zoom to the given value
@@ -123,6 +158,47 @@ pub struct EngineConfig {
     pen_sounds: bool,
     #[serde(rename = "optimize_epd")]
     optimize_epd: bool,
+    #[serde(rename = "show_rulers")]
+    show_rulers: bool,
+    #[serde(rename = "rulers_unit")]
+    rulers_unit: MeasureUnit,
+    #[serde(rename = "pen_hover_preview")]
+    pen_hover_preview: bool,
+    #[serde(rename = "contrast_mode")]
+    contrast_mode: ContrastMode,
+}
+
+impl EngineConfig {
+    /// Deserialize the engine config from a Json string.
+    ///
+    /// Sections that can't be deserialized, for example because their format has changed between versions,
+    /// fall back to their defaults, so that the remaining configuration is still restored.
+    pub fn from_json_lenient(serialized_config: &str) -> anyhow::Result<Self> {
+        let value = serde_json::from_str::<serde_json::Value>(serialized_config)?;
+        if let Ok(engine_config) = serde_json::from_value::<Self>(value.clone()) {
+            return Ok(engine_config);
+        }
+        let object = value
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("The engine config is not a Json object."))?;
+
+        Ok(Self {
+            document: deserialize_field_or_default(object, "document"),
+            pens_config: object
+                .get("pens_config")
+                .map(PensConfig::from_json_value_lenient)
+                .unwrap_or_default(),
+            penholder: deserialize_field_or_default(object, "penholder"),
+            import_prefs: deserialize_field_or_default(object, "import_prefs"),
+            export_prefs: deserialize_field_or_default(object, "export_prefs"),
+            pen_sounds: deserialize_field_or_default(object, "pen_sounds"),
+            optimize_epd: deserialize_field_or_default(object, "optimize_epd"),
+            show_rulers: deserialize_field_or_default(object, "show_rulers"),
+            rulers_unit: deserialize_field_or_default(object, "rulers_unit"),
+            pen_hover_preview: deserialize_field_or_default(object, "pen_hover_preview"),
+            contrast_mode: deserialize_field_or_default(object, "contrast_mode"),
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -171,11 +247,47 @@ pub struct Engine {
     pen_sounds: bool,
     #[serde(rename = "optimize_epd")]
     optimize_epd: bool,
+    #[serde(rename = "show_rulers")]
+    show_rulers: bool,
+    #[serde(rename = "rulers_unit")]
+    rulers_unit: MeasureUnit,
+    #[serde(rename = "pen_hover_preview")]
+    pen_hover_preview: bool,
+    #[serde(rename = "contrast_mode")]
+    contrast_mode: ContrastMode,
 
     #[serde(skip)]
     audioplayer: Option<AudioPlayer>,
     #[serde(skip)]
     visual_debug: bool,
+    #[serde(skip)]
+    debug_hud: bool,
+    /// Simulates a color vision deficiency when drawing the canvas.
+    #[serde(skip)]
+    color_vision_filter: ColorVisionFilter,
+    /// The rendering statistics of the drawn frames, gathered while the debug HUD is shown.
+    #[serde(skip)]
+    render_stats: RefCell<RenderStats>,
+    /// The current cursor position in document coordinates, if the cursor is above the canvas.
+    #[serde(skip)]
+    cursor_pos: Option<na::Vector2<f64>>,
+    /// The index of the guide that is currently dragged.
+    #[serde(skip)]
+    guide_drag: Option<usize>,
+    /// Whether a checkbox was toggled with the current press.
+    #[serde(skip)]
+    checkbox_pressed: bool,
+    /// The style that was copied from a stroke, to be pasted onto other strokes.
+    #[serde(skip)]
+    copied_style: Option<Style>,
+    #[serde(skip)]
+    spellcheck: Spellcheck,
+    #[serde(skip)]
+    collab_session: Option<CollabSession>,
+    /// The number of pages at the end of the document that were added automatically when starting strokes below
+    /// the last page.
+    #[serde(skip)]
+    auto_added_pages: u32,
     // the task sender. Must not be modified, only cloned.
     #[serde(skip)]
     tasks_tx: EngineTaskSender,
@@ -184,6 +296,9 @@ pub struct Engine {
     // Background rendering
     #[serde(skip)]
     background_tile_image: Option<render::Image>,
+    /// The tile images of the page background overrides, in the same order as the document page backgrounds.
+    #[serde(skip)]
+    page_background_tile_images: Vec<render::Image>,
     #[cfg(feature = "ui")]
     #[serde(skip)]
     background_rendernodes: Vec<gtk4::gsk::RenderNode>,
@@ -198,10 +313,14 @@ pub struct Engine {
 impl Default for Engine {
     fn default() -> Self {
         let (tasks_tx, tasks_rx) = futures::channel::mpsc::unbounded::<EngineTask>();
+        let document = Document::default();
+        let mut store = StrokeStore::default();
+        store.set_document_state(document.history_state());
+        let _ = store.clear_history(store.create_history_entry());
 
         Self {
-            document: Document::default(),
-            store: StrokeStore::default(),
+            document,
+            store,
             camera: Camera::default(),
             pens_config: PensConfig::default(),
             penholder: PenHolder::default(),
@@ -209,12 +328,27 @@ impl Default for Engine {
             export_prefs: ExportPrefs::default(),
             pen_sounds: false,
             optimize_epd: false,
+            show_rulers: false,
+            rulers_unit: MeasureUnit::default(),
+            pen_hover_preview: false,
+            contrast_mode: ContrastMode::default(),
 
             audioplayer: None,
             visual_debug: false,
+            debug_hud: false,
+            color_vision_filter: ColorVisionFilter::default(),
+            render_stats: RefCell::new(RenderStats::default()),
+            cursor_pos: None,
+            guide_drag: None,
+            checkbox_pressed: false,
+            copied_style: None,
+            spellcheck: Spellcheck::default(),
+            collab_session: None,
+            auto_added_pages: 0,
             tasks_tx: EngineTaskSender(tasks_tx),
             tasks_rx: Some(EngineTaskReceiver(tasks_rx)),
             background_tile_image: None,
+            page_background_tile_images: Vec::default(),
             #[cfg(feature = "ui")]
             background_rendernodes: Vec::default(),
             origin_indicator_image: None,
@@ -296,6 +430,71 @@ impl Engine {
         self.optimize_epd = optimize_epd
     }
 
+    /// Whether the rulers along the canvas edges are shown.
+    pub fn show_rulers(&self) -> bool {
+        self.show_rulers
+    }
+
+    pub fn set_show_rulers(&mut self, show_rulers: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.show_rulers = show_rulers;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// The unit the rulers and the cursor position readout are marked in.
+    pub fn rulers_unit(&self) -> MeasureUnit {
+        self.rulers_unit
+    }
+
+    pub fn set_rulers_unit(&mut self, rulers_unit: MeasureUnit) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.rulers_unit = rulers_unit;
+        widget_flags.redraw = self.show_rulers;
+        widget_flags
+    }
+
+    /// Whether an outline of the size of the current pen is shown at the cursor while hovering.
+    pub fn pen_hover_preview(&self) -> bool {
+        self.pen_hover_preview
+    }
+
+    pub fn set_pen_hover_preview(&mut self, pen_hover_preview: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.pen_hover_preview = pen_hover_preview;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// How the contrast of the strokes against the background is enforced when drawing the canvas.
+    pub fn contrast_mode(&self) -> ContrastMode {
+        self.contrast_mode
+    }
+
+    pub fn set_contrast_mode(&mut self, contrast_mode: ContrastMode) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.contrast_mode = contrast_mode;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// The current cursor position in document coordinates, if the cursor is above the canvas.
+    pub fn cursor_pos(&self) -> Option<na::Vector2<f64>> {
+        self.cursor_pos
+    }
+
+    /// Update the current cursor position in document coordinates.
+    ///
+    /// Expected to be called on every cursor motion, with `None` when the cursor leaves the canvas.
+    pub fn set_cursor_pos(&mut self, cursor_pos: Option<na::Vector2<f64>>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.cursor_pos = cursor_pos;
+        widget_flags.redraw = self.show_rulers
+            || self.pen_hover_preview
+            || self.penholder.current_pen_style_w_override() == PenStyle::Eraser;
+        widget_flags
+    }
+
     pub fn visual_debug(&self) -> bool {
         self.visual_debug
     }
@@ -307,44 +506,80 @@ impl Engine {
         widget_flags
     }
 
-    /// Takes a snapshot of the current state.
-    pub fn take_snapshot(&self) -> EngineSnapshot {
-        let mut store_history_entry = self.store.create_history_entry();
+    pub fn color_vision_filter(&self) -> ColorVisionFilter {
+        self.color_vision_filter
+    }
 
-        // Remove all trashed strokes
-        let trashed_keys = store_history_entry
-            .trash_components
-            .iter()
-            .filter_map(|(key, trash_comp)| if trash_comp.trashed { Some(key) } else { None })
-            .collect::<Vec<StrokeKey>>();
+    pub fn set_color_vision_filter(
+        &mut self,
+        color_vision_filter: ColorVisionFilter,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.color_vision_filter = color_vision_filter;
+        widget_flags.redraw = true;
+        widget_flags
+    }
 
-        for key in trashed_keys {
-            Arc::make_mut(&mut store_history_entry.stroke_components).remove(key);
-        }
+    pub fn debug_hud(&self) -> bool {
+        self.debug_hud
+    }
 
-        EngineSnapshot {
-            document: self.document.clone_config(),
-            camera: self.camera.clone_config(),
-            stroke_components: Arc::clone(&store_history_entry.stroke_components),
-            chrono_components: Arc::clone(&store_history_entry.chrono_components),
-            chrono_counter: store_history_entry.chrono_counter,
-        }
+    pub fn set_debug_hud(&mut self, debug_hud: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.debug_hud = debug_hud;
+        self.render_stats.borrow_mut().reset();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Takes a snapshot of the current state.
+    ///
+    /// Strokes of a lazily loaded document that are not yet loaded are not part of the snapshot,
+    /// load them first with [`Engine::load_pending_strokes()`] when the snapshot is saved or exported.
+    pub fn take_snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot::from_history_entry(
+            self.document.clone(),
+            self.camera.clone_config(),
+            self.store.create_history_entry(),
+        )
     }
 
     /// Imports an engine snapshot. A save file should always be loaded with this method.
+    #[tracing::instrument(skip_all)]
     pub fn load_snapshot(&mut self, snapshot: EngineSnapshot) -> WidgetFlags {
-        self.document = snapshot.document.clone_config();
+        self.document = snapshot.document.clone();
+        self.spellcheck
+            .set_language(self.document.spellcheck_language.as_deref());
         self.camera = snapshot.camera.clone_config();
+        self.auto_added_pages = 0;
+        self.store.set_document_state(self.document.history_state());
         let mut widget_flags = self.store.import_from_snapshot(&snapshot)
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.background_rendering_regenerate()
             | self.update_content_rendering_current_viewport();
+        self.store
+            .load_pending_strokes_in_background(self.engine_tasks_tx(), self.camera.viewport());
         widget_flags.refresh_ui = true;
         widget_flags.view_modified = true;
         widget_flags
     }
 
+    /// Loads the remaining strokes of a lazily loaded document right away.
+    ///
+    /// Needs to be called before the whole document is saved, exported or edited.
+    pub fn load_pending_strokes(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.store.load_pending_strokes(None).is_empty() {
+            return widget_flags;
+        }
+        self.document
+            .links
+            .remap_keys(self.store.pending_strokes_keys());
+        widget_flags |= self.update_content_rendering_current_viewport();
+        widget_flags
+    }
+
     /// Records the current store state and saves it as a history entry.
     pub fn record(&mut self, now: Instant) -> WidgetFlags {
         self.store.record(now)
@@ -357,7 +592,10 @@ impl Engine {
 
     /// Undo the latest changes.
     pub fn undo(&mut self, now: Instant) -> WidgetFlags {
+        let prev_document_state = self.store.document_state();
         self.store.undo(now)
+            | self.doc_import_history_state(prev_document_state)
+            | self.doc_remove_empty_auto_added_pages()
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.update_rendering_current_viewport()
@@ -365,12 +603,43 @@ impl Engine {
 
     /// Redo the latest changes.
     pub fn redo(&mut self, now: Instant) -> WidgetFlags {
+        let prev_document_state = self.store.document_state();
         self.store.redo(now)
+            | self.doc_import_history_state(prev_document_state)
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.update_rendering_current_viewport()
     }
 
+    /// Save the document state in the store, so that it becomes part of the next recorded history entry.
+    ///
+    /// Needs to be called after changing the page backgrounds, bookmarks, links or the height of a document with a
+    /// fixed size.
+    pub(crate) fn doc_record_history_state(&mut self) {
+        self.store.set_document_state(self.document.history_state());
+    }
+
+    /// Restore the document state of the current history entry after undo or redo, when it differs from the previous one.
+    fn doc_import_history_state(
+        &mut self,
+        prev_document_state: Option<Arc<DocumentHistoryState>>,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(document_state) = self.store.document_state() else {
+            return widget_flags;
+        };
+        if prev_document_state.is_some_and(|prev| Arc::ptr_eq(&prev, &document_state)) {
+            return widget_flags;
+        }
+        self.document.import_history_state(&document_state);
+        // The height is restored from the history, which includes the automatically added pages
+        self.auto_added_pages = 0;
+        widget_flags |= self.background_rendering_regenerate();
+        widget_flags.resize = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
     pub fn can_undo(&self) -> bool {
         self.store.can_undo()
     }
@@ -460,6 +729,18 @@ impl Engine {
                     | self.background_rendering_regenerate()
                     | self.update_rendering_current_viewport();
             }
+            EngineTask::InsertLoadedStrokes { load_id, strokes } => {
+                if !self
+                    .store
+                    .insert_loaded_pending_strokes(load_id, strokes)
+                    .is_empty()
+                {
+                    self.document
+                        .links
+                        .remap_keys(self.store.pending_strokes_keys());
+                    widget_flags |= self.update_content_rendering_current_viewport();
+                }
+            }
             EngineTask::Quit => {
                 widget_flags |= self.set_active(false);
                 quit = true;
@@ -470,15 +751,28 @@ impl Engine {
     }
 
     /// Handle a pen event.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn handle_pen_event(
         &mut self,
         event: PenEvent,
         pen_mode: Option<PenMode>,
+        input_device: Option<&InputDevice>,
         now: Instant,
     ) -> (EventPropagation, WidgetFlags) {
-        self.penholder.handle_pen_event(
+        if let Some(result) = self.handle_pen_event_guides(&event) {
+            return result;
+        }
+        if let Some(result) = self.handle_pen_event_checkboxes(&event) {
+            return result;
+        }
+        if let Some(result) = self.handle_pen_event_links(&event) {
+            return result;
+        }
+        let widget_flags = self.doc_add_page_for_pen_event(&event);
+        let (propagate, pen_widget_flags) = self.penholder.handle_pen_event(
             event,
             pen_mode,
+            input_device,
             now,
             &mut EngineViewMut {
                 tasks_tx: self.engine_tasks_tx(),
@@ -488,17 +782,440 @@ impl Engine {
                 camera: &mut self.camera,
                 audioplayer: &mut self.audioplayer,
             },
-        )
+        );
+        (propagate, widget_flags | pen_widget_flags)
+    }
+
+    /// Add a page when a stroke is started just below the last page in fixed size layout,
+    /// so that writing can continue without adding the page manually.
+    fn doc_add_page_for_pen_event(&mut self, event: &PenEvent) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let PenEvent::Down { element, .. } = event else {
+            return widget_flags;
+        };
+        if self.document.layout != Layout::FixedSize
+            || self.penholder.current_pen_progress() != PenProgress::Idle
+            || !matches!(
+                self.penholder.current_pen_style_w_override(),
+                PenStyle::Brush | PenStyle::Shaper | PenStyle::Typewriter
+            )
+        {
+            return widget_flags;
+        }
+        let doc_bottom = self.document.y + self.document.height;
+        if element.pos[0] < self.document.x
+            || element.pos[0] > self.document.x + self.document.width
+            || element.pos[1] <= doc_bottom
+            || element.pos[1] > doc_bottom + self.document.format.height()
+        {
+            return widget_flags;
+        }
+        if self.document.add_page_fixed_size() {
+            self.auto_added_pages += 1;
+            // Recorded together with the stroke, so that undoing the stroke removes the page again
+            self.doc_record_history_state();
+            widget_flags |= self.update_rendering_current_viewport();
+            widget_flags.resize = true;
+        }
+        widget_flags
+    }
+
+    /// Remove the automatically added pages at the end of the document again once they are empty,
+    /// for example after the stroke that added them was undone.
+    fn doc_remove_empty_auto_added_pages(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        while self.auto_added_pages > 0 {
+            let last_page_y =
+                self.document.y + self.document.height - self.document.format.height();
+            let last_page_empty = self
+                .store
+                .strokes_bounds(&self.store.stroke_keys_unordered())
+                .iter()
+                .all(|bounds| bounds.maxs[1] <= last_page_y);
+            if !last_page_empty || !self.document.remove_page_fixed_size() {
+                break;
+            }
+            self.auto_added_pages -= 1;
+            widget_flags.resize = true;
+        }
+        widget_flags
+    }
+
+    /// Handle dragging guides out of the rulers and back into them.
+    ///
+    /// Returns `None` if the event is unrelated to guides and should be handled by the pens.
+    fn handle_pen_event_guides(
+        &mut self,
+        event: &PenEvent,
+    ) -> Option<(EventPropagation, WidgetFlags)> {
+        /// The distance in surface coordinates where guides can be grabbed in the rulers.
+        const GUIDE_GRAB_DIST: f64 = 6.0;
+        let mut widget_flags = WidgetFlags::default();
+
+        let on_ruler = |camera: &Camera, pos: na::Vector2<f64>| {
+            let surface_pos = camera.transform().transform_point(&pos.into()).coords;
+            (
+                surface_pos[0] > Self::RULER_SIZE && surface_pos[1] <= Self::RULER_SIZE,
+                surface_pos[0] <= Self::RULER_SIZE && surface_pos[1] > Self::RULER_SIZE,
+            )
+        };
+
+        match event {
+            PenEvent::Down { element, .. } => {
+                if let Some(i) = self.guide_drag {
+                    if let Some(guide) = self.document.guides.get_mut(i) {
+                        guide.position = element.pos[guide.orientation.axis()];
+                    }
+                } else {
+                    if !self.show_rulers
+                        || self.camera.rotation() != 0.0
+                        || self.penholder.current_pen_progress() != PenProgress::Idle
+                    {
+                        return None;
+                    }
+                    let grab_dist = GUIDE_GRAB_DIST / self.camera.total_zoom();
+                    // Guides cross the ruler that is perpendicular to them,
+                    // new guides are dragged out of the ruler that is parallel to them.
+                    let (orientation_grab, orientation_new) =
+                        match on_ruler(&self.camera, element.pos) {
+                            (true, _) => (GuideOrientation::Vertical, GuideOrientation::Horizontal),
+                            (_, true) => (GuideOrientation::Horizontal, GuideOrientation::Vertical),
+                            _ => return None,
+                        };
+                    let i = self
+                        .document
+                        .guides
+                        .nearest(orientation_grab, element.pos, grab_dist)
+                        .unwrap_or_else(|| {
+                            self.document.guides.push(Guide::new(
+                                orientation_new,
+                                element.pos[orientation_new.axis()],
+                            ))
+                        });
+                    self.guide_drag = Some(i);
+                }
+            }
+            PenEvent::Up { element, .. } => {
+                let i = self.guide_drag.take()?;
+                let (on_top_ruler, on_left_ruler) = on_ruler(&self.camera, element.pos);
+                // Guides that are released above the rulers are removed
+                if on_top_ruler || on_left_ruler {
+                    self.document.guides.remove(i);
+                } else if let Some(guide) = self.document.guides.get_mut(i) {
+                    guide.position = element.pos[guide.orientation.axis()];
+                }
+                widget_flags.store_modified = true;
+            }
+            PenEvent::Cancel => {
+                let i = self.guide_drag.take()?;
+                self.document.guides.remove(i);
+            }
+            PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {
+                self.guide_drag?;
+            }
+        }
+
+        widget_flags.redraw = true;
+        Some((EventPropagation::Stop, widget_flags))
+    }
+
+    /// Remove all guides of the document.
+    pub fn clear_guides(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self.document.guides.is_empty() {
+            self.document.guides.clear();
+            widget_flags.store_modified = true;
+        }
+        self.guide_drag = None;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Set the perspective guides mode, placing missing vanishing points inside the current viewport.
+    pub fn set_perspective_mode(&mut self, mode: PerspectiveMode) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.document.perspective.mode != mode {
+            self.document
+                .perspective
+                .set_mode(mode, self.camera.viewport());
+            widget_flags.store_modified = true;
+            widget_flags.redraw = true;
+        }
+        widget_flags
+    }
+
+    /// Move the vanishing points back into the current viewport.
+    pub fn reset_vanishing_points(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.document
+            .perspective
+            .reset_vanishing_points(self.camera.viewport());
+        widget_flags.store_modified = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Toggle checkboxes that are clicked while holding Ctrl, so they can be ticked off without switching pens.
+    ///
+    /// Returns `None` if the event is unrelated to checkboxes and should be handled by the pens.
+    fn handle_pen_event_checkboxes(
+        &mut self,
+        event: &PenEvent,
+    ) -> Option<(EventPropagation, WidgetFlags)> {
+        match event {
+            PenEvent::Down {
+                element,
+                modifier_keys,
+            } => {
+                // Down events are repeated while the pen is pressed
+                if self.checkbox_pressed {
+                    return Some((EventPropagation::Stop, WidgetFlags::default()));
+                }
+                if !modifier_keys.contains(&ModifierKey::KeyboardCtrl)
+                    || self.penholder.current_pen_progress() != PenProgress::Idle
+                {
+                    return None;
+                }
+                let key = self.checkbox_at(element.pos)?;
+                self.checkbox_pressed = true;
+                Some((EventPropagation::Stop, self.toggle_checkbox(key)))
+            }
+            PenEvent::Up { .. } | PenEvent::Cancel => {
+                if !std::mem::take(&mut self.checkbox_pressed) {
+                    return None;
+                }
+                Some((EventPropagation::Stop, WidgetFlags::default()))
+            }
+            PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {
+                None
+            }
+        }
+    }
+
+    /// The topmost checkbox at the given position.
+    fn checkbox_at(&self, pos: na::Vector2<f64>) -> Option<StrokeKey> {
+        self.store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|key| {
+                matches!(
+                    self.store.get_stroke_ref(*key),
+                    Some(Stroke::CheckboxStroke(_))
+                )
+            })
+    }
+
+    fn toggle_checkbox(&mut self, key: StrokeKey) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(Stroke::CheckboxStroke(checkbox)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        checkbox.toggle();
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// Update the stroke after it was modified in place, and record the modification in the history.
+    fn finish_stroke_modification(&mut self, key: StrokeKey) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.store.update_geometry_for_stroke(key);
+        self.store.regenerate_rendering_for_stroke(
+            key,
+            self.camera.viewport(),
+            self.camera.image_scale(),
+        );
+        widget_flags |= self.doc_resize_autoexpand() | self.current_pen_update_state();
+        widget_flags |= self.store.record(Instant::now());
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Follow links that are clicked while holding Ctrl.
+    ///
+    /// Returns `None` if the event is unrelated to links and should be handled by the pens.
+    fn handle_pen_event_links(
+        &mut self,
+        event: &PenEvent,
+    ) -> Option<(EventPropagation, WidgetFlags)> {
+        let PenEvent::Down {
+            element,
+            modifier_keys,
+        } = event
+        else {
+            return None;
+        };
+        if !modifier_keys.contains(&ModifierKey::KeyboardCtrl)
+            || self.penholder.current_pen_progress() != PenProgress::Idle
+        {
+            return None;
+        }
+        let target = self
+            .document
+            .links
+            .link_at(element.pos, &self.store)?
+            .target
+            .clone();
+        Some((EventPropagation::Stop, self.follow_link(target)))
+    }
+
+    /// Open the link target, either by moving the view to it or by requesting the UI to open the URI.
+    pub fn follow_link(&mut self, target: LinkTarget) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        match target {
+            LinkTarget::Uri(uri) => widget_flags.open_uri = Some(uri),
+            LinkTarget::Position(pos) => {
+                widget_flags |= self.camera_set_offset_expand(pos * self.camera.total_zoom())
+                    | self.update_rendering_current_viewport();
+            }
+        }
+        widget_flags
+    }
+
+    /// Bookmark the current view with the given name.
+    pub fn add_bookmark(&mut self, name: String) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let pos = self.camera.viewport().mins.coords;
+        self.document.bookmarks.push(Bookmark::new(name, pos));
+        self.doc_record_history_state();
+        widget_flags |= self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    pub fn remove_bookmark(&mut self, index: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if index >= self.document.bookmarks.len() {
+            return widget_flags;
+        }
+        self.document.bookmarks.remove(index);
+        self.doc_record_history_state();
+        widget_flags |= self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    /// Move the view to the bookmark with the given index.
+    pub fn goto_bookmark(&mut self, index: usize) -> WidgetFlags {
+        let Some(bookmark) = self.document.bookmarks.get(index) else {
+            return WidgetFlags::default();
+        };
+        self.follow_link(LinkTarget::Position(bookmark.pos))
+    }
+
+    /// The document outline, made up of the text strokes marked as headings and the bookmarks.
+    ///
+    /// The entries are ordered by their position from top to bottom.
+    pub fn outline(&self) -> Vec<OutlineEntry> {
+        let mut outline = self
+            .store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| {
+                let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_ref(key) else {
+                    return None;
+                };
+                if !textstroke.text_style.heading {
+                    return None;
+                }
+                let title = textstroke
+                    .text
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())?
+                    .to_string();
+                Some(OutlineEntry {
+                    kind: OutlineEntryKind::Heading,
+                    title,
+                    pos: self.store.bounds_for_strokes(&[key])?.mins.coords,
+                })
+            })
+            .chain(self.document.bookmarks.iter().map(|bookmark| OutlineEntry {
+                kind: OutlineEntryKind::Bookmark,
+                title: bookmark.name.clone(),
+                pos: bookmark.pos,
+            }))
+            .collect::<Vec<OutlineEntry>>();
+        outline.sort_by(|a, b| {
+            a.pos[1]
+                .total_cmp(&b.pos[1])
+                .then(a.pos[0].total_cmp(&b.pos[0]))
+        });
+        outline
+    }
+
+    /// Center the view on the stroke that was edited last.
+    pub fn goto_last_edit(&mut self) -> WidgetFlags {
+        let Some(bounds) = self
+            .store
+            .latest_key()
+            .and_then(|key| self.store.bounds_for_strokes(&[key]))
+        else {
+            return WidgetFlags::default();
+        };
+        let viewport_extents = self.camera.viewport().extents();
+        self.follow_link(LinkTarget::Position(
+            bounds.center().coords - viewport_extents * 0.5,
+        ))
+    }
+
+    /// The upper left corner of the page with the given index as link target.
+    ///
+    /// The pages are counted the same way as when the document is exported.
+    pub fn page_link_target(&self, page: usize) -> Option<LinkTarget> {
+        self.pages_bounds_w_content(self.export_prefs.doc_export_prefs.page_order)
+            .get(page)
+            .map(|bounds| LinkTarget::Position(bounds.mins.coords))
+    }
+
+    /// Add a link to the strokes of the current selection. It is anchored to them, so that it moves along.
+    pub fn add_link_to_selection(&mut self, target: LinkTarget) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let keys = self.store.selection_keys_as_rendered();
+        let Some(bounds) = self.store.bounds_for_strokes(&keys) else {
+            return widget_flags;
+        };
+        self.document
+            .links
+            .push(Link::new_anchored(bounds, target, keys));
+        self.doc_record_history_state();
+        widget_flags |= self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Remove all links intersecting the bounds of the current selection.
+    pub fn remove_links_from_selection(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(bounds) = self
+            .store
+            .bounds_for_strokes(&self.store.selection_keys_as_rendered())
+        else {
+            return widget_flags;
+        };
+        if self.document.links.remove_intersecting(bounds, &self.store) {
+            self.doc_record_history_state();
+            widget_flags |= self.record(Instant::now());
+            widget_flags.store_modified = true;
+            widget_flags.redraw = true;
+        }
+        widget_flags
     }
 
     /// Handle a pressed shortcut key.
     pub fn handle_pressed_shortcut_key(
         &mut self,
         shortcut_key: ShortcutKey,
+        input_device: Option<&InputDevice>,
         now: Instant,
     ) -> (EventPropagation, WidgetFlags) {
         self.penholder.handle_pressed_shortcut_key(
             shortcut_key,
+            input_device,
             now,
             &mut EngineViewMut {
                 tasks_tx: self.engine_tasks_tx(),
@@ -526,22 +1243,77 @@ impl Engine {
         )
     }
 
-    /// Change the pen style (temporary) override.
-    pub fn change_pen_style_override(
-        &mut self,
-        new_style_override: Option<PenStyle>,
-    ) -> WidgetFlags {
-        self.penholder.change_style_override(
-            new_style_override,
-            &mut EngineViewMut {
-                tasks_tx: self.engine_tasks_tx(),
-                pens_config: &mut self.pens_config,
-                document: &mut self.document,
-                store: &mut self.store,
-                camera: &mut self.camera,
-                audioplayer: &mut self.audioplayer,
-            },
-        )
+    /// Save the options of the current pen as a new preset.
+    ///
+    /// Only the brush and the shaper can be saved as presets.
+    pub fn save_current_pen_as_preset(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let options = match self.penholder.current_pen_style_w_override() {
+            PenStyle::Brush => PenPresetOptions::Brush(self.pens_config.brush_config.clone()),
+            PenStyle::Shaper => PenPresetOptions::Shaper(self.pens_config.shaper_config.clone()),
+            PenStyle::Typewriter | PenStyle::Eraser | PenStyle::Selector | PenStyle::Tools => {
+                return widget_flags;
+            }
+        };
+        let preset = PenPreset::new(options.default_name(), options);
+        if self.pens_config.presets.push(preset).is_none() {
+            error!("Saving pen preset failed, the maximum number of presets is reached");
+            return widget_flags;
+        }
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    /// Switch to the pen of the preset at the given index and load its options.
+    pub fn apply_pen_preset(&mut self, i: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(preset) = self.pens_config.presets.get(i).cloned() else {
+            return widget_flags;
+        };
+        let pen_style = preset.options.pen_style();
+        match preset.options {
+            PenPresetOptions::Brush(config) => self.pens_config.brush_config = config,
+            PenPresetOptions::Shaper(config) => self.pens_config.shaper_config = config,
+        }
+        widget_flags |= self.change_pen_style(pen_style);
+        widget_flags |= self.change_pen_style_override(None);
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    pub fn remove_pen_preset(&mut self, i: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.pens_config.presets.remove(i).is_some() {
+            widget_flags.refresh_ui = true;
+        }
+        widget_flags
+    }
+
+    /// Change the pen style (temporary) override.
+    pub fn change_pen_style_override(
+        &mut self,
+        new_style_override: Option<PenStyle>,
+    ) -> WidgetFlags {
+        self.penholder.change_style_override(
+            new_style_override,
+            &mut EngineViewMut {
+                tasks_tx: self.engine_tasks_tx(),
+                pens_config: &mut self.pens_config,
+                document: &mut self.document,
+                store: &mut self.store,
+                camera: &mut self.camera,
+                audioplayer: &mut self.audioplayer,
+            },
+        )
+    }
+
+    /// Grow (positive) or shrink (negative) the eraser width by the given number of steps.
+    pub fn eraser_adjust_width(&mut self, steps: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.pens_config.eraser_config.adjust_width(steps);
+        widget_flags.redraw = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
     }
 
     /// Change the pen mode. Relevant for stylus input.
@@ -702,7 +1474,10 @@ impl Engine {
     pub fn doc_add_page_fixed_size(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if self.document.add_page_fixed_size() {
-            widget_flags |= self.update_rendering_current_viewport();
+            // Pages that were added by the user are kept, even when empty
+            self.auto_added_pages = 0;
+            self.doc_record_history_state();
+            widget_flags |= self.record(Instant::now()) | self.update_rendering_current_viewport();
             widget_flags.resize = true;
         }
         widget_flags
@@ -714,12 +1489,14 @@ impl Engine {
     pub fn doc_remove_page_fixed_size(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if self.document.remove_page_fixed_size() {
+            self.auto_added_pages = self.auto_added_pages.saturating_sub(1);
             self.store.set_trashed_keys(
                 &self
                     .store
                     .keys_below_y(self.document.y + self.document.height),
                 true,
             );
+            self.doc_record_history_state();
             widget_flags |= self.record(Instant::now()) | self.update_rendering_current_viewport();
             widget_flags.resize = true;
         }
@@ -742,6 +1519,15 @@ impl Engine {
         self.camera.set_offset(offset, &self.document) | self.doc_expand_autoexpand()
     }
 
+    /// Rotate the view to the given angle in radians, clockwise, around the center of the viewport.
+    ///
+    /// Only changes how the document is displayed, document coordinates are not affected.
+    ///
+    /// Background and content rendering then need to be updated.
+    pub fn camera_set_rotation(&mut self, rotation: f64) -> WidgetFlags {
+        self.camera.set_rotation(rotation)
+    }
+
     /// Update the viewport size of the camera.
     ///
     /// Background and content rendering then need to be updated.
@@ -811,9 +1597,11 @@ impl Engine {
     }
 
     pub fn select_all_strokes(&mut self) -> WidgetFlags {
-        let widget_flags = self.change_pen_style(PenStyle::Selector);
-        self.store
-            .set_selected_keys(&self.store.stroke_keys_as_rendered(), true);
+        let widget_flags = self.change_pen_style(PenStyle::Selector) | self.load_pending_strokes();
+        let all_strokes = self
+            .store
+            .filter_unlocked_keys(self.store.stroke_keys_as_rendered());
+        self.store.set_selected_keys(&all_strokes, true);
         widget_flags
             | self.current_pen_update_state()
             | self.doc_resize_autoexpand()
@@ -843,6 +1631,7 @@ impl Engine {
                 .store
                 .stroke_keys_as_rendered_intersecting_bounds(bounds),
         };
+        let select = self.store.filter_unlocked_keys(select);
         self.store.set_selected_keys(&select, true);
         self.doc_resize_autoexpand()
             | self.record(Instant::now())
@@ -885,6 +1674,19 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    pub fn change_selection_opacity(&mut self, opacity: f64) -> WidgetFlags {
+        self.store
+            .change_opacity(&self.store.selection_keys_as_rendered(), opacity)
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    pub fn change_selection_blend_mode(&mut self, blend_mode: BlendMode) -> WidgetFlags {
+        self.store
+            .change_blend_mode(&self.store.selection_keys_as_rendered(), blend_mode)
+            | self.record(Instant::now())
+    }
+
     pub fn invert_selection_colors(&mut self) -> WidgetFlags {
         self.store
             .invert_color_brightness(&self.store.selection_keys_as_rendered())
@@ -892,6 +1694,528 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    /// The bounds of the current selection, if any strokes are selected.
+    pub fn selection_bounds(&self) -> Option<Aabb> {
+        self.store
+            .bounds_for_strokes(&self.store.selection_keys_as_rendered())
+    }
+
+    /// Move and resize the selection so that its bounds match the new bounds,
+    /// then rotate it around the center of the new bounds by the given angle (in radians).
+    pub fn transform_selection(&mut self, new_bounds: Aabb, angle: f64) -> WidgetFlags {
+        let selection = self.store.selection_keys_as_rendered();
+        let Some(bounds) = self.store.bounds_for_strokes(&selection) else {
+            return WidgetFlags::default();
+        };
+        // Degenerated extents can't be scaled, they are kept as they are
+        let scale = na::vector![
+            if bounds.extents()[0] > 0.0 {
+                new_bounds.extents()[0] / bounds.extents()[0]
+            } else {
+                1.0
+            },
+            if bounds.extents()[1] > 0.0 {
+                new_bounds.extents()[1] / bounds.extents()[1]
+            } else {
+                1.0
+            }
+        ];
+        self.store
+            .scale_strokes_with_pivot(&selection, scale, bounds.mins.coords);
+        self.store
+            .translate_strokes(&selection, new_bounds.mins.coords - bounds.mins.coords);
+        if angle != 0.0 {
+            self.store
+                .rotate_strokes(&selection, angle, new_bounds.center());
+        }
+        self.store.update_geometry_for_strokes(&selection);
+        self.store.reroute_attached_connectors(&selection);
+
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Lock the selected strokes against accidental edits. They are deselected, since locked strokes can't be selected.
+    pub fn lock_selection(&mut self) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_selected_keys(&selection_keys, false);
+        self.store.set_locked_keys(&selection_keys, true);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Move the selected strokes in front of all other strokes of their layers.
+    pub fn selection_bring_to_front(&mut self) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        for &key in selection_keys.iter() {
+            self.store.update_chrono_to_last(key);
+        }
+        let mut widget_flags =
+            self.record(Instant::now()) | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Move the selected strokes behind all other strokes of their layers.
+    pub fn selection_send_to_back(&mut self) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.update_chrono_to_first(&selection_keys);
+        let mut widget_flags =
+            self.record(Instant::now()) | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Whether there are any locked strokes in the document.
+    pub fn any_strokes_locked(&self) -> bool {
+        !self.store.locked_keys_unordered().is_empty()
+    }
+
+    /// Unlock all locked strokes and select them, so that they can be edited right away.
+    pub fn unlock_all_strokes(&mut self) -> WidgetFlags {
+        let locked_keys = self.store.locked_keys_unordered();
+        if locked_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        let mut widget_flags = self.change_pen_style(PenStyle::Selector);
+        self.store.set_locked_keys(&locked_keys, false);
+        self.store
+            .set_selected_keys(&self.store.selection_keys_as_rendered(), false);
+        self.store.set_selected_keys(&locked_keys, true);
+        widget_flags |= self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Compress the embedded bitmap images with the image compression options of the document.
+    pub fn compress_images(&mut self) -> WidgetFlags {
+        let compression = self.document.image_compression;
+        let mut compressed_keys = vec![];
+        for key in self.store.stroke_keys_unordered() {
+            let Some(Stroke::BitmapImage(bitmapimage)) = self.store.get_stroke_mut(key) else {
+                continue;
+            };
+            match bitmapimage.compress(&compression) {
+                Ok(true) => compressed_keys.push(key),
+                Ok(false) => {}
+                Err(e) => error!("Compressing bitmap image failed, Err: {e:?}"),
+            }
+        }
+        if compressed_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_rendering_dirty_for_strokes(&compressed_keys);
+        let mut widget_flags =
+            self.record(Instant::now()) | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// The areas covered by redactions.
+    pub fn redacted_areas(&self) -> Vec<Aabb> {
+        self.store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| match self.store.get_stroke_ref(key) {
+                Some(Stroke::RedactionStroke(redaction)) => Some(redaction.redacted_area()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Apply all redactions, permanently deleting the content underneath them.
+    ///
+    /// The history is cleared, so that the deleted content can't be restored with undo.
+    pub fn apply_redactions(&mut self) -> WidgetFlags {
+        // Content that is not yet loaded must be redacted as well
+        let mut widget_flags = self.load_pending_strokes();
+        let areas = self.redacted_areas();
+        if areas.is_empty() {
+            return widget_flags;
+        }
+        self.store
+            .set_selected_keys(&self.store.selection_keys_as_rendered(), false);
+        let (modified_keys, redact_widget_flags) = self.store.redact_strokes(&areas);
+        widget_flags |= redact_widget_flags;
+        self.store.remove_trashed_strokes();
+        self.store.update_geometry_for_strokes(&modified_keys);
+        widget_flags |= self.store.clear_history(self.store.create_history_entry())
+            | self.doc_resize_autoexpand()
+            | self.current_pen_update_state()
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// The number of linked images whose file is not loaded.
+    pub fn broken_linked_images_count(&self) -> usize {
+        self.store
+            .stroke_keys_unordered()
+            .into_iter()
+            .filter(|&key| {
+                matches!(
+                    self.store.get_stroke_ref(key),
+                    Some(Stroke::BitmapImage(bitmapimage)) if bitmapimage.is_broken_link()
+                )
+            })
+            .count()
+    }
+
+    /// Loads the files of the linked images.
+    ///
+    /// Linked images are not loaded together with the document, since the document alone should not decide which
+    /// files are read. This should only be called after the user requested it.
+    ///
+    /// Returns the number of images that could not be loaded, they keep being drawn as broken links.
+    pub fn load_linked_images(&mut self) -> (usize, WidgetFlags) {
+        let mut widget_flags = self.load_pending_strokes();
+        let keys = self.store.stroke_keys_unordered();
+        let (n_failed, loaded_keys) = self.load_linked_images_for_keys(&keys);
+        if !loaded_keys.is_empty() {
+            self.store.set_rendering_dirty_for_strokes(&loaded_keys);
+            widget_flags |= self.update_content_rendering_current_viewport();
+        }
+        (n_failed, widget_flags)
+    }
+
+    /// Loads the files of the linked images with the given keys.
+    ///
+    /// Returns the number of images that failed to load and the keys of the loaded images.
+    pub(crate) fn load_linked_images_for_keys(
+        &mut self,
+        keys: &[StrokeKey],
+    ) -> (usize, Vec<StrokeKey>) {
+        let mut n_failed = 0;
+        let mut loaded_keys = vec![];
+        for &key in keys {
+            if !matches!(
+                self.store.get_stroke_ref(key),
+                Some(Stroke::BitmapImage(bitmapimage)) if bitmapimage.linked_path.is_some()
+            ) {
+                continue;
+            }
+            let Some(Stroke::BitmapImage(bitmapimage)) = self.store.get_stroke_mut(key) else {
+                continue;
+            };
+            match bitmapimage.load_linked_file() {
+                Ok(()) => loaded_keys.push(key),
+                Err(e) => {
+                    error!("Loading linked image file failed, Err: {e:?}");
+                    n_failed += 1;
+                }
+            }
+        }
+        (n_failed, loaded_keys)
+    }
+
+    /// The file of the first selected linked image.
+    pub fn selection_linked_image_path(&self) -> Option<PathBuf> {
+        self.store
+            .selection_keys_as_rendered()
+            .into_iter()
+            .find_map(|key| match self.store.get_stroke_ref(key) {
+                Some(Stroke::BitmapImage(bitmapimage)) => bitmapimage.linked_path.clone(),
+                _ => None,
+            })
+    }
+
+    /// Link the selected linked images to a new file.
+    pub fn relink_selected_images(
+        &mut self,
+        path: &std::path::Path,
+    ) -> anyhow::Result<WidgetFlags> {
+        let mut relinked_keys = vec![];
+        for key in self.store.selection_keys_as_rendered() {
+            let Some(Stroke::BitmapImage(bitmapimage)) = self.store.get_stroke_mut(key) else {
+                continue;
+            };
+            if bitmapimage.linked_path.is_none() {
+                continue;
+            }
+            bitmapimage.relink(path)?;
+            relinked_keys.push(key);
+        }
+        if relinked_keys.is_empty() {
+            return Ok(WidgetFlags::default());
+        }
+        self.store.set_rendering_dirty_for_strokes(&relinked_keys);
+        let mut widget_flags =
+            self.record(Instant::now()) | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        Ok(widget_flags)
+    }
+
+    /// Convert the selected brush strokes into shape strokes with their filled envelope as path.
+    ///
+    /// Returns None if no brush strokes are selected.
+    pub fn convert_selected_brushstrokes_to_outlines(&mut self) -> Option<WidgetFlags> {
+        let mut converted_keys = vec![];
+        let mut new_keys = vec![];
+        for key in self.store.selection_keys_as_rendered() {
+            let Some(Stroke::BrushStroke(brushstroke)) = self.store.get_stroke_ref(key) else {
+                continue;
+            };
+            let Some(shapestroke) = brushstroke.to_outline_shapestroke() else {
+                continue;
+            };
+            let layer = self.store.stroke_layer(key);
+            converted_keys.push(key);
+            new_keys.push(
+                self.store
+                    .insert_stroke(Stroke::ShapeStroke(shapestroke), layer),
+            );
+        }
+        if converted_keys.is_empty() {
+            return None;
+        }
+        self.store.set_selected_keys(&converted_keys, false);
+        self.store.set_trashed_keys(&converted_keys, true);
+        self.store.set_selected_keys(&new_keys, true);
+        self.store.update_geometry_for_strokes(&new_keys);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        Some(widget_flags)
+    }
+
+    /// Add a frame with the given name around the selection.
+    ///
+    /// Returns None if nothing is selected.
+    pub fn add_frame_around_selection(&mut self, name: String) -> Option<WidgetFlags> {
+        let bounds = self
+            .selection_bounds()?
+            .loosened(FrameStroke::SELECTION_MARGIN);
+        let key = self.store.insert_stroke(
+            Stroke::FrameStroke(FrameStroke::new(name, Rectangle::from_p2d_aabb(bounds))),
+            None,
+        );
+        self.store.update_geometry_for_stroke(key);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        Some(widget_flags)
+    }
+
+    /// Render the selected strokes at the given Dpi into a single bitmap image that replaces them.
+    ///
+    /// Returns None if nothing is selected.
+    pub fn flatten_selection(&mut self, dpi: f64) -> anyhow::Result<Option<WidgetFlags>> {
+        if !dpi.is_finite() || dpi <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Invalid Dpi {dpi} for flattening the selection"
+            ));
+        }
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(&top_key) = selection_keys.last() else {
+            return Ok(None);
+        };
+        let image_scale = dpi / self.document.format.dpi();
+        let bitmapimage =
+            BitmapImage::from_strokes(self.store.get_strokes_ref(&selection_keys), image_scale)?;
+        // The image takes the place of the topmost stroke
+        let layer = self.store.stroke_layer(top_key);
+        self.store.set_selected_keys(&selection_keys, false);
+        self.store.set_trashed_keys(&selection_keys, true);
+        let key = self
+            .store
+            .insert_stroke(Stroke::BitmapImage(bitmapimage), layer);
+        self.store.set_selected(key, true);
+        self.store.update_geometry_for_stroke(key);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        Ok(Some(widget_flags))
+    }
+
+    /// Trace the selected bitmap images into filled shapes, which replace the images in the selection.
+    ///
+    /// Returns `None` when no image could be traced.
+    pub fn vectorize_selected_images(&mut self) -> Option<WidgetFlags> {
+        let mut traced_keys = vec![];
+        let mut new_keys = vec![];
+        for key in self.store.selection_keys_as_rendered() {
+            let Some(Stroke::BitmapImage(bitmapimage)) = self.store.get_stroke_ref(key) else {
+                continue;
+            };
+            let shapestrokes = match crate::vectorize::trace_bitmapimage(bitmapimage) {
+                Ok(shapestrokes) => shapestrokes,
+                Err(e) => {
+                    error!("Tracing bitmap image failed, Err: {e:?}");
+                    continue;
+                }
+            };
+            if shapestrokes.is_empty() {
+                continue;
+            }
+            let layer = self.store.stroke_layer(key);
+            traced_keys.push(key);
+            new_keys.extend(shapestrokes.into_iter().map(|shapestroke| {
+                self.store
+                    .insert_stroke(Stroke::ShapeStroke(shapestroke), layer)
+            }));
+        }
+        if traced_keys.is_empty() {
+            return None;
+        }
+        self.store.set_selected_keys(&traced_keys, false);
+        self.store.set_trashed_keys(&traced_keys, true);
+        self.store.set_selected_keys(&new_keys, true);
+        self.store.update_geometry_for_strokes(&new_keys);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        Some(widget_flags)
+    }
+
+    /// Whether a stroke style was copied and can be pasted.
+    pub fn has_copied_style(&self) -> bool {
+        self.copied_style.is_some()
+    }
+
+    /// Copy the style of the first selected stroke that has one.
+    pub fn copy_selection_style(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(style) = self
+            .store
+            .selection_keys_as_rendered()
+            .into_iter()
+            .find_map(|key| self.store.get_stroke_ref(key).and_then(|s| s.style()))
+        else {
+            return widget_flags;
+        };
+        self.copied_style = Some(style);
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    /// Whether the copied style can be applied to any of the selected strokes.
+    pub fn can_paste_style_to_selection(&self) -> bool {
+        let Some(style) = self.copied_style.as_ref() else {
+            return false;
+        };
+        self.store
+            .selection_keys_unordered()
+            .into_iter()
+            .any(|key| {
+                self.store
+                    .get_stroke_ref(key)
+                    .is_some_and(|stroke| stroke.style_applies(style))
+            })
+    }
+
+    /// Paste the copied style onto all selected strokes.
+    pub fn paste_style_to_selection(&mut self) -> WidgetFlags {
+        let Some(style) = self.copied_style.clone() else {
+            return WidgetFlags::default();
+        };
+        self.store
+            .apply_style(&self.store.selection_keys_as_rendered(), &style)
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    /// Set the copied style as the options of the shaper, if it is the current pen, else of the brush.
+    pub fn apply_copied_style_to_pen_options(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(style) = self.copied_style.clone() else {
+            return widget_flags;
+        };
+
+        if self.penholder.current_pen_style_w_override() == PenStyle::Shaper {
+            let shaper_config = &mut self.pens_config.shaper_config;
+            match style {
+                Style::Smooth(options) => {
+                    shaper_config.style = ShaperStyle::Smooth;
+                    shaper_config.smooth_options = options;
+                }
+                Style::Rough(options) => {
+                    shaper_config.style = ShaperStyle::Rough;
+                    shaper_config.rough_options = options;
+                }
+                Style::Textured(TexturedOptions {
+                    stroke_width,
+                    stroke_color,
+                    ..
+                })
+                | Style::Custom(CustomOptions {
+                    stroke_width,
+                    stroke_color,
+                    ..
+                }) => {
+                    // The shaper has no textured or custom style, so only the width and color are taken over.
+                    shaper_config.style = ShaperStyle::Smooth;
+                    shaper_config.smooth_options.stroke_width = stroke_width;
+                    shaper_config.smooth_options.stroke_color = stroke_color;
+                }
+            }
+        } else {
+            let brush_config = &mut self.pens_config.brush_config;
+            match style {
+                Style::Smooth(options) => {
+                    if brush_config.style == BrushStyle::Marker {
+                        *brush_config.marker_options = options;
+                    } else {
+                        brush_config.style = BrushStyle::Solid;
+                        *brush_config.solid_options = options;
+                    }
+                }
+                Style::Rough(options) => {
+                    // The brush has no rough style, so only the width and color are taken over.
+                    if matches!(
+                        brush_config.style,
+                        BrushStyle::Textured | BrushStyle::Custom
+                    ) {
+                        brush_config.style = BrushStyle::Solid;
+                    }
+                    let smooth_options = if brush_config.style == BrushStyle::Marker {
+                        &mut *brush_config.marker_options
+                    } else {
+                        &mut *brush_config.solid_options
+                    };
+                    smooth_options.stroke_width = options.stroke_width;
+                    smooth_options.stroke_color = options.stroke_color;
+                }
+                Style::Textured(options) => {
+                    brush_config.style = BrushStyle::Textured;
+                    brush_config.textured_options = options;
+                }
+                Style::Custom(options) => {
+                    brush_config.style = BrushStyle::Custom;
+                    brush_config.custom_options = options;
+                }
+            }
+        }
+
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
     pub fn text_selection_change_style<F>(&mut self, modify_func: F) -> WidgetFlags
     where
         F: FnOnce(&mut TextStyle),
@@ -968,6 +2292,468 @@ impl Engine {
         widget_flags
     }
 
+    /// The background of the page at the given position.
+    pub fn page_background_at(&self, pos: na::Vector2<f64>) -> Option<Background> {
+        let page = self.document.page_at(pos)?;
+        Some(self.document.background_for_page(page))
+    }
+
+    /// Whether the page at the given position overrides the document background.
+    pub fn page_background_overridden_at(&self, pos: na::Vector2<f64>) -> bool {
+        self.document.page_at(pos).is_some_and(|page| {
+            self.document
+                .page_backgrounds
+                .iter()
+                .any(|page_background| page_background.page == page)
+        })
+    }
+
+    /// Override the background of the page at the given position.
+    /// Resets the page to the document background when `background` is `None`.
+    pub fn set_page_background_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        background: Option<Background>,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(page) = self.document.page_at(pos) else {
+            return widget_flags;
+        };
+        self.document
+            .page_backgrounds
+            .retain(|page_background| page_background.page != page);
+        if let Some(background) = background {
+            self.document
+                .page_backgrounds
+                .push(PageBackground { page, background });
+        }
+        widget_flags |= self.background_rendering_regenerate();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Update the document palette to the most used colors of the strokes.
+    ///
+    /// Skipped while strokes of a lazily loaded document are not yet loaded, since their colors would be missing.
+    /// Returns true when the palette changed.
+    pub fn update_document_palette(&mut self) -> bool {
+        if self.store.has_pending_strokes() {
+            return false;
+        }
+        let mut usage: Vec<(Color, usize)> = vec![];
+        for key in self.store.stroke_keys_unordered() {
+            let Some(stroke) = self.store.get_stroke_ref(key) else {
+                continue;
+            };
+            for color in stroke.colors() {
+                match usage
+                    .iter_mut()
+                    .find(|(c, _)| u32::from(*c) == u32::from(color))
+                {
+                    Some((_, count)) => *count += 1,
+                    None => usage.push((color, 1)),
+                }
+            }
+        }
+        usage.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let palette = usage
+            .into_iter()
+            .take(Document::PALETTE_SIZE)
+            .map(|(color, _)| color)
+            .collect::<Vec<Color>>();
+        if palette == self.document.palette {
+            return false;
+        }
+        self.document.palette = palette;
+        true
+    }
+
+    pub fn spellcheck_language(&self) -> Option<String> {
+        self.document.spellcheck_language.clone()
+    }
+
+    /// Set the spellcheck language of the document. Disables spellchecking when `None`.
+    pub fn set_spellcheck_language(&mut self, language: Option<String>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.document.spellcheck_language == language {
+            return widget_flags;
+        }
+        self.spellcheck.set_language(language.as_deref());
+        self.document.spellcheck_language = language;
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// The languages that are available for spellchecking.
+    pub fn spellcheck_available_languages(&mut self) -> Vec<String> {
+        self.spellcheck.available_languages()
+    }
+
+    /// The misspelled word in the text that is currently being edited at the given position.
+    ///
+    /// Returns the textstroke key and the range of the word.
+    fn misspelled_word_at(&self, pos: na::Vector2<f64>) -> Option<(StrokeKey, Range<usize>)> {
+        let Pen::Typewriter(typewriter) = self.penholder.current_pen_ref() else {
+            return None;
+        };
+        let stroke_key = typewriter.modifying_stroke_key()?;
+        let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_ref(stroke_key) else {
+            return None;
+        };
+        let index = textstroke
+            .get_cursor_for_global_coord(pos)
+            .ok()?
+            .cur_cursor();
+        self.spellcheck
+            .misspelled_words(&textstroke.text)
+            .into_iter()
+            .find(|range| range.contains(&index) || range.end == index)
+            .map(|range| (stroke_key, range))
+    }
+
+    /// Replacement suggestions for the misspelled word at the given position, if there is one.
+    pub fn spellcheck_suggestions_at(&self, pos: na::Vector2<f64>) -> Option<Vec<String>> {
+        let (stroke_key, range) = self.misspelled_word_at(pos)?;
+        let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_ref(stroke_key) else {
+            return None;
+        };
+        Some(
+            self.spellcheck
+                .suggestions(textstroke.get_text_slice_for_range(range)),
+        )
+    }
+
+    /// Replace the misspelled word at the given position.
+    pub fn spellcheck_correct_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        replacement: &str,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some((_, range)) = self.misspelled_word_at(pos) else {
+            return widget_flags;
+        };
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            widget_flags |= typewriter.replace_text_range_in_modifying_stroke(
+                range,
+                replacement,
+                &mut EngineViewMut {
+                    tasks_tx: self.tasks_tx.clone(),
+                    pens_config: &mut self.pens_config,
+                    document: &mut self.document,
+                    store: &mut self.store,
+                    camera: &mut self.camera,
+                    audioplayer: &mut self.audioplayer,
+                },
+            )
+        }
+        widget_flags
+    }
+
+    /// Whether a text is currently being edited with the typewriter.
+    pub fn text_is_being_edited(&self) -> bool {
+        matches!(self.penholder.current_pen_ref(), Pen::Typewriter(typewriter) if typewriter.is_modifying())
+    }
+
+    pub fn text_toggle_bullet_list(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            widget_flags |= typewriter.toggle_bullet_list(&mut EngineViewMut {
+                tasks_tx: self.tasks_tx.clone(),
+                pens_config: &mut self.pens_config,
+                document: &mut self.document,
+                store: &mut self.store,
+                camera: &mut self.camera,
+                audioplayer: &mut self.audioplayer,
+            })
+        }
+        widget_flags
+    }
+
+    /// Change the font family of the current text selection, or of the entire text if nothing is selected.
+    pub fn text_change_font_family(&mut self, font_family: String) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            if typewriter.selection_range().is_some() {
+                widget_flags |= typewriter.replace_text_attribute_current_selection(
+                    TextAttribute::FontFamily(font_family),
+                    &mut EngineViewMut {
+                        tasks_tx: self.tasks_tx.clone(),
+                        pens_config: &mut self.pens_config,
+                        document: &mut self.document,
+                        store: &mut self.store,
+                        camera: &mut self.camera,
+                        audioplayer: &mut self.audioplayer,
+                    },
+                )
+            } else {
+                widget_flags |= typewriter.change_text_style_in_modifying_stroke(
+                    |style| style.font_family = font_family,
+                    &mut EngineViewMut {
+                        tasks_tx: self.tasks_tx.clone(),
+                        pens_config: &mut self.pens_config,
+                        document: &mut self.document,
+                        store: &mut self.store,
+                        camera: &mut self.camera,
+                        audioplayer: &mut self.audioplayer,
+                    },
+                )
+            }
+        }
+        widget_flags
+    }
+
+    /// The table and the row and column of its cell at the given position.
+    fn table_cell_at(&self, pos: na::Vector2<f64>) -> Option<(StrokeKey, usize, usize)> {
+        self.store
+            .stroke_keys_as_rendered_intersecting_bounds(Aabb::from_half_extents(
+                pos.into(),
+                na::Vector2::repeat(0.5),
+            ))
+            .into_iter()
+            .rev()
+            .find_map(|key| match self.store.get_stroke_ref(key) {
+                Some(Stroke::TableStroke(table)) => {
+                    table.cell_at(pos).map(|(row, column)| (key, row, column))
+                }
+                _ => None,
+            })
+    }
+
+    /// Whether there is a table cell at the given position.
+    pub fn table_cell_exists_at(&self, pos: na::Vector2<f64>) -> bool {
+        self.table_cell_at(pos).is_some()
+    }
+
+    /// The text of the table cell at the given position.
+    pub fn table_cell_text_at(&self, pos: na::Vector2<f64>) -> Option<String> {
+        let (key, row, column) = self.table_cell_at(pos)?;
+        let Some(Stroke::TableStroke(table)) = self.store.get_stroke_ref(key) else {
+            return None;
+        };
+        table.cell_text(row, column).map(|text| text.to_string())
+    }
+
+    /// Set the text of the table cell at the given position.
+    pub fn table_set_cell_text_at(&mut self, pos: na::Vector2<f64>, text: String) -> WidgetFlags {
+        self.modify_table_at(pos, |table, row, column| {
+            table.set_cell_text(row, column, text)
+        })
+    }
+
+    /// Insert or remove rows and columns of the table at the given position.
+    pub fn table_edit_at(&mut self, pos: na::Vector2<f64>, edit: TableEdit) -> WidgetFlags {
+        self.modify_table_at(pos, |table, row, column| {
+            table.apply_edit(edit, row, column)
+        })
+    }
+
+    fn modify_table_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        modify: impl FnOnce(&mut TableStroke, usize, usize),
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some((key, row, column)) = self.table_cell_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::TableStroke(table)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        modify(table, row, column);
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// The topmost sticky note at the given position.
+    fn sticky_note_at(&self, pos: na::Vector2<f64>) -> Option<StrokeKey> {
+        self.store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|key| {
+                matches!(
+                    self.store.get_stroke_ref(*key),
+                    Some(Stroke::StickyNoteStroke(_))
+                )
+            })
+    }
+
+    /// The text of the sticky note at the given position.
+    pub fn sticky_note_text_at(&self, pos: na::Vector2<f64>) -> Option<String> {
+        match self.store.get_stroke_ref(self.sticky_note_at(pos)?) {
+            Some(Stroke::StickyNoteStroke(note)) => Some(note.text.clone()),
+            _ => None,
+        }
+    }
+
+    /// Set the text of the sticky note at the given position. The note grows or shrinks to fit it.
+    pub fn sticky_note_set_text_at(&mut self, pos: na::Vector2<f64>, text: String) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(key) = self.sticky_note_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::StickyNoteStroke(note)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        note.text = text;
+        // The notes below move along when the note on a board grows or shrinks
+        let reflowed = self.store.reflow_boards(&[key]);
+        self.store.regenerate_rendering_for_strokes(
+            &reflowed,
+            self.camera.viewport(),
+            self.camera.image_scale(),
+        );
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// The topmost board at the given position.
+    fn board_at(&self, pos: na::Vector2<f64>) -> Option<StrokeKey> {
+        self.store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|key| {
+                matches!(
+                    self.store.get_stroke_ref(*key),
+                    Some(Stroke::BoardStroke(_))
+                )
+            })
+    }
+
+    /// The column titles of the board at the given position.
+    pub fn board_columns_at(&self, pos: na::Vector2<f64>) -> Option<Vec<String>> {
+        match self.store.get_stroke_ref(self.board_at(pos)?) {
+            Some(Stroke::BoardStroke(board)) => Some(board.columns.clone()),
+            _ => None,
+        }
+    }
+
+    /// Set the column titles of the board at the given position. The notes in the columns are reflowed.
+    pub fn board_set_columns_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        columns: Vec<String>,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(key) = self.board_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::BoardStroke(board)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        board.columns = columns;
+        let reflowed = self.store.reflow_boards(&[key]);
+        self.store.regenerate_rendering_for_strokes(
+            &reflowed,
+            self.camera.viewport(),
+            self.camera.image_scale(),
+        );
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// The topmost mind map with a node at the given position.
+    fn mind_map_at(&self, pos: na::Vector2<f64>) -> Option<StrokeKey> {
+        self.store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|key| {
+                matches!(
+                    self.store.get_stroke_ref(*key),
+                    Some(Stroke::MindMapStroke(_))
+                )
+            })
+    }
+
+    /// The nodes of the mind map at the given position as outline, and its layout.
+    pub fn mind_map_outline_at(&self, pos: na::Vector2<f64>) -> Option<(String, MindMapLayout)> {
+        match self.store.get_stroke_ref(self.mind_map_at(pos)?) {
+            Some(Stroke::MindMapStroke(mind_map)) => {
+                Some((mind_map.root.to_outline(), mind_map.layout))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replace the nodes and the layout of the mind map at the given position.
+    pub fn mind_map_set_outline_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        root: MindMapNode,
+        layout: MindMapLayout,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(key) = self.mind_map_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::MindMapStroke(mind_map)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        mind_map.set_root(root);
+        mind_map.layout = layout;
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// Collapse or expand the mind map node at the given position.
+    pub fn mind_map_toggle_collapsed_at(&mut self, pos: na::Vector2<f64>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(key) = self.mind_map_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::MindMapStroke(mind_map)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        let Some(path) = mind_map.node_at(pos) else {
+            return widget_flags;
+        };
+        if !mind_map.toggle_collapsed(&path) {
+            return widget_flags;
+        }
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
+    /// The topmost frame with its border or its name at the given position.
+    fn frame_at(&self, pos: na::Vector2<f64>) -> Option<StrokeKey> {
+        self.store
+            .stroke_hitboxes_contain_coord(self.camera.viewport(), pos)
+            .into_iter()
+            .rev()
+            .find(|key| {
+                matches!(
+                    self.store.get_stroke_ref(*key),
+                    Some(Stroke::FrameStroke(_))
+                )
+            })
+    }
+
+    /// The name of the frame at the given position.
+    pub fn frame_name_at(&self, pos: na::Vector2<f64>) -> Option<String> {
+        match self.store.get_stroke_ref(self.frame_at(pos)?) {
+            Some(Stroke::FrameStroke(frame)) => Some(frame.name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Rename the frame at the given position.
+    pub fn frame_set_name_at(&mut self, pos: na::Vector2<f64>, name: String) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(key) = self.frame_at(pos) else {
+            return widget_flags;
+        };
+        let Some(Stroke::FrameStroke(frame)) = self.store.get_stroke_mut(key) else {
+            return widget_flags;
+        };
+        frame.name = name;
+        widget_flags |= self.finish_stroke_modification(key);
+        widget_flags
+    }
+
     pub fn text_change_color(&mut self, color: Color) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
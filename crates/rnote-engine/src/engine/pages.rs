@@ -0,0 +1,456 @@
+// Imports
+use super::{Engine, EngineSnapshot, StrokeContent};
+use crate::document::links::{Link, LinkTarget, Links};
+use crate::document::{Bookmark, Layout};
+use crate::render;
+use crate::store::StrokeKey;
+use crate::WidgetFlags;
+use futures::channel::oneshot;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::transform::Transformable;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::error;
+
+/// Operations on the pages of documents with a fixed width, where the pages are stacked vertically.
+///
+/// Strokes belong to the page that contains the center of their bounds.
+impl Engine {
+    /// Whether the pages can be rearranged. Requires a layout where the pages are stacked vertically.
+    pub fn pages_rearrangeable(&self) -> bool {
+        self.document.layout.is_fixed_width() && self.document.format.height() > 0.0
+    }
+
+    /// The number of vertically stacked pages.
+    pub fn pages_count(&self) -> usize {
+        if !self.pages_rearrangeable() {
+            return 0;
+        }
+        ((self.document.height / self.document.format.height()).round() as usize).max(1)
+    }
+
+    /// The bounds of the vertically stacked pages, from top to bottom.
+    pub fn pages_bounds_vertical(&self) -> Vec<Aabb> {
+        (0..self.pages_count())
+            .map(|i| self.document.page_bounds([0, i as i64]))
+            .collect()
+    }
+
+    /// Generate thumbnail images of all pages with the given width.
+    pub fn gen_page_thumbnails(
+        &self,
+        width: f64,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<render::Image>>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Vec<render::Image>>>();
+        let image_scale = width / self.document.format.width().max(1.0);
+        let pages_content = self
+            .pages_bounds_vertical()
+            .into_iter()
+            .map(|bounds| {
+                StrokeContent::default()
+                    .with_strokes(
+                        self.store.get_strokes_arc(
+                            &self
+                                .store
+                                .stroke_keys_as_rendered_intersecting_bounds(bounds),
+                        ),
+                    )
+                    .with_bounds(Some(bounds))
+                    .with_background(Some(self.document.background))
+                    .with_page_backgrounds(self.document.page_backgrounds_intersecting(bounds))
+            })
+            .collect::<Vec<StrokeContent>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<render::Image>> {
+                pages_content
+                    .into_iter()
+                    .map(|page_content| {
+                        let svg = page_content
+                            .gen_svg(true, true, false, 0.0)?
+                            .ok_or_else(|| anyhow::anyhow!("Page content has no bounds."))?;
+                        svg.gen_image(image_scale)
+                    })
+                    .collect()
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while generating page thumbnails. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Move the page to a new index, shifting the pages in between.
+    pub fn page_move(&mut self, from: usize, to: usize) -> WidgetFlags {
+        let n_pages = self.pages_count();
+        if from >= n_pages || to >= n_pages || from == to {
+            return WidgetFlags::default();
+        }
+        let mut order = (0..n_pages).collect::<Vec<usize>>();
+        let moved = order.remove(from);
+        order.insert(to, moved);
+
+        let widget_flags = self.load_pending_strokes();
+        let pages_keys = self.pages_stroke_keys();
+        for (new_index, &old_index) in order.iter().enumerate() {
+            if new_index != old_index {
+                self.translate_page_strokes(
+                    &pages_keys[old_index],
+                    new_index as i64 - old_index as i64,
+                );
+            }
+        }
+        for page_background in self.document.page_backgrounds.iter_mut() {
+            let Some(new_index) = usize::try_from(page_background.page[1])
+                .ok()
+                .and_then(|old_index| order.iter().position(|&i| i == old_index))
+            else {
+                continue;
+            };
+            page_background.page[1] = new_index as i64;
+        }
+        self.pages_remap_bookmarks_links(|page| {
+            Some(order.iter().position(|&i| i == page).unwrap_or(page))
+        });
+
+        widget_flags | self.pages_modified()
+    }
+
+    /// Insert a copy of the page below it.
+    pub fn page_duplicate(&mut self, index: usize) -> WidgetFlags {
+        if index >= self.pages_count() {
+            return WidgetFlags::default();
+        }
+        let widget_flags = self.load_pending_strokes();
+        let pages_keys = self.pages_stroke_keys();
+        for keys in pages_keys.iter().skip(index + 1) {
+            self.translate_page_strokes(keys, 1);
+        }
+
+        let offset = na::vector![0.0, self.document.format.height()];
+        let mut key_map = HashMap::new();
+        let duplicated = pages_keys[index]
+            .iter()
+            .filter_map(|&key| {
+                let mut stroke = self.store.get_stroke_ref(key)?.clone();
+                stroke.translate(offset);
+                let layer = self.store.stroke_layer(key);
+                let new_key = self.store.insert_stroke(stroke, layer);
+                key_map.insert(key, new_key);
+                Some(new_key)
+            })
+            .collect::<Vec<StrokeKey>>();
+        self.store
+            .remap_connector_attachments(&duplicated, &key_map);
+        self.store.update_geometry_for_strokes(&duplicated);
+
+        let mut duplicated_backgrounds = vec![];
+        for page_background in self.document.page_backgrounds.iter_mut() {
+            if page_background.page[1] > index as i64 {
+                page_background.page[1] += 1;
+            } else if page_background.page[1] == index as i64 {
+                let mut duplicated_background = *page_background;
+                duplicated_background.page[1] += 1;
+                duplicated_backgrounds.push(duplicated_background);
+            }
+        }
+        self.document
+            .page_backgrounds
+            .extend(duplicated_backgrounds);
+
+        // Bookmarks and links on the page are duplicated as well, links within the page then lead to the copy
+        let format_height = self.document.format.height();
+        let shift_page = |page: usize| if page > index { page + 1 } else { page };
+        let copy_pos = |pos: na::Vector2<f64>| -> Option<na::Vector2<f64>> {
+            let page = page_at(pos, format_height)?;
+            let new_page = if page == index {
+                page + 1
+            } else {
+                shift_page(page)
+            };
+            Some(pos + na::vector![0.0, (new_page as f64 - page as f64) * format_height])
+        };
+        let duplicated_bookmarks = self
+            .document
+            .bookmarks
+            .iter()
+            .filter(|bookmark| page_at(bookmark.pos, format_height) == Some(index))
+            .filter_map(|bookmark| {
+                Some(Bookmark::new(
+                    bookmark.name.clone(),
+                    copy_pos(bookmark.pos)?,
+                ))
+            })
+            .collect::<Vec<Bookmark>>();
+        // Anchored links are duplicated along with their strokes
+        let duplicated_links = self
+            .document
+            .links
+            .iter()
+            .filter_map(|link| {
+                if link.keys.is_empty() {
+                    if page_at(link.bounds.mins.coords, format_height) != Some(index) {
+                        return None;
+                    }
+                    return map_link(link, &copy_pos);
+                }
+                let keys = link
+                    .keys
+                    .iter()
+                    .filter_map(|key| key_map.get(key).copied())
+                    .collect::<Vec<StrokeKey>>();
+                if keys.is_empty() {
+                    return None;
+                }
+                let mut link = map_link(link, &copy_pos)?;
+                link.bounds = link.bounds.translate(offset);
+                link.keys = keys;
+                Some(link)
+            })
+            .collect::<Vec<Link>>();
+        self.pages_remap_bookmarks_links(|page| Some(shift_page(page)));
+        self.document.bookmarks.extend(duplicated_bookmarks);
+        for link in duplicated_links {
+            self.document.links.push(link);
+        }
+        self.document.height += format_height;
+
+        widget_flags | self.pages_modified()
+    }
+
+    /// Remove the page and its strokes, moving the pages below it up.
+    pub fn page_delete(&mut self, index: usize) -> WidgetFlags {
+        self.pages_delete(&[index])
+    }
+
+    /// Remove the pages with the given indices and their strokes, moving the remaining pages up.
+    ///
+    /// At least one page is kept.
+    pub fn pages_delete(&mut self, indices: &[usize]) -> WidgetFlags {
+        let n_pages = self.pages_count();
+        let mut removed = indices
+            .iter()
+            .copied()
+            .filter(|i| *i < n_pages)
+            .collect::<Vec<usize>>();
+        removed.sort_unstable();
+        removed.dedup();
+        if removed.is_empty() {
+            return WidgetFlags::default();
+        }
+        // The number of removed pages above the given page
+        let n_removed_above = |page: usize| removed.partition_point(|i| *i < page);
+
+        let widget_flags = self.load_pending_strokes();
+        let pages_keys = self.pages_stroke_keys();
+        for (i, keys) in pages_keys.iter().enumerate() {
+            if removed.binary_search(&i).is_ok() {
+                self.store.set_trashed_keys(keys, true);
+            } else if n_removed_above(i) > 0 {
+                self.translate_page_strokes(keys, -(n_removed_above(i) as i64));
+            }
+        }
+
+        self.document
+            .page_backgrounds
+            .retain_mut(|page_background| {
+                let Ok(page) = usize::try_from(page_background.page[1]) else {
+                    return true;
+                };
+                if removed.binary_search(&page).is_ok() {
+                    return false;
+                }
+                page_background.page[1] -= n_removed_above(page) as i64;
+                true
+            });
+        self.pages_remap_bookmarks_links(|page| {
+            removed
+                .binary_search(&page)
+                .is_err()
+                .then(|| page - n_removed_above(page))
+        });
+        self.document.height -=
+            removed.len().min(n_pages - 1) as f64 * self.document.format.height();
+
+        widget_flags | self.pages_modified()
+    }
+
+    /// Take a snapshot of a document made up of the pages with the given indices, stacked in the given order.
+    ///
+    /// Bookmarks, links and page background overrides on the pages are taken over.
+    /// A page that is given more than once is only taken at its first position,
+    /// so that the height matches the final number of pages.
+    pub fn take_pages_snapshot(&self, indices: &[usize]) -> EngineSnapshot {
+        let mut snapshot = self.take_snapshot();
+        let n_pages = self.pages_count();
+        let mut taken = HashSet::new();
+        let indices = indices
+            .iter()
+            .copied()
+            .filter(|i| *i < n_pages && taken.insert(*i))
+            .collect::<Vec<usize>>();
+        let format_height = self.document.format.height();
+        let page_offset = |page: usize| -> Option<i64> {
+            let new_page = indices.iter().position(|i| *i == page)?;
+            Some(new_page as i64 - page as i64)
+        };
+        // The position on the extracted pages, or None when it is on a page that is not extracted
+        let map_pos = |pos: na::Vector2<f64>| -> Option<na::Vector2<f64>> {
+            let offset = page_offset(page_at(pos, format_height)?)?;
+            Some(pos + na::vector![0.0, offset as f64 * format_height])
+        };
+
+        let stroke_components = Arc::make_mut(&mut snapshot.stroke_components);
+        let chrono_components = Arc::make_mut(&mut snapshot.chrono_components);
+        let mut extracted_keys = HashSet::new();
+        for (page, keys) in self.pages_stroke_keys().into_iter().enumerate() {
+            let Some(offset) = page_offset(page) else {
+                continue;
+            };
+            for key in keys {
+                if let Some(stroke) = stroke_components.get_mut(key) {
+                    Arc::make_mut(stroke)
+                        .translate(na::vector![0.0, offset as f64 * format_height]);
+                }
+                extracted_keys.insert(key);
+            }
+        }
+        stroke_components.retain(|key, _| extracted_keys.contains(&key));
+        chrono_components.retain(|key, _| extracted_keys.contains(&key));
+
+        let document = &mut snapshot.document;
+        document.height = indices.len().max(1) as f64 * format_height;
+        document.bookmarks = self
+            .document
+            .bookmarks
+            .iter()
+            .filter_map(|bookmark| {
+                Some(Bookmark::new(bookmark.name.clone(), map_pos(bookmark.pos)?))
+            })
+            .collect();
+        let mut links = Links::default();
+        for link in self
+            .document
+            .links
+            .iter()
+            .filter(|link| {
+                link.keys.is_empty() || link.keys.iter().any(|key| extracted_keys.contains(key))
+            })
+            .filter_map(|link| map_link(link, &map_pos))
+        {
+            links.push(link);
+        }
+        document.links = links;
+        document.page_backgrounds = self
+            .document
+            .page_backgrounds
+            .iter()
+            .filter_map(|page_background| {
+                let page = usize::try_from(page_background.page[1]).ok()?;
+                let mut page_background = *page_background;
+                page_background.page[1] += page_offset(page)?;
+                Some(page_background)
+            })
+            .collect();
+
+        snapshot
+    }
+
+    /// The keys of the strokes of each page.
+    fn pages_stroke_keys(&self) -> Vec<Vec<StrokeKey>> {
+        let format_height = self.document.format.height();
+        let mut pages_keys = vec![vec![]; self.pages_count()];
+        for key in self.store.stroke_keys_as_rendered() {
+            let Some(bounds) = self.store.bounds_for_strokes(&[key]) else {
+                continue;
+            };
+            let index = (bounds.center()[1] / format_height).floor();
+            if index >= 0.0 && (index as usize) < pages_keys.len() {
+                pages_keys[index as usize].push(key);
+            }
+        }
+        pages_keys
+    }
+
+    /// Translate the strokes by the given number of pages.
+    fn translate_page_strokes(&mut self, keys: &[StrokeKey], n_pages: i64) {
+        let offset = na::vector![0.0, n_pages as f64 * self.document.format.height()];
+        self.store.translate_strokes(keys, offset);
+        self.store.translate_strokes_images(keys, offset);
+        self.store.reroute_attached_connectors(keys);
+    }
+
+    /// Move the bookmarks and links along with their pages.
+    ///
+    /// `new_page` returns the new index of a page, or `None` when it is removed. Bookmarks and links on removed pages
+    /// and links leading to them are removed as well.
+    fn pages_remap_bookmarks_links(&mut self, new_page: impl Fn(usize) -> Option<usize>) {
+        let format_height = self.document.format.height();
+        let map_pos = |pos: na::Vector2<f64>| -> Option<na::Vector2<f64>> {
+            let Some(page) = page_at(pos, format_height) else {
+                return Some(pos);
+            };
+            let offset = new_page(page)? as f64 - page as f64;
+            Some(pos + na::vector![0.0, offset * format_height])
+        };
+
+        self.document.bookmarks = std::mem::take(&mut self.document.bookmarks)
+            .into_iter()
+            .filter_map(|bookmark| Some(Bookmark::new(bookmark.name, map_pos(bookmark.pos)?)))
+            .collect();
+        let mut links = Links::default();
+        for link in self
+            .document
+            .links
+            .iter()
+            .filter_map(|link| map_link(link, &map_pos))
+        {
+            links.push(link);
+        }
+        self.document.links = links;
+    }
+
+    fn pages_modified(&mut self) -> WidgetFlags {
+        self.doc_record_history_state();
+        let mut widget_flags = self.current_pen_update_state();
+        if self.document.layout != Layout::FixedSize {
+            widget_flags |= self.doc_resize_autoexpand();
+        }
+        widget_flags |= self.record(Instant::now())
+            | self.background_rendering_regenerate()
+            | self.update_rendering_current_viewport();
+        widget_flags.resize = true;
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+}
+
+/// The index of the vertically stacked page at the position, or `None` when it is above the first page.
+fn page_at(pos: na::Vector2<f64>, format_height: f64) -> Option<usize> {
+    let page = (pos[1] / format_height).floor();
+    (page >= 0.0).then_some(page as usize)
+}
+
+/// Move the link and the position it leads to with `map_pos`, which returns `None` for positions that are removed.
+///
+/// Links that are anchored to strokes already move along with them, so only the position they lead to is mapped.
+fn map_link(
+    link: &Link,
+    map_pos: impl Fn(na::Vector2<f64>) -> Option<na::Vector2<f64>>,
+) -> Option<Link> {
+    let bounds = if link.keys.is_empty() {
+        let mins = map_pos(link.bounds.mins.coords)?;
+        link.bounds.translate(mins - link.bounds.mins.coords)
+    } else {
+        link.bounds
+    };
+    let target = match &link.target {
+        LinkTarget::Position(pos) => LinkTarget::Position(map_pos(*pos)?),
+        target => target.clone(),
+    };
+    Some(Link::new_anchored(bounds, target, link.keys.clone()))
+}
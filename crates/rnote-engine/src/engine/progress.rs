@@ -0,0 +1,78 @@
+// Imports
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The progress of a long running operation, like importing a large Pdf or exporting many pages.
+///
+/// It is shared between the worker thread that runs the operation and the UI,
+/// which reports it and may cancel the operation mid-way.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    inner: Arc<ProgressInner>,
+}
+
+#[derive(Debug, Default)]
+struct ProgressInner {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+/// The error returned by operations that were cancelled through their [Progress].
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("the operation was cancelled")]
+pub struct Cancelled;
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the total number of steps of the operation. Resets the steps that are done.
+    pub fn set_total(&self, total: usize) {
+        self.inner.done.store(0, Ordering::Relaxed);
+        self.inner.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Mark one step of the operation as done.
+    pub fn advance(&self) {
+        self.inner.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The fraction of the operation that is done, between 0.0 and 1.0.
+    ///
+    /// None when the total number of steps is not known (yet).
+    pub fn fraction(&self) -> Option<f64> {
+        let total = self.inner.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return None;
+        }
+        let done = self.inner.done.load(Ordering::Relaxed);
+        Some((done as f64 / total as f64).clamp(0.0, 1.0))
+    }
+
+    /// Request the cancellation of the operation.
+    ///
+    /// The operation stops the next time it checks for cancellation and returns the [Cancelled] error.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the [Cancelled] error if the cancellation of the operation was requested.
+    pub fn check_cancelled(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Whether the error was caused by cancelling the operation.
+pub fn is_cancelled_err(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Cancelled>().is_some()
+}
@@ -1,12 +1,49 @@
 // Imports
-use crate::render::Image;
+use crate::engine::StrokeContent;
+use crate::render::{self, Image};
 use crate::{Engine, WidgetFlags};
+use futures::channel::oneshot;
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
 use rnote_compose::color;
+use rnote_compose::ext::AabbExt;
 use tracing::error;
 
 impl Engine {
+    /// Generate an image of the whole document with the given width, used as an overview for navigating it.
+    ///
+    /// The image covers the document bounds at the time of the call.
+    pub fn gen_overview_image(&self, width: f64) -> oneshot::Receiver<anyhow::Result<Image>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Image>>();
+        let bounds = self.document.bounds();
+        let image_scale = width / bounds.extents()[0].max(1.0);
+        let content = StrokeContent::default()
+            .with_strokes(
+                self.store.get_strokes_arc(
+                    &self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(bounds),
+                ),
+            )
+            .with_bounds(Some(bounds))
+            .with_background(Some(self.document.background))
+            .with_page_backgrounds(self.document.page_backgrounds_intersecting(bounds));
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Image> {
+                let svg = content
+                    .gen_svg(true, false, false, 0.0)?
+                    .ok_or_else(|| anyhow::anyhow!("Document content has no bounds."))?;
+                svg.gen_image(image_scale)
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while generating the document overview image. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Update the background rendering for the current viewport.
     ///
     /// If the background pattern or zoom has changed, the background pattern needs to be regenerated first.
@@ -15,7 +52,7 @@ impl Engine {
 
         #[cfg(feature = "ui")]
         {
-            use crate::ext::GrapheneRectExt;
+            use crate::ext::{GdkRGBAExt, GrapheneRectExt};
             use gtk4::{graphene, gsk, prelude::*};
             use rnote_compose::ext::AabbExt;
             use rnote_compose::SplitOrder;
@@ -47,6 +84,50 @@ impl Engine {
                 }
             }
 
+            // Pages with a background override are drawn over the document background
+            for (page_background, image) in self
+                .document
+                .page_backgrounds
+                .iter()
+                .zip(self.page_background_tile_images.iter())
+            {
+                let page_bounds = self.document.page_bounds(page_background.page);
+                let Some(visible_bounds) = page_bounds.intersection(&viewport) else {
+                    continue;
+                };
+                let texture = match image.to_memtexture() {
+                    Ok(t) => t,
+                    Err(e) => {
+                        error!("Failed to generate memory-texture of page background tile image, Err: {e:?}");
+                        continue;
+                    }
+                };
+                let mut page_rendernodes: Vec<gsk::RenderNode> = vec![gsk::ColorNode::new(
+                    &gtk4::gdk::RGBA::from_compose_color(page_background.background.color),
+                    &graphene::Rect::from_p2d_aabb(page_bounds),
+                )
+                .upcast()];
+                for split_bounds in visible_bounds.split_extended_origin_aligned(
+                    page_background.background.tile_size(),
+                    SplitOrder::default(),
+                ) {
+                    page_rendernodes.push(
+                        gsk::TextureNode::new(
+                            &texture,
+                            &graphene::Rect::from_p2d_aabb(split_bounds),
+                        )
+                        .upcast(),
+                    );
+                }
+                rendernodes.push(
+                    gsk::ClipNode::new(
+                        &gsk::ContainerNode::new(&page_rendernodes),
+                        &graphene::Rect::from_p2d_aabb(page_bounds),
+                    )
+                    .upcast(),
+                );
+            }
+
             self.background_rendernodes = rendernodes;
         }
 
@@ -89,6 +170,19 @@ impl Engine {
     /// Update the content rendering for the current viewport.
     pub fn update_content_rendering_current_viewport(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
+        // Strokes of a lazily loaded document that came into view are loaded right away
+        let viewport = self.camera.viewport();
+        if !self
+            .store
+            .load_pending_strokes(Some(
+                viewport.extend_by(viewport.extents() * render::VIEWPORT_EXTENTS_MARGIN_FACTOR),
+            ))
+            .is_empty()
+        {
+            self.document
+                .links
+                .remap_keys(self.store.pending_strokes_keys());
+        }
         self.store.regenerate_rendering_in_viewport_threaded(
             self.engine_tasks_tx(),
             false,
@@ -112,6 +206,7 @@ impl Engine {
         let mut widget_flags = WidgetFlags::default();
         self.store.clear_rendering();
         self.background_tile_image.take();
+        self.page_background_tile_images.clear();
         self.origin_indicator_image.take();
         #[cfg(feature = "ui")]
         {
@@ -138,6 +233,21 @@ impl Engine {
             }
         }
 
+        self.page_background_tile_images = self
+            .document
+            .page_backgrounds
+            .iter()
+            .filter_map(|page_background| {
+                match page_background.background.gen_tile_image(image_scale) {
+                    Ok(image) => Some(image),
+                    Err(e) => {
+                        error!("Regenerating page background tile image failed, Err: {e:?}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
         match gen_origin_indicator_image(scale_factor) {
             Ok(image) => {
                 self.origin_indicator_image = Some(image);
@@ -156,6 +266,7 @@ impl Engine {
 
     /// Draws the entire engine (doc, pens, strokes, selection, ..) to a GTK snapshot.
     #[cfg(feature = "ui")]
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn draw_to_gtk_snapshot(
         &self,
         snapshot: &gtk4::Snapshot,
@@ -165,19 +276,20 @@ impl Engine {
         use crate::engine::visual_debug;
         use crate::engine::EngineView;
         use gtk4::prelude::*;
+        use std::time::Instant;
 
+        let frame_start = Instant::now();
         let doc_bounds = self.document.bounds();
         let viewport = self.camera.viewport();
         let camera_transform = self.camera.transform_for_gtk_snapshot();
+        let color_vision_matrix = self.color_vision_filter.gtk_color_matrix();
 
+        if let Some((color_matrix, color_offset)) = &color_vision_matrix {
+            snapshot.push_color_matrix(color_matrix, color_offset);
+        }
         snapshot.save();
         snapshot.transform(Some(&camera_transform));
-        self.draw_document_shadow_to_gtk_snapshot(snapshot);
-        self.draw_background_to_gtk_snapshot(snapshot)?;
-        self.draw_format_borders_to_gtk_snapshot(snapshot)?;
-        self.draw_origin_indicator_to_gtk_snapshot(snapshot)?;
-        self.store
-            .draw_strokes_to_gtk_snapshot(snapshot, doc_bounds, viewport);
+        let doc_res = self.draw_doc_to_gtk_snapshot(snapshot, doc_bounds, viewport);
         snapshot.restore();
         /*
                let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(surface_bounds));
@@ -190,30 +302,84 @@ impl Engine {
                    self.camera.image_scale(),
                );
         */
-        self.penholder.draw_on_doc_to_gtk_snapshot(
-            snapshot,
-            &EngineView {
-                tasks_tx: self.engine_tasks_tx(),
-                pens_config: &self.pens_config,
-                document: &self.document,
-                store: &self.store,
-                camera: &self.camera,
-                audioplayer: &self.audioplayer,
-            },
-        )?;
+        let res = doc_res.and_then(|strokes_draw_stats| {
+            self.penholder.draw_on_doc_to_gtk_snapshot(
+                snapshot,
+                &EngineView {
+                    tasks_tx: self.engine_tasks_tx(),
+                    pens_config: &self.pens_config,
+                    document: &self.document,
+                    store: &self.store,
+                    camera: &self.camera,
+                    audioplayer: &self.audioplayer,
+                },
+            )?;
+
+            // The rulers are aligned to the document axes, which are not aligned to the surface when the view is
+            // rotated
+            if self.show_rulers && self.camera.rotation() == 0.0 {
+                self.draw_rulers_to_gtk_snapshot(snapshot, surface_bounds)?;
+            }
+            Ok(strokes_draw_stats)
+        });
+        // The filter is always popped, so that the snapshot stays balanced when drawing fails
+        if color_vision_matrix.is_some() {
+            snapshot.pop();
+        }
+        let strokes_draw_stats = res?;
 
         if self.visual_debug {
             snapshot.save();
             snapshot.transform(Some(&camera_transform));
-            visual_debug::draw_stroke_debug_to_gtk_snapshot(snapshot, self, surface_bounds)?;
+            let res =
+                visual_debug::draw_stroke_debug_to_gtk_snapshot(snapshot, self, surface_bounds);
             snapshot.restore();
+            res?;
 
             visual_debug::draw_statistics_to_gtk_snapshot(snapshot, self, surface_bounds)?;
         }
 
+        if self.debug_hud {
+            let mut render_stats = self.render_stats.borrow_mut();
+            render_stats.record_frame(
+                frame_start,
+                strokes_draw_stats,
+                self.store.stroke_keys_as_rendered().len(),
+            );
+            visual_debug::draw_hud_to_gtk_snapshot(snapshot, &render_stats, surface_bounds)?;
+        }
+
         Ok(())
     }
 
+    /// Draws the document and its strokes, in document coordinates.
+    #[cfg(feature = "ui")]
+    fn draw_doc_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        doc_bounds: p2d::bounding_volume::Aabb,
+        viewport: p2d::bounding_volume::Aabb,
+    ) -> anyhow::Result<crate::store::render_comp::StrokesDrawStats> {
+        self.draw_document_shadow_to_gtk_snapshot(snapshot);
+        self.draw_background_to_gtk_snapshot(snapshot)?;
+        self.draw_format_borders_to_gtk_snapshot(snapshot)?;
+        self.draw_origin_indicator_to_gtk_snapshot(snapshot)?;
+        let strokes_draw_stats = self.store.draw_strokes_to_gtk_snapshot(
+            snapshot,
+            doc_bounds,
+            viewport,
+            self.contrast_mode,
+            &self.document,
+        );
+        self.draw_guides_to_gtk_snapshot(snapshot);
+        self.draw_perspective_to_gtk_snapshot(snapshot)?;
+        self.draw_links_to_gtk_snapshot(snapshot);
+        self.draw_spellcheck_to_gtk_snapshot(snapshot)?;
+        self.draw_collab_cursors_to_gtk_snapshot(snapshot)?;
+        self.draw_pen_hover_preview_to_gtk_snapshot(snapshot)?;
+        Ok(strokes_draw_stats)
+    }
+
     #[cfg(feature = "ui")]
     fn draw_document_shadow_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
@@ -326,6 +492,304 @@ impl Engine {
         Ok(())
     }
 
+    /// Draw the guide lines of the document.
+    #[cfg(feature = "ui")]
+    fn draw_guides_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
+        use crate::ext::GrapheneRectExt;
+        use gtk4::{gdk, graphene};
+        use p2d::bounding_volume::BoundingVolume;
+
+        let line_width = 1.0 / self.camera.total_zoom();
+        let viewport = self.camera.viewport();
+
+        for guide in self.document.guides.iter() {
+            let (start, end) = guide.line_in_bounds(viewport);
+            let line_bounds = Aabb::new(start.into(), end.into()).loosened(line_width * 0.5);
+            snapshot.append_color(
+                &gdk::RGBA::new(0.208, 0.518, 0.894, 0.9),
+                &graphene::Rect::from_p2d_aabb(line_bounds),
+            );
+        }
+    }
+
+    /// Draw the perspective guide lines radiating from the vanishing points.
+    #[cfg(feature = "ui")]
+    fn draw_perspective_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) -> anyhow::Result<()> {
+        use crate::document::Perspective;
+        use crate::ext::GrapheneRectExt;
+        use gtk4::graphene;
+        use rnote_compose::ext::Vector2Ext;
+        const LINE_COLOR: piet::Color = color::GNOME_PURPLES[2].with_a8(90);
+        const POINT_COLOR: piet::Color = color::GNOME_PURPLES[3];
+
+        let vanishing_points = self.document.perspective.vanishing_points();
+        if vanishing_points.is_empty() {
+            return Ok(());
+        }
+        let total_zoom = self.camera.total_zoom();
+        let viewport = self.camera.viewport();
+        let line_width = 1.0 / total_zoom;
+        let point_radius = Perspective::GRAB_DIST * 0.5 / total_zoom;
+        let n_lines = (360.0 / Perspective::LINES_ANGLE_STEP).round() as usize;
+
+        let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(viewport));
+        let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+
+        for vp in vanishing_points {
+            // Long enough to always reach the viewport edges
+            let len = (viewport.center().coords - vp).norm() + viewport.extents().norm();
+            let mut lines = kurbo::BezPath::new();
+            for i in 0..n_lines {
+                let angle = (i as f64 * Perspective::LINES_ANGLE_STEP).to_radians();
+                lines.move_to(vp.to_kurbo_point());
+                lines.line_to(
+                    vp.to_kurbo_point() + kurbo::Vec2::new(angle.cos(), angle.sin()) * len,
+                );
+            }
+            piet_cx.stroke(lines, &LINE_COLOR, line_width);
+            piet_cx.stroke(
+                kurbo::Circle::new(vp.to_kurbo_point(), point_radius),
+                &POINT_COLOR,
+                line_width * 2.0,
+            );
+        }
+
+        piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// Draw the link regions of the document, tinted and underlined.
+    #[cfg(feature = "ui")]
+    fn draw_links_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
+        use crate::ext::GrapheneRectExt;
+        use gtk4::{gdk, graphene};
+        use p2d::bounding_volume::BoundingVolume;
+
+        let line_width = 1.0 / self.camera.total_zoom();
+        let viewport = self.camera.viewport();
+
+        for bounds in self
+            .document
+            .links
+            .iter()
+            .filter_map(|link| link.current_bounds(&self.store))
+            .filter(|bounds| bounds.intersects(&viewport))
+        {
+            snapshot.append_color(
+                &gdk::RGBA::new(0.208, 0.518, 0.894, 0.08),
+                &graphene::Rect::from_p2d_aabb(bounds),
+            );
+            let underline_bounds = Aabb::new(
+                na::point![bounds.mins[0], bounds.maxs[1] - line_width],
+                bounds.maxs,
+            );
+            snapshot.append_color(
+                &gdk::RGBA::new(0.208, 0.518, 0.894, 0.9),
+                &graphene::Rect::from_p2d_aabb(underline_bounds),
+            );
+        }
+    }
+
+    /// Draw the cursors of the remote peers of the collaboration session, labeled with their names.
+    #[cfg(feature = "ui")]
+    fn draw_collab_cursors_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) -> anyhow::Result<()> {
+        use crate::ext::GrapheneRectExt;
+        use gtk4::graphene;
+        use piet::{Text, TextLayout, TextLayoutBuilder};
+        use rnote_compose::ext::Vector2Ext;
+        const LABEL_FONT_SIZE: f64 = 12.0;
+        const LABEL_PADDING: f64 = 3.0;
+
+        let Some(session) = &self.collab_session else {
+            return Ok(());
+        };
+        let total_zoom = self.camera.total_zoom();
+        let viewport = self.camera.viewport();
+        let radius = 5.0 / total_zoom;
+
+        let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(viewport));
+        let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+
+        for cursor in session.remote_cursors() {
+            piet_cx.fill(
+                kurbo::Circle::new(cursor.pos.to_kurbo_point(), radius),
+                &cursor.color,
+            );
+
+            // The label keeps its size independent of the zoom
+            let text_layout = piet_cx
+                .text()
+                .new_text_layout(cursor.name.clone())
+                .text_color(color::GNOME_BRIGHTS[0])
+                .font(piet::FontFamily::SANS_SERIF, LABEL_FONT_SIZE)
+                .build()
+                .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            let label_size = text_layout.size();
+            piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            piet_cx.transform(
+                kurbo::Affine::translate((cursor.pos + na::Vector2::repeat(radius)).to_kurbo_vec())
+                    * kurbo::Affine::scale(1.0 / total_zoom),
+            );
+            piet_cx.fill(
+                kurbo::Rect::new(
+                    0.0,
+                    0.0,
+                    label_size.width + LABEL_PADDING * 2.0,
+                    label_size.height + LABEL_PADDING * 2.0,
+                )
+                .to_rounded_rect(LABEL_PADDING),
+                &cursor.color,
+            );
+            piet_cx.draw_text(&text_layout, (LABEL_PADDING, LABEL_PADDING));
+            piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// Draw an outline of the size of the current pen at the cursor while it is hovering above the canvas.
+    ///
+    /// Brushes show a circle in their color when enabled,
+    /// the eraser always outlines its square area so that its effective area is visible before erasing.
+    #[cfg(feature = "ui")]
+    fn draw_pen_hover_preview_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+    ) -> anyhow::Result<()> {
+        use crate::ext::GrapheneRectExt;
+        use crate::pens::PenStyle;
+        use gtk4::graphene;
+        use p2d::bounding_volume::BoundingVolume;
+        use rnote_compose::ext::Vector2Ext;
+        use rnote_compose::penevent::PenProgress;
+        use rnote_compose::penpath::Element;
+
+        let Some(cursor_pos) = self.cursor_pos else {
+            return Ok(());
+        };
+        if self.penholder.current_pen_progress() != PenProgress::Idle {
+            return Ok(());
+        }
+        let outline_width = 1.0 / self.camera.total_zoom();
+        // The contrasting outer outline keeps the preview visible on content with a similar color
+        let contrast_color = color::GNOME_BRIGHTS[0].with_a8(200);
+
+        match self.penholder.current_pen_style_w_override() {
+            PenStyle::Brush if self.pen_hover_preview => {
+                let style = self.pens_config.brush_config.style_for_current_options();
+                let pen_color = style
+                    .stroke_color()
+                    .map(piet::Color::from)
+                    .unwrap_or(color::GNOME_DARKS[4]);
+                let radius = (style.stroke_width() * 0.5).max(outline_width);
+                let bounds = Aabb::from_half_extents(
+                    cursor_pos.into(),
+                    na::Vector2::repeat(radius + outline_width * 2.0),
+                );
+
+                let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(bounds));
+                let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+                piet_cx.stroke(
+                    kurbo::Circle::new(cursor_pos.to_kurbo_point(), radius + outline_width),
+                    &contrast_color,
+                    outline_width,
+                );
+                piet_cx.stroke(
+                    kurbo::Circle::new(cursor_pos.to_kurbo_point(), radius),
+                    &pen_color,
+                    outline_width,
+                );
+                piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            }
+            PenStyle::Eraser => {
+                let eraser_bounds = self
+                    .pens_config
+                    .eraser_config
+                    .eraser_bounds(Element::new(cursor_pos, 0.0));
+                let bounds = eraser_bounds.loosened(outline_width * 2.0);
+
+                let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(bounds));
+                let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+                piet_cx.stroke(
+                    eraser_bounds.loosened(outline_width).to_kurbo_rect(),
+                    &contrast_color,
+                    outline_width,
+                );
+                piet_cx.stroke(
+                    eraser_bounds.to_kurbo_rect(),
+                    &color::GNOME_REDS[2],
+                    outline_width,
+                );
+                piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Draw squiggly lines beneath the misspelled words of the text that is currently being edited.
+    #[cfg(feature = "ui")]
+    fn draw_spellcheck_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) -> anyhow::Result<()> {
+        use crate::ext::GrapheneRectExt;
+        use crate::pens::Pen;
+        use crate::strokes::Stroke;
+        use gtk4::graphene;
+        use p2d::bounding_volume::BoundingVolume;
+        use rnote_compose::ext::AabbExt;
+        use unicode_segmentation::GraphemeCursor;
+        const SQUIGGLE_COLOR: piet::Color = color::GNOME_REDS[3];
+
+        let Pen::Typewriter(typewriter) = self.penholder.current_pen_ref() else {
+            return Ok(());
+        };
+        let Some(Stroke::TextStroke(textstroke)) = typewriter
+            .modifying_stroke_key()
+            .and_then(|key| self.store.get_stroke_ref(key))
+        else {
+            return Ok(());
+        };
+        let misspelled = self.spellcheck.misspelled_words(&textstroke.text);
+        if misspelled.is_empty() {
+            return Ok(());
+        }
+        let total_zoom = self.camera.total_zoom();
+        let amplitude = 1.5 / total_zoom;
+        let line_width = 1.0 / total_zoom;
+        let transform = textstroke.transform.to_kurbo();
+        let text_len = textstroke.text.len();
+
+        let mut squiggles = kurbo::BezPath::new();
+        for range in misspelled {
+            for rect in textstroke.text_style.get_selection_rects_for_cursors(
+                textstroke.text.clone(),
+                &GraphemeCursor::new(range.start, text_len, true),
+                &GraphemeCursor::new(range.end, text_len, true),
+            )? {
+                let n_waves = ((rect.width() / (amplitude * 2.0)).ceil() as usize).max(1);
+                let step = rect.width() / n_waves as f64;
+                squiggles.move_to(transform * kurbo::Point::new(rect.x0, rect.y1));
+                for i in 0..n_waves {
+                    let y = if i % 2 == 0 {
+                        rect.y1 + amplitude
+                    } else {
+                        rect.y1
+                    };
+                    squiggles
+                        .line_to(transform * kurbo::Point::new(rect.x0 + step * (i + 1) as f64, y));
+                }
+            }
+        }
+
+        let bounds = Aabb::from_kurbo_rect(kurbo::Shape::bounding_box(&squiggles))
+            .loosened(line_width + amplitude);
+        let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(bounds));
+        let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+        piet_cx.stroke(squiggles, &SQUIGGLE_COLOR, line_width);
+        piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
     /// Draw the document origin indicator cross.
     #[cfg(feature = "ui")]
     fn draw_origin_indicator_to_gtk_snapshot(
@@ -344,6 +808,265 @@ impl Engine {
     }
 }
 
+impl Engine {
+    /// The size of the rulers perpendicular to the canvas edges, in surface coordinates.
+    pub(crate) const RULER_SIZE: f64 = 20.0;
+    /// The minimum distance between the major ticks of the rulers, in surface coordinates.
+    const RULER_MAJOR_TICK_MIN_DIST: f64 = 60.0;
+
+    /// Draw the rulers along the top and left canvas edges and the cursor position readout.
+    ///
+    /// Expects that the snapshot is untransformed in surface coordinate space.
+    #[cfg(feature = "ui")]
+    fn draw_rulers_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        surface_bounds: Aabb,
+    ) -> anyhow::Result<()> {
+        use crate::document::format::MeasureUnit;
+        use crate::ext::GrapheneRectExt;
+        use gtk4::{graphene, prelude::*};
+        use piet::{Text, TextLayout, TextLayoutBuilder};
+        use rnote_compose::ext::{AabbExt, Vector2Ext};
+
+        const BACKGROUND_COLOR: piet::Color = piet::Color::rgba8(36, 36, 36, 220);
+        const TICK_COLOR: piet::Color = piet::Color::rgba8(222, 221, 218, 255);
+        const CURSOR_COLOR: piet::Color = color::GNOME_ORANGES[3];
+        const GUIDE_COLOR: piet::Color = color::GNOME_BLUES[3];
+
+        let unit = self.rulers_unit;
+        let dpi = self.document.format.dpi();
+        let transform = self.camera.transform();
+        let viewport = self.camera.viewport();
+        let unit_in_px = MeasureUnit::convert_measurement(1.0, unit, dpi, MeasureUnit::Px, dpi);
+        let (major_step, subdivisions) = ruler_major_step(
+            Self::RULER_MAJOR_TICK_MIN_DIST / (unit_in_px * self.camera.total_zoom()),
+        );
+        let minor_step = major_step / subdivisions as f64;
+        let cursor_pos = self
+            .cursor_pos
+            .map(|pos| transform.transform_point(&pos.into()).coords);
+
+        for vertical in [false, true] {
+            // The axis along the ruler
+            let axis = if vertical { 1 } else { 0 };
+            let ruler_bounds = if vertical {
+                Aabb::new(
+                    na::point![
+                        surface_bounds.mins[0],
+                        surface_bounds.mins[1] + Self::RULER_SIZE
+                    ],
+                    na::point![
+                        surface_bounds.mins[0] + Self::RULER_SIZE,
+                        surface_bounds.maxs[1]
+                    ],
+                )
+            } else {
+                Aabb::new(
+                    na::point![
+                        surface_bounds.mins[0] + Self::RULER_SIZE,
+                        surface_bounds.mins[1]
+                    ],
+                    na::point![
+                        surface_bounds.maxs[0],
+                        surface_bounds.mins[1] + Self::RULER_SIZE
+                    ],
+                )
+            };
+            let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(ruler_bounds));
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+            piet_cx.fill(ruler_bounds.to_kurbo_rect(), &BACKGROUND_COLOR);
+
+            let first_tick = (viewport.mins[axis] / unit_in_px / minor_step).floor() as i64;
+            let last_tick = (viewport.maxs[axis] / unit_in_px / minor_step).ceil() as i64;
+
+            for i in first_tick..=last_tick {
+                let value = i as f64 * minor_step;
+                let mut doc_pos = na::Vector2::<f64>::zeros();
+                doc_pos[axis] = value * unit_in_px;
+                let surface_pos = transform.transform_point(&doc_pos.into()).coords[axis];
+                if surface_pos < ruler_bounds.mins[axis] || surface_pos > ruler_bounds.maxs[axis] {
+                    continue;
+                }
+                let is_major = i.rem_euclid(subdivisions) == 0;
+                let tick_len = if is_major {
+                    Self::RULER_SIZE
+                } else {
+                    Self::RULER_SIZE * 0.3
+                };
+                let tick = if vertical {
+                    kurbo::Line::new(
+                        (ruler_bounds.maxs[0] - tick_len, surface_pos),
+                        (ruler_bounds.maxs[0], surface_pos),
+                    )
+                } else {
+                    kurbo::Line::new(
+                        (surface_pos, ruler_bounds.maxs[1] - tick_len),
+                        (surface_pos, ruler_bounds.maxs[1]),
+                    )
+                };
+                piet_cx.stroke(tick, &TICK_COLOR, 1.0);
+
+                if is_major {
+                    let text_layout = piet_cx
+                        .text()
+                        .new_text_layout(format_ruler_value(value))
+                        .text_color(TICK_COLOR)
+                        .font(piet::FontFamily::SANS_SERIF, 9.0)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                    if vertical {
+                        piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                        piet_cx.transform(
+                            kurbo::Affine::translate((
+                                ruler_bounds.mins[0] + 2.0,
+                                surface_pos - 2.0,
+                            )) * kurbo::Affine::rotate(-std::f64::consts::FRAC_PI_2),
+                        );
+                        piet_cx.draw_text(&text_layout, (0.0, 0.0));
+                        piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                    } else {
+                        piet_cx.draw_text(
+                            &text_layout,
+                            (surface_pos + 2.0, ruler_bounds.mins[1] + 1.0),
+                        );
+                    }
+                }
+            }
+
+            // Mark where the guides cross the ruler
+            for guide in self
+                .document
+                .guides
+                .iter()
+                .filter(|g| g.orientation.axis() == axis)
+            {
+                let mut doc_pos = na::Vector2::<f64>::zeros();
+                doc_pos[axis] = guide.position;
+                let surface_pos = transform.transform_point(&doc_pos.into()).coords[axis];
+                let marker = if vertical {
+                    kurbo::Line::new(
+                        (ruler_bounds.mins[0], surface_pos),
+                        (ruler_bounds.maxs[0], surface_pos),
+                    )
+                } else {
+                    kurbo::Line::new(
+                        (surface_pos, ruler_bounds.mins[1]),
+                        (surface_pos, ruler_bounds.maxs[1]),
+                    )
+                };
+                piet_cx.stroke(marker, &GUIDE_COLOR, 3.0);
+            }
+
+            if let Some(cursor_pos) = cursor_pos {
+                let marker = if vertical {
+                    kurbo::Line::new(
+                        (ruler_bounds.mins[0], cursor_pos[1]),
+                        (ruler_bounds.maxs[0], cursor_pos[1]),
+                    )
+                } else {
+                    kurbo::Line::new(
+                        (cursor_pos[0], ruler_bounds.mins[1]),
+                        (cursor_pos[0], ruler_bounds.maxs[1]),
+                    )
+                };
+                piet_cx.stroke(marker, &CURSOR_COLOR, 1.5);
+            }
+            piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        // The corner where the rulers meet
+        {
+            let corner_bounds = Aabb::new(
+                surface_bounds.mins,
+                surface_bounds.mins + na::Vector2::repeat(Self::RULER_SIZE),
+            );
+            let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(corner_bounds));
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+            piet_cx.fill(corner_bounds.to_kurbo_rect(), &BACKGROUND_COLOR);
+            let text_layout = piet_cx
+                .text()
+                .new_text_layout(unit.symbol())
+                .text_color(TICK_COLOR)
+                .font(piet::FontFamily::SANS_SERIF, 8.0)
+                .build()
+                .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            let text_pos = corner_bounds.center().coords
+                - na::Vector2::<f64>::from_kurbo_vec(text_layout.size().to_vec2()) * 0.5;
+            piet_cx.draw_text(&text_layout, text_pos.to_kurbo_point());
+            piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        // The cursor position readout
+        if let Some(cursor_pos) = self.cursor_pos {
+            let x =
+                MeasureUnit::convert_measurement(cursor_pos[0], MeasureUnit::Px, dpi, unit, dpi);
+            let y =
+                MeasureUnit::convert_measurement(cursor_pos[1], MeasureUnit::Px, dpi, unit, dpi);
+            let readout_bounds = Aabb::new(
+                na::point![
+                    surface_bounds.mins[0] + Self::RULER_SIZE + 6.0,
+                    surface_bounds.maxs[1] - 28.0
+                ],
+                na::point![
+                    surface_bounds.mins[0] + Self::RULER_SIZE + 206.0,
+                    surface_bounds.maxs[1] - 6.0
+                ],
+            );
+            let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(readout_bounds));
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+            let text_layout = piet_cx
+                .text()
+                .new_text_layout(format!(
+                    "x: {x:.2} {symbol}   y: {y:.2} {symbol}",
+                    symbol = unit.symbol()
+                ))
+                .text_color(TICK_COLOR)
+                .font(piet::FontFamily::MONOSPACE, 10.0)
+                .build()
+                .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            let text_size = na::Vector2::<f64>::from_kurbo_vec(text_layout.size().to_vec2());
+            let background_bounds = Aabb::new(
+                readout_bounds.mins,
+                readout_bounds.mins + text_size + na::Vector2::repeat(8.0),
+            );
+            piet_cx.fill(
+                background_bounds.to_kurbo_rect().to_rounded_rect(4.0),
+                &BACKGROUND_COLOR,
+            );
+            piet_cx.draw_text(
+                &text_layout,
+                (readout_bounds.mins.coords + na::Vector2::repeat(4.0)).to_kurbo_point(),
+            );
+            piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The step between the major ruler ticks and the number of subdivisions into minor ticks.
+///
+/// The step is the smallest of 1, 2, 5 times a power of ten that is at least the given minimum step.
+fn ruler_major_step(min_step: f64) -> (f64, i64) {
+    let magnitude = 10.0_f64.powf(min_step.log10().floor());
+    if min_step <= magnitude {
+        (magnitude, 10)
+    } else if min_step <= 2.0 * magnitude {
+        (2.0 * magnitude, 4)
+    } else if min_step <= 5.0 * magnitude {
+        (5.0 * magnitude, 5)
+    } else {
+        (10.0 * magnitude, 10)
+    }
+}
+
+fn format_ruler_value(value: f64) -> String {
+    // Strip insignificant digits that are introduced by the floating point arithmetic
+    let value = (value * 1e6).round() / 1e6;
+    format!("{value}")
+}
+
 /// Origin indicator bounds in document coordinate space.
 fn origin_indicator_bounds() -> Aabb {
     const SIZE: na::Vector2<f64> = na::vector![17., 17.];
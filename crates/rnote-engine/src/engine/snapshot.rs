@@ -1,16 +1,37 @@
 // Imports
-use crate::document::background;
+use crate::document::{background, Layout};
 use crate::engine::import::XoppImportPrefs;
-use crate::fileformats::{rnoteformat, xoppformat, FileFormatLoader};
-use crate::store::{ChronoComponent, StrokeKey};
-use crate::strokes::Stroke;
+use crate::fileformats::{enexformat, rnoteformat, xoppformat, FileFormatLoader};
+use crate::store::{ChronoComponent, HistoryEntry, PendingStroke, StrokeKey};
+use crate::strokes::resize::ImageSizeOption;
+use crate::strokes::textstroke::TextStyle;
+use crate::strokes::{BitmapImage, Stroke, TextStroke};
 use crate::{Camera, Document, Engine};
 use anyhow::Context;
 use futures::channel::oneshot;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
-use slotmap::{HopSlotMap, SecondaryMap};
+use slotmap::{HopSlotMap, KeyData, SecondaryMap};
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
+
+/// Mirrors how slotmap (de)serializes the slots of its maps, used to deserialize them slot by slot.
+#[derive(Debug, Serialize, Deserialize)]
+struct SerdeSlot<T> {
+    value: Option<T>,
+    version: u32,
+}
+
+impl<T> SerdeSlot<T> {
+    /// Mark the slot as vacant. Occupied slots have an odd version, vacant slots an even one.
+    fn vacate(&mut self) {
+        self.value = None;
+        if self.version % 2 == 1 {
+            self.version = self.version.wrapping_add(1);
+        }
+    }
+}
 
 // An engine snapshot, used when loading/saving the current document from/into a file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +47,14 @@ pub struct EngineSnapshot {
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    /// The bounds of the strokes, stored so that documents can be loaded lazily.
+    ///
+    /// Only read through [`EngineSnapshot::load_from_rnote_bytes_lazy()`].
+    #[serde(rename = "stroke_bounds", skip_deserializing)]
+    pub stroke_bounds: Arc<SecondaryMap<StrokeKey, Aabb>>,
+    /// Strokes that are not yet deserialized.
+    #[serde(skip)]
+    pub(crate) pending_strokes: Vec<PendingStroke>,
 }
 
 impl Default for EngineSnapshot {
@@ -36,22 +65,113 @@ impl Default for EngineSnapshot {
             stroke_components: Arc::new(HopSlotMap::with_key()),
             chrono_components: Arc::new(SecondaryMap::new()),
             chrono_counter: 0,
+            stroke_bounds: Arc::new(SecondaryMap::new()),
+            pending_strokes: vec![],
         }
     }
 }
 
 impl EngineSnapshot {
+    /// Create a snapshot from a history entry of the store, leaving out the trashed strokes.
+    ///
+    /// The components of history entries are shared and copy-on-write,
+    /// so this can run on another thread while the store keeps being edited.
+    pub(crate) fn from_history_entry(
+        document: Document,
+        camera: Camera,
+        mut history_entry: HistoryEntry,
+    ) -> Self {
+        let trashed_keys = history_entry
+            .trash_components
+            .iter()
+            .filter_map(|(key, trash_comp)| if trash_comp.trashed { Some(key) } else { None })
+            .collect::<Vec<StrokeKey>>();
+        if !trashed_keys.is_empty() {
+            let stroke_components = Arc::make_mut(&mut history_entry.stroke_components);
+            for key in trashed_keys {
+                stroke_components.remove(key);
+            }
+        }
+
+        let stroke_bounds = history_entry
+            .stroke_components
+            .iter()
+            .map(|(key, stroke)| (key, stroke.bounds()))
+            .collect();
+
+        Self {
+            document,
+            camera,
+            stroke_components: history_entry.stroke_components,
+            chrono_components: history_entry.chrono_components,
+            chrono_counter: history_entry.chrono_counter,
+            stroke_bounds: Arc::new(stroke_bounds),
+            pending_strokes: vec![],
+        }
+    }
+
     /// Loads a snapshot from the bytes of a .rnote file.
     ///
+    /// Parts of the document that can't be read are dropped and logged,
+    /// see [`EngineSnapshot::load_from_rnote_bytes_w_recovery()`].
+    ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
     pub async fn load_from_rnote_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
-        let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
+        let (snapshot, dropped) = Self::load_from_rnote_bytes_w_recovery(bytes).await?;
+        for d in dropped {
+            warn!("Dropped unreadable content while loading rnote bytes: {d}");
+        }
+        Ok(snapshot)
+    }
+
+    /// Loads a snapshot from the bytes of a .rnote file, recovering as much of a corrupt file as possible.
+    ///
+    /// Strokes (and other parts of the snapshot) that fail to deserialize are skipped.
+    /// Returns the snapshot together with descriptions of everything that was dropped.
+    #[tracing::instrument(skip_all, fields(n_bytes = bytes.len()))]
+    pub async fn load_from_rnote_bytes_w_recovery(
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<(Self, Vec<String>)> {
+        Self::load_from_rnote_bytes_impl(bytes, false).await
+    }
+
+    /// Loads a snapshot from the bytes of a .rnote file like
+    /// [`EngineSnapshot::load_from_rnote_bytes_w_recovery()`], but defers deserializing the strokes of large
+    /// documents that are far away from the stored view.
+    ///
+    /// The deferred strokes are loaded in the background after the snapshot was imported with
+    /// [`Engine::load_snapshot()`], or on demand when they come into view.
+    #[tracing::instrument(skip_all, fields(n_bytes = bytes.len()))]
+    pub async fn load_from_rnote_bytes_lazy(bytes: Vec<u8>) -> anyhow::Result<(Self, Vec<String>)> {
+        Self::load_from_rnote_bytes_impl(bytes, true).await
+    }
+
+    async fn load_from_rnote_bytes_impl(
+        bytes: Vec<u8>,
+        lazy: bool,
+    ) -> anyhow::Result<(Self, Vec<String>)> {
+        let (snapshot_sender, snapshot_receiver) =
+            oneshot::channel::<anyhow::Result<(Self, Vec<String>)>>();
 
         rayon::spawn(move || {
-            let result = || -> anyhow::Result<Self> {
-                let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
+            let result = || -> anyhow::Result<(Self, Vec<String>)> {
+                let mut rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
                     .context("loading RnoteFile from bytes failed.")?;
-                Ok(ijson::from_value(&rnote_file.engine_snapshot)?)
+                let pending_strokes = if lazy {
+                    Self::take_pending_strokes(&mut rnote_file.engine_snapshot)
+                } else {
+                    vec![]
+                };
+                let (mut snapshot, dropped) =
+                    Self::from_value_w_recovery(&rnote_file.engine_snapshot)?;
+                if !pending_strokes.is_empty() {
+                    // The chrono components of the pending strokes are kept with them
+                    let stroke_components = Arc::clone(&snapshot.stroke_components);
+                    Arc::make_mut(&mut snapshot.chrono_components)
+                        .retain(|key, _| stroke_components.contains_key(key));
+                    snapshot.pending_strokes = pending_strokes;
+                }
+                Ok((snapshot, dropped))
             };
 
             if let Err(_data) = snapshot_sender.send(result()) {
@@ -63,6 +183,207 @@ impl EngineSnapshot {
 
         snapshot_receiver.await?
     }
+
+    /// Take the strokes that are far away from the stored view out of the snapshot value,
+    /// if the document is large enough to be loaded lazily.
+    ///
+    /// Their slots are left vacant. Files without stored stroke bounds are always loaded completely.
+    fn take_pending_strokes(value: &mut ijson::IValue) -> Vec<PendingStroke> {
+        /// The number of stroke slots from which on documents are loaded lazily.
+        const LAZY_LOADING_THRESHOLD: usize = 2000;
+
+        let Some(object) = value.as_object_mut() else {
+            return vec![];
+        };
+        let Some(stroke_bounds) = object
+            .get("stroke_bounds")
+            .and_then(|v| ijson::from_value::<Vec<SerdeSlot<Aabb>>>(v).ok())
+        else {
+            return vec![];
+        };
+        let chrono_slots = object
+            .get("chrono_components")
+            .and_then(|v| ijson::from_value::<Vec<SerdeSlot<ChronoComponent>>>(v).ok())
+            .unwrap_or_default();
+        let viewport = object
+            .get("camera")
+            .and_then(|v| ijson::from_value::<Camera>(v).ok())
+            .unwrap_or_default()
+            .viewport();
+        // Strokes around the view are loaded right away, so that scrolling a bit does not reveal missing strokes
+        let load_bounds = viewport.loosened(viewport.extents().max());
+        let Some(stroke_slots) = object
+            .get_mut("stroke_components")
+            .and_then(|v| v.as_array_mut())
+            .filter(|slots| slots.len() > LAZY_LOADING_THRESHOLD)
+        else {
+            return vec![];
+        };
+
+        let mut pending_strokes = vec![];
+        for (i, slot) in stroke_slots.iter_mut().enumerate() {
+            let Some(bounds) = stroke_bounds.get(i).and_then(|slot| slot.value) else {
+                continue;
+            };
+            let Some(chrono) = chrono_slots.get(i).and_then(|slot| slot.value.clone()) else {
+                continue;
+            };
+            if bounds.intersects(&load_bounds) {
+                continue;
+            }
+            let Some(slot) = slot.as_object_mut() else {
+                continue;
+            };
+            // Occupied slots have an odd version
+            let Some(version) = slot
+                .get("version")
+                .and_then(|v| v.to_u64())
+                .filter(|version| version % 2 == 1)
+            else {
+                continue;
+            };
+            let Some(stroke_value) = slot.get_mut("value").map(std::mem::take) else {
+                continue;
+            };
+            if stroke_value.is_null() {
+                continue;
+            }
+            // Vacant slots have an even version
+            slot.insert("version", version.wrapping_add(1));
+            // The key the stroke had in the file, which connectors and links might refer to
+            let key = StrokeKey::from(KeyData::from_ffi((version << 32) | i as u64));
+            pending_strokes.push(PendingStroke {
+                key,
+                bounds,
+                value: Arc::new(stroke_value),
+                chrono: Arc::new(chrono),
+            });
+        }
+        pending_strokes
+    }
+
+    /// Deserialize the snapshot from the value of a loaded [rnoteformat::RnoteFile], recovering as much as possible
+    /// when it is corrupt.
+    ///
+    /// Returns the snapshot together with descriptions of everything that was dropped.
+    pub(crate) fn from_value_w_recovery(
+        value: &ijson::IValue,
+    ) -> anyhow::Result<(Self, Vec<String>)> {
+        match ijson::from_value::<Self>(value) {
+            Ok(snapshot) => Ok((snapshot, vec![])),
+            Err(e) => {
+                warn!("Deserializing engine snapshot failed, trying to recover the readable parts. Err: {e:?}");
+                Self::recover_from_value(value)
+            }
+        }
+    }
+
+    /// Deserialize the snapshot field by field and the strokes one by one, skipping everything that fails.
+    fn recover_from_value(value: &ijson::IValue) -> anyhow::Result<(Self, Vec<String>)> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("Engine snapshot is not an object."))?;
+        let mut dropped = Vec::new();
+        let mut field = |key: &str, description: &str| -> Option<ijson::IValue> {
+            let value = object.get(key).cloned();
+            if value.is_none() {
+                dropped.push(format!("The {description} is missing."));
+            }
+            value
+        };
+        let document_value = field("document", "document settings");
+        let camera_value = field("camera", "view");
+        let strokes_value = field("stroke_components", "strokes");
+        let chronos_value = field("chrono_components", "stroke order");
+        let chrono_counter_value = field("chrono_counter", "stroke counter");
+
+        let mut snapshot = Self::default();
+        if let Some(v) = document_value {
+            match ijson::from_value::<Document>(&v) {
+                Ok(document) => snapshot.document = document,
+                Err(e) => dropped.push(format!(
+                    "The document settings could not be read and were reset, Err: {e}"
+                )),
+            }
+        }
+        if let Some(v) = camera_value {
+            match ijson::from_value::<Camera>(&v) {
+                Ok(camera) => snapshot.camera = camera,
+                Err(e) => dropped.push(format!("The view could not be read, Err: {e}")),
+            }
+        }
+
+        // The stroke slots are checked individually. Unreadable strokes are turned into vacant slots,
+        // which keeps the keys of the remaining strokes intact.
+        let mut stroke_slots = strokes_value
+            .map(|v| ijson::from_value::<Vec<SerdeSlot<ijson::IValue>>>(&v))
+            .transpose()
+            .context("Reading the stroke slots failed.")?
+            .unwrap_or_default();
+        let mut n_dropped_strokes = 0;
+        for (i, slot) in stroke_slots.iter_mut().enumerate() {
+            let Some(stroke_value) = slot.value.as_ref() else {
+                continue;
+            };
+            if let Err(e) = ijson::from_value::<Stroke>(stroke_value) {
+                dropped.push(format!("Stroke {i} could not be read, Err: {e}"));
+                n_dropped_strokes += 1;
+                slot.vacate();
+            }
+        }
+        let vacant = stroke_slots
+            .iter()
+            .map(|slot| slot.value.is_none())
+            .collect::<Vec<bool>>();
+        snapshot.stroke_components = Arc::new(ijson::from_value(&ijson::to_value(&stroke_slots)?)?);
+
+        if let Some(v) = chronos_value {
+            match ijson::from_value::<Vec<SerdeSlot<ijson::IValue>>>(&v) {
+                Ok(mut chrono_slots) => {
+                    for (i, slot) in chrono_slots.iter_mut().enumerate() {
+                        let readable = slot.value.as_ref().is_some_and(|chrono_value| {
+                            ijson::from_value::<ChronoComponent>(chrono_value).is_ok()
+                        });
+                        if !readable || vacant.get(i).copied().unwrap_or(true) {
+                            slot.vacate();
+                        }
+                    }
+                    snapshot.chrono_components =
+                        Arc::new(ijson::from_value(&ijson::to_value(&chrono_slots)?)?);
+                }
+                Err(e) => dropped.push(format!("The stroke order could not be read, Err: {e}")),
+            }
+        }
+        if let Some(v) = chrono_counter_value {
+            match ijson::from_value::<u32>(&v) {
+                Ok(chrono_counter) => snapshot.chrono_counter = chrono_counter,
+                Err(e) => dropped.push(format!("The stroke counter could not be read, Err: {e}")),
+            }
+        }
+        // Strokes without a readable chrono component are drawn on top of the others
+        let stroke_components = Arc::clone(&snapshot.stroke_components);
+        let chrono_components = Arc::make_mut(&mut snapshot.chrono_components);
+        for (key, stroke) in stroke_components.iter() {
+            if !chrono_components.contains_key(key) {
+                snapshot.chrono_counter += 1;
+                chrono_components.insert(
+                    key,
+                    Arc::new(ChronoComponent::new(
+                        snapshot.chrono_counter,
+                        stroke.extract_default_layer(),
+                    )),
+                );
+            }
+        }
+        if n_dropped_strokes > 0 {
+            warn!(
+                "Dropped {n_dropped_strokes} unreadable strokes while recovering engine snapshot."
+            );
+        }
+
+        Ok((snapshot, dropped))
+    }
+
     /// Loads from the bytes of a Xournal++ .xopp file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
@@ -194,4 +515,108 @@ impl EngineSnapshot {
 
         snapshot_receiver.await?
     }
+
+    /// Loads from the bytes of an Evernote .enex export file.
+    ///
+    /// Every note starts on a new page, with its title and text followed by its embedded images.
+    ///
+    /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
+    pub async fn load_from_enex_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        /// The margin around the content of a note.
+        const MARGIN: f64 = 48.0;
+        /// The spacing between the title, text and images of a note.
+        const SPACING: f64 = 24.0;
+        let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Self> {
+                let enex_file = enexformat::EnexFile::load_from_bytes(&bytes)?;
+
+                let mut engine = Engine::default();
+                engine.document.layout = Layout::FixedSize;
+                engine.document.x = 0.0;
+                engine.document.y = 0.0;
+                let page_width = engine.document.format.width();
+                let page_height = engine.document.format.height();
+                let content_width = page_width - 2.0 * MARGIN;
+                let mut page_y = 0.0;
+
+                for note in enex_file.notes.into_iter() {
+                    let mut y = page_y + MARGIN;
+                    let mut insert_text = |text: String, text_style: TextStyle, y: &mut f64| {
+                        if text.is_empty() {
+                            return;
+                        }
+                        let mut textstroke =
+                            TextStroke::new(text, na::vector![MARGIN, *y], text_style);
+                        textstroke.text_style.set_max_width(Some(content_width));
+                        *y += textstroke.bounds().extents()[1] + SPACING;
+                        engine
+                            .store
+                            .insert_stroke(Stroke::TextStroke(textstroke), None);
+                    };
+
+                    let mut title_style = TextStyle::default();
+                    title_style.font_size = TextStyle::FONT_SIZE_DEFAULT * 1.5;
+                    title_style.font_weight = piet::FontWeight::BOLD.to_raw();
+                    insert_text(note.title.clone(), title_style, &mut y);
+                    insert_text(note.content_as_text(), TextStyle::default(), &mut y);
+
+                    for resource in note
+                        .resources
+                        .iter()
+                        .filter(|resource| resource.is_bitmap_image())
+                    {
+                        let size =
+                            match image::ImageReader::new(std::io::Cursor::new(&resource.data))
+                                .with_guessed_format()
+                                .map_err(anyhow::Error::from)
+                                .and_then(|reader| {
+                                    reader.into_dimensions().map_err(anyhow::Error::from)
+                                }) {
+                                Ok((width, height)) => {
+                                    let size = na::vector![f64::from(width), f64::from(height)];
+                                    size * (content_width / size[0]).min(1.0)
+                                }
+                                Err(e) => {
+                                    error!(
+                                    "Reading dimensions of image of Enex note failed, Err: {e:?}"
+                                );
+                                    continue;
+                                }
+                            };
+                        match BitmapImage::from_image_bytes(
+                            &resource.data,
+                            na::vector![MARGIN, y],
+                            ImageSizeOption::ImposeSize(size),
+                        ) {
+                            Ok(bitmapimage) => {
+                                y += size[1] + SPACING;
+                                engine
+                                    .store
+                                    .insert_stroke(Stroke::BitmapImage(bitmapimage), None);
+                            }
+                            Err(e) => {
+                                error!("Creating BitmapImage from image of Enex note failed, Err: {e:?}");
+                            }
+                        }
+                    }
+
+                    // The next note starts on a new page
+                    page_y += ((y - page_y) / page_height).ceil().max(1.0) * page_height;
+                }
+
+                engine.document.width = page_width;
+                engine.document.height = page_y.max(page_height);
+
+                Ok(engine.take_snapshot())
+            };
+
+            if snapshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver while loading Enex bytes failed. Receiver already dropped");
+            }
+        });
+
+        snapshot_receiver.await?
+    }
 }
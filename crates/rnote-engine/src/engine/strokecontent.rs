@@ -1,8 +1,11 @@
 // Imports
+use super::Engine;
 use crate::document::Background;
 use crate::render::Svg;
+use crate::store::StrokeKey;
 use crate::strokes::Stroke;
 use crate::Drawable;
+use anyhow::Context;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
@@ -17,10 +20,18 @@ use tracing::warn;
 pub struct StrokeContent {
     #[serde(rename = "strokes")]
     pub strokes: Vec<Arc<Stroke>>,
+    /// The keys the strokes had in the store they were taken from, in the same order as the strokes.
+    ///
+    /// Used to keep connectors attached to the strokes that are inserted along with them. Might be empty.
+    #[serde(rename = "keys")]
+    pub keys: Vec<StrokeKey>,
     #[serde(rename = "bounds")]
     pub bounds: Option<Aabb>,
     #[serde(rename = "background")]
     pub background: Option<Background>,
+    /// Backgrounds that are drawn over the background in their bounds, for pages with a background override.
+    #[serde(rename = "page_backgrounds")]
+    pub page_backgrounds: Vec<(Aabb, Background)>,
 }
 
 impl StrokeContent {
@@ -37,11 +48,21 @@ impl StrokeContent {
         self
     }
 
+    pub fn with_keys(mut self, keys: Vec<StrokeKey>) -> Self {
+        self.keys = keys;
+        self
+    }
+
     pub fn with_background(mut self, background: Option<Background>) -> Self {
         self.background = background;
         self
     }
 
+    pub fn with_page_backgrounds(mut self, page_backgrounds: Vec<(Aabb, Background)>) -> Self {
+        self.page_backgrounds = page_backgrounds;
+        self
+    }
+
     pub fn bounds(&self) -> Option<Aabb> {
         if self.bounds.is_some() {
             return self.bounds;
@@ -96,6 +117,101 @@ impl StrokeContent {
         Ok(Some(svg))
     }
 
+    /// Generate a single page Pdf that is cropped to the content.
+    ///
+    /// Returns Ok(None) if there is no content stored.
+    pub fn gen_pdf(
+        &self,
+        draw_background: bool,
+        draw_pattern: bool,
+        optimize_printing: bool,
+        margin: f64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(bounds_loosened) = self.bounds().map(|b| b.loosened(margin)) else {
+            return Ok(None);
+        };
+        let target_surface = cairo::PdfSurface::for_stream(
+            bounds_loosened.extents()[0],
+            bounds_loosened.extents()[1],
+            Vec::<u8>::new(),
+        )
+        .context("Creating Pdf target surface failed.")?;
+        self.draw_to_stream_surface(
+            &target_surface,
+            bounds_loosened,
+            draw_background,
+            draw_pattern,
+            optimize_printing,
+            margin,
+        )
+        .map(Some)
+    }
+
+    /// Generate an encapsulated PostScript (Eps) from the content, for applications that don't accept Svg.
+    ///
+    /// Returns Ok(None) if there is no content stored.
+    pub fn gen_eps(
+        &self,
+        draw_background: bool,
+        draw_pattern: bool,
+        optimize_printing: bool,
+        margin: f64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(bounds_loosened) = self.bounds().map(|b| b.loosened(margin)) else {
+            return Ok(None);
+        };
+        let target_surface = cairo::PsSurface::for_stream(
+            bounds_loosened.extents()[0],
+            bounds_loosened.extents()[1],
+            Vec::<u8>::new(),
+        )
+        .context("Creating Eps target surface failed.")?;
+        target_surface.set_eps(true);
+        self.draw_to_stream_surface(
+            &target_surface,
+            bounds_loosened,
+            draw_background,
+            draw_pattern,
+            optimize_printing,
+            margin,
+        )
+        .map(Some)
+    }
+
+    /// Draw the content onto a surface that was created for a `Vec<u8>` stream and return the finished stream.
+    fn draw_to_stream_surface(
+        &self,
+        target_surface: &cairo::Surface,
+        bounds_loosened: Aabb,
+        draw_background: bool,
+        draw_pattern: bool,
+        optimize_printing: bool,
+        margin: f64,
+    ) -> anyhow::Result<Vec<u8>> {
+        // New scope to avoid errors when flushing
+        {
+            let cairo_cx = cairo::Context::new(target_surface)
+                .context("Creating new cairo context for target surface failed.")?;
+            cairo_cx.translate(-bounds_loosened.mins[0], -bounds_loosened.mins[1]);
+            self.draw_to_cairo(
+                &cairo_cx,
+                draw_background,
+                draw_pattern,
+                optimize_printing,
+                margin,
+                Engine::STROKE_EXPORT_IMAGE_SCALE,
+            )?;
+        }
+        let data = *target_surface
+            .finish_output_stream()
+            .map_err(|e| anyhow::anyhow!("Finishing outputstream failed, Err: {e:?}"))?
+            .downcast::<Vec<u8>>()
+            .map_err(|e| {
+                anyhow::anyhow!("Downcasting finished output stream failed, Err: {e:?}")
+            })?;
+        Ok(data)
+    }
+
     pub fn draw_to_cairo(
         &self,
         cairo_cx: &cairo::Context,
@@ -128,6 +244,14 @@ impl StrokeContent {
                     optimize_printing,
                 )?;
             }
+            for (page_bounds, page_background) in self.page_backgrounds.iter() {
+                page_background.draw_to_cairo(
+                    cairo_cx,
+                    *page_bounds,
+                    draw_pattern,
+                    optimize_printing,
+                )?;
+            }
         }
 
         cairo_cx.restore()?;
@@ -152,8 +276,22 @@ impl StrokeContent {
 
         for stroke in self.strokes.iter() {
             let stroke_bounds = stroke.bounds();
+            // Text strokes, checkboxes, sticky notes, mind maps and boards are drawn as real text runs. Marking each
+            // one as a paragraph makes Pdf exports tagged, so that the text is found and copied as separate paragraphs.
+            // Other surfaces ignore the tags.
+            let is_text = matches!(
+                stroke.as_ref(),
+                Stroke::TextStroke(_)
+                    | Stroke::CheckboxStroke(_)
+                    | Stroke::StickyNoteStroke(_)
+                    | Stroke::MindMapStroke(_)
+                    | Stroke::BoardStroke(_)
+            );
+            if is_text {
+                cairo_cx.tag_begin("P", "");
+            }
 
-            if optimize_printing
+            let res = if optimize_printing
                 && image_bounds
                     .iter()
                     .all(|bounds| !bounds.contains(&stroke_bounds))
@@ -164,10 +302,16 @@ impl StrokeContent {
                 let mut darkest_color_stroke = stroke.as_ref().clone();
                 darkest_color_stroke.set_to_darkest_color();
 
-                darkest_color_stroke.draw_to_cairo(cairo_cx, image_scale)?;
+                darkest_color_stroke.draw_to_cairo(cairo_cx, image_scale)
             } else {
-                stroke.draw_to_cairo(cairo_cx, image_scale)?;
+                stroke.draw_to_cairo(cairo_cx, image_scale)
+            };
+
+            // The tag is always closed, so that it stays balanced when drawing fails
+            if is_text {
+                cairo_cx.tag_end("P");
             }
+            res?;
         }
 
         cairo_cx.restore()?;
@@ -1,5 +1,8 @@
 // Imports
+use crate::store::render_comp::StrokesDrawStats;
 use rnote_compose::Color;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 pub const COLOR_POS: Color = Color {
     r: 1.0,
@@ -123,6 +126,130 @@ pub(crate) fn draw_fill_to_gtk_snapshot(
     );
 }
 
+/// Rendering statistics of the recently drawn frames, displayed in the debug HUD.
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    /// The instants of the frames drawn in the last second.
+    frame_instants: VecDeque<Instant>,
+    last_frame_time: Duration,
+    /// The exponential moving average of the frame times.
+    avg_frame_time: Duration,
+    last_strokes: StrokesDrawStats,
+    last_strokes_culled: usize,
+    total_cache_hits: u64,
+    total_cache_misses: u64,
+}
+
+impl RenderStats {
+    const FRAME_TIME_SMOOTHING: f64 = 0.1;
+
+    pub(crate) fn record_frame(
+        &mut self,
+        start: Instant,
+        strokes: StrokesDrawStats,
+        strokes_total: usize,
+    ) {
+        let now = Instant::now();
+        while self
+            .frame_instants
+            .front()
+            .is_some_and(|instant| now.duration_since(*instant) > Duration::from_secs(1))
+        {
+            self.frame_instants.pop_front();
+        }
+        self.frame_instants.push_back(now);
+
+        self.last_frame_time = now.duration_since(start);
+        self.avg_frame_time = if self.avg_frame_time.is_zero() {
+            self.last_frame_time
+        } else {
+            self.avg_frame_time
+                .mul_f64(1.0 - Self::FRAME_TIME_SMOOTHING)
+                + self.last_frame_time.mul_f64(Self::FRAME_TIME_SMOOTHING)
+        };
+        self.last_strokes = strokes;
+        self.last_strokes_culled = strokes_total.saturating_sub(strokes.drawn);
+        self.total_cache_hits += strokes.cache_hits as u64;
+        self.total_cache_misses += strokes.cache_misses as u64;
+    }
+
+    /// The number of frames drawn in the last second.
+    pub fn fps(&self) -> usize {
+        self.frame_instants.len()
+    }
+
+    /// The ratio of strokes that were drawn with an up-to-date rendering, over all recorded frames.
+    pub fn cache_hit_rate(&self) -> f64 {
+        let total = self.total_cache_hits + self.total_cache_misses;
+        if total == 0 {
+            return 1.0;
+        }
+        self.total_cache_hits as f64 / total as f64
+    }
+
+    /// Clear all recorded statistics.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Draw the debug HUD with rendering statistics.
+///
+/// Expects that the snapshot is untransformed in surface coordinate space.
+#[cfg(feature = "ui")]
+pub(crate) fn draw_hud_to_gtk_snapshot(
+    snapshot: &gtk4::Snapshot,
+    render_stats: &RenderStats,
+    surface_bounds: p2d::bounding_volume::Aabb,
+) -> anyhow::Result<()> {
+    use crate::ext::GrapheneRectExt;
+    use gtk4::{graphene, prelude::*};
+    use p2d::bounding_volume::Aabb;
+    use piet::{RenderContext, Text, TextLayoutBuilder};
+    use rnote_compose::ext::{AabbExt, Vector2Ext};
+
+    let text_bounds = Aabb::new(
+        na::point![surface_bounds.mins[0] + 20.0, surface_bounds.mins[1] + 20.0],
+        na::point![
+            surface_bounds.mins[0] + 320.0,
+            surface_bounds.mins[1] + 140.0
+        ],
+    );
+    let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(text_bounds));
+    let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+
+    let hud_text_string = format!(
+        "fps: {}\nframe time: {:.2} ms (avg {:.2} ms)\nstrokes drawn: {}\nstrokes culled: {}\ncache hits / misses: {} / {}\ncache hit rate: {:.1} %",
+        render_stats.fps(),
+        render_stats.last_frame_time.as_secs_f64() * 1000.0,
+        render_stats.avg_frame_time.as_secs_f64() * 1000.0,
+        render_stats.last_strokes.drawn,
+        render_stats.last_strokes_culled,
+        render_stats.last_strokes.cache_hits,
+        render_stats.last_strokes.cache_misses,
+        render_stats.cache_hit_rate() * 100.0,
+    );
+    let text_layout = piet_cx
+        .text()
+        .new_text_layout(hud_text_string)
+        .text_color(piet::Color::rgba(0.8, 1.0, 0.8, 1.0))
+        .max_width(text_bounds.extents()[0] - 20.0)
+        .font(piet::FontFamily::MONOSPACE, 10.0)
+        .build()
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+    piet_cx.fill(
+        text_bounds.to_kurbo_rect(),
+        &piet::Color::rgba(0.1, 0.1, 0.1, 0.8),
+    );
+    piet_cx.draw_text(
+        &text_layout,
+        (text_bounds.mins.coords + na::vector![10.0, 10.0]).to_kurbo_point(),
+    );
+    piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    Ok(())
+}
+
 /// Draw some engine statistics for debugging purposes.
 ///
 /// Expects that the snapshot is untransformed in surface coordinate space.
@@ -0,0 +1,335 @@
+// Imports
+use super::{FileFormatLoader, XmlLoadable};
+use roxmltree::{Node, NodeType};
+use tracing::error;
+
+/// Represents an Evernote `.enex` export file.
+///
+/// The notes content is saved in ENML, a subset of Xhtml. Embedded images and files are saved as base64 encoded
+/// resources of the notes.
+///
+/// The format is described here: <https://evernote.com/blog/how-evernotes-xml-export-format-works>
+#[derive(Debug, Default, Clone)]
+pub struct EnexFile {
+    /// The exported notes.
+    pub notes: Vec<EnexNote>,
+}
+
+impl FileFormatLoader for EnexFile {
+    fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let parsed_doc = roxmltree::Document::parse_with_options(
+            std::str::from_utf8(bytes)?,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..Default::default()
+            },
+        )?;
+        let root_node = parsed_doc.root_element();
+        if root_node.tag_name().name() != "en-export" {
+            return Err(anyhow::anyhow!(
+                "Root element of Enex file is `{}`, expected `en-export`.",
+                root_node.tag_name().name()
+            ));
+        }
+        let mut enex_file = Self::default();
+        enex_file.load_from_xml(root_node)?;
+        Ok(enex_file)
+    }
+}
+
+impl XmlLoadable for EnexFile {
+    fn load_from_xml(&mut self, root_node: Node) -> anyhow::Result<()> {
+        for child in root_node
+            .children()
+            .filter(|child| child.is_element() && child.tag_name().name() == "note")
+        {
+            let mut new_note = EnexNote::default();
+            new_note.load_from_xml(child)?;
+            self.notes.push(new_note);
+        }
+        Ok(())
+    }
+}
+
+/// A note of an Enex file.
+#[derive(Debug, Default, Clone)]
+pub struct EnexNote {
+    /// The note title.
+    pub title: String,
+    /// The note content as ENML.
+    pub content: String,
+    /// The resources embedded in the note.
+    pub resources: Vec<EnexResource>,
+}
+
+impl XmlLoadable for EnexNote {
+    fn load_from_xml(&mut self, node: Node) -> anyhow::Result<()> {
+        for child in node.children().filter(|child| child.is_element()) {
+            match child.tag_name().name() {
+                "title" => {
+                    self.title = child.text().unwrap_or_default().trim().to_string();
+                }
+                "content" => {
+                    // The content is usually wrapped in a CDATA section, which roxmltree reports as text
+                    self.content = child
+                        .children()
+                        .filter_map(|n| n.text())
+                        .collect::<String>();
+                }
+                "resource" => {
+                    let mut new_resource = EnexResource::default();
+                    match new_resource.load_from_xml(child) {
+                        Ok(()) => self.resources.push(new_resource),
+                        Err(e) => error!("Loading resource of Enex note failed, Err: {e:?}"),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EnexNote {
+    /// Converts the ENML content of the note to plain text.
+    ///
+    /// Block elements are separated by newlines, list items are prefixed with a bullet.
+    /// Falls back to the raw content when it can't be parsed.
+    pub fn content_as_text(&self) -> String {
+        // The ENML Dtd that declares the Html entities is not loaded, so they are replaced beforehand
+        let content = replace_html_entities(&self.content);
+        let parsed_content = match roxmltree::Document::parse_with_options(
+            &content,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..Default::default()
+            },
+        ) {
+            Ok(parsed_content) => parsed_content,
+            Err(e) => {
+                error!("Parsing content of Enex note failed, Err: {e:?}");
+                return self.content.trim().to_string();
+            }
+        };
+        let mut text = String::new();
+        append_node_text(parsed_content.root_element(), &mut text);
+
+        // Collapse the blank lines produced by nested block elements
+        let mut collapsed = String::with_capacity(text.len());
+        let mut blank_lines = 0;
+        for line in text.lines().map(|line| line.trim_end()) {
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            collapsed.push_str(line);
+            collapsed.push('\n');
+        }
+        collapsed.trim().to_string()
+    }
+}
+
+/// The named Html entities that are commonly found in ENML, with their code points.
+const HTML_ENTITIES: &[(&str, u32)] = &[
+    ("nbsp", 0xa0),
+    ("iexcl", 0xa1),
+    ("cent", 0xa2),
+    ("pound", 0xa3),
+    ("yen", 0xa5),
+    ("sect", 0xa7),
+    ("copy", 0xa9),
+    ("laquo", 0xab),
+    ("shy", 0xad),
+    ("reg", 0xae),
+    ("deg", 0xb0),
+    ("plusmn", 0xb1),
+    ("para", 0xb6),
+    ("middot", 0xb7),
+    ("raquo", 0xbb),
+    ("iquest", 0xbf),
+    ("times", 0xd7),
+    ("divide", 0xf7),
+    ("ensp", 0x2002),
+    ("emsp", 0x2003),
+    ("thinsp", 0x2009),
+    ("zwnj", 0x200c),
+    ("zwj", 0x200d),
+    ("ndash", 0x2013),
+    ("mdash", 0x2014),
+    ("lsquo", 0x2018),
+    ("rsquo", 0x2019),
+    ("sbquo", 0x201a),
+    ("ldquo", 0x201c),
+    ("rdquo", 0x201d),
+    ("bdquo", 0x201e),
+    ("bull", 0x2022),
+    ("hellip", 0x2026),
+    ("prime", 0x2032),
+    ("euro", 0x20ac),
+    ("trade", 0x2122),
+    ("larr", 0x2190),
+    ("uarr", 0x2191),
+    ("rarr", 0x2192),
+    ("darr", 0x2193),
+    ("harr", 0x2194),
+    ("ne", 0x2260),
+    ("le", 0x2264),
+    ("ge", 0x2265),
+];
+
+/// Replaces the named Html entities with character references, so that the content can be parsed as Xml.
+///
+/// The entities predefined in Xml and character references are kept, unknown entities are escaped
+/// so that they appear literally in the text.
+fn replace_html_entities(content: &str) -> String {
+    let mut replaced = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find('&') {
+        replaced.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let name = rest
+            .find(';')
+            .map(|end| &rest[..end])
+            .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()));
+        match name {
+            Some("amp" | "lt" | "gt" | "quot" | "apos") => replaced.push('&'),
+            Some(name) => match HTML_ENTITIES.iter().find(|(entity, _)| *entity == name) {
+                Some((_, code_point)) => {
+                    replaced.push_str(&format!("&#{code_point};"));
+                    rest = &rest[name.len() + 1..];
+                }
+                None => replaced.push_str("&amp;"),
+            },
+            // Character references start with `#` and are kept
+            None if rest.starts_with('#') => replaced.push('&'),
+            None => replaced.push_str("&amp;"),
+        }
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// Appends the text of the ENML node and its children.
+fn append_node_text(node: Node, text: &mut String) {
+    match node.node_type() {
+        NodeType::Text => {
+            // Whitespace in Html is collapsed, except non-breaking spaces
+            let node_text = node.text().unwrap_or_default();
+            let collapsed = node_text
+                .split_ascii_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+            if collapsed.is_empty() {
+                return;
+            }
+            if node_text.starts_with(|c: char| c.is_ascii_whitespace())
+                && !text.is_empty()
+                && !text.ends_with([' ', '\n'])
+            {
+                text.push(' ');
+            }
+            text.push_str(&collapsed);
+            if node_text.ends_with(|c: char| c.is_ascii_whitespace()) {
+                text.push(' ');
+            }
+        }
+        NodeType::Element => {
+            let tag_name = node.tag_name().name();
+            let is_block = matches!(
+                tag_name,
+                "div"
+                    | "p"
+                    | "h1"
+                    | "h2"
+                    | "h3"
+                    | "h4"
+                    | "h5"
+                    | "h6"
+                    | "li"
+                    | "tr"
+                    | "blockquote"
+                    | "pre"
+                    | "hr"
+                    | "ul"
+                    | "ol"
+                    | "table"
+            );
+            if is_block && !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            match tag_name {
+                "br" => text.push('\n'),
+                "li" => text.push_str("• "),
+                "en-todo" => {
+                    if node.attribute("checked") == Some("true") {
+                        text.push_str("☑ ");
+                    } else {
+                        text.push_str("☐ ");
+                    }
+                }
+                _ => {}
+            }
+            for child in node.children() {
+                append_node_text(child, text);
+            }
+            if is_block && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A resource embedded in a note of an Enex file.
+#[derive(Debug, Default, Clone)]
+pub struct EnexResource {
+    /// The mime type of the resource.
+    pub mime: String,
+    /// The decoded data of the resource.
+    pub data: Vec<u8>,
+}
+
+impl XmlLoadable for EnexResource {
+    fn load_from_xml(&mut self, node: Node) -> anyhow::Result<()> {
+        for child in node.children().filter(|child| child.is_element()) {
+            match child.tag_name().name() {
+                "mime" => {
+                    self.mime = child.text().unwrap_or_default().trim().to_string();
+                }
+                "data" => {
+                    if let Some(encoding) = child.attribute("encoding") {
+                        if encoding != "base64" {
+                            return Err(anyhow::anyhow!(
+                                "Unsupported encoding `{encoding}` of Enex resource data."
+                            ));
+                        }
+                    }
+                    // The base64 data is split into multiple lines
+                    let data = child
+                        .text()
+                        .unwrap_or_default()
+                        .split_whitespace()
+                        .collect::<String>();
+                    self.data =
+                        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EnexResource {
+    /// Whether the resource is a bitmap image that can be imported.
+    pub fn is_bitmap_image(&self) -> bool {
+        matches!(
+            self.mime.as_str(),
+            "image/png" | "image/jpeg" | "image/gif" | "image/bmp" | "image/webp"
+        )
+    }
+}
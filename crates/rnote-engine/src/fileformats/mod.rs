@@ -1,4 +1,5 @@
 // Modules
+pub mod enexformat;
 pub mod rnoteformat;
 pub mod xoppformat;
 
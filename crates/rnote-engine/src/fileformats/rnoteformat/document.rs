@@ -0,0 +1,148 @@
+//! A simple interface for reading and writing `.rnote` files from other programs.
+//!
+//! The file format itself is described in the [parent module](super). Tools that want to read or write rnote files
+//! can go through [RnoteDocument] instead of the engine snapshot. Files of older versions are upgraded when they are
+//! read, but the document and stroke types are the ones of the engine and might change between releases.
+//!
+//! ```no_run
+//! use rnote_engine::fileformats::rnoteformat::RnoteDocument;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let bytes = std::fs::read("notes.rnote")?;
+//! let mut doc = RnoteDocument::from_rnote_bytes(&bytes)?;
+//! println!("{} strokes", doc.strokes.len());
+//! doc.strokes.retain(|s| !matches!(s.stroke, rnote_engine::strokes::Stroke::BitmapImage(_)));
+//! std::fs::write("notes-without-images.rnote", doc.to_rnote_bytes()?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+// Imports
+use super::{encryption, RnoteFile};
+use crate::engine::EngineSnapshot;
+use crate::fileformats::{FileFormatLoader, FileFormatSaver};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::store::ChronoComponent;
+use crate::strokes::Stroke;
+use crate::{Camera, Document};
+use anyhow::Context;
+use slotmap::{HopSlotMap, SecondaryMap};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Errors when reading a [RnoteDocument].
+#[derive(Debug, thiserror::Error)]
+pub enum RnoteDocumentError {
+    /// The file is encrypted, it needs to be decrypted with [encryption::decrypt()] first.
+    #[error("the file is encrypted")]
+    Encrypted,
+    /// The file could not be read.
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// A stroke of a [RnoteDocument] together with the layer it is drawn on.
+#[derive(Debug, Clone)]
+pub struct RnoteDocumentStroke {
+    pub stroke: Stroke,
+    pub layer: StrokeLayer,
+}
+
+/// The content of a `.rnote` file.
+#[derive(Debug, Clone, Default)]
+pub struct RnoteDocument {
+    /// The document settings: dimensions, format, layout, background, guides and links.
+    pub document: Document,
+    /// The strokes, in the order they are drawn.
+    pub strokes: Vec<RnoteDocumentStroke>,
+}
+
+impl RnoteDocument {
+    /// The version of the file format that is written by [RnoteDocument::to_rnote_bytes()].
+    ///
+    /// Files of all older versions can be read, they are upgraded when loaded.
+    pub const FORMAT_VERSION: &'static str = RnoteFile::SEMVER;
+
+    /// Read the document from the bytes of a `.rnote` file.
+    ///
+    /// Parts of the document that can't be read are dropped and logged,
+    /// see [RnoteDocument::from_rnote_bytes_w_recovery()].
+    pub fn from_rnote_bytes(bytes: &[u8]) -> Result<Self, RnoteDocumentError> {
+        let (document, dropped) = Self::from_rnote_bytes_w_recovery(bytes)?;
+        for d in dropped {
+            warn!("Dropped unreadable content while reading rnote document: {d}");
+        }
+        Ok(document)
+    }
+
+    /// Read the document from the bytes of a `.rnote` file, recovering as much of a corrupt file as possible.
+    ///
+    /// Returns the document together with descriptions of everything that was dropped.
+    pub fn from_rnote_bytes_w_recovery(
+        bytes: &[u8],
+    ) -> Result<(Self, Vec<String>), RnoteDocumentError> {
+        if encryption::is_encrypted(bytes) {
+            return Err(RnoteDocumentError::Encrypted);
+        }
+        let rnote_file =
+            RnoteFile::load_from_bytes(bytes).context("Loading RnoteFile from bytes failed.")?;
+        let (snapshot, dropped) =
+            EngineSnapshot::from_value_w_recovery(&rnote_file.engine_snapshot)?;
+        Ok((Self::from_snapshot(snapshot), dropped))
+    }
+
+    /// Write the document as the bytes of a `.rnote` file in the current format version.
+    pub fn to_rnote_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let rnote_file = RnoteFile {
+            engine_snapshot: ijson::to_value(self.to_snapshot())?,
+        };
+        rnote_file.save_as_bytes("")
+    }
+
+    fn from_snapshot(snapshot: EngineSnapshot) -> Self {
+        let mut strokes = snapshot
+            .stroke_components
+            .iter()
+            .map(|(key, stroke)| {
+                let chrono = snapshot
+                    .chrono_components
+                    .get(key)
+                    .map(|c| **c)
+                    .unwrap_or_else(|| ChronoComponent::new(0, stroke.extract_default_layer()));
+                (chrono, stroke)
+            })
+            .collect::<Vec<(ChronoComponent, &Arc<Stroke>)>>();
+        strokes.sort_by_key(|(chrono, _)| (chrono.layer, *chrono));
+
+        Self {
+            document: snapshot.document.clone(),
+            strokes: strokes
+                .into_iter()
+                .map(|(chrono, stroke)| RnoteDocumentStroke {
+                    stroke: (**stroke).clone(),
+                    layer: chrono.layer,
+                })
+                .collect(),
+        }
+    }
+
+    fn to_snapshot(&self) -> EngineSnapshot {
+        let mut stroke_components = HopSlotMap::with_key();
+        let mut chrono_components = SecondaryMap::new();
+        let mut chrono_counter = 0;
+        for s in self.strokes.iter() {
+            chrono_counter += 1;
+            let key = stroke_components.insert(Arc::new(s.stroke.clone()));
+            chrono_components.insert(key, Arc::new(ChronoComponent::new(chrono_counter, s.layer)));
+        }
+
+        EngineSnapshot {
+            document: self.document.clone(),
+            camera: Camera::default(),
+            stroke_components: Arc::new(stroke_components),
+            chrono_components: Arc::new(chrono_components),
+            chrono_counter,
+            ..Default::default()
+        }
+    }
+}
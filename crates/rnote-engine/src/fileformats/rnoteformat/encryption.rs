@@ -0,0 +1,158 @@
+//! Optional passphrase encryption of `.rnote` files.
+//!
+//! An encrypted file contains the regular (gzip compressed) `.rnote` file, encrypted with ChaCha20-Poly1305.
+//! The key is derived from the passphrase with Argon2id. The layout is:
+//!
+//! `magic (8 bytes) | format version (1 byte) | m_cost, t_cost, p_cost (each 4 bytes, little endian) | salt (16 bytes)
+//! | nonce (12 bytes) | ciphertext and tag`
+//!
+//! The Argon2 parameters are stored so that files stay readable when the defaults change.
+//! Files of format version 1 have no parameters in the header and were written with [KDF_M_COST], [KDF_T_COST]
+//! and [KDF_P_COST]. The header before the ciphertext is authenticated as associated data.
+
+// Imports
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+
+/// The magic bytes at the start of an encrypted file.
+pub const MAGIC: &[u8; 8] = b"RNOTEENC";
+/// The version of the encrypted container.
+const FORMAT_VERSION: u8 = 2;
+/// The first version of the encrypted container, without the Argon2 parameters in the header.
+const FORMAT_VERSION_1: u8 = 1;
+/// The Argon2 memory cost in KiB used for encrypting.
+pub const KDF_M_COST: u32 = 19 * 1024;
+/// The Argon2 number of iterations used for encrypting.
+pub const KDF_T_COST: u32 = 2;
+/// The Argon2 degree of parallelism used for encrypting.
+pub const KDF_P_COST: u32 = 1;
+/// The maximum Argon2 memory cost in KiB that is accepted when decrypting, to not exhaust the memory on damaged files.
+const KDF_M_COST_MAX: u32 = 1024 * 1024;
+/// The maximum Argon2 number of iterations that is accepted when decrypting.
+const KDF_T_COST_MAX: u32 = 64;
+/// The maximum Argon2 degree of parallelism that is accepted when decrypting.
+const KDF_P_COST_MAX: u32 = 64;
+const KDF_PARAMS_LEN: usize = 3 * 4;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + KDF_PARAMS_LEN + SALT_LEN + NONCE_LEN;
+
+/// Errors when decrypting an encrypted file.
+#[derive(Debug, thiserror::Error)]
+pub enum DecryptError {
+    /// The passphrase is wrong, or the file was modified.
+    #[error("the passphrase is wrong or the file is damaged")]
+    WrongPassphrase,
+    /// The bytes are not a valid encrypted file.
+    #[error("invalid encrypted file: {0}")]
+    Invalid(String),
+}
+
+/// Whether the bytes are an encrypted file.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn kdf_params(m_cost: u32, t_cost: u32, p_cost: u32) -> Result<argon2::Params, DecryptError> {
+    if m_cost > KDF_M_COST_MAX || t_cost > KDF_T_COST_MAX || p_cost > KDF_P_COST_MAX {
+        return Err(DecryptError::Invalid(format!(
+            "the key derivation parameters m_cost: {m_cost}, t_cost: {t_cost}, p_cost: {p_cost} are too large"
+        )));
+    }
+    argon2::Params::new(m_cost, t_cost, p_cost, Some(Key::default().len()))
+        .map_err(|e| DecryptError::Invalid(format!("invalid key derivation parameters, Err: {e}")))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: argon2::Params) -> anyhow::Result<Key> {
+    let mut key = Key::default();
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|e| anyhow::anyhow!("Deriving the key from the passphrase failed, Err: {e}"))?;
+    Ok(key)
+}
+
+/// Encrypt the bytes of a `.rnote` file with the passphrase.
+///
+/// Deriving the key is deliberately slow, so this should not be called on the main thread.
+pub fn encrypt(bytes: &[u8], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let mut salt = [0_u8; SALT_LEN];
+    let mut nonce = [0_u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let mut encrypted = Vec::with_capacity(HEADER_LEN + bytes.len() + 16);
+    encrypted.extend_from_slice(MAGIC);
+    encrypted.push(FORMAT_VERSION);
+    for cost in [KDF_M_COST, KDF_T_COST, KDF_P_COST] {
+        encrypted.extend_from_slice(&cost.to_le_bytes());
+    }
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+
+    let params = kdf_params(KDF_M_COST, KDF_T_COST, KDF_P_COST)?;
+    let key = derive_key(passphrase, &salt, params)?;
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: bytes,
+                aad: &encrypted,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Encrypting failed, Err: {e}"))?;
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypt the bytes of an encrypted file with the passphrase, returning the bytes of the `.rnote` file.
+///
+/// Deriving the key is deliberately slow, so this should not be called on the main thread.
+pub fn decrypt(bytes: &[u8], passphrase: &str) -> Result<Vec<u8>, DecryptError> {
+    if !is_encrypted(bytes) {
+        return Err(DecryptError::Invalid(String::from(
+            "the magic bytes are missing",
+        )));
+    }
+    let truncated = || DecryptError::Invalid(String::from("the header is truncated"));
+    let version = *bytes.get(MAGIC.len()).ok_or_else(truncated)?;
+    let (params, header_len) = match version {
+        FORMAT_VERSION_1 => (
+            kdf_params(KDF_M_COST, KDF_T_COST, KDF_P_COST)?,
+            HEADER_LEN - KDF_PARAMS_LEN,
+        ),
+        FORMAT_VERSION => {
+            let cost = |i: usize| {
+                let start = MAGIC.len() + 1 + i * 4;
+                bytes
+                    .get(start..start + 4)
+                    .map(|cost| u32::from_le_bytes(cost.try_into().unwrap()))
+                    .ok_or_else(truncated)
+            };
+            (kdf_params(cost(0)?, cost(1)?, cost(2)?)?, HEADER_LEN)
+        }
+        version => {
+            return Err(DecryptError::Invalid(format!(
+                "unsupported format version {version}"
+            )));
+        }
+    };
+    if bytes.len() < header_len {
+        return Err(truncated());
+    }
+    let (header, ciphertext) = bytes.split_at(header_len);
+    let salt = &header[header_len - NONCE_LEN - SALT_LEN..header_len - NONCE_LEN];
+    let nonce = &header[header_len - NONCE_LEN..];
+
+    let key =
+        derive_key(passphrase, salt, params).map_err(|e| DecryptError::Invalid(e.to_string()))?;
+    ChaCha20Poly1305::new(&key)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| DecryptError::WrongPassphrase)
+}
@@ -4,13 +4,44 @@
 //! where X: semver major, Y: semver minor version.
 //!
 //! Then [TryFrom] can be implemented to allow conversions and chaining from older to newer versions.
+//!
+//! # The format
+//!
+//! A `.rnote` file is gzip compressed JSON. The top level object is the wrapper
+//! `{ "version": "<semver>", "data": { .. } }`, where `version` is the version of Rnote that wrote the file
+//! and decides how `data` is interpreted. Since version 0.9.0 `data` is `{ "engine_snapshot": { .. } }` with:
+//!
+//! - `document`: the document settings (`x`, `y`, `width`, `height`, `format`, `background`, `layout`,
+//!   `snap_positions`, `guides`, `links`). Fields missing in the file fall back to their defaults.
+//! - `camera`: the view when the file was saved.
+//! - `stroke_components`: the strokes as a slotmap, serialized as a list of slots `{ "value": <stroke>, "version": n }`.
+//!   Occupied slots have an odd version, vacant slots have `"value": null` and an even version.
+//!   The position of a slot in the list is the index of the stroke key.
+//! - `chrono_components`: the slots of the same keys with `{ "t": <u32>, "layer": <stroke layer> }`, which decide
+//!   the order in which the strokes are drawn: first by layer, then by `t`.
+//! - `chrono_counter`: the highest `t` that was handed out.
+//! - `stroke_bounds`: the slots of the same keys with the bounds of the strokes, `{ "mins": [x, y], "maxs": [x, y] }`.
+//!   Optional, used to load large documents lazily without deserializing every stroke.
+//!
+//! Strokes are externally tagged enums, for example `{ "brushstroke": { .. } }` or `{ "textstroke": { .. } }`.
+//! Their fields are the serde serialization of the types in [crate::strokes].
+//!
+//! Files can optionally be encrypted with a passphrase, see [encryption].
+//!
+//! Files of older versions are upgraded when they are loaded, and files are always written in the current version.
+//! Third party tools can use [RnoteDocument] instead of depending on the engine snapshot.
 
 // Modules
+pub mod document;
+pub mod encryption;
 pub(crate) mod maj0min5patch8;
 pub(crate) mod maj0min5patch9;
 pub(crate) mod maj0min6;
 pub(crate) mod maj0min9;
 
+// Re-exports
+pub use document::{RnoteDocument, RnoteDocumentError, RnoteDocumentStroke};
+
 // Imports
 use self::maj0min5patch8::RnoteFileMaj0Min5Patch8;
 use self::maj0min5patch9::RnoteFileMaj0Min5Patch9;
@@ -80,6 +111,11 @@ impl RnoteFile {
 
 impl FileFormatLoader for RnoteFile {
     fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if encryption::is_encrypted(bytes) {
+            return Err(anyhow::anyhow!(
+                "The file is encrypted and needs to be decrypted with its passphrase first."
+            ));
+        }
         let wrapper = serde_json::from_slice::<RnotefileWrapper>(
             &decompress_from_gzip(bytes).context("decompressing bytes failed.")?,
         )
@@ -0,0 +1,306 @@
+//! Flood filling the regions enclosed by strokes.
+//!
+//! The strokes are rasterized into a mask, which is flood filled from the start position.
+//! The outline of the filled pixels is then traced and simplified into a polygon.
+
+// Imports
+use crate::render::Image;
+use crate::store::chrono_comp::StrokeLayer;
+use crate::{Drawable, StrokeStore};
+use geo::Simplify;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::shapes::Polygon;
+use tracing::error;
+
+/// Pixels with a higher alpha value block the fill.
+const ALPHA_THRESHOLD: u8 = 64;
+/// Gaps in the outlines up to twice this size in pixels are closed before filling.
+const GAP_CLOSING: usize = 1;
+/// The fill grows by this size in pixels beyond the closed gaps, so that it reaches underneath the outlines.
+const GROW: usize = 2;
+/// The tolerance in pixels when simplifying the outline of the filled region.
+const SIMPLIFY_EPSILON: f64 = 0.75;
+/// The maximum extent of the rasterized mask in pixels.
+const MASK_EXTENT_MAX: f64 = 2048.0;
+
+/// Computes the region enclosed by the strokes around the given position.
+///
+/// Only strokes inside `bounds` are considered, rasterized with the given scale.
+/// Returns `None` when the position is not enclosed by strokes inside the bounds.
+pub(crate) fn enclosed_region(
+    store: &StrokeStore,
+    pos: na::Vector2<f64>,
+    bounds: Aabb,
+    scale: f64,
+) -> anyhow::Result<Option<Polygon>> {
+    let scale = scale.min(MASK_EXTENT_MAX / bounds.extents().max());
+    let image = Image::gen_with_piet(
+        |piet_cx| {
+            // Only the content drawn by the user acts as outline, not highlighters, images or earlier fills
+            for key in store
+                .stroke_keys_as_rendered_intersecting_bounds(bounds)
+                .into_iter()
+                .filter(|&key| matches!(store.stroke_layer(key), Some(StrokeLayer::UserLayer(_))))
+            {
+                if let Some(stroke) = store.get_stroke_ref(key) {
+                    if let Err(e) = stroke.draw(piet_cx, scale) {
+                        error!("Drawing stroke for fill mask failed, Err: {e:?}");
+                    }
+                }
+            }
+            Ok(())
+        },
+        bounds,
+        scale,
+    )?;
+    let image_bounds = image.rect.bounds();
+    let width = image.pixel_width as usize;
+    let height = image.pixel_height as usize;
+    let pixel_scale = na::vector![
+        width as f64 / image_bounds.extents()[0],
+        height as f64 / image_bounds.extents()[1]
+    ];
+
+    let blocked = Mask {
+        width,
+        height,
+        data: image
+            .data
+            .chunks_exact(4)
+            .map(|pixel| pixel[3] > ALPHA_THRESHOLD)
+            .collect(),
+    }
+    .dilated(GAP_CLOSING);
+
+    let start = (pos - image_bounds.mins.coords).component_mul(&pixel_scale);
+    if start[0] < 0.0 || start[1] < 0.0 {
+        return Ok(None);
+    }
+    let Some(region) = blocked.flood_fill(start[0] as usize, start[1] as usize) else {
+        return Ok(None);
+    };
+    let region = region.dilated(GAP_CLOSING + GROW);
+
+    let to_doc_coords = |coord: &geo::Coord<f64>| {
+        image_bounds.mins.coords + na::vector![coord.x, coord.y].component_div(&pixel_scale)
+    };
+    let mut loops = region
+        .outline_loops()
+        .into_iter()
+        .map(|outline| {
+            let line_string = geo::LineString::from(
+                outline
+                    .into_iter()
+                    .map(|(x, y)| (x as f64, y as f64))
+                    .collect::<Vec<(f64, f64)>>(),
+            )
+            .simplify(&SIMPLIFY_EPSILON);
+            line_string
+                .coords()
+                .map(to_doc_coords)
+                .collect::<Vec<na::Vector2<f64>>>()
+        })
+        .filter(|outline| outline.len() >= 3)
+        .collect::<Vec<Vec<na::Vector2<f64>>>>();
+    if loops.is_empty() {
+        return Ok(None);
+    }
+
+    // The outer outline is the longest one, the others are holes with the opposite orientation.
+    let outer_index = loops
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, outline)| outline.len())
+        .map(|(i, _)| i)
+        .unwrap();
+    let outer = loops.swap_remove(outer_index);
+    let path = bridge_holes(outer, loops);
+
+    Ok(Some(Polygon {
+        start: path[0],
+        path: path[1..].to_vec(),
+        ..Default::default()
+    }))
+}
+
+/// Bridges the holes into the path of the outer outline.
+///
+/// The holes must have the opposite orientation of the outer outline, so that the bridges cancel out
+/// when the resulting polygon is filled.
+pub(crate) fn bridge_holes(
+    mut path: Vec<na::Vector2<f64>>,
+    holes: Vec<Vec<na::Vector2<f64>>>,
+) -> Vec<na::Vector2<f64>> {
+    for hole in holes {
+        let Some((path_i, hole_i)) = nearest_vertices(&path, &hole) else {
+            continue;
+        };
+        let bridged_hole = hole[hole_i..]
+            .iter()
+            .chain(hole[..=hole_i].iter())
+            .copied()
+            .chain(std::iter::once(path[path_i]))
+            .collect::<Vec<na::Vector2<f64>>>();
+        path.splice(path_i + 1..path_i + 1, bridged_hole);
+    }
+    path
+}
+
+/// The indices of the nearest pair of vertices of the two paths.
+fn nearest_vertices(a: &[na::Vector2<f64>], b: &[na::Vector2<f64>]) -> Option<(usize, usize)> {
+    a.iter()
+        .enumerate()
+        .flat_map(|(i, va)| {
+            b.iter()
+                .enumerate()
+                .map(move |(j, vb)| ((i, j), (va - vb).norm_squared()))
+        })
+        .min_by(|(_, d0), (_, d1)| d0.total_cmp(d1))
+        .map(|(indices, _)| indices)
+}
+
+/// A binary pixel mask.
+#[derive(Debug, Clone)]
+pub(crate) struct Mask {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) data: Vec<bool>,
+}
+
+impl Mask {
+    pub(crate) fn get(&self, x: usize, y: usize) -> bool {
+        self.data[y * self.width + x]
+    }
+
+    /// Dilates the set pixels by the given radius, using a square structuring element.
+    pub(crate) fn dilated(&self, radius: usize) -> Self {
+        if radius == 0 {
+            return self.clone();
+        }
+        let mut horizontal = vec![false; self.data.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let x_range = x.saturating_sub(radius)..(x + radius + 1).min(self.width);
+                horizontal[y * self.width + x] = x_range.into_iter().any(|xi| self.get(xi, y));
+            }
+        }
+        let mut data = vec![false; self.data.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let y_range = y.saturating_sub(radius)..(y + radius + 1).min(self.height);
+                data[y * self.width + x] = y_range
+                    .into_iter()
+                    .any(|yi| horizontal[yi * self.width + x]);
+            }
+        }
+        Self {
+            width: self.width,
+            height: self.height,
+            data,
+        }
+    }
+
+    /// Flood fills the unset pixels that are four-connected with the start pixel.
+    ///
+    /// Returns `None` when the start pixel is set or the filled region reaches the border of the mask,
+    /// meaning that it is not enclosed.
+    fn flood_fill(&self, start_x: usize, start_y: usize) -> Option<Self> {
+        if start_x >= self.width || start_y >= self.height || self.get(start_x, start_y) {
+            return None;
+        }
+        let mut filled = vec![false; self.data.len()];
+        let mut stack = vec![(start_x, start_y)];
+        filled[start_y * self.width + start_x] = true;
+
+        while let Some((x, y)) = stack.pop() {
+            if x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1 {
+                return None;
+            }
+            for (nx, ny) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                let i = ny * self.width + nx;
+                if !filled[i] && !self.data[i] {
+                    filled[i] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+
+        Some(Self {
+            width: self.width,
+            height: self.height,
+            data: filled,
+        })
+    }
+
+    /// Traces the outlines between set and unset pixels as closed loops of pixel corners.
+    ///
+    /// The loops are oriented with the set pixels on the right side (in y-down coordinates),
+    /// so outer outlines and the outlines of holes have opposite orientations.
+    pub(crate) fn outline_loops(&self) -> Vec<Vec<(usize, usize)>> {
+        let is_set = |x: isize, y: isize| {
+            x >= 0
+                && y >= 0
+                && (x as usize) < self.width
+                && (y as usize) < self.height
+                && self.get(x as usize, y as usize)
+        };
+        // Directed edges between pixel corners, keyed by their start corner
+        let mut edges = std::collections::HashMap::<(usize, usize), Vec<(usize, usize)>>::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.get(x, y) {
+                    continue;
+                }
+                let (xi, yi) = (x as isize, y as isize);
+                if !is_set(xi, yi - 1) {
+                    edges.entry((x, y)).or_default().push((x + 1, y));
+                }
+                if !is_set(xi + 1, yi) {
+                    edges.entry((x + 1, y)).or_default().push((x + 1, y + 1));
+                }
+                if !is_set(xi, yi + 1) {
+                    edges.entry((x + 1, y + 1)).or_default().push((x, y + 1));
+                }
+                if !is_set(xi - 1, yi) {
+                    edges.entry((x, y + 1)).or_default().push((x, y));
+                }
+            }
+        }
+
+        let mut loops = vec![];
+        while let Some(&start) = edges.keys().next() {
+            let mut outline = vec![start];
+            let mut current = start;
+            while let Some(next) = edges.get_mut(&current).and_then(|ends| ends.pop()) {
+                if edges.get(&current).is_some_and(|ends| ends.is_empty()) {
+                    edges.remove(&current);
+                }
+                if next == start {
+                    break;
+                }
+                outline.push(next);
+                current = next;
+            }
+            loops.push(remove_collinear(outline));
+        }
+        loops
+    }
+}
+
+/// Removes the corners that lie on straight runs of the outline.
+fn remove_collinear(outline: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let n = outline.len();
+    if n < 3 {
+        return outline;
+    }
+    (0..n)
+        .filter(|&i| {
+            let prev = outline[(i + n - 1) % n];
+            let current = outline[i];
+            let next = outline[(i + 1) % n];
+            !((prev.0 == current.0 && current.0 == next.0)
+                || (prev.1 == current.1 && current.1 == next.1))
+        })
+        .map(|i| outline[i])
+        .collect()
+}
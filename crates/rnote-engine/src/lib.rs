@@ -7,6 +7,10 @@
 //! The rnote-engine crate is the core of Rnote. It holds the strokes store, the pens, has methods for importing / exporting, rendering, etc.. .
 //!
 //! The main entry point is the [Engine] struct.
+//!
+//! The engine is independent of any UI toolkit. The optional features are:
+//! - `ui`: drawing the engine into Gtk snapshots, used by the Gtk frontend. Pulls in the `gtk4` dependency.
+//! - `cli`: argument parsing support for some types, used by the CLI frontend.
 
 // Modules
 pub mod audioplayer;
@@ -17,14 +21,18 @@ pub mod drawable;
 pub mod engine;
 pub mod ext;
 pub mod fileformats;
+pub mod fill;
 pub mod pens;
+pub mod photocorrection;
 pub mod render;
 pub mod selectioncollision;
 pub mod snap;
+pub mod spellcheck;
 pub mod store;
 pub mod strokes;
 pub mod tasks;
 pub mod utils;
+pub mod vectorize;
 pub mod widgetflags;
 
 // Re-exports
@@ -37,6 +45,7 @@ pub use drawable::DrawableOnDoc;
 pub use engine::Engine;
 pub use pens::PenHolder;
 pub use selectioncollision::SelectionCollision;
+pub use spellcheck::Spellcheck;
 pub use store::StrokeStore;
 pub use widgetflags::WidgetFlags;
 
@@ -1,5 +1,5 @@
 // Imports
-use super::pensconfig::brushconfig::BrushStyle;
+use super::pensconfig::brushconfig::{BrushStyle, SymmetryMode, SymmetryTransform};
 use super::PenBehaviour;
 use super::PenStyle;
 use crate::engine::{EngineView, EngineViewMut};
@@ -14,9 +14,10 @@ use rnote_compose::builders::{
     PenPathBuilderType, PenPathCurvedBuilder, PenPathModeledBuilder, PenPathSimpleBuilder,
 };
 use rnote_compose::eventresult::{EventPropagation, EventResult};
+use rnote_compose::ext::Vector2Ext;
 use rnote_compose::penevent::{PenEvent, PenProgress};
 use rnote_compose::penpath::{Element, Segment};
-use rnote_compose::Constraints;
+use rnote_compose::{color, Constraints};
 use std::time::Instant;
 
 #[derive(Debug)]
@@ -25,18 +26,25 @@ enum BrushState {
     Drawing {
         path_builder: Box<dyn Buildable<Emit = Segment>>,
         current_stroke_key: StrokeKey,
+        /// The symmetric copies of the current stroke.
+        symmetry_strokes: Vec<(StrokeKey, SymmetryTransform)>,
     },
 }
 
 #[derive(Debug)]
 pub struct Brush {
     state: BrushState,
+    pressure_simulator: PressureSimulator,
+    /// Whether the events come from a device that reports pressure, for which it is not simulated.
+    input_has_pressure: bool,
 }
 
 impl Default for Brush {
     fn default() -> Self {
         Self {
             state: BrushState::Idle,
+            pressure_simulator: PressureSimulator::default(),
+            input_has_pressure: false,
         }
     }
 }
@@ -66,6 +74,18 @@ impl PenBehaviour for Brush {
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
 
+        let event = if engine_view.pens_config.brush_config.simulate_pressure
+            && !self.input_has_pressure
+        {
+            if matches!(self.state, BrushState::Idle) {
+                self.pressure_simulator.reset();
+            }
+            self.pressure_simulator
+                .simulate_for_event(event, now, engine_view.camera.total_zoom())
+        } else {
+            event
+        };
+
         let event_result = match (&mut self.state, event) {
             (BrushState::Idle, PenEvent::Down { element, .. }) => {
                 if !element.filter_by_bounds(
@@ -105,6 +125,40 @@ impl PenBehaviour for Brush {
                         engine_view.camera.image_scale(),
                     );
 
+                    let symmetry_strokes = engine_view
+                        .pens_config
+                        .brush_config
+                        .symmetry
+                        .transforms()
+                        .into_iter()
+                        .map(|transform| {
+                            let mut element = element;
+                            transform.apply(&mut element);
+                            let brushstroke = Stroke::BrushStroke(BrushStroke::new(
+                                element,
+                                engine_view
+                                    .pens_config
+                                    .brush_config
+                                    .style_for_current_options(),
+                            ));
+                            let stroke_key = engine_view.store.insert_stroke(
+                                brushstroke,
+                                Some(
+                                    engine_view
+                                        .pens_config
+                                        .brush_config
+                                        .layer_for_current_options(),
+                                ),
+                            );
+                            engine_view.store.regenerate_rendering_for_stroke(
+                                stroke_key,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                            (stroke_key, transform)
+                        })
+                        .collect();
+
                     self.state = BrushState::Drawing {
                         path_builder: new_builder(
                             engine_view.pens_config.brush_config.builder_type,
@@ -112,6 +166,7 @@ impl PenBehaviour for Brush {
                             now,
                         ),
                         current_stroke_key,
+                        symmetry_strokes,
                     };
 
                     EventResult {
@@ -134,20 +189,18 @@ impl PenBehaviour for Brush {
             },
             (
                 BrushState::Drawing {
-                    current_stroke_key, ..
+                    current_stroke_key,
+                    symmetry_strokes,
+                    ..
                 },
                 PenEvent::Cancel,
             ) => {
                 // Finish up the last stroke
-                engine_view
-                    .store
-                    .update_geometry_for_stroke(*current_stroke_key);
-                engine_view.store.regenerate_rendering_for_stroke_threaded(
-                    engine_view.tasks_tx.clone(),
-                    *current_stroke_key,
-                    engine_view.camera.viewport(),
-                    engine_view.camera.image_scale(),
-                );
+                for stroke_key in std::iter::once(*current_stroke_key)
+                    .chain(symmetry_strokes.iter().map(|(key, _)| *key))
+                {
+                    finish_stroke(stroke_key, engine_view);
+                }
                 widget_flags |= engine_view
                     .document
                     .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -167,6 +220,7 @@ impl PenBehaviour for Brush {
                 BrushState::Drawing {
                     path_builder,
                     current_stroke_key,
+                    symmetry_strokes,
                 },
                 pen_event,
             ) => {
@@ -188,57 +242,35 @@ impl PenBehaviour for Brush {
                             trigger_brush_sound(engine_view);
                         }
 
-                        let n_segments = segments.len();
-
-                        if n_segments != 0 {
-                            if let Some(Stroke::BrushStroke(brushstroke)) =
-                                engine_view.store.get_stroke_mut(*current_stroke_key)
-                            {
-                                brushstroke.extend_w_segments(segments);
-                                widget_flags.store_modified = true;
-                            }
-
-                            engine_view.store.append_rendering_last_segments(
-                                engine_view.tasks_tx.clone(),
+                        if !segments.is_empty() {
+                            widget_flags.store_modified = true;
+                            extend_strokes(
                                 *current_stroke_key,
-                                n_segments,
-                                engine_view.camera.viewport(),
-                                engine_view.camera.image_scale(),
+                                symmetry_strokes,
+                                segments,
+                                engine_view,
                             );
                         }
 
                         PenProgress::InProgress
                     }
                     BuilderProgress::Finished(segments) => {
-                        let n_segments = segments.len();
-
-                        if n_segments != 0 {
-                            if let Some(Stroke::BrushStroke(brushstroke)) =
-                                engine_view.store.get_stroke_mut(*current_stroke_key)
-                            {
-                                brushstroke.extend_w_segments(segments);
-                                widget_flags.store_modified = true;
-                            }
-
-                            engine_view.store.append_rendering_last_segments(
-                                engine_view.tasks_tx.clone(),
+                        if !segments.is_empty() {
+                            widget_flags.store_modified = true;
+                            extend_strokes(
                                 *current_stroke_key,
-                                n_segments,
-                                engine_view.camera.viewport(),
-                                engine_view.camera.image_scale(),
+                                symmetry_strokes,
+                                segments,
+                                engine_view,
                             );
                         }
 
                         // Finish up the last stroke
-                        engine_view
-                            .store
-                            .update_geometry_for_stroke(*current_stroke_key);
-                        engine_view.store.regenerate_rendering_for_stroke_threaded(
-                            engine_view.tasks_tx.clone(),
-                            *current_stroke_key,
-                            engine_view.camera.viewport(),
-                            engine_view.camera.image_scale(),
-                        );
+                        for stroke_key in std::iter::once(*current_stroke_key)
+                            .chain(symmetry_strokes.iter().map(|(key, _)| *key))
+                        {
+                            finish_stroke(stroke_key, engine_view);
+                        }
                         widget_flags |= engine_view
                             .document
                             .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -271,6 +303,11 @@ impl DrawableOnDoc for Brush {
             .brush_config
             .style_for_current_options();
 
+        if engine_view.pens_config.brush_config.symmetry.mode != SymmetryMode::Off {
+            // The symmetry axes span the entire viewport
+            return Some(engine_view.camera.viewport());
+        }
+
         match &self.state {
             BrushState::Idle => None,
             BrushState::Drawing { path_builder, .. } => {
@@ -286,19 +323,32 @@ impl DrawableOnDoc for Brush {
     ) -> anyhow::Result<()> {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
+        self.draw_symmetry_axes(cx, engine_view);
+
         match &self.state {
             BrushState::Idle => {}
-            BrushState::Drawing { path_builder, .. } => {
+            BrushState::Drawing {
+                path_builder,
+                symmetry_strokes,
+                ..
+            } => {
                 match engine_view.pens_config.brush_config.style {
                     BrushStyle::Marker => {
                         // Don't draw the marker, as the pen would render on top of other strokes, while the stroke itself would render underneath them.
                     }
-                    BrushStyle::Solid | BrushStyle::Textured => {
+                    BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Custom => {
                         let style = engine_view
                             .pens_config
                             .brush_config
                             .style_for_current_options();
                         path_builder.draw_styled(cx, &style, engine_view.camera.total_zoom());
+
+                        for (_, transform) in symmetry_strokes.iter() {
+                            cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                            cx.transform(transform.to_kurbo());
+                            path_builder.draw_styled(cx, &style, engine_view.camera.total_zoom());
+                            cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                        }
                     }
                 }
             }
@@ -311,6 +361,173 @@ impl DrawableOnDoc for Brush {
 
 impl Brush {
     const INPUT_OVERSHOOT: f64 = 30.0;
+    const SYMMETRY_AXIS_COLOR: piet::Color = color::GNOME_BLUES[2].with_a8(0xa0);
+    const SYMMETRY_AXIS_LINE_WIDTH: f64 = 1.0;
+    const SYMMETRY_AXIS_DASH_LEN: f64 = 6.0;
+
+    /// Set whether the following events come from a device that reports pressure, e.g. a stylus.
+    ///
+    /// The pressure is only simulated for devices without it, like mice and touchscreens.
+    pub(crate) fn set_input_has_pressure(&mut self, input_has_pressure: bool) {
+        self.input_has_pressure = input_has_pressure;
+    }
+
+    /// Draws the axes of the symmetry mode, so the user knows where strokes will be mirrored.
+    fn draw_symmetry_axes(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) {
+        let symmetry = &engine_view.pens_config.brush_config.symmetry;
+        let total_zoom = engine_view.camera.total_zoom();
+        let viewport = engine_view.camera.viewport();
+        // Long enough to always reach the viewport edges
+        let len = (viewport.center().coords - symmetry.center).norm() + viewport.extents().norm();
+        let stroke_style = piet::StrokeStyle::new().dash_pattern(&[
+            Self::SYMMETRY_AXIS_DASH_LEN / total_zoom,
+            Self::SYMMETRY_AXIS_DASH_LEN / total_zoom,
+        ]);
+        let center = symmetry.center.to_kurbo_point();
+
+        for angle in symmetry.axes_angles() {
+            let direction = kurbo::Vec2::new(angle.sin(), -angle.cos());
+            // In mirror mode the axis is a line through the center, in radial mode a ray.
+            let start = if symmetry.mode == SymmetryMode::Mirror {
+                center - direction * len
+            } else {
+                center
+            };
+            cx.stroke_styled(
+                kurbo::Line::new(start, center + direction * len),
+                &Self::SYMMETRY_AXIS_COLOR,
+                Self::SYMMETRY_AXIS_LINE_WIDTH / total_zoom,
+                &stroke_style,
+            );
+        }
+    }
+}
+
+/// Synthesizes the pen pressure from the velocity of the input, for devices that don't report any pressure.
+///
+/// Faster movements result in a lower pressure, and with that in thinner strokes.
+#[derive(Debug, Clone, Copy)]
+struct PressureSimulator {
+    prev: Option<(na::Vector2<f64>, Instant)>,
+    pressure: f64,
+}
+
+impl Default for PressureSimulator {
+    fn default() -> Self {
+        Self {
+            prev: None,
+            pressure: Self::PRESSURE_START,
+        }
+    }
+}
+
+impl PressureSimulator {
+    /// The velocity in surface coordinates per second, at and above which the minimum pressure is reached.
+    const VELOCITY_MAX: f64 = 2500.0;
+    const PRESSURE_MIN: f64 = 0.15;
+    const PRESSURE_MAX: f64 = 1.0;
+    const PRESSURE_START: f64 = 0.7;
+    /// Weight of a new pressure sample in the exponential moving average. Must be in the range (0.0, 1.0].
+    const SMOOTHING_WEIGHT: f64 = 0.25;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Replaces the pressure of the event elements with the simulated pressure.
+    fn simulate_for_event(&mut self, event: PenEvent, now: Instant, zoom: f64) -> PenEvent {
+        match event {
+            PenEvent::Down {
+                mut element,
+                modifier_keys,
+            } => {
+                element.set_pressure_clamped(self.simulate(element.pos, now, zoom));
+                PenEvent::Down {
+                    element,
+                    modifier_keys,
+                }
+            }
+            PenEvent::Up {
+                mut element,
+                modifier_keys,
+            } => {
+                element.set_pressure_clamped(self.simulate(element.pos, now, zoom));
+                PenEvent::Up {
+                    element,
+                    modifier_keys,
+                }
+            }
+            other => other,
+        }
+    }
+
+    fn simulate(&mut self, pos: na::Vector2<f64>, now: Instant, zoom: f64) -> f64 {
+        if let Some((prev_pos, prev_time)) = self.prev {
+            let delta_secs = now.saturating_duration_since(prev_time).as_secs_f64();
+            if delta_secs <= 0.0 {
+                return self.pressure;
+            }
+            let velocity = (pos - prev_pos).magnitude() * zoom / delta_secs;
+            let target = Self::PRESSURE_MAX
+                - (Self::PRESSURE_MAX - Self::PRESSURE_MIN)
+                    * (velocity / Self::VELOCITY_MAX).min(1.0);
+
+            self.pressure += (target - self.pressure) * Self::SMOOTHING_WEIGHT;
+        }
+        self.prev = Some((pos, now));
+        self.pressure
+    }
+}
+
+/// Extends the current stroke and its symmetric copies with the new segments.
+fn extend_strokes(
+    current_stroke_key: StrokeKey,
+    symmetry_strokes: &[(StrokeKey, SymmetryTransform)],
+    segments: Vec<Segment>,
+    engine_view: &mut EngineViewMut,
+) {
+    for (stroke_key, transform) in symmetry_strokes.iter() {
+        let transformed_segments = segments
+            .iter()
+            .map(|segment| {
+                let mut segment = *segment;
+                transform.apply(&mut segment);
+                segment
+            })
+            .collect::<Vec<Segment>>();
+        extend_stroke(*stroke_key, transformed_segments, engine_view);
+    }
+    extend_stroke(current_stroke_key, segments, engine_view);
+}
+
+fn extend_stroke(stroke_key: StrokeKey, segments: Vec<Segment>, engine_view: &mut EngineViewMut) {
+    let n_segments = segments.len();
+
+    if let Some(Stroke::BrushStroke(brushstroke)) = engine_view.store.get_stroke_mut(stroke_key) {
+        brushstroke.extend_w_segments(segments);
+    }
+
+    engine_view.store.append_rendering_last_segments(
+        engine_view.tasks_tx.clone(),
+        stroke_key,
+        n_segments,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+}
+
+fn finish_stroke(stroke_key: StrokeKey, engine_view: &mut EngineViewMut) {
+    engine_view.store.update_geometry_for_stroke(stroke_key);
+    engine_view.store.regenerate_rendering_for_stroke_threaded(
+        engine_view.tasks_tx.clone(),
+        stroke_key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
 }
 
 fn play_marker_sound(engine_view: &mut EngineViewMut) {
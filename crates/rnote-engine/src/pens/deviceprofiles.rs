@@ -0,0 +1,223 @@
+// Imports
+use super::shortcuts::ShortcutAction;
+use rnote_compose::penevent::ShortcutKey;
+use rnote_compose::style::PressureCurve;
+use rnote_compose::PenEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The type of an input device.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "input_device_type")]
+pub enum InputDeviceType {
+    /// A stylus, usually of a drawing tablet or a pen display.
+    #[serde(rename = "pen")]
+    Pen,
+    /// A mouse or a touchpad.
+    #[serde(rename = "mouse")]
+    Mouse,
+    /// A touchscreen.
+    #[serde(rename = "touchscreen")]
+    Touchscreen,
+}
+
+impl TryFrom<u32> for InputDeviceType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "InputDeviceType try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+/// An input device, identified by its type and name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputDevice {
+    pub device_type: InputDeviceType,
+    /// The name of the device as reported by the windowing system, e.g. "Wacom Intuos S Pen".
+    pub name: String,
+}
+
+impl InputDevice {
+    pub fn new(device_type: InputDeviceType, name: impl Into<String>) -> Self {
+        Self {
+            device_type,
+            name: name.into(),
+        }
+    }
+}
+
+/// What the input of a device does on the canvas.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "input_device_action")]
+pub enum InputDeviceAction {
+    /// The input is handled by the current pen.
+    #[serde(rename = "draw")]
+    Draw,
+    /// The input pans the canvas.
+    #[serde(rename = "pan")]
+    Pan,
+}
+
+impl Default for InputDeviceAction {
+    fn default() -> Self {
+        Self::Draw
+    }
+}
+
+impl TryFrom<u32> for InputDeviceAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "InputDeviceAction try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+/// The configuration profile of an input device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "input_device_profile")]
+pub struct InputDeviceProfile {
+    /// Whether the device draws or pans.
+    #[serde(rename = "action")]
+    pub action: InputDeviceAction,
+    /// The curve that is applied to the pressure reported by the device.
+    #[serde(rename = "pressure_curve")]
+    pub pressure_curve: PressureCurve,
+    /// Shortcut actions that override the globally registered shortcuts while using the device.
+    #[serde(rename = "button_mapping")]
+    pub button_mapping: HashMap<ShortcutKey, ShortcutAction>,
+}
+
+impl Default for InputDeviceProfile {
+    fn default() -> Self {
+        Self {
+            action: InputDeviceAction::default(),
+            pressure_curve: PressureCurve::Linear,
+            button_mapping: HashMap::default(),
+        }
+    }
+}
+
+impl InputDeviceProfile {
+    /// Applies the pressure curve of the profile to the elements of the event.
+    pub fn map_event(&self, event: PenEvent) -> PenEvent {
+        let map_element = |mut element: rnote_compose::penpath::Element| {
+            element.set_pressure_clamped(self.pressure_curve.apply(1.0, element.pressure));
+            element
+        };
+
+        match event {
+            PenEvent::Down {
+                element,
+                modifier_keys,
+            } => PenEvent::Down {
+                element: map_element(element),
+                modifier_keys,
+            },
+            PenEvent::Up {
+                element,
+                modifier_keys,
+            } => PenEvent::Up {
+                element: map_element(element),
+                modifier_keys,
+            },
+            PenEvent::Proximity {
+                element,
+                modifier_keys,
+            } => PenEvent::Proximity {
+                element: map_element(element),
+                modifier_keys,
+            },
+            other => other,
+        }
+    }
+}
+
+/// The profiles of the input devices.
+///
+/// A profile for a specific device, looked up by its name, takes precedence over the profile for its device type.
+/// Devices without any registered profile use the default profile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "device_profiles")]
+pub struct DeviceProfiles {
+    #[serde(rename = "type_profiles")]
+    type_profiles: HashMap<InputDeviceType, InputDeviceProfile>,
+    #[serde(rename = "named_profiles")]
+    named_profiles: HashMap<String, InputDeviceProfile>,
+}
+
+impl DeviceProfiles {
+    /// The profile that applies to the given device.
+    pub fn profile_for(&self, device: &InputDevice) -> InputDeviceProfile {
+        self.named_profiles
+            .get(&device.name)
+            .or_else(|| self.type_profiles.get(&device.device_type))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The profile for the device type.
+    pub fn type_profile(&self, device_type: InputDeviceType) -> InputDeviceProfile {
+        self.type_profiles
+            .get(&device_type)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Replace the profile for the device type.
+    pub fn set_type_profile(&mut self, device_type: InputDeviceType, profile: InputDeviceProfile) {
+        self.type_profiles.insert(device_type, profile);
+    }
+
+    /// The profile for the device with the given name, if one is registered.
+    pub fn named_profile(&self, name: &str) -> Option<InputDeviceProfile> {
+        self.named_profiles.get(name).cloned()
+    }
+
+    /// Register or replace the profile for the device with the given name.
+    pub fn set_named_profile(&mut self, name: impl Into<String>, profile: InputDeviceProfile) {
+        self.named_profiles.insert(name.into(), profile);
+    }
+
+    /// Remove the profile for the device with the given name, so that the profile for its device type applies again.
+    pub fn remove_named_profile(&mut self, name: &str) -> Option<InputDeviceProfile> {
+        self.named_profiles.remove(name)
+    }
+
+    /// The names of all devices that have their own profile.
+    pub fn named_profile_names(&self) -> Vec<String> {
+        let mut names = self.named_profiles.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+}
@@ -9,7 +9,7 @@ use piet::RenderContext;
 use rnote_compose::color;
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::ext::AabbExt;
-use rnote_compose::penevent::{PenEvent, PenProgress};
+use rnote_compose::penevent::{KeyboardKey, PenEvent, PenProgress};
 use rnote_compose::penpath::Element;
 use std::time::Instant;
 
@@ -59,6 +59,27 @@ impl PenBehaviour for Eraser {
         let mut widget_flags = WidgetFlags::default();
 
         let event_result = match (&mut self.state, event) {
+            (
+                state,
+                PenEvent::KeyPressed {
+                    keyboard_key: KeyboardKey::Unicode(key @ ('[' | ']')),
+                    ..
+                },
+            ) => {
+                // Change the width without having to open the settings
+                let steps = if key == ']' { 1.0 } else { -1.0 };
+                engine_view.pens_config.eraser_config.adjust_width(steps);
+                widget_flags.redraw = true;
+                widget_flags.refresh_ui = true;
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: match state {
+                        EraserState::Down(_) => PenProgress::InProgress,
+                        _ => PenProgress::Idle,
+                    },
+                }
+            }
             (EraserState::Up | EraserState::Proximity { .. }, PenEvent::Down { element, .. }) => {
                 widget_flags |= erase(element, engine_view);
                 self.state = EraserState::Down(element);
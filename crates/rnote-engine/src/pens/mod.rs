@@ -1,5 +1,6 @@
 // Modules
 pub mod brush;
+pub mod deviceprofiles;
 pub mod eraser;
 pub mod penbehaviour;
 pub mod penholder;
@@ -13,6 +14,7 @@ pub mod typewriter;
 
 // Re-exports
 pub use brush::Brush;
+pub use deviceprofiles::DeviceProfiles;
 pub use eraser::Eraser;
 pub use penbehaviour::PenBehaviour;
 pub use penholder::PenHolder;
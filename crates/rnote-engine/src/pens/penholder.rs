@@ -1,9 +1,10 @@
 // Imports
+use super::deviceprofiles::{InputDevice, InputDeviceAction, InputDeviceType};
 use super::penmode::PenModeState;
 use super::shortcuts::ShortcutMode;
 use super::{
-    Brush, Eraser, Pen, PenBehaviour, PenMode, PenStyle, Selector, Shaper, Shortcuts, Tools,
-    Typewriter,
+    Brush, DeviceProfiles, Eraser, Pen, PenBehaviour, PenMode, PenStyle, Selector, Shaper,
+    Shortcuts, Tools, Typewriter,
 };
 use crate::camera::NudgeDirection;
 use crate::engine::{EngineView, EngineViewMut};
@@ -33,6 +34,8 @@ pub struct PenHolder {
     shortcuts: Shortcuts,
     #[serde(rename = "pen_mode_state")]
     pen_mode_state: PenModeState,
+    #[serde(rename = "device_profiles")]
+    device_profiles: DeviceProfiles,
 
     /// The policy for the retrieval of input event backlogs.
     #[serde(skip)]
@@ -45,6 +48,9 @@ pub struct PenHolder {
     toggle_pen_style: Option<PenStyle>,
     #[serde(skip)]
     prev_shortcut_key: Option<ShortcutKey>,
+    /// The document position where panning with a device that is configured to pan has started.
+    #[serde(skip)]
+    device_pan_start: Option<na::Vector2<f64>>,
 }
 
 impl Default for PenHolder {
@@ -52,12 +58,14 @@ impl Default for PenHolder {
         Self {
             shortcuts: Shortcuts::default(),
             pen_mode_state: PenModeState::default(),
+            device_profiles: DeviceProfiles::default(),
             backlog_policy: BacklogPolicy::NoLimit,
 
             current_pen: Pen::default(),
             progress: PenProgress::Idle,
             toggle_pen_style: None,
             prev_shortcut_key: None,
+            device_pan_start: None,
         }
     }
 }
@@ -67,6 +75,7 @@ impl CloneConfig for PenHolder {
         Self {
             shortcuts: self.shortcuts.clone(),
             pen_mode_state: self.pen_mode_state.clone_config(),
+            device_profiles: self.device_profiles.clone(),
             ..Default::default()
         }
     }
@@ -121,6 +130,16 @@ impl PenHolder {
         self.pen_mode_state = pen_mode_state;
     }
 
+    /// Get the input device profiles.
+    pub fn device_profiles(&self) -> DeviceProfiles {
+        self.device_profiles.clone()
+    }
+
+    /// Replace the input device profiles.
+    pub fn set_device_profiles(&mut self, device_profiles: DeviceProfiles) {
+        self.device_profiles = device_profiles;
+    }
+
     pub fn backlog_policy(&self) -> BacklogPolicy {
         self.backlog_policy
     }
@@ -140,7 +159,7 @@ impl PenHolder {
         self.progress
     }
 
-    pub fn current_pen_ref(&mut self) -> &Pen {
+    pub fn current_pen_ref(&self) -> &Pen {
         &self.current_pen
     }
 
@@ -228,19 +247,38 @@ impl PenHolder {
     }
 
     /// Handle a pen event.
+    ///
+    /// When the input device is known, its profile is applied to the event.
     pub fn handle_pen_event(
         &mut self,
         event: PenEvent,
         pen_mode: Option<PenMode>,
+        input_device: Option<&InputDevice>,
         now: Instant,
         engine_view: &mut EngineViewMut,
     ) -> (EventPropagation, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
 
+        let event = if let Some(profile) =
+            input_device.map(|device| self.device_profiles.profile_for(device))
+        {
+            if profile.action == InputDeviceAction::Pan {
+                return self.handle_pen_event_device_pan(event, engine_view);
+            }
+            profile.map_event(event)
+        } else {
+            event
+        };
+
         if let Some(pen_mode) = pen_mode {
             widget_flags |= self.change_pen_mode(pen_mode, engine_view);
         }
 
+        // Events without a device, like key presses, keep the pressure state of the previous pointer events
+        if let (Pen::Brush(brush), Some(device)) = (&mut self.current_pen, input_device) {
+            brush.set_input_has_pressure(device.device_type == InputDeviceType::Pen);
+        }
+
         // Handle the event with the current pen
         let (mut event_result, wf) = self
             .current_pen
@@ -260,16 +298,29 @@ impl PenHolder {
     }
 
     /// Handle a pressed shortcut key.
+    ///
+    /// When the input device is known, the button mapping of its profile takes precedence over the registered shortcuts.
     pub fn handle_pressed_shortcut_key(
         &mut self,
         shortcut_key: ShortcutKey,
+        input_device: Option<&InputDevice>,
         _now: Instant,
         engine_view: &mut EngineViewMut,
     ) -> (EventPropagation, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
         let mut propagate = EventPropagation::Proceed;
 
-        if let Some(action) = self.get_shortcut_action(shortcut_key) {
+        let action = input_device
+            .and_then(|device| {
+                self.device_profiles
+                    .profile_for(device)
+                    .button_mapping
+                    .get(&shortcut_key)
+                    .copied()
+            })
+            .or_else(|| self.get_shortcut_action(shortcut_key));
+
+        if let Some(action) = action {
             match action {
                 ShortcutAction::ChangePenStyle { style, mode } => match mode {
                     ShortcutMode::Temporary => {
@@ -301,6 +352,12 @@ impl PenHolder {
                     }
                     ShortcutMode::Disabled => {}
                 },
+                ShortcutAction::OpenRadialMenu => {
+                    widget_flags.open_radial_menu = true;
+                }
+                ShortcutAction::OpenContextMenu => {
+                    widget_flags.open_context_menu = true;
+                }
             }
 
             propagate = EventPropagation::Stop;
@@ -461,6 +518,63 @@ impl PenHolder {
         (propagate, widget_flags)
     }
 
+    /// Pans the camera with the events of a device that is configured to pan instead of drawing.
+    fn handle_pen_event_device_pan(
+        &mut self,
+        event: PenEvent,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventPropagation, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let propagate = match event {
+            PenEvent::Down { element, .. } => {
+                if let Some(start) = self.device_pan_start {
+                    let surface_offset = engine_view
+                        .camera
+                        .transform()
+                        .transform_point(&element.pos.into())
+                        .coords
+                        - engine_view
+                            .camera
+                            .transform()
+                            .transform_point(&start.into())
+                            .coords;
+                    let offset = engine_view.camera.unrotate_surface_vec(surface_offset);
+
+                    widget_flags |= engine_view
+                        .camera
+                        .set_offset(engine_view.camera.offset() - offset, engine_view.document);
+                    widget_flags |= engine_view
+                        .document
+                        .expand_autoexpand(engine_view.camera, engine_view.store);
+                } else {
+                    self.device_pan_start = Some(element.pos);
+                }
+                EventPropagation::Stop
+            }
+            PenEvent::Up { .. } | PenEvent::Cancel => {
+                if self.device_pan_start.take().is_some() {
+                    widget_flags |= engine_view
+                        .document
+                        .resize_autoexpand(engine_view.store, engine_view.camera);
+                    engine_view.store.regenerate_rendering_in_viewport_threaded(
+                        engine_view.tasks_tx.clone(),
+                        false,
+                        engine_view.camera.viewport(),
+                        engine_view.camera.image_scale(),
+                    );
+                }
+                EventPropagation::Stop
+            }
+            PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. } => {
+                EventPropagation::Proceed
+            }
+        };
+        widget_flags.redraw = true;
+
+        (propagate, widget_flags)
+    }
+
     fn handle_pen_progress(
         &mut self,
         progress: PenProgress,
@@ -2,9 +2,11 @@
 use crate::store::chrono_comp::StrokeLayer;
 use rand::{Rng, SeedableRng};
 use rnote_compose::builders::PenPathBuilderType;
+use rnote_compose::style::custom::CustomOptions;
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::style::textured::TexturedOptions;
 use rnote_compose::style::PressureCurve;
+use rnote_compose::transform::Transformable;
 use rnote_compose::Style;
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +29,8 @@ pub enum BrushStyle {
     Solid,
     #[serde(rename = "textured")]
     Textured,
+    #[serde(rename = "custom")]
+    Custom,
 }
 
 impl Default for BrushStyle {
@@ -97,6 +101,177 @@ impl std::ops::DerefMut for SolidOptions {
     }
 }
 
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "symmetry_mode")]
+pub enum SymmetryMode {
+    /// No symmetry.
+    #[serde(rename = "off")]
+    Off = 0,
+    /// Strokes are mirrored on the axis.
+    #[serde(rename = "mirror")]
+    Mirror,
+    /// Strokes are repeated n times around the center.
+    #[serde(rename = "radial")]
+    Radial,
+}
+
+impl Default for SymmetryMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl TryFrom<u32> for SymmetryMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("SymmetryMode try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// The configuration of the symmetry drawing mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "symmetry_config")]
+pub struct SymmetryConfig {
+    #[serde(rename = "mode")]
+    pub mode: SymmetryMode,
+    /// The center of the symmetry in document coordinates. The axis goes through it.
+    #[serde(
+        rename = "center",
+        with = "rnote_compose::serialize::na_vector2_f64_dp3"
+    )]
+    pub center: na::Vector2<f64>,
+    /// The angle of the axis in degrees, clockwise starting from the vertical.
+    #[serde(rename = "axis_angle", with = "rnote_compose::serialize::f64_dp3")]
+    pub axis_angle: f64,
+    /// The number of repetitions around the center in radial mode.
+    #[serde(rename = "n_fold")]
+    pub n_fold: u32,
+    /// Additionally mirror every repetition on its axis in radial mode.
+    #[serde(rename = "radial_mirror")]
+    pub radial_mirror: bool,
+}
+
+impl Default for SymmetryConfig {
+    fn default() -> Self {
+        Self {
+            mode: SymmetryMode::default(),
+            center: na::Vector2::zeros(),
+            axis_angle: 0.0,
+            n_fold: 6,
+            radial_mirror: false,
+        }
+    }
+}
+
+impl SymmetryConfig {
+    pub const N_FOLD_MIN: u32 = 2;
+    pub const N_FOLD_MAX: u32 = 32;
+
+    /// The transforms producing the symmetric copies of a stroke, excluding the original.
+    pub fn transforms(&self) -> Vec<SymmetryTransform> {
+        let axis_angle = self.axis_angle.to_radians();
+        let mirrored = |rotation: f64| SymmetryTransform {
+            center: self.center,
+            rotation,
+            mirror_axis_angle: Some(axis_angle),
+        };
+        let rotated = |rotation: f64| SymmetryTransform {
+            center: self.center,
+            rotation,
+            mirror_axis_angle: None,
+        };
+
+        match self.mode {
+            SymmetryMode::Off => vec![],
+            SymmetryMode::Mirror => vec![mirrored(0.0)],
+            SymmetryMode::Radial => {
+                let n_fold = self.n_fold.clamp(Self::N_FOLD_MIN, Self::N_FOLD_MAX);
+                let angle_step = std::f64::consts::TAU / n_fold as f64;
+                let mut transforms = (1..n_fold)
+                    .map(|i| rotated(angle_step * i as f64))
+                    .collect::<Vec<SymmetryTransform>>();
+                if self.radial_mirror {
+                    transforms.extend((0..n_fold).map(|i| mirrored(angle_step * i as f64)));
+                }
+                transforms
+            }
+        }
+    }
+
+    /// The angles of the axes that are displayed as guidance, in radians.
+    pub fn axes_angles(&self) -> Vec<f64> {
+        let axis_angle = self.axis_angle.to_radians();
+
+        match self.mode {
+            SymmetryMode::Off => vec![],
+            SymmetryMode::Mirror => vec![axis_angle],
+            SymmetryMode::Radial => {
+                let n_fold = self.n_fold.clamp(Self::N_FOLD_MIN, Self::N_FOLD_MAX);
+                let angle_step = std::f64::consts::TAU / n_fold as f64;
+                (0..n_fold)
+                    .map(|i| axis_angle + angle_step * i as f64)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A transform producing a symmetric copy: an optional mirroring on an axis through the center,
+/// followed by a rotation around the center.
+#[derive(Debug, Clone, Copy)]
+pub struct SymmetryTransform {
+    center: na::Vector2<f64>,
+    rotation: f64,
+    /// The angle of the mirror axis in radians, clockwise starting from the vertical.
+    mirror_axis_angle: Option<f64>,
+}
+
+impl SymmetryTransform {
+    /// Applies the transform to the given geometry.
+    pub fn apply(&self, geometry: &mut impl Transformable) {
+        let origin = na::Point2::origin();
+        geometry.translate(-self.center);
+        if let Some(axis_angle) = self.mirror_axis_angle {
+            // Align the axis with the y-axis, then mirror on it
+            geometry.rotate(-axis_angle, origin);
+            geometry.scale(na::vector![-1.0, 1.0]);
+            geometry.rotate(axis_angle, origin);
+        }
+        geometry.rotate(self.rotation, origin);
+        geometry.translate(self.center);
+    }
+
+    /// The transform as affine.
+    pub fn to_kurbo(self) -> kurbo::Affine {
+        let center = kurbo::Vec2::new(self.center[0], self.center[1]);
+        let mirror = match self.mirror_axis_angle {
+            Some(axis_angle) => {
+                kurbo::Affine::rotate(axis_angle)
+                    * kurbo::Affine::scale_non_uniform(-1.0, 1.0)
+                    * kurbo::Affine::rotate(-axis_angle)
+            }
+            None => kurbo::Affine::IDENTITY,
+        };
+        kurbo::Affine::translate(center)
+            * kurbo::Affine::rotate(self.rotation)
+            * mirror
+            * kurbo::Affine::translate(-center)
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "brush_config")]
 pub struct BrushConfig {
@@ -110,6 +285,17 @@ pub struct BrushConfig {
     pub solid_options: SolidOptions,
     #[serde(rename = "textured_options")]
     pub textured_options: TexturedOptions,
+    /// The options of the custom style, holding the brush definition.
+    #[serde(rename = "custom_options")]
+    pub custom_options: CustomOptions,
+    /// Synthesize the pen pressure from the drawing velocity.
+    ///
+    /// Meant for input devices that don't report any pressure, like mice or touchscreens.
+    #[serde(rename = "simulate_pressure")]
+    pub simulate_pressure: bool,
+    /// Mirror or repeat strokes while drawing.
+    #[serde(rename = "symmetry")]
+    pub symmetry: SymmetryConfig,
 }
 
 impl BrushConfig {
@@ -119,7 +305,9 @@ impl BrushConfig {
     pub(crate) fn layer_for_current_options(&self) -> StrokeLayer {
         match &self.style {
             BrushStyle::Marker => StrokeLayer::Highlighter,
-            BrushStyle::Solid | BrushStyle::Textured => StrokeLayer::UserLayer(0),
+            BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Custom => {
+                StrokeLayer::UserLayer(0)
+            }
         }
     }
 
@@ -127,6 +315,7 @@ impl BrushConfig {
     pub(crate) fn new_style_seeds(&mut self) {
         let seed = Some(rand_pcg::Pcg64::from_entropy().gen());
         self.textured_options.seed = seed;
+        self.custom_options.seed = seed;
     }
 
     pub(crate) fn style_for_current_options(&self) -> Style {
@@ -146,6 +335,11 @@ impl BrushConfig {
 
                 Style::Textured(options)
             }
+            BrushStyle::Custom => {
+                let options = self.custom_options.clone();
+
+                Style::Custom(options)
+            }
         }
     }
 }
@@ -52,6 +52,14 @@ impl EraserConfig {
     pub const WIDTH_MIN: f64 = 1.0;
     pub const WIDTH_MAX: f64 = 500.0;
     pub const WIDTH_DEFAULT: f64 = 12.0;
+    /// The factor the width is changed by with one adjustment step.
+    pub const WIDTH_STEP_FACTOR: f64 = 1.15;
+
+    /// Grow (positive) or shrink (negative) the width by the given number of steps.
+    pub(crate) fn adjust_width(&mut self, steps: f64) {
+        self.width = (self.width * Self::WIDTH_STEP_FACTOR.powf(steps))
+            .clamp(Self::WIDTH_MIN, Self::WIDTH_MAX);
+    }
 
     pub(crate) fn eraser_bounds(&self, element: Element) -> Aabb {
         Aabb::from_half_extents(element.pos.into(), na::Vector2::repeat(self.width * 0.5))
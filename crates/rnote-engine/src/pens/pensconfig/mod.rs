@@ -1,6 +1,7 @@
 // Modules
 pub mod brushconfig;
 pub mod eraserconfig;
+pub mod presets;
 pub mod selectorconfig;
 pub mod shaperconfig;
 pub mod toolsconfig;
@@ -9,12 +10,14 @@ pub mod typewriterconfig;
 // Re-exports
 pub use brushconfig::BrushConfig;
 pub use eraserconfig::EraserConfig;
+pub use presets::PenPresets;
 pub use selectorconfig::SelectorConfig;
 pub use shaperconfig::ShaperConfig;
 pub use toolsconfig::ToolsConfig;
 pub use typewriterconfig::TypewriterConfig;
 
 // Imports
+use crate::utils::deserialize_field_or_default;
 use crate::CloneConfig;
 use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
@@ -34,6 +37,8 @@ pub struct PensConfig {
     pub selector_config: SelectorConfig,
     #[serde(default, rename = "tools_config")]
     pub tools_config: ToolsConfig,
+    #[serde(default, rename = "presets")]
+    pub presets: PenPresets,
 }
 
 impl CloneConfig for PensConfig {
@@ -43,10 +48,33 @@ impl CloneConfig for PensConfig {
 }
 
 impl PensConfig {
+    /// Deserialize the pens config from a Json value.
+    ///
+    /// Pen configs that can't be deserialized fall back to their defaults
+    /// instead of resetting the configs of all pens.
+    pub fn from_json_value_lenient(value: &serde_json::Value) -> Self {
+        if let Ok(pens_config) = serde_json::from_value::<Self>(value.clone()) {
+            return pens_config;
+        }
+        let Some(object) = value.as_object() else {
+            return Self::default();
+        };
+        Self {
+            brush_config: deserialize_field_or_default(object, "brush_config"),
+            shaper_config: deserialize_field_or_default(object, "shaper_config"),
+            typewriter_config: deserialize_field_or_default(object, "typewriter_config"),
+            eraser_config: deserialize_field_or_default(object, "eraser_config"),
+            selector_config: deserialize_field_or_default(object, "selector_config"),
+            tools_config: deserialize_field_or_default(object, "tools_config"),
+            presets: deserialize_field_or_default(object, "presets"),
+        }
+    }
+
     pub fn set_all_stroke_colors(&mut self, stroke_color: Color) {
         self.brush_config.marker_options.stroke_color = Some(stroke_color);
         self.brush_config.solid_options.stroke_color = Some(stroke_color);
         self.brush_config.textured_options.stroke_color = Some(stroke_color);
+        self.brush_config.custom_options.stroke_color = Some(stroke_color);
         self.shaper_config.smooth_options.stroke_color = Some(stroke_color);
         self.shaper_config.rough_options.stroke_color = Some(stroke_color);
         self.typewriter_config.text_style.color = stroke_color;
@@ -57,5 +85,6 @@ impl PensConfig {
         self.brush_config.solid_options.fill_color = Some(fill_color);
         self.shaper_config.smooth_options.fill_color = Some(fill_color);
         self.shaper_config.rough_options.fill_color = Some(fill_color);
+        self.tools_config.fill_tool_config.fill_color = fill_color;
     }
 }
@@ -0,0 +1,138 @@
+// Imports
+use super::{BrushConfig, ShaperConfig};
+use crate::pens::pensconfig::brushconfig::BrushStyle;
+use crate::pens::pensconfig::shaperconfig::ShaperStyle;
+use crate::pens::PenStyle;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// The pen options that are stored in a preset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "pen_preset_options")]
+pub enum PenPresetOptions {
+    #[serde(rename = "brush")]
+    Brush(BrushConfig),
+    #[serde(rename = "shaper")]
+    Shaper(ShaperConfig),
+}
+
+impl PenPresetOptions {
+    pub fn pen_style(&self) -> PenStyle {
+        match self {
+            PenPresetOptions::Brush(_) => PenStyle::Brush,
+            PenPresetOptions::Shaper(_) => PenStyle::Shaper,
+        }
+    }
+
+    /// A name describing the options, used for new presets.
+    pub fn default_name(&self) -> String {
+        let style_name = match self {
+            PenPresetOptions::Brush(config) => match config.style {
+                BrushStyle::Marker => "Marker",
+                BrushStyle::Solid => "Solid Brush",
+                BrushStyle::Textured => "Textured Brush",
+                BrushStyle::Custom => "Custom Brush",
+            },
+            PenPresetOptions::Shaper(config) => match config.style {
+                ShaperStyle::Smooth => "Smooth Shape",
+                ShaperStyle::Rough => "Rough Shape",
+            },
+        };
+        format!("{style_name} {:.1}", self.stroke_color_width().1)
+    }
+
+    /// The stroke color and width of the currently selected style of the options.
+    pub fn stroke_color_width(&self) -> (Option<Color>, f64) {
+        match self {
+            PenPresetOptions::Brush(config) => match config.style {
+                BrushStyle::Marker => (
+                    config.marker_options.stroke_color,
+                    config.marker_options.stroke_width,
+                ),
+                BrushStyle::Solid => (
+                    config.solid_options.stroke_color,
+                    config.solid_options.stroke_width,
+                ),
+                BrushStyle::Textured => (
+                    config.textured_options.stroke_color,
+                    config.textured_options.stroke_width,
+                ),
+                BrushStyle::Custom => (
+                    config.custom_options.stroke_color,
+                    config.custom_options.stroke_width,
+                ),
+            },
+            PenPresetOptions::Shaper(config) => match config.style {
+                ShaperStyle::Smooth => (
+                    config.smooth_options.stroke_color,
+                    config.smooth_options.stroke_width,
+                ),
+                ShaperStyle::Rough => (
+                    config.rough_options.stroke_color,
+                    config.rough_options.stroke_width,
+                ),
+            },
+        }
+    }
+}
+
+/// A saved combination of a pen and its options, for quickly switching between often used pens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "pen_preset")]
+pub struct PenPreset {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "options")]
+    pub options: PenPresetOptions,
+}
+
+impl PenPreset {
+    pub fn new(name: impl Into<String>, options: PenPresetOptions) -> Self {
+        Self {
+            name: name.into(),
+            options,
+        }
+    }
+}
+
+/// The pen presets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "pen_presets")]
+pub struct PenPresets {
+    #[serde(rename = "presets")]
+    presets: Vec<PenPreset>,
+}
+
+impl PenPresets {
+    /// The maximum number of presets, so that every preset can be switched to with a number key.
+    pub const MAX_PRESETS: usize = 9;
+
+    pub fn iter(&self) -> impl Iterator<Item = &PenPreset> {
+        self.presets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&PenPreset> {
+        self.presets.get(i)
+    }
+
+    /// Add a preset. Returns its index, or `None` if the maximum number of presets is already reached.
+    pub fn push(&mut self, preset: PenPreset) -> Option<usize> {
+        if self.presets.len() >= Self::MAX_PRESETS {
+            return None;
+        }
+        self.presets.push(preset);
+        Some(self.presets.len() - 1)
+    }
+
+    pub fn remove(&mut self, i: usize) -> Option<PenPreset> {
+        (i < self.presets.len()).then(|| self.presets.remove(i))
+    }
+}
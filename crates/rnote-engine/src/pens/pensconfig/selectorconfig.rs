@@ -49,6 +49,9 @@ pub struct SelectorConfig {
     pub style: SelectorStyle,
     #[serde(rename = "resize_lock_aspectratio")]
     pub resize_lock_aspectratio: bool,
+    /// Whether the nodes of a single selected shape are exposed for editing.
+    #[serde(rename = "edit_nodes")]
+    pub edit_nodes: bool,
 }
 
 impl Default for SelectorConfig {
@@ -56,6 +59,7 @@ impl Default for SelectorConfig {
         Self {
             style: SelectorStyle::default(),
             resize_lock_aspectratio: false,
+            edit_nodes: false,
         }
     }
 }
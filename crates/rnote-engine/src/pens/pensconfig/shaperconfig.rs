@@ -1,4 +1,5 @@
 // Imports
+use crate::strokes::{Gradient, Hatch};
 use rand::{Rng, SeedableRng};
 use rnote_compose::builders::ShapeBuilderType;
 use rnote_compose::constraints::ConstraintRatio;
@@ -46,6 +47,10 @@ pub struct ShaperConfig {
     pub smooth_options: SmoothOptions,
     #[serde(rename = "rough_options")]
     pub rough_options: RoughOptions,
+    #[serde(rename = "fill_gradient")]
+    pub fill_gradient: Gradient,
+    #[serde(rename = "hatch")]
+    pub hatch: Hatch,
     #[serde(rename = "constraints")]
     pub constraints: Constraints,
 }
@@ -62,6 +67,8 @@ impl Default for ShaperConfig {
             style: ShaperStyle::default(),
             smooth_options: SmoothOptions::default(),
             rough_options: RoughOptions::default(),
+            fill_gradient: Gradient::default(),
+            hatch: Hatch::default(),
             constraints,
         }
     }
@@ -1,4 +1,7 @@
 // Imports
+use crate::document::format::MeasureUnit;
+use crate::strokes::connectorstroke::{ConnectorRouting, ConnectorStroke};
+use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -22,6 +25,18 @@ pub enum ToolStyle {
     OffsetCamera,
     #[serde(rename = "zoom")]
     Zoom,
+    #[serde(rename = "measure")]
+    Measure,
+    #[serde(rename = "stamp")]
+    Stamp,
+    #[serde(rename = "perspective")]
+    Perspective,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "redaction")]
+    Redaction,
+    #[serde(rename = "connector")]
+    Connector,
 }
 
 impl Default for ToolStyle {
@@ -49,10 +64,97 @@ pub struct VerticalSpaceToolConfig {
     pub limit_movement_vertical_borders: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "measure_tool_config")]
+pub struct MeasureToolConfig {
+    /// The unit the measured length is reported in.
+    #[serde(rename = "unit")]
+    pub unit: MeasureUnit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "stamp_tool_config")]
+pub struct StampToolConfig {
+    /// The name of the selected stamp in the stamp library.
+    #[serde(rename = "stamp_name")]
+    pub stamp_name: String,
+    /// The Svg data of the selected stamp. Empty when no stamp is selected.
+    #[serde(rename = "svg_data")]
+    pub svg_data: String,
+    /// The size of the longer side of a placed stamp.
+    #[serde(rename = "size")]
+    pub size: f64,
+}
+
+impl Default for StampToolConfig {
+    fn default() -> Self {
+        Self {
+            stamp_name: String::default(),
+            svg_data: String::default(),
+            size: Self::SIZE_DEFAULT,
+        }
+    }
+}
+
+impl StampToolConfig {
+    pub const SIZE_DEFAULT: f64 = 64.0;
+    pub const SIZE_MIN: f64 = 8.0;
+    pub const SIZE_MAX: f64 = 1024.0;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "fill_tool_config")]
+pub struct FillToolConfig {
+    /// The color of the created fills.
+    #[serde(rename = "fill_color")]
+    pub fill_color: Color,
+}
+
+impl Default for FillToolConfig {
+    fn default() -> Self {
+        Self {
+            fill_color: Color::new(0.6, 0.757, 0.945, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "connector_tool_config")]
+pub struct ConnectorToolConfig {
+    #[serde(rename = "routing")]
+    pub routing: ConnectorRouting,
+    /// Whether created connectors have an arrow head at their end.
+    #[serde(rename = "arrow")]
+    pub arrow: bool,
+    #[serde(rename = "color")]
+    pub color: Color,
+    #[serde(rename = "width")]
+    pub width: f64,
+}
+
+impl Default for ConnectorToolConfig {
+    fn default() -> Self {
+        Self {
+            routing: ConnectorRouting::default(),
+            arrow: true,
+            color: Color::BLACK,
+            width: ConnectorStroke::WIDTH_DEFAULT,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "tools_config")]
 pub struct ToolsConfig {
     #[serde(rename = "style")]
     pub style: ToolStyle,
     pub verticalspace_tool_config: VerticalSpaceToolConfig,
+    #[serde(rename = "measure_tool_config")]
+    pub measure_tool_config: MeasureToolConfig,
+    #[serde(rename = "stamp_tool_config")]
+    pub stamp_tool_config: StampToolConfig,
+    #[serde(rename = "fill_tool_config")]
+    pub fill_tool_config: FillToolConfig,
+    #[serde(rename = "connector_tool_config")]
+    pub connector_tool_config: ConnectorToolConfig,
 }
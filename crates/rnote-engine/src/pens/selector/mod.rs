@@ -8,8 +8,9 @@ use super::PenStyle;
 use crate::engine::{EngineView, EngineViewMut, StrokeContent};
 use crate::render::Svg;
 use crate::snap::SnapCorner;
-use crate::store::StrokeKey;
-use crate::strokes::Content;
+use crate::store::{StrokeKey, StrokeStore};
+use crate::strokes::tablestroke::TableBorder;
+use crate::strokes::{Content, ShapeStroke, Stroke};
 use crate::{Camera, DrawableOnDoc, Engine, WidgetFlags};
 use futures::channel::oneshot;
 use kurbo::Shape;
@@ -19,6 +20,7 @@ use piet::RenderContext;
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{ModifierKey, PenEvent, PenProgress, PenState};
 use rnote_compose::penpath::Element;
+use rnote_compose::shapes::{Shape, ShapeNodeKind};
 use rnote_compose::style::indicators;
 use rnote_compose::EventResult;
 use rnote_compose::{color, Color};
@@ -54,6 +56,18 @@ pub(super) enum ModifyState {
         start_pos: na::Vector2<f64>,
         last_rendered_bounds: Aabb,
     },
+    /// Dragging a row or column border of a selected table.
+    ResizeTableBorder {
+        key: StrokeKey,
+        border: TableBorder,
+    },
+    /// Dragging a node of a selected shape.
+    ///
+    /// The node is `None` when the press removed or toggled a node, so there is nothing to drag.
+    DragShapeNode {
+        key: StrokeKey,
+        node: Option<usize>,
+    },
 }
 
 impl Default for ModifyState {
@@ -433,6 +447,19 @@ impl DrawableOnDoc for Selector {
                     engine_view.camera,
                 )?;
 
+                if let Some((_, shapestroke)) = Self::node_edited_shapestroke(
+                    selection,
+                    engine_view.pens_config.selector_config.edit_nodes,
+                    engine_view.store,
+                ) {
+                    Self::draw_shape_nodes(
+                        cx,
+                        &shapestroke.shape,
+                        modify_state,
+                        engine_view.camera,
+                    );
+                }
+
                 match modify_state {
                     ModifyState::Rotate {
                         rotation_center,
@@ -476,6 +503,158 @@ impl Selector {
     const SELECTION_OUTLINE_COLOR: piet::Color = color::GNOME_BRIGHTS[4].with_a8(240);
     /// The fill color when drawing a selection
     const SELECTION_FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[2].with_a8(13);
+    /// The distance to a table border where it can be grabbed, in surface coordinates.
+    const TABLE_BORDER_GRAB_TOLERANCE: f64 = 6.0;
+    /// Shape node size, in surface coordinates.
+    const SHAPE_NODE_SIZE: na::Vector2<f64> = na::vector![12.0, 12.0];
+    /// The distance to a shape segment where a new node can be inserted, in surface coordinates.
+    const SHAPE_SEGMENT_GRAB_TOLERANCE: f64 = 6.0;
+
+    /// The selected shape stroke, if node editing is enabled and the selection is a single shape stroke.
+    fn node_edited_shapestroke<'a>(
+        selection: &[StrokeKey],
+        edit_nodes: bool,
+        store: &'a StrokeStore,
+    ) -> Option<(StrokeKey, &'a ShapeStroke)> {
+        let [key] = selection else {
+            return None;
+        };
+        if !edit_nodes {
+            return None;
+        }
+        match store.get_stroke_ref(*key) {
+            Some(Stroke::ShapeStroke(shapestroke)) => Some((*key, shapestroke)),
+            _ => None,
+        }
+    }
+
+    fn shape_node_bounds(pos: na::Vector2<f64>, camera: &Camera) -> Aabb {
+        Aabb::from_half_extents(
+            pos.into(),
+            Self::SHAPE_NODE_SIZE * 0.5 / camera.total_zoom(),
+        )
+    }
+
+    /// The node of the edited shape at the position.
+    fn shape_node_at(
+        selection: &[StrokeKey],
+        pos: na::Vector2<f64>,
+        engine_view: &EngineViewMut,
+    ) -> Option<(StrokeKey, usize)> {
+        let (key, shapestroke) = Self::node_edited_shapestroke(
+            selection,
+            engine_view.pens_config.selector_config.edit_nodes,
+            engine_view.store,
+        )?;
+        // reversed, so that the node drawn on top is picked first
+        shapestroke
+            .shape
+            .nodes()
+            .into_iter()
+            .enumerate()
+            .rev()
+            .find(|(_, node)| {
+                Self::shape_node_bounds(node.pos, engine_view.camera)
+                    .contains_local_point(&pos.into())
+            })
+            .map(|(i, _)| (key, i))
+    }
+
+    /// Edit the shape of a shape stroke and update it.
+    fn edit_shape<T>(
+        key: StrokeKey,
+        engine_view: &mut EngineViewMut,
+        edit: impl FnOnce(&mut Shape) -> T,
+    ) -> Option<T> {
+        let Some(Stroke::ShapeStroke(shapestroke)) = engine_view.store.get_stroke_mut(key) else {
+            return None;
+        };
+        let res = edit(&mut shapestroke.shape);
+        engine_view.store.update_geometry_for_stroke(key);
+        engine_view.store.regenerate_rendering_for_stroke(
+            key,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+        Some(res)
+    }
+
+    fn draw_shape_nodes(
+        piet_cx: &mut impl RenderContext,
+        shape: &Shape,
+        modify_state: &ModifyState,
+        camera: &Camera,
+    ) {
+        let total_zoom = camera.total_zoom();
+        let nodes = shape.nodes();
+
+        // the handles, connecting control points to their anchors
+        for (i, node) in nodes.iter().enumerate() {
+            if node.kind != ShapeNodeKind::Control {
+                continue;
+            }
+            let adjacent_anchors = [i.checked_sub(1), Some(i + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|j| nodes.get(j))
+                .filter(|adjacent| adjacent.kind == ShapeNodeKind::Anchor);
+            for anchor in adjacent_anchors {
+                piet_cx.stroke(
+                    kurbo::Line::new(anchor.pos.to_kurbo_point(), node.pos.to_kurbo_point()),
+                    &Self::SELECTION_OUTLINE_COLOR,
+                    1.0 / total_zoom,
+                );
+            }
+        }
+
+        for (i, node) in nodes.iter().enumerate() {
+            let bounds = Self::shape_node_bounds(node.pos, camera);
+            let node_state = match modify_state {
+                ModifyState::DragShapeNode {
+                    node: Some(dragged),
+                    ..
+                } if *dragged == i => PenState::Down,
+                ModifyState::Hover(pos) if bounds.contains_local_point(&(*pos).into()) => {
+                    PenState::Proximity
+                }
+                _ => PenState::Up,
+            };
+            match node.kind {
+                ShapeNodeKind::Anchor => {
+                    indicators::draw_rectangular_node(piet_cx, node_state, bounds, total_zoom)
+                }
+                ShapeNodeKind::Control => indicators::draw_circular_node(
+                    piet_cx,
+                    node_state,
+                    BoundingSphere::new(
+                        node.pos.into(),
+                        Self::SHAPE_NODE_SIZE[0] * 0.5 / total_zoom,
+                    ),
+                    total_zoom,
+                ),
+            }
+        }
+    }
+
+    /// The table border at the position, if the selection is a single table.
+    fn table_border_at(
+        selection: &[StrokeKey],
+        pos: na::Vector2<f64>,
+        engine_view: &EngineViewMut,
+    ) -> Option<(StrokeKey, TableBorder)> {
+        let [key] = selection else {
+            return None;
+        };
+        let Some(Stroke::TableStroke(table)) = engine_view.store.get_stroke_ref(*key) else {
+            return None;
+        };
+        table
+            .border_at(
+                pos,
+                Self::TABLE_BORDER_GRAB_TOLERANCE / engine_view.camera.total_zoom(),
+            )
+            .map(|border| (*key, border))
+    }
 
     fn add_to_select_path(style: SelectorStyle, path: &mut Vec<Element>, element: Element) {
         match style {
@@ -782,8 +961,16 @@ impl Selector {
         widget_flags: &mut WidgetFlags,
     ) {
         if modifier_keys.contains(&ModifierKey::KeyboardCtrl) {
-            // Select all keys
-            let all_strokes = engine_view.store.stroke_keys_as_rendered();
+            // Select all keys, including the ones of a lazily loaded document that are not yet loaded
+            if !engine_view.store.load_pending_strokes(None).is_empty() {
+                engine_view
+                    .document
+                    .links
+                    .remap_keys(engine_view.store.pending_strokes_keys());
+            }
+            let all_strokes = engine_view
+                .store
+                .filter_unlocked_keys(engine_view.store.stroke_keys_as_rendered());
 
             if let Some(new_bounds) = engine_view.store.bounds_for_strokes(&all_strokes) {
                 engine_view.store.set_selected_keys(&all_strokes, true);
@@ -4,6 +4,7 @@ use crate::engine::EngineViewMut;
 use crate::pens::pensconfig::selectorconfig::SelectorStyle;
 use crate::snap::SnapCorner;
 use crate::store::StrokeKey;
+use crate::strokes::Stroke;
 use crate::{DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::Aabb;
 use p2d::query::PointQuery;
@@ -88,7 +89,54 @@ impl Selector {
                             )
                             .pop();
 
-                        if (engine_view.pens_config.selector_config.style == SelectorStyle::Single
+                        let node_edited_key = Self::node_edited_shapestroke(
+                            selection,
+                            engine_view.pens_config.selector_config.edit_nodes,
+                            engine_view.store,
+                        )
+                        .map(|(key, _)| key);
+
+                        if let Some((key, node)) =
+                            Self::shape_node_at(selection, element.pos, engine_view)
+                        {
+                            // clicking on a node of the edited shape
+                            let node = if modifier_keys.contains(&ModifierKey::KeyboardCtrl) {
+                                Self::edit_shape(key, engine_view, |shape| shape.remove_node(node));
+                                None
+                            } else if modifier_keys.contains(&ModifierKey::KeyboardShift) {
+                                Self::edit_shape(key, engine_view, |shape| {
+                                    shape.toggle_node_smooth(node)
+                                });
+                                None
+                            } else {
+                                Some(node)
+                            };
+                            if let Some(new_bounds) =
+                                engine_view.store.bounds_for_strokes(selection)
+                            {
+                                *selection_bounds = new_bounds;
+                            }
+                            *modify_state = ModifyState::DragShapeNode { key, node };
+                        } else if let Some((key, node)) = node_edited_key
+                            .filter(|_| modifier_keys.contains(&ModifierKey::KeyboardCtrl))
+                            .and_then(|key| {
+                                // inserting a node on a segment of the edited shape, which is then dragged.
+                                // Away from the segments the press is handled like any other.
+                                let tolerance = Self::SHAPE_SEGMENT_GRAB_TOLERANCE
+                                    / engine_view.camera.total_zoom();
+                                Self::edit_shape(key, engine_view, |shape| {
+                                    shape.insert_node(element.pos, tolerance)
+                                })
+                                .flatten()
+                                .map(|node| (key, node))
+                            })
+                        {
+                            *modify_state = ModifyState::DragShapeNode {
+                                key,
+                                node: Some(node),
+                            };
+                        } else if (engine_view.pens_config.selector_config.style
+                            == SelectorStyle::Single
                             || modifier_keys.contains(&ModifierKey::KeyboardShift))
                             && key_to_add
                                 .and_then(|key| engine_view.store.selected(key).map(|s| !s))
@@ -169,6 +217,11 @@ impl Selector {
                                 start_pos: element.pos,
                                 last_rendered_bounds: *selection_bounds,
                             }
+                        } else if let Some((key, border)) =
+                            Self::table_border_at(selection, element.pos, engine_view)
+                        {
+                            // clicking on a row or column border of a table
+                            *modify_state = ModifyState::ResizeTableBorder { key, border };
                         } else if selection_bounds.contains_local_point(&element.pos.into()) {
                             let snap_corner =
                                 SnapCorner::determine_from_bounds(*selection_bounds, element.pos);
@@ -216,6 +269,12 @@ impl Selector {
                             engine_view
                                 .store
                                 .translate_strokes_images(selection, offset);
+                            let rerouted = engine_view.store.reroute_attached_connectors(selection);
+                            engine_view.store.regenerate_rendering_for_strokes(
+                                &rerouted,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
                             *selection_bounds = selection_bounds.translate(offset);
                             *current_pos += offset;
                         }
@@ -256,6 +315,12 @@ impl Selector {
                                 angle_delta,
                                 *rotation_center,
                             );
+                            let rerouted = engine_view.store.reroute_attached_connectors(selection);
+                            engine_view.store.regenerate_rendering_for_strokes(
+                                &rerouted,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
 
                             if let Some(new_bounds) =
                                 engine_view.store.bounds_for_strokes(selection)
@@ -336,6 +401,12 @@ impl Selector {
                         engine_view
                             .store
                             .scale_strokes_images_with_pivot(selection, scale, pivot);
+                        let rerouted = engine_view.store.reroute_attached_connectors(selection);
+                        engine_view.store.regenerate_rendering_for_strokes(
+                            &rerouted,
+                            engine_view.camera.viewport(),
+                            engine_view.camera.image_scale(),
+                        );
                         *selection_bounds = selection_bounds
                             .translate(-pivot)
                             .scale_non_uniform(scale)
@@ -376,6 +447,32 @@ impl Selector {
                             *last_rendered_bounds = *selection_bounds;
                         }
                     }
+                    ModifyState::ResizeTableBorder { key, border } => {
+                        if let Some(Stroke::TableStroke(table)) =
+                            engine_view.store.get_stroke_mut(*key)
+                        {
+                            table.drag_border_to(*border, element.pos);
+                        }
+                        engine_view.store.update_geometry_for_stroke(*key);
+                        engine_view.store.regenerate_rendering_for_stroke(
+                            *key,
+                            engine_view.camera.viewport(),
+                            engine_view.camera.image_scale(),
+                        );
+                        if let Some(new_bounds) = engine_view.store.bounds_for_strokes(selection) {
+                            *selection_bounds = new_bounds;
+                        }
+                    }
+                    ModifyState::DragShapeNode { key, node } => {
+                        if let Some(node) = *node {
+                            Self::edit_shape(*key, engine_view, |shape| {
+                                shape.move_node(node, element.pos)
+                            });
+                        }
+                        if let Some(new_bounds) = engine_view.store.bounds_for_strokes(selection) {
+                            *selection_bounds = new_bounds;
+                        }
+                    }
                 }
 
                 widget_flags.store_modified = true;
@@ -490,7 +587,14 @@ impl Selector {
                 match modify_state {
                     ModifyState::Translate { .. }
                     | ModifyState::Rotate { .. }
-                    | ModifyState::Resize { .. } => {
+                    | ModifyState::Resize { .. }
+                    | ModifyState::ResizeTableBorder { .. }
+                    | ModifyState::DragShapeNode { .. } => {
+                        if matches!(modify_state, ModifyState::Translate { .. }) {
+                            // Dropped sticky notes snap into board columns. The reflowed strokes are marked dirty
+                            // and are regenerated together with the viewport below.
+                            engine_view.store.reflow_boards(selection);
+                        }
                         engine_view.store.update_geometry_for_strokes(selection);
                         widget_flags |= engine_view
                             .document
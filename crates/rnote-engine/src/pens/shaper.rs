@@ -1,6 +1,7 @@
 // Imports
 use super::PenBehaviour;
 use super::PenStyle;
+use crate::document::Guides;
 use crate::engine::{EngineView, EngineViewMut};
 use crate::strokes::ShapeStroke;
 use crate::strokes::Stroke;
@@ -64,6 +65,7 @@ impl PenBehaviour for Shaper {
         engine_view: &mut EngineViewMut,
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
+        let event = snap_event_to_guides(event, &engine_view.document.guides);
 
         let event_result = match (&mut self.state, event) {
             (ShaperState::Idle, PenEvent::Down { element, .. }) => {
@@ -115,6 +117,10 @@ impl PenBehaviour for Shaper {
                     } => constraints.enabled ^ modifier_keys.contains(&ModifierKey::KeyboardCtrl),
                     PenEvent::Text { .. } | PenEvent::Cancel => false,
                 };
+                if engine_view.document.perspective.constrain_lines {
+                    constraints.vanishing_points =
+                        engine_view.document.perspective.vanishing_points().to_vec();
+                }
                 let builder_result = builder.handle_event(event.clone(), now, constraints);
                 let handled = builder_result.handled;
                 let propagate = builder_result.propagate;
@@ -126,11 +132,17 @@ impl PenBehaviour for Shaper {
                             .pens_config
                             .shaper_config
                             .gen_style_for_current_options();
+                        let fill_gradient = engine_view.pens_config.shaper_config.fill_gradient;
+                        let hatch = engine_view.pens_config.shaper_config.hatch;
                         let shapes_emitted = !shapes.is_empty();
 
                         for shape in shapes {
                             let key = engine_view.store.insert_stroke(
-                                Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
+                                Stroke::ShapeStroke(
+                                    ShapeStroke::new(shape, style.clone())
+                                        .with_fill_gradient(fill_gradient)
+                                        .with_hatch(hatch),
+                                ),
                                 None,
                             );
                             style.advance_seed();
@@ -152,11 +164,17 @@ impl PenBehaviour for Shaper {
                             .pens_config
                             .shaper_config
                             .gen_style_for_current_options();
+                        let fill_gradient = engine_view.pens_config.shaper_config.fill_gradient;
+                        let hatch = engine_view.pens_config.shaper_config.hatch;
 
                         let shapes_emitted = !shapes.is_empty();
                         for shape in shapes {
                             let key = engine_view.store.insert_stroke(
-                                Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
+                                Stroke::ShapeStroke(
+                                    ShapeStroke::new(shape, style.clone())
+                                        .with_fill_gradient(fill_gradient)
+                                        .with_hatch(hatch),
+                                ),
                                 None,
                             );
                             style.advance_seed();
@@ -265,3 +283,30 @@ fn new_builder(
         ShapeBuilderType::Polygon => Box::new(PolygonBuilder::start(element, now)),
     }
 }
+
+/// Snap the positions of the event to the document guides.
+fn snap_event_to_guides(event: PenEvent, guides: &Guides) -> PenEvent {
+    match event {
+        PenEvent::Down {
+            mut element,
+            modifier_keys,
+        } => {
+            element.pos = guides.snap_position(element.pos);
+            PenEvent::Down {
+                element,
+                modifier_keys,
+            }
+        }
+        PenEvent::Up {
+            mut element,
+            modifier_keys,
+        } => {
+            element.pos = guides.snap_position(element.pos);
+            PenEvent::Up {
+                element,
+                modifier_keys,
+            }
+        }
+        event => event,
+    }
+}
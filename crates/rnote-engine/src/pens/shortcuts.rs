@@ -49,6 +49,12 @@ pub enum ShortcutAction {
         #[serde(rename = "mode")]
         mode: ShortcutMode,
     },
+    /// Open the radial quick menu near the pen tip.
+    #[serde(rename = "open_radial_menu")]
+    OpenRadialMenu,
+    /// Open the context menu at the pointer position.
+    #[serde(rename = "open_context_menu")]
+    OpenContextMenu,
 }
 
 /// The registered shortcut actions for the given shortcut keys.
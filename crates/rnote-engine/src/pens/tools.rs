@@ -1,17 +1,27 @@
 // Imports
-use super::pensconfig::toolsconfig::ToolStyle;
+use super::pensconfig::toolsconfig::{ConnectorToolConfig, ToolStyle};
 use super::PenBehaviour;
 use super::PenStyle;
+use crate::document::format::MeasureUnit;
+use crate::document::Perspective;
 use crate::engine::{EngineView, EngineViewMut};
+use crate::store::chrono_comp::StrokeLayer;
 use crate::store::StrokeKey;
-use crate::{Camera, DrawableOnDoc, WidgetFlags};
-use p2d::bounding_volume::Aabb;
-use piet::RenderContext;
+use crate::strokes::connectorstroke::{ConnectorAttachment, ConnectorEnd};
+use crate::strokes::resize::ImageSizeOption;
+use crate::strokes::{ConnectorStroke, RedactionStroke, ShapeStroke, Stroke, VectorImage};
+use crate::{Camera, Drawable, DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
 use rnote_compose::color;
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{PenEvent, PenProgress};
+use rnote_compose::shapes::{Rectangle, Shape, Shapeable};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::Style;
 use std::time::Instant;
+use tracing::{debug, error};
 
 #[derive(Clone, Debug)]
 pub struct VerticalSpaceTool {
@@ -257,6 +267,343 @@ impl DrawableOnDoc for ZoomTool {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct MeasureTool {
+    /// The measured segment in document coordinates.
+    ///
+    /// The last measured segment stays visible until a new measurement is started.
+    pub segment: Option<(na::Vector2<f64>, na::Vector2<f64>)>,
+}
+
+impl MeasureTool {
+    const LINE_WIDTH: f64 = 1.5;
+    const END_RADIUS: f64 = 3.0;
+    const LINE_COLOR: piet::Color = color::GNOME_ORANGES[3];
+    const LABEL_MARGIN: f64 = 6.0;
+    const LABEL_FONT_SIZE: f64 = 12.0;
+    const LABEL_BG_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(220);
+    const LABEL_TEXT_COLOR: piet::Color = color::GNOME_BRIGHTS[1];
+    /// The label is positioned in surface coordinates, so its size is not known in advance.
+    const LABEL_MAX_SIZE: na::Vector2<f64> = na::vector![240.0, 40.0];
+
+    /// The length of the segment in the given unit and its angle to the horizontal in degrees.
+    ///
+    /// The angle is counter-clockwise positive, as usual in technical drawings.
+    pub fn measurement(
+        start: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+        unit: MeasureUnit,
+        dpi: f64,
+    ) -> (f64, f64) {
+        let delta = end - start;
+        let length =
+            MeasureUnit::convert_measurement(delta.norm(), MeasureUnit::Px, dpi, unit, dpi);
+        let angle = (-delta[1]).atan2(delta[0]).to_degrees();
+        (length, angle)
+    }
+}
+
+impl DrawableOnDoc for MeasureTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        let (start, end) = self.segment?;
+        let total_zoom = engine_view.camera.total_zoom();
+
+        Some(
+            Aabb::new_positive(start.into(), end.into())
+                .extend_by(na::Vector2::repeat(Self::END_RADIUS + Self::LINE_WIDTH) / total_zoom)
+                .merged(&Aabb::new(
+                    end.into(),
+                    (end + (Self::LABEL_MAX_SIZE + na::Vector2::repeat(Self::LABEL_MARGIN))
+                        / total_zoom)
+                        .into(),
+                )),
+        )
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        let Some((start, end)) = self.segment else {
+            return Ok(());
+        };
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let total_zoom = engine_view.camera.total_zoom();
+        let unit = engine_view
+            .pens_config
+            .tools_config
+            .measure_tool_config
+            .unit;
+        let (length, angle) =
+            Self::measurement(start, end, unit, engine_view.document.format.dpi());
+
+        cx.stroke(
+            kurbo::Line::new(start.to_kurbo_point(), end.to_kurbo_point()),
+            &Self::LINE_COLOR,
+            Self::LINE_WIDTH / total_zoom,
+        );
+        for pos in [start, end] {
+            cx.fill(
+                kurbo::Circle::new(pos.to_kurbo_point(), Self::END_RADIUS / total_zoom),
+                &Self::LINE_COLOR,
+            );
+        }
+
+        // The label is drawn with a constant size on the surface
+        cx.transform(kurbo::Affine::translate(end.to_kurbo_vec()));
+        cx.transform(kurbo::Affine::scale(1.0 / total_zoom));
+        let text_layout = cx
+            .text()
+            .new_text_layout(format!("{length:.2} {}   {angle:.1}°", unit.symbol()))
+            .text_color(Self::LABEL_TEXT_COLOR)
+            .font(piet::FontFamily::SANS_SERIF, Self::LABEL_FONT_SIZE)
+            .build()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let label_size = text_layout.size();
+        let label_rect = kurbo::Rect::new(
+            Self::LABEL_MARGIN,
+            Self::LABEL_MARGIN,
+            Self::LABEL_MARGIN * 2.0 + label_size.width,
+            Self::LABEL_MARGIN * 2.0 + label_size.height,
+        );
+        cx.fill(label_rect.to_rounded_rect(4.0), &Self::LABEL_BG_COLOR);
+        cx.draw_text(
+            &text_layout,
+            (Self::LABEL_MARGIN * 1.5, Self::LABEL_MARGIN * 1.5),
+        );
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StampTool;
+
+impl StampTool {
+    /// Creates the selected stamp as vector image centered at the given position.
+    ///
+    /// The longer side of the stamp gets the configured size, the aspect ratio is preserved.
+    pub fn create_stamp(
+        svg_data: &str,
+        size: f64,
+        center: na::Vector2<f64>,
+    ) -> anyhow::Result<VectorImage> {
+        let intrinsic_size = VectorImage::from_svg_str(
+            svg_data,
+            na::Vector2::zeros(),
+            ImageSizeOption::RespectOriginalSize,
+        )?
+        .intrinsic_size;
+        let stamp_size = intrinsic_size * (size / intrinsic_size.max().max(1.0));
+        VectorImage::from_svg_str(
+            svg_data,
+            center - stamp_size * 0.5,
+            ImageSizeOption::ImposeSize(stamp_size),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FillTool;
+
+impl FillTool {
+    /// Creates a fill of the region enclosed by strokes around the given position.
+    ///
+    /// Only the strokes inside the viewport are considered. The fill is placed in the highlighter layer,
+    /// underneath the outlines drawn by the user.
+    fn create_fill(
+        pos: na::Vector2<f64>,
+        engine_view: &mut EngineViewMut,
+    ) -> anyhow::Result<Option<StrokeKey>> {
+        let Some(region) = crate::fill::enclosed_region(
+            engine_view.store,
+            pos,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        )?
+        else {
+            return Ok(None);
+        };
+        let style = Style::Smooth(SmoothOptions {
+            stroke_color: None,
+            fill_color: Some(
+                engine_view
+                    .pens_config
+                    .tools_config
+                    .fill_tool_config
+                    .fill_color,
+            ),
+            ..Default::default()
+        });
+        let key = engine_view.store.insert_stroke(
+            Stroke::ShapeStroke(ShapeStroke::new(Shape::Polygon(region), style)),
+            Some(StrokeLayer::Highlighter),
+        );
+        engine_view.store.regenerate_rendering_for_stroke(
+            key,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+        Ok(Some(key))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RedactionTool {
+    /// The corners of the redaction that is currently dragged, in document coordinates.
+    corners: Option<(na::Vector2<f64>, na::Vector2<f64>)>,
+}
+
+impl RedactionTool {
+    const OUTLINE_WIDTH: f64 = 1.5;
+    const OUTLINE_COLOR: piet::Color = color::GNOME_REDS[3];
+    const FILL_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(160);
+
+    /// Creates the redaction from the dragged corners. Returns None if it would be too small.
+    fn create_redaction(&self) -> Option<RedactionStroke> {
+        let (first, second) = self.corners?;
+        let size = (second - first).abs();
+        if size[0] < RedactionStroke::SIZE_MIN || size[1] < RedactionStroke::SIZE_MIN {
+            return None;
+        }
+        Some(RedactionStroke::new(Rectangle::from_corners(first, second)))
+    }
+}
+
+impl DrawableOnDoc for RedactionTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        let (first, second) = self.corners?;
+        Some(
+            Aabb::new_positive(first.into(), second.into())
+                .loosened(Self::OUTLINE_WIDTH / engine_view.camera.total_zoom()),
+        )
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        let Some((first, second)) = self.corners else {
+            return Ok(());
+        };
+        let rect = kurbo::Rect::from_points(first.to_kurbo_point(), second.to_kurbo_point());
+        cx.fill(rect, &Self::FILL_COLOR);
+        cx.stroke(
+            rect,
+            &Self::OUTLINE_COLOR,
+            Self::OUTLINE_WIDTH / engine_view.camera.total_zoom(),
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConnectorTool {
+    /// The connector that is currently dragged.
+    connector: Option<ConnectorStroke>,
+}
+
+impl ConnectorTool {
+    const ATTACHED_END_RADIUS: f64 = 4.0;
+    const ATTACHED_END_COLOR: piet::Color = color::GNOME_BLUES[3];
+    /// Connectors shorter than this are not created.
+    const LENGTH_MIN: f64 = 4.0;
+
+    /// The connector end at the position. It is attached to the nearest anchor of a stroke within the snap distance.
+    fn connector_end(pos: na::Vector2<f64>, engine_view: &EngineViewMut) -> ConnectorEnd {
+        let snap_dist = ConnectorStroke::ANCHOR_SNAP_DIST / engine_view.camera.total_zoom();
+        engine_view
+            .store
+            .stroke_keys_as_rendered_intersecting_bounds(Aabb::from_half_extents(
+                pos.into(),
+                na::Vector2::repeat(snap_dist),
+            ))
+            .into_iter()
+            .filter_map(|key| Some((key, engine_view.store.get_stroke_ref(key)?)))
+            .filter(|(_, stroke)| !matches!(stroke, Stroke::ConnectorStroke(_)))
+            .flat_map(|(key, stroke)| {
+                ConnectorStroke::anchors(stroke.bounds())
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(anchor, anchor_pos)| {
+                        (ConnectorAttachment { key, anchor }, anchor_pos)
+                    })
+            })
+            .map(|(attachment, anchor_pos)| {
+                (attachment, anchor_pos, (anchor_pos - pos).magnitude())
+            })
+            .filter(|(_, _, dist)| *dist <= snap_dist)
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+            .map(|(attachment, anchor_pos, _)| ConnectorEnd::new(anchor_pos, Some(attachment)))
+            .unwrap_or_else(|| ConnectorEnd::new(engine_view.document.snap_position(pos), None))
+    }
+
+    fn start(&mut self, pos: na::Vector2<f64>, engine_view: &EngineViewMut) {
+        let config: &ConnectorToolConfig =
+            &engine_view.pens_config.tools_config.connector_tool_config;
+        let start = Self::connector_end(pos, engine_view);
+        self.connector = Some(ConnectorStroke::new(
+            start,
+            start,
+            config.routing,
+            config.arrow,
+            config.color,
+            config.width,
+        ));
+    }
+
+    /// Takes the dragged connector. Returns None if it would be too short.
+    fn finish(&mut self) -> Option<ConnectorStroke> {
+        self.connector.take().filter(|connector| {
+            (connector.end.pos - connector.start.pos).magnitude() >= Self::LENGTH_MIN
+        })
+    }
+}
+
+impl DrawableOnDoc for ConnectorTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        let connector = self.connector.as_ref()?;
+        Some(
+            connector
+                .bounds()
+                .loosened(Self::ATTACHED_END_RADIUS / engine_view.camera.total_zoom()),
+        )
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        let Some(connector) = self.connector.as_ref() else {
+            return Ok(());
+        };
+        connector.draw(cx, engine_view.camera.image_scale())?;
+        // Mark the ends that are attached to a stroke
+        for end in [connector.start, connector.end] {
+            if end.attachment.is_some() {
+                cx.fill(
+                    kurbo::Circle::new(
+                        end.pos.to_kurbo_point(),
+                        Self::ATTACHED_END_RADIUS / engine_view.camera.total_zoom(),
+                    ),
+                    &Self::ATTACHED_END_COLOR,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PerspectiveTool {
+    /// The index of the vanishing point that is currently dragged.
+    grabbed: Option<usize>,
+}
+
 #[derive(Debug, Clone, Copy)]
 enum ToolsState {
     Idle,
@@ -274,6 +621,10 @@ pub struct Tools {
     pub verticalspace_tool: VerticalSpaceTool,
     pub offsetcamera_tool: OffsetCameraTool,
     pub zoom_tool: ZoomTool,
+    pub measure_tool: MeasureTool,
+    pub perspective_tool: PerspectiveTool,
+    pub redaction_tool: RedactionTool,
+    pub connector_tool: ConnectorTool,
     state: ToolsState,
 }
 
@@ -283,7 +634,11 @@ impl PenBehaviour for Tools {
     }
 
     fn deinit(&mut self) -> WidgetFlags {
-        WidgetFlags::default()
+        let mut widget_flags = WidgetFlags::default();
+        if self.measure_tool.segment.take().is_some() {
+            widget_flags.redraw = true;
+        }
+        widget_flags
     }
 
     fn style(&self) -> PenStyle {
@@ -366,6 +721,66 @@ impl PenBehaviour for Tools {
                             .transform_point(&element.pos.into())
                             .coords;
                     }
+                    ToolStyle::Measure => {
+                        let start = engine_view.document.snap_position(element.pos);
+                        self.measure_tool.segment = Some((start, start));
+                    }
+                    ToolStyle::Stamp => {
+                        let stamp_tool_config =
+                            &engine_view.pens_config.tools_config.stamp_tool_config;
+                        if !stamp_tool_config.svg_data.is_empty() {
+                            match StampTool::create_stamp(
+                                &stamp_tool_config.svg_data,
+                                stamp_tool_config.size,
+                                engine_view.document.snap_position(element.pos),
+                            ) {
+                                Ok(stamp) => {
+                                    let key = engine_view
+                                        .store
+                                        .insert_stroke(Stroke::VectorImage(stamp), None);
+                                    engine_view.store.regenerate_rendering_for_stroke(
+                                        key,
+                                        engine_view.camera.viewport(),
+                                        engine_view.camera.image_scale(),
+                                    );
+                                    widget_flags |= engine_view.store.record(Instant::now());
+                                    widget_flags.store_modified = true;
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "Creating stamp `{}` failed, Err: {e:?}",
+                                        stamp_tool_config.stamp_name
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    ToolStyle::Fill => match FillTool::create_fill(element.pos, engine_view) {
+                        Ok(Some(_)) => {
+                            widget_flags |= engine_view.store.record(Instant::now());
+                            widget_flags.store_modified = true;
+                        }
+                        Ok(None) => {
+                            debug!("Fill position is not enclosed by strokes inside the viewport");
+                        }
+                        Err(e) => {
+                            error!("Creating fill failed, Err: {e:?}");
+                        }
+                    },
+                    ToolStyle::Redaction => {
+                        let start = engine_view.document.snap_position(element.pos);
+                        self.redaction_tool.corners = Some((start, start));
+                    }
+                    ToolStyle::Connector => {
+                        self.connector_tool.start(element.pos, engine_view);
+                    }
+                    ToolStyle::Perspective => {
+                        self.perspective_tool.grabbed =
+                            engine_view.document.perspective.nearest_vanishing_point(
+                                element.pos,
+                                Perspective::GRAB_DIST / engine_view.camera.total_zoom(),
+                            );
+                    }
                 }
                 widget_flags |= engine_view
                     .document
@@ -427,7 +842,7 @@ impl PenBehaviour for Tools {
                         );
                     }
                     ToolStyle::OffsetCamera => {
-                        let offset = engine_view
+                        let surface_offset = engine_view
                             .camera
                             .transform()
                             .transform_point(&element.pos.into())
@@ -437,6 +852,7 @@ impl PenBehaviour for Tools {
                                 .transform()
                                 .transform_point(&self.offsetcamera_tool.start.into())
                                 .coords;
+                        let offset = engine_view.camera.unrotate_surface_vec(surface_offset);
 
                         widget_flags |= engine_view
                             .camera
@@ -468,11 +884,13 @@ impl PenBehaviour for Tools {
 
                             // Translate the camera view so that the start_surface_coord has the same surface position
                             // as before the zoom occurred
-                            let new_camera_offset = (((camera_offset
-                                + self.zoom_tool.start_surface_coord)
+                            let start_surface_coord = engine_view
+                                .camera
+                                .unrotate_surface_pos(self.zoom_tool.start_surface_coord);
+                            let new_camera_offset = (((camera_offset + start_surface_coord)
                                 / total_zoom_old)
                                 * new_zoom)
-                                - self.zoom_tool.start_surface_coord;
+                                - start_surface_coord;
                             widget_flags |= engine_view
                                 .camera
                                 .set_offset(new_camera_offset, engine_view.document);
@@ -483,6 +901,34 @@ impl PenBehaviour for Tools {
                         }
                         self.zoom_tool.current_surface_coord = new_surface_coord;
                     }
+                    ToolStyle::Measure => {
+                        if let Some((_, end)) = self.measure_tool.segment.as_mut() {
+                            *end = engine_view.document.snap_position(element.pos);
+                        }
+                    }
+                    ToolStyle::Redaction => {
+                        if let Some((_, second)) = self.redaction_tool.corners.as_mut() {
+                            *second = engine_view.document.snap_position(element.pos);
+                            widget_flags.redraw = true;
+                        }
+                    }
+                    ToolStyle::Connector => {
+                        let end = ConnectorTool::connector_end(element.pos, engine_view);
+                        if let Some(connector) = self.connector_tool.connector.as_mut() {
+                            connector.end = end;
+                            widget_flags.redraw = true;
+                        }
+                    }
+                    // A stamp or a fill is placed once per click
+                    ToolStyle::Stamp | ToolStyle::Fill => {}
+                    ToolStyle::Perspective => {
+                        if let Some(i) = self.perspective_tool.grabbed {
+                            let pos = engine_view.document.snap_position(element.pos);
+                            engine_view.document.perspective.set_vanishing_point(i, pos);
+                            widget_flags.redraw = true;
+                            widget_flags.store_modified = true;
+                        }
+                    }
                 }
 
                 EventResult {
@@ -501,7 +947,40 @@ impl PenBehaviour for Tools {
                         widget_flags |= engine_view.store.record(Instant::now());
                         widget_flags.store_modified = true;
                     }
-                    ToolStyle::OffsetCamera | ToolStyle::Zoom => {}
+                    ToolStyle::Redaction => {
+                        if let Some(redaction) = self.redaction_tool.create_redaction() {
+                            let key = engine_view
+                                .store
+                                .insert_stroke(Stroke::RedactionStroke(redaction), None);
+                            engine_view.store.regenerate_rendering_for_stroke(
+                                key,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                            widget_flags |= engine_view.store.record(Instant::now());
+                            widget_flags.store_modified = true;
+                        }
+                    }
+                    ToolStyle::Connector => {
+                        if let Some(connector) = self.connector_tool.finish() {
+                            let key = engine_view
+                                .store
+                                .insert_stroke(Stroke::ConnectorStroke(connector), None);
+                            engine_view.store.regenerate_rendering_for_stroke(
+                                key,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                            widget_flags |= engine_view.store.record(Instant::now());
+                            widget_flags.store_modified = true;
+                        }
+                    }
+                    ToolStyle::OffsetCamera
+                    | ToolStyle::Zoom
+                    | ToolStyle::Measure
+                    | ToolStyle::Stamp
+                    | ToolStyle::Fill
+                    | ToolStyle::Perspective => {}
                 }
 
                 widget_flags |= engine_view
@@ -569,8 +1048,16 @@ impl DrawableOnDoc for Tools {
                 ToolStyle::VerticalSpace => self.verticalspace_tool.bounds_on_doc(engine_view),
                 ToolStyle::OffsetCamera => self.offsetcamera_tool.bounds_on_doc(engine_view),
                 ToolStyle::Zoom => self.zoom_tool.bounds_on_doc(engine_view),
+                ToolStyle::Measure => self.measure_tool.bounds_on_doc(engine_view),
+                ToolStyle::Redaction => self.redaction_tool.bounds_on_doc(engine_view),
+                ToolStyle::Connector => self.connector_tool.bounds_on_doc(engine_view),
+                // The perspective guides are drawn by the engine
+                ToolStyle::Stamp | ToolStyle::Fill | ToolStyle::Perspective => None,
+            },
+            ToolsState::Idle => match engine_view.pens_config.tools_config.style {
+                ToolStyle::Measure => self.measure_tool.bounds_on_doc(engine_view),
+                _ => None,
             },
-            ToolsState::Idle => None,
         }
     }
 
@@ -591,6 +1078,16 @@ impl DrawableOnDoc for Tools {
             ToolStyle::Zoom => {
                 self.zoom_tool.draw_on_doc(cx, engine_view)?;
             }
+            ToolStyle::Measure => {
+                self.measure_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Redaction => {
+                self.redaction_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Connector => {
+                self.connector_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Stamp | ToolStyle::Fill | ToolStyle::Perspective => {}
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -612,6 +1109,17 @@ impl Tools {
                 self.zoom_tool.start_surface_coord = na::Vector2::zeros();
                 self.zoom_tool.current_surface_coord = na::Vector2::zeros();
             }
+            // The measured segment is kept until the next measurement
+            ToolStyle::Measure | ToolStyle::Stamp | ToolStyle::Fill => {}
+            ToolStyle::Perspective => {
+                self.perspective_tool.grabbed = None;
+            }
+            ToolStyle::Redaction => {
+                self.redaction_tool.corners = None;
+            }
+            ToolStyle::Connector => {
+                self.connector_tool.connector = None;
+            }
         }
         self.state = ToolsState::Idle;
     }
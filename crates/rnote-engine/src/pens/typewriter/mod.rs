@@ -534,6 +534,25 @@ fn update_cursors_for_textstroke(
     }
 }
 
+// Add text attributes that are relative to the given offset to the textstroke.
+fn extend_translated_attrs(
+    textstroke: &mut TextStroke,
+    offset: usize,
+    ranged_text_attributes: Vec<RangedTextAttribute>,
+) {
+    textstroke
+        .text_style
+        .ranged_text_attributes
+        .extend(
+            ranged_text_attributes
+                .into_iter()
+                .map(|attr| RangedTextAttribute {
+                    range: attr.range.start + offset..attr.range.end + offset,
+                    attribute: attr.attribute,
+                }),
+        );
+}
+
 impl Typewriter {
     // The size of the translate node, located in the upper left corner.
     const TRANSLATE_NODE_SIZE: na::Vector2<f64> = na::vector![18.0, 18.0];
@@ -556,6 +575,19 @@ impl Typewriter {
         self.cursor_visible = !self.cursor_visible;
     }
 
+    /// Whether a textstroke is currently being modified.
+    pub(crate) fn is_modifying(&self) -> bool {
+        matches!(self.state, TypewriterState::Modifying { .. })
+    }
+
+    /// The key of the textstroke that is currently being modified.
+    pub(crate) fn modifying_stroke_key(&self) -> Option<StrokeKey> {
+        match &self.state {
+            TypewriterState::Modifying { stroke_key, .. } => Some(*stroke_key),
+            _ => None,
+        }
+    }
+
     /// The range of the current selection, if available.
     pub(crate) fn selection_range(&self) -> Option<(Range<usize>, StrokeKey)> {
         if let TypewriterState::Modifying {
@@ -623,9 +655,11 @@ impl Typewriter {
     /// Insert text either at the current cursor position or, if the state is idle, in a new textstroke.
     ///
     /// Inserts at the given position, if supplied. Else at a default offset.
+    /// The ranges of the given text attributes are relative to the start of the inserted text.
     pub(crate) fn insert_text(
         &mut self,
         text: String,
+        ranged_text_attributes: Vec<RangedTextAttribute>,
         preferred_pos: Option<na::Vector2<f64>>,
         engine_view: &mut EngineViewMut,
     ) -> WidgetFlags {
@@ -639,7 +673,7 @@ impl Typewriter {
         match &mut self.state {
             TypewriterState::Idle => {
                 let text_len = text.len();
-                text_style.ranged_text_attributes.clear();
+                text_style.ranged_text_attributes = ranged_text_attributes;
                 text_style.set_max_width(Some(text_width));
                 let textstroke = TextStroke::new(text, pos, text_style);
                 let cursor = GraphemeCursor::new(text_len, textstroke.text.len(), true);
@@ -666,7 +700,7 @@ impl Typewriter {
             }
             TypewriterState::Start(pos) => {
                 let text_len = text.len();
-                text_style.ranged_text_attributes.clear();
+                text_style.ranged_text_attributes = ranged_text_attributes;
                 text_style.set_max_width(Some(text_width));
                 let textstroke = TextStroke::new(text, *pos, text_style);
                 let cursor = GraphemeCursor::new(text_len, textstroke.text.len(), true);
@@ -708,6 +742,11 @@ impl Typewriter {
                             selection_cursor,
                             text.as_str(),
                         );
+                        extend_translated_attrs(
+                            textstroke,
+                            cursor.cur_cursor() - text.len(),
+                            ranged_text_attributes,
+                        );
                         engine_view.store.update_geometry_for_stroke(*stroke_key);
                         engine_view.store.regenerate_rendering_for_stroke(
                             *stroke_key,
@@ -734,6 +773,11 @@ impl Typewriter {
                         engine_view.store.get_stroke_mut(*stroke_key)
                     {
                         textstroke.insert_text_after_cursor(text.as_str(), cursor);
+                        extend_translated_attrs(
+                            textstroke,
+                            cursor.cur_cursor() - text.len(),
+                            ranged_text_attributes,
+                        );
                         engine_view.store.update_geometry_for_stroke(*stroke_key);
                         engine_view.store.regenerate_rendering_for_stroke(
                             *stroke_key,
@@ -906,6 +950,94 @@ impl Typewriter {
         widget_flags
     }
 
+    /// Replace the text in the given range of the textstroke that is currently being modified.
+    ///
+    /// The cursor is moved to the end of the inserted text.
+    pub(crate) fn replace_text_range_in_modifying_stroke(
+        &mut self,
+        range: Range<usize>,
+        text: &str,
+        engine_view: &mut EngineViewMut,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if let TypewriterState::Modifying {
+            modify_state,
+            stroke_key,
+            cursor,
+            ..
+        } = &mut self.state
+        {
+            if let Some(Stroke::TextStroke(textstroke)) =
+                engine_view.store.get_stroke_mut(*stroke_key)
+            {
+                if range.end > textstroke.text.len() {
+                    return widget_flags;
+                }
+                let mut selection_cursor =
+                    GraphemeCursor::new(range.end, textstroke.text.len(), true);
+                *cursor = GraphemeCursor::new(range.start, textstroke.text.len(), true);
+                textstroke.replace_text_between_selection_cursors(
+                    cursor,
+                    &mut selection_cursor,
+                    text,
+                );
+                *modify_state = ModifyState::Up;
+                engine_view.store.update_geometry_for_stroke(*stroke_key);
+                engine_view.store.regenerate_rendering_for_stroke(
+                    *stroke_key,
+                    engine_view.camera.viewport(),
+                    engine_view.camera.image_scale(),
+                );
+
+                widget_flags |= engine_view.store.record(Instant::now());
+                widget_flags.redraw = true;
+                widget_flags.store_modified = true;
+            }
+        }
+
+        self.reset_blink();
+        widget_flags
+    }
+
+    /// Toggle the bullet list for the paragraphs in the current selection, or the paragraph at the cursor.
+    pub(crate) fn toggle_bullet_list(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if let TypewriterState::Modifying {
+            modify_state,
+            stroke_key,
+            cursor,
+            ..
+        } = &mut self.state
+        {
+            if let Some(Stroke::TextStroke(textstroke)) =
+                engine_view.store.get_stroke_mut(*stroke_key)
+            {
+                let selection_cursor = match modify_state {
+                    ModifyState::Selecting {
+                        selection_cursor, ..
+                    } => Some(selection_cursor),
+                    _ => None,
+                };
+                textstroke.toggle_bullet_list(cursor, selection_cursor);
+                engine_view.store.update_geometry_for_stroke(*stroke_key);
+                engine_view.store.regenerate_rendering_for_stroke(
+                    *stroke_key,
+                    engine_view.camera.viewport(),
+                    engine_view.camera.image_scale(),
+                );
+
+                widget_flags |= engine_view.store.record(Instant::now());
+                widget_flags.redraw = true;
+                widget_flags.store_modified = true;
+            }
+        }
+
+        self.reset_blink();
+        widget_flags
+    }
+
     /// Resets the blink
     fn reset_blink(&mut self) {
         if let Some(handle) = &mut self.blink_task_handle {
@@ -167,6 +167,7 @@ impl Typewriter {
                                 // If we click outside, reset to idle
                                 self.state = TypewriterState::Idle;
                                 progress = PenProgress::Finished;
+                                widget_flags.refresh_ui = true;
                             }
                         }
 
@@ -223,6 +224,7 @@ impl Typewriter {
                                 // If we click outside, reset to idle
                                 self.state = TypewriterState::Idle;
                                 progress = PenProgress::Finished;
+                                widget_flags.refresh_ui = true;
                             }
                         }
 
@@ -1208,7 +1210,7 @@ impl Typewriter {
         _now: Instant,
         _engine_view: &mut EngineViewMut,
     ) -> (EventResult<PenProgress>, WidgetFlags) {
-        let widget_flags = WidgetFlags::default();
+        let mut widget_flags = WidgetFlags::default();
 
         let event_result = match &mut self.state {
             TypewriterState::Idle => EventResult {
@@ -1218,6 +1220,7 @@ impl Typewriter {
             },
             _ => {
                 self.state = TypewriterState::Idle;
+                widget_flags.refresh_ui = true;
 
                 EventResult {
                     handled: true,
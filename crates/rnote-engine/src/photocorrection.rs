@@ -0,0 +1,190 @@
+//! Correcting photos of whiteboards and documents.
+//!
+//! The quadrilateral spanned by the four corners of the photographed surface is rectified into a rectangle
+//! with a perspective transform. Optionally the contrast is enhanced by stretching the luminance range.
+
+// Imports
+use image::Pixel;
+use std::io::Cursor;
+
+/// The fraction of the darkest and brightest pixels that are clipped when enhancing the contrast.
+const CONTRAST_CLIP: f64 = 0.01;
+
+/// The corners of the photographed surface, relative to the photo size in the range `[0.0, 1.0]`.
+///
+/// The order is top-left, top-right, bottom-right, bottom-left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhotoCorners(pub [na::Vector2<f64>; 4]);
+
+impl Default for PhotoCorners {
+    fn default() -> Self {
+        Self([
+            na::vector![0.0, 0.0],
+            na::vector![1.0, 0.0],
+            na::vector![1.0, 1.0],
+            na::vector![0.0, 1.0],
+        ])
+    }
+}
+
+/// Corrects the perspective of the photo so that the area inside the corners becomes rectangular.
+///
+/// Returns the corrected photo encoded as Png.
+pub fn correct_photo(
+    bytes: &[u8],
+    corners: PhotoCorners,
+    enhance_contrast: bool,
+) -> anyhow::Result<Vec<u8>> {
+    let source = image::load_from_memory(bytes)?.into_rgba8();
+    let source_size = na::vector![f64::from(source.width()), f64::from(source.height())];
+    let corners = corners.0.map(|corner| {
+        corner
+            .map(|c| c.clamp(0.0, 1.0))
+            .component_mul(&source_size)
+    });
+
+    // The size of the rectified image is the longer one of the opposite edges
+    let width = (corners[1] - corners[0])
+        .norm()
+        .max((corners[2] - corners[3]).norm())
+        .round()
+        .max(1.0);
+    let height = (corners[3] - corners[0])
+        .norm()
+        .max((corners[2] - corners[1]).norm())
+        .round()
+        .max(1.0);
+    let homography =
+        homography_from_rect(na::vector![width, height], &corners).ok_or_else(|| {
+            anyhow::anyhow!("Corners of the photo do not span a valid quadrilateral.")
+        })?;
+
+    let mut corrected = image::RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+        let pos = homography
+            .transform_point(&na::point![f64::from(x) + 0.5, f64::from(y) + 0.5])
+            .coords;
+        sample_bilinear(&source, pos)
+    });
+    if enhance_contrast {
+        stretch_contrast(&mut corrected);
+    }
+
+    let mut png_data = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(corrected).write_to(&mut png_data, image::ImageFormat::Png)?;
+    Ok(png_data.into_inner())
+}
+
+/// Computes the projective transform that maps the rectangle from the origin to `size` onto the corners.
+///
+/// Returns `None` when the corners are degenerate.
+fn homography_from_rect(
+    size: na::Vector2<f64>,
+    corners: &[na::Vector2<f64>; 4],
+) -> Option<na::Projective2<f64>> {
+    let rect_corners = [
+        na::vector![0.0, 0.0],
+        na::vector![size[0], 0.0],
+        na::vector![size[0], size[1]],
+        na::vector![0.0, size[1]],
+    ];
+    let mut a = na::SMatrix::<f64, 8, 8>::zeros();
+    let mut b = na::SVector::<f64, 8>::zeros();
+    for (i, (from, to)) in rect_corners.iter().zip(corners.iter()).enumerate() {
+        let (u, v) = (from[0], from[1]);
+        let (x, y) = (to[0], to[1]);
+        a.set_row(
+            2 * i,
+            &na::SMatrix::<f64, 1, 8>::from_row_slice(&[u, v, 1.0, 0.0, 0.0, 0.0, -u * x, -v * x]),
+        );
+        a.set_row(
+            2 * i + 1,
+            &na::SMatrix::<f64, 1, 8>::from_row_slice(&[0.0, 0.0, 0.0, u, v, 1.0, -u * y, -v * y]),
+        );
+        b[2 * i] = x;
+        b[2 * i + 1] = y;
+    }
+    let h = a.lu().solve(&b)?;
+    let matrix = na::Matrix3::new(h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7], 1.0);
+    if !matrix.iter().all(|v| v.is_finite()) || matrix.try_inverse().is_none() {
+        return None;
+    }
+    Some(na::Projective2::from_matrix_unchecked(matrix))
+}
+
+/// Samples the image at the position with bilinear interpolation.
+///
+/// Positions outside of the image are transparent.
+fn sample_bilinear(image: &image::RgbaImage, pos: na::Vector2<f64>) -> image::Rgba<u8> {
+    let (width, height) = image.dimensions();
+    let x = pos[0] - 0.5;
+    let y = pos[1] - 0.5;
+    if !x.is_finite()
+        || !y.is_finite()
+        || x < -1.0
+        || y < -1.0
+        || x >= f64::from(width)
+        || y >= f64::from(height)
+    {
+        return image::Rgba([0, 0, 0, 0]);
+    }
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let (tx, ty) = (x - x0, y - y0);
+    let pixel = |px: f64, py: f64| -> [f64; 4] {
+        let px = px.clamp(0.0, f64::from(width - 1)) as u32;
+        let py = py.clamp(0.0, f64::from(height - 1)) as u32;
+        image.get_pixel(px, py).0.map(f64::from)
+    };
+    let (p00, p10, p01, p11) = (
+        pixel(x0, y0),
+        pixel(x0 + 1.0, y0),
+        pixel(x0, y0 + 1.0),
+        pixel(x0 + 1.0, y0 + 1.0),
+    );
+    let mut sampled = [0_u8; 4];
+    for (i, s) in sampled.iter_mut().enumerate() {
+        let top = p00[i] * (1.0 - tx) + p10[i] * tx;
+        let bottom = p01[i] * (1.0 - tx) + p11[i] * tx;
+        *s = (top * (1.0 - ty) + bottom * ty).round().clamp(0.0, 255.0) as u8;
+    }
+    image::Rgba(sampled)
+}
+
+/// Stretches the luminance range of the image to the full range, clipping the darkest and brightest pixels.
+fn stretch_contrast(image: &mut image::RgbaImage) {
+    let mut histogram = [0_usize; 256];
+    for pixel in image.pixels() {
+        histogram[usize::from(pixel.to_luma().0[0])] += 1;
+    }
+    let total = image.width() as usize * image.height() as usize;
+    let clip = (total as f64 * CONTRAST_CLIP) as usize;
+    let lower_bound = histogram
+        .iter()
+        .scan(0, |count, &n| {
+            *count += n;
+            Some(*count)
+        })
+        .position(|count| count > clip)
+        .unwrap_or(0);
+    let upper_bound = 255
+        - histogram
+            .iter()
+            .rev()
+            .scan(0, |count, &n| {
+                *count += n;
+                Some(*count)
+            })
+            .position(|count| count > clip)
+            .unwrap_or(0);
+    if upper_bound <= lower_bound {
+        return;
+    }
+    let (lower, upper) = (lower_bound as f64, upper_bound as f64);
+    for pixel in image.pixels_mut() {
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = ((f64::from(*channel) - lower) / (upper - lower) * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
+    }
+}
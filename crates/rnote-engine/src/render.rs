@@ -204,6 +204,22 @@ impl Image {
         }
     }
 
+    /// Multiply the image by the given opacity.
+    pub fn multiply_opacity(&mut self, opacity: f64) {
+        match self.memory_format {
+            // The color channels are premultiplied, so all channels are multiplied.
+            ImageMemoryFormat::R8g8b8a8Premultiplied => {
+                let opacity = opacity.clamp(0.0, 1.0);
+                let data = self
+                    .data
+                    .iter()
+                    .map(|c| (f64::from(*c) * opacity).round() as u8)
+                    .collect::<Vec<u8>>();
+                self.data = glib::Bytes::from_owned(data);
+            }
+        }
+    }
+
     pub fn try_from_encoded_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
         let reader = ImageReader::new(io::Cursor::new(bytes)).with_guessed_format()?;
         Ok(Image::from(reader.decode()?))
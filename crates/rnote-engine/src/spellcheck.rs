@@ -0,0 +1,134 @@
+// Imports
+use std::cell::RefCell;
+use std::ops::Range;
+#[cfg(feature = "spellcheck")]
+use tracing::error;
+#[cfg(feature = "spellcheck")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Spellchecking for text, backed by the dictionaries available through enchant.
+///
+/// Without the `spellcheck` feature no dictionaries are available and spellchecking stays disabled.
+pub struct Spellcheck {
+    #[cfg(feature = "spellcheck")]
+    broker: enchant::Broker,
+    #[cfg(feature = "spellcheck")]
+    dict: Option<enchant::Dict>,
+    /// The last checked text and the ranges of its misspelled words.
+    cache: RefCell<Option<(String, Vec<Range<usize>>)>>,
+}
+
+impl Default for Spellcheck {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "spellcheck")]
+            broker: enchant::Broker::new(),
+            #[cfg(feature = "spellcheck")]
+            dict: None,
+            cache: RefCell::new(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for Spellcheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Spellcheck");
+        #[cfg(feature = "spellcheck")]
+        debug
+            .field("broker", &"{.. no debug impl ..}")
+            .field("dict", &"{.. no debug impl ..}");
+        debug.field("cache", &self.cache).finish()
+    }
+}
+
+impl Spellcheck {
+    /// The languages for which dictionaries are installed.
+    #[cfg(feature = "spellcheck")]
+    pub fn available_languages(&mut self) -> Vec<String> {
+        self.broker
+            .list_dicts()
+            .into_iter()
+            .map(|dict| dict.lang)
+            .collect()
+    }
+
+    /// The languages for which dictionaries are installed.
+    #[cfg(not(feature = "spellcheck"))]
+    pub fn available_languages(&mut self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Load the dictionary for the given language. Disables spellchecking when `None`.
+    #[cfg(feature = "spellcheck")]
+    pub fn set_language(&mut self, language: Option<&str>) {
+        self.cache.replace(None);
+        self.dict = language.and_then(|language| {
+            self.broker
+                .request_dict(language)
+                .map_err(|e| {
+                    error!("Requesting spellcheck dictionary for language '{language}' failed, Err: {e:?}")
+                })
+                .ok()
+        });
+    }
+
+    /// Load the dictionary for the given language. Disables spellchecking when `None`.
+    #[cfg(not(feature = "spellcheck"))]
+    pub fn set_language(&mut self, _language: Option<&str>) {
+        self.cache.replace(None);
+    }
+
+    #[cfg(feature = "spellcheck")]
+    pub fn enabled(&self) -> bool {
+        self.dict.is_some()
+    }
+
+    #[cfg(not(feature = "spellcheck"))]
+    pub fn enabled(&self) -> bool {
+        false
+    }
+
+    /// The byte ranges of the misspelled words in the text.
+    #[cfg(feature = "spellcheck")]
+    pub fn misspelled_words(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(dict) = &self.dict else {
+            return vec![];
+        };
+        if let Some((cached_text, ranges)) = &*self.cache.borrow() {
+            if cached_text == text {
+                return ranges.clone();
+            }
+        }
+
+        let ranges = text
+            .unicode_word_indices()
+            // don't flag numbers
+            .filter(|(_, word)| word.chars().any(char::is_alphabetic))
+            .filter(|(_, word)| !dict.check(word).unwrap_or(true))
+            .map(|(i, word)| i..i + word.len())
+            .collect::<Vec<Range<usize>>>();
+        self.cache.replace(Some((text.to_string(), ranges.clone())));
+        ranges
+    }
+
+    /// The byte ranges of the misspelled words in the text.
+    #[cfg(not(feature = "spellcheck"))]
+    pub fn misspelled_words(&self, _text: &str) -> Vec<Range<usize>> {
+        vec![]
+    }
+
+    /// Replacement suggestions for a misspelled word.
+    #[cfg(feature = "spellcheck")]
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        self.dict
+            .as_ref()
+            .map(|dict| dict.suggest(word))
+            .unwrap_or_default()
+    }
+
+    /// Replacement suggestions for a misspelled word.
+    #[cfg(not(feature = "spellcheck"))]
+    pub fn suggestions(&self, _word: &str) -> Vec<String> {
+        vec![]
+    }
+}
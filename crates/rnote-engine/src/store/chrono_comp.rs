@@ -4,6 +4,7 @@ use p2d::bounding_volume::Aabb;
 use rayon::slice::ParallelSliceMut;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq)]
@@ -68,6 +69,9 @@ pub struct ChronoComponent {
     t: u32,
     #[serde(rename = "layer")]
     pub layer: StrokeLayer,
+    /// Locked strokes are ignored by the eraser and can't be selected.
+    #[serde(rename = "locked")]
+    pub locked: bool,
 }
 
 impl Default for ChronoComponent {
@@ -75,13 +79,18 @@ impl Default for ChronoComponent {
         Self {
             t: 0,
             layer: StrokeLayer::default(),
+            locked: false,
         }
     }
 }
 
 impl ChronoComponent {
     pub(crate) fn new(t: u32, layer: StrokeLayer) -> Self {
-        Self { t, layer }
+        Self {
+            t,
+            layer,
+            locked: false,
+        }
     }
 }
 
@@ -94,6 +103,73 @@ impl StrokeStore {
         }
     }
 
+    /// The stroke that was added or brought to the front most recently, ignoring trashed strokes.
+    pub(crate) fn latest_key(&self) -> Option<StrokeKey> {
+        self.chrono_components
+            .iter()
+            .filter(|(key, _)| !self.trashed(*key).unwrap_or(true))
+            .max_by_key(|(_, chrono_comp)| chrono_comp.t)
+            .map(|(key, _)| key)
+    }
+
+    /// Moves the strokes to the back of their layers, keeping their relative order.
+    ///
+    /// Renumbers the chronological order of all strokes, since it can't go below zero.
+    pub(crate) fn update_chrono_to_first(&mut self, keys: &[StrokeKey]) {
+        let keys = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        let (moved, rest): (Vec<StrokeKey>, Vec<StrokeKey>) = self
+            .keys_sorted_chrono()
+            .into_iter()
+            .partition(|key| keys.contains(key));
+        let chrono_components = Arc::make_mut(&mut self.chrono_components);
+        self.chrono_counter = 0;
+        for key in moved.into_iter().chain(rest) {
+            if let Some(chrono_comp) = chrono_components.get_mut(key) {
+                self.chrono_counter += 1;
+                Arc::make_mut(chrono_comp).t = self.chrono_counter;
+            }
+        }
+    }
+
+    /// The layer of the stroke.
+    pub(crate) fn stroke_layer(&self, key: StrokeKey) -> Option<StrokeLayer> {
+        self.chrono_components
+            .get(key)
+            .map(|chrono_comp| chrono_comp.layer)
+    }
+
+    pub(crate) fn locked(&self, key: StrokeKey) -> Option<bool> {
+        self.chrono_components
+            .get(key)
+            .map(|chrono_comp| chrono_comp.locked)
+    }
+
+    /// Lock or unlock the strokes. Their chronological order is kept.
+    pub(crate) fn set_locked_keys(&mut self, keys: &[StrokeKey], locked: bool) {
+        keys.iter().for_each(|&key| {
+            if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                chrono_comp.locked = locked;
+            }
+        });
+    }
+
+    /// Filters out the locked strokes, keeping the order of the keys.
+    pub(crate) fn filter_unlocked_keys(&self, keys: Vec<StrokeKey>) -> Vec<StrokeKey> {
+        keys.into_iter()
+            .filter(|&key| !self.locked(key).unwrap_or(false))
+            .collect()
+    }
+
+    pub(crate) fn locked_keys_unordered(&self) -> Vec<StrokeKey> {
+        self.stroke_components
+            .keys()
+            .filter(|&key| self.locked(key).unwrap_or(false))
+            .collect()
+    }
+
     /// Returns the keys in chronological order, as in first: gets drawn first, last: gets drawn last.
     pub(crate) fn keys_sorted_chrono(&self) -> Vec<StrokeKey> {
         let chrono_components = &self.chrono_components;
@@ -1,27 +1,31 @@
 // Modules
 pub mod chrono_comp;
 pub mod keytree;
+pub mod pending;
 pub mod render_comp;
 pub mod selection_comp;
 pub mod stroke_comp;
 pub mod trash_comp;
+mod unrecorded;
 
 // Re-exports
 pub use chrono_comp::ChronoComponent;
 use keytree::KeyTree;
+pub use pending::PendingStroke;
 pub use render_comp::RenderComponent;
 pub use selection_comp::SelectionComponent;
 pub use trash_comp::TrashComponent;
 
 // Imports
 use self::chrono_comp::StrokeLayer;
+use crate::document::DocumentHistoryState;
 use crate::engine::EngineSnapshot;
 use crate::strokes::Stroke;
 use crate::WidgetFlags;
 use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
 use slotmap::{HopSlotMap, SecondaryMap};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::debug;
@@ -41,6 +45,9 @@ pub struct HistoryEntry {
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    /// The state of the document, set by the engine.
+    #[serde(skip)]
+    pub(crate) document_state: Option<Arc<DocumentHistoryState>>,
 }
 
 impl Default for HistoryEntry {
@@ -51,6 +58,7 @@ impl Default for HistoryEntry {
             chrono_components: Arc::new(SecondaryMap::new()),
 
             chrono_counter: 0,
+            document_state: None,
         }
     }
 }
@@ -86,6 +94,9 @@ pub struct StrokeStore {
     chrono_counter: u32,
     #[serde(skip)]
     render_components: SecondaryMap<StrokeKey, RenderComponent>,
+    /// The part of the document state that is kept in the history. Set by the engine when the document changed.
+    #[serde(skip)]
+    document_state: Option<Arc<DocumentHistoryState>>,
     #[serde(skip)]
     history: VecDeque<HistoryEntry>,
     /// The index of the current live document in the history stack.
@@ -96,6 +107,17 @@ pub struct StrokeStore {
     /// Needs to be updated with `update_with_key()` when strokes changed their geometry or position!
     #[serde(skip)]
     key_tree: KeyTree,
+    /// Strokes of a lazily loaded document that are not yet deserialized.
+    ///
+    /// Entries are taken out when the strokes get loaded.
+    #[serde(skip)]
+    pending_strokes: Vec<Option<PendingStroke>>,
+    /// Identifies the background tasks loading the current pending strokes.
+    #[serde(skip)]
+    pending_strokes_load_id: u32,
+    /// Maps the keys the loaded pending strokes had in the file to their keys in the store.
+    #[serde(skip)]
+    pending_strokes_keys: HashMap<StrokeKey, StrokeKey>,
 }
 
 impl Default for StrokeStore {
@@ -106,12 +128,16 @@ impl Default for StrokeStore {
             selection_components: Arc::new(SecondaryMap::new()),
             chrono_components: Arc::new(SecondaryMap::new()),
             render_components: SecondaryMap::new(),
+            document_state: None,
 
             // Start off with state in the history
             history: VecDeque::from(vec![HistoryEntry::default()]),
             live_index: 0,
 
             key_tree: KeyTree::default(),
+            pending_strokes: vec![],
+            pending_strokes_load_id: 0,
+            pending_strokes_keys: HashMap::new(),
 
             chrono_counter: 0,
         }
@@ -138,6 +164,7 @@ impl StrokeStore {
         self.rebuild_trash_components_slotmap();
         self.rebuild_render_components_slotmap();
         self.rebuild_rtree();
+        self.set_pending_strokes(snapshot.pending_strokes.clone());
         widget_flags |= self.clear_history(self.create_history_entry());
         widget_flags
     }
@@ -159,6 +186,11 @@ impl StrokeStore {
             && Arc::ptr_eq(&self.trash_components, &history_entry.trash_components)
             && Arc::ptr_eq(&self.chrono_components, &history_entry.chrono_components)
             && self.chrono_counter == history_entry.chrono_counter
+            && match (&self.document_state, &history_entry.document_state) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
     }
 
     /// Create a history entry from the current state.
@@ -168,6 +200,7 @@ impl StrokeStore {
             trash_components: Arc::clone(&self.trash_components),
             chrono_components: Arc::clone(&self.chrono_components),
             chrono_counter: self.chrono_counter,
+            document_state: self.document_state.clone(),
         }
     }
 
@@ -177,6 +210,7 @@ impl StrokeStore {
         self.trash_components = Arc::clone(&history_entry.trash_components);
         self.chrono_components = Arc::clone(&history_entry.chrono_components);
         self.chrono_counter = history_entry.chrono_counter;
+        self.document_state = history_entry.document_state;
 
         // Since we don't store the rtree in the history, we need to rebuild it.
         self.rebuild_rtree();
@@ -189,6 +223,16 @@ impl StrokeStore {
         self.set_rendering_dirty_for_strokes(&all_strokes);
     }
 
+    /// The document state of the current history state.
+    pub(crate) fn document_state(&self) -> Option<Arc<DocumentHistoryState>> {
+        self.document_state.clone()
+    }
+
+    /// Set the document state, which is then saved with the next recorded history entry.
+    pub(crate) fn set_document_state(&mut self, document_state: DocumentHistoryState) {
+        self.document_state = Some(Arc::new(document_state));
+    }
+
     /// Record the current state and save it in the history.
     pub(crate) fn record(&mut self, _now: Instant) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
@@ -365,6 +409,7 @@ impl StrokeStore {
 
         self.render_components.clear();
         self.key_tree.clear();
+        self.set_pending_strokes(vec![]);
 
         widget_flags
     }
@@ -0,0 +1,221 @@
+// Imports
+use super::{ChronoComponent, StrokeKey, StrokeStore};
+use crate::document::DocumentHistoryState;
+use crate::engine::{EngineTask, EngineTaskSender};
+use crate::strokes::{Content, Stroke};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+/// A stroke of a loaded document that is not yet deserialized.
+///
+/// Large documents are loaded lazily: only the strokes near the initial viewport are deserialized right away,
+/// the others are kept as raw values together with their bounds that were stored in the file.
+#[derive(Debug, Clone)]
+pub struct PendingStroke {
+    /// The key the stroke has in the file.
+    pub(crate) key: StrokeKey,
+    pub(crate) bounds: Aabb,
+    pub(crate) value: Arc<ijson::IValue>,
+    pub(crate) chrono: Arc<ChronoComponent>,
+}
+
+impl PendingStroke {
+    fn deserialize(&self) -> anyhow::Result<Stroke> {
+        ijson::from_value(&self.value).map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+/// The number of pending strokes that are deserialized in one background task.
+const LOAD_CHUNK_SIZE: usize = 256;
+
+impl StrokeStore {
+    /// Whether there are strokes that are not yet loaded.
+    pub fn has_pending_strokes(&self) -> bool {
+        self.pending_strokes.iter().any(|p| p.is_some())
+    }
+
+    /// The bounds of the strokes that are not yet loaded.
+    pub(crate) fn pending_strokes_bounds(&self) -> impl Iterator<Item = Aabb> + '_ {
+        self.pending_strokes
+            .iter()
+            .filter_map(|p| p.as_ref().map(|p| p.bounds))
+    }
+
+    /// Replace the pending strokes with the ones of a newly loaded document.
+    pub(crate) fn set_pending_strokes(&mut self, pending_strokes: Vec<PendingStroke>) {
+        self.pending_strokes = pending_strokes.into_iter().map(Some).collect();
+        // Results of background tasks that are still loading the strokes of the previous document are discarded
+        self.pending_strokes_load_id = self.pending_strokes_load_id.wrapping_add(1);
+        self.pending_strokes_keys.clear();
+    }
+
+    /// Maps the keys the loaded pending strokes have in the file to their keys in the store.
+    ///
+    /// Anything else referring to strokes of the file, like the links of the document, needs to be remapped with it.
+    pub(crate) fn pending_strokes_keys(&self) -> &HashMap<StrokeKey, StrokeKey> {
+        &self.pending_strokes_keys
+    }
+
+    /// Load the pending strokes that intersect the given bounds, or all of them when `None`.
+    ///
+    /// Returns the keys of the loaded strokes. They then need to update their rendering.
+    pub(crate) fn load_pending_strokes(&mut self, bounds: Option<Aabb>) -> Vec<StrokeKey> {
+        let strokes = self
+            .pending_strokes
+            .iter_mut()
+            .filter(|p| {
+                p.as_ref()
+                    .is_some_and(|p| bounds.map_or(true, |bounds| bounds.intersects(&p.bounds)))
+            })
+            .filter_map(|p| {
+                let pending = p.take()?;
+                match pending.deserialize() {
+                    Ok(stroke) => Some((pending.key, stroke, pending.chrono)),
+                    Err(e) => {
+                        warn!(
+                            "Dropped unreadable stroke while loading pending strokes, Err: {e:?}"
+                        );
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<(StrokeKey, Stroke, Arc<ChronoComponent>)>>();
+        self.insert_loaded_strokes(strokes)
+    }
+
+    /// Deserialize all pending strokes in background tasks, starting with the ones closest to the viewport.
+    ///
+    /// The loaded strokes are sent back with [EngineTask::InsertLoadedStrokes].
+    pub(crate) fn load_pending_strokes_in_background(
+        &self,
+        tasks_tx: EngineTaskSender,
+        viewport: Aabb,
+    ) {
+        let load_id = self.pending_strokes_load_id;
+        let viewport_center = viewport.center();
+        let mut pending = self
+            .pending_strokes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| Some((i, p.clone()?)))
+            .collect::<Vec<(usize, PendingStroke)>>();
+        if pending.is_empty() {
+            return;
+        }
+        pending.sort_unstable_by(|(_, a), (_, b)| {
+            na::distance_squared(&a.bounds.center(), &viewport_center)
+                .total_cmp(&na::distance_squared(&b.bounds.center(), &viewport_center))
+        });
+
+        rayon::spawn(move || {
+            for chunk in pending.chunks(LOAD_CHUNK_SIZE) {
+                let strokes = chunk
+                    .iter()
+                    .map(|(i, pending)| {
+                        let stroke = match pending.deserialize() {
+                            Ok(stroke) => Some(stroke),
+                            Err(e) => {
+                                error!("Deserializing pending stroke failed, Err: {e:?}");
+                                None
+                            }
+                        };
+                        (*i, stroke)
+                    })
+                    .collect();
+                tasks_tx.send(EngineTask::InsertLoadedStrokes { load_id, strokes });
+            }
+        });
+    }
+
+    /// Insert the strokes that were loaded in a background task.
+    ///
+    /// Strokes that were loaded in the meantime or that belong to a previously loaded document are skipped.
+    /// Unreadable strokes (`None`) are dropped.
+    pub(crate) fn insert_loaded_pending_strokes(
+        &mut self,
+        load_id: u32,
+        strokes: Vec<(usize, Option<Stroke>)>,
+    ) -> Vec<StrokeKey> {
+        if load_id != self.pending_strokes_load_id {
+            return vec![];
+        }
+        let strokes = strokes
+            .into_iter()
+            .filter_map(|(i, stroke)| {
+                let pending = self.pending_strokes.get_mut(i)?.take()?;
+                if stroke.is_none() {
+                    warn!("Dropped unreadable stroke while loading pending strokes.");
+                }
+                Some((pending.key, stroke?, pending.chrono))
+            })
+            .collect::<Vec<(StrokeKey, Stroke, Arc<ChronoComponent>)>>();
+        self.insert_loaded_strokes(strokes)
+    }
+
+    /// Insert loaded strokes, keeping their chronological order.
+    ///
+    /// Loading strokes is not an undoable change, so they are added to all history entries as well.
+    /// The strokes get new keys, connectors and links referring to their keys in the file are remapped.
+    fn insert_loaded_strokes(
+        &mut self,
+        strokes: Vec<(StrokeKey, Stroke, Arc<ChronoComponent>)>,
+    ) -> Vec<StrokeKey> {
+        if strokes.is_empty() {
+            return vec![];
+        }
+        let (original_keys, strokes): (Vec<StrokeKey>, Vec<_>) = strokes
+            .into_iter()
+            .map(|(original_key, mut stroke, chrono)| {
+                // Connectors attached to strokes that were loaded earlier
+                if let Stroke::ConnectorStroke(connector) = &mut stroke {
+                    connector.remap_attachments(|key| {
+                        Some(self.pending_strokes_keys.get(&key).copied().unwrap_or(key))
+                    });
+                }
+                stroke.update_geometry();
+                (original_key, (Arc::new(stroke), chrono))
+            })
+            .unzip();
+        let keys = self.insert_strokes_unrecorded(strokes, &original_keys);
+        self.pending_strokes_keys
+            .extend(original_keys.into_iter().zip(keys.iter().copied()));
+        self.remap_pending_strokes_keys_in_document_states();
+        keys
+    }
+
+    /// Remap the keys of the links in the document states of the current state and the history.
+    ///
+    /// History entries often share their document state, which is patched only once.
+    fn remap_pending_strokes_keys_in_document_states(&mut self) {
+        let key_map = &self.pending_strokes_keys;
+        let mut patched: Vec<(Arc<DocumentHistoryState>, Arc<DocumentHistoryState>)> = vec![];
+        let mut remap = |document_state: &mut Option<Arc<DocumentHistoryState>>| {
+            let Some(original) = document_state.as_ref() else {
+                return;
+            };
+            if let Some((_, remapped)) = patched.iter().find(|(o, _)| Arc::ptr_eq(o, original)) {
+                *document_state = Some(Arc::clone(remapped));
+                return;
+            }
+            let remapped = if original
+                .links
+                .iter()
+                .any(|link| link.keys.iter().any(|key| key_map.contains_key(key)))
+            {
+                let mut remapped = (**original).clone();
+                remapped.links.remap_keys(key_map);
+                Arc::new(remapped)
+            } else {
+                Arc::clone(original)
+            };
+            patched.push((Arc::clone(original), Arc::clone(&remapped)));
+            *document_state = Some(remapped);
+        };
+        remap(&mut self.document_state);
+        for entry in self.history.iter_mut() {
+            remap(&mut entry.document_state);
+        }
+    }
+}
@@ -26,6 +26,17 @@ impl Default for RenderCompState {
     }
 }
 
+/// Statistics gathered while drawing the strokes of a frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrokesDrawStats {
+    /// The number of strokes intersecting the viewport that were drawn.
+    pub drawn: usize,
+    /// The number of drawn strokes that had an up-to-date rendering.
+    pub cache_hits: usize,
+    /// The number of drawn strokes where the rendering was missing or outdated.
+    pub cache_misses: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct RenderComponent {
     pub(super) state: RenderCompState,
@@ -96,6 +107,7 @@ impl StrokeStore {
             .unwrap_or(false)
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(?key))]
     pub(crate) fn regenerate_rendering_for_stroke(
         &mut self,
         key: StrokeKey,
@@ -198,8 +210,9 @@ impl StrokeStore {
             render_comp.state = RenderCompState::BusyRenderingInTask;
 
             // Spawn a new thread for image rendering
-            rayon::spawn(
-                move || match stroke.gen_images(viewport_extended, image_scale) {
+            rayon::spawn(move || {
+                let _span = tracing::debug_span!("gen_stroke_images", ?key).entered();
+                match stroke.gen_images(viewport_extended, image_scale) {
                     Ok(images) => {
                         tasks_tx.send(EngineTask::UpdateStrokeWithImages {
                             key,
@@ -212,8 +225,8 @@ impl StrokeStore {
                             "Generating images of stroke failed while regenerating stroke rendering, stroke key {key:?} , Err: {e:?}"
                         );
                     }
-                },
-            );
+                }
+            });
         }
     }
 
@@ -235,6 +248,7 @@ impl StrokeStore {
     }
 
     /// Regenerate the rendering of all keys for the given viewport that need to be rerendered.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) fn regenerate_rendering_in_viewport_threaded(
         &mut self,
         tasks_tx: EngineTaskSender,
@@ -377,7 +391,15 @@ impl StrokeStore {
                 Stroke::ShapeStroke(_)
                 | Stroke::TextStroke(_)
                 | Stroke::VectorImage(_)
-                | Stroke::BitmapImage(_) => {
+                | Stroke::BitmapImage(_)
+                | Stroke::TableStroke(_)
+                | Stroke::CheckboxStroke(_)
+                | Stroke::StickyNoteStroke(_)
+                | Stroke::RedactionStroke(_)
+                | Stroke::ConnectorStroke(_)
+                | Stroke::MindMapStroke(_)
+                | Stroke::BoardStroke(_)
+                | Stroke::FrameStroke(_) => {
                     self.regenerate_rendering_for_stroke_threaded(
                         tasks_tx,
                         key,
@@ -478,23 +500,42 @@ impl StrokeStore {
 
     /// Draw all strokes on the gtk snapshot.
     #[cfg(feature = "ui")]
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) fn draw_strokes_to_gtk_snapshot(
         &self,
         snapshot: &gtk4::Snapshot,
         doc_bounds: Aabb,
         viewport: Aabb,
-    ) {
+        contrast_mode: crate::engine::ContrastMode,
+        document: &crate::Document,
+    ) -> StrokesDrawStats {
+        use crate::engine::ContrastMode;
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
+        use crate::strokes::BlendMode;
         use gtk4::{gdk, graphene, prelude::*};
         use rnote_compose::color;
 
         snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds));
 
+        // The strokes are collected in their own snapshot,
+        // so that strokes with a blend mode can be blended with the strokes beneath them.
+        let mut strokes_snapshot = gtk4::Snapshot::new();
+        let mut stats = StrokesDrawStats::default();
+
         for key in self.stroke_keys_as_rendered_intersecting_bounds(viewport) {
             if let (Some(stroke), Some(render_comp)) = (
                 self.stroke_components.get(key),
                 self.render_components.get(key),
             ) {
+                stats.drawn += 1;
+                match render_comp.state {
+                    RenderCompState::Complete => stats.cache_hits += 1,
+                    RenderCompState::ForViewport(bounds) if bounds.contains(&viewport) => {
+                        stats.cache_hits += 1
+                    }
+                    _ => stats.cache_misses += 1,
+                }
+
                 // if the stroke currently does not have a rendering and is will create one,
                 // draw a placeholder filled rect
                 if render_comp.rendernodes.is_empty()
@@ -503,19 +544,62 @@ impl StrokeStore {
                         RenderCompState::Dirty | RenderCompState::BusyRenderingInTask
                     )
                 {
-                    snapshot.append_color(
+                    strokes_snapshot.append_color(
                         &gdk::RGBA::from_piet_color(color::GNOME_BRIGHTS[1].with_alpha(0.13)),
                         &graphene::Rect::from_p2d_aabb(stroke.bounds()),
                     );
                 }
 
-                for rendernode in render_comp.rendernodes.iter() {
-                    snapshot.append_node(rendernode);
+                // The contrast is adjusted when compositing, the rendernodes keep the original colors
+                let contrast_color_matrix = if contrast_mode == ContrastMode::Normal {
+                    None
+                } else {
+                    // The contrast is computed against the background of the page the stroke is on
+                    let background_color = document
+                        .page_at(stroke.bounds().center().coords)
+                        .map(|page| document.background_for_page(page).color)
+                        .unwrap_or(document.background.color);
+                    contrast_mode.gtk_color_matrix(
+                        &stroke.colors(),
+                        stroke.is_filled(),
+                        background_color,
+                    )
+                };
+                let append_rendernodes = |snapshot: &gtk4::Snapshot| {
+                    if let Some((color_matrix, color_offset)) = &contrast_color_matrix {
+                        snapshot.push_color_matrix(color_matrix, color_offset);
+                    }
+                    for rendernode in render_comp.rendernodes.iter() {
+                        snapshot.append_node(rendernode);
+                    }
+                    if contrast_color_matrix.is_some() {
+                        snapshot.pop();
+                    }
+                };
+
+                let blend_mode = stroke.compositing().blend_mode;
+                if blend_mode == BlendMode::Normal || render_comp.rendernodes.is_empty() {
+                    append_rendernodes(&strokes_snapshot);
+                } else {
+                    let backdrop = strokes_snapshot.to_node();
+                    strokes_snapshot = gtk4::Snapshot::new();
+                    strokes_snapshot.push_blend(blend_mode.into());
+                    if let Some(backdrop) = backdrop {
+                        strokes_snapshot.append_node(backdrop);
+                    }
+                    strokes_snapshot.pop();
+                    append_rendernodes(&strokes_snapshot);
+                    strokes_snapshot.pop();
                 }
             }
         }
 
+        if let Some(strokes_node) = strokes_snapshot.to_node() {
+            snapshot.append_node(strokes_node);
+        }
+
         snapshot.pop();
+        stats
     }
 
     /// Draw the strokes for the given keys on the [piet::RenderContext].
@@ -5,6 +5,7 @@ use crate::strokes::content::GeneratedContentImages;
 use crate::strokes::Stroke;
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -99,12 +100,14 @@ impl StrokeStore {
         let old_selected = self.selection_keys_as_rendered();
         self.set_selected_keys(&old_selected, false);
 
+        let mut key_map = HashMap::new();
         let new_selected = old_selected
             .iter()
             .filter_map(|&old_key| {
                 let new_key =
                     self.insert_stroke((**self.stroke_components.get(old_key)?).clone(), None);
                 self.set_selected(new_key, true);
+                key_map.insert(old_key, new_key);
 
                 // duplicate and insert the render images of the old stroke to avoid flickering
                 if let Some(render_comp) = self.render_components.get(old_key) {
@@ -124,6 +127,8 @@ impl StrokeStore {
                 Some(new_key)
             })
             .collect::<Vec<StrokeKey>>();
+        // Duplicated connectors stay attached to the duplicated strokes
+        self.remap_connector_attachments(&new_selected, &key_map);
 
         // Offsetting the new selected stroke to make the duplication apparent
         self.translate_strokes(&new_selected, Stroke::IMPORT_OFFSET_DEFAULT);
@@ -2,7 +2,7 @@
 use super::render_comp::RenderCompState;
 use super::StrokeKey;
 use crate::engine::StrokeContent;
-use crate::strokes::{Content, Stroke};
+use crate::strokes::{BlendMode, BoardStroke, ConnectorStroke, Content, Stroke};
 use crate::{StrokeStore, WidgetFlags};
 use geo::intersects::Intersects;
 use geo::prelude::Contains;
@@ -10,7 +10,8 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::penpath::Element;
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transformable;
-use rnote_compose::Color;
+use rnote_compose::{Color, Style};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::error;
 
@@ -129,12 +130,14 @@ impl StrokeStore {
         let strokes_iter = self
             .stroke_keys_unordered()
             .into_iter()
-            .filter_map(|key| self.stroke_components.get(key));
+            .filter_map(|key| self.stroke_components.get(key))
+            .map(|stroke| stroke.bounds())
+            .chain(self.pending_strokes_bounds());
 
         let strokes_min_y = strokes_iter
             .clone()
-            .fold(0.0, |acc, stroke| stroke.bounds().mins[1].min(acc));
-        let strokes_max_y = strokes_iter.fold(0.0, |acc, stroke| stroke.bounds().maxs[1].max(acc));
+            .fold(0.0, |acc, bounds| bounds.mins[1].min(acc));
+        let strokes_max_y = strokes_iter.fold(0.0, |acc, bounds| bounds.maxs[1].max(acc));
 
         strokes_max_y - strokes_min_y
     }
@@ -145,12 +148,14 @@ impl StrokeStore {
         let strokes_iter = self
             .stroke_keys_unordered()
             .into_iter()
-            .filter_map(|key| self.stroke_components.get(key));
+            .filter_map(|key| self.stroke_components.get(key))
+            .map(|stroke| stroke.bounds())
+            .chain(self.pending_strokes_bounds());
 
         let strokes_min_x = strokes_iter
             .clone()
-            .fold(0.0, |acc, stroke| stroke.bounds().mins[0].min(acc));
-        let strokes_max_x = strokes_iter.fold(0.0, |acc, stroke| stroke.bounds().maxs[0].max(acc));
+            .fold(0.0, |acc, bounds| bounds.mins[0].min(acc));
+        let strokes_max_x = strokes_iter.fold(0.0, |acc, bounds| bounds.maxs[0].max(acc));
 
         strokes_max_x - strokes_min_x
     }
@@ -256,6 +261,90 @@ impl StrokeStore {
         });
     }
 
+    /// Re-route the connectors that are attached to the given strokes, by moving the attached ends onto the
+    /// anchors of the strokes.
+    ///
+    /// Must be called after the strokes were transformed.
+    /// Returns the keys of the re-routed connectors. They then need to update their rendering.
+    pub(crate) fn reroute_attached_connectors(&mut self, keys: &[StrokeKey]) -> Vec<StrokeKey> {
+        let anchors = keys
+            .iter()
+            .filter_map(|&key| match self.stroke_components.get(key)?.as_ref() {
+                Stroke::ConnectorStroke(_) => None,
+                stroke => Some((key, ConnectorStroke::anchors(stroke.bounds()))),
+            })
+            .collect::<HashMap<StrokeKey, [na::Vector2<f64>; 4]>>();
+        if anchors.is_empty() {
+            return vec![];
+        }
+        let connector_keys = self.connector_keys_attached_to(|key| anchors.contains_key(&key));
+
+        for &key in connector_keys.iter() {
+            if let Some(Stroke::ConnectorStroke(connector)) =
+                Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+            {
+                for attachment in connector.attachments().collect::<Vec<_>>() {
+                    if let Some(anchors) = anchors.get(&attachment.key) {
+                        connector.reroute(attachment.key, anchors);
+                    }
+                }
+                self.key_tree
+                    .update_with_key(key, ConnectorStroke::bounds(connector));
+            }
+        }
+        self.set_rendering_dirty_for_strokes(&connector_keys);
+        connector_keys
+    }
+
+    /// Detach the connectors from the given strokes, for example when they are removed.
+    pub(crate) fn detach_connectors(&mut self, keys: &[StrokeKey]) {
+        for key in self.connector_keys_attached_to(|key| keys.contains(&key)) {
+            if let Some(Stroke::ConnectorStroke(connector)) =
+                Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+            {
+                connector.detach(|key| keys.contains(&key));
+            }
+        }
+    }
+
+    /// Remap the attachments of the connectors among the given keys, from the keys of the strokes they were copied
+    /// from to the keys of the copies.
+    ///
+    /// Ends that are attached to strokes that were not copied are detached.
+    pub(crate) fn remap_connector_attachments(
+        &mut self,
+        keys: &[StrokeKey],
+        key_map: &HashMap<StrokeKey, StrokeKey>,
+    ) {
+        for &key in keys {
+            if let Some(Stroke::ConnectorStroke(connector)) =
+                Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+            {
+                connector.remap_attachments(|key| key_map.get(&key).copied());
+            }
+        }
+    }
+
+    /// The keys of the connectors with an end attached to a stroke for which `attached` returns true.
+    fn connector_keys_attached_to(&self, attached: impl Fn(StrokeKey) -> bool) -> Vec<StrokeKey> {
+        self.stroke_components
+            .iter()
+            .filter(|(_, stroke)| match stroke.as_ref() {
+                Stroke::ConnectorStroke(connector) => connector
+                    .attachments()
+                    .any(|attachment| attached(attachment.key)),
+                _ => false,
+            })
+            .map(|(key, _)| key)
+            .collect()
+    }
+
     /// Change the stroke and text color for the given keys.
     ///
     /// The strokes then need to update their rendering.
@@ -326,6 +415,36 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Apply the style to the strokes of the given keys.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn apply_style(&mut self, keys: &[StrokeKey], style: &Style) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if keys.is_empty() {
+            return widget_flags;
+        }
+
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                let stroke_modified = stroke.apply_style(style);
+
+                if stroke_modified {
+                    // the stroke width might have changed, which changes the bounds
+                    self.update_geometry_for_stroke(key);
+                }
+            }
+        });
+
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
     /// Change the fill color of the given keys.
     ///
     /// The strokes then need to update their rendering.
@@ -363,6 +482,61 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Change the opacity of the given keys.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn change_opacity(&mut self, keys: &[StrokeKey], opacity: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if keys.is_empty() {
+            return widget_flags;
+        }
+
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                stroke.compositing_mut().set_opacity(opacity);
+                self.set_rendering_dirty(key);
+            }
+        });
+
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
+    /// Change the blend mode of the given keys.
+    ///
+    /// The blend mode is applied when the stroke renderings are composited, so they don't need to be updated.
+    pub(crate) fn change_blend_mode(
+        &mut self,
+        keys: &[StrokeKey],
+        blend_mode: BlendMode,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if keys.is_empty() {
+            return widget_flags;
+        }
+
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                stroke.compositing_mut().blend_mode = blend_mode;
+            }
+        });
+
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
     /// Rotate the stroke rendering images.
     ///
     /// The strokes then need to update their rendering.
@@ -494,6 +668,10 @@ impl StrokeStore {
                 if self.trashed(key)? {
                     return None;
                 }
+                // skip if stroke is locked
+                if self.locked(key)? {
+                    return None;
+                }
 
                 let stroke = self.stroke_components.get(key)?;
                 let stroke_bounds = stroke.bounds();
@@ -549,6 +727,10 @@ impl StrokeStore {
                 if self.trashed(key)? {
                     return None;
                 }
+                // skip if stroke is locked
+                if self.locked(key)? {
+                    return None;
+                }
 
                 let stroke = self.stroke_components.get(key)?;
                 let stroke_bounds = stroke.bounds();
@@ -582,6 +764,10 @@ impl StrokeStore {
                 if self.trashed(key)? {
                     return None;
                 }
+                // skip if stroke is locked
+                if self.locked(key)? {
+                    return None;
+                }
 
                 let stroke = self.stroke_components.get(key)?;
                 let stroke_bounds = stroke.bounds();
@@ -615,6 +801,9 @@ impl StrokeStore {
         self.stroke_keys_as_rendered_intersecting_bounds(bounds)
             .into_iter()
             .filter(|&key| {
+                if self.locked(key).unwrap_or(false) {
+                    return false;
+                }
                 if let Some(stroke) = self.stroke_components.get(key) {
                     stroke
                         .hitboxes()
@@ -687,26 +876,25 @@ impl StrokeStore {
     }
 
     pub(crate) fn fetch_stroke_content(&self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
+        let (keys, strokes) = keys
             .iter()
-            .filter_map(|k| self.stroke_components.get(*k).cloned())
-            .collect();
+            .filter_map(|&k| Some((k, self.stroke_components.get(k).cloned()?)))
+            .unzip();
 
-        StrokeContent::default().with_strokes(strokes)
+        StrokeContent::default()
+            .with_strokes(strokes)
+            .with_keys(keys)
     }
 
     /// Cut the strokes for the given keys and return them as stroke content.
     pub(crate) fn cut_stroke_content(&mut self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
-            .iter()
-            .filter_map(|k| {
-                self.set_selected(*k, false);
-                self.set_trashed(*k, true);
-                self.stroke_components.get(*k).cloned()
-            })
-            .collect();
-
-        StrokeContent::default().with_strokes(strokes)
+        // Fetched before trashing, which detaches the connectors
+        let content = self.fetch_stroke_content(keys);
+        for &key in keys {
+            self.set_selected(key, false);
+            self.set_trashed(key, true);
+        }
+        content
     }
 
     /// Paste the clipboard content as a selection.
@@ -728,7 +916,8 @@ impl StrokeStore {
             .iter()
             .fold(Aabb::new_invalid(), |acc, s| acc.merged(&s.bounds()));
 
-        clipboard_content
+        let source_keys = clipboard_content.keys;
+        let inserted = clipboard_content
             .strokes
             .into_iter()
             .map(|s| {
@@ -746,6 +935,13 @@ impl StrokeStore {
                 self.set_selected(key, true);
                 key
             })
-            .collect()
+            .collect::<Vec<StrokeKey>>();
+        // Connectors stay attached to the strokes that were inserted along with them
+        let key_map = source_keys
+            .into_iter()
+            .zip(inserted.iter().copied())
+            .collect::<HashMap<StrokeKey, StrokeKey>>();
+        self.remap_connector_attachments(&inserted, &key_map);
+        inserted
     }
 }
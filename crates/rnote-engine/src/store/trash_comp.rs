@@ -1,13 +1,14 @@
 // Imports
 use super::chrono_comp::StrokeLayer;
 use super::{StrokeKey, StrokeStore};
-use crate::strokes::{BrushStroke, Stroke};
+use crate::strokes::{BrushStroke, RedactionStroke, Stroke};
 use crate::WidgetFlags;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::PenPath;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::error;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(default, rename = "trash_component")]
@@ -50,6 +51,9 @@ impl StrokeStore {
         {
             trash_comp.trashed = trash;
             self.update_chrono_to_last(key);
+            if trash {
+                self.detach_connectors(&[key]);
+            }
         }
     }
 
@@ -69,7 +73,6 @@ impl StrokeStore {
     }
 
     /// Removes all trashed strokes permanently from the store.
-    #[allow(unused)]
     pub(crate) fn remove_trashed_strokes(&mut self) -> Vec<Stroke> {
         self.trashed_keys_unordered()
             .into_iter()
@@ -87,12 +90,15 @@ impl StrokeStore {
 
         self.stroke_keys_as_rendered_intersecting_bounds(viewport)
             .into_iter()
+            .filter(|&key| !self.locked(key).unwrap_or(false))
             .for_each(|key| {
                 let mut trash_current_stroke = false;
 
                 if let Some(stroke) = self.stroke_components.get(key) {
                     match stroke.as_ref() {
-                        Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) => {
+                        Stroke::BrushStroke(_)
+                        | Stroke::ShapeStroke(_)
+                        | Stroke::ConnectorStroke(_) => {
                             // First check if eraser even intersects stroke bounds, avoiding unnecessary work
                             if eraser_bounds.intersects(&stroke.bounds()) {
                                 for hitbox in stroke.hitboxes().into_iter() {
@@ -105,8 +111,16 @@ impl StrokeStore {
                             }
                         }
                         // Ignore other strokes when trashing with the Eraser
-                        Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {
-                        }
+                        Stroke::TextStroke(_)
+                        | Stroke::VectorImage(_)
+                        | Stroke::BitmapImage(_)
+                        | Stroke::TableStroke(_)
+                        | Stroke::CheckboxStroke(_)
+                        | Stroke::StickyNoteStroke(_)
+                        | Stroke::MindMapStroke(_)
+                        | Stroke::BoardStroke(_)
+                        | Stroke::FrameStroke(_)
+                        | Stroke::RedactionStroke(_) => {}
                     }
                 }
 
@@ -137,6 +151,7 @@ impl StrokeStore {
         let new_strokes = self
             .stroke_keys_as_rendered_intersecting_bounds(viewport)
             .into_iter()
+            .filter(|&key| !self.locked(key).unwrap_or(false))
             .flat_map(|key| {
                 let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                     .get_mut(key)
@@ -156,62 +171,18 @@ impl StrokeStore {
                 match stroke {
                     Stroke::BrushStroke(brushstroke) => {
                         if eraser_bounds.intersects(&stroke_bounds) {
-                            let mut split = Vec::new();
-
-                            let mut hits = brushstroke
-                                .path
-                                .hittest(&eraser_bounds, brushstroke.style.stroke_width() * 0.5)
-                                .into_iter();
-
-                            if let Some(first_hit) = hits.next() {
-                                let mut prev = first_hit;
-                                for hit in hits {
-                                    let split_slice = &brushstroke.path.segments[prev..hit];
-
-                                    // skip splits that don't have at least two segments (one's end as path start, one additional)
-                                    if split_slice.len() > 1 {
-                                        split.push(split_slice.to_vec());
-                                    }
-
-                                    prev = hit;
-                                }
-
-                                // Catch the last
-                                let last_split = &brushstroke.path.segments[prev..];
-                                if last_split.len() > 1 {
-                                    split.push(last_split.to_vec());
-                                }
-
-                                for next_split in split {
-                                    let mut next_split_iter = next_split.into_iter();
-                                    let next_start = next_split_iter.next().unwrap().end();
-
-                                    new_strokes.push((
-                                        Stroke::BrushStroke(BrushStroke::from_penpath(
-                                            PenPath::new_w_segments(next_start, next_split_iter),
-                                            brushstroke.style.clone(),
-                                        )),
-                                        chrono_comp.layer,
-                                    ));
-                                }
-
-                                let first_split = &brushstroke.path.segments[..first_hit];
-                                // Modify the original stroke at the end.
-                                // We keep the start, so we only need at least one segment
-                                if !first_split.is_empty() {
-                                    brushstroke.replace_path(PenPath::new_w_segments(
-                                        brushstroke.path.start,
-                                        first_split.to_vec(),
-                                    ));
-                                } else {
-                                    trash_current_stroke = true;
-                                }
-
+                            if let Some((split, keep_current_stroke)) =
+                                split_brushstroke(brushstroke, eraser_bounds)
+                            {
+                                new_strokes.extend(split.into_iter().map(|new_brushstroke| {
+                                    (Stroke::BrushStroke(new_brushstroke), chrono_comp.layer)
+                                }));
+                                trash_current_stroke = !keep_current_stroke;
                                 modified_keys.push(key);
                             }
                         }
                     }
-                    Stroke::ShapeStroke(_) => {
+                    Stroke::ShapeStroke(_) | Stroke::ConnectorStroke(_) => {
                         if eraser_bounds.intersects(&stroke_bounds) {
                             for hitbox_elem in stroke.hitboxes().iter() {
                                 if eraser_bounds.intersects(hitbox_elem) {
@@ -222,7 +193,16 @@ impl StrokeStore {
                         }
                     }
                     // Ignore other strokes when trashing with the Eraser
-                    Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {}
+                    Stroke::TextStroke(_)
+                    | Stroke::VectorImage(_)
+                    | Stroke::BitmapImage(_)
+                    | Stroke::TableStroke(_)
+                    | Stroke::CheckboxStroke(_)
+                    | Stroke::StickyNoteStroke(_)
+                    | Stroke::MindMapStroke(_)
+                    | Stroke::BoardStroke(_)
+                    | Stroke::FrameStroke(_)
+                    | Stroke::RedactionStroke(_) => {}
                 }
 
                 if trash_current_stroke {
@@ -249,4 +229,163 @@ impl StrokeStore {
 
         (modified_keys, widget_flags)
     }
+
+    /// Delete the content underneath the given redacted areas, including locked strokes.
+    ///
+    /// Brush strokes are split, the colliding segments are removed.
+    /// All other colliding strokes are replaced by bitmap images in which the redacted areas are painted over.
+    /// Redactions themselves are kept.
+    ///
+    /// Returns the keys of all created or modified strokes.
+    ///
+    /// The returned strokes need to update their rendering.
+    pub(crate) fn redact_strokes(&mut self, areas: &[Aabb]) -> (Vec<StrokeKey>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        let mut modified_keys = vec![];
+
+        // Strokes created by splitting must be checked against the next areas, so the areas are processed one by one
+        for &area in areas {
+            let mut new_strokes = vec![];
+
+            for key in self.stroke_keys_as_rendered_intersecting_bounds(area) {
+                let Some(layer) = self.stroke_layer(key) else {
+                    continue;
+                };
+                let Some(Stroke::BrushStroke(brushstroke)) =
+                    Arc::make_mut(&mut self.stroke_components)
+                        .get_mut(key)
+                        .map(Arc::make_mut)
+                else {
+                    continue;
+                };
+                let Some((split, keep_current_stroke)) = split_brushstroke(brushstroke, area)
+                else {
+                    continue;
+                };
+                new_strokes.extend(
+                    split
+                        .into_iter()
+                        .map(|new_brushstroke| (Stroke::BrushStroke(new_brushstroke), layer)),
+                );
+                if keep_current_stroke {
+                    modified_keys.push(key);
+                } else {
+                    self.set_trashed(key, true);
+                }
+            }
+
+            modified_keys.extend(
+                new_strokes
+                    .into_iter()
+                    .map(|(new_stroke, layer)| self.insert_stroke(new_stroke, Some(layer))),
+            );
+        }
+
+        for key in self.stroke_keys_as_rendered() {
+            let Some(stroke) = self.stroke_components.get(key) else {
+                continue;
+            };
+            let stroke_areas = areas
+                .iter()
+                .copied()
+                .filter(|area| match stroke.as_ref() {
+                    // Brush strokes are split above, redactions and frames have no content that could be redacted
+                    Stroke::BrushStroke(_)
+                    | Stroke::RedactionStroke(_)
+                    | Stroke::FrameStroke(_) => false,
+                    Stroke::ShapeStroke(_) | Stroke::ConnectorStroke(_) => stroke
+                        .hitboxes()
+                        .into_iter()
+                        .any(|hitbox| area.intersects(&hitbox)),
+                    _ => area.intersects(&stroke.bounds()),
+                })
+                .collect::<Vec<Aabb>>();
+            if stroke_areas.is_empty() {
+                continue;
+            }
+
+            match RedactionStroke::rasterize_redacted(stroke, &stroke_areas) {
+                Ok(bitmapimage) => {
+                    if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                        .get_mut(key)
+                        .map(Arc::make_mut)
+                    {
+                        *stroke = Stroke::BitmapImage(bitmapimage);
+                        self.key_tree.update_with_key(key, stroke.bounds());
+                        modified_keys.push(key);
+                    }
+                }
+                Err(e) => {
+                    // The content must not survive, so it is removed entirely
+                    error!("Rasterizing redacted stroke failed, trashing it instead. Err: {e:?}");
+                    self.set_trashed(key, true);
+                }
+            }
+        }
+
+        widget_flags.store_modified = true;
+        widget_flags.resize = true;
+
+        (modified_keys, widget_flags)
+    }
+}
+
+/// Removes the segments of the brush stroke that collide with the given bounds.
+///
+/// Returns None if nothing collides. Otherwise returns the strokes that were split off after the first collision,
+/// and whether the original stroke still has segments left before it.
+fn split_brushstroke(
+    brushstroke: &mut BrushStroke,
+    bounds: Aabb,
+) -> Option<(Vec<BrushStroke>, bool)> {
+    let mut hits = brushstroke
+        .path
+        .hittest(&bounds, brushstroke.style.stroke_width() * 0.5)
+        .into_iter();
+    let first_hit = hits.next()?;
+
+    let mut split = Vec::new();
+    let mut prev = first_hit;
+    for hit in hits {
+        let split_slice = &brushstroke.path.segments[prev..hit];
+
+        // skip splits that don't have at least two segments (one's end as path start, one additional)
+        if split_slice.len() > 1 {
+            split.push(split_slice.to_vec());
+        }
+
+        prev = hit;
+    }
+
+    // Catch the last
+    let last_split = &brushstroke.path.segments[prev..];
+    if last_split.len() > 1 {
+        split.push(last_split.to_vec());
+    }
+
+    let new_brushstrokes = split
+        .into_iter()
+        .map(|next_split| {
+            let mut next_split_iter = next_split.into_iter();
+            let next_start = next_split_iter.next().unwrap().end();
+
+            BrushStroke::from_penpath(
+                PenPath::new_w_segments(next_start, next_split_iter),
+                brushstroke.style.clone(),
+            )
+        })
+        .collect();
+
+    let first_split = &brushstroke.path.segments[..first_hit];
+    // Modify the original stroke at the end.
+    // We keep the start, so we only need at least one segment
+    let keep_current_stroke = !first_split.is_empty();
+    if keep_current_stroke {
+        brushstroke.replace_path(PenPath::new_w_segments(
+            brushstroke.path.start,
+            first_split.to_vec(),
+        ));
+    }
+
+    Some((new_brushstrokes, keep_current_stroke))
 }
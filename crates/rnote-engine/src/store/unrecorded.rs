@@ -0,0 +1,284 @@
+// Imports
+use super::chrono_comp::StrokeLayer;
+use super::render_comp::RenderComponent;
+use super::{ChronoComponent, SelectionComponent, StrokeKey, StrokeStore, TrashComponent};
+use crate::strokes::Stroke;
+use rnote_compose::shapes::Shapeable;
+use slotmap::HopSlotMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type StrokeComponents = Arc<HopSlotMap<StrokeKey, Arc<Stroke>>>;
+
+/// Systems for changes that are not undoable, like loading strokes or applying the changes of remote peers.
+///
+/// These changes are applied to the current state and all history entries, so that undo and redo keep them.
+impl StrokeStore {
+    /// Insert the strokes, keeping their chronological order. Their geometry is expected to be up to date.
+    ///
+    /// `original_keys` are the keys the strokes had before, for example in a loaded file.
+    /// Connectors attached to them are re-attached to the inserted strokes.
+    ///
+    /// Returns the keys of the inserted strokes in the current state. They then need to update their rendering.
+    pub(crate) fn insert_strokes_unrecorded(
+        &mut self,
+        strokes: Vec<(Arc<Stroke>, Arc<ChronoComponent>)>,
+        original_keys: &[StrokeKey],
+    ) -> Vec<StrokeKey> {
+        if strokes.is_empty() {
+            return vec![];
+        }
+        let live_unchanged = self.eq_w_history_entry(&self.history[self.live_index]);
+        self.insert_strokes_unrecorded_inner(strokes, original_keys, live_unchanged)
+    }
+
+    /// Insert new strokes on top of the existing ones. The layer is extracted from the stroke when `None`.
+    ///
+    /// Returns the keys of the inserted strokes in the current state. They then need to update their rendering.
+    pub(crate) fn insert_new_strokes_unrecorded(
+        &mut self,
+        strokes: Vec<(Arc<Stroke>, Option<StrokeLayer>)>,
+    ) -> Vec<StrokeKey> {
+        if strokes.is_empty() {
+            return vec![];
+        }
+        let live_unchanged = self.eq_w_history_entry(&self.history[self.live_index]);
+        let strokes = strokes
+            .into_iter()
+            .map(|(stroke, layer)| {
+                self.chrono_counter += 1;
+                let layer = layer.unwrap_or_else(|| stroke.extract_default_layer());
+                let chrono = Arc::new(ChronoComponent::new(self.chrono_counter, layer));
+                (stroke, chrono)
+            })
+            .collect();
+        self.insert_strokes_unrecorded_inner(strokes, &[], live_unchanged)
+    }
+
+    fn insert_strokes_unrecorded_inner(
+        &mut self,
+        strokes: Vec<(Arc<Stroke>, Arc<ChronoComponent>)>,
+        original_keys: &[StrokeKey],
+        live_unchanged: bool,
+    ) -> Vec<StrokeKey> {
+        let keys = strokes
+            .iter()
+            .map(|(stroke, chrono)| {
+                let key = Arc::make_mut(&mut self.stroke_components).insert(Arc::clone(stroke));
+                self.key_tree.insert_with_key(key, stroke.bounds());
+                Arc::make_mut(&mut self.trash_components)
+                    .insert(key, Arc::new(TrashComponent::default()));
+                Arc::make_mut(&mut self.selection_components)
+                    .insert(key, Arc::new(SelectionComponent::default()));
+                Arc::make_mut(&mut self.chrono_components).insert(key, Arc::clone(chrono));
+                self.render_components
+                    .insert(key, RenderComponent::default());
+                key
+            })
+            .collect::<Vec<StrokeKey>>();
+        if !original_keys.is_empty() {
+            reattach_connectors(
+                Arc::make_mut(&mut self.stroke_components),
+                original_keys,
+                &keys,
+            );
+        }
+
+        self.add_strokes_to_history(
+            &strokes,
+            original_keys,
+            live_unchanged.then_some(self.live_index),
+        );
+        if live_unchanged {
+            self.history[self.live_index] = self.create_history_entry();
+        }
+        keys
+    }
+
+    /// Trash the given strokes, identified by their Arc's.
+    ///
+    /// Returns the keys of the trashed strokes in the current state. They then need to update their rendering.
+    pub(crate) fn trash_strokes_unrecorded(&mut self, strokes: &[Arc<Stroke>]) -> Vec<StrokeKey> {
+        if strokes.is_empty() {
+            return vec![];
+        }
+        let live_unchanged = self.eq_w_history_entry(&self.history[self.live_index]);
+
+        let keys = keys_of_strokes(&self.stroke_components, strokes);
+        for &key in keys.iter() {
+            self.set_selected(key, false);
+            if let Some(trash_comp) = Arc::make_mut(&mut self.trash_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                trash_comp.trashed = true;
+            }
+        }
+
+        let mut patched_trash_components = vec![];
+        for (i, entry) in self.history.iter_mut().enumerate() {
+            if live_unchanged && i == self.live_index {
+                continue;
+            }
+            let entry_keys = keys_of_strokes(&entry.stroke_components, strokes);
+            if entry_keys.is_empty() {
+                continue;
+            }
+            entry.trash_components = patch_shared(
+                &mut patched_trash_components,
+                &entry.trash_components,
+                &entry.stroke_components,
+                |trash_components| {
+                    for key in entry_keys {
+                        if let Some(trash_comp) = trash_components.get_mut(key) {
+                            Arc::make_mut(trash_comp).trashed = true;
+                        }
+                    }
+                },
+            );
+        }
+        if live_unchanged {
+            self.history[self.live_index] = self.create_history_entry();
+        }
+        keys
+    }
+
+    /// Add the strokes to the history entries, except the one at the `skip` index.
+    ///
+    /// History entries often share their components, which are patched only once.
+    fn add_strokes_to_history(
+        &mut self,
+        strokes: &[(Arc<Stroke>, Arc<ChronoComponent>)],
+        original_keys: &[StrokeKey],
+        skip: Option<usize>,
+    ) {
+        let mut patched_stroke_components: Vec<(
+            StrokeComponents,
+            StrokeComponents,
+            Vec<StrokeKey>,
+        )> = vec![];
+        let mut patched_trash_components = vec![];
+        let mut patched_chrono_components = vec![];
+
+        for (i, entry) in self.history.iter_mut().enumerate() {
+            if skip == Some(i) {
+                continue;
+            }
+            let original_stroke_components = Arc::clone(&entry.stroke_components);
+            let keys = match patched_stroke_components
+                .iter()
+                .find(|(original, ..)| Arc::ptr_eq(original, &original_stroke_components))
+            {
+                Some((_, patched, keys)) => {
+                    entry.stroke_components = Arc::clone(patched);
+                    keys.clone()
+                }
+                None => {
+                    let keys = strokes
+                        .iter()
+                        .map(|(stroke, _)| {
+                            Arc::make_mut(&mut entry.stroke_components).insert(Arc::clone(stroke))
+                        })
+                        .collect::<Vec<StrokeKey>>();
+                    if !original_keys.is_empty() {
+                        reattach_connectors(
+                            Arc::make_mut(&mut entry.stroke_components),
+                            original_keys,
+                            &keys,
+                        );
+                    }
+                    patched_stroke_components.push((
+                        Arc::clone(&original_stroke_components),
+                        Arc::clone(&entry.stroke_components),
+                        keys.clone(),
+                    ));
+                    keys
+                }
+            };
+
+            entry.trash_components = patch_shared(
+                &mut patched_trash_components,
+                &entry.trash_components,
+                &original_stroke_components,
+                |trash_components| {
+                    for &key in keys.iter() {
+                        trash_components.insert(key, Arc::new(TrashComponent::default()));
+                    }
+                },
+            );
+            entry.chrono_components = patch_shared(
+                &mut patched_chrono_components,
+                &entry.chrono_components,
+                &original_stroke_components,
+                |chrono_components| {
+                    for (&key, (_, chrono)) in keys.iter().zip(strokes.iter()) {
+                        chrono_components.insert(key, Arc::clone(chrono));
+                    }
+                },
+            );
+        }
+    }
+}
+
+/// The keys of the given strokes in the stroke components, compared by pointer.
+fn keys_of_strokes(
+    stroke_components: &StrokeComponents,
+    strokes: &[Arc<Stroke>],
+) -> Vec<StrokeKey> {
+    stroke_components
+        .iter()
+        .filter(|(_, stroke)| strokes.iter().any(|s| Arc::ptr_eq(s, stroke)))
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// Re-attach the connectors that are attached to the original keys to the corresponding new keys.
+fn reattach_connectors(
+    stroke_components: &mut HopSlotMap<StrokeKey, Arc<Stroke>>,
+    original_keys: &[StrokeKey],
+    new_keys: &[StrokeKey],
+) {
+    let key_map = original_keys
+        .iter()
+        .copied()
+        .zip(new_keys.iter().copied())
+        .collect::<HashMap<StrokeKey, StrokeKey>>();
+    for stroke in stroke_components.values_mut() {
+        let Stroke::ConnectorStroke(connector) = stroke.as_ref() else {
+            continue;
+        };
+        if !connector
+            .attachments()
+            .any(|attachment| key_map.contains_key(&attachment.key))
+        {
+            continue;
+        }
+        if let Stroke::ConnectorStroke(connector) = Arc::make_mut(stroke) {
+            connector.remap_attachments(|key| Some(key_map.get(&key).copied().unwrap_or(key)));
+        }
+    }
+}
+
+/// Patch a component map that might be shared between history entries, reusing the patched map for entries that
+/// share the same original map and the same original stroke components (which decide the patched keys).
+fn patch_shared<T: Clone>(
+    patched: &mut Vec<(Arc<T>, StrokeComponents, Arc<T>)>,
+    original: &Arc<T>,
+    original_stroke_components: &StrokeComponents,
+    patch: impl FnOnce(&mut T),
+) -> Arc<T> {
+    if let Some((_, _, patched)) = patched.iter().find(|(o, o_strokes, _)| {
+        Arc::ptr_eq(o, original) && Arc::ptr_eq(o_strokes, original_stroke_components)
+    }) {
+        return Arc::clone(patched);
+    }
+    let mut components = (**original).clone();
+    patch(&mut components);
+    let components = Arc::new(components);
+    patched.push((
+        Arc::clone(original),
+        Arc::clone(original_stroke_components),
+        Arc::clone(&components),
+    ));
+    components
+}
@@ -1,13 +1,15 @@
 // Imports
+use super::content::GeneratedContentImages;
 use super::resize::{calculate_resize_ratio, ImageSizeOption};
-use super::{Content, Stroke};
-use crate::document::Format;
+use super::{Compositing, Content, Stroke};
+use crate::document::{Format, ImageCompression};
 use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
+use crate::engine::progress::Progress;
 use crate::render;
 use crate::Drawable;
 use anyhow::Context;
 use kurbo::Shape;
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rnote_compose::color;
 use rnote_compose::ext::{AabbExt, Affine2Ext};
@@ -16,19 +18,232 @@ use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
 use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::error;
+
+thread_local! {
+    /// The parsed documents of the Pdf page sources, by the id of the source.
+    ///
+    /// Poppler documents can't be shared between threads, so every rendering thread keeps its own.
+    static PDF_PAGE_SOURCE_DOCUMENTS: RefCell<HashMap<u64, poppler::Document>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The maximum number of parsed Pdf page source documents that are kept per thread.
+const PDF_PAGE_SOURCE_DOCUMENTS_MAX: usize = 32;
+
+/// The Pdf page a bitmap image was imported from.
+///
+/// It is kept so that the page can be re-rendered sharply at the current zoom level.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename = "pdf_page_source")]
+pub struct PdfPageSource {
+    /// A Pdf document containing only the page.
+    ///
+    /// Is (de)serialized with base64 encoding.
+    #[serde(rename = "data", with = "crate::utils::glib_bytes_base64")]
+    pub data: glib::Bytes,
+    /// Whether a border is drawn around the page.
+    #[serde(rename = "page_borders")]
+    pub page_borders: bool,
+    /// Identifies the data for caching the parsed document. Clones share it, because the data can't change.
+    #[serde(skip, default = "PdfPageSource::new_id")]
+    id: u64,
+}
+
+impl Default for PdfPageSource {
+    fn default() -> Self {
+        Self {
+            data: glib::Bytes::from_owned(Vec::new()),
+            page_borders: false,
+            id: Self::new_id(),
+        }
+    }
+}
+
+impl std::fmt::Debug for PdfPageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PdfPageSource")
+            .field("data", &String::from("- no debug impl -"))
+            .field("page_borders", &self.page_borders)
+            .finish()
+    }
+}
+
+impl PdfPageSource {
+    /// Extracts the page into a new Pdf document.
+    fn from_page(page: &poppler::Page, page_borders: bool) -> anyhow::Result<Self> {
+        let intrinsic_size = page.size();
+        let surface =
+            cairo::PdfSurface::for_stream(intrinsic_size.0, intrinsic_size.1, Vec::<u8>::new())
+                .map_err(|e| {
+                    anyhow::anyhow!("Creating Pdf surface for Pdf page source failed, Err: {e:?}")
+                })?;
+        {
+            let cx = cairo::Context::new(&surface).context("Creating new cairo Context failed")?;
+            page.render_for_printing(&cx);
+        }
+        let data = *surface
+            .finish_output_stream()
+            .map_err(|e| {
+                anyhow::anyhow!("Finishing Pdf page source output stream failed, Err: {e:?}")
+            })?
+            .downcast::<Vec<u8>>()
+            .map_err(|e| anyhow::anyhow!("Downcasting Pdf page source data failed, Err: {e:?}"))?;
+        Ok(Self {
+            data: glib::Bytes::from_owned(data),
+            page_borders,
+            id: Self::new_id(),
+        })
+    }
+
+    fn new_id() -> u64 {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// The parsed document, which is only parsed once per thread.
+    fn document(&self) -> anyhow::Result<poppler::Document> {
+        PDF_PAGE_SOURCE_DOCUMENTS.with_borrow_mut(|documents| {
+            if let Some(doc) = documents.get(&self.id) {
+                return Ok(doc.clone());
+            }
+            let doc = poppler::Document::from_bytes(&self.data, None)?;
+            if documents.len() >= PDF_PAGE_SOURCE_DOCUMENTS_MAX {
+                documents.clear();
+            }
+            documents.insert(self.id, doc.clone());
+            Ok(doc)
+        })
+    }
+
+    /// Renders the page into a bitmap image with the given size in pixels.
+    fn render_image(&self, pixel_width: u32, pixel_height: u32) -> anyhow::Result<render::Image> {
+        let surface = cairo::ImageSurface::create(
+            cairo::Format::ARgb32,
+            pixel_width as i32,
+            pixel_height as i32,
+        )
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Creating image surface for rendering Pdf page source failed, Err: {e:?}"
+            )
+        })?;
+        let bounds = Aabb::new(
+            na::point![0.0, 0.0],
+            na::point![f64::from(pixel_width), f64::from(pixel_height)],
+        );
+        {
+            let cx = cairo::Context::new(&surface).context("Creating new cairo Context failed")?;
+            self.render(&cx, bounds)?;
+        }
+        render::Image::try_from_cairo_surface(surface, bounds)
+    }
+
+    /// Renders the page into the given rect, in the current coordinate space of the context.
+    fn render(&self, cx: &cairo::Context, rect: Aabb) -> anyhow::Result<()> {
+        let doc = self.document()?;
+        let page = doc
+            .page(0)
+            .ok_or_else(|| anyhow::anyhow!("Pdf page source does not contain a page"))?;
+        let intrinsic_size = page.size();
+
+        cx.save()?;
+        cx.rectangle(
+            rect.mins[0],
+            rect.mins[1],
+            rect.extents()[0],
+            rect.extents()[1],
+        );
+        cx.clip();
+        cx.translate(rect.mins[0], rect.mins[1]);
+        cx.scale(
+            rect.extents()[0] / intrinsic_size.0,
+            rect.extents()[1] / intrinsic_size.1,
+        );
+
+        // Set margin to white
+        cx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+        cx.paint()?;
+
+        page.render_for_printing(cx);
+
+        if self.page_borders {
+            draw_page_border(cx, intrinsic_size)?;
+        }
+        cx.restore()?;
+        Ok(())
+    }
+}
+
+/// Draws an outline around a Pdf page with the given intrinsic size.
+fn draw_page_border(cx: &cairo::Context, intrinsic_size: (f64, f64)) -> anyhow::Result<()> {
+    cx.set_source_rgba(
+        color::GNOME_REDS[4].as_rgba().0,
+        color::GNOME_REDS[4].as_rgba().1,
+        color::GNOME_REDS[4].as_rgba().2,
+        1.0,
+    );
+
+    let line_width = 1.0;
+    cx.set_line_width(line_width);
+    cx.rectangle(
+        line_width * 0.5,
+        line_width * 0.5,
+        intrinsic_size.0 - line_width,
+        intrinsic_size.1 - line_width,
+    );
+    cx.stroke()?;
+    Ok(())
+}
+
+/// Draws the placeholder for a linked image whose file could not be loaded.
+fn draw_broken_link_placeholder(cx: &mut impl piet::RenderContext, rect: Aabb) {
+    const FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[1];
+    const LINE_COLOR: piet::Color = color::GNOME_REDS[3];
+    let line_width = (rect.extents().min() * 0.01).clamp(1.0, 4.0);
+    let rect = rect.to_kurbo_rect();
+
+    cx.fill(rect, &FILL_COLOR);
+    cx.stroke(rect.inset(-line_width * 0.5), &LINE_COLOR, line_width);
+    cx.stroke(
+        kurbo::Line::new((rect.x0, rect.y0), (rect.x1, rect.y1)),
+        &LINE_COLOR,
+        line_width,
+    );
+    cx.stroke(
+        kurbo::Line::new((rect.x0, rect.y1), (rect.x1, rect.y0)),
+        &LINE_COLOR,
+        line_width,
+    );
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default, rename = "bitmapimage")]
+#[serde(from = "BitmapImageRepr", into = "BitmapImageRepr")]
 pub struct BitmapImage {
     /// The bitmap image.
     ///
     /// The bounds field of the image should not be used to determine the stroke bounds.
     /// Use rectangle.bounds() instead.
-    #[serde(rename = "image")]
     pub image: render::Image,
-    #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    pub compositing: Compositing,
+    /// The source Pdf page, when the image was imported from a Pdf.
+    pub pdf_page: Option<PdfPageSource>,
+    /// The image encoded as Jpeg, when it was compressed.
+    ///
+    /// It is saved in place of the pixel data, which is decoded from it when loading.
+    pub jpeg_data: Option<glib::Bytes>,
+    /// The image file, when the image is linked instead of embedded.
+    ///
+    /// The pixel data is not saved. It is not re-loaded while deserializing, but only when requested through
+    /// [BitmapImage::load_linked_file()], until then the image is drawn as a broken link.
+    pub linked_path: Option<PathBuf>,
 }
 
 impl Default for BitmapImage {
@@ -36,11 +251,151 @@ impl Default for BitmapImage {
         Self {
             image: render::Image::default(),
             rectangle: Rectangle::default(),
+            compositing: Compositing::default(),
+            pdf_page: None,
+            jpeg_data: None,
+            linked_path: None,
+        }
+    }
+}
+
+/// The saved representation of a [BitmapImage].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename = "bitmapimage")]
+struct BitmapImageRepr {
+    /// The image, with empty pixel data when the Jpeg data is present or the image is linked.
+    #[serde(rename = "image")]
+    image: render::Image,
+    #[serde(rename = "rectangle")]
+    rectangle: Rectangle,
+    #[serde(default, rename = "compositing")]
+    compositing: Compositing,
+    #[serde(rename = "pdf_page")]
+    pdf_page: Option<PdfPageSource>,
+    /// Is (de)serialized with base64 encoding, empty when the image is not compressed.
+    #[serde(rename = "jpeg_data", with = "crate::utils::glib_bytes_base64")]
+    jpeg_data: glib::Bytes,
+    #[serde(rename = "linked_path")]
+    linked_path: Option<PathBuf>,
+}
+
+impl Default for BitmapImageRepr {
+    fn default() -> Self {
+        BitmapImage::default().into()
+    }
+}
+
+impl From<BitmapImage> for BitmapImageRepr {
+    fn from(bitmapimage: BitmapImage) -> Self {
+        let mut image = bitmapimage.image;
+        let jpeg_data = match bitmapimage.jpeg_data {
+            Some(jpeg_data) if bitmapimage.linked_path.is_none() => jpeg_data,
+            _ => glib::Bytes::from_owned(Vec::new()),
+        };
+        // Only one representation of the image is saved, the others are restored from it when loading
+        if bitmapimage.linked_path.is_some()
+            || !jpeg_data.is_empty()
+            || bitmapimage.pdf_page.is_some()
+        {
+            image.data = glib::Bytes::from_owned(Vec::new());
+        }
+        Self {
+            image,
+            rectangle: bitmapimage.rectangle,
+            compositing: bitmapimage.compositing,
+            pdf_page: bitmapimage.pdf_page,
+            jpeg_data,
+            linked_path: bitmapimage.linked_path,
+        }
+    }
+}
+
+impl From<BitmapImageRepr> for BitmapImage {
+    fn from(repr: BitmapImageRepr) -> Self {
+        let mut image = repr.image;
+        let jpeg_data = if repr.jpeg_data.is_empty() {
+            None
+        } else {
+            match render::Image::try_from_encoded_bytes(&repr.jpeg_data) {
+                Ok(decoded) => image = decoded,
+                Err(e) => {
+                    // The Jpeg data is kept so that it is not lost when saving again,
+                    // until then the image is transparent.
+                    error!("Decoding compressed bitmap image data failed, Err: {e:?}");
+                    image.data = glib::Bytes::from_owned(vec![
+                        0;
+                        image.pixel_width as usize
+                            * image.pixel_height as usize
+                            * 4
+                    ]);
+                }
+            }
+            Some(repr.jpeg_data)
+        };
+        if let Some(pdf_page) = repr.pdf_page.as_ref().filter(|_| image.data.is_empty()) {
+            match pdf_page.render_image(image.pixel_width, image.pixel_height) {
+                Ok(rendered) => image = rendered,
+                Err(e) => {
+                    error!("Rendering bitmap image from its Pdf page source failed, Err: {e:?}");
+                    image.data = glib::Bytes::from_owned(vec![
+                        0;
+                        image.pixel_width as usize
+                            * image.pixel_height as usize
+                            * 4
+                    ]);
+                }
+            }
+        }
+        Self {
+            image,
+            rectangle: repr.rectangle,
+            compositing: repr.compositing,
+            pdf_page: repr.pdf_page,
+            jpeg_data,
+            linked_path: repr.linked_path,
         }
     }
 }
 
 impl Content for BitmapImage {
+    fn gen_images(
+        &self,
+        viewport: Aabb,
+        image_scale: f64,
+    ) -> Result<GeneratedContentImages, anyhow::Error> {
+        let bounds = self.bounds();
+        // Pdf pages are re-rendered from their source, so that they stay sharp at every zoom level
+        let gen_image = |bounds: Aabb| -> anyhow::Result<render::Image> {
+            if self.pdf_page.is_some() {
+                render::Image::gen_with_cairo(
+                    |cairo_cx| self.draw_to_cairo(cairo_cx, image_scale),
+                    bounds,
+                    image_scale,
+                )
+            } else {
+                render::Image::gen_with_piet(
+                    |piet_cx| self.draw(piet_cx, image_scale),
+                    bounds,
+                    image_scale,
+                )
+            }
+        };
+
+        if viewport.contains(&bounds) {
+            Ok(GeneratedContentImages::Full(vec![gen_image(bounds)?]))
+        } else if let Some(intersection_bounds) = viewport.intersection(&bounds) {
+            Ok(GeneratedContentImages::Partial {
+                images: vec![gen_image(intersection_bounds)?],
+                viewport,
+            })
+        } else {
+            Ok(GeneratedContentImages::Partial {
+                images: vec![],
+                viewport,
+            })
+        }
+    }
+
     fn update_geometry(&mut self) {}
 }
 
@@ -51,6 +406,12 @@ impl Drawable for BitmapImage {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         cx.transform(self.rectangle.transform.affine.to_kurbo());
 
+        if self.is_broken_link() {
+            draw_broken_link_placeholder(cx, self.rectangle.cuboid.local_aabb());
+            cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            return Ok(());
+        }
+
         let piet_image = cx
             .make_image(
                 self.image.pixel_width as usize,
@@ -67,6 +428,21 @@ impl Drawable for BitmapImage {
 
         Ok(())
     }
+
+    fn draw_to_cairo(&self, cx: &cairo::Context, image_scale: f64) -> anyhow::Result<()> {
+        let Some(pdf_page) = &self.pdf_page else {
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(cx);
+            self.draw(&mut piet_cx, image_scale)?;
+            return piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"));
+        };
+        let [xx, yx, xy, yy, x0, y0] = self.rectangle.transform.affine.to_kurbo().as_coeffs();
+
+        cx.save()?;
+        cx.transform(cairo::Matrix::new(xx, yx, xy, yy, x0, y0));
+        let res = pdf_page.render(cx, self.rectangle.cuboid.local_aabb());
+        cx.restore()?;
+        res
+    }
 }
 
 impl Shapeable for BitmapImage {
@@ -98,6 +474,11 @@ impl Transformable for BitmapImage {
 }
 
 impl BitmapImage {
+    /// The maximum size of linked image files that are loaded.
+    pub const LINKED_FILE_MAX_SIZE: u64 = 256 * 1024 * 1024;
+    /// The maximum width and height in pixels of images rendered from strokes, the limit of cairo image surfaces.
+    pub const RENDERED_MAX_DIMENSION: f64 = 32767.0;
+
     pub fn from_image_bytes(
         bytes: &[u8],
         pos: na::Vector2<f64>,
@@ -123,7 +504,198 @@ impl BitmapImage {
             cuboid: p2d::shape::Cuboid::new(size * 0.5),
             transform,
         };
-        Ok(Self { image, rectangle })
+        Ok(Self {
+            image,
+            rectangle,
+            compositing: Compositing::default(),
+            pdf_page: None,
+            jpeg_data: None,
+            linked_path: None,
+        })
+    }
+
+    /// Renders the strokes in the given order into a single image covering their bounds.
+    pub fn from_strokes<'a>(
+        strokes: impl IntoIterator<Item = &'a Stroke>,
+        image_scale: f64,
+    ) -> Result<Self, anyhow::Error> {
+        let strokes = strokes.into_iter().collect::<Vec<&Stroke>>();
+        let bounds = strokes
+            .iter()
+            .map(|stroke| stroke.bounds())
+            .reduce(|acc, bounds| acc.merged(&bounds))
+            .ok_or_else(|| anyhow::anyhow!("No strokes to render into a bitmap image"))?;
+        if !image_scale.is_finite() || image_scale <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Invalid image scale {image_scale} for rendering strokes into a bitmap image"
+            ));
+        }
+        // The rendered image is loosened by one unit on each side
+        let (width, height) = (
+            ((bounds.extents()[0] + 2.0) * image_scale).round(),
+            ((bounds.extents()[1] + 2.0) * image_scale).round(),
+        );
+        if width > Self::RENDERED_MAX_DIMENSION || height > Self::RENDERED_MAX_DIMENSION {
+            return Err(anyhow::anyhow!(
+                "Rendering strokes into a bitmap image with dimensions ({width}, {height}) exceeds the maximum of {} pixels, a lower resolution is needed",
+                Self::RENDERED_MAX_DIMENSION
+            ));
+        }
+        let image = render::Image::gen_with_cairo(
+            |cx| {
+                for stroke in strokes {
+                    stroke.draw_to_cairo(cx, image_scale)?;
+                }
+                Ok(())
+            },
+            bounds,
+            image_scale,
+        )?;
+        Ok(Self {
+            rectangle: image.rect,
+            image,
+            ..Default::default()
+        })
+    }
+
+    /// Loads the image from the file and links it instead of embedding it.
+    pub fn from_linked_file(
+        path: &Path,
+        pos: na::Vector2<f64>,
+        size_option: ImageSizeOption,
+    ) -> Result<Self, anyhow::Error> {
+        let bytes = Self::read_linked_file(path)?;
+        let mut bitmapimage = Self::from_image_bytes(&bytes, pos, size_option)?;
+        bitmapimage.linked_path = Some(path.to_path_buf());
+        Ok(bitmapimage)
+    }
+
+    /// Reads a linked image file.
+    ///
+    /// Only regular files up to [BitmapImage::LINKED_FILE_MAX_SIZE] are read,
+    /// so that links to devices, pipes or huge files can't block or exhaust the memory.
+    fn read_linked_file(path: &Path) -> anyhow::Result<Vec<u8>> {
+        let metadata = std::fs::metadata(path)
+            .with_context(|| format!("Reading metadata of linked image file {path:?} failed"))?;
+        if !metadata.is_file() {
+            return Err(anyhow::anyhow!(
+                "Linked image path {path:?} is not a regular file"
+            ));
+        }
+        if metadata.len() > Self::LINKED_FILE_MAX_SIZE {
+            return Err(anyhow::anyhow!(
+                "Linked image file {path:?} exceeds the maximum size of {} bytes",
+                Self::LINKED_FILE_MAX_SIZE
+            ));
+        }
+        let mut bytes = Vec::with_capacity(metadata.len() as usize);
+        // The file might have grown in the meantime
+        std::fs::File::open(path)
+            .with_context(|| format!("Opening linked image file {path:?} failed"))?
+            .take(Self::LINKED_FILE_MAX_SIZE + 1)
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("Reading linked image file {path:?} failed"))?;
+        if bytes.len() as u64 > Self::LINKED_FILE_MAX_SIZE {
+            return Err(anyhow::anyhow!(
+                "Linked image file {path:?} exceeds the maximum size of {} bytes",
+                Self::LINKED_FILE_MAX_SIZE
+            ));
+        }
+        Ok(bytes)
+    }
+
+    /// Loads the pixel data of a linked image from its file.
+    ///
+    /// On failure the image keeps the data it was loaded with. Does nothing for embedded images.
+    pub fn load_linked_file(&mut self) -> anyhow::Result<()> {
+        let Some(linked_path) = &self.linked_path else {
+            return Ok(());
+        };
+        let bytes = Self::read_linked_file(linked_path)?;
+        self.image = render::Image::try_from_encoded_bytes(&bytes)?;
+        Ok(())
+    }
+
+    /// Whether the image is linked, but its file could not be loaded.
+    pub fn is_broken_link(&self) -> bool {
+        self.linked_path.is_some() && self.image.data.is_empty()
+    }
+
+    /// Links the image to a new file and loads it, keeping the current position and size.
+    pub fn relink(&mut self, path: &Path) -> anyhow::Result<()> {
+        let bytes = Self::read_linked_file(path)?;
+        self.image = render::Image::try_from_encoded_bytes(&bytes)?;
+        self.jpeg_data = None;
+        self.linked_path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Compresses the image with the given options.
+    ///
+    /// Images larger than the max dimension are downscaled. Opaque images are then encoded as Jpeg,
+    /// images with transparency are only downscaled since Jpeg has no alpha channel.
+    /// Already compressed images are not compressed again if they don't need to be downscaled,
+    /// otherwise they are decoded from their original Jpeg data. When that fails, the image is kept as it is.
+    /// The Pdf page source of compressed images is removed, because it would be rendered instead of the compressed image.
+    ///
+    /// Returns whether the image was changed.
+    pub fn compress(&mut self, compression: &ImageCompression) -> anyhow::Result<bool> {
+        // Linked images are not saved, so there is nothing to compress
+        if self.linked_path.is_some() {
+            return Ok(false);
+        }
+        let max_dimension = compression.max_dimension.clamp(
+            ImageCompression::MAX_DIMENSION_MIN,
+            ImageCompression::MAX_DIMENSION_MAX,
+        );
+        let needs_downscale = self.image.pixel_width.max(self.image.pixel_height) > max_dimension;
+        if self.jpeg_data.is_some() && !needs_downscale {
+            return Ok(false);
+        }
+
+        let mut dynamic_image = match &self.jpeg_data {
+            Some(jpeg_data) => image::load_from_memory(jpeg_data)
+                .context("Decoding the original Jpeg data of the bitmap image failed")?,
+            None => image::DynamicImage::ImageRgba8(self.image.clone().into_imgbuf()?),
+        };
+        if needs_downscale {
+            dynamic_image = dynamic_image.resize(
+                max_dimension,
+                max_dimension,
+                image::imageops::FilterType::Lanczos3,
+            );
+        }
+        let opaque = !dynamic_image.color().has_alpha()
+            || dynamic_image
+                .as_rgba8()
+                .map(|imgbuf| imgbuf.pixels().all(|pixel| pixel[3] == u8::MAX))
+                .unwrap_or(false);
+
+        if opaque {
+            let mut jpeg_data = std::io::Cursor::new(Vec::new());
+            image::codecs::jpeg::JpegEncoder::new_with_quality(
+                &mut jpeg_data,
+                compression.jpeg_quality.clamp(
+                    ImageCompression::JPEG_QUALITY_MIN,
+                    ImageCompression::JPEG_QUALITY_MAX,
+                ),
+            )
+            .encode_image(&image::DynamicImage::ImageRgb8(dynamic_image.to_rgb8()))
+            .context("Encoding compressed bitmap image as Jpeg failed")?;
+            let jpeg_data = jpeg_data.into_inner();
+
+            self.image = render::Image::try_from_encoded_bytes(&jpeg_data)?;
+            self.jpeg_data = Some(glib::Bytes::from_owned(jpeg_data));
+            self.pdf_page = None;
+            Ok(true)
+        } else if needs_downscale {
+            self.image = render::Image::from(dynamic_image);
+            self.jpeg_data = None;
+            self.pdf_page = None;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 
     pub fn from_pdf_bytes(
@@ -132,9 +704,11 @@ impl BitmapImage {
         insert_pos: na::Vector2<f64>,
         page_range: Option<Range<u32>>,
         format: &Format,
+        progress: &Progress,
     ) -> Result<Vec<Self>, anyhow::Error> {
         let doc = poppler::Document::from_bytes(&glib::Bytes::from(to_be_read), None)?;
         let page_range = page_range.unwrap_or(0..doc.n_pages() as u32);
+        progress.set_total(page_range.len());
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
@@ -151,6 +725,7 @@ impl BitmapImage {
 
         let pngs = page_range
             .map(|page_i| {
+                progress.check_cancelled()?;
                 let page = doc
                     .page(page_i as i32)
                     .ok_or_else(|| anyhow::anyhow!("no page at index '{page_i}"))?;
@@ -188,24 +763,10 @@ impl BitmapImage {
 
                     if pdf_import_prefs.page_borders {
                         // Draw outline around page
-                        cx.set_source_rgba(
-                            color::GNOME_REDS[4].as_rgba().0,
-                            color::GNOME_REDS[4].as_rgba().1,
-                            color::GNOME_REDS[4].as_rgba().2,
-                            1.0,
-                        );
-
-                        let line_width = 1.0;
-                        cx.set_line_width(line_width);
-                        cx.rectangle(
-                            line_width * 0.5,
-                            line_width * 0.5,
-                            intrinsic_size.0 - line_width,
-                            intrinsic_size.1 - line_width,
-                        );
-                        cx.stroke()?;
+                        draw_page_border(&cx, intrinsic_size)?;
                     }
                 }
+                let pdf_page = PdfPageSource::from_page(&page, pdf_import_prefs.page_borders)?;
 
                 let mut png_data: Vec<u8> = Vec::new();
                 surface.write_to_png(&mut png_data)?;
@@ -222,14 +783,25 @@ impl BitmapImage {
                         PdfImportPageSpacing::OnePerDocumentPage => format.height(),
                     };
                 }
+                progress.advance();
 
-                Ok((png_data, image_pos, image_size))
+                Ok((png_data, pdf_page, image_pos, image_size))
             })
-            .collect::<anyhow::Result<Vec<(Vec<u8>, na::Vector2<f64>, na::Vector2<f64>)>>>()?;
+            .collect::<anyhow::Result<
+                Vec<(
+                    Vec<u8>,
+                    PdfPageSource,
+                    na::Vector2<f64>,
+                    na::Vector2<f64>,
+                )>,
+            >>()?;
 
         pngs.into_par_iter()
-            .map(|(png_data, pos, size)| {
-                Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))
+            .map(|(png_data, pdf_page, pos, size)| {
+                let mut bitmapimage =
+                    Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))?;
+                bitmapimage.pdf_page = Some(pdf_page);
+                Ok(bitmapimage)
             })
             .collect()
     }
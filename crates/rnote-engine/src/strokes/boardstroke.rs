@@ -0,0 +1,201 @@
+// Imports
+use super::textstroke::TextStyle;
+use super::{Compositing, Content};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use p2d::query::PointQuery;
+use piet::RenderContext;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// A kanban-style board: a frame with titled columns that sticky notes snap into.
+///
+/// Boards always stay axis-aligned, the height grows with the notes in the columns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "boardstroke")]
+pub struct BoardStroke {
+    /// The column titles.
+    #[serde(rename = "columns")]
+    pub columns: Vec<String>,
+    /// The position of the upper left corner.
+    #[serde(rename = "pos", with = "rnote_compose::serialize::na_vector2_f64_dp3")]
+    pub pos: na::Vector2<f64>,
+    #[serde(rename = "column_width")]
+    pub column_width: f64,
+    #[serde(rename = "height")]
+    pub height: f64,
+    /// The color of the frame and the column separators.
+    #[serde(rename = "color")]
+    pub color: Color,
+    /// The text style of the column titles.
+    #[serde(rename = "text_style")]
+    pub text_style: TextStyle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for BoardStroke {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            pos: na::Vector2::zeros(),
+            column_width: Self::COLUMN_WIDTH_DEFAULT,
+            height: Self::HEIGHT_MIN,
+            color: Color::BLACK,
+            text_style: TextStyle::default(),
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for BoardStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        // Boards are not rotated themselves, only their center follows the rotation
+        let board_center = self.bounds().center();
+        let mut isometry = na::Isometry2::identity();
+        isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
+        self.pos += isometry.transform_point(&board_center) - board_center;
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.pos = self.pos.component_mul(&scale);
+        self.column_width = (self.column_width * scale[0]).max(Self::COLUMN_WIDTH_MIN);
+        self.height *= scale[1];
+    }
+}
+
+impl Shapeable for BoardStroke {
+    fn bounds(&self) -> Aabb {
+        Aabb::new(
+            self.pos.into(),
+            (self.pos
+                + na::vector![
+                    self.column_width * self.columns.len().max(1) as f64,
+                    self.height
+                ])
+            .into(),
+        )
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Content for BoardStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for BoardStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        let bounds = self.bounds();
+        let color = piet::Color::from(self.color);
+        let frame = kurbo::RoundedRect::from_rect(bounds.to_kurbo_rect(), Self::CORNER_RADIUS);
+
+        cx.fill(
+            frame,
+            &piet::Color::from(Color {
+                a: self.color.a * Self::BACKGROUND_ALPHA,
+                ..self.color
+            }),
+        );
+
+        let mut lines = kurbo::BezPath::new();
+        let header_bottom = self.pos[1] + Self::HEADER_HEIGHT;
+        lines.move_to((bounds.mins[0], header_bottom));
+        lines.line_to((bounds.maxs[0], header_bottom));
+        for column in 1..self.columns.len() {
+            let x = self.pos[0] + self.column_width * column as f64;
+            lines.move_to((x, bounds.mins[1]));
+            lines.line_to((x, bounds.maxs[1]));
+        }
+        cx.stroke(lines, &color, Self::BORDER_WIDTH);
+        cx.stroke(frame, &color, Self::BORDER_WIDTH);
+
+        let mut text_style = self.text_style.clone();
+        text_style.set_max_width(Some(self.note_width()));
+        for (column, title) in self.columns.iter().enumerate() {
+            if let Ok(text_layout) = text_style.build_text_layout(cx.text(), title.clone()) {
+                cx.draw_text(
+                    &text_layout,
+                    kurbo::Point::new(
+                        self.pos[0] + self.column_width * column as f64 + Self::NOTE_GAP,
+                        self.pos[1] + Self::TITLE_PADDING,
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BoardStroke {
+    pub const COLUMN_WIDTH_DEFAULT: f64 = 280.0;
+    pub const COLUMN_WIDTH_MIN: f64 = 96.0;
+    pub const HEIGHT_MIN: f64 = 480.0;
+    pub const HEADER_HEIGHT: f64 = 48.0;
+    pub const TITLE_PADDING: f64 = 12.0;
+    /// The gap around the notes in the columns.
+    pub const NOTE_GAP: f64 = 20.0;
+    pub const CORNER_RADIUS: f64 = 8.0;
+    pub const BORDER_WIDTH: f64 = 2.0;
+    const BACKGROUND_ALPHA: f64 = 0.05;
+
+    pub fn new(
+        columns: Vec<String>,
+        upper_left_pos: na::Vector2<f64>,
+        color: Color,
+        text_style: TextStyle,
+    ) -> Self {
+        Self {
+            columns,
+            pos: upper_left_pos,
+            color,
+            text_style,
+            ..Default::default()
+        }
+    }
+
+    /// The column that contains the given position.
+    pub fn column_at(&self, pos: na::Vector2<f64>) -> Option<usize> {
+        if self.columns.is_empty() || !self.bounds().contains_local_point(&pos.into()) {
+            return None;
+        }
+        let column = ((pos[0] - self.pos[0]) / self.column_width).floor() as usize;
+        Some(column.min(self.columns.len() - 1))
+    }
+
+    /// The position of the upper left corner of the first note in the column.
+    pub fn column_notes_origin(&self, column: usize) -> na::Vector2<f64> {
+        self.pos
+            + na::vector![
+                self.column_width * column as f64 + Self::NOTE_GAP,
+                Self::HEADER_HEIGHT + Self::NOTE_GAP
+            ]
+    }
+
+    /// The width notes get when they are placed in a column.
+    pub fn note_width(&self) -> f64 {
+        (self.column_width - 2.0 * Self::NOTE_GAP).max(0.0)
+    }
+
+    /// Grow or shrink the height to fit columns that end at the given y coordinates.
+    pub fn fit_height(&mut self, column_ends: impl IntoIterator<Item = f64>) {
+        self.height = column_ends
+            .into_iter()
+            .map(|end| end + Self::NOTE_GAP - self.pos[1])
+            .fold(Self::HEIGHT_MIN, f64::max);
+    }
+}
@@ -1,6 +1,6 @@
 // Imports
 use super::content::GeneratedContentImages;
-use super::Content;
+use super::{Compositing, Content, ShapeStroke};
 use crate::Drawable;
 use crate::{
     render::{self},
@@ -9,8 +9,9 @@ use crate::{
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::AabbExt;
 use rnote_compose::penpath::{Element, Segment};
-use rnote_compose::shapes::Shapeable;
-use rnote_compose::style::Composer;
+use rnote_compose::shapes::{Shape, Shapeable};
+use rnote_compose::style::smooth::{self, SmoothOptions};
+use rnote_compose::style::{Composer, PressureCurve};
 use rnote_compose::transform::Transformable;
 use rnote_compose::{PenPath, Style};
 use serde::{Deserialize, Serialize};
@@ -23,6 +24,8 @@ pub struct BrushStroke {
     pub path: PenPath,
     #[serde(default, rename = "style")]
     pub style: Style,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
     // since the path can have many hitboxes, we store them here and update them when the stroke geometry changes
     #[serde(skip)]
     hitboxes: Vec<Aabb>,
@@ -94,6 +97,24 @@ impl Content for BrushStroke {
                         image_scale,
                     );
 
+                    match image {
+                        Ok(image) => vec![image],
+                        Err(e) => {
+                            error!("Generating images for brushstroke failed , Err: {e:?}");
+                            vec![]
+                        }
+                    }
+                }
+                Style::Custom(options) => {
+                    let image = render::Image::gen_with_piet(
+                        |piet_cx| {
+                            self.path.draw_composed(piet_cx, options);
+                            Ok(())
+                        },
+                        bounds,
+                        image_scale,
+                    );
+
                     match image {
                         Ok(image) => vec![image],
                         Err(e) => {
@@ -162,6 +183,35 @@ impl Content for BrushStroke {
                         prev = seg.end();
                     }
 
+                    images
+                }
+                Style::Custom(options) => {
+                    let mut options = options.clone();
+                    let mut images = Vec::with_capacity(self.path.segments.len());
+
+                    let mut prev = self.path.start;
+                    for seg in self.path.segments.iter() {
+                        let seg_path = PenPath::new_w_segments(prev, [*seg]);
+                        let image = render::Image::gen_with_piet(
+                            |piet_cx| {
+                                seg_path.draw_composed(piet_cx, &options);
+                                Ok(())
+                            },
+                            seg_path.composed_bounds(&options),
+                            image_scale,
+                        );
+                        match image {
+                            Ok(image) => images.push(image),
+                            Err(e) => {
+                                error!("generating images for brushstroke failed , Err: {e:?}");
+                            }
+                        }
+
+                        options.advance_seed();
+
+                        prev = seg.end();
+                    }
+
                     images
                 }
             }
@@ -216,6 +266,7 @@ impl Drawable for BrushStroke {
                 unimplemented!()
             }
             Style::Textured(options) => self.path.draw_composed(cx, options),
+            Style::Custom(options) => self.path.draw_composed(cx, options),
         };
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -229,6 +280,7 @@ impl Shapeable for BrushStroke {
             Style::Smooth(options) => self.path.composed_bounds(options),
             Style::Rough(_options) => unimplemented!(),
             Style::Textured(options) => self.path.composed_bounds(options),
+            Style::Custom(options) => self.path.composed_bounds(options),
         }
     }
 
@@ -269,6 +321,7 @@ impl BrushStroke {
         let mut new_brushstroke = Self {
             path,
             style,
+            compositing: Compositing::default(),
             hitboxes: vec![],
         };
         new_brushstroke.update_geometry();
@@ -290,6 +343,34 @@ impl BrushStroke {
         self.update_geometry();
     }
 
+    /// Converts the stroke into a shape stroke, with the filled envelope of the stroke as polygon.
+    ///
+    /// Smooth strokes keep their pressure-varying width, the other styles are converted with a constant width.
+    /// Returns None if the stroke has no color.
+    pub fn to_outline_shapestroke(&self) -> Option<ShapeStroke> {
+        let color = self.style.stroke_color()?;
+        let options = match &self.style {
+            Style::Smooth(options) => options.clone(),
+            style => SmoothOptions {
+                stroke_width: style.stroke_width(),
+                pressure_curve: PressureCurve::Const,
+                tilt_sensitivity: 0.0,
+                ..Default::default()
+            },
+        };
+        let outline = smooth::compose_penpath_outline(&self.path, &options);
+        let mut shapestroke = ShapeStroke::new(
+            Shape::Polygon(outline),
+            Style::Smooth(SmoothOptions {
+                stroke_color: None,
+                fill_color: Some(color),
+                ..Default::default()
+            }),
+        );
+        shapestroke.compositing = self.compositing;
+        Some(shapestroke)
+    }
+
     // internal method generating the current hitboxes.
     fn gen_hitboxes_int(&self) -> Vec<Aabb> {
         let stroke_width = self.style.stroke_width();
@@ -367,6 +448,39 @@ impl BrushStroke {
                     image_scale,
                 )?;
 
+                Some(image)
+            }
+            Style::Custom(options) => {
+                let mut options = options.clone();
+                let path_len = self.path.segments.len();
+
+                (0..path_len.saturating_sub(n_last_segments)).for_each(|_| {
+                    options.advance_seed();
+                });
+
+                let start_el = self
+                    .path
+                    .segments
+                    .get(path_len.saturating_sub(n_last_segments).saturating_sub(1))
+                    .map(|s| s.end())
+                    .unwrap_or(self.path.start);
+
+                let range_path = PenPath::new_w_segments(
+                    start_el,
+                    self.path.segments[path_len.saturating_sub(n_last_segments)..]
+                        .iter()
+                        .copied(),
+                );
+
+                let image = render::Image::gen_with_piet(
+                    |piet_cx| {
+                        range_path.draw_composed(piet_cx, &options);
+                        Ok(())
+                    },
+                    range_path.composed_bounds(&options),
+                    image_scale,
+                )?;
+
                 Some(image)
             }
         };
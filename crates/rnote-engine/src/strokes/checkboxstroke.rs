@@ -0,0 +1,146 @@
+// Imports
+use super::textstroke::{RangedTextAttribute, TextAttribute, TextStyle};
+use super::{Compositing, Content};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use piet::RenderContext;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::Transform;
+use serde::{Deserialize, Serialize};
+
+/// A todo item with a checkbox in front of its label.
+///
+/// The box is drawn as a text glyph, so it ends up as text in exported documents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "checkboxstroke")]
+pub struct CheckboxStroke {
+    #[serde(rename = "label")]
+    pub label: String,
+    #[serde(rename = "checked")]
+    pub checked: bool,
+    /// The transformation.
+    ///
+    /// The translation part is the position of the upper left corner.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+    #[serde(rename = "text_style")]
+    pub text_style: TextStyle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for CheckboxStroke {
+    fn default() -> Self {
+        Self {
+            label: String::default(),
+            checked: false,
+            transform: Transform::default(),
+            text_style: TextStyle::default(),
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for CheckboxStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for CheckboxStroke {
+    fn bounds(&self) -> Aabb {
+        let untransformed_size = self
+            .text_style()
+            .untransformed_size(&mut piet_cairo::CairoText::new(), self.text())
+            .unwrap_or_else(|| na::Vector2::repeat(self.text_style.font_size))
+            .maxs(&na::vector![1.0, 1.0]);
+
+        self.transform
+            .transform_aabb(Aabb::new(na::point![0.0, 0.0], untransformed_size.into()))
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Content for CheckboxStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for CheckboxStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+        if let Ok(text_layout) = self
+            .layout_text_style()
+            .build_text_layout(cx.text(), self.text())
+        {
+            cx.transform(self.transform.to_kurbo());
+            cx.draw_text(&text_layout, kurbo::Point::new(0.0, 0.0));
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+impl CheckboxStroke {
+    pub const GLYPH_UNCHECKED: &'static str = "☐";
+    pub const GLYPH_CHECKED: &'static str = "☑";
+
+    pub fn new(label: String, upper_left_pos: na::Vector2<f64>, text_style: TextStyle) -> Self {
+        Self {
+            label,
+            checked: false,
+            transform: Transform::new_w_isometry(na::Isometry2::new(upper_left_pos, 0.0)),
+            text_style,
+            compositing: Compositing::default(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+    }
+
+    fn glyph(&self) -> &'static str {
+        if self.checked {
+            Self::GLYPH_CHECKED
+        } else {
+            Self::GLYPH_UNCHECKED
+        }
+    }
+
+    /// The glyph followed by the label.
+    fn text(&self) -> String {
+        format!("{} {}", self.glyph(), self.label)
+    }
+
+    /// Labels of checked items are struck through.
+    fn layout_text_style(&self) -> TextStyle {
+        let mut text_style = self.text_style.clone();
+        if self.checked {
+            let label_start = self.glyph().len() + 1;
+            text_style.ranged_text_attributes = vec![RangedTextAttribute {
+                range: label_start..label_start + self.label.len(),
+                attribute: TextAttribute::Strikethrough(true),
+            }];
+        }
+        text_style
+    }
+}
@@ -0,0 +1,122 @@
+// Imports
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// The mode in which a stroke is blended with the content beneath it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "blend_mode")]
+pub enum BlendMode {
+    #[serde(rename = "normal")]
+    Normal,
+    /// Darkens, like overlapping layers of ink. Useful for highlighters.
+    #[serde(rename = "multiply")]
+    Multiply,
+    /// Lightens, like overlapping projections of light.
+    #[serde(rename = "screen")]
+    Screen,
+    /// Multiplies dark and screens light parts of the content beneath. Useful for shading.
+    #[serde(rename = "overlay")]
+    Overlay,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl std::str::FromStr for BlendMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "multiply" => Ok(Self::Multiply),
+            "screen" => Ok(Self::Screen),
+            "overlay" => Ok(Self::Overlay),
+            s => Err(anyhow::anyhow!(
+                "BlendMode from_string failed, invalid name: {s}"
+            )),
+        }
+    }
+}
+
+impl Display for BlendMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlendMode::Normal => write!(f, "normal"),
+            BlendMode::Multiply => write!(f, "multiply"),
+            BlendMode::Screen => write!(f, "screen"),
+            BlendMode::Overlay => write!(f, "overlay"),
+        }
+    }
+}
+
+impl From<BlendMode> for cairo::Operator {
+    fn from(value: BlendMode) -> Self {
+        match value {
+            BlendMode::Normal => cairo::Operator::Over,
+            BlendMode::Multiply => cairo::Operator::Multiply,
+            BlendMode::Screen => cairo::Operator::Screen,
+            BlendMode::Overlay => cairo::Operator::Overlay,
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl From<BlendMode> for gtk4::gsk::BlendMode {
+    fn from(value: BlendMode) -> Self {
+        match value {
+            BlendMode::Normal => gtk4::gsk::BlendMode::Default,
+            BlendMode::Multiply => gtk4::gsk::BlendMode::Multiply,
+            BlendMode::Screen => gtk4::gsk::BlendMode::Screen,
+            BlendMode::Overlay => gtk4::gsk::BlendMode::Overlay,
+        }
+    }
+}
+
+/// How a stroke is composited onto the content beneath it.
+///
+/// Unlike the alpha of the stroke colors, this is applied to the stroke as a whole when it is drawn,
+/// so overlapping parts of the same stroke do not add up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "compositing")]
+pub struct Compositing {
+    /// The opacity of the stroke, between 0.0 and 1.0.
+    #[serde(rename = "opacity", with = "rnote_compose::serialize::f64_dp3")]
+    pub opacity: f64,
+    #[serde(rename = "blend_mode")]
+    pub blend_mode: BlendMode,
+}
+
+impl Default for Compositing {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            blend_mode: BlendMode::default(),
+        }
+    }
+}
+
+impl Compositing {
+    /// Whether drawing with this compositing is the same as drawing the stroke directly.
+    pub fn is_identity(&self) -> bool {
+        self.opacity >= 1.0 && self.blend_mode == BlendMode::Normal
+    }
+
+    pub fn set_opacity(&mut self, opacity: f64) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    /// The attributes of a Svg group element that applies the compositing to its content.
+    pub(crate) fn svg_group_attributes(&self) -> String {
+        let mut attributes = String::new();
+        if self.opacity < 1.0 {
+            attributes.push_str(&format!(" opacity=\"{:.3}\"", self.opacity));
+        }
+        if self.blend_mode != BlendMode::Normal {
+            attributes.push_str(&format!(" style=\"mix-blend-mode:{}\"", self.blend_mode));
+        }
+        attributes
+    }
+}
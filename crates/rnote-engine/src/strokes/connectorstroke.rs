@@ -0,0 +1,323 @@
+// Imports
+use super::{Compositing, Content};
+use crate::store::StrokeKey;
+use crate::Drawable;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
+use rnote_compose::shapes::{Line, Shapeable};
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// How a connector is routed between its ends.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "connector_routing")]
+pub enum ConnectorRouting {
+    /// A straight line.
+    #[serde(rename = "straight")]
+    Straight = 0,
+    /// Horizontal and vertical segments, bending halfway between the ends.
+    #[serde(rename = "elbow")]
+    Elbow,
+}
+
+impl Default for ConnectorRouting {
+    fn default() -> Self {
+        Self::Straight
+    }
+}
+
+impl TryFrom<u32> for ConnectorRouting {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "ConnectorRouting try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+/// The anchor of a stroke that a connector end is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "connector_attachment")]
+pub struct ConnectorAttachment {
+    #[serde(rename = "key")]
+    pub key: StrokeKey,
+    /// The index into the anchors of the stroke, see [ConnectorStroke::anchors()].
+    #[serde(rename = "anchor")]
+    pub anchor: usize,
+}
+
+/// An end of a connector.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename = "connector_end")]
+pub struct ConnectorEnd {
+    #[serde(rename = "pos", with = "rnote_compose::serialize::na_vector2_f64_dp3")]
+    pub pos: na::Vector2<f64>,
+    /// The anchor the end is attached to. The end follows it when the stroke is transformed.
+    #[serde(rename = "attachment")]
+    pub attachment: Option<ConnectorAttachment>,
+}
+
+impl ConnectorEnd {
+    pub fn new(pos: na::Vector2<f64>, attachment: Option<ConnectorAttachment>) -> Self {
+        Self { pos, attachment }
+    }
+}
+
+/// A line or arrow connecting two strokes, for flowcharts and diagrams.
+///
+/// Its ends can be attached to the anchors of other strokes, it then is re-routed when they are moved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "connectorstroke")]
+pub struct ConnectorStroke {
+    #[serde(rename = "start")]
+    pub start: ConnectorEnd,
+    #[serde(rename = "end")]
+    pub end: ConnectorEnd,
+    #[serde(rename = "routing")]
+    pub routing: ConnectorRouting,
+    /// Whether an arrow head is drawn at the end.
+    #[serde(rename = "arrow")]
+    pub arrow: bool,
+    #[serde(rename = "color")]
+    pub color: Color,
+    #[serde(rename = "width")]
+    pub width: f64,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for ConnectorStroke {
+    fn default() -> Self {
+        Self {
+            start: ConnectorEnd::default(),
+            end: ConnectorEnd::default(),
+            routing: ConnectorRouting::default(),
+            arrow: true,
+            color: Color::BLACK,
+            width: Self::WIDTH_DEFAULT,
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for ConnectorStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.start.pos += offset;
+        self.end.pos += offset;
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        let mut isometry = na::Isometry2::identity();
+        isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
+
+        self.start.pos = isometry.transform_point(&self.start.pos.into()).coords;
+        self.end.pos = isometry.transform_point(&self.end.pos.into()).coords;
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.start.pos = self.start.pos.component_mul(&scale);
+        self.end.pos = self.end.pos.component_mul(&scale);
+    }
+}
+
+impl Shapeable for ConnectorStroke {
+    fn bounds(&self) -> Aabb {
+        let mut bounds = Aabb::new_positive(self.start.pos.into(), self.end.pos.into());
+        for point in self
+            .route()
+            .into_iter()
+            .chain(self.arrow_head().into_iter().flatten())
+        {
+            bounds.take_point(point.into());
+        }
+        bounds.loosened(self.width * 0.5)
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.route()
+            .windows(2)
+            .flat_map(|segment| Line::new(segment[0], segment[1]).hitboxes())
+            .map(|hitbox| hitbox.loosened(self.width * 0.5))
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.route_path()
+    }
+}
+
+impl Content for ConnectorStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for ConnectorStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        let color = piet::Color::from(self.color);
+        cx.stroke_styled(
+            self.route_path(),
+            &color,
+            self.width,
+            &piet::StrokeStyle::new()
+                .line_join(piet::LineJoin::Round)
+                .line_cap(piet::LineCap::Round),
+        );
+        if let Some([tip, left, right]) = self.arrow_head() {
+            let mut head = kurbo::BezPath::new();
+            head.move_to(tip.to_kurbo_point());
+            head.line_to(left.to_kurbo_point());
+            head.line_to(right.to_kurbo_point());
+            head.close_path();
+            cx.fill(head, &color);
+        }
+        Ok(())
+    }
+}
+
+impl ConnectorStroke {
+    pub const WIDTH_DEFAULT: f64 = 2.0;
+    pub const WIDTH_MIN: f64 = 0.5;
+    pub const WIDTH_MAX: f64 = 20.0;
+    /// The distance to an anchor where connector ends get attached to it.
+    pub const ANCHOR_SNAP_DIST: f64 = 12.0;
+
+    pub fn new(
+        start: ConnectorEnd,
+        end: ConnectorEnd,
+        routing: ConnectorRouting,
+        arrow: bool,
+        color: Color,
+        width: f64,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            routing,
+            arrow,
+            color,
+            width,
+            compositing: Compositing::default(),
+        }
+    }
+
+    /// The anchors of a stroke with the given bounds that connectors can be attached to:
+    /// the centers of its edges.
+    pub fn anchors(bounds: Aabb) -> [na::Vector2<f64>; 4] {
+        let center = bounds.center().coords;
+        [
+            na::vector![center[0], bounds.mins[1]],
+            na::vector![bounds.maxs[0], center[1]],
+            na::vector![center[0], bounds.maxs[1]],
+            na::vector![bounds.mins[0], center[1]],
+        ]
+    }
+
+    /// The points the connector passes through.
+    pub fn route(&self) -> Vec<na::Vector2<f64>> {
+        let (start, end) = (self.start.pos, self.end.pos);
+        match self.routing {
+            ConnectorRouting::Straight => vec![start, end],
+            ConnectorRouting::Elbow => {
+                let mid_x = (start[0] + end[0]) * 0.5;
+                vec![
+                    start,
+                    na::vector![mid_x, start[1]],
+                    na::vector![mid_x, end[1]],
+                    end,
+                ]
+            }
+        }
+    }
+
+    fn route_path(&self) -> kurbo::BezPath {
+        let mut path = kurbo::BezPath::new();
+        let mut points = self.route().into_iter();
+        if let Some(first) = points.next() {
+            path.move_to(first.to_kurbo_point());
+            for point in points {
+                path.line_to(point.to_kurbo_point());
+            }
+        }
+        path
+    }
+
+    /// The tip and the two back corners of the arrow head, if the connector has one.
+    fn arrow_head(&self) -> Option<[na::Vector2<f64>; 3]> {
+        if !self.arrow {
+            return None;
+        }
+        let route = self.route();
+        let tip = *route.last()?;
+        // The last segment that is not degenerate determines the direction
+        let direction = route
+            .windows(2)
+            .rev()
+            .map(|segment| segment[1] - segment[0])
+            .find(|vec| vec.magnitude() > 0.0)?
+            .normalize();
+        let length = self.width * 3.0 + 6.0;
+        let back = tip - direction * length;
+        let orth = direction.orth_unit() * length * 0.5;
+        Some([tip, back + orth, back - orth])
+    }
+
+    /// The attachments of the ends.
+    pub(crate) fn attachments(&self) -> impl Iterator<Item = ConnectorAttachment> {
+        [self.start.attachment, self.end.attachment]
+            .into_iter()
+            .flatten()
+    }
+
+    /// Move the ends that are attached to the stroke with the given key onto its anchors.
+    pub(crate) fn reroute(&mut self, key: StrokeKey, anchors: &[na::Vector2<f64>; 4]) {
+        for end in [&mut self.start, &mut self.end] {
+            if let Some(pos) = end
+                .attachment
+                .filter(|attachment| attachment.key == key)
+                .and_then(|attachment| anchors.get(attachment.anchor))
+            {
+                end.pos = *pos;
+            }
+        }
+    }
+
+    /// Detach the ends that are attached to strokes for which `detach` returns true.
+    pub(crate) fn detach(&mut self, detach: impl Fn(StrokeKey) -> bool) {
+        for end in [&mut self.start, &mut self.end] {
+            if end
+                .attachment
+                .is_some_and(|attachment| detach(attachment.key))
+            {
+                end.attachment = None;
+            }
+        }
+    }
+
+    /// Replace the keys of the attachments with the ones returned by `map`. Ends for which it returns `None` are
+    /// detached.
+    pub(crate) fn remap_attachments(&mut self, map: impl Fn(StrokeKey) -> Option<StrokeKey>) {
+        for end in [&mut self.start, &mut self.end] {
+            end.attachment = end.attachment.and_then(|attachment| {
+                Some(ConnectorAttachment {
+                    key: map(attachment.key)?,
+                    ..attachment
+                })
+            });
+        }
+    }
+}
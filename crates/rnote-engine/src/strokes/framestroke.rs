@@ -0,0 +1,127 @@
+// Imports
+use super::textstroke::TextStyle;
+use super::{Compositing, Content};
+use crate::Drawable;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// A frame: a named rectangular region of the document, that can be exported as its own file.
+///
+/// The name is drawn above the upper left corner.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "framestroke")]
+pub struct FrameStroke {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "rectangle")]
+    pub rectangle: Rectangle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Transformable for FrameStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.rectangle.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.rectangle.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.rectangle.scale(scale);
+    }
+}
+
+impl Shapeable for FrameStroke {
+    fn bounds(&self) -> Aabb {
+        self.rectangle.bounds().merged(&self.label_bounds())
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        // Only the border and the name, so that the content inside the frame can still be selected
+        self.rectangle
+            .outline_lines()
+            .iter()
+            .flat_map(|line| line.hitboxes())
+            .map(|hitbox| hitbox.loosened(Self::BORDER_WIDTH * 0.5))
+            .chain(std::iter::once(self.label_bounds()))
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.rectangle.outline_path()
+    }
+}
+
+impl Content for FrameStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for FrameStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        let color = piet::Color::from(Self::COLOR);
+        cx.stroke_styled(
+            self.rectangle.outline_path(),
+            &color,
+            Self::BORDER_WIDTH,
+            &piet::StrokeStyle::new().dash_pattern(&Self::BORDER_DASH_PATTERN),
+        );
+
+        let mut text_style = TextStyle::default();
+        text_style.font_family = String::from("sans-serif");
+        text_style.font_size = Self::LABEL_FONT_SIZE;
+        text_style.color = Self::COLOR;
+        text_style.set_max_width(Some(self.rectangle.bounds().extents()[0]));
+        if let Ok(text_layout) = text_style.build_text_layout(cx.text(), self.name.clone()) {
+            cx.draw_text(
+                &text_layout,
+                self.label_bounds().mins.coords.to_kurbo_point(),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl FrameStroke {
+    /// Frames are guides, their color can't be changed.
+    pub const COLOR: Color = Color {
+        r: 0.208,
+        g: 0.518,
+        b: 0.894,
+        a: 1.0,
+    };
+    pub const BORDER_WIDTH: f64 = 1.5;
+    pub const LABEL_FONT_SIZE: f64 = 12.0;
+    /// The height of the area above the frame where the name is drawn.
+    pub const LABEL_HEIGHT: f64 = 20.0;
+    /// The margin between a frame added around the selection and the selected content.
+    pub const SELECTION_MARGIN: f64 = 12.0;
+    const BORDER_DASH_PATTERN: [f64; 2] = [6.0, 4.0];
+
+    pub fn new(name: String, rectangle: Rectangle) -> Self {
+        Self {
+            name,
+            rectangle,
+            compositing: Compositing::default(),
+        }
+    }
+
+    /// The region that is exported.
+    pub fn region(&self) -> Aabb {
+        self.rectangle.bounds()
+    }
+
+    fn label_bounds(&self) -> Aabb {
+        let bounds = self.rectangle.bounds();
+        Aabb::new(
+            na::point![bounds.mins[0], bounds.mins[1] - Self::LABEL_HEIGHT],
+            na::point![bounds.maxs[0], bounds.mins[1]],
+        )
+    }
+}
@@ -0,0 +1,127 @@
+// Imports
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "gradient_kind")]
+pub enum GradientKind {
+    #[serde(rename = "none")]
+    None = 0,
+    /// Blends the colors along a straight line.
+    #[serde(rename = "linear")]
+    Linear,
+    /// Blends the colors from the center outwards.
+    #[serde(rename = "radial")]
+    Radial,
+}
+
+impl Default for GradientKind {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl TryFrom<u32> for GradientKind {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("GradientKind try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// A gradient fill between two colors.
+///
+/// The gradient spans the bounds of the filled path, so it does not need to be updated when the path is moved or resized.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "gradient")]
+pub struct Gradient {
+    #[serde(rename = "kind")]
+    pub kind: GradientKind,
+    #[serde(rename = "start_color")]
+    pub start_color: Color,
+    #[serde(rename = "end_color")]
+    pub end_color: Color,
+    /// The direction of linear gradients (in radians).
+    #[serde(rename = "angle", with = "rnote_compose::serialize::f64_dp3")]
+    pub angle: f64,
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            kind: GradientKind::default(),
+            start_color: Color::BLACK,
+            end_color: Color::TRANSPARENT,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Gradient {
+    pub fn is_none(&self) -> bool {
+        self.kind == GradientKind::None
+    }
+
+    /// Fill the path with the gradient.
+    ///
+    /// piet-cairo creates cairo gradients from it, which end up as `<linearGradient>` or `<radialGradient>`
+    /// definitions when generating Svgs.
+    pub(crate) fn fill(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        path: &kurbo::BezPath,
+    ) -> anyhow::Result<()> {
+        use kurbo::Shape;
+
+        let bounds = path.bounding_box();
+        let center = bounds.center();
+        let stops = vec![
+            piet::GradientStop {
+                pos: 0.0,
+                color: piet::Color::from(self.start_color),
+            },
+            piet::GradientStop {
+                pos: 1.0,
+                color: piet::Color::from(self.end_color),
+            },
+        ];
+
+        let gradient = match self.kind {
+            GradientKind::None => return Ok(()),
+            GradientKind::Linear => {
+                let dir = kurbo::Vec2::from_angle(self.angle);
+                // The distance from the center to the bounds edge in the direction of the gradient
+                let half_extent =
+                    (0.5 * bounds.width() * dir.x).abs() + (0.5 * bounds.height() * dir.y).abs();
+                piet::FixedGradient::Linear(piet::FixedLinearGradient {
+                    start: center - dir * half_extent,
+                    end: center + dir * half_extent,
+                    stops,
+                })
+            }
+            GradientKind::Radial => piet::FixedGradient::Radial(piet::FixedRadialGradient {
+                center,
+                origin_offset: kurbo::Vec2::ZERO,
+                radius: 0.5 * bounds.size().to_vec2().hypot(),
+                stops,
+            }),
+        };
+        let brush = cx
+            .gradient(gradient)
+            .map_err(|e| anyhow::anyhow!("Creating gradient brush failed, Err: {e:?}"))?;
+        cx.fill(path.clone(), &brush);
+        Ok(())
+    }
+}
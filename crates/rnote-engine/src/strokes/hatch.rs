@@ -0,0 +1,218 @@
+// Imports
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "hatch_style")]
+pub enum HatchStyle {
+    #[serde(rename = "none")]
+    None = 0,
+    /// Parallel lines.
+    #[serde(rename = "hatch")]
+    Hatch,
+    /// Two sets of parallel lines, perpendicular to each other.
+    #[serde(rename = "cross_hatch")]
+    CrossHatch,
+}
+
+impl Default for HatchStyle {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl TryFrom<u32> for HatchStyle {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("HatchStyle try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// A hatch fill pattern for closed shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "hatch")]
+pub struct Hatch {
+    #[serde(rename = "style")]
+    pub style: HatchStyle,
+    /// The distance between the lines.
+    #[serde(rename = "spacing", with = "rnote_compose::serialize::f64_dp3")]
+    pub spacing: f64,
+    /// The angle of the lines (in radians).
+    #[serde(rename = "angle", with = "rnote_compose::serialize::f64_dp3")]
+    pub angle: f64,
+}
+
+impl Default for Hatch {
+    fn default() -> Self {
+        Self {
+            style: HatchStyle::default(),
+            spacing: 8.0,
+            angle: std::f64::consts::FRAC_PI_4,
+        }
+    }
+}
+
+impl Hatch {
+    pub const SPACING_MIN: f64 = 1.0;
+    pub const SPACING_MAX: f64 = 500.0;
+
+    pub fn is_none(&self) -> bool {
+        self.style == HatchStyle::None
+    }
+
+    fn spacing_clamped(&self) -> f64 {
+        self.spacing.clamp(Self::SPACING_MIN, Self::SPACING_MAX)
+    }
+
+    fn line_angles(&self) -> Vec<f64> {
+        match self.style {
+            HatchStyle::None => vec![],
+            HatchStyle::Hatch => vec![self.angle],
+            HatchStyle::CrossHatch => vec![self.angle, self.angle + std::f64::consts::FRAC_PI_2],
+        }
+    }
+
+    /// Draw the hatch lines inside the outline.
+    ///
+    /// The lines are aligned to the center of the outline bounds, the same way as in [Hatch::draw_to_cairo()].
+    pub(crate) fn draw(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        outline: &kurbo::BezPath,
+        color: Color,
+        line_width: f64,
+    ) -> anyhow::Result<()> {
+        use kurbo::Shape;
+
+        if self.is_none() {
+            return Ok(());
+        }
+        let spacing = self.spacing_clamped();
+        let bounds = outline.bounding_box();
+        let center = bounds.center();
+        let radius = 0.5 * bounds.size().to_vec2().hypot();
+        let n_lines = (radius / spacing).ceil() as i64;
+        let color = piet::Color::from(color);
+
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.clip(outline.clone());
+        for angle in self.line_angles() {
+            let dir = kurbo::Vec2::from_angle(angle);
+            let normal = dir.turn_90();
+            for i in -n_lines..=n_lines {
+                let p = center + normal * (i as f64 * spacing);
+                cx.stroke(
+                    kurbo::Line::new(p - dir * radius, p + dir * radius),
+                    &color,
+                    line_width,
+                );
+            }
+        }
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// Fill the outline with the hatch as a repeating cairo pattern.
+    ///
+    /// When drawing to a Svg surface, the hatch ends up as a `<pattern>` definition.
+    pub(crate) fn draw_to_cairo(
+        &self,
+        cx: &cairo::Context,
+        outline: &kurbo::BezPath,
+        color: Color,
+        line_width: f64,
+    ) -> anyhow::Result<()> {
+        use kurbo::Shape;
+
+        if self.is_none() {
+            return Ok(());
+        }
+        let spacing = self.spacing_clamped();
+        let center = outline.bounding_box().center();
+
+        // The tile contains lines on both of its edges, which make up one line when the tiles are repeated.
+        let tile = cairo::RecordingSurface::create(
+            cairo::Content::ColorAlpha,
+            Some(cairo::Rectangle::new(0.0, 0.0, spacing, spacing)),
+        )?;
+        {
+            let tile_cx = cairo::Context::new(&tile)?;
+            tile_cx.set_source_rgba(color.r, color.g, color.b, color.a);
+            tile_cx.set_line_width(line_width);
+            tile_cx.move_to(0.0, 0.0);
+            tile_cx.line_to(spacing, 0.0);
+            tile_cx.move_to(0.0, spacing);
+            tile_cx.line_to(spacing, spacing);
+            if self.style == HatchStyle::CrossHatch {
+                tile_cx.move_to(0.0, 0.0);
+                tile_cx.line_to(0.0, spacing);
+                tile_cx.move_to(spacing, 0.0);
+                tile_cx.line_to(spacing, spacing);
+            }
+            tile_cx.stroke()?;
+        }
+        let pattern = cairo::SurfacePattern::create(&tile);
+        pattern.set_extend(cairo::Extend::Repeat);
+        // The pattern matrix maps from user space to pattern space.
+        let mut matrix = cairo::Matrix::identity();
+        matrix.translate(center.x, center.y);
+        matrix.rotate(self.angle);
+        matrix.invert();
+        pattern.set_matrix(matrix);
+
+        cx.save()?;
+        cx.new_path();
+        append_kurbo_path(cx, outline);
+        cx.set_source(&pattern)?;
+        cx.fill()?;
+        cx.restore()?;
+        Ok(())
+    }
+}
+
+fn append_kurbo_path(cx: &cairo::Context, path: &kurbo::BezPath) {
+    let mut start = kurbo::Point::ZERO;
+    let mut last = kurbo::Point::ZERO;
+
+    for el in path.elements() {
+        match *el {
+            kurbo::PathEl::MoveTo(p) => {
+                cx.move_to(p.x, p.y);
+                start = p;
+                last = p;
+            }
+            kurbo::PathEl::LineTo(p) => {
+                cx.line_to(p.x, p.y);
+                last = p;
+            }
+            kurbo::PathEl::QuadTo(p1, p2) => {
+                // elevate to a cubic bezier curve
+                let c1 = last + (p1 - last) * (2.0 / 3.0);
+                let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+                cx.curve_to(c1.x, c1.y, c2.x, c2.y, p2.x, p2.y);
+                last = p2;
+            }
+            kurbo::PathEl::CurveTo(p1, p2, p3) => {
+                cx.curve_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                last = p3;
+            }
+            kurbo::PathEl::ClosePath => {
+                cx.close_path();
+                last = start;
+            }
+        }
+    }
+}
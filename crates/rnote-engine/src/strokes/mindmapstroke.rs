@@ -0,0 +1,541 @@
+// Imports
+use super::textstroke::TextStyle;
+use super::{Compositing, Content};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use p2d::query::PointQuery;
+use piet::{RenderContext, TextLayout};
+use rnote_compose::ext::{AabbExt, Vector2Ext};
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::{Color, Transform};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// How the nodes of a mind map are arranged.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "mind_map_layout")]
+pub enum MindMapLayout {
+    /// The root in the center, the children on rings around it.
+    #[serde(rename = "radial")]
+    Radial = 0,
+    /// The root on the left, the children in columns to the right of their parents.
+    #[serde(rename = "tree")]
+    Tree,
+}
+
+impl Default for MindMapLayout {
+    fn default() -> Self {
+        Self::Radial
+    }
+}
+
+impl TryFrom<u32> for MindMapLayout {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!("MindMapLayout try_from::<u32>() for value {} failed", value)
+        })
+    }
+}
+
+/// A node of a mind map with its text and child nodes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "mind_map_node")]
+pub struct MindMapNode {
+    #[serde(rename = "text")]
+    pub text: String,
+    /// Whether the children are hidden.
+    #[serde(rename = "collapsed")]
+    pub collapsed: bool,
+    #[serde(rename = "children")]
+    pub children: Vec<MindMapNode>,
+}
+
+impl MindMapNode {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            ..Default::default()
+        }
+    }
+
+    /// Parse an outline where the first line is the root and the following lines are its descendants,
+    /// nested by their indentation. A tab counts as four spaces.
+    ///
+    /// Returns `None` if the outline has no lines with text.
+    pub fn from_outline(outline: &str) -> Option<Self> {
+        let mut stack: Vec<(usize, MindMapNode)> = Vec::new();
+
+        for line in outline.lines() {
+            let text = line.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let indent = line
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .map(|c| if c == '\t' { 4 } else { 1 })
+                .sum::<usize>();
+            // The root is never popped, so lines that are not indented further than it become its children
+            while stack.len() > 1 && stack.last().is_some_and(|(i, _)| *i >= indent) {
+                let (_, node) = stack.pop().unwrap();
+                stack.last_mut().unwrap().1.children.push(node);
+            }
+            stack.push((indent, MindMapNode::new(text.to_string())));
+        }
+        while stack.len() > 1 {
+            let (_, node) = stack.pop().unwrap();
+            stack.last_mut().unwrap().1.children.push(node);
+        }
+        stack.pop().map(|(_, root)| root)
+    }
+
+    /// The node and its descendants as outline, indented with tabs.
+    pub fn to_outline(&self) -> String {
+        fn append(node: &MindMapNode, depth: usize, outline: &mut String) {
+            outline.push_str(&"\t".repeat(depth));
+            outline.push_str(&node.text);
+            outline.push('\n');
+            for child in node.children.iter() {
+                append(child, depth + 1, outline);
+            }
+        }
+        let mut outline = String::new();
+        append(self, 0, &mut outline);
+        outline
+    }
+
+    /// Take over the collapsed state from the nodes of the other tree at the same place and with the same text.
+    pub fn keep_collapsed_from(&mut self, other: &MindMapNode) {
+        if self.text != other.text {
+            return;
+        }
+        self.collapsed = other.collapsed;
+        for (child, other_child) in self.children.iter_mut().zip(other.children.iter()) {
+            child.keep_collapsed_from(other_child);
+        }
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&MindMapNode> {
+        match path.split_first() {
+            Some((&i, rest)) => self.children.get(i)?.node(rest),
+            None => Some(self),
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut MindMapNode> {
+        match path.split_first() {
+            Some((&i, rest)) => self.children.get_mut(i)?.node_mut(rest),
+            None => Some(self),
+        }
+    }
+}
+
+/// A node placed by the layout, in the coordinates of the mind map without its transform.
+#[derive(Debug, Clone)]
+pub struct MindMapNodeLayout {
+    /// The child indices leading from the root to the node.
+    pub path: Vec<usize>,
+    pub rect: Aabb,
+    /// The index of the parent node in the layout.
+    pub parent: Option<usize>,
+    /// Whether the node has children, shown or collapsed.
+    pub has_children: bool,
+    pub collapsed: bool,
+}
+
+/// A mind map: nodes with text, connected by edges to their parent, and arranged automatically.
+///
+/// The translation of the transform is the center of the root node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "mindmapstroke")]
+pub struct MindMapStroke {
+    #[serde(rename = "root")]
+    pub root: MindMapNode,
+    #[serde(rename = "layout")]
+    pub layout: MindMapLayout,
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+    /// The color of the node borders and the edges.
+    #[serde(rename = "color")]
+    pub color: Color,
+    #[serde(rename = "text_style")]
+    pub text_style: TextStyle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for MindMapStroke {
+    fn default() -> Self {
+        Self {
+            root: MindMapNode::default(),
+            layout: MindMapLayout::default(),
+            transform: Transform::default(),
+            color: Color::BLACK,
+            text_style: TextStyle::default(),
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for MindMapStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for MindMapStroke {
+    fn bounds(&self) -> Aabb {
+        self.transform.transform_aabb(self.untransformed_bounds())
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        // Only the nodes, so that the strokes in the gaps between them can still be selected
+        self.layout_nodes()
+            .into_iter()
+            .map(|node| {
+                self.transform
+                    .transform_aabb(node.rect.loosened(Self::INDICATOR_RADIUS))
+            })
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Content for MindMapStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for MindMapStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.transform.to_kurbo());
+
+        let nodes = self.layout_nodes();
+        let color = piet::Color::from(self.color);
+        let fill_color = piet::Color::from(Color {
+            a: self.color.a * Self::NODE_FILL_ALPHA,
+            ..self.color
+        });
+        let background_color = piet::Color::from(Color::WHITE);
+
+        // The edges first, so that the nodes are drawn on top of them
+        let mut edges = kurbo::BezPath::new();
+        for node in nodes.iter() {
+            let Some(parent) = node.parent.and_then(|i| nodes.get(i)) else {
+                continue;
+            };
+            match self.layout {
+                MindMapLayout::Radial => {
+                    edges.move_to(parent.rect.center().coords.to_kurbo_point());
+                    edges.line_to(node.rect.center().coords.to_kurbo_point());
+                }
+                MindMapLayout::Tree => {
+                    let start =
+                        kurbo::Point::new(parent.rect.maxs[0], parent.rect.center().coords[1]);
+                    let end = kurbo::Point::new(node.rect.mins[0], node.rect.center().coords[1]);
+                    let mid_x = (start.x + end.x) * 0.5;
+                    edges.move_to(start);
+                    edges.curve_to((mid_x, start.y), (mid_x, end.y), end);
+                }
+            }
+        }
+        cx.stroke(edges, &color, Self::EDGE_WIDTH);
+
+        let text_style = self.node_text_style();
+        for node in nodes.iter() {
+            let Some(node_data) = self.root.node(&node.path) else {
+                continue;
+            };
+            let shape =
+                kurbo::RoundedRect::from_rect(node.rect.to_kurbo_rect(), Self::CORNER_RADIUS);
+            let border_width = if node.parent.is_none() {
+                Self::ROOT_BORDER_WIDTH
+            } else {
+                Self::BORDER_WIDTH
+            };
+            // Covers the edges that pass underneath the translucent fill
+            cx.fill(shape, &background_color);
+            cx.fill(shape, &fill_color);
+            cx.stroke(shape, &color, border_width);
+            if let Ok(text_layout) = text_style.build_text_layout(cx.text(), node_data.text.clone())
+            {
+                cx.draw_text(
+                    &text_layout,
+                    (node.rect.mins.coords + na::Vector2::repeat(Self::NODE_PADDING))
+                        .to_kurbo_point(),
+                );
+            }
+
+            if node.has_children {
+                let center = Self::indicator_center(&node.rect);
+                let circle = kurbo::Circle::new(center.to_kurbo_point(), Self::INDICATOR_RADIUS);
+                let r = Self::INDICATOR_RADIUS * 0.5;
+                let mut sign = kurbo::BezPath::new();
+                sign.move_to((center[0] - r, center[1]));
+                sign.line_to((center[0] + r, center[1]));
+                if node.collapsed {
+                    sign.move_to((center[0], center[1] - r));
+                    sign.line_to((center[0], center[1] + r));
+                }
+                cx.fill(circle, &background_color);
+                cx.stroke(circle, &color, Self::BORDER_WIDTH);
+                cx.stroke(sign, &color, Self::BORDER_WIDTH);
+            }
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+impl MindMapStroke {
+    /// Node texts wrap at this width.
+    pub const NODE_TEXT_WIDTH_MAX: f64 = 160.0;
+    pub const NODE_PADDING: f64 = 8.0;
+    /// The gap between the nodes of neighboring levels.
+    pub const LEVEL_GAP: f64 = 48.0;
+    /// The gap between sibling nodes in the tree layout.
+    pub const SIBLING_GAP: f64 = 12.0;
+    pub const CORNER_RADIUS: f64 = 6.0;
+    pub const BORDER_WIDTH: f64 = 1.5;
+    pub const ROOT_BORDER_WIDTH: f64 = 3.0;
+    pub const EDGE_WIDTH: f64 = 1.5;
+    /// The radius of the indicator on nodes with children that shows whether they are collapsed.
+    pub const INDICATOR_RADIUS: f64 = 6.0;
+    const NODE_FILL_ALPHA: f64 = 0.12;
+
+    pub fn new(
+        root: MindMapNode,
+        layout: MindMapLayout,
+        upper_left_pos: na::Vector2<f64>,
+        color: Color,
+        text_style: TextStyle,
+    ) -> Self {
+        let mut mind_map = Self {
+            root,
+            layout,
+            color,
+            text_style,
+            ..Default::default()
+        };
+        let offset = upper_left_pos - mind_map.untransformed_bounds().mins.coords;
+        mind_map.transform = Transform::new_w_isometry(na::Isometry2::new(offset, 0.0));
+        mind_map
+    }
+
+    /// The nodes that are not hidden by a collapsed ancestor, placed by the layout.
+    ///
+    /// The nodes are ordered depth-first, parents always come before their children.
+    pub fn layout_nodes(&self) -> Vec<MindMapNodeLayout> {
+        let text_style = self.node_text_style();
+        let mut piet_text = piet_cairo::CairoText::new();
+
+        // Collect the visible nodes with their sizes and children
+        let mut nodes: Vec<(MindMapNodeLayout, Vec<usize>)> = Vec::new();
+        let mut pending = vec![(Vec::<usize>::new(), &self.root, None)];
+        while let Some((path, node, parent)) = pending.pop() {
+            let text_size = text_style
+                .build_text_layout(&mut piet_text, node.text.clone())
+                .map(|layout| {
+                    let size = layout.size();
+                    na::vector![size.width, size.height]
+                })
+                .unwrap_or_else(|_| na::Vector2::repeat(self.text_style.font_size));
+            let size = na::vector![
+                text_size[0].max(self.text_style.font_size),
+                text_size[1].max(self.text_style.font_size)
+            ] + na::Vector2::repeat(2.0 * Self::NODE_PADDING);
+            let index = nodes.len();
+            if let Some(parent) = parent {
+                nodes[parent].1.push(index);
+            }
+            nodes.push((
+                MindMapNodeLayout {
+                    path: path.clone(),
+                    rect: Aabb::new(na::point![0.0, 0.0], size.into()),
+                    parent,
+                    has_children: !node.children.is_empty(),
+                    collapsed: node.collapsed,
+                },
+                Vec::new(),
+            ));
+            if !node.collapsed {
+                // Reversed, so that the children are popped in order
+                for (i, child) in node.children.iter().enumerate().rev() {
+                    let mut child_path = path.clone();
+                    child_path.push(i);
+                    pending.push((child_path, child, Some(index)));
+                }
+            }
+        }
+
+        match self.layout {
+            MindMapLayout::Radial => Self::arrange_radial(&mut nodes),
+            MindMapLayout::Tree => Self::arrange_tree(&mut nodes),
+        }
+        nodes.into_iter().map(|(node, _)| node).collect()
+    }
+
+    /// The node at the given position in document coordinates.
+    ///
+    /// Returns the path of child indices leading from the root to it.
+    pub fn node_at(&self, pos: na::Vector2<f64>) -> Option<Vec<usize>> {
+        let local = self.transform.affine.inverse().transform_point(&pos.into());
+        self.layout_nodes()
+            .into_iter()
+            .rev()
+            .find(|node| {
+                node.rect
+                    .loosened(Self::INDICATOR_RADIUS)
+                    .contains_local_point(&local)
+            })
+            .map(|node| node.path)
+    }
+
+    /// Collapse or expand the node with the given path.
+    ///
+    /// Returns false if the node does not exist or has no children.
+    pub fn toggle_collapsed(&mut self, path: &[usize]) -> bool {
+        match self.root.node_mut(path) {
+            Some(node) if !node.children.is_empty() => {
+                node.collapsed = !node.collapsed;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace the nodes, keeping the collapsed state of the nodes that did not change.
+    pub fn set_root(&mut self, mut root: MindMapNode) {
+        root.keep_collapsed_from(&self.root);
+        self.root = root;
+    }
+
+    fn untransformed_bounds(&self) -> Aabb {
+        self.layout_nodes()
+            .into_iter()
+            .map(|node| node.rect)
+            .reduce(|acc, rect| acc.merged(&rect))
+            .unwrap_or_else(|| Aabb::new(na::point![0.0, 0.0], na::point![0.0, 0.0]))
+            .loosened(Self::INDICATOR_RADIUS + Self::ROOT_BORDER_WIDTH * 0.5)
+    }
+
+    fn node_text_style(&self) -> TextStyle {
+        let mut text_style = self.text_style.clone();
+        text_style.set_max_width(Some(Self::NODE_TEXT_WIDTH_MAX));
+        text_style
+    }
+
+    fn indicator_center(rect: &Aabb) -> na::Vector2<f64> {
+        na::vector![rect.maxs[0], rect.center().coords[1]]
+    }
+
+    /// Place the root at the origin and the descendants on rings around it.
+    /// Each node gets an angular section sized by the number of leaves below it.
+    fn arrange_radial(nodes: &mut [(MindMapNodeLayout, Vec<usize>)]) {
+        // Children always come after their parents, so iterating in reverse visits them first
+        let mut leaves = vec![1_usize; nodes.len()];
+        for i in (0..nodes.len()).rev() {
+            if !nodes[i].1.is_empty() {
+                leaves[i] = nodes[i].1.iter().map(|&c| leaves[c]).sum();
+            }
+        }
+        let ring_dist = nodes
+            .iter()
+            .map(|(node, _)| node.rect.extents().magnitude())
+            .fold(0.0, f64::max)
+            + Self::LEVEL_GAP;
+
+        let mut sections = vec![(-PI * 0.5, PI * 1.5, 0_usize); nodes.len()];
+        for i in 0..nodes.len() {
+            let (start, end, depth) = sections[i];
+            let center = if depth == 0 {
+                na::Vector2::zeros()
+            } else {
+                let angle = (start + end) * 0.5;
+                na::vector![angle.cos(), angle.sin()] * ring_dist * depth as f64
+            };
+            let half_extents = nodes[i].0.rect.half_extents();
+            nodes[i].0.rect = Aabb::new(
+                (center - half_extents).into(),
+                (center + half_extents).into(),
+            );
+
+            let per_leaf = (end - start) / leaves[i] as f64;
+            let mut child_start = start;
+            for &c in nodes[i].1.iter() {
+                let child_end = child_start + per_leaf * leaves[c] as f64;
+                sections[c] = (child_start, child_end, depth + 1);
+                child_start = child_end;
+            }
+        }
+    }
+
+    /// Place the root at the origin and the children in a column to the right of their parent,
+    /// vertically centered on it.
+    fn arrange_tree(nodes: &mut [(MindMapNodeLayout, Vec<usize>)]) {
+        let mut subtree_heights = vec![0.0; nodes.len()];
+        for i in (0..nodes.len()).rev() {
+            let children = &nodes[i].1;
+            let children_height = children.iter().map(|&c| subtree_heights[c]).sum::<f64>()
+                + Self::SIBLING_GAP * children.len().saturating_sub(1) as f64;
+            subtree_heights[i] = nodes[i].0.rect.extents()[1].max(children_height);
+        }
+
+        // The upper left corner of the area of each subtree
+        let mut origins = vec![na::Vector2::<f64>::zeros(); nodes.len()];
+        for i in 0..nodes.len() {
+            let origin = origins[i];
+            let extents = nodes[i].0.rect.extents();
+            let mins = na::vector![
+                origin[0],
+                origin[1] + (subtree_heights[i] - extents[1]) * 0.5
+            ];
+            nodes[i].0.rect = Aabb::new(mins.into(), (mins + extents).into());
+
+            let children = nodes[i].1.clone();
+            let children_height = children.iter().map(|&c| subtree_heights[c]).sum::<f64>()
+                + Self::SIBLING_GAP * children.len().saturating_sub(1) as f64;
+            let mut y = origin[1] + (subtree_heights[i] - children_height) * 0.5;
+            for c in children {
+                origins[c] = na::vector![origin[0] + extents[0] + Self::LEVEL_GAP, y];
+                y += subtree_heights[c] + Self::SIBLING_GAP;
+            }
+        }
+
+        // Move the root center to the origin
+        if let Some(root_center) = nodes.first().map(|(node, _)| node.rect.center().coords) {
+            for (node, _) in nodes.iter_mut() {
+                node.rect = Aabb::new(
+                    (node.rect.mins.coords - root_center).into(),
+                    (node.rect.maxs.coords - root_center).into(),
+                );
+            }
+        }
+    }
+}
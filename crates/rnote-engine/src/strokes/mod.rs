@@ -1,19 +1,41 @@
 // Modules
 pub mod bitmapimage;
+pub mod boardstroke;
 pub mod brushstroke;
+pub mod checkboxstroke;
+pub mod compositing;
+pub mod connectorstroke;
 pub mod content;
+pub mod framestroke;
+pub mod gradient;
+pub mod hatch;
+pub mod mindmapstroke;
 pub mod resize;
+pub mod redactionstroke;
 pub mod shapestroke;
+pub mod stickynotestroke;
 pub mod stroke;
+pub mod tablestroke;
 pub mod textstroke;
 pub mod vectorimage;
 
 // Re-exports
 pub use bitmapimage::BitmapImage;
+pub use boardstroke::BoardStroke;
 pub use brushstroke::BrushStroke;
+pub use checkboxstroke::CheckboxStroke;
+pub use compositing::{BlendMode, Compositing};
+pub use connectorstroke::ConnectorStroke;
 pub use content::Content;
+pub use framestroke::FrameStroke;
+pub use gradient::Gradient;
+pub use hatch::Hatch;
+pub use mindmapstroke::MindMapStroke;
 pub use resize::Resize;
+pub use redactionstroke::RedactionStroke;
 pub use shapestroke::ShapeStroke;
+pub use stickynotestroke::StickyNoteStroke;
 pub use stroke::Stroke;
+pub use tablestroke::TableStroke;
 pub use textstroke::TextStroke;
 pub use vectorimage::VectorImage;
@@ -0,0 +1,111 @@
+// Imports
+use super::{BitmapImage, Compositing, Content, Stroke};
+use crate::{render, Drawable};
+use p2d::bounding_volume::Aabb;
+use piet::RenderContext;
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// A redaction: an opaque rectangle covering an area of the document.
+///
+/// Applying redactions deletes the content underneath, so that it can't be recovered from saved or exported files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "redactionstroke")]
+pub struct RedactionStroke {
+    #[serde(rename = "rectangle")]
+    pub rectangle: Rectangle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Transformable for RedactionStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.rectangle.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.rectangle.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.rectangle.scale(scale);
+    }
+}
+
+impl Shapeable for RedactionStroke {
+    fn bounds(&self) -> Aabb {
+        self.rectangle.bounds()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        // The entire covered area, so that a redaction can be selected by tapping anywhere on it
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.rectangle.outline_path()
+    }
+}
+
+impl Content for RedactionStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for RedactionStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        cx.fill(
+            self.rectangle.outline_path(),
+            &piet::Color::from(Self::COLOR),
+        );
+        Ok(())
+    }
+}
+
+impl RedactionStroke {
+    /// Redactions are always fully opaque black, their color can't be changed.
+    pub const COLOR: Color = Color::BLACK;
+    /// Redactions smaller than this in any dimension are not created.
+    pub const SIZE_MIN: f64 = 2.0;
+    /// The image scale at which redacted strokes are rasterized.
+    pub const RASTERIZE_IMAGE_SCALE: f64 = 2.0;
+
+    pub fn new(rectangle: Rectangle) -> Self {
+        Self {
+            rectangle,
+            compositing: Compositing::default(),
+        }
+    }
+
+    /// The area in which the underlying content gets deleted when redactions are applied.
+    pub fn redacted_area(&self) -> Aabb {
+        self.bounds()
+    }
+
+    /// Rasterizes the stroke into a bitmap image, with the given areas painted over.
+    ///
+    /// Nothing of the original stroke data is carried over, so the covered content can't be recovered.
+    pub fn rasterize_redacted(stroke: &Stroke, areas: &[Aabb]) -> anyhow::Result<BitmapImage> {
+        let image = render::Image::gen_with_piet(
+            |piet_cx| {
+                stroke.draw(piet_cx, Self::RASTERIZE_IMAGE_SCALE)?;
+                for area in areas {
+                    piet_cx.fill(area.to_kurbo_rect(), &piet::Color::from(Self::COLOR));
+                }
+                Ok(())
+            },
+            stroke.bounds(),
+            Self::RASTERIZE_IMAGE_SCALE,
+        )?;
+        Ok(BitmapImage {
+            rectangle: image.rect,
+            image,
+            compositing: Compositing::default(),
+            pdf_page: None,
+            jpeg_data: None,
+            linked_path: None,
+        })
+    }
+}
@@ -12,7 +12,7 @@ pub enum ImageSizeOption {
     ResizeImage(Resize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Resize {
     /// width of a page
     pub width: f64,
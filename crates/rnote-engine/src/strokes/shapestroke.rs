@@ -1,13 +1,16 @@
 // Imports
-use super::Content;
+use super::gradient::Gradient;
+use super::hatch::Hatch;
+use super::{Compositing, Content};
 use crate::{strokes::content, Drawable};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
 use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::Shape;
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::style::Composer;
 use rnote_compose::transform::Transformable;
-use rnote_compose::Style;
+use rnote_compose::{Color, Style};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +20,14 @@ pub struct ShapeStroke {
     pub shape: Shape,
     #[serde(rename = "style")]
     pub style: Style,
+    /// The gradient fill, replacing the fill color of the style. Only drawn for closed shapes.
+    #[serde(rename = "fill_gradient")]
+    pub fill_gradient: Gradient,
+    /// The hatch fill. Only drawn for closed shapes.
+    #[serde(rename = "hatch")]
+    pub hatch: Hatch,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
     #[serde(skip)]
     // since the shape can have many hitboxes, we store them and update them when the stroke geometry changes
     hitboxes: Vec<Aabb>,
@@ -57,11 +68,35 @@ impl Content for ShapeStroke {
 
 impl Drawable for ShapeStroke {
     fn draw(&self, cx: &mut impl piet::RenderContext, _image_scale: f64) -> anyhow::Result<()> {
-        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        self.draw_shape(cx)?;
+
+        if let Some(color) = self.hatch_color() {
+            self.hatch.draw(
+                cx,
+                &self.shape.outline_path(),
+                color,
+                self.hatch_line_width(),
+            )?;
+        }
+        Ok(())
+    }
 
-        self.shape.draw_composed(cx, &self.style);
+    fn draw_to_cairo(&self, cx: &cairo::Context, _image_scale: f64) -> anyhow::Result<()> {
+        {
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(cx);
+            self.draw_shape(&mut piet_cx)?;
+            piet_cx.finish().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
 
-        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        // Drawn as a cairo pattern, so that it is emitted as pattern when generating Svgs.
+        if let Some(color) = self.hatch_color() {
+            self.hatch.draw_to_cairo(
+                cx,
+                &self.shape.outline_path(),
+                color,
+                self.hatch_line_width(),
+            )?;
+        }
         Ok(())
     }
 }
@@ -71,7 +106,7 @@ impl Shapeable for ShapeStroke {
         match &self.style {
             Style::Smooth(options) => self.shape.composed_bounds(options),
             Style::Rough(options) => self.shape.composed_bounds(options),
-            Style::Textured(_) => self.shape.bounds(),
+            Style::Textured(_) | Style::Custom(_) => self.shape.bounds(),
         }
     }
 
@@ -90,6 +125,8 @@ impl Transformable for ShapeStroke {
     }
     fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
         self.shape.rotate(angle, center);
+        self.fill_gradient.angle += angle;
+        self.hatch.angle += angle;
     }
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.shape.scale(scale);
@@ -97,6 +134,7 @@ impl Transformable for ShapeStroke {
         let scale_scalar = (scale[0] * scale[1]).sqrt();
         self.style
             .set_stroke_width(self.style.stroke_width() * scale_scalar);
+        self.hatch.spacing *= scale_scalar;
     }
 }
 
@@ -105,6 +143,9 @@ impl ShapeStroke {
         let mut shapestroke = Self {
             shape,
             style,
+            fill_gradient: Gradient::default(),
+            hatch: Hatch::default(),
+            compositing: Compositing::default(),
             hitboxes: vec![],
         };
         shapestroke.update_geometry();
@@ -112,6 +153,52 @@ impl ShapeStroke {
         shapestroke
     }
 
+    pub fn with_fill_gradient(mut self, fill_gradient: Gradient) -> Self {
+        self.fill_gradient = fill_gradient;
+        self
+    }
+
+    pub fn with_hatch(mut self, hatch: Hatch) -> Self {
+        self.hatch = hatch;
+        self
+    }
+
+    /// Whether the shape encloses an area that can be filled.
+    pub fn is_closed(&self) -> bool {
+        matches!(
+            self.shape,
+            Shape::Rectangle(_) | Shape::Ellipse(_) | Shape::Polygon(_)
+        )
+    }
+
+    fn draw_shape(&self, cx: &mut impl piet::RenderContext) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+        if self.fill_gradient.is_none() || !self.is_closed() {
+            self.shape.draw_composed(cx, &self.style);
+        } else {
+            self.fill_gradient.fill(cx, &self.shape.outline_path())?;
+            let mut style = self.style.clone();
+            style.set_fill_color(Color::TRANSPARENT);
+            self.shape.draw_composed(cx, &style);
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// The hatch is drawn in the stroke color, or the fill color when the shape has no outline.
+    fn hatch_color(&self) -> Option<Color> {
+        if self.hatch.is_none() || !self.is_closed() {
+            return None;
+        }
+        self.style.stroke_color().or(self.style.fill_color())
+    }
+
+    fn hatch_line_width(&self) -> f64 {
+        (self.style.stroke_width() * 0.5).max(0.5)
+    }
+
     fn gen_hitboxes_int(&self) -> Vec<Aabb> {
         let width = self.style.stroke_width();
 
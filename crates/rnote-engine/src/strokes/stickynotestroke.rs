@@ -0,0 +1,157 @@
+// Imports
+use super::textstroke::TextStyle;
+use super::{Compositing, Content};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use piet::{RenderContext, TextLayout};
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::{Color, Transform};
+use serde::{Deserialize, Serialize};
+
+/// A sticky note: a colored rounded rectangle containing text.
+///
+/// The width is fixed, the height grows with the text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "stickynotestroke")]
+pub struct StickyNoteStroke {
+    #[serde(rename = "text")]
+    pub text: String,
+    /// The transformation.
+    ///
+    /// The translation part is the position of the upper left corner.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+    #[serde(rename = "width")]
+    pub width: f64,
+    #[serde(rename = "color")]
+    pub color: Color,
+    #[serde(rename = "text_style")]
+    pub text_style: TextStyle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for StickyNoteStroke {
+    fn default() -> Self {
+        Self {
+            text: String::default(),
+            transform: Transform::default(),
+            width: Self::WIDTH_DEFAULT,
+            color: Self::COLOR_DEFAULT,
+            text_style: TextStyle::default(),
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for StickyNoteStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for StickyNoteStroke {
+    fn bounds(&self) -> Aabb {
+        self.transform.transform_aabb(Aabb::new(
+            na::point![0.0, 0.0],
+            self.untransformed_size().into(),
+        ))
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        vec![self.bounds()]
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Content for StickyNoteStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for StickyNoteStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.transform.to_kurbo());
+
+        let size = self.untransformed_size();
+        cx.fill(
+            kurbo::RoundedRect::new(0.0, 0.0, size[0], size[1], Self::CORNER_RADIUS),
+            &piet::Color::from(self.color),
+        );
+        if let Ok(text_layout) = self
+            .note_text_style()
+            .build_text_layout(cx.text(), self.text.clone())
+        {
+            cx.draw_text(
+                &text_layout,
+                kurbo::Point::new(Self::PADDING, Self::PADDING),
+            );
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+impl StickyNoteStroke {
+    pub const WIDTH_DEFAULT: f64 = 240.0;
+    pub const WIDTH_MIN: f64 = 48.0;
+    /// The default yellow note color.
+    pub const COLOR_DEFAULT: Color = Color {
+        r: 0.976,
+        g: 0.941,
+        b: 0.420,
+        a: 1.0,
+    };
+    pub const PADDING: f64 = 12.0;
+    pub const CORNER_RADIUS: f64 = 8.0;
+
+    pub fn new(
+        text: String,
+        upper_left_pos: na::Vector2<f64>,
+        color: Color,
+        text_style: TextStyle,
+    ) -> Self {
+        Self {
+            text,
+            transform: Transform::new_w_isometry(na::Isometry2::new(upper_left_pos, 0.0)),
+            color,
+            text_style,
+            ..Default::default()
+        }
+    }
+
+    /// The size of the note without the transform. The height fits the text.
+    pub fn untransformed_size(&self) -> na::Vector2<f64> {
+        let text_height = self
+            .note_text_style()
+            .build_text_layout(&mut piet_cairo::CairoText::new(), self.text.clone())
+            .map(|layout| layout.size().height)
+            .unwrap_or(self.text_style.font_size);
+        na::vector![
+            self.width.max(Self::WIDTH_MIN),
+            text_height.max(self.text_style.font_size) + 2.0 * Self::PADDING
+        ]
+    }
+
+    /// The text wraps at the note width.
+    fn note_text_style(&self) -> TextStyle {
+        let mut text_style = self.text_style.clone();
+        text_style.set_max_width(Some(self.width.max(Self::WIDTH_MIN) - 2.0 * Self::PADDING));
+        text_style
+    }
+}
@@ -1,10 +1,18 @@
 // Imports
 use super::bitmapimage::BitmapImage;
+use super::boardstroke::BoardStroke;
 use super::brushstroke::BrushStroke;
+use super::checkboxstroke::CheckboxStroke;
+use super::connectorstroke::ConnectorStroke;
 use super::content::GeneratedContentImages;
+use super::framestroke::FrameStroke;
+use super::mindmapstroke::MindMapStroke;
+use super::redactionstroke::RedactionStroke;
 use super::shapestroke::ShapeStroke;
+use super::stickynotestroke::StickyNoteStroke;
+use super::tablestroke::TableStroke;
 use super::vectorimage::VectorImage;
-use super::{Content, TextStroke};
+use super::{Compositing, Content, TextStroke};
 use crate::fileformats::xoppformat::{self, XoppColor};
 use crate::store::chrono_comp::StrokeLayer;
 use crate::{render, Engine};
@@ -16,7 +24,7 @@ use rnote_compose::shapes::{Rectangle, Shapeable};
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
-use rnote_compose::{Color, PenPath, Style};
+use rnote_compose::{Color, PenPath, Shape, Style};
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
@@ -33,17 +41,50 @@ pub enum Stroke {
     VectorImage(VectorImage),
     #[serde(rename = "bitmapimage")]
     BitmapImage(BitmapImage),
+    #[serde(rename = "tablestroke")]
+    TableStroke(TableStroke),
+    #[serde(rename = "checkboxstroke")]
+    CheckboxStroke(CheckboxStroke),
+    #[serde(rename = "stickynotestroke")]
+    StickyNoteStroke(StickyNoteStroke),
+    #[serde(rename = "redactionstroke")]
+    RedactionStroke(RedactionStroke),
+    #[serde(rename = "connectorstroke")]
+    ConnectorStroke(ConnectorStroke),
+    #[serde(rename = "mindmapstroke")]
+    MindMapStroke(MindMapStroke),
+    #[serde(rename = "boardstroke")]
+    BoardStroke(BoardStroke),
+    #[serde(rename = "framestroke")]
+    FrameStroke(FrameStroke),
 }
 
 impl Content for Stroke {
     fn gen_svg(&self) -> Result<render::Svg, anyhow::Error> {
-        match self {
+        let mut svg = match self {
             Stroke::BrushStroke(brushstroke) => brushstroke.gen_svg(),
             Stroke::ShapeStroke(shapestroke) => shapestroke.gen_svg(),
             Stroke::TextStroke(textstroke) => textstroke.gen_svg(),
             Stroke::VectorImage(vectorimage) => vectorimage.gen_svg(),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.gen_svg(),
+            Stroke::TableStroke(tablestroke) => tablestroke.gen_svg(),
+            Stroke::CheckboxStroke(checkboxstroke) => checkboxstroke.gen_svg(),
+            Stroke::StickyNoteStroke(stickynotestroke) => stickynotestroke.gen_svg(),
+            Stroke::RedactionStroke(redactionstroke) => redactionstroke.gen_svg(),
+            Stroke::ConnectorStroke(connectorstroke) => connectorstroke.gen_svg(),
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.gen_svg(),
+            Stroke::BoardStroke(boardstroke) => boardstroke.gen_svg(),
+            Stroke::FrameStroke(framestroke) => framestroke.gen_svg(),
+        }?;
+        let compositing = self.compositing();
+        if !compositing.is_identity() {
+            svg.svg_data = format!(
+                "<g{}>{}</g>",
+                compositing.svg_group_attributes(),
+                svg.svg_data
+            );
         }
+        Ok(svg)
     }
 
     fn gen_images(
@@ -51,13 +92,39 @@ impl Content for Stroke {
         viewport: Aabb,
         image_scale: f64,
     ) -> Result<GeneratedContentImages, anyhow::Error> {
-        match self {
+        let mut generated_images = match self {
             Stroke::BrushStroke(brushstroke) => brushstroke.gen_images(viewport, image_scale),
             Stroke::ShapeStroke(shapestroke) => shapestroke.gen_images(viewport, image_scale),
             Stroke::TextStroke(textstroke) => textstroke.gen_images(viewport, image_scale),
             Stroke::VectorImage(vectorimage) => vectorimage.gen_images(viewport, image_scale),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.gen_images(viewport, image_scale),
+            Stroke::TableStroke(tablestroke) => tablestroke.gen_images(viewport, image_scale),
+            Stroke::CheckboxStroke(checkboxstroke) => {
+                checkboxstroke.gen_images(viewport, image_scale)
+            }
+            Stroke::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.gen_images(viewport, image_scale)
+            }
+            Stroke::RedactionStroke(redactionstroke) => {
+                redactionstroke.gen_images(viewport, image_scale)
+            }
+            Stroke::ConnectorStroke(connectorstroke) => {
+                connectorstroke.gen_images(viewport, image_scale)
+            }
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.gen_images(viewport, image_scale),
+            Stroke::BoardStroke(boardstroke) => boardstroke.gen_images(viewport, image_scale),
+            Stroke::FrameStroke(framestroke) => framestroke.gen_images(viewport, image_scale),
+        }?;
+        // The blend mode is applied when the images are composited.
+        let compositing = self.compositing();
+        if compositing.opacity < 1.0 {
+            let (GeneratedContentImages::Partial { images, .. }
+            | GeneratedContentImages::Full(images)) = &mut generated_images;
+            for image in images.iter_mut() {
+                image.multiply_opacity(compositing.opacity);
+            }
         }
+        Ok(generated_images)
     }
 
     fn draw_highlight(
@@ -71,6 +138,20 @@ impl Content for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.draw_highlight(cx, total_zoom),
             Stroke::VectorImage(vectorimage) => vectorimage.draw_highlight(cx, total_zoom),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw_highlight(cx, total_zoom),
+            Stroke::TableStroke(tablestroke) => tablestroke.draw_highlight(cx, total_zoom),
+            Stroke::CheckboxStroke(checkboxstroke) => checkboxstroke.draw_highlight(cx, total_zoom),
+            Stroke::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.draw_highlight(cx, total_zoom)
+            }
+            Stroke::RedactionStroke(redactionstroke) => {
+                redactionstroke.draw_highlight(cx, total_zoom)
+            }
+            Stroke::ConnectorStroke(connectorstroke) => {
+                connectorstroke.draw_highlight(cx, total_zoom)
+            }
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.draw_highlight(cx, total_zoom),
+            Stroke::BoardStroke(boardstroke) => boardstroke.draw_highlight(cx, total_zoom),
+            Stroke::FrameStroke(framestroke) => framestroke.draw_highlight(cx, total_zoom),
         }
     }
 
@@ -81,28 +162,99 @@ impl Content for Stroke {
             Stroke::TextStroke(textstroke) => textstroke.update_geometry(),
             Stroke::VectorImage(vectorimage) => vectorimage.update_geometry(),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.update_geometry(),
+            Stroke::TableStroke(tablestroke) => tablestroke.update_geometry(),
+            Stroke::CheckboxStroke(checkboxstroke) => checkboxstroke.update_geometry(),
+            Stroke::StickyNoteStroke(stickynotestroke) => stickynotestroke.update_geometry(),
+            Stroke::RedactionStroke(redactionstroke) => redactionstroke.update_geometry(),
+            Stroke::ConnectorStroke(connectorstroke) => connectorstroke.update_geometry(),
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.update_geometry(),
+            Stroke::BoardStroke(boardstroke) => boardstroke.update_geometry(),
+            Stroke::FrameStroke(framestroke) => framestroke.update_geometry(),
         }
     }
 }
 
 impl Drawable for Stroke {
     fn draw(&self, cx: &mut impl piet::RenderContext, image_scale: f64) -> anyhow::Result<()> {
+        let compositing = self.compositing();
+        if compositing.opacity < 1.0 {
+            // piet has no notion of layers or blend modes, so only the opacity is applied,
+            // through an intermediate image.
+            let mut image = render::Image::gen_with_piet(
+                |piet_cx| self.draw_uncomposited(piet_cx, image_scale),
+                self.bounds(),
+                image_scale,
+            )?;
+            image.multiply_opacity(compositing.opacity);
+            return image.draw(cx, image_scale);
+        }
+        self.draw_uncomposited(cx, image_scale)
+    }
+
+    fn draw_to_cairo(&self, cx: &cairo::Context, image_scale: f64) -> anyhow::Result<()> {
+        let compositing = self.compositing();
+        if compositing.is_identity() {
+            return self.draw_to_cairo_uncomposited(cx, image_scale);
+        }
+        cx.save()?;
+        cx.push_group();
+        let res = self.draw_to_cairo_uncomposited(cx, image_scale);
+        cx.pop_group_to_source()?;
+        cx.set_operator(compositing.blend_mode.into());
+        cx.paint_with_alpha(compositing.opacity)?;
+        cx.restore()?;
+        res
+    }
+}
+
+impl Stroke {
+    fn draw_uncomposited(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        image_scale: f64,
+    ) -> anyhow::Result<()> {
         match self {
             Stroke::BrushStroke(brushstroke) => brushstroke.draw(cx, image_scale),
             Stroke::ShapeStroke(shapestroke) => shapestroke.draw(cx, image_scale),
             Stroke::TextStroke(textstroke) => textstroke.draw(cx, image_scale),
             Stroke::VectorImage(vectorimage) => vectorimage.draw(cx, image_scale),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw(cx, image_scale),
+            Stroke::TableStroke(tablestroke) => tablestroke.draw(cx, image_scale),
+            Stroke::CheckboxStroke(checkboxstroke) => checkboxstroke.draw(cx, image_scale),
+            Stroke::StickyNoteStroke(stickynotestroke) => stickynotestroke.draw(cx, image_scale),
+            Stroke::RedactionStroke(redactionstroke) => redactionstroke.draw(cx, image_scale),
+            Stroke::ConnectorStroke(connectorstroke) => connectorstroke.draw(cx, image_scale),
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.draw(cx, image_scale),
+            Stroke::BoardStroke(boardstroke) => boardstroke.draw(cx, image_scale),
+            Stroke::FrameStroke(framestroke) => framestroke.draw(cx, image_scale),
         }
     }
 
-    fn draw_to_cairo(&self, cx: &cairo::Context, image_scale: f64) -> anyhow::Result<()> {
+    fn draw_to_cairo_uncomposited(
+        &self,
+        cx: &cairo::Context,
+        image_scale: f64,
+    ) -> anyhow::Result<()> {
         match self {
             Stroke::BrushStroke(brushstroke) => brushstroke.draw_to_cairo(cx, image_scale),
             Stroke::ShapeStroke(shapestroke) => shapestroke.draw_to_cairo(cx, image_scale),
             Stroke::TextStroke(textstroke) => textstroke.draw_to_cairo(cx, image_scale),
             Stroke::VectorImage(vectorimage) => vectorimage.draw_to_cairo(cx, image_scale),
             Stroke::BitmapImage(bitmapimage) => bitmapimage.draw_to_cairo(cx, image_scale),
+            Stroke::TableStroke(tablestroke) => tablestroke.draw_to_cairo(cx, image_scale),
+            Stroke::CheckboxStroke(checkboxstroke) => checkboxstroke.draw_to_cairo(cx, image_scale),
+            Stroke::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.draw_to_cairo(cx, image_scale)
+            }
+            Stroke::RedactionStroke(redactionstroke) => {
+                redactionstroke.draw_to_cairo(cx, image_scale)
+            }
+            Stroke::ConnectorStroke(connectorstroke) => {
+                connectorstroke.draw_to_cairo(cx, image_scale)
+            }
+            Stroke::MindMapStroke(mindmapstroke) => mindmapstroke.draw_to_cairo(cx, image_scale),
+            Stroke::BoardStroke(boardstroke) => boardstroke.draw_to_cairo(cx, image_scale),
+            Stroke::FrameStroke(framestroke) => framestroke.draw_to_cairo(cx, image_scale),
         }
     }
 }
@@ -115,6 +267,14 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.bounds(),
             Self::VectorImage(vectorimage) => vectorimage.bounds(),
             Self::BitmapImage(bitmapimage) => bitmapimage.bounds(),
+            Self::TableStroke(tablestroke) => tablestroke.bounds(),
+            Self::CheckboxStroke(checkboxstroke) => checkboxstroke.bounds(),
+            Self::StickyNoteStroke(stickynotestroke) => stickynotestroke.bounds(),
+            Self::RedactionStroke(redactionstroke) => redactionstroke.bounds(),
+            Self::ConnectorStroke(connectorstroke) => connectorstroke.bounds(),
+            Self::MindMapStroke(mindmapstroke) => mindmapstroke.bounds(),
+            Self::BoardStroke(boardstroke) => boardstroke.bounds(),
+            Self::FrameStroke(framestroke) => framestroke.bounds(),
         }
     }
 
@@ -125,6 +285,14 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.hitboxes(),
             Self::VectorImage(vectorimage) => vectorimage.hitboxes(),
             Self::BitmapImage(bitmapimage) => bitmapimage.hitboxes(),
+            Self::TableStroke(tablestroke) => tablestroke.hitboxes(),
+            Self::CheckboxStroke(checkboxstroke) => checkboxstroke.hitboxes(),
+            Self::StickyNoteStroke(stickynotestroke) => stickynotestroke.hitboxes(),
+            Self::RedactionStroke(redactionstroke) => redactionstroke.hitboxes(),
+            Self::ConnectorStroke(connectorstroke) => connectorstroke.hitboxes(),
+            Self::MindMapStroke(mindmapstroke) => mindmapstroke.hitboxes(),
+            Self::BoardStroke(boardstroke) => boardstroke.hitboxes(),
+            Self::FrameStroke(framestroke) => framestroke.hitboxes(),
         }
     }
 
@@ -135,6 +303,14 @@ impl Shapeable for Stroke {
             Self::TextStroke(textstroke) => textstroke.outline_path(),
             Self::VectorImage(vectorimage) => vectorimage.outline_path(),
             Self::BitmapImage(bitmapimage) => bitmapimage.outline_path(),
+            Self::TableStroke(tablestroke) => tablestroke.outline_path(),
+            Self::CheckboxStroke(checkboxstroke) => checkboxstroke.outline_path(),
+            Self::StickyNoteStroke(stickynotestroke) => stickynotestroke.outline_path(),
+            Self::RedactionStroke(redactionstroke) => redactionstroke.outline_path(),
+            Self::ConnectorStroke(connectorstroke) => connectorstroke.outline_path(),
+            Self::MindMapStroke(mindmapstroke) => mindmapstroke.outline_path(),
+            Self::BoardStroke(boardstroke) => boardstroke.outline_path(),
+            Self::FrameStroke(framestroke) => framestroke.outline_path(),
         }
     }
 }
@@ -157,6 +333,30 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.translate(offset);
             }
+            Self::TableStroke(tablestroke) => {
+                tablestroke.translate(offset);
+            }
+            Self::CheckboxStroke(checkboxstroke) => {
+                checkboxstroke.translate(offset);
+            }
+            Self::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.translate(offset);
+            }
+            Self::RedactionStroke(redactionstroke) => {
+                redactionstroke.translate(offset);
+            }
+            Self::ConnectorStroke(connectorstroke) => {
+                connectorstroke.translate(offset);
+            }
+            Self::MindMapStroke(mindmapstroke) => {
+                mindmapstroke.translate(offset);
+            }
+            Self::BoardStroke(boardstroke) => {
+                boardstroke.translate(offset);
+            }
+            Self::FrameStroke(framestroke) => {
+                framestroke.translate(offset);
+            }
         }
     }
 
@@ -177,6 +377,30 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.rotate(angle, center);
             }
+            Self::TableStroke(tablestroke) => {
+                tablestroke.rotate(angle, center);
+            }
+            Self::CheckboxStroke(checkboxstroke) => {
+                checkboxstroke.rotate(angle, center);
+            }
+            Self::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.rotate(angle, center);
+            }
+            Self::RedactionStroke(redactionstroke) => {
+                redactionstroke.rotate(angle, center);
+            }
+            Self::ConnectorStroke(connectorstroke) => {
+                connectorstroke.rotate(angle, center);
+            }
+            Self::MindMapStroke(mindmapstroke) => {
+                mindmapstroke.rotate(angle, center);
+            }
+            Self::BoardStroke(boardstroke) => {
+                boardstroke.rotate(angle, center);
+            }
+            Self::FrameStroke(framestroke) => {
+                framestroke.rotate(angle, center);
+            }
         }
     }
 
@@ -197,6 +421,30 @@ impl Transformable for Stroke {
             Self::BitmapImage(bitmapimage) => {
                 bitmapimage.scale(scale);
             }
+            Self::TableStroke(tablestroke) => {
+                tablestroke.scale(scale);
+            }
+            Self::CheckboxStroke(checkboxstroke) => {
+                checkboxstroke.scale(scale);
+            }
+            Self::StickyNoteStroke(stickynotestroke) => {
+                stickynotestroke.scale(scale);
+            }
+            Self::RedactionStroke(redactionstroke) => {
+                redactionstroke.scale(scale);
+            }
+            Self::ConnectorStroke(connectorstroke) => {
+                connectorstroke.scale(scale);
+            }
+            Self::MindMapStroke(mindmapstroke) => {
+                mindmapstroke.scale(scale);
+            }
+            Self::BoardStroke(boardstroke) => {
+                boardstroke.scale(scale);
+            }
+            Self::FrameStroke(framestroke) => {
+                framestroke.scale(scale);
+            }
         }
     }
 }
@@ -209,11 +457,245 @@ impl Stroke {
         match self {
             Stroke::BrushStroke(_) => StrokeLayer::UserLayer(0),
             Stroke::ShapeStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::ConnectorStroke(_) => StrokeLayer::UserLayer(0),
             Stroke::TextStroke(_) => StrokeLayer::UserLayer(0),
-            Stroke::VectorImage(_) | Stroke::BitmapImage(_) => StrokeLayer::Image,
+            Stroke::CheckboxStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::StickyNoteStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::MindMapStroke(_) => StrokeLayer::UserLayer(0),
+            Stroke::FrameStroke(_) => StrokeLayer::UserLayer(0),
+            // Redactions are placed above everything else, so that they always cover the content underneath
+            Stroke::RedactionStroke(_) => StrokeLayer::UserLayer(u32::MAX),
+            // Tables and boards are placed below the user layers, so that handwriting and notes lie on top
+            Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::TableStroke(_)
+            | Stroke::BoardStroke(_) => StrokeLayer::Image,
+        }
+    }
+
+    pub fn compositing(&self) -> &Compositing {
+        match self {
+            Stroke::BrushStroke(brushstroke) => &brushstroke.compositing,
+            Stroke::ShapeStroke(shapestroke) => &shapestroke.compositing,
+            Stroke::TextStroke(textstroke) => &textstroke.compositing,
+            Stroke::VectorImage(vectorimage) => &vectorimage.compositing,
+            Stroke::BitmapImage(bitmapimage) => &bitmapimage.compositing,
+            Stroke::TableStroke(tablestroke) => &tablestroke.compositing,
+            Stroke::CheckboxStroke(checkboxstroke) => &checkboxstroke.compositing,
+            Stroke::StickyNoteStroke(stickynotestroke) => &stickynotestroke.compositing,
+            Stroke::RedactionStroke(redactionstroke) => &redactionstroke.compositing,
+            Stroke::ConnectorStroke(connectorstroke) => &connectorstroke.compositing,
+            Stroke::MindMapStroke(mindmapstroke) => &mindmapstroke.compositing,
+            Stroke::BoardStroke(boardstroke) => &boardstroke.compositing,
+            Stroke::FrameStroke(framestroke) => &framestroke.compositing,
+        }
+    }
+
+    pub fn compositing_mut(&mut self) -> &mut Compositing {
+        match self {
+            Stroke::BrushStroke(brushstroke) => &mut brushstroke.compositing,
+            Stroke::ShapeStroke(shapestroke) => &mut shapestroke.compositing,
+            Stroke::TextStroke(textstroke) => &mut textstroke.compositing,
+            Stroke::VectorImage(vectorimage) => &mut vectorimage.compositing,
+            Stroke::BitmapImage(bitmapimage) => &mut bitmapimage.compositing,
+            Stroke::TableStroke(tablestroke) => &mut tablestroke.compositing,
+            Stroke::CheckboxStroke(checkboxstroke) => &mut checkboxstroke.compositing,
+            Stroke::StickyNoteStroke(stickynotestroke) => &mut stickynotestroke.compositing,
+            Stroke::RedactionStroke(redactionstroke) => &mut redactionstroke.compositing,
+            Stroke::ConnectorStroke(connectorstroke) => &mut connectorstroke.compositing,
+            Stroke::MindMapStroke(mindmapstroke) => &mut mindmapstroke.compositing,
+            Stroke::BoardStroke(boardstroke) => &mut boardstroke.compositing,
+            Stroke::FrameStroke(framestroke) => &mut framestroke.compositing,
+        }
+    }
+
+    /// The style of the stroke, if it has one that can be transferred to other strokes.
+    pub fn style(&self) -> Option<Style> {
+        match self {
+            Stroke::BrushStroke(brush_stroke) => Some(brush_stroke.style.clone()),
+            Stroke::ShapeStroke(shape_stroke) => Some(shape_stroke.style.clone()),
+            Stroke::TextStroke(_)
+            | Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::TableStroke(_)
+            | Stroke::CheckboxStroke(_)
+            | Stroke::StickyNoteStroke(_)
+            | Stroke::RedactionStroke(_)
+            | Stroke::ConnectorStroke(_)
+            | Stroke::MindMapStroke(_)
+            | Stroke::BoardStroke(_)
+            | Stroke::FrameStroke(_) => None,
+        }
+    }
+
+    /// Whether the given style can be applied to the stroke with [Stroke::apply_style].
+    ///
+    /// Pen paths can't be drawn in the rough style, and only lines can be drawn in the textured style.
+    pub fn style_applies(&self, style: &Style) -> bool {
+        match self {
+            Stroke::BrushStroke(_) => !matches!(style, Style::Rough(_)),
+            Stroke::ShapeStroke(shape_stroke) => {
+                !matches!(style, Style::Textured(_)) || matches!(shape_stroke.shape, Shape::Line(_))
+            }
+            Stroke::VectorImage(_)
+            | Stroke::BitmapImage(_)
+            | Stroke::RedactionStroke(_)
+            | Stroke::FrameStroke(_) => false,
+            // These only take over the stroke color
+            Stroke::TextStroke(_)
+            | Stroke::TableStroke(_)
+            | Stroke::CheckboxStroke(_)
+            | Stroke::StickyNoteStroke(_)
+            | Stroke::ConnectorStroke(_)
+            | Stroke::MindMapStroke(_)
+            | Stroke::BoardStroke(_) => style.stroke_color().is_some(),
+        }
+    }
+
+    /// Apply the given style to the stroke. The seed of rough, textured and custom styles is preserved,
+    /// so the stroke keeps its shape. Text strokes only take over the stroke color.
+    ///
+    /// Returns true if the stroke was modified and needs to update its geometry and rendering.
+    pub fn apply_style(&mut self, style: &Style) -> bool {
+        if !self.style_applies(style) {
+            return false;
+        }
+
+        fn with_preserved_seed(current: &Style, style: &Style) -> Style {
+            let mut style = style.clone();
+            match (&mut style, current) {
+                (Style::Rough(new), Style::Rough(current)) => new.seed = current.seed,
+                (Style::Textured(new), Style::Textured(current)) => new.seed = current.seed,
+                (Style::Custom(new), Style::Custom(current)) => new.seed = current.seed,
+                _ => {}
+            }
+            style
+        }
+
+        match self {
+            Stroke::BrushStroke(brush_stroke) => {
+                brush_stroke.style = with_preserved_seed(&brush_stroke.style, style);
+                true
+            }
+            Stroke::ShapeStroke(shape_stroke) => {
+                shape_stroke.style = with_preserved_seed(&shape_stroke.style, style);
+                true
+            }
+            Stroke::TextStroke(text_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                text_stroke.text_style.color = color;
+                true
+            }
+            Stroke::TableStroke(table_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                table_stroke.text_style.color = color;
+                table_stroke.border_color = color;
+                true
+            }
+            Stroke::CheckboxStroke(checkbox_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                checkbox_stroke.text_style.color = color;
+                true
+            }
+            Stroke::StickyNoteStroke(sticky_note_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                sticky_note_stroke.text_style.color = color;
+                true
+            }
+            Stroke::ConnectorStroke(connector_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                connector_stroke.color = color;
+                true
+            }
+            Stroke::MindMapStroke(mind_map_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                mind_map_stroke.text_style.color = color;
+                mind_map_stroke.color = color;
+                true
+            }
+            Stroke::BoardStroke(board_stroke) => {
+                let Some(color) = style.stroke_color() else {
+                    return false;
+                };
+                board_stroke.text_style.color = color;
+                board_stroke.color = color;
+                true
+            }
+            Stroke::VectorImage(_) => false,
+            Stroke::BitmapImage(_) => false,
+            // Redactions must keep covering the content underneath
+            Stroke::RedactionStroke(_) => false,
+            // Frames are guides with a fixed color
+            Stroke::FrameStroke(_) => false,
         }
     }
 
+    /// All colors of the stroke. Fully transparent colors are excluded.
+    pub fn colors(&self) -> Vec<Color> {
+        let colors = match self {
+            Stroke::BrushStroke(brush_stroke) => vec![
+                brush_stroke.style.stroke_color(),
+                brush_stroke.style.fill_color(),
+            ],
+            Stroke::ShapeStroke(shape_stroke) => vec![
+                shape_stroke.style.stroke_color(),
+                shape_stroke.style.fill_color(),
+            ],
+            Stroke::TextStroke(text_stroke) => vec![Some(text_stroke.text_style.color)],
+            Stroke::TableStroke(table_stroke) => vec![
+                Some(table_stroke.text_style.color),
+                Some(table_stroke.border_color),
+            ],
+            Stroke::CheckboxStroke(checkbox_stroke) => vec![Some(checkbox_stroke.text_style.color)],
+            Stroke::StickyNoteStroke(sticky_note_stroke) => vec![
+                Some(sticky_note_stroke.color),
+                Some(sticky_note_stroke.text_style.color),
+            ],
+            Stroke::RedactionStroke(_) => vec![Some(RedactionStroke::COLOR)],
+            Stroke::FrameStroke(_) => vec![Some(FrameStroke::COLOR)],
+            Stroke::ConnectorStroke(connector_stroke) => vec![Some(connector_stroke.color)],
+            Stroke::MindMapStroke(mind_map_stroke) => vec![
+                Some(mind_map_stroke.color),
+                Some(mind_map_stroke.text_style.color),
+            ],
+            Stroke::BoardStroke(board_stroke) => vec![
+                Some(board_stroke.color),
+                Some(board_stroke.text_style.color),
+            ],
+            Stroke::VectorImage(_) | Stroke::BitmapImage(_) => vec![],
+        };
+        colors
+            .into_iter()
+            .flatten()
+            .filter(|color| color.a > 0.0)
+            .collect()
+    }
+
+    /// Whether the stroke has a visible fill that its outline or text is drawn on.
+    pub fn is_filled(&self) -> bool {
+        let fill_color = match self {
+            Stroke::BrushStroke(brush_stroke) => brush_stroke.style.fill_color(),
+            Stroke::ShapeStroke(shape_stroke) => shape_stroke.style.fill_color(),
+            Stroke::StickyNoteStroke(sticky_note_stroke) => Some(sticky_note_stroke.color),
+            Stroke::MindMapStroke(mind_map_stroke) => Some(mind_map_stroke.color),
+            Stroke::BoardStroke(board_stroke) => Some(board_stroke.color),
+            _ => None,
+        };
+        fill_color.is_some_and(|color| color.a > 0.0)
+    }
+
     /// Invert the brightness of all colors of the stroke.
     ///
     /// Returns true if the stroke was modified and needs to update its rendering.
@@ -255,8 +737,58 @@ impl Stroke {
 
                 true
             }
+            Stroke::TableStroke(table_stroke) => {
+                table_stroke.text_style.color =
+                    table_stroke.text_style.color.to_inverted_brightness_color();
+                table_stroke.border_color =
+                    table_stroke.border_color.to_inverted_brightness_color();
+
+                true
+            }
+            Stroke::CheckboxStroke(checkbox_stroke) => {
+                checkbox_stroke.text_style.color = checkbox_stroke
+                    .text_style
+                    .color
+                    .to_inverted_brightness_color();
+
+                true
+            }
+            Stroke::StickyNoteStroke(sticky_note_stroke) => {
+                sticky_note_stroke.color = sticky_note_stroke.color.to_inverted_brightness_color();
+                sticky_note_stroke.text_style.color = sticky_note_stroke
+                    .text_style
+                    .color
+                    .to_inverted_brightness_color();
+
+                true
+            }
+            Stroke::ConnectorStroke(connector_stroke) => {
+                connector_stroke.color = connector_stroke.color.to_inverted_brightness_color();
+
+                true
+            }
+            Stroke::MindMapStroke(mind_map_stroke) => {
+                mind_map_stroke.color = mind_map_stroke.color.to_inverted_brightness_color();
+                mind_map_stroke.text_style.color = mind_map_stroke
+                    .text_style
+                    .color
+                    .to_inverted_brightness_color();
+
+                true
+            }
+            Stroke::BoardStroke(board_stroke) => {
+                board_stroke.color = board_stroke.color.to_inverted_brightness_color();
+                board_stroke.text_style.color =
+                    board_stroke.text_style.color.to_inverted_brightness_color();
+
+                true
+            }
             Stroke::VectorImage(_) => false,
             Stroke::BitmapImage(_) => false,
+            // Redactions must keep covering the content underneath
+            Stroke::RedactionStroke(_) => false,
+            // Frames are guides with a fixed color
+            Stroke::FrameStroke(_) => false,
         }
     }
 
@@ -296,8 +828,49 @@ impl Stroke {
 
                 true
             }
+            Stroke::TableStroke(table_stroke) => {
+                table_stroke.text_style.color = table_stroke.text_style.color.to_darkest_color();
+                table_stroke.border_color = table_stroke.border_color.to_darkest_color();
+
+                true
+            }
+            Stroke::CheckboxStroke(checkbox_stroke) => {
+                checkbox_stroke.text_style.color =
+                    checkbox_stroke.text_style.color.to_darkest_color();
+
+                true
+            }
+            Stroke::StickyNoteStroke(sticky_note_stroke) => {
+                // Only the text, a darkened note would hide it
+                sticky_note_stroke.text_style.color =
+                    sticky_note_stroke.text_style.color.to_darkest_color();
+
+                true
+            }
+            Stroke::ConnectorStroke(connector_stroke) => {
+                connector_stroke.color = connector_stroke.color.to_darkest_color();
+
+                true
+            }
+            Stroke::MindMapStroke(mind_map_stroke) => {
+                mind_map_stroke.color = mind_map_stroke.color.to_darkest_color();
+                mind_map_stroke.text_style.color =
+                    mind_map_stroke.text_style.color.to_darkest_color();
+
+                true
+            }
+            Stroke::BoardStroke(board_stroke) => {
+                board_stroke.color = board_stroke.color.to_darkest_color();
+                board_stroke.text_style.color = board_stroke.text_style.color.to_darkest_color();
+
+                true
+            }
             Stroke::VectorImage(_) => false,
             Stroke::BitmapImage(_) => false,
+            // Redactions must keep covering the content underneath
+            Stroke::RedactionStroke(_) => false,
+            // Frames are guides with a fixed color
+            Stroke::FrameStroke(_) => false,
         }
     }
 
@@ -424,7 +997,14 @@ impl Stroke {
         };
         let image = render::Image::try_from_encoded_bytes(&bytes)?;
 
-        Ok(Stroke::BitmapImage(BitmapImage { image, rectangle }))
+        Ok(Stroke::BitmapImage(BitmapImage {
+            image,
+            rectangle,
+            compositing: Compositing::default(),
+            pdf_page: None,
+            jpeg_data: None,
+            linked_path: None,
+        }))
     }
 
     pub fn into_xopp(self, current_dpi: f64) -> Option<xoppformat::XoppStrokeType> {
@@ -444,6 +1024,10 @@ impl Stroke {
                         options.stroke_width,
                         crate::utils::xoppcolor_from_color(options.stroke_color?),
                     ),
+                    Style::Custom(options) => (
+                        options.stroke_width,
+                        crate::utils::xoppcolor_from_color(options.stroke_color?),
+                    ),
                 };
 
                 let tool = xoppformat::XoppTool::Pen;
@@ -462,7 +1046,9 @@ impl Stroke {
                         Style::Smooth(options) => {
                             options.pressure_curve.apply(stroke_width, element.pressure)
                         }
-                        Style::Rough(_) | Style::Textured(_) => stroke_width * element.pressure,
+                        Style::Rough(_) | Style::Textured(_) | Style::Custom(_) => {
+                            stroke_width * element.pressure
+                        }
                     })
                     .collect();
                 width_vec.append(&mut pressures);
@@ -537,6 +1123,56 @@ impl Stroke {
                     },
                 ))
             }
+            stroke @ (Stroke::TableStroke(_)
+            | Stroke::CheckboxStroke(_)
+            | Stroke::StickyNoteStroke(_)
+            | Stroke::RedactionStroke(_)
+            | Stroke::ConnectorStroke(_)
+            | Stroke::MindMapStroke(_)
+            | Stroke::BoardStroke(_)
+            | Stroke::FrameStroke(_)) => {
+                // Xournal++ has no equivalent for these, so they are exported as bitmap images.
+                let png_data = match stroke.export_to_bitmap_image_bytes(
+                    image::ImageFormat::Png,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    Ok(image_bytes) => image_bytes,
+                    Err(e) => {
+                        error!("Converting Stroke to XoppImage failed, Err: {e:?}");
+                        return None;
+                    }
+                };
+                let bounds = stroke.bounds();
+
+                Some(xoppformat::XoppStrokeType::XoppImage(
+                    xoppformat::XoppImage {
+                        left: utils::convert_value_dpi(
+                            bounds.mins[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        top: utils::convert_value_dpi(
+                            bounds.mins[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        right: utils::convert_value_dpi(
+                            bounds.maxs[0],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        bottom: utils::convert_value_dpi(
+                            bounds.maxs[1],
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        data: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            png_data,
+                        ),
+                    },
+                ))
+            }
             Stroke::TextStroke(textstroke) => {
                 // Xournal++ text strokes do not support affine transformations, so we have to convert on best effort here.
                 // The best solution for now seems to be to export them as a bitmap image.
@@ -0,0 +1,464 @@
+// Imports
+use super::textstroke::TextStyle;
+use super::{Compositing, Content};
+use crate::Drawable;
+use kurbo::Shape;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, TextLayout};
+use rnote_compose::ext::AabbExt;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::{Color, Transform};
+use serde::{Deserialize, Serialize};
+
+/// A border of a table that can be dragged to resize the adjacent row or column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableBorder {
+    /// The bottom border of the row at the index.
+    Row(usize),
+    /// The right border of the column at the index.
+    Column(usize),
+}
+
+/// A structural edit of a table, relative to a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableEdit {
+    InsertRowAbove,
+    InsertRowBelow,
+    InsertColumnLeft,
+    InsertColumnRight,
+    RemoveRow,
+    RemoveColumn,
+}
+
+impl std::str::FromStr for TableEdit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "insert-row-above" => Ok(Self::InsertRowAbove),
+            "insert-row-below" => Ok(Self::InsertRowBelow),
+            "insert-column-left" => Ok(Self::InsertColumnLeft),
+            "insert-column-right" => Ok(Self::InsertColumnRight),
+            "remove-row" => Ok(Self::RemoveRow),
+            "remove-column" => Ok(Self::RemoveColumn),
+            s => Err(anyhow::anyhow!(
+                "TableEdit from_string failed, invalid name: {s}"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "tablestroke")]
+pub struct TableStroke {
+    /// The transformation.
+    ///
+    /// The translation part is the position of the upper left corner.
+    #[serde(rename = "transform")]
+    pub transform: Transform,
+    #[serde(rename = "column_widths")]
+    column_widths: Vec<f64>,
+    #[serde(rename = "row_heights")]
+    row_heights: Vec<f64>,
+    /// The cell texts, stored row by row.
+    #[serde(rename = "cells")]
+    cells: Vec<Vec<String>>,
+    #[serde(rename = "text_style")]
+    pub text_style: TextStyle,
+    #[serde(rename = "border_color")]
+    pub border_color: Color,
+    #[serde(rename = "border_width")]
+    pub border_width: f64,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
+}
+
+impl Default for TableStroke {
+    fn default() -> Self {
+        Self {
+            transform: Transform::default(),
+            column_widths: vec![Self::COLUMN_WIDTH_DEFAULT],
+            row_heights: vec![Self::ROW_HEIGHT_DEFAULT],
+            cells: vec![vec![String::default()]],
+            text_style: TextStyle::default(),
+            border_color: Self::BORDER_COLOR_DEFAULT,
+            border_width: Self::BORDER_WIDTH_DEFAULT,
+            compositing: Compositing::default(),
+        }
+    }
+}
+
+impl Transformable for TableStroke {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.transform.append_translation_mut(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.transform.append_rotation_wrt_point_mut(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.transform.append_scale_mut(scale);
+    }
+}
+
+impl Shapeable for TableStroke {
+    fn bounds(&self) -> Aabb {
+        self.transform
+            .transform_aabb(Aabb::new(na::point![0.0, 0.0], self.size().into()))
+            .loosened(self.border_width * 0.5)
+    }
+
+    /// Only the borders are hitboxes, so that strokes written inside the cells can be selected and erased
+    /// without touching the table.
+    fn hitboxes(&self) -> Vec<Aabb> {
+        let size = self.size();
+        let half_width = self.border_width.max(Self::BORDER_HITBOX_WIDTH_MIN) * 0.5;
+
+        let horizontal = std::iter::once(0.0)
+            .chain(self.row_offsets())
+            .map(|y| Aabb::new(na::point![0.0, y], na::point![size[0], y]));
+        let vertical = std::iter::once(0.0)
+            .chain(self.column_offsets())
+            .map(|x| Aabb::new(na::point![x, 0.0], na::point![x, size[1]]));
+
+        horizontal
+            .chain(vertical)
+            .map(|line| self.transform.transform_aabb(line.loosened(half_width)))
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        self.bounds().to_kurbo_rect().to_path(0.25)
+    }
+}
+
+impl Content for TableStroke {
+    fn update_geometry(&mut self) {}
+}
+
+impl Drawable for TableStroke {
+    fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(self.transform.to_kurbo());
+
+        let mut y = 0.0;
+        for (row, row_height) in self.row_heights.iter().enumerate() {
+            let mut x = 0.0;
+            for (column, column_width) in self.column_widths.iter().enumerate() {
+                let text = self.cell_text(row, column).unwrap_or_default();
+                if !text.is_empty() {
+                    if let Ok(text_layout) = self
+                        .cell_text_style(column)
+                        .build_text_layout(cx.text(), text.to_string())
+                    {
+                        cx.draw_text(
+                            &text_layout,
+                            kurbo::Point::new(x + Self::CELL_PADDING, y + Self::CELL_PADDING),
+                        );
+                    }
+                }
+                x += column_width;
+            }
+            y += row_height;
+        }
+
+        let size = self.size();
+        let mut borders = kurbo::BezPath::new();
+        for y in std::iter::once(0.0).chain(self.row_offsets()) {
+            borders.move_to((0.0, y));
+            borders.line_to((size[0], y));
+        }
+        for x in std::iter::once(0.0).chain(self.column_offsets()) {
+            borders.move_to((x, 0.0));
+            borders.line_to((x, size[1]));
+        }
+        cx.stroke_styled(
+            borders,
+            &piet::Color::from(self.border_color),
+            self.border_width,
+            &piet::StrokeStyle::new().line_cap(piet::LineCap::Square),
+        );
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+impl TableStroke {
+    pub const COLUMN_WIDTH_DEFAULT: f64 = 160.0;
+    pub const ROW_HEIGHT_DEFAULT: f64 = 56.0;
+    pub const CELL_SIZE_MIN: f64 = 16.0;
+    pub const CELL_PADDING: f64 = 6.0;
+    pub const BORDER_COLOR_DEFAULT: Color = Color::BLACK;
+    pub const BORDER_WIDTH_DEFAULT: f64 = 1.5;
+    pub const ROWS_MAX: usize = 100;
+    pub const COLUMNS_MAX: usize = 26;
+    /// The minimum width of the border hitboxes, so that thin borders can still be picked.
+    const BORDER_HITBOX_WIDTH_MIN: f64 = 6.0;
+
+    /// A new table with empty cells. The table has at least one row and column.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        upper_left_pos: na::Vector2<f64>,
+        text_style: TextStyle,
+    ) -> Self {
+        let rows = rows.clamp(1, Self::ROWS_MAX);
+        let columns = columns.clamp(1, Self::COLUMNS_MAX);
+        let row_height = Self::ROW_HEIGHT_DEFAULT.max(Self::min_row_height(&text_style));
+
+        Self {
+            transform: Transform::new_w_isometry(na::Isometry2::new(upper_left_pos, 0.0)),
+            column_widths: vec![Self::COLUMN_WIDTH_DEFAULT; columns],
+            row_heights: vec![row_height; rows],
+            cells: vec![vec![String::default(); columns]; rows],
+            text_style,
+            ..Default::default()
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.row_heights.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.column_widths.len()
+    }
+
+    /// The untransformed size of the table.
+    pub fn size(&self) -> na::Vector2<f64> {
+        na::vector![
+            self.column_widths.iter().sum::<f64>(),
+            self.row_heights.iter().sum::<f64>()
+        ]
+    }
+
+    pub fn cell_text(&self, row: usize, column: usize) -> Option<&str> {
+        self.cells
+            .get(row)
+            .and_then(|r| r.get(column))
+            .map(|t| t.as_str())
+    }
+
+    /// Set the text of a cell. The row grows when the text doesn't fit into it.
+    pub fn set_cell_text(&mut self, row: usize, column: usize, text: String) {
+        let Some(cell) = self.cells.get_mut(row).and_then(|r| r.get_mut(column)) else {
+            return;
+        };
+        *cell = text;
+        self.fit_row_to_content(row);
+    }
+
+    /// The row and column of the cell at the coordinate.
+    ///
+    /// `coord` must be in global coordinate space.
+    pub fn cell_at(&self, coord: na::Vector2<f64>) -> Option<(usize, usize)> {
+        let local = self.global_to_local(coord);
+        let row = Self::index_at(&self.row_heights, local[1])?;
+        let column = Self::index_at(&self.column_widths, local[0])?;
+        Some((row, column))
+    }
+
+    /// The border at the coordinate, preferring inner borders over the outer ones when they are close together.
+    ///
+    /// `coord` must be in global coordinate space. The outer left and top borders can't be dragged.
+    pub fn border_at(&self, coord: na::Vector2<f64>, tolerance: f64) -> Option<TableBorder> {
+        let local = self.global_to_local(coord);
+        let size = self.size();
+        let tolerance = tolerance.max(self.border_width * 0.5);
+
+        if local[0] >= -tolerance && local[0] <= size[0] + tolerance {
+            if let Some(row) = self
+                .row_offsets()
+                .position(|y| (local[1] - y).abs() <= tolerance)
+            {
+                return Some(TableBorder::Row(row));
+            }
+        }
+        if local[1] >= -tolerance && local[1] <= size[1] + tolerance {
+            if let Some(column) = self
+                .column_offsets()
+                .position(|x| (local[0] - x).abs() <= tolerance)
+            {
+                return Some(TableBorder::Column(column));
+            }
+        }
+        None
+    }
+
+    /// Move the border to the coordinate, resizing the row or column before it.
+    ///
+    /// `coord` must be in global coordinate space.
+    pub fn drag_border_to(&mut self, border: TableBorder, coord: na::Vector2<f64>) {
+        let local = self.global_to_local(coord);
+        match border {
+            TableBorder::Row(row) => {
+                if row >= self.rows() {
+                    return;
+                }
+                let start: f64 = self.row_heights[..row].iter().sum();
+                self.row_heights[row] = (local[1] - start)
+                    .max(Self::CELL_SIZE_MIN)
+                    .max(self.content_height(row));
+            }
+            TableBorder::Column(column) => {
+                if column >= self.columns() {
+                    return;
+                }
+                let start: f64 = self.column_widths[..column].iter().sum();
+                self.column_widths[column] = (local[0] - start).max(Self::CELL_SIZE_MIN);
+                // Text in the narrower column might wrap into more lines
+                for row in 0..self.rows() {
+                    self.fit_row_to_content(row);
+                }
+            }
+        }
+    }
+
+    /// Insert an empty row at the index, with the height of the row before it.
+    pub fn insert_row(&mut self, at: usize) {
+        if self.rows() >= Self::ROWS_MAX {
+            return;
+        }
+        let at = at.min(self.rows());
+        let height = self
+            .row_heights
+            .get(at.saturating_sub(1))
+            .copied()
+            .unwrap_or(Self::ROW_HEIGHT_DEFAULT);
+        self.row_heights.insert(at, height);
+        self.cells
+            .insert(at, vec![String::default(); self.columns()]);
+    }
+
+    /// Insert an empty column at the index, with the width of the column before it.
+    pub fn insert_column(&mut self, at: usize) {
+        if self.columns() >= Self::COLUMNS_MAX {
+            return;
+        }
+        let at = at.min(self.columns());
+        let width = self
+            .column_widths
+            .get(at.saturating_sub(1))
+            .copied()
+            .unwrap_or(Self::COLUMN_WIDTH_DEFAULT);
+        self.column_widths.insert(at, width);
+        for row in self.cells.iter_mut() {
+            row.insert(at, String::default());
+        }
+    }
+
+    /// Remove the row at the index. The last remaining row can't be removed.
+    pub fn remove_row(&mut self, at: usize) {
+        if self.rows() <= 1 || at >= self.rows() {
+            return;
+        }
+        self.row_heights.remove(at);
+        self.cells.remove(at);
+    }
+
+    /// Remove the column at the index. The last remaining column can't be removed.
+    pub fn remove_column(&mut self, at: usize) {
+        if self.columns() <= 1 || at >= self.columns() {
+            return;
+        }
+        self.column_widths.remove(at);
+        for row in self.cells.iter_mut() {
+            row.remove(at);
+        }
+    }
+
+    /// Apply the edit relative to the cell at the row and column.
+    pub fn apply_edit(&mut self, edit: TableEdit, row: usize, column: usize) {
+        match edit {
+            TableEdit::InsertRowAbove => self.insert_row(row),
+            TableEdit::InsertRowBelow => self.insert_row(row + 1),
+            TableEdit::InsertColumnLeft => self.insert_column(column),
+            TableEdit::InsertColumnRight => self.insert_column(column + 1),
+            TableEdit::RemoveRow => self.remove_row(row),
+            TableEdit::RemoveColumn => self.remove_column(column),
+        }
+    }
+
+    fn global_to_local(&self, coord: na::Vector2<f64>) -> na::Vector2<f64> {
+        self.transform
+            .affine
+            .inverse()
+            .transform_point(&coord.into())
+            .coords
+    }
+
+    /// The offsets of the bottom borders of the rows.
+    fn row_offsets(&self) -> impl Iterator<Item = f64> + '_ {
+        self.row_heights.iter().scan(0.0, |acc, h| {
+            *acc += h;
+            Some(*acc)
+        })
+    }
+
+    /// The offsets of the right borders of the columns.
+    fn column_offsets(&self) -> impl Iterator<Item = f64> + '_ {
+        self.column_widths.iter().scan(0.0, |acc, w| {
+            *acc += w;
+            Some(*acc)
+        })
+    }
+
+    fn index_at(sizes: &[f64], offset: f64) -> Option<usize> {
+        if offset < 0.0 {
+            return None;
+        }
+        let mut end = 0.0;
+        sizes.iter().position(|size| {
+            end += size;
+            offset < end
+        })
+    }
+
+    /// Cell text wraps at the column width.
+    ///
+    /// Loaded documents might have cells without a column width, these get the default width.
+    fn cell_text_style(&self, column: usize) -> TextStyle {
+        let column_width = self
+            .column_widths
+            .get(column)
+            .copied()
+            .unwrap_or(Self::COLUMN_WIDTH_DEFAULT);
+        let mut text_style = self.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        text_style.set_max_width(Some((column_width - 2.0 * Self::CELL_PADDING).max(0.0)));
+        text_style
+    }
+
+    fn min_row_height(text_style: &TextStyle) -> f64 {
+        text_style.font_size * 1.2 + 2.0 * Self::CELL_PADDING
+    }
+
+    /// The height needed to display the text of all cells in the row.
+    fn content_height(&self, row: usize) -> f64 {
+        let mut piet_text = piet_cairo::CairoText::new();
+        let Some(cells) = self.cells.get(row) else {
+            return Self::CELL_SIZE_MIN;
+        };
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .filter_map(|(column, text)| {
+                self.cell_text_style(column)
+                    .build_text_layout(&mut piet_text, text.clone())
+                    .ok()
+            })
+            .map(|layout| layout.size().height + 2.0 * Self::CELL_PADDING)
+            .fold(Self::CELL_SIZE_MIN, f64::max)
+    }
+
+    fn fit_row_to_content(&mut self, row: usize) {
+        if row >= self.rows() {
+            return;
+        }
+        self.row_heights[row] = self.row_heights[row].max(self.content_height(row));
+    }
+}
@@ -1,5 +1,5 @@
 // Imports
-use super::Content;
+use super::{Compositing, Content};
 use crate::{Camera, Drawable};
 use itertools::Itertools;
 use kurbo::Shape;
@@ -195,6 +195,9 @@ pub struct TextStyle {
     max_width: Option<f64>,
     #[serde(rename = "alignment")]
     pub alignment: TextAlignment,
+    /// Whether the text is a heading and shows up in the document outline.
+    #[serde(rename = "heading")]
+    pub heading: bool,
 
     #[serde(rename = "ranged_text_attributes")]
     pub ranged_text_attributes: Vec<RangedTextAttribute>,
@@ -210,6 +213,7 @@ impl Default for TextStyle {
             color: Self::FONT_COLOR_DEFAULT,
             max_width: None,
             alignment: TextAlignment::Start,
+            heading: false,
             ranged_text_attributes: vec![],
         }
     }
@@ -441,6 +445,8 @@ pub struct TextStroke {
     pub transform: Transform,
     #[serde(rename = "text_style")]
     pub text_style: TextStyle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
 }
 
 impl Default for TextStroke {
@@ -449,6 +455,7 @@ impl Default for TextStroke {
             text: String::default(),
             transform: Transform::default(),
             text_style: TextStyle::default(),
+            compositing: Compositing::default(),
         }
     }
 }
@@ -540,11 +547,15 @@ impl Drawable for TextStroke {
 }
 
 impl TextStroke {
+    /// The prefix of paragraphs that are bullet list items.
+    pub const BULLET_PREFIX: &'static str = "• ";
+
     pub fn new(text: String, upper_left_pos: na::Vector2<f64>, text_style: TextStyle) -> Self {
         Self {
             text,
             transform: Transform::new_w_isometry(na::Isometry2::new(upper_left_pos, 0.0)),
             text_style,
+            compositing: Compositing::default(),
         }
     }
 
@@ -821,6 +832,73 @@ impl TextStroke {
         self.text_style.ranged_text_attributes = non_matching_attrs;
     }
 
+    /// Toggle the bullet list prefix for all paragraphs touched by the range.
+    ///
+    /// If all paragraphs already are list items, the prefixes are removed, else they are added where missing.
+    /// The cursors are moved accordingly.
+    pub fn toggle_bullet_list(
+        &mut self,
+        cursor: &mut GraphemeCursor,
+        mut selection_cursor: Option<&mut GraphemeCursor>,
+    ) {
+        let bullet_len = Self::BULLET_PREFIX.len();
+        let range = crate::utils::positive_range(
+            cursor.cur_cursor(),
+            selection_cursor
+                .as_ref()
+                .map(|c| c.cur_cursor())
+                .unwrap_or(cursor.cur_cursor()),
+        );
+        let first_start = self.text[..range.start]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let paragraph_starts = std::iter::once(first_start)
+            .chain(
+                self.text[range.clone()]
+                    .match_indices('\n')
+                    .map(|(i, _)| range.start + i + 1),
+            )
+            .collect::<Vec<usize>>();
+        let remove = paragraph_starts
+            .iter()
+            .all(|&start| self.text[start..].starts_with(Self::BULLET_PREFIX));
+
+        let mut cursor_pos = cursor.cur_cursor();
+        let mut selection_cursor_pos = selection_cursor.as_ref().map(|c| c.cur_cursor());
+
+        // Iterating in reverse, so that the paragraph starts stay valid while modifying the text
+        for &start in paragraph_starts.iter().rev() {
+            let offset = if remove {
+                self.text.replace_range(start..start + bullet_len, "");
+                -(bullet_len as i32)
+            } else if !self.text[start..].starts_with(Self::BULLET_PREFIX) {
+                self.text.insert_str(start, Self::BULLET_PREFIX);
+                bullet_len as i32
+            } else {
+                continue;
+            };
+            self.translate_attrs_after_cursor(start, offset);
+
+            for pos in std::iter::once(&mut cursor_pos).chain(selection_cursor_pos.as_mut()) {
+                if *pos >= start {
+                    *pos = if offset >= 0 {
+                        *pos + bullet_len
+                    } else {
+                        start + pos.saturating_sub(start + bullet_len)
+                    };
+                }
+            }
+        }
+
+        *cursor = GraphemeCursor::new(cursor_pos, self.text.len(), true);
+        if let (Some(selection_cursor), Some(selection_cursor_pos)) =
+            (selection_cursor.as_mut(), selection_cursor_pos)
+        {
+            **selection_cursor = GraphemeCursor::new(selection_cursor_pos, self.text.len(), true);
+        }
+    }
+
     pub fn update_selection_entire_text(
         &self,
         cursor: &mut GraphemeCursor,
@@ -1024,3 +1102,107 @@ fn remove_intersecting_attrs_in_range(
         .filter(|attr| !attr.range.is_empty())
         .collect::<Vec<RangedTextAttribute>>()
 }
+
+/// Parse basic markdown into plain text and the ranged text attributes styling it.
+///
+/// Supports headings, bullet lists and inline bold, italic, strikethrough and code spans.
+/// Markup without a closing counterpart is kept as text.
+/// Headings are scaled relative to the given font size.
+pub fn parse_markdown(markdown: &str, font_size: f64) -> (String, Vec<RangedTextAttribute>) {
+    const HEADING_SCALES: [f64; 6] = [2.0, 1.5, 1.25, 1.1, 1.0, 1.0];
+    const BULLET_MARKERS: [&str; 3] = ["- ", "* ", "+ "];
+    let mut text = String::with_capacity(markdown.len());
+    let mut attrs = Vec::new();
+
+    for (i, line) in markdown.lines().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let line_start = text.len();
+        let heading_level = line.chars().take_while(|c| *c == '#').count();
+
+        if (1..=6).contains(&heading_level) && line[heading_level..].starts_with(' ') {
+            parse_markdown_inline(line[heading_level..].trim_start(), &mut text, &mut attrs);
+            let range = line_start..text.len();
+            attrs.push(RangedTextAttribute {
+                range: range.clone(),
+                attribute: TextAttribute::FontWeight(piet::FontWeight::BOLD.to_raw()),
+            });
+            attrs.push(RangedTextAttribute {
+                range,
+                attribute: TextAttribute::FontSize(font_size * HEADING_SCALES[heading_level - 1]),
+            });
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if let Some(item) = BULLET_MARKERS
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            text.push_str(&line[..line.len() - trimmed.len()]);
+            text.push_str("• ");
+            parse_markdown_inline(item, &mut text, &mut attrs);
+        } else {
+            parse_markdown_inline(line, &mut text, &mut attrs);
+        }
+    }
+    if markdown.ends_with('\n') {
+        text.push('\n');
+    }
+
+    (text, attrs)
+}
+
+/// Parse the inline markup of a single markdown line, appending to the text and attributes.
+fn parse_markdown_inline(line: &str, text: &mut String, attrs: &mut Vec<RangedTextAttribute>) {
+    const MARKERS: [&str; 6] = ["**", "__", "~~", "`", "*", "_"];
+    // the currently open markers and the start of their range in the text
+    let mut open: Vec<(&str, usize)> = vec![];
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        // markup is not parsed inside code spans
+        let in_code = open.iter().any(|(marker, _)| *marker == "`");
+        let marker = MARKERS
+            .iter()
+            .copied()
+            .filter(|marker| !in_code || *marker == "`")
+            .find(|marker| rest.starts_with(marker));
+
+        if let Some(marker) = marker {
+            let after = &rest[marker.len()..];
+
+            if let Some(i) = open.iter().rposition(|(m, _)| *m == marker) {
+                let (_, start) = open.remove(i);
+                if start < text.len() {
+                    let attribute = match marker {
+                        "**" | "__" => TextAttribute::FontWeight(piet::FontWeight::BOLD.to_raw()),
+                        "*" | "_" => TextAttribute::Style(FontStyle::Italic),
+                        "~~" => TextAttribute::Strikethrough(true),
+                        _ => TextAttribute::FontFamily(String::from("monospace")),
+                    };
+                    attrs.push(RangedTextAttribute {
+                        range: start..text.len(),
+                        attribute,
+                    });
+                }
+                rest = after;
+                continue;
+            }
+
+            // underscores inside words, like in `snake_case`, are not markup
+            let inside_word =
+                marker.starts_with('_') && text.chars().last().is_some_and(char::is_alphanumeric);
+            let closed = after.find(marker).is_some_and(|i| i > 0);
+            if closed && !inside_word && !after.starts_with(char::is_whitespace) {
+                open.push((marker, text.len()));
+                rest = after;
+                continue;
+            }
+        }
+
+        text.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+}
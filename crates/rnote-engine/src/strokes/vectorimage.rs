@@ -1,19 +1,24 @@
 // Imports
 use super::content::GeneratedContentImages;
 use super::resize::{calculate_resize_ratio, ImageSizeOption};
-use super::{Content, Stroke};
+use super::{Compositing, Content, ShapeStroke, Stroke};
 use crate::document::Format;
 use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
+use crate::engine::progress::Progress;
 use crate::{render, Drawable};
 use kurbo::Shape;
 use p2d::bounding_volume::Aabb;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rnote_compose::color;
 use rnote_compose::ext::AabbExt;
-use rnote_compose::shapes::Rectangle;
 use rnote_compose::shapes::Shapeable;
+use rnote_compose::shapes::{
+    CubicBezier, FillRule, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shape,
+};
+use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
+use rnote_compose::{Color, Style};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use std::sync::Arc;
@@ -31,6 +36,8 @@ pub struct VectorImage {
     pub intrinsic_size: na::Vector2<f64>,
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    #[serde(default, rename = "compositing")]
+    pub compositing: Compositing,
 }
 
 impl Default for VectorImage {
@@ -39,6 +46,7 @@ impl Default for VectorImage {
             svg_data: String::default(),
             intrinsic_size: na::Vector2::zeros(),
             rectangle: Rectangle::default(),
+            compositing: Compositing::default(),
         }
     }
 }
@@ -201,6 +209,7 @@ impl VectorImage {
             svg_data,
             intrinsic_size,
             rectangle,
+            compositing: Compositing::default(),
         })
     }
 
@@ -210,9 +219,11 @@ impl VectorImage {
         insert_pos: na::Vector2<f64>,
         page_range: Option<Range<u32>>,
         format: &Format,
+        progress: &Progress,
     ) -> Result<Vec<Self>, anyhow::Error> {
         let doc = poppler::Document::from_bytes(&glib::Bytes::from(bytes), None)?;
         let page_range = page_range.unwrap_or(0..doc.n_pages() as u32);
+        progress.set_total(page_range.len());
 
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
@@ -230,6 +241,9 @@ impl VectorImage {
 
         let svgs = page_range
             .filter_map(|page_i| {
+                if progress.is_cancelled() {
+                    return None;
+                }
                 let page = doc.page(page_i as i32)?;
                 let intrinsic_size = page.size();
                 let width = intrinsic_size.0 * page_zoom;
@@ -319,15 +333,18 @@ impl VectorImage {
                     };
                 }
 
-                match res() {
+                let svg = match res() {
                     Ok(svg_data) => Some(render::Svg { svg_data, bounds }),
                     Err(e) => {
                         error!("Importing page {page_i} from pdf failed, Err: {e:?}");
                         None
                     }
-                }
+                };
+                progress.advance();
+                svg
             })
             .collect::<Vec<render::Svg>>();
+        progress.check_cancelled()?;
 
         svgs.into_par_iter()
             .map(|svg| {
@@ -340,3 +357,245 @@ impl VectorImage {
             .collect()
     }
 }
+
+/// Converts the paths of a simple Svg into shape strokes, so that they can be edited individually.
+///
+/// Returns `None` when the Svg is not simple, meaning it contains text, images, gradients, patterns, clip-paths,
+/// masks or filters, which can't be represented by shape strokes.
+pub fn shapestrokes_from_svg_str(
+    svg_data: &str,
+    pos: na::Vector2<f64>,
+    size_option: ImageSizeOption,
+) -> anyhow::Result<Option<Vec<ShapeStroke>>> {
+    let svg_tree = usvg::Tree::from_str(
+        svg_data,
+        &usvg::Options {
+            fontdb: Arc::clone(&render::USVG_FONTDB),
+            ..Default::default()
+        },
+    )?;
+    let mut paths = vec![];
+    if !collect_simple_paths(svg_tree.root(), &mut paths) || paths.is_empty() {
+        return Ok(None);
+    }
+
+    let intrinsic_size = na::vector![
+        svg_tree.size().width() as f64,
+        svg_tree.size().height() as f64
+    ];
+    let scale = match size_option {
+        ImageSizeOption::RespectOriginalSize => na::Vector2::repeat(1.0),
+        ImageSizeOption::ImposeSize(given_size) => given_size.component_div(&intrinsic_size),
+        ImageSizeOption::ResizeImage(resize_struct) => {
+            na::Vector2::repeat(calculate_resize_ratio(resize_struct, intrinsic_size, pos))
+        }
+    };
+    let placement = kurbo::Affine::translate((pos[0], pos[1]))
+        * kurbo::Affine::scale_non_uniform(scale[0], scale[1]);
+
+    Ok(Some(
+        paths
+            .into_iter()
+            .flat_map(|path| shapestrokes_from_svg_path(path, placement))
+            .collect(),
+    ))
+}
+
+/// Collects the paths of the group and its children.
+///
+/// Returns false when the group contains content that is not simple.
+fn collect_simple_paths<'a>(group: &'a usvg::Group, paths: &mut Vec<&'a usvg::Path>) -> bool {
+    if group.clip_path().is_some() || group.mask().is_some() || !group.filters().is_empty() {
+        return false;
+    }
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(group) => {
+                if !collect_simple_paths(group, paths) {
+                    return false;
+                }
+            }
+            usvg::Node::Path(path) => {
+                let simple_paint = |paint: &usvg::Paint| matches!(paint, usvg::Paint::Color(_));
+                if path.fill().is_some_and(|fill| !simple_paint(fill.paint()))
+                    || path
+                        .stroke()
+                        .is_some_and(|stroke| !simple_paint(stroke.paint()))
+                {
+                    return false;
+                }
+                if path.is_visible() {
+                    paths.push(path);
+                }
+            }
+            usvg::Node::Image(_) | usvg::Node::Text(_) => return false,
+        }
+    }
+    true
+}
+
+/// Converts a Svg path into shape strokes, one for each of its sub-paths.
+///
+/// Sub-paths that consist of a single segment become lines or bezier curves, others are flattened into polylines,
+/// or polygons when they are closed. Filled paths with several sub-paths become a single compound polygon with the
+/// fill rule of the path, so that holes stay cut out.
+fn shapestrokes_from_svg_path(path: &usvg::Path, placement: kurbo::Affine) -> Vec<ShapeStroke> {
+    /// The tolerance when flattening curves.
+    const FLATTEN_TOLERANCE: f64 = 0.1;
+    let ts = path.abs_transform();
+    let transform = placement
+        * kurbo::Affine::new([
+            ts.sx as f64,
+            ts.ky as f64,
+            ts.kx as f64,
+            ts.sy as f64,
+            ts.tx as f64,
+            ts.ty as f64,
+        ]);
+    let to_color = |paint: &usvg::Paint, opacity: f32| match paint {
+        usvg::Paint::Color(c) => Some(Color::new(
+            f64::from(c.red) / 255.0,
+            f64::from(c.green) / 255.0,
+            f64::from(c.blue) / 255.0,
+            f64::from(opacity),
+        )),
+        _ => None,
+    };
+    let style = Style::Smooth(SmoothOptions {
+        // Non-uniform scaling can't be represented by the stroke width, the average scale is used instead
+        stroke_width: path
+            .stroke()
+            .map(|stroke| f64::from(stroke.width().get()) * transform.determinant().abs().sqrt())
+            .unwrap_or(0.0),
+        stroke_color: path
+            .stroke()
+            .and_then(|stroke| to_color(stroke.paint(), stroke.opacity().get())),
+        fill_color: path
+            .fill()
+            .and_then(|fill| to_color(fill.paint(), fill.opacity().get())),
+        ..Default::default()
+    });
+
+    let mut bez_path = kurbo::BezPath::new();
+    for segment in path.data().segments() {
+        let to_kurbo = |p: usvg::tiny_skia_path::Point| kurbo::Point::new(p.x as f64, p.y as f64);
+        match segment {
+            usvg::tiny_skia_path::PathSegment::MoveTo(p) => bez_path.move_to(to_kurbo(p)),
+            usvg::tiny_skia_path::PathSegment::LineTo(p) => bez_path.line_to(to_kurbo(p)),
+            usvg::tiny_skia_path::PathSegment::QuadTo(p1, p2) => {
+                bez_path.quad_to(to_kurbo(p1), to_kurbo(p2))
+            }
+            usvg::tiny_skia_path::PathSegment::CubicTo(p1, p2, p3) => {
+                bez_path.curve_to(to_kurbo(p1), to_kurbo(p2), to_kurbo(p3))
+            }
+            usvg::tiny_skia_path::PathSegment::Close => bez_path.close_path(),
+        }
+    }
+    bez_path.apply_affine(transform);
+
+    let to_vec = |p: kurbo::Point| na::vector![p.x, p.y];
+    let flattened = |subpath: &kurbo::BezPath| {
+        let mut points = vec![];
+        kurbo::flatten(
+            subpath.elements().iter().copied(),
+            FLATTEN_TOLERANCE,
+            |el| match el {
+                kurbo::PathEl::MoveTo(p) | kurbo::PathEl::LineTo(p) => points.push(to_vec(p)),
+                _ => {}
+            },
+        );
+        points
+    };
+
+    let mut subpaths = vec![];
+    let mut subpath = kurbo::BezPath::new();
+    for el in bez_path.elements() {
+        if matches!(el, kurbo::PathEl::MoveTo(_)) && !subpath.elements().is_empty() {
+            subpaths.push(subpath);
+            subpath = kurbo::BezPath::new();
+        }
+        subpath.push(*el);
+    }
+    if !subpath.elements().is_empty() {
+        subpaths.push(subpath);
+    }
+
+    if let Some(fill) = path.fill().filter(|_| subpaths.len() > 1) {
+        // Filling closes all sub-paths, so they are all part of the compound polygon
+        let mut rings = subpaths
+            .iter()
+            .map(flattened)
+            .filter(|points| points.len() >= 2)
+            .collect::<Vec<Vec<na::Vector2<f64>>>>();
+        if rings.is_empty() {
+            return vec![];
+        }
+        let mut outer = rings.remove(0);
+        let start = outer.remove(0);
+        let polygon = Polygon {
+            start,
+            path: outer,
+            subpaths: rings,
+            fill_rule: match fill.rule() {
+                usvg::FillRule::NonZero => FillRule::NonZero,
+                usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+            },
+        };
+        return vec![ShapeStroke::new(Shape::Polygon(polygon), style)];
+    }
+
+    let mut shapes = vec![];
+    let push_subpath = |subpath: &kurbo::BezPath, shapes: &mut Vec<Shape>| {
+        let closed = subpath
+            .elements()
+            .last()
+            .is_some_and(|el| *el == kurbo::PathEl::ClosePath);
+        let segments = subpath.segments().collect::<Vec<kurbo::PathSeg>>();
+        let shape = match segments.as_slice() {
+            [] => return,
+            [kurbo::PathSeg::Line(line)] if !closed => Shape::Line(Line {
+                start: to_vec(line.p0),
+                end: to_vec(line.p1),
+            }),
+            [kurbo::PathSeg::Quad(quad)] if !closed => Shape::QuadraticBezier(QuadraticBezier {
+                start: to_vec(quad.p0),
+                cp: to_vec(quad.p1),
+                end: to_vec(quad.p2),
+            }),
+            [kurbo::PathSeg::Cubic(cubic)] if !closed => Shape::CubicBezier(CubicBezier {
+                start: to_vec(cubic.p0),
+                cp1: to_vec(cubic.p1),
+                cp2: to_vec(cubic.p2),
+                end: to_vec(cubic.p3),
+            }),
+            _ => {
+                let mut points = flattened(subpath);
+                if points.len() < 2 {
+                    return;
+                }
+                let start = points.remove(0);
+                if closed {
+                    Shape::Polygon(Polygon {
+                        start,
+                        path: points,
+                        ..Default::default()
+                    })
+                } else {
+                    Shape::Polyline(Polyline {
+                        start,
+                        path: points,
+                    })
+                }
+            }
+        };
+        shapes.push(shape);
+    };
+    for subpath in subpaths.iter() {
+        push_subpath(subpath, &mut shapes);
+    }
+
+    shapes
+        .into_iter()
+        .map(|shape| ShapeStroke::new(shape, style.clone()))
+        .collect()
+}
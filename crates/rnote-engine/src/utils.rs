@@ -4,6 +4,7 @@ use geo::line_string;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::Color;
 use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 pub const fn crate_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -31,10 +32,39 @@ pub fn now_formatted_string() -> String {
     chrono::Local::now().format("%Y-%m-%d_%H:%M:%S").to_string()
 }
 
+/// The Rnote files in the directory, sorted by path. Subdirectories are not searched.
+pub fn rnote_files_in_dir(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut rnote_files = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rnote"))
+        .collect::<Vec<PathBuf>>();
+    rnote_files.sort();
+    Ok(rnote_files)
+}
+
 pub fn doc_pages_files_names(file_stem_name: String, i: usize) -> String {
     file_stem_name + &format!(" - Page {i:02}")
 }
 
+/// The file stem names for exported frames.
+///
+/// Path separators in the names are replaced, unnamed frames are numbered and duplicate names get the frame number appended.
+pub fn frames_files_stem_names<'a>(frame_names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut stem_names: Vec<String> = Vec::new();
+    for (i, name) in frame_names.into_iter().enumerate() {
+        let name = name.trim().replace(['/', '\\'], "-");
+        let stem_name = if name.is_empty() {
+            format!("Frame {:02}", i + 1)
+        } else if stem_names.contains(&name) {
+            format!("{name} - Frame {:02}", i + 1)
+        } else {
+            name
+        };
+        stem_names.push(stem_name);
+    }
+    stem_names
+}
+
 pub fn convert_value_dpi(value: f64, current_dpi: f64, target_dpi: f64) -> f64 {
     (value / current_dpi) * target_dpi
 }
@@ -82,6 +112,28 @@ where
     }
 }
 
+/// Deserialize the value of the key in the Json object.
+///
+/// Falls back to the default when the key is missing or the value can't be deserialized,
+/// for example because its format has changed between versions.
+pub fn deserialize_field_or_default<T>(
+    object: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> T
+where
+    T: serde::de::DeserializeOwned + Default,
+{
+    let Some(value) = object.get(key) else {
+        return T::default();
+    };
+    serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Deserializing field `{key}` failed, falling back to the default. Err: {e:?}"
+        );
+        T::default()
+    })
+}
+
 /// (De)Serialize a [glib::Bytes] with base64 encoding
 pub mod glib_bytes_base64 {
     use serde::{Deserializer, Serializer};
@@ -0,0 +1,227 @@
+//! Tracing bitmap images into vector shapes.
+//!
+//! The image is thresholded into a mask of its dark foreground pixels. The outlines of the mask are then traced,
+//! simplified and filled with the mean foreground color.
+
+// Imports
+use crate::fill::{bridge_holes, Mask};
+use crate::strokes::{BitmapImage, ShapeStroke};
+use geo::{Contains, Simplify};
+use rnote_compose::shapes::{Polygon, Shape};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::{Color, Style};
+
+/// Pixels with a lower alpha value are part of the background.
+const ALPHA_THRESHOLD: u8 = 128;
+/// Images with a larger width or height in pixels are downscaled to it before tracing.
+const TRACE_EXTENT_MAX: u32 = 1024;
+/// Outlines enclosing a smaller area in pixels are dropped as speckles.
+const AREA_MIN: f64 = 6.0;
+/// The tolerance in pixels when simplifying the outlines.
+const SIMPLIFY_EPSILON: f64 = 0.75;
+
+/// Traces the bitmap image into filled polygon shapes.
+///
+/// Returns an empty vec when no foreground could be separated from the background.
+pub(crate) fn trace_bitmapimage(bitmapimage: &BitmapImage) -> anyhow::Result<Vec<ShapeStroke>> {
+    let mut imgbuf = bitmapimage.image.clone().into_imgbuf()?;
+    if imgbuf.width().max(imgbuf.height()) > TRACE_EXTENT_MAX {
+        imgbuf = image::DynamicImage::ImageRgba8(imgbuf)
+            .resize(
+                TRACE_EXTENT_MAX,
+                TRACE_EXTENT_MAX,
+                image::imageops::FilterType::Triangle,
+            )
+            .into_rgba8();
+    }
+    let width = imgbuf.width() as usize;
+    let height = imgbuf.height() as usize;
+
+    // The unpremultiplied colors of the opaque pixels
+    let colors = imgbuf
+        .pixels()
+        .map(|pixel| {
+            if pixel[3] < ALPHA_THRESHOLD {
+                return None;
+            }
+            let alpha = f64::from(pixel[3]);
+            Some([
+                f64::from(pixel[0]) / alpha,
+                f64::from(pixel[1]) / alpha,
+                f64::from(pixel[2]) / alpha,
+            ])
+        })
+        .collect::<Vec<Option<[f64; 3]>>>();
+    let Some(threshold) = otsu_threshold(colors.iter().flatten().map(luminance)) else {
+        return Ok(vec![]);
+    };
+    let foreground = Mask {
+        width,
+        height,
+        data: colors
+            .iter()
+            .map(|color| color.is_some_and(|color| luminance(&color) <= threshold))
+            .collect(),
+    };
+
+    let (color_sum, n_foreground) = colors
+        .iter()
+        .zip(foreground.data.iter())
+        .filter_map(|(color, &is_foreground)| color.filter(|_| is_foreground))
+        .fold(([0.0; 3], 0_usize), |(mut sum, n), color| {
+            sum.iter_mut().zip(color).for_each(|(s, c)| *s += c);
+            (sum, n + 1)
+        });
+    if n_foreground == 0 {
+        return Ok(vec![]);
+    }
+    let fill_color = Color::new(
+        color_sum[0] / n_foreground as f64,
+        color_sum[1] / n_foreground as f64,
+        color_sum[2] / n_foreground as f64,
+        1.0,
+    );
+
+    let mut outers = vec![];
+    let mut holes = vec![];
+    for outline in foreground.outline_loops() {
+        let line_string = geo::LineString::from(
+            outline
+                .into_iter()
+                .map(|(x, y)| (x as f64, y as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        // Outer outlines are oriented with a positive, holes with a negative area
+        let area = signed_area(&line_string);
+        if area.abs() < AREA_MIN {
+            continue;
+        }
+        let simplified = line_string.simplify(&SIMPLIFY_EPSILON);
+        if simplified.0.len() < 3 {
+            continue;
+        }
+        if area > 0.0 {
+            outers.push((geo::Polygon::new(simplified, vec![]), area, vec![]));
+        } else {
+            holes.push(simplified);
+        }
+    }
+
+    // Every hole belongs to the smallest outer outline that contains it
+    for hole in holes {
+        let point = geo::Point::from(hole.0[0]);
+        if let Some((_, _, outer_holes)) = outers
+            .iter_mut()
+            .filter(|(outer, _, _)| outer.contains(&point))
+            .min_by(|(_, a0, _), (_, a1, _)| a0.total_cmp(a1))
+        {
+            outer_holes.push(hole);
+        }
+    }
+
+    let local_aabb = bitmapimage.rectangle.cuboid.local_aabb();
+    let pixel_scale = na::vector![
+        local_aabb.extents()[0] / width as f64,
+        local_aabb.extents()[1] / height as f64
+    ];
+    let to_doc_coords = |coord: &geo::Coord<f64>| {
+        bitmapimage
+            .rectangle
+            .transform
+            .transform_point(
+                local_aabb.mins + na::vector![coord.x, coord.y].component_mul(&pixel_scale),
+            )
+            .coords
+    };
+    let style = Style::Smooth(SmoothOptions {
+        stroke_color: None,
+        fill_color: Some(fill_color),
+        ..Default::default()
+    });
+
+    Ok(outers
+        .into_iter()
+        .map(|(outer, _, outer_holes)| {
+            // The exterior of the polygon is closed, so its first coordinate is repeated at the end
+            let path = bridge_holes(
+                outer
+                    .exterior()
+                    .coords()
+                    .skip(1)
+                    .map(to_doc_coords)
+                    .collect(),
+                outer_holes
+                    .iter()
+                    .map(|hole| hole.coords().map(to_doc_coords).collect())
+                    .collect(),
+            );
+            ShapeStroke::new(
+                Shape::Polygon(Polygon {
+                    start: path[0],
+                    path: path[1..].to_vec(),
+                    ..Default::default()
+                }),
+                style.clone(),
+            )
+        })
+        .collect())
+}
+
+/// The relative luminance of the color.
+fn luminance(color: &[f64; 3]) -> f64 {
+    0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2]
+}
+
+/// The shoelace area of the outline, positive when it is oriented clockwise in the y-down coordinate space.
+fn signed_area(line_string: &geo::LineString<f64>) -> f64 {
+    let coords = &line_string.0;
+    coords
+        .iter()
+        .zip(coords.iter().cycle().skip(1))
+        .map(|(c0, c1)| c0.x * c1.y - c1.x * c0.y)
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Computes the threshold that best separates the values in the range `[0.0, 1.0]` into two classes, with Otsu's method.
+///
+/// Returns `None` when there are no values.
+fn otsu_threshold(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut histogram = [0_usize; 256];
+    let mut total = 0_usize;
+    for value in values {
+        histogram[(value.clamp(0.0, 1.0) * 255.0).round() as usize] += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return None;
+    }
+    let sum_total = histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| i as f64 * count as f64)
+        .sum::<f64>();
+
+    let mut sum_lower = 0.0;
+    let mut weight_lower = 0_usize;
+    let mut best = (0.0, 0_usize);
+    for (i, &count) in histogram.iter().enumerate() {
+        weight_lower += count;
+        if weight_lower == 0 {
+            continue;
+        }
+        let weight_upper = total - weight_lower;
+        if weight_upper == 0 {
+            break;
+        }
+        sum_lower += i as f64 * count as f64;
+        let mean_lower = sum_lower / weight_lower as f64;
+        let mean_upper = (sum_total - sum_lower) / weight_upper as f64;
+        let variance =
+            weight_lower as f64 * weight_upper as f64 * (mean_lower - mean_upper).powi(2);
+        if variance > best.0 {
+            best = (variance, i);
+        }
+    }
+    Some(best.1 as f64 / 255.0)
+}
@@ -18,6 +18,12 @@ pub struct WidgetFlags {
     pub zoomed: bool,
     /// Deselect the elements of the global color picker.
     pub deselect_color_setters: bool,
+    /// Open the radial quick menu at the current pointer position.
+    pub open_radial_menu: bool,
+    /// Open the context menu at the current pointer position.
+    pub open_context_menu: bool,
+    /// Is Some when the UI should open the URI, for example when a link was clicked.
+    pub open_uri: Option<String>,
     /// Is Some when undo button visibility should be changed. Is None if should not be changed.
     pub hide_undo: Option<bool>,
     /// Is Some when redo button visibility should be changed. Is None if should not be changed.
@@ -39,6 +45,9 @@ impl Default for WidgetFlags {
             zoomed_temporarily: false,
             zoomed: false,
             deselect_color_setters: false,
+            open_radial_menu: false,
+            open_context_menu: false,
+            open_uri: None,
             hide_undo: None,
             hide_redo: None,
             enable_text_preprocessing: None,
@@ -65,6 +74,11 @@ impl std::ops::BitOrAssign for WidgetFlags {
         self.zoomed_temporarily |= rhs.zoomed_temporarily;
         self.zoomed |= rhs.zoomed;
         self.deselect_color_setters |= rhs.deselect_color_setters;
+        self.open_radial_menu |= rhs.open_radial_menu;
+        self.open_context_menu |= rhs.open_context_menu;
+        if rhs.open_uri.is_some() {
+            self.open_uri = rhs.open_uri;
+        }
         if rhs.hide_undo.is_some() {
             self.hide_undo = rhs.hide_undo
         }
@@ -0,0 +1,76 @@
+//! Tests for the encryption of `.rnote` files.
+
+// Imports
+use rnote_engine::fileformats::rnoteformat::encryption::{self, DecryptError};
+
+#[test]
+fn encrypt_decrypt_roundtrip() {
+    let bytes = b"some rnote file bytes".to_vec();
+    let encrypted = encryption::encrypt(&bytes, "passphrase").unwrap();
+
+    assert!(encryption::is_encrypted(&encrypted));
+    assert!(!encryption::is_encrypted(&bytes));
+    assert_eq!(
+        encryption::decrypt(&encrypted, "passphrase").unwrap(),
+        bytes
+    );
+}
+
+#[test]
+fn decrypt_wrong_passphrase() {
+    let encrypted = encryption::encrypt(b"some rnote file bytes", "passphrase").unwrap();
+
+    assert!(matches!(
+        encryption::decrypt(&encrypted, "wrong passphrase"),
+        Err(DecryptError::WrongPassphrase)
+    ));
+}
+
+#[test]
+fn decrypt_modified_header() {
+    let mut encrypted = encryption::encrypt(b"some rnote file bytes", "passphrase").unwrap();
+    // Flip a bit in the salt, which follows the version and the key derivation parameters
+    encrypted[encryption::MAGIC.len() + 1 + 3 * 4] ^= 1;
+
+    assert!(encryption::decrypt(&encrypted, "passphrase").is_err());
+}
+
+#[test]
+fn encrypt_writes_key_derivation_params() {
+    let encrypted = encryption::encrypt(b"some rnote file bytes", "passphrase").unwrap();
+    let cost = |i: usize| {
+        let start = encryption::MAGIC.len() + 1 + i * 4;
+        u32::from_le_bytes(encrypted[start..start + 4].try_into().unwrap())
+    };
+
+    assert_eq!(cost(0), encryption::KDF_M_COST);
+    assert_eq!(cost(1), encryption::KDF_T_COST);
+    assert_eq!(cost(2), encryption::KDF_P_COST);
+}
+
+#[test]
+fn decrypt_fixed_header() {
+    // Encrypted with Argon2id m_cost: 64, t_cost: 1, p_cost: 1, which differ from the parameters used for encrypting,
+    // so the parameters must be read from the header.
+    let mut encrypted = encryption::MAGIC.to_vec();
+    encrypted.push(2);
+    for cost in [64_u32, 1, 1] {
+        encrypted.extend_from_slice(&cost.to_le_bytes());
+    }
+    encrypted.extend(0..16_u8);
+    encrypted.extend(0..12_u8);
+    encrypted.extend_from_slice(&[
+        0xc4, 0x03, 0xe8, 0xcf, 0x0e, 0x8e, 0x36, 0x4b, 0xe8, 0xd6, 0x89, 0xd8, 0xfb, 0x75, 0x61,
+        0x08, 0x51, 0x44, 0x7f, 0xf5, 0x52, 0x8a, 0xa2, 0x1e, 0x6c, 0x17, 0xe9, 0xdb, 0xe4, 0x61,
+        0x31, 0xe2, 0xba, 0x5d, 0xd3, 0xcd, 0x17,
+    ]);
+
+    assert_eq!(
+        encryption::decrypt(&encrypted, "passphrase").unwrap(),
+        b"some rnote file bytes"
+    );
+    assert!(matches!(
+        encryption::decrypt(&encrypted, "wrong passphrase"),
+        Err(DecryptError::WrongPassphrase)
+    ));
+}
@@ -0,0 +1,164 @@
+//! Tests for loading `.rnote` files.
+
+// Imports
+use nalgebra as na;
+use rnote_compose::penpath::Element;
+use rnote_compose::shapes::Rectangle;
+use rnote_compose::{PenPath, Shape, Style};
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::fileformats::enexformat::EnexNote;
+use rnote_engine::fileformats::rnoteformat::{
+    encryption, RnoteDocument, RnoteDocumentError, RnoteDocumentStroke,
+};
+use rnote_engine::store::chrono_comp::StrokeLayer;
+use rnote_engine::strokes::{BrushStroke, ShapeStroke, Stroke, TableStroke};
+use std::io::{Read, Write};
+
+fn document() -> RnoteDocument {
+    let elements = (0..10).map(|i| Element::new(na::vector![i as f64 * 10.0, 0.0], 0.5));
+    let brushstroke = Stroke::BrushStroke(BrushStroke::from_penpath(
+        PenPath::try_from_elements(elements).unwrap(),
+        Style::default(),
+    ));
+    let shapestroke = Stroke::ShapeStroke(ShapeStroke::new(
+        Shape::Rectangle(Rectangle::from_corners(
+            na::vector![0.0, 0.0],
+            na::vector![80.0, 50.0],
+        )),
+        Style::default(),
+    ));
+    RnoteDocument {
+        strokes: [brushstroke, shapestroke]
+            .into_iter()
+            .map(|stroke| RnoteDocumentStroke {
+                stroke,
+                layer: StrokeLayer::default(),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Decompress the bytes of a `.rnote` file into its JSON value.
+fn to_json(bytes: &[u8]) -> serde_json::Value {
+    let mut json = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes)
+        .read_to_end(&mut json)
+        .unwrap();
+    serde_json::from_slice(&json).unwrap()
+}
+
+/// Compress a JSON value into the bytes of a `.rnote` file.
+fn from_json(value: &serde_json::Value) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&serde_json::to_vec(value).unwrap())
+        .unwrap();
+    encoder.finish().unwrap()
+}
+
+/// Remove all fields with the given name, at any depth.
+fn remove_fields(value: &mut serde_json::Value, name: &str) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.remove(name);
+            map.values_mut().for_each(|v| remove_fields(v, name));
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(|v| remove_fields(v, name)),
+        _ => {}
+    }
+}
+
+#[test]
+fn load_document_without_compositing() {
+    // Documents that were saved before strokes had a compositing
+    let mut json = to_json(&document().to_rnote_bytes().unwrap());
+    remove_fields(&mut json, "compositing");
+
+    let loaded = RnoteDocument::from_rnote_bytes(&from_json(&json)).unwrap();
+    assert_eq!(loaded.strokes.len(), 2);
+    for s in loaded.strokes {
+        let compositing = match s.stroke {
+            Stroke::BrushStroke(brushstroke) => brushstroke.compositing,
+            Stroke::ShapeStroke(shapestroke) => shapestroke.compositing,
+            stroke => panic!("Unexpected stroke {stroke:?}"),
+        };
+        assert!(compositing.is_identity());
+    }
+}
+
+#[test]
+fn recover_document_with_broken_stroke() {
+    let mut json = to_json(&document().to_rnote_bytes().unwrap());
+    let stroke_slots = json["data"]["engine_snapshot"]["stroke_components"]
+        .as_array_mut()
+        .unwrap();
+    // The first slot is always vacant
+    assert_eq!(stroke_slots.len(), 3);
+    stroke_slots[1]["value"] = serde_json::json!({ "brushstroke": { "path": "broken" } });
+
+    let (snapshot, dropped) = futures::executor::block_on(
+        EngineSnapshot::load_from_rnote_bytes_w_recovery(from_json(&json)),
+    )
+    .unwrap();
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(snapshot.stroke_components.len(), 1);
+    assert_eq!(snapshot.chrono_components.len(), 1);
+    let (key, stroke) = snapshot.stroke_components.iter().next().unwrap();
+    assert!(matches!(stroke.as_ref(), Stroke::ShapeStroke(_)));
+    assert!(snapshot.chrono_components.contains_key(key));
+
+    // The recovered slots are consistent, so the snapshot can be saved and loaded again
+    let value = serde_json::to_value(&snapshot).unwrap();
+    let reloaded = serde_json::from_value::<EngineSnapshot>(value).unwrap();
+    assert_eq!(reloaded.stroke_components.len(), 1);
+}
+
+#[test]
+fn read_document_with_broken_stroke() {
+    let mut json = to_json(&document().to_rnote_bytes().unwrap());
+    json["data"]["engine_snapshot"]["stroke_components"][1]["value"] =
+        serde_json::json!({ "brushstroke": { "path": "broken" } });
+
+    let (loaded, dropped) = RnoteDocument::from_rnote_bytes_w_recovery(&from_json(&json)).unwrap();
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(loaded.strokes.len(), 1);
+    assert!(matches!(loaded.strokes[0].stroke, Stroke::ShapeStroke(_)));
+}
+
+#[test]
+fn read_encrypted_document() {
+    let bytes = encryption::encrypt(&document().to_rnote_bytes().unwrap(), "passphrase").unwrap();
+    assert!(matches!(
+        RnoteDocument::from_rnote_bytes(&bytes),
+        Err(RnoteDocumentError::Encrypted)
+    ));
+}
+
+#[test]
+fn enex_note_content_with_html_entities() {
+    let note = EnexNote {
+        content: String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+<en-note><div>One&nbsp;two &mdash; three &amp; four</div><div>&unknown;</div></en-note>"#,
+        ),
+        ..Default::default()
+    };
+    assert_eq!(
+        note.content_as_text(),
+        "One\u{a0}two \u{2014} three & four\n&unknown;"
+    );
+}
+
+#[test]
+fn table_with_missing_column_widths() {
+    let mut table = serde_json::from_value::<TableStroke>(serde_json::json!({
+        "column_widths": [100.0],
+        "row_heights": [40.0],
+        "cells": [["first", "second"]]
+    }))
+    .unwrap();
+    table.set_cell_text(0, 1, String::from("changed"));
+    assert_eq!(table.cell_text(0, 1), Some("changed"));
+}
@@ -0,0 +1,162 @@
+//! Golden image tests for stroke rendering.
+//!
+//! Each test renders a small document headlessly and compares the result against a golden image in `tests/golden/`.
+//! This catches rendering regressions like changed stroke widths, pressure mapping or offsets.
+//!
+//! To create or update the golden images after an intended change in rendering, run the tests with
+//! `RNOTE_BLESS_GOLDEN=1` and commit the changed images. Without it, a missing golden image fails the test.
+//! On a mismatch, the actual rendering is written to the temporary directory of the build as `<name>.actual.png`.
+
+// Imports
+use nalgebra as na;
+use rnote_compose::penpath::Element;
+use rnote_compose::shapes::{Line, Rectangle};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::style::PressureCurve;
+use rnote_compose::transform::Transformable;
+use rnote_compose::{PenPath, Shape, Style};
+use rnote_engine::engine::StrokeContent;
+use rnote_engine::strokes::{BrushStroke, ShapeStroke, Stroke};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The maximum allowed difference of a single channel of a pixel.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// The maximum allowed fraction of pixels that exceed the channel tolerance.
+const MISMATCH_TOLERANCE: f64 = 0.001;
+/// The margin around the rendered strokes.
+const MARGIN: f64 = 4.0;
+
+/// The directory for the renderings that don't match, in the target directory of the build.
+fn actual_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+}
+
+fn render(strokes: Vec<Stroke>) -> anyhow::Result<image::RgbaImage> {
+    let content =
+        StrokeContent::default().with_strokes(strokes.into_iter().map(Arc::new).collect());
+    let svg = content
+        .gen_svg(false, false, false, MARGIN)?
+        .ok_or_else(|| anyhow::anyhow!("Rendered content is empty."))?;
+    svg.gen_image(1.0)?.into_imgbuf()
+}
+
+fn assert_golden(name: &str, actual: &image::RgbaImage) {
+    let golden_path = golden_dir().join(format!("{name}.png"));
+    let actual_path = actual_dir().join(format!("{name}.actual.png"));
+    let bless = std::env::var("RNOTE_BLESS_GOLDEN").is_ok_and(|v| v == "1");
+
+    if bless {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        actual.save(&golden_path).unwrap();
+        return;
+    }
+    if !golden_path.exists() {
+        panic!(
+            "Golden image '{}' is missing. Run the tests with `RNOTE_BLESS_GOLDEN=1` to create it.",
+            golden_path.display()
+        );
+    }
+
+    let golden = image::open(&golden_path)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Opening golden image '{}' failed, Err: {e:?}",
+                golden_path.display()
+            )
+        })
+        .into_rgba8();
+
+    let mismatch = if golden.dimensions() != actual.dimensions() {
+        Some(format!(
+            "dimensions differ, golden: {:?}, actual: {:?}",
+            golden.dimensions(),
+            actual.dimensions()
+        ))
+    } else {
+        let n_mismatched = golden
+            .pixels()
+            .zip(actual.pixels())
+            .filter(|(g, a)| {
+                g.0.iter()
+                    .zip(a.0.iter())
+                    .any(|(g, a)| g.abs_diff(*a) > CHANNEL_TOLERANCE)
+            })
+            .count();
+        let n_pixels = (golden.width() * golden.height()).max(1) as f64;
+        (n_mismatched as f64 / n_pixels > MISMATCH_TOLERANCE)
+            .then(|| format!("{n_mismatched} of {n_pixels} pixels differ"))
+    };
+
+    if let Some(mismatch) = mismatch {
+        std::fs::create_dir_all(actual_dir()).unwrap();
+        actual.save(&actual_path).unwrap();
+        panic!(
+            "Rendering of '{name}' does not match the golden image: {mismatch}. The actual rendering was written to '{}'.",
+            actual_path.display()
+        );
+    } else if actual_path.exists() {
+        let _ = std::fs::remove_file(&actual_path);
+    }
+}
+
+fn brushstroke(pressure_curve: PressureCurve, stroke_width: f64) -> Stroke {
+    let elements = (0..=20).map(|i| {
+        let t = i as f64 / 20.0;
+        Element::new(
+            na::vector![t * 200.0, (t * std::f64::consts::TAU).sin() * 30.0],
+            0.1 + t * 0.9,
+        )
+    });
+    let style = Style::Smooth(SmoothOptions {
+        stroke_width,
+        pressure_curve,
+        ..Default::default()
+    });
+    Stroke::BrushStroke(BrushStroke::from_penpath(
+        PenPath::try_from_elements(elements).unwrap(),
+        style,
+    ))
+}
+
+#[test]
+fn brushstroke_const_width() {
+    let image = render(vec![brushstroke(PressureCurve::Const, 4.0)]).unwrap();
+    assert_golden("brushstroke_const_width", &image);
+}
+
+#[test]
+fn brushstroke_pressure_linear() {
+    let image = render(vec![brushstroke(PressureCurve::Linear, 8.0)]).unwrap();
+    assert_golden("brushstroke_pressure_linear", &image);
+}
+
+#[test]
+fn shapestrokes_offset() {
+    let style = Style::Smooth(SmoothOptions {
+        stroke_width: 3.0,
+        ..Default::default()
+    });
+    let rectangle = ShapeStroke::new(
+        Shape::Rectangle(Rectangle::from_corners(
+            na::vector![0.0, 0.0],
+            na::vector![80.0, 50.0],
+        )),
+        style.clone(),
+    );
+    let mut line = Stroke::ShapeStroke(ShapeStroke::new(
+        Shape::Line(Line::new(na::vector![0.0, 0.0], na::vector![80.0, 50.0])),
+        style,
+    ));
+    // The offset must be applied to the rendering exactly, otherwise the line does not line up with the rectangle.
+    line.translate(na::vector![40.0, 25.0]);
+
+    let image = render(vec![Stroke::ShapeStroke(rectangle), line]).unwrap();
+    assert_golden("shapestrokes_offset", &image);
+}
@@ -0,0 +1,236 @@
+//! Tests for using the engine without any UI toolkit.
+//!
+//! They only use the engine API that is available without the `ui` feature. The `engine-cargo-check` meson target
+//! builds them without it, which verifies that editing and exporting a document doesn't depend on Gtk.
+
+// Imports
+use nalgebra as na;
+use rnote_compose::penpath::Element;
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::{Color, PenPath, Shape, Style};
+use rnote_engine::document::Layout;
+use rnote_engine::engine::export::{DocExportFormat, DocExportPrefs, FigureSnippetMarkup};
+use rnote_engine::engine::{EngineSnapshot, Progress};
+use rnote_engine::store::ChronoComponent;
+use rnote_engine::strokes::connectorstroke::{ConnectorAttachment, ConnectorEnd, ConnectorRouting};
+use rnote_engine::strokes::{BitmapImage, BrushStroke, ConnectorStroke, ShapeStroke, Stroke};
+use rnote_engine::Engine;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn strokes() -> Vec<Stroke> {
+    let elements = (0..10).map(|i| Element::new(na::vector![i as f64 * 10.0, 0.0], 0.5));
+    vec![
+        Stroke::BrushStroke(BrushStroke::from_penpath(
+            PenPath::try_from_elements(elements).unwrap(),
+            Style::default(),
+        )),
+        Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Rectangle(Rectangle::from_corners(
+                na::vector![0.0, 0.0],
+                na::vector![80.0, 50.0],
+            )),
+            Style::default(),
+        )),
+    ]
+}
+
+#[test]
+fn edit_and_undo() {
+    let mut engine = Engine::default();
+    let _ = engine.import_generated_content(
+        strokes().into_iter().map(|stroke| (stroke, None)).collect(),
+        false,
+    );
+    assert_eq!(engine.extract_document_content().strokes.len(), 2);
+
+    let _ = engine.undo(Instant::now());
+    assert!(engine.extract_document_content().strokes.is_empty());
+
+    let _ = engine.redo(Instant::now());
+    assert_eq!(engine.extract_document_content().strokes.len(), 2);
+}
+
+#[test]
+fn undo_adding_and_removing_pages() {
+    let mut engine = Engine::default();
+    let _ = engine.set_doc_layout(Layout::FixedSize);
+    let page_height = engine.document.format.height();
+    let _ = engine.doc_add_page_fixed_size();
+    let height = engine.document.height;
+
+    // Every added or removed page is its own undo step
+    let _ = engine.doc_add_page_fixed_size();
+    assert_eq!(engine.document.height, height + page_height);
+    let _ = engine.undo(Instant::now());
+    assert_eq!(engine.document.height, height);
+    let _ = engine.redo(Instant::now());
+    assert_eq!(engine.document.height, height + page_height);
+
+    let _ = engine.doc_remove_page_fixed_size();
+    assert_eq!(engine.document.height, height);
+    let _ = engine.undo(Instant::now());
+    assert_eq!(engine.document.height, height + page_height);
+}
+
+#[test]
+fn export_document_as_svg() {
+    let mut engine = Engine::default();
+    let _ = engine.import_generated_content(
+        strokes().into_iter().map(|stroke| (stroke, None)).collect(),
+        false,
+    );
+
+    let svg = engine
+        .extract_document_content()
+        .gen_svg(true, true, false, 0.0)
+        .unwrap()
+        .expect("the document has content");
+    assert!(svg.svg_data.contains("<svg"));
+    assert!(svg.bounds.extents()[0] > 0.0);
+}
+
+#[test]
+fn export_document_as_html() {
+    let mut engine = Engine::default();
+    let _ = engine.import_generated_content(
+        strokes().into_iter().map(|stroke| (stroke, None)).collect(),
+        false,
+    );
+
+    let doc_export_prefs = DocExportPrefs {
+        export_format: DocExportFormat::Html,
+        ..Default::default()
+    };
+    let bytes = futures::executor::block_on(engine.export_doc(
+        String::from("Notes & <Sketches>"),
+        Some(doc_export_prefs),
+        None,
+        Progress::default(),
+    ))
+    .unwrap()
+    .unwrap();
+    let html = String::from_utf8(bytes).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Notes &amp; &lt;Sketches&gt;</title>"));
+    assert!(html.contains("<svg"));
+}
+
+#[test]
+fn figure_snippet_file_names() {
+    let size_mm = na::vector![20.0, 10.0];
+    assert_eq!(
+        FigureSnippetMarkup::Latex
+            .snippet("graph_1 & 2.pdf", size_mm)
+            .unwrap(),
+        "\\includegraphics[width=20.00mm,height=10.00mm]{\\detokenize{graph_1 & 2.pdf}}\n"
+    );
+    assert!(FigureSnippetMarkup::Latex
+        .snippet("100%.pdf", size_mm)
+        .is_err());
+    assert_eq!(
+        FigureSnippetMarkup::Typst
+            .snippet("say \"hi\".svg", size_mm)
+            .unwrap(),
+        "#image(\"say \\\"hi\\\".svg\", width: 20.00mm, height: 10.00mm)\n"
+    );
+}
+
+#[test]
+fn render_strokes_into_bitmap_image() {
+    let strokes = strokes();
+    let bitmapimage = BitmapImage::from_strokes(&strokes, 1.0).unwrap();
+    assert!(bitmapimage.image.pixel_width > 0);
+
+    // Images larger than cairo can render and invalid scales are rejected before rendering
+    assert!(BitmapImage::from_strokes(&strokes, 1000.0).is_err());
+    assert!(BitmapImage::from_strokes(&strokes, 0.0).is_err());
+    assert!(BitmapImage::from_strokes(&strokes, f64::NAN).is_err());
+}
+
+#[test]
+fn lazy_load_connected_strokes() {
+    let mut snapshot = EngineSnapshot::default();
+    let mut insert = |stroke: Stroke| {
+        let bounds = stroke.bounds();
+        let key = Arc::make_mut(&mut snapshot.stroke_components).insert(Arc::new(stroke));
+        Arc::make_mut(&mut snapshot.chrono_components)
+            .insert(key, Arc::new(ChronoComponent::default()));
+        Arc::make_mut(&mut snapshot.stroke_bounds).insert(key, bounds);
+        key
+    };
+    let rectangle = |y: f64| {
+        Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Rectangle(Rectangle::from_corners(
+                na::vector![0.0, y],
+                na::vector![80.0, y + 50.0],
+            )),
+            Style::default(),
+        ))
+    };
+    // Large enough to be loaded lazily, with a stroke far away from the view
+    let near_key = insert(rectangle(0.0));
+    for _ in 0..2000 {
+        insert(rectangle(0.0));
+    }
+    let far_key = insert(rectangle(100_000.0));
+    let start = na::vector![40.0, 50.0];
+    let end = na::vector![40.0, 100_000.0];
+    insert(Stroke::ConnectorStroke(ConnectorStroke::new(
+        ConnectorEnd::new(
+            start,
+            Some(ConnectorAttachment {
+                key: near_key,
+                anchor: 2,
+            }),
+        ),
+        ConnectorEnd::new(
+            end,
+            Some(ConnectorAttachment {
+                key: far_key,
+                anchor: 0,
+            }),
+        ),
+        ConnectorRouting::Straight,
+        true,
+        Color::BLACK,
+        ConnectorStroke::WIDTH_DEFAULT,
+    )));
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(snapshot);
+    let bytes = futures::executor::block_on(engine.save_as_rnote_bytes(String::from("lazy.rnote")))
+        .unwrap()
+        .unwrap();
+
+    let (snapshot, dropped) =
+        futures::executor::block_on(EngineSnapshot::load_from_rnote_bytes_lazy(bytes)).unwrap();
+    assert!(dropped.is_empty());
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(snapshot);
+    assert!(engine.store.has_pending_strokes());
+    let _ = engine.load_pending_strokes();
+    assert!(!engine.store.has_pending_strokes());
+
+    // The connector is attached to the far away stroke in its new slot
+    let snapshot = engine.take_snapshot();
+    let connector = snapshot
+        .stroke_components
+        .values()
+        .find_map(|stroke| match stroke.as_ref() {
+            Stroke::ConnectorStroke(connector) => Some(connector.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let attached = snapshot
+        .stroke_components
+        .get(connector.end.attachment.unwrap().key)
+        .unwrap();
+    assert!(matches!(attached.as_ref(), Stroke::ShapeStroke(_)));
+    assert_eq!(
+        attached.bounds().center().coords,
+        na::vector![40.0, 100_025.0]
+    );
+    assert!(snapshot
+        .stroke_components
+        .contains_key(connector.start.attachment.unwrap().key));
+}
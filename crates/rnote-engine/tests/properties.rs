@@ -0,0 +1,48 @@
+//! Property based tests for the unit conversions.
+
+// Imports
+use proptest::prelude::*;
+use rnote_engine::document::format::MeasureUnit;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * (1.0 + a.abs().max(b.abs()))
+}
+
+fn measure_unit() -> impl Strategy<Value = MeasureUnit> {
+    prop_oneof![
+        Just(MeasureUnit::Px),
+        Just(MeasureUnit::Mm),
+        Just(MeasureUnit::Cm)
+    ]
+}
+
+proptest! {
+    #[test]
+    fn convert_measurement_roundtrip(
+        value in -1e5..1e5f64,
+        unit in measure_unit(),
+        dpi in 1.0..1200.0f64,
+        desired_unit in measure_unit(),
+        desired_dpi in 1.0..1200.0f64,
+    ) {
+        let converted = MeasureUnit::convert_measurement(value, unit, dpi, desired_unit, desired_dpi);
+        let back = MeasureUnit::convert_measurement(converted, desired_unit, desired_dpi, unit, dpi);
+        prop_assert!(approx(back, value), "value: {value}, back: {back}");
+    }
+
+    #[test]
+    fn convert_measurement_same_unit_is_identity(
+        value in -1e5..1e5f64,
+        unit in measure_unit(),
+        dpi in 1.0..1200.0f64,
+    ) {
+        let converted = MeasureUnit::convert_measurement(value, unit, dpi, unit, dpi);
+        prop_assert!(approx(converted, value));
+    }
+
+    #[test]
+    fn convert_mm_to_cm(value in -1e5..1e5f64, dpi in 1.0..1200.0f64) {
+        let cm = MeasureUnit::convert_measurement(value, MeasureUnit::Mm, dpi, MeasureUnit::Cm, dpi);
+        prop_assert!(approx(cm * 10.0, value));
+    }
+}
@@ -2,6 +2,7 @@
 use crate::RnApp;
 use adw::prelude::*;
 use gtk4::{gio, glib, glib::clone};
+use tracing::error;
 
 impl RnApp {
     pub(crate) fn setup_actions(&self) {
@@ -17,6 +18,13 @@ impl RnApp {
 
         let action_new_window = gio::SimpleAction::new("new-window", None);
         self.add_action(&action_new_window);
+        let action_quick_note = gio::SimpleAction::new("quick-note", None);
+        self.add_action(&action_quick_note);
+        let action_switch_recent_tab = gio::SimpleAction::new("switch-recent-tab", None);
+        self.add_action(&action_switch_recent_tab);
+        let action_present_window =
+            gio::SimpleAction::new("present-window", Some(&u32::static_variant_type()));
+        self.add_action(&action_present_window);
 
         action_color_scheme
             .bind_property("state", &self.style_manager(), "color-scheme")
@@ -54,6 +62,14 @@ impl RnApp {
             }
         ));
 
+        action_switch_recent_tab.connect_activate(clone!(
+            #[weak(rename_to = app)]
+            self,
+            move |_, _| {
+                app.switch_to_recent_tab();
+            }
+        ));
+
         action_new_window.connect_activate(clone!(
             #[weak(rename_to = app)]
             self,
@@ -61,11 +77,38 @@ impl RnApp {
                 app.new_appwindow_init_show();
             }
         ));
+
+        action_quick_note.connect_activate(clone!(
+            #[weak(rename_to = app)]
+            self,
+            move |_, _| {
+                app.new_quick_note_window();
+            }
+        ));
+
+        // Present the window with the given id, used by the windows menu
+        action_present_window.connect_activate(clone!(
+            #[weak(rename_to = app)]
+            self,
+            move |_, target| {
+                let Some(id) = target.and_then(|t| t.get::<u32>()) else {
+                    error!("Activated present-window action with invalid target");
+                    return;
+                };
+                if let Some(window) = app.window_by_id(id) {
+                    window.present();
+                }
+            }
+        ));
     }
 
     // Accelerators / Keyboard Shortcuts
     pub(crate) fn setup_action_accels(&self) {
         self.set_accels_for_action("app.quit", &["<Ctrl>q"]);
         self.set_accels_for_action("app.new-window", &["<Ctrl>n"]);
+        self.set_accels_for_action(
+            "app.switch-recent-tab",
+            &["<Ctrl>Tab", "<Ctrl><Shift>ISO_Left_Tab"],
+        );
     }
 }
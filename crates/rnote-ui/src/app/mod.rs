@@ -9,12 +9,18 @@ use crate::{
     strokewidthpicker::RnStrokeWidthPreview, strokewidthpicker::RnStrokeWidthSetter,
     strokewidthpicker::StrokeWidthPreviewStyle, workspacebrowser::workspacesbar::RnWorkspaceRow,
     workspacebrowser::RnFileRow, workspacebrowser::RnWorkspacesBar, RnAppMenu, RnAppWindow,
-    RnCanvas, RnCanvasMenu, RnCanvasWrapper, RnColorPicker, RnIconPicker, RnMainHeader, RnOverlays,
-    RnPenPicker, RnPensSideBar, RnSettingsPanel, RnSidebar, RnStrokeContentPreview,
-    RnStrokeWidthPicker, RnUnitEntry, RnWorkspaceBrowser,
+    RnCanvas, RnCanvasMenu, RnCanvasWrapper, RnColorPicker, RnIconPicker, RnMainHeader, RnMinimap,
+    RnOverlays, RnPenPicker, RnPenPresetsBar, RnPensSideBar, RnSettingsPanel, RnSidebar,
+    RnStrokeContentPreview, RnStrokeWidthPicker, RnUnitEntry, RnWorkspaceBrowser,
 };
 use adw::subclass::prelude::AdwApplicationImpl;
+use gettextrs::gettext;
 use gtk4::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use std::cell::{Cell, RefCell};
+use tracing::error;
+
+/// The long name of the command line flag that opens a quick note window.
+const QUICK_NOTE_FLAG: &str = "quick-note";
 
 mod imp {
     use super::*;
@@ -22,19 +28,37 @@ mod imp {
     #[derive(Debug)]
     pub(crate) struct RnApp {
         pub(crate) app_settings: Option<gio::Settings>,
+        pub(crate) windows_menu: gio::Menu,
+        /// The tabs of all windows, the most recently used first.
+        pub(crate) recent_tabs: RefCell<Vec<glib::WeakRef<adw::TabPage>>>,
+        /// Whether a quick note window should be opened instead of a regular window on activation.
+        pub(crate) quick_note_requested: Cell<bool>,
     }
 
     impl Default for RnApp {
         fn default() -> Self {
+            // In portable mode the settings are stored in a keyfile in the portable directory
+            let settings_backend = crate::env::portable_settings_file().and_then(|file| {
+                Some(gio::keyfile_settings_backend_new(
+                    file.to_str()?,
+                    config::APP_IDPATH,
+                    None,
+                ))
+            });
             let app_settings = gio::SettingsSchemaSource::default().and_then(|schema_source| {
                 Some(gio::Settings::new_full(
                     &schema_source.lookup(config::APP_ID, true)?,
-                    None::<&gio::SettingsBackend>,
+                    settings_backend.as_ref(),
                     None,
                 ))
             });
 
-            Self { app_settings }
+            Self {
+                app_settings,
+                windows_menu: gio::Menu::new(),
+                recent_tabs: RefCell::new(Vec::new()),
+                quick_note_requested: Cell::new(false),
+            }
         }
     }
 
@@ -55,13 +79,51 @@ mod imp {
             self.setup_buildables();
             obj.setup_actions();
             obj.setup_action_accels();
+            obj.setup_windows_menu();
         }
 
         fn activate(&self) {
             self.parent_activate();
 
+            if self.quick_note_requested.take() {
+                self.obj().new_quick_note_window();
+                return;
+            }
+
             // init and show a new window
             self.new_appwindow_init_show(None);
+
+            if self
+                .obj()
+                .app_settings()
+                .is_some_and(|app_settings| app_settings.boolean("daily-notes-open-on-startup"))
+            {
+                if let Some(appwindow) = self.obj().active_window().and_downcast::<RnAppWindow>() {
+                    glib::spawn_future_local(async move {
+                        appwindow.open_daily_note().await;
+                    });
+                }
+            }
+        }
+
+        fn handle_local_options(
+            &self,
+            options: &glib::VariantDict,
+        ) -> std::ops::ControlFlow<glib::ExitCode> {
+            if options.contains(QUICK_NOTE_FLAG) {
+                let obj = self.obj();
+                if let Err(e) = obj.register(None::<&gio::Cancellable>) {
+                    error!("Registering the app failed, Err: {e:?}");
+                    return std::ops::ControlFlow::Break(glib::ExitCode::FAILURE);
+                }
+                if obj.is_remote() {
+                    // An instance is already running, it opens the quick note window
+                    obj.activate_action("quick-note", None);
+                    return std::ops::ControlFlow::Break(glib::ExitCode::SUCCESS);
+                }
+                self.quick_note_requested.set(true);
+            }
+            self.parent_handle_local_options(options)
         }
 
         fn open(&self, files: &[gio::File], hint: &str) {
@@ -127,6 +189,8 @@ mod imp {
             RnStrokeContentPreview::static_type();
             RnSidebar::static_type();
             RnPenPicker::static_type();
+            RnPenPresetsBar::static_type();
+            RnMinimap::static_type();
         }
 
         /// Initializes and shows a new app window
@@ -163,12 +227,35 @@ impl Default for RnApp {
 
 impl RnApp {
     pub(crate) fn new() -> Self {
-        glib::Object::builder()
+        let mut flags = gio::ApplicationFlags::HANDLES_OPEN;
+        // A portable instance must not hand its files over to an already running instance with other settings
+        if crate::env::portable_dir().is_some() {
+            flags |= gio::ApplicationFlags::NON_UNIQUE;
+        }
+        let app: Self = glib::Object::builder()
             .property("application-id", config::APP_ID)
             .property("resource-base-path", config::APP_IDPATH)
-            .property("flags", gio::ApplicationFlags::HANDLES_OPEN)
+            .property("flags", flags)
             .property("register-session", true)
-            .build()
+            .build();
+        // Portable mode is already set up before the app is created, the option only needs to be accepted here.
+        app.add_main_option(
+            crate::env::PORTABLE_FLAG,
+            glib::Char(0),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::None,
+            &gettext("Store the settings and user data in a directory next to the executable"),
+            None,
+        );
+        app.add_main_option(
+            QUICK_NOTE_FLAG,
+            glib::Char(0),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::None,
+            &gettext("Open a small window for quickly taking a note"),
+            None,
+        );
+        app
     }
 
     /// Returns the app settings, if the schema is found in the compiled gschema. If not, returns None.
@@ -185,4 +272,89 @@ impl RnApp {
     pub(crate) fn new_appwindow_init_show(&self) {
         self.imp().new_appwindow_init_show(None);
     }
+
+    /// Initializes and shows a new quick note window.
+    pub(crate) fn new_quick_note_window(&self) {
+        let appwindow = RnAppWindow::new(self.upcast_ref::<gtk4::Application>());
+        appwindow.init();
+        appwindow.setup_quick_note();
+        appwindow.present();
+    }
+
+    /// The menu listing the open windows, shared by the app menus of all windows.
+    pub(crate) fn windows_menu(&self) -> gio::Menu {
+        self.imp().windows_menu.clone()
+    }
+
+    /// Moves the tab to the front of the most recently used tabs.
+    pub(crate) fn record_recent_tab(&self, tab_page: &adw::TabPage) {
+        let mut recent_tabs = self.imp().recent_tabs.borrow_mut();
+        recent_tabs.retain(|t| t.upgrade().is_some_and(|t| t != *tab_page));
+        recent_tabs.insert(0, tab_page.downgrade());
+    }
+
+    /// Switches to the tab that was used before the current one, which might be in another window.
+    pub(crate) fn switch_to_recent_tab(&self) {
+        let recent_tab = {
+            let mut recent_tabs = self.imp().recent_tabs.borrow_mut();
+            // Closed tabs or tabs of closed windows are dropped
+            recent_tabs.retain(|t| {
+                t.upgrade()
+                    .is_some_and(|t| t.child().root().and_downcast::<RnAppWindow>().is_some())
+            });
+            recent_tabs.get(1).and_then(|t| t.upgrade())
+        };
+        let Some(recent_tab) = recent_tab else {
+            return;
+        };
+        let Some(appwindow) = recent_tab.child().root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        appwindow
+            .overlays()
+            .tabview()
+            .set_selected_page(&recent_tab);
+        appwindow.present();
+        // Also record it here, because the selected page might not have changed when only the window was switched
+        self.record_recent_tab(&recent_tab);
+    }
+
+    /// Keeps the windows menu updated when windows are opened, closed or their title changes.
+    fn setup_windows_menu(&self) {
+        self.connect_window_added(|app, window| {
+            window.connect_title_notify(clone!(
+                #[weak]
+                app,
+                move |_| {
+                    app.refresh_windows_menu();
+                }
+            ));
+            app.refresh_windows_menu();
+        });
+        self.connect_window_removed(|app, _| {
+            app.refresh_windows_menu();
+        });
+    }
+
+    fn refresh_windows_menu(&self) {
+        let windows_menu = self.windows_menu();
+        windows_menu.remove_all();
+        for appwindow in self
+            .windows()
+            .into_iter()
+            .filter_map(|window| window.downcast::<RnAppWindow>().ok())
+        {
+            // Underscores would otherwise be interpreted as mnemonics
+            let label = appwindow
+                .title()
+                .map(|title| title.replace('_', "__"))
+                .unwrap_or_else(|| config::APP_NAME_CAPITALIZED.to_string());
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(
+                Some("app.present-window"),
+                Some(&appwindow.id().to_variant()),
+            );
+            windows_menu.append_item(&item);
+        }
+    }
 }
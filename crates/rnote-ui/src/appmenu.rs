@@ -1,5 +1,6 @@
 // Imports
 use crate::appwindow::RnAppWindow;
+use crate::share::share_targets;
 use adw::{prelude::*, subclass::prelude::*};
 use gtk4::{gio, glib, CompositeTemplate, MenuButton, PopoverMenu, ToggleButton, Widget};
 
@@ -16,6 +17,10 @@ mod imp {
         #[template_child]
         pub(crate) menu_model: TemplateChild<gio::MenuModel>,
         #[template_child]
+        pub(crate) windows_submenu: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) share_submenu: TemplateChild<gio::Menu>,
+        #[template_child]
         pub(crate) lefthanded_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) righthanded_toggle: TemplateChild<ToggleButton>,
@@ -95,5 +100,15 @@ impl RnAppMenu {
             .sync_create()
             .bidirectional()
             .build();
+        self.imp()
+            .windows_submenu
+            .append_section(None, &appwindow.app().windows_menu());
+
+        for target in share_targets() {
+            self.imp().share_submenu.append(
+                Some(&target.name()),
+                Some(&format!("win.share::{}", target.id())),
+            );
+        }
     }
 }
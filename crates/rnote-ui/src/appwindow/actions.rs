@@ -1,4 +1,5 @@
 // Imports
+use crate::colorpicker::SetterPalette;
 use crate::{config, dialogs, RnAppWindow, RnCanvas};
 use gettextrs::gettext;
 use gtk4::graphene;
@@ -9,9 +10,14 @@ use gtk4::{
 use p2d::bounding_volume::BoundingVolume;
 use rnote_compose::penevent::ShortcutKey;
 use rnote_compose::SplitOrder;
-use rnote_engine::engine::StrokeContent;
+use rnote_engine::engine::export::PageHeaderFooter;
+use rnote_engine::engine::{ColorVisionFilter, StrokeContent};
+use rnote_engine::pens::pensconfig::PenPresets;
 use rnote_engine::pens::PenStyle;
 use rnote_engine::strokes::resize::{ImageSizeOption, Resize};
+use rnote_engine::strokes::tablestroke::TableEdit;
+use rnote_engine::strokes::textstroke::{FontStyle, TextAttribute};
+use rnote_engine::strokes::BlendMode;
 use rnote_engine::{Camera, Engine};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -33,6 +39,9 @@ impl RnAppWindow {
     pub(crate) fn setup_actions(&self) {
         let action_fullscreen = gio::PropertyAction::new("fullscreen", self, "fullscreened");
         self.add_action(&action_fullscreen);
+        let action_reveal_headerbar =
+            gio::PropertyAction::new("reveal-headerbar", &self.toolbar_view(), "reveal-top-bars");
+        self.add_action(&action_reveal_headerbar);
         let action_open_settings = gio::SimpleAction::new("open-settings", None);
         self.add_action(&action_open_settings);
         let action_about = gio::SimpleAction::new("about", None);
@@ -55,12 +64,19 @@ impl RnAppWindow {
         let action_visual_debug =
             gio::SimpleAction::new_stateful("visual-debug", None, &false.to_variant());
         self.add_action(&action_visual_debug);
+        let action_debug_hud =
+            gio::SimpleAction::new_stateful("debug-hud", None, &false.to_variant());
+        self.add_action(&action_debug_hud);
         let action_debug_export_engine_state =
             gio::SimpleAction::new("debug-export-engine-state", None);
         self.add_action(&action_debug_export_engine_state);
         let action_debug_export_engine_config =
             gio::SimpleAction::new("debug-export-engine-config", None);
         self.add_action(&action_debug_export_engine_config);
+        let action_export_settings = gio::SimpleAction::new("export-settings", None);
+        self.add_action(&action_export_settings);
+        let action_import_settings = gio::SimpleAction::new("import-settings", None);
+        self.add_action(&action_import_settings);
         let action_righthanded = gio::PropertyAction::new("righthanded", self, "righthanded");
         self.add_action(&action_righthanded);
         let action_touch_drawing = gio::PropertyAction::new("touch-drawing", self, "touch-drawing");
@@ -80,6 +96,15 @@ impl RnAppWindow {
         let action_show_origin_indicator =
             gio::SimpleAction::new_stateful("show-origin-indicator", None, &true.to_variant());
         self.add_action(&action_show_origin_indicator);
+        let action_color_vision_filter = gio::SimpleAction::new_stateful(
+            "color-vision-filter",
+            Some(&String::static_variant_type()),
+            &String::from("none").to_variant(),
+        );
+        self.add_action(&action_color_vision_filter);
+        let action_colorpicker_palette =
+            gio::SimpleAction::new("colorpicker-palette", Some(&String::static_variant_type()));
+        self.add_action(&action_colorpicker_palette);
         let action_block_pinch_zoom =
             gio::PropertyAction::new("block-pinch-zoom", self, "block-pinch-zoom");
         self.add_action(&action_block_pinch_zoom);
@@ -92,6 +117,14 @@ impl RnAppWindow {
             &String::from("brush").to_variant(),
         );
         self.add_action(&action_pen_style);
+        let action_pen_preset_save = gio::SimpleAction::new("pen-preset-save", None);
+        self.add_action(&action_pen_preset_save);
+        let action_pen_preset_apply =
+            gio::SimpleAction::new("pen-preset-apply", Some(&i32::static_variant_type()));
+        self.add_action(&action_pen_preset_apply);
+        let action_pen_preset_remove =
+            gio::SimpleAction::new("pen-preset-remove", Some(&i32::static_variant_type()));
+        self.add_action(&action_pen_preset_remove);
         let action_undo_stroke = gio::SimpleAction::new("undo", None);
         self.add_action(&action_undo_stroke);
         let action_redo_stroke = gio::SimpleAction::new("redo", None);
@@ -104,6 +137,12 @@ impl RnAppWindow {
         self.add_action(&action_zoomin);
         let action_zoomout = gio::SimpleAction::new("zoom-out", None);
         self.add_action(&action_zoomout);
+        let action_rotate_view_left = gio::SimpleAction::new("rotate-view-left", None);
+        self.add_action(&action_rotate_view_left);
+        let action_rotate_view_right = gio::SimpleAction::new("rotate-view-right", None);
+        self.add_action(&action_rotate_view_right);
+        let action_rotate_view_reset = gio::SimpleAction::new("rotate-view-reset", None);
+        self.add_action(&action_rotate_view_reset);
         let action_add_page_to_doc = gio::SimpleAction::new("add-page-to-doc", None);
         self.add_action(&action_add_page_to_doc);
         let action_remove_page_from_doc = gio::SimpleAction::new("remove-page-from-doc", None);
@@ -112,38 +151,141 @@ impl RnAppWindow {
         self.add_action(&action_resize_to_fit_content);
         let action_return_origin_page = gio::SimpleAction::new("return-origin-page", None);
         self.add_action(&action_return_origin_page);
+        let action_pages_overview = gio::SimpleAction::new("pages-overview", None);
+        self.add_action(&action_pages_overview);
+        let action_bookmark_add = gio::SimpleAction::new("bookmark-add", None);
+        self.add_action(&action_bookmark_add);
+        let action_bookmark_goto =
+            gio::SimpleAction::new("bookmark-goto", Some(&u32::static_variant_type()));
+        self.add_action(&action_bookmark_goto);
+        let action_bookmark_remove =
+            gio::SimpleAction::new("bookmark-remove", Some(&u32::static_variant_type()));
+        self.add_action(&action_bookmark_remove);
+        let action_goto_last_edit = gio::SimpleAction::new("goto-last-edit", None);
+        self.add_action(&action_goto_last_edit);
         let action_selection_trash = gio::SimpleAction::new("selection-trash", None);
         self.add_action(&action_selection_trash);
         let action_selection_duplicate = gio::SimpleAction::new("selection-duplicate", None);
         self.add_action(&action_selection_duplicate);
         let action_selection_invert_color = gio::SimpleAction::new("selection-invert-color", None);
         self.add_action(&action_selection_invert_color);
+        let action_selection_lock = gio::SimpleAction::new("selection-lock", None);
+        self.add_action(&action_selection_lock);
+        let action_selection_bring_to_front =
+            gio::SimpleAction::new("selection-bring-to-front", None);
+        self.add_action(&action_selection_bring_to_front);
+        let action_selection_send_to_back = gio::SimpleAction::new("selection-send-to-back", None);
+        self.add_action(&action_selection_send_to_back);
+        let action_unlock_all_strokes = gio::SimpleAction::new("unlock-all-strokes", None);
+        self.add_action(&action_unlock_all_strokes);
+        let action_selection_relink_image = gio::SimpleAction::new("selection-relink-image", None);
+        self.add_action(&action_selection_relink_image);
+        let action_selection_vectorize = gio::SimpleAction::new("selection-vectorize", None);
+        self.add_action(&action_selection_vectorize);
+        let action_selection_convert_to_outlines =
+            gio::SimpleAction::new("selection-convert-to-outlines", None);
+        self.add_action(&action_selection_convert_to_outlines);
+        let action_selection_add_frame = gio::SimpleAction::new("selection-add-frame", None);
+        self.add_action(&action_selection_add_frame);
+        let action_selection_flatten = gio::SimpleAction::new("selection-flatten", None);
+        self.add_action(&action_selection_flatten);
+        let action_selection_opacity =
+            gio::SimpleAction::new("selection-opacity", Some(&f64::static_variant_type()));
+        self.add_action(&action_selection_opacity);
+        let action_selection_blend_mode =
+            gio::SimpleAction::new("selection-blend-mode", Some(&String::static_variant_type()));
+        self.add_action(&action_selection_blend_mode);
+        let action_text_toggle_bold = gio::SimpleAction::new("text-toggle-bold", None);
+        self.add_action(&action_text_toggle_bold);
+        let action_text_toggle_italic = gio::SimpleAction::new("text-toggle-italic", None);
+        self.add_action(&action_text_toggle_italic);
+        let action_text_toggle_underline = gio::SimpleAction::new("text-toggle-underline", None);
+        self.add_action(&action_text_toggle_underline);
+        let action_text_toggle_strikethrough =
+            gio::SimpleAction::new("text-toggle-strikethrough", None);
+        self.add_action(&action_text_toggle_strikethrough);
+        let action_text_toggle_bullet_list =
+            gio::SimpleAction::new("text-toggle-bullet-list", None);
+        self.add_action(&action_text_toggle_bullet_list);
+        let action_text_reset_attributes = gio::SimpleAction::new("text-reset-attributes", None);
+        self.add_action(&action_text_reset_attributes);
+        let action_selection_copy_style = gio::SimpleAction::new("selection-copy-style", None);
+        self.add_action(&action_selection_copy_style);
+        let action_selection_paste_style = gio::SimpleAction::new("selection-paste-style", None);
+        self.add_action(&action_selection_paste_style);
+        let action_copied_style_to_pen = gio::SimpleAction::new("copied-style-to-pen", None);
+        self.add_action(&action_copied_style_to_pen);
+        let action_selection_add_link = gio::SimpleAction::new("selection-add-link", None);
+        self.add_action(&action_selection_add_link);
+        let action_selection_remove_links = gio::SimpleAction::new("selection-remove-links", None);
+        self.add_action(&action_selection_remove_links);
         let action_selection_select_all = gio::SimpleAction::new("selection-select-all", None);
         self.add_action(&action_selection_select_all);
         let action_selection_deselect_all = gio::SimpleAction::new("selection-deselect-all", None);
         self.add_action(&action_selection_deselect_all);
         let action_clear_doc = gio::SimpleAction::new("clear-doc", None);
         self.add_action(&action_clear_doc);
+        let action_clear_guides = gio::SimpleAction::new("clear-guides", None);
+        self.add_action(&action_clear_guides);
+        let action_apply_redactions = gio::SimpleAction::new("apply-redactions", None);
+        self.add_action(&action_apply_redactions);
         let action_new_doc = gio::SimpleAction::new("new-doc", None);
         self.add_action(&action_new_doc);
         let action_save_doc = gio::SimpleAction::new("save-doc", None);
         self.add_action(&action_save_doc);
         let action_save_doc_as = gio::SimpleAction::new("save-doc-as", None);
         self.add_action(&action_save_doc_as);
+        let action_save_all = gio::SimpleAction::new("save-all", None);
+        self.add_action(&action_save_all);
+        let action_save_doc_copy = gio::SimpleAction::new("save-doc-copy", None);
+        self.add_action(&action_save_doc_copy);
+        let action_duplicate_doc = gio::SimpleAction::new("duplicate-doc", None);
+        self.add_action(&action_duplicate_doc);
+        let action_doc_password = gio::SimpleAction::new("doc-password", None);
+        self.add_action(&action_doc_password);
         let action_autosave = gio::PropertyAction::new("autosave", self, "autosave");
         self.add_action(&action_autosave);
         let action_open_doc = gio::SimpleAction::new("open-doc", None);
         self.add_action(&action_open_doc);
+        let action_daily_note = gio::SimpleAction::new("daily-note", None);
+        self.add_action(&action_daily_note);
+        let action_quick_note_append = gio::SimpleAction::new("quick-note-append", None);
+        self.add_action(&action_quick_note_append);
         let action_print_doc = gio::SimpleAction::new("print-doc", None);
         self.add_action(&action_print_doc);
         let action_import_file = gio::SimpleAction::new("import-file", None);
         self.add_action(&action_import_file);
+        let action_import_pages_from_file = gio::SimpleAction::new("import-pages-from-file", None);
+        self.add_action(&action_import_pages_from_file);
+        let action_import_photo = gio::SimpleAction::new("import-photo", None);
+        self.add_action(&action_import_photo);
+        let action_collab_host = gio::SimpleAction::new("collab-host", None);
+        self.add_action(&action_collab_host);
+        let action_collab_join = gio::SimpleAction::new("collab-join", None);
+        self.add_action(&action_collab_join);
+        let action_collab_leave = gio::SimpleAction::new("collab-leave", None);
+        self.add_action(&action_collab_leave);
+        let action_share = gio::SimpleAction::new("share", Some(&String::static_variant_type()));
+        self.add_action(&action_share);
+        let action_broadcast_start = gio::SimpleAction::new("broadcast-start", None);
+        self.add_action(&action_broadcast_start);
+        let action_broadcast_stop = gio::SimpleAction::new("broadcast-stop", None);
+        self.add_action(&action_broadcast_stop);
         let action_export_doc = gio::SimpleAction::new("export-doc", None);
         self.add_action(&action_export_doc);
         let action_export_doc_pages = gio::SimpleAction::new("export-doc-pages", None);
         self.add_action(&action_export_doc_pages);
+        let action_extract_pages = gio::SimpleAction::new("extract-pages", None);
+        self.add_action(&action_extract_pages);
         let action_export_selection = gio::SimpleAction::new("export-selection", None);
         self.add_action(&action_export_selection);
+        let action_export_selection_figure =
+            gio::SimpleAction::new("export-selection-figure", None);
+        self.add_action(&action_export_selection_figure);
+        let action_export_frames = gio::SimpleAction::new("export-frames", None);
+        self.add_action(&action_export_frames);
+        let action_batch_export = gio::SimpleAction::new("batch-export", None);
+        self.add_action(&action_batch_export);
         let action_clipboard_copy = gio::SimpleAction::new("clipboard-copy", None);
         self.add_action(&action_clipboard_copy);
         let action_clipboard_cut = gio::SimpleAction::new("clipboard-cut", None);
@@ -153,6 +295,51 @@ impl RnAppWindow {
         let action_clipboard_paste_contextmenu =
             gio::SimpleAction::new("clipboard-paste-contextmenu", None);
         self.add_action(&action_clipboard_paste_contextmenu);
+        let action_clipboard_paste_in_place =
+            gio::SimpleAction::new("clipboard-paste-in-place", None);
+        self.add_action(&action_clipboard_paste_in_place);
+        let action_insert_image_contextmenu =
+            gio::SimpleAction::new("insert-image-contextmenu", None);
+        self.add_action(&action_insert_image_contextmenu);
+        let action_insert_text_contextmenu =
+            gio::SimpleAction::new("insert-text-contextmenu", None);
+        self.add_action(&action_insert_text_contextmenu);
+        let action_insert_datetime = gio::SimpleAction::new("insert-datetime", None);
+        self.add_action(&action_insert_datetime);
+        let action_insert_datetime_contextmenu =
+            gio::SimpleAction::new("insert-datetime-contextmenu", None);
+        self.add_action(&action_insert_datetime_contextmenu);
+        let action_page_background_contextmenu =
+            gio::SimpleAction::new("page-background-contextmenu", None);
+        self.add_action(&action_page_background_contextmenu);
+        let action_spellcheck_correct =
+            gio::SimpleAction::new("spellcheck-correct", Some(&String::static_variant_type()));
+        self.add_action(&action_spellcheck_correct);
+        let action_insert_table = gio::SimpleAction::new("insert-table", None);
+        self.add_action(&action_insert_table);
+        let action_insert_todo_list = gio::SimpleAction::new("insert-todo-list", None);
+        self.add_action(&action_insert_todo_list);
+        let action_table_edit_cell = gio::SimpleAction::new("table-edit-cell", None);
+        self.add_action(&action_table_edit_cell);
+        let action_table_edit =
+            gio::SimpleAction::new("table-edit", Some(&String::static_variant_type()));
+        self.add_action(&action_table_edit);
+        let action_insert_sticky_note = gio::SimpleAction::new("insert-sticky-note", None);
+        self.add_action(&action_insert_sticky_note);
+        let action_sticky_note_edit = gio::SimpleAction::new("sticky-note-edit", None);
+        self.add_action(&action_sticky_note_edit);
+        let action_insert_mind_map = gio::SimpleAction::new("insert-mind-map", None);
+        self.add_action(&action_insert_mind_map);
+        let action_mind_map_edit = gio::SimpleAction::new("mind-map-edit", None);
+        self.add_action(&action_mind_map_edit);
+        let action_mind_map_toggle_node = gio::SimpleAction::new("mind-map-toggle-node", None);
+        self.add_action(&action_mind_map_toggle_node);
+        let action_insert_board = gio::SimpleAction::new("insert-board", None);
+        self.add_action(&action_insert_board);
+        let action_board_edit = gio::SimpleAction::new("board-edit", None);
+        self.add_action(&action_board_edit);
+        let action_frame_rename = gio::SimpleAction::new("frame-rename", None);
+        self.add_action(&action_frame_rename);
         let action_active_tab_move_left = gio::SimpleAction::new("active-tab-move-left", None);
         self.add_action(&action_active_tab_move_left);
         let action_active_tab_move_right = gio::SimpleAction::new("active-tab-move-right", None);
@@ -253,6 +440,8 @@ impl RnAppWindow {
             action_devel_menu,
             #[weak]
             action_visual_debug,
+            #[weak]
+            action_debug_hud,
             move |action, _| {
                 let state = action.state().unwrap().get::<bool>().unwrap();
 
@@ -263,6 +452,7 @@ impl RnAppWindow {
                 if state {
                     debug!("Disabling developer mode, disabling visual debugging.");
                     action_visual_debug.change_state(&false.to_variant());
+                    action_debug_hud.change_state(&false.to_variant());
                 }
                 action.change_state(&(!state).to_variant());
             }
@@ -286,6 +476,19 @@ impl RnAppWindow {
             }
         ));
 
+        // Debug HUD
+        action_debug_hud.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let debug_hud = state_request.unwrap().get::<bool>().unwrap();
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().set_debug_hud(debug_hud);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                action.set_state(&debug_hud.to_variant());
+            }
+        ));
+
         // Create page
         action_new_tab.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -334,6 +537,36 @@ impl RnAppWindow {
             }
         ));
 
+        // Export settings
+        action_export_settings.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::filechooser_export_settings(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Import settings
+        action_import_settings.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_settings(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
         // Pen sounds
         action_pen_sounds.connect_change_state(clone!(
             #[weak(rename_to=appwindow)]
@@ -391,6 +624,46 @@ impl RnAppWindow {
             }
         ));
 
+        // Color vision filter
+        action_color_vision_filter.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let Some(color_vision_filter) = state_request
+                    .and_then(|s| s.get::<String>())
+                    .and_then(|s| ColorVisionFilter::from_str(&s).ok())
+                else {
+                    error!("Requested invalid state for color-vision-filter action");
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas
+                    .engine_mut()
+                    .set_color_vision_filter(color_vision_filter);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                action.set_state(&color_vision_filter.to_string().to_variant());
+            }
+        ));
+
+        // Colorpicker palette
+        action_colorpicker_palette.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(palette) = target
+                    .and_then(|t| t.get::<String>())
+                    .and_then(|s| SetterPalette::from_str(&s).ok())
+                else {
+                    error!("Activated colorpicker-palette action with invalid target");
+                    return;
+                };
+                appwindow
+                    .overlays()
+                    .colorpicker()
+                    .apply_setter_palette(palette);
+            }
+        ));
+
         // Pen style
         action_pen_style.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -418,6 +691,47 @@ impl RnAppWindow {
             }
         ));
 
+        // Save the current pen as preset
+        action_pen_preset_save.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().save_current_pen_as_preset();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Apply a pen preset
+        action_pen_preset_apply.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(i) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated pen-preset-apply action with invalid target");
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().apply_pen_preset(i.max(0) as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Remove a pen preset
+        action_pen_preset_remove.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(i) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated pen-preset-remove action with invalid target");
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().remove_pen_preset(i.max(0) as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
         // Tab actions
         action_active_tab_move_left.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -462,9 +776,11 @@ impl RnAppWindow {
             move |_, _| {
                 debug!("Pressed drawing pad button 0");
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let (_, widget_flags) = canvas
-                    .engine_mut()
-                    .handle_pressed_shortcut_key(ShortcutKey::DrawingPadButton0, Instant::now());
+                let (_, widget_flags) = canvas.engine_mut().handle_pressed_shortcut_key(
+                    ShortcutKey::DrawingPadButton0,
+                    None,
+                    Instant::now(),
+                );
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
@@ -475,9 +791,11 @@ impl RnAppWindow {
             move |_, _| {
                 debug!("Pressed drawing pad button 1");
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let (_, widget_flags) = canvas
-                    .engine_mut()
-                    .handle_pressed_shortcut_key(ShortcutKey::DrawingPadButton1, Instant::now());
+                let (_, widget_flags) = canvas.engine_mut().handle_pressed_shortcut_key(
+                    ShortcutKey::DrawingPadButton1,
+                    None,
+                    Instant::now(),
+                );
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
@@ -488,9 +806,11 @@ impl RnAppWindow {
             move |_, _| {
                 debug!("Pressed drawing pad button 2");
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let (_, widget_flags) = canvas
-                    .engine_mut()
-                    .handle_pressed_shortcut_key(ShortcutKey::DrawingPadButton2, Instant::now());
+                let (_, widget_flags) = canvas.engine_mut().handle_pressed_shortcut_key(
+                    ShortcutKey::DrawingPadButton2,
+                    None,
+                    Instant::now(),
+                );
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
@@ -501,9 +821,11 @@ impl RnAppWindow {
             move |_, _| {
                 debug!("Pressed drawing pad button 3");
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let (_, widget_flags) = canvas
-                    .engine_mut()
-                    .handle_pressed_shortcut_key(ShortcutKey::DrawingPadButton3, Instant::now());
+                let (_, widget_flags) = canvas.engine_mut().handle_pressed_shortcut_key(
+                    ShortcutKey::DrawingPadButton3,
+                    None,
+                    Instant::now(),
+                );
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
@@ -541,30 +863,59 @@ impl RnAppWindow {
             }
         ));
 
-        // select all strokes
-        action_selection_select_all.connect_activate(clone!(
+        // lock the selection against accidental edits
+        action_selection_lock.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().select_all_strokes();
+                let widget_flags = canvas.engine_mut().lock_selection();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // deselect all strokes
-        action_selection_deselect_all.connect_activate(clone!(
+        // move the selection in front of the other strokes
+        action_selection_bring_to_front.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().deselect_all_strokes();
+                let widget_flags = canvas.engine_mut().selection_bring_to_front();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Clear doc
-        action_clear_doc.connect_activate(clone!(
+        // move the selection behind the other strokes
+        action_selection_send_to_back.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().selection_send_to_back();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // unlock all locked strokes
+        action_unlock_all_strokes.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                if !canvas.engine_ref().any_strokes_locked() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("No locked strokes"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                let widget_flags = canvas.engine_mut().unlock_all_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // link the selected linked images to a new file
+        action_selection_relink_image.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -572,160 +923,212 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::dialog_clear_doc(
-                            &appwindow,
-                            &appwindow.active_tab_wrapper().canvas(),
-                        )
-                        .await;
+                        let canvas = appwindow.active_tab_wrapper().canvas();
+                        dialogs::import::filedialog_relink_image(&appwindow, &canvas).await;
                     }
                 ));
             }
         ));
 
-        // Undo stroke
-        action_undo_stroke.connect_activate(clone!(
+        // trace the selected images into shapes
+        action_selection_vectorize.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().undo(Instant::now());
+                let Some(widget_flags) = canvas.engine_mut().vectorize_selected_images() else {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("No images selected that could be vectorized"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                };
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Redo stroke
-        action_redo_stroke.connect_activate(clone!(
+        // convert the selected brush strokes into their outlines
+        action_selection_convert_to_outlines.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().redo(Instant::now());
+                let Some(widget_flags) = canvas
+                    .engine_mut()
+                    .convert_selected_brushstrokes_to_outlines()
+                else {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("No brush strokes selected"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                };
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Zoom reset
-        action_zoom_reset.connect_activate(clone!(
+        // add a named frame around the selection
+        action_selection_add_frame.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                let canvas = appwindow.active_tab_wrapper().canvas();
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom = Camera::ZOOM_DEFAULT;
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_add_frame(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
             }
         ));
 
-        // Zoom fit to width
-        action_zoom_fit_width.connect_activate(clone!(
+        // render the selection into a single image
+        action_selection_flatten.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                let canvaswrapper = appwindow.active_tab_wrapper();
-                let canvas = canvaswrapper.canvas();
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom = f64::from(canvaswrapper.scroller().width())
-                    / (canvaswrapper.canvas().engine_ref().document.format.width()
-                        + 2.0 * Camera::OVERSHOOT_HORIZONTAL);
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                if canvas.engine_ref().nothing_selected() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Nothing selected"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_flatten_selection(&appwindow, &canvas).await;
+                    }
+                ));
             }
         ));
 
-        // Zoom in
-        action_zoomin.connect_activate(clone!(
+        // change the opacity of the selection
+        action_selection_opacity.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
-            move |_, _| {
+            move |_, target| {
+                let Some(opacity) = target.and_then(|t| t.get::<f64>()) else {
+                    error!("Activated selection-opacity action with invalid target");
+                    return;
+                };
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom =
-                    canvas.engine_ref().camera.total_zoom() * (1.0 + RnCanvas::ZOOM_SCROLL_STEP);
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let widget_flags = canvas.engine_mut().change_selection_opacity(opacity);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Zoom out
-        action_zoomout.connect_activate(clone!(
+        // change the blend mode of the selection
+        action_selection_blend_mode.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
-            move |_, _| {
+            move |_, target| {
+                let Some(blend_mode) = target
+                    .and_then(|t| t.get::<String>())
+                    .and_then(|s| BlendMode::from_str(&s).ok())
+                else {
+                    error!("Activated selection-blend-mode action with invalid target");
+                    return;
+                };
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom =
-                    canvas.engine_ref().camera.total_zoom() * (1.0 - RnCanvas::ZOOM_SCROLL_STEP);
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let widget_flags = canvas.engine_mut().change_selection_blend_mode(blend_mode);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Add page to doc in fixed size mode
-        action_add_page_to_doc.connect_activate(clone!(
+        // Text formatting
+        for (action, text_attribute) in [
+            (
+                &action_text_toggle_bold,
+                TextAttribute::FontWeight(piet::FontWeight::BOLD.to_raw()),
+            ),
+            (
+                &action_text_toggle_italic,
+                TextAttribute::Style(FontStyle::Italic),
+            ),
+            (
+                &action_text_toggle_underline,
+                TextAttribute::Underline(true),
+            ),
+            (
+                &action_text_toggle_strikethrough,
+                TextAttribute::Strikethrough(true),
+            ),
+        ] {
+            action.connect_activate(clone!(
+                #[weak(rename_to=appwindow)]
+                self,
+                move |_, _| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas
+                        .engine_mut()
+                        .text_selection_toggle_attribute(text_attribute.clone());
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+        }
+
+        action_text_toggle_bullet_list.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
-            move |_action_add_page_to_doc, _| {
+            move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().doc_add_page_fixed_size();
+                let widget_flags = canvas.engine_mut().text_toggle_bullet_list();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Remove page from doc in fixed size mode
-        action_remove_page_from_doc.connect_activate(clone!(
+        action_text_reset_attributes.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().doc_remove_page_fixed_size();
+                let widget_flags = canvas.engine_mut().text_selection_remove_attributes();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Resize to fit content
-        action_resize_to_fit_content.connect_activate(clone!(
+        // copy the style of the selected stroke
+        action_selection_copy_style.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
-                let widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
+                let widget_flags = canvas.engine_mut().copy_selection_style();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Return to the origin page
-        action_return_origin_page.connect_activate(clone!(
+        // paste the copied style onto the selected strokes
+        action_selection_paste_style.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().paste_style_to_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
 
-                let widget_flags = canvas
-                    .engine_mut()
-                    .return_to_origin(canvas.parent().map(|p| p.width() as f64));
+        // set the copied style as the current pen options
+        action_copied_style_to_pen.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().apply_copied_style_to_pen_options();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // New doc
-        action_new_doc.connect_activate(clone!(
+        // add a link to the selection
+        action_selection_add_link.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -733,7 +1136,7 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::dialog_new_doc(
+                        dialogs::dialog_selection_add_link(
                             &appwindow,
                             &appwindow.active_tab_wrapper().canvas(),
                         )
@@ -743,57 +1146,902 @@ impl RnAppWindow {
             }
         ));
 
-        // Open doc
-        action_open_doc.connect_activate(clone!(
+        // remove the links of the selection
+        action_selection_remove_links.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                glib::spawn_future_local(clone!(
-                    #[weak]
-                    appwindow,
-                    async move {
-                        dialogs::import::filedialog_open_doc(&appwindow).await;
-                    }
-                ));
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().remove_links_from_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Save doc
-        action_save_doc.connect_activate(clone!(
+        // select all strokes
+        action_selection_select_all.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                glib::spawn_future_local(clone!(
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().select_all_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // deselect all strokes
+        action_selection_deselect_all.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().deselect_all_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Clear doc
+        action_clear_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_clear_doc(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Apply redactions
+        action_apply_redactions.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                if canvas.engine_ref().redacted_areas().is_empty() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("No redactions"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_apply_redactions(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Undo stroke
+        action_undo_stroke.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().undo(Instant::now());
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Redo stroke
+        action_redo_stroke.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().redo(Instant::now());
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Zoom reset
+        action_zoom_reset.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let viewport_center = canvas.engine_ref().camera.viewport_center();
+                let new_zoom = Camera::ZOOM_DEFAULT;
+                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+                widget_flags |= canvas
+                    .engine_mut()
+                    .camera
+                    .set_viewport_center(viewport_center);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom fit to width
+        action_zoom_fit_width.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvaswrapper = appwindow.active_tab_wrapper();
+                let canvas = canvaswrapper.canvas();
+                let viewport_center = canvas.engine_ref().camera.viewport_center();
+                let new_zoom = f64::from(canvaswrapper.scroller().width())
+                    / (canvaswrapper.canvas().engine_ref().document.format.width()
+                        + 2.0 * Camera::OVERSHOOT_HORIZONTAL);
+                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+                widget_flags |= canvas
+                    .engine_mut()
+                    .camera
+                    .set_viewport_center(viewport_center);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom in
+        action_zoomin.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let viewport_center = canvas.engine_ref().camera.viewport_center();
+                let new_zoom =
+                    canvas.engine_ref().camera.total_zoom() * (1.0 + RnCanvas::ZOOM_SCROLL_STEP);
+                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+                widget_flags |= canvas
+                    .engine_mut()
+                    .camera
+                    .set_viewport_center(viewport_center);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom out
+        action_zoomout.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let viewport_center = canvas.engine_ref().camera.viewport_center();
+                let new_zoom =
+                    canvas.engine_ref().camera.total_zoom() * (1.0 - RnCanvas::ZOOM_SCROLL_STEP);
+                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+                widget_flags |= canvas
+                    .engine_mut()
+                    .camera
+                    .set_viewport_center(viewport_center);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Rotate view counterclockwise
+        action_rotate_view_left.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let new_rotation = canvas.engine_ref().camera.rotation() - Camera::ROTATION_STEP;
+                let mut widget_flags = canvas.engine_mut().camera_set_rotation(new_rotation);
+                widget_flags |= canvas.engine_mut().update_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Rotate view clockwise
+        action_rotate_view_right.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let new_rotation = canvas.engine_ref().camera.rotation() + Camera::ROTATION_STEP;
+                let mut widget_flags = canvas.engine_mut().camera_set_rotation(new_rotation);
+                widget_flags |= canvas.engine_mut().update_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Reset view rotation
+        action_rotate_view_reset.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let mut widget_flags = canvas.engine_mut().camera_set_rotation(0.0);
+                widget_flags |= canvas.engine_mut().update_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Add page to doc in fixed size mode
+        action_add_page_to_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_action_add_page_to_doc, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().doc_add_page_fixed_size();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Remove page from doc in fixed size mode
+        action_remove_page_from_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().doc_remove_page_fixed_size();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Resize to fit content
+        action_resize_to_fit_content.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Return to the origin page
+        action_return_origin_page.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+
+                let widget_flags = canvas
+                    .engine_mut()
+                    .return_to_origin(canvas.parent().map(|p| p.width() as f64));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Pages overview
+        action_pages_overview.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                dialogs::dialog_pages(&appwindow, &canvas);
+            }
+        ));
+
+        // Bookmark the current view
+        action_bookmark_add.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_bookmark_add(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Jump to a bookmark
+        action_bookmark_goto.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<u32>()) else {
+                    error!("Activated bookmark-goto action with invalid target");
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().goto_bookmark(index as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Remove a bookmark
+        action_bookmark_remove.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<u32>()) else {
+                    error!("Activated bookmark-remove action with invalid target");
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().remove_bookmark(index as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Jump to the last edited stroke
+        action_goto_last_edit.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().goto_last_edit();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Clear guides
+        action_clear_guides.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().clear_guides();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // New doc
+        action_new_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_new_doc(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Open doc
+        action_open_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_open_doc(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Daily note
+        action_daily_note.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        appwindow.open_daily_note().await;
+                    }
+                ));
+            }
+        ));
+
+        // Append quick note to inbox
+        action_quick_note_append.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        appwindow.append_quick_note_to_inbox().await;
+                    }
+                ));
+            }
+        ));
+
+        // Save doc
+        action_save_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let canvas = appwindow.active_tab_wrapper().canvas();
+
+                        if let Some(output_file) = canvas.output_file() {
+                            appwindow.overlays().progressbar_start_pulsing();
+
+                            if let Err(e) = canvas.save_document_to_file(&output_file).await {
+                                error!("Saving document failed, Err: `{e:?}`");
+                                canvas.set_output_file(None);
+                                appwindow
+                                    .overlays()
+                                    .dispatch_toast_error(&gettext("Saving document failed"));
+                                appwindow.overlays().progressbar_abort();
+                            } else {
+                                appwindow.overlays().progressbar_finish();
+                                // No success toast on saving without dialog, success is already indicated in the header title
+                            }
+                        } else {
+                            // Open a dialog to choose a save location
+                            dialogs::export::dialog_save_doc_as(&appwindow, &canvas).await;
+                        }
+                    }
+                ));
+            }
+        ));
+
+        // Save all
+        self.bind_property("unsaved-tabs", &action_save_all, "enabled")
+            .transform_to(|_, unsaved_tabs: u32| Some(unsaved_tabs > 0))
+            .sync_create()
+            .build();
+        action_save_all.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        appwindow.save_all_tabs().await;
+                    }
+                ));
+            }
+        ));
+
+        // Save a copy of the doc
+        action_save_doc_copy.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_save_doc_copy(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Duplicate the doc into a new tab
+        action_duplicate_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let wrapper = appwindow.new_canvas_wrapper();
+                let widget_flags = wrapper.canvas().load_duplicate_of(&canvas);
+                appwindow.append_wrapper_new_tab(&wrapper);
+                appwindow.handle_widget_flags(widget_flags, &wrapper.canvas());
+            }
+        ));
+
+        // Document password
+        action_doc_password.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_doc_password(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Save doc as
+        action_save_doc_as.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_save_doc_as(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Print doc
+        action_print_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                // TODO: Expose these variables as options in the print dialog
+                let draw_background = true;
+                let draw_pattern = true;
+                let optimize_printing = false;
+                let page_order = SplitOrder::default();
+                let margin = 0.0;
+
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                canvas.load_pending_strokes();
+                let pages_content = canvas.engine_ref().extract_pages_content(page_order);
+                let n_pages = pages_content.len();
+                let header_footer = canvas
+                    .engine_ref()
+                    .export_prefs
+                    .doc_export_prefs
+                    .header_footer;
+                let title = canvas.doc_title_display();
+                let header_date = PageHeaderFooter::date_now();
+
+                appwindow.overlays().progressbar_start_pulsing();
+
+                let print_op = PrintOperation::builder().unit(Unit::None).build();
+
+                print_op.connect_begin_print(clone!(move |print_op, _print_cx| {
+                    print_op.set_n_pages(n_pages as i32);
+                }));
+
+                print_op.connect_draw_page(clone!(move |_print_op, print_cx, page_no| {
+                    let page_content = &pages_content[page_no as usize];
+                    let page_bounds = page_content.bounds.unwrap().loosened(margin);
+                    let print_scale = (print_cx.width() / page_bounds.extents()[0])
+                        .min(print_cx.height() / page_bounds.extents()[1]);
+                    let cairo_cx = print_cx.cairo_context();
+
+                    cairo_cx.scale(print_scale, print_scale);
+                    cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
+                    if let Err(e) = page_content.draw_to_cairo(
+                        &cairo_cx,
+                        draw_background,
+                        draw_pattern,
+                        optimize_printing,
+                        margin,
+                        Engine::STROKE_EXPORT_IMAGE_SCALE,
+                    ) {
+                        error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
+                    }
+                    cairo_cx.translate(page_bounds.mins[0], page_bounds.mins[1]);
+                    if let Err(e) = header_footer.draw_to_cairo(
+                        &cairo_cx,
+                        page_bounds.extents(),
+                        &title,
+                        &header_date,
+                        page_no as usize + 1,
+                        n_pages,
+                    ) {
+                        error!(
+                            "Drawing header and footer of page no: {page_no} while printing failed, Err: {e:?}"
+                        );
+                    }
+                }));
+
+                print_op.connect_status_changed(clone!(move |print_op| {
+                    debug!(
+                        "Print operation status has changed to: {:?}",
+                        print_op.status()
+                    );
+                }));
+
+                // Run the print op
+                if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(&appwindow)) {
+                    error!("Running print operation failed , Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Printing document failed"));
+                    appwindow.overlays().progressbar_abort();
+                } else {
+                    appwindow.overlays().progressbar_finish();
+                }
+            }
+        ));
+
+        // Import
+        action_import_file.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_file(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Append the pages of another document
+        action_import_pages_from_file.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::import::filedialog_import_pages_from_doc(&appwindow, &canvas)
+                            .await;
+                    }
+                ));
+            }
+        ));
+
+        // Import photo with perspective correction
+        action_import_photo.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_photo(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Host a collaboration session with the current document
+        action_collab_host.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_collab_host(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Join a collaboration session with the current document
+        action_collab_join.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_collab_join(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Leave the collaboration session
+        action_collab_leave.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                if !canvas.collab_active() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Not in a collaboration session"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                canvas.collab_stop();
+            }
+        ));
+
+        // Share the current view or the selection with a share target
+        action_share.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(target_id) = target.and_then(|t| t.str()).map(|t| t.to_string()) else {
+                    error!("Activated share action without target id");
+                    return;
+                };
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_share(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                            &target_id,
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Broadcast the canvas read-only
+        action_broadcast_start.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_broadcast(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Stop broadcasting the canvas
+        action_broadcast_stop.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                if !canvas.broadcast_active() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("The canvas is not being broadcast"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                canvas.broadcast_stop();
+            }
+        ));
+
+        // Export document
+        action_export_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_export_doc_w_prefs(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Export document pages
+        action_export_doc_pages.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_export_doc_pages_w_prefs(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Extract pages into a new document
+        action_extract_pages.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_extract_pages(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
+            }
+        ));
+
+        // Batch export
+        action_batch_export.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::dialog_batch_export(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Export selection
+        action_export_selection.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
                     #[weak]
                     appwindow,
                     async move {
                         let canvas = appwindow.active_tab_wrapper().canvas();
 
-                        if let Some(output_file) = canvas.output_file() {
-                            appwindow.overlays().progressbar_start_pulsing();
+                        if !canvas.engine_ref().nothing_selected() {
+                            dialogs::export::dialog_export_selection_w_prefs(
+                                &appwindow,
+                                &appwindow.active_tab_wrapper().canvas(),
+                            )
+                            .await;
+                        } else {
+                            appwindow.overlays().dispatch_toast_error(&gettext(
+                                "Exporting selection failed, nothing selected",
+                            ));
+                        }
+                    }
+                ));
+            }
+        ));
 
-                            if let Err(e) = canvas.save_document_to_file(&output_file).await {
-                                error!("Saving document failed, Err: `{e:?}`");
-                                canvas.set_output_file(None);
-                                appwindow
-                                    .overlays()
-                                    .dispatch_toast_error(&gettext("Saving document failed"));
-                                appwindow.overlays().progressbar_abort();
-                            } else {
-                                appwindow.overlays().progressbar_finish();
-                                // No success toast on saving without dialog, success is already indicated in the header title
-                            }
+        // Export selection as figure with a LaTeX or Typst snippet
+        action_export_selection_figure.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let canvas = appwindow.active_tab_wrapper().canvas();
+
+                        if !canvas.engine_ref().nothing_selected() {
+                            dialogs::export::dialog_export_figure(&appwindow, &canvas).await;
                         } else {
-                            // Open a dialog to choose a save location
-                            dialogs::export::dialog_save_doc_as(&appwindow, &canvas).await;
+                            appwindow.overlays().dispatch_toast_error(&gettext(
+                                "Exporting figure failed, nothing selected",
+                            ));
                         }
                     }
                 ));
             }
         ));
 
-        // Save doc as
-        action_save_doc_as.connect_activate(clone!(
+        // Export frames
+        action_export_frames.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -801,7 +2049,7 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::export::dialog_save_doc_as(
+                        dialogs::export::dialog_export_frames(
                             &appwindow,
                             &appwindow.active_tab_wrapper().canvas(),
                         )
@@ -811,73 +2059,210 @@ impl RnAppWindow {
             }
         ));
 
-        // Print doc
-        action_print_doc.connect_activate(clone!(
+        // Clipboard copy
+        action_clipboard_copy.connect_activate(clone!(#[weak(rename_to=appwindow)] self, move |_, _| {
+            glib::spawn_future_local(clone!(#[weak] appwindow, async move {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let receiver = canvas.engine_ref().fetch_clipboard_content();
+                let (content, widget_flags) = match receiver.await {
+                    Ok(Ok((content, widget_flags))) => (content,widget_flags),
+                    Ok(Err(e)) => {
+                        error!("Fetching clipboard content failed in clipboard-copy action, Err: {e:?}");
+                        return;
+                    }
+                    Err(e) => {
+                        error!("Awaiting fetched clipboard content failed in clipboard-copy action, Err: {e:?}");
+                        return;
+                    }
+                };
+
+                let gdk_content_provider = gdk::ContentProvider::new_union(content.into_iter().map(|(data, mime_type)| {
+                    gdk::ContentProvider::for_bytes(mime_type.as_str(), &glib::Bytes::from_owned(data))
+                }).collect::<Vec<gdk::ContentProvider>>().as_slice());
+
+                if let Err(e) = appwindow.clipboard().set_content(Some(&gdk_content_provider)) {
+                    error!("Set appwindow clipboard content failed in clipboard-copy action, Err: {e:?}");
+                }
+
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }));
+        }));
+
+        // Clipboard cut
+        action_clipboard_cut.connect_activate(clone!(#[weak(rename_to=appwindow)] self, move |_, _| {
+            glib::spawn_future_local(clone!(#[weak] appwindow, async move {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let receiver = canvas.engine_mut().cut_clipboard_content();
+                let (content, widget_flags) = match receiver.await {
+                    Ok(Ok((content, widget_flags))) => (content,widget_flags),
+                    Ok(Err(e)) => {
+                        error!("Cutting clipboard content failed in clipboard-cut action, Err: {e:?}");
+                        return;
+                    }
+                    Err(e) => {
+                        error!("Awaiting cut clipboard content failed in clipboard-cut action, Err: {e:?}");
+                        return;
+                    }
+                };
+                let gdk_content_provider = gdk::ContentProvider::new_union(content.into_iter().map(|(data, mime_type)| {
+                    gdk::ContentProvider::for_bytes(mime_type.as_str(), &glib::Bytes::from_owned(data))
+                }).collect::<Vec<gdk::ContentProvider>>().as_slice());
+
+                if let Err(e) = appwindow.clipboard().set_content(Some(&gdk_content_provider)) {
+                    error!("Set appwindow clipboard content failed in clipboard-cut action, Err: {e:?}");
+                }
+
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }));
+        }));
+
+        // Clipboard paste
+        action_clipboard_paste.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                // TODO: Expose these variables as options in the print dialog
-                let draw_background = true;
-                let draw_pattern = true;
-                let optimize_printing = false;
-                let page_order = SplitOrder::default();
-                let margin = 0.0;
-
-                let canvas = appwindow.active_tab_wrapper().canvas();
-                let pages_content = canvas.engine_ref().extract_pages_content(page_order);
-                let n_pages = pages_content.len();
+                appwindow.clipboard_paste(None, false);
+            }
+        ));
 
-                appwindow.overlays().progressbar_start_pulsing();
+        action_clipboard_paste_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
 
-                let print_op = PrintOperation::builder().unit(Unit::None).build();
+                let last_contextmenu_pos = canvas_wrapper.last_contextmenu_pos().map(|vec2| {
+                    let p = graphene::Point::new(vec2.x as f32, vec2.y as f32);
+                    (canvas.engine_ref().camera.transform().inverse()
+                        * na::point![p.x() as f64, p.y() as f64])
+                    .coords
+                });
 
-                print_op.connect_begin_print(clone!(move |print_op, _print_cx| {
-                    print_op.set_n_pages(n_pages as i32);
-                }));
+                appwindow.clipboard_paste(last_contextmenu_pos, false);
+            }
+        ));
 
-                print_op.connect_draw_page(clone!(move |_print_op, print_cx, page_no| {
-                    let page_content = &pages_content[page_no as usize];
-                    let page_bounds = page_content.bounds.unwrap().loosened(margin);
-                    let print_scale = (print_cx.width() / page_bounds.extents()[0])
-                        .min(print_cx.height() / page_bounds.extents()[1]);
-                    let cairo_cx = print_cx.cairo_context();
+        action_clipboard_paste_in_place.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.clipboard_paste(None, true);
+            }
+        ));
 
-                    cairo_cx.scale(print_scale, print_scale);
-                    cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
-                    if let Err(e) = page_content.draw_to_cairo(
-                        &cairo_cx,
-                        draw_background,
-                        draw_pattern,
-                        optimize_printing,
-                        margin,
-                        Engine::STROKE_EXPORT_IMAGE_SCALE,
-                    ) {
-                        error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
+        // Insert an image file at the context menu position
+        action_insert_image_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let target_pos = canvas_wrapper.last_contextmenu_pos().map(|pos| {
+                    (canvas.engine_ref().camera.transform().inverse() * na::point![pos.x, pos.y])
+                        .coords
+                });
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_insert_image(&appwindow, target_pos).await;
                     }
-                }));
+                ));
+            }
+        ));
 
-                print_op.connect_status_changed(clone!(move |print_op| {
-                    debug!(
-                        "Print operation status has changed to: {:?}",
-                        print_op.status()
-                    );
-                }));
+        // Start writing text at the context menu position
+        action_insert_text_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let target_pos = canvas_wrapper.last_contextmenu_pos().map(|pos| {
+                    (canvas.engine_ref().camera.transform().inverse() * na::point![pos.x, pos.y])
+                        .coords
+                });
+                let widget_flags = canvas.engine_mut().insert_text(String::new(), target_pos);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                canvas.grab_focus();
+            }
+        ));
 
-                // Run the print op
-                if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(&appwindow)) {
-                    error!("Running print operation failed , Err: {e:?}");
-                    appwindow
-                        .overlays()
-                        .dispatch_toast_error(&gettext("Printing document failed"));
-                    appwindow.overlays().progressbar_abort();
-                } else {
-                    appwindow.overlays().progressbar_finish();
-                }
+        // Insert the current date and time at the text cursor, or at the top of the viewport
+        action_insert_datetime.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.insert_datetime(None);
+            }
+        ));
+
+        // Insert the current date and time at the context menu position
+        action_insert_datetime_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let target_pos = canvas_wrapper.last_contextmenu_pos().map(|pos| {
+                    (canvas.engine_ref().camera.transform().inverse() * na::point![pos.x, pos.y])
+                        .coords
+                });
+                appwindow.insert_datetime(target_pos);
+            }
+        ));
+
+        // Override the background of the page at the context menu position
+        action_page_background_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_page_background(&appwindow, &canvas, pos).await;
+                    }
+                ));
+            }
+        ));
+
+        // Replace the misspelled word at the context menu position with the chosen suggestion
+        action_spellcheck_correct.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(replacement) = target.and_then(|t| t.get::<String>()) else {
+                    error!("Activated spellcheck-correct action with invalid target");
+                    return;
+                };
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
+
+                let widget_flags = canvas.engine_mut().spellcheck_correct_at(pos, &replacement);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Import
-        action_import_file.connect_activate(clone!(
+        // insert a table with the number of rows and columns chosen in a dialog
+        action_insert_table.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -885,14 +2270,18 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::import::filedialog_import_file(&appwindow).await;
+                        dialogs::dialog_insert_table(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
                     }
                 ));
             }
         ));
 
-        // Export document
-        action_export_doc.connect_activate(clone!(
+        // insert todo items with the labels entered in a dialog
+        action_insert_todo_list.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -900,7 +2289,7 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::export::dialog_export_doc_w_prefs(
+                        dialogs::dialog_insert_todo_list(
                             &appwindow,
                             &appwindow.active_tab_wrapper().canvas(),
                         )
@@ -910,16 +2299,66 @@ impl RnAppWindow {
             }
         ));
 
-        // Export document pages
-        action_export_doc_pages.connect_activate(clone!(
+        // Edit the text of the table cell at the context menu position
+        action_table_edit_cell.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
+
                 glib::spawn_future_local(clone!(
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::export::dialog_export_doc_pages_w_prefs(
+                        dialogs::dialog_edit_table_cell(&appwindow, &canvas, pos).await;
+                    }
+                ));
+            }
+        ));
+
+        // Insert or remove rows and columns of the table at the context menu position
+        action_table_edit.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(edit) = target
+                    .and_then(|t| t.get::<String>())
+                    .and_then(|s| TableEdit::from_str(&s).ok())
+                else {
+                    error!("Activated table-edit action with invalid target");
+                    return;
+                };
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
+
+                let widget_flags = canvas.engine_mut().table_edit_at(pos, edit);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // insert a sticky note with the text and color chosen in a dialog
+        action_insert_sticky_note.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_insert_sticky_note(
                             &appwindow,
                             &appwindow.active_tab_wrapper().canvas(),
                         )
@@ -929,114 +2368,156 @@ impl RnAppWindow {
             }
         ));
 
-        // Export selection
-        action_export_selection.connect_activate(clone!(
+        // Edit the text of the sticky note at the context menu position
+        action_sticky_note_edit.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
+
                 glib::spawn_future_local(clone!(
                     #[weak]
                     appwindow,
                     async move {
-                        let canvas = appwindow.active_tab_wrapper().canvas();
-
-                        if !canvas.engine_ref().nothing_selected() {
-                            dialogs::export::dialog_export_selection_w_prefs(
-                                &appwindow,
-                                &appwindow.active_tab_wrapper().canvas(),
-                            )
-                            .await;
-                        } else {
-                            appwindow.overlays().dispatch_toast_error(&gettext(
-                                "Exporting selection failed, nothing selected",
-                            ));
-                        }
+                        dialogs::dialog_edit_sticky_note(&appwindow, &canvas, pos).await;
                     }
                 ));
             }
         ));
 
-        // Clipboard copy
-        action_clipboard_copy.connect_activate(clone!(#[weak(rename_to=appwindow)] self, move |_, _| {
-            glib::spawn_future_local(clone!(#[weak] appwindow, async move {
-                let canvas = appwindow.active_tab_wrapper().canvas();
-                let receiver = canvas.engine_ref().fetch_clipboard_content();
-                let (content, widget_flags) = match receiver.await {
-                    Ok(Ok((content, widget_flags))) => (content,widget_flags),
-                    Ok(Err(e)) => {
-                        error!("Fetching clipboard content failed in clipboard-copy action, Err: {e:?}");
-                        return;
-                    }
-                    Err(e) => {
-                        error!("Awaiting fetched clipboard content failed in clipboard-copy action, Err: {e:?}");
-                        return;
+        // insert a mind map with the nodes and layout entered in a dialog
+        action_insert_mind_map.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_insert_mind_map(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
                     }
-                };
-
-                let gdk_content_provider = gdk::ContentProvider::new_union(content.into_iter().map(|(data, mime_type)| {
-                    gdk::ContentProvider::for_bytes(mime_type.as_str(), &glib::Bytes::from_owned(data))
-                }).collect::<Vec<gdk::ContentProvider>>().as_slice());
-
-                if let Err(e) = appwindow.clipboard().set_content(Some(&gdk_content_provider)) {
-                    error!("Set appwindow clipboard content failed in clipboard-copy action, Err: {e:?}");
-                }
+                ));
+            }
+        ));
 
-                appwindow.handle_widget_flags(widget_flags, &canvas);
-            }));
-        }));
+        // Edit the nodes of the mind map at the context menu position
+        action_mind_map_edit.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
 
-        // Clipboard cut
-        action_clipboard_cut.connect_activate(clone!(#[weak(rename_to=appwindow)] self, move |_, _| {
-            glib::spawn_future_local(clone!(#[weak] appwindow, async move {
-                let canvas = appwindow.active_tab_wrapper().canvas();
-                let receiver = canvas.engine_mut().cut_clipboard_content();
-                let (content, widget_flags) = match receiver.await {
-                    Ok(Ok((content, widget_flags))) => (content,widget_flags),
-                    Ok(Err(e)) => {
-                        error!("Cutting clipboard content failed in clipboard-cut action, Err: {e:?}");
-                        return;
-                    }
-                    Err(e) => {
-                        error!("Awaiting cut clipboard content failed in clipboard-cut action, Err: {e:?}");
-                        return;
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_edit_mind_map(&appwindow, &canvas, pos).await;
                     }
-                };
-                let gdk_content_provider = gdk::ContentProvider::new_union(content.into_iter().map(|(data, mime_type)| {
-                    gdk::ContentProvider::for_bytes(mime_type.as_str(), &glib::Bytes::from_owned(data))
-                }).collect::<Vec<gdk::ContentProvider>>().as_slice());
+                ));
+            }
+        ));
 
-                if let Err(e) = appwindow.clipboard().set_content(Some(&gdk_content_provider)) {
-                    error!("Set appwindow clipboard content failed in clipboard-cut action, Err: {e:?}");
-                }
+        // Collapse or expand the mind map node at the context menu position
+        action_mind_map_toggle_node.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
 
+                let widget_flags = canvas.engine_mut().mind_map_toggle_collapsed_at(pos);
                 appwindow.handle_widget_flags(widget_flags, &canvas);
-            }));
-        }));
+            }
+        ));
 
-        // Clipboard paste
-        action_clipboard_paste.connect_activate(clone!(
+        // insert a board with the column titles entered in a dialog
+        action_insert_board.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                appwindow.clipboard_paste(None);
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_insert_board(
+                            &appwindow,
+                            &appwindow.active_tab_wrapper().canvas(),
+                        )
+                        .await;
+                    }
+                ));
             }
         ));
 
-        action_clipboard_paste_contextmenu.connect_activate(clone!(
+        // Edit the column titles of the board at the context menu position
+        action_board_edit.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let canvas_wrapper = appwindow.active_tab_wrapper();
                 let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
 
-                let last_contextmenu_pos = canvas_wrapper.last_contextmenu_pos().map(|vec2| {
-                    let p = graphene::Point::new(vec2.x as f32, vec2.y as f32);
-                    (canvas.engine_ref().camera.transform().inverse()
-                        * na::point![p.x() as f64, p.y() as f64])
-                    .coords
-                });
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_edit_board(&appwindow, &canvas, pos).await;
+                    }
+                ));
+            }
+        ));
+
+        // rename the frame at the context menu position
+        action_frame_rename.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let canvas_wrapper = appwindow.active_tab_wrapper();
+                let canvas = canvas_wrapper.canvas();
+                let Some(last_contextmenu_pos) = canvas_wrapper.last_contextmenu_pos() else {
+                    return;
+                };
+                let pos = (canvas.engine_ref().camera.transform().inverse()
+                    * na::point![last_contextmenu_pos.x, last_contextmenu_pos.y])
+                .coords;
 
-                appwindow.clipboard_paste(last_contextmenu_pos);
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_rename_frame(&appwindow, &canvas, pos).await;
+                    }
+                ));
             }
         ));
     }
@@ -1045,41 +2526,67 @@ impl RnAppWindow {
         let app = self.app();
 
         app.set_accels_for_action("win.active-tab-close", &["<Ctrl>w"]);
-        app.set_accels_for_action("win.fullscreen", &["F11"]);
+        app.set_accels_for_action("win.focus-mode", &["F11"]);
+        app.set_accels_for_action("win.fullscreen", &["<Shift>F11"]);
         app.set_accels_for_action("win.keyboard-shortcuts", &["<Ctrl>question"]);
         app.set_accels_for_action("win.open-canvasmenu", &["F9"]);
         app.set_accels_for_action("win.open-appmenu", &["F10"]);
         app.set_accels_for_action("win.open-doc", &["<Ctrl>o"]);
+        app.set_accels_for_action("win.daily-note", &["<Ctrl><Shift>d"]);
         app.set_accels_for_action("win.save-doc", &["<Ctrl>s"]);
         app.set_accels_for_action("win.save-doc-as", &["<Ctrl><Shift>s"]);
+        app.set_accels_for_action("win.save-all", &["<Ctrl><Alt>s"]);
         app.set_accels_for_action("win.new-tab", &["<Ctrl>t"]);
         app.set_accels_for_action("win.snap-positions", &["<Ctrl><Shift>p"]);
         app.set_accels_for_action("win.clear-doc", &["<Ctrl>l"]);
         app.set_accels_for_action("win.print-doc", &["<Ctrl>p"]);
         app.set_accels_for_action("win.add-page-to-doc", &["<Ctrl><Shift>a"]);
         app.set_accels_for_action("win.remove-page-from-doc", &["<Ctrl><Shift>r"]);
+        app.set_accels_for_action("win.pages-overview", &["<Ctrl><Shift>o"]);
+        app.set_accels_for_action("win.bookmark-add", &["<Ctrl><Shift>b"]);
+        app.set_accels_for_action("win.goto-last-edit", &["<Ctrl><Shift>e"]);
         app.set_accels_for_action("win.zoom-in", &["<Ctrl>plus"]);
         app.set_accels_for_action("win.zoom-out", &["<Ctrl>minus"]);
+        app.set_accels_for_action("win.rotate-view-left", &["<Ctrl>bracketleft"]);
+        app.set_accels_for_action("win.rotate-view-right", &["<Ctrl>bracketright"]);
+        app.set_accels_for_action("win.rotate-view-reset", &["<Ctrl>0"]);
         app.set_accels_for_action("win.import-file", &["<Ctrl>i"]);
+        app.set_accels_for_action("win.insert-datetime", &["<Ctrl>semicolon"]);
         app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
         app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
         app.set_accels_for_action("win.clipboard-copy", &["<Ctrl>c"]);
         app.set_accels_for_action("win.clipboard-cut", &["<Ctrl>x"]);
         app.set_accels_for_action("win.clipboard-paste", &["<Ctrl>v"]);
+        app.set_accels_for_action("win.selection-copy-style", &["<Ctrl><Alt>c"]);
+        app.set_accels_for_action("win.selection-paste-style", &["<Ctrl><Alt>v"]);
+        app.set_accels_for_action("win.selection-add-link", &["<Ctrl>k"]);
+        app.set_accels_for_action("win.selection-remove-links", &["<Ctrl><Shift>k"]);
+        app.set_accels_for_action("win.selection-bring-to-front", &["<Ctrl><Shift>Page_Up"]);
+        app.set_accels_for_action("win.selection-send-to-back", &["<Ctrl><Shift>Page_Down"]);
         app.set_accels_for_action("win.pen-style::brush", &["<Ctrl>1"]);
         app.set_accels_for_action("win.pen-style::shaper", &["<Ctrl>2"]);
         app.set_accels_for_action("win.pen-style::typewriter", &["<Ctrl>3"]);
         app.set_accels_for_action("win.pen-style::eraser", &["<Ctrl>4"]);
         app.set_accels_for_action("win.pen-style::selector", &["<Ctrl>5"]);
         app.set_accels_for_action("win.pen-style::tools", &["<Ctrl>6"]);
+        for i in 0..PenPresets::MAX_PRESETS {
+            app.set_accels_for_action(
+                &format!("win.pen-preset-apply({i})"),
+                &[&format!("<Alt>{}", i + 1)],
+            );
+        }
 
         // shortcuts for devel build
         if config::PROFILE.to_lowercase().as_str() == "devel" {
             app.set_accels_for_action("win.visual-debug", &["<Ctrl><Shift>v"]);
+            app.set_accels_for_action("win.debug-hud", &["<Ctrl><Shift>h"]);
         }
     }
 
-    fn clipboard_paste(&self, target_pos: Option<na::Vector2<f64>>) {
+    /// Paste the clipboard content at the target position, or at the default import position when it is `None`.
+    ///
+    /// When `in_place` is true, copied strokes are pasted at the position they were copied from.
+    fn clipboard_paste(&self, target_pos: Option<na::Vector2<f64>>, in_place: bool) {
         let canvas_wrapper = self.active_tab_wrapper();
         let canvas = canvas_wrapper.canvas();
         let content_formats = self.clipboard().formats();
@@ -1180,7 +2687,12 @@ impl RnAppWindow {
                                             restrain_to_viewport: false,
                                             respect_borders: appwindow.respect_borders(),
                                         });
-                                    if let Err(e) = canvas.insert_stroke_content(json_string.to_string(), resize_argument, target_pos).await {
+                                    let res = if in_place {
+                                        canvas.insert_stroke_content_in_place(json_string.to_string()).await
+                                    } else {
+                                        canvas.insert_stroke_content(json_string.to_string(), resize_argument, target_pos).await
+                                    };
+                                    if let Err(e) = res {
                                         error!("Failed to insert stroke content while pasting as `{}`, Err: {e:?}", StrokeContent::MIME_TYPE);
                                     }
                                 }
@@ -1286,6 +2798,7 @@ impl RnAppWindow {
                                         texture.save_to_png_bytes().to_vec(),
                                         target_pos,
                                         appwindow.respect_borders(),
+                                        None,
                                     )
                                     .await
                                 {
@@ -1304,6 +2817,65 @@ impl RnAppWindow {
                     }
                 ));
             }
+        } else if content_formats.contain_mime_type("text/markdown") {
+            glib::spawn_future_local(clone!(
+                #[weak]
+                canvas,
+                #[weak(rename_to=appwindow)]
+                self,
+                async move {
+                    debug!("Recognized clipboard content: markdown text");
+
+                    match appwindow
+                        .clipboard()
+                        .read_future(&["text/markdown"], glib::source::Priority::DEFAULT)
+                        .await
+                    {
+                        Ok((input_stream, _)) => {
+                            let mut acc = Vec::new();
+                            loop {
+                                match input_stream
+                                    .read_future(
+                                        vec![0; CLIPBOARD_INPUT_STREAM_BUFSIZE],
+                                        glib::source::Priority::DEFAULT,
+                                    )
+                                    .await
+                                {
+                                    Ok((mut bytes, n)) => {
+                                        if n == 0 {
+                                            break;
+                                        }
+                                        acc.append(&mut bytes);
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to read clipboard input stream while pasting as markdown, Err: {e:?}");
+                                        acc.clear();
+                                        break;
+                                    }
+                                }
+                            }
+
+                            if !acc.is_empty() {
+                                match crate::utils::str_from_u8_nul_utf8(&acc) {
+                                    Ok(text) => {
+                                        if let Err(e) =
+                                            canvas.load_in_text(text.to_string(), true, target_pos)
+                                        {
+                                            error!("Failed to paste clipboard markdown text, Err: {e:?}");
+                                        }
+                                    }
+                                    Err(e) => error!("Failed to get string from clipboard data while pasting as markdown, Err: {e:?}"),
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to read clipboard data while pasting as markdown, Err: {e:?}"
+                            );
+                        }
+                    };
+                }
+            ));
         } else if content_formats.contain_mime_type("text/plain")
             || content_formats.contain_mime_type("text/plain;charset=utf-8")
         {
@@ -1317,7 +2889,8 @@ impl RnAppWindow {
 
                     match appwindow.clipboard().read_text_future().await {
                         Ok(Some(text)) => {
-                            if let Err(e) = canvas.load_in_text(text.to_string(), target_pos) {
+                            if let Err(e) = canvas.load_in_text(text.to_string(), false, target_pos)
+                            {
                                 error!("Failed to paste clipboard text, Err: {e:?}");
                             }
                         }
@@ -1337,4 +2910,32 @@ impl RnAppWindow {
             );
         }
     }
+
+    /// Insert the current date and time as text, formatted with the format that is set in the settings.
+    ///
+    /// When text is being edited it is inserted at the text cursor. Else a new text stroke is created at the
+    /// target position, or at the top of the viewport when it is `None`.
+    fn insert_datetime(&self, target_pos: Option<na::Vector2<f64>>) {
+        let format = self
+            .app()
+            .app_settings()
+            .map(|app_settings| app_settings.string("datetime-format").to_string())
+            .filter(|format| !format.trim().is_empty())
+            .unwrap_or_else(|| String::from("%Y-%m-%d %H:%M"));
+        let text = match glib::DateTime::now_local().and_then(|now| now.format(&format)) {
+            Ok(text) => text,
+            Err(e) => {
+                error!("Formatting the current date and time failed, Err: {e:?}");
+                self.overlays()
+                    .dispatch_toast_error(&gettext("Invalid date and time format"));
+                return;
+            }
+        };
+        let canvas = self.active_tab_wrapper().canvas();
+        let widget_flags = canvas
+            .engine_mut()
+            .insert_text(text.to_string(), target_pos);
+        self.handle_widget_flags(widget_flags, &canvas);
+        canvas.grab_focus();
+    }
 }
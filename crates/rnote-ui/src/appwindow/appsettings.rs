@@ -2,7 +2,47 @@
 use crate::appwindow::RnAppWindow;
 use adw::{prelude::*, subclass::prelude::*};
 use gtk4::{gdk, glib, glib::clone};
-use tracing::error;
+use rnote_engine::engine::EngineConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{error, warn};
+
+/// The settings and the engine config, exported together to move them to another machine or share them.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "settings_profile")]
+struct SettingsProfile {
+    #[serde(rename = "version")]
+    version: u32,
+    /// The settings keys with their values in the GVariant text format.
+    #[serde(rename = "settings")]
+    settings: BTreeMap<String, String>,
+    /// The engine config containing the pen configs and presets.
+    #[serde(rename = "engine_config")]
+    engine_config: serde_json::Value,
+}
+
+impl SettingsProfile {
+    const VERSION: u32 = 1;
+    /// Keys that are specific to the machine or the window, or are exported separately.
+    ///
+    /// Export hooks are excluded as well, so that importing a shared profile can't set up commands that get run.
+    const EXCLUDED_KEYS: &'static [&'static str] = &[
+        "window-width",
+        "window-height",
+        "is-maximized",
+        "sidebar-show",
+        "selected-workspace-index",
+        "workspace-list",
+        "engine-config",
+        "export-hooks",
+        "export-hook-doc",
+        "export-hook-doc-pages",
+        "export-hook-selection",
+        "daily-notes-dir",
+        "daily-notes-template",
+        "quick-note-inbox",
+    ];
+}
 
 impl RnAppWindow {
     /// Setup settings binds.
@@ -29,45 +69,160 @@ impl RnAppWindow {
             }
         ));
 
+        // Showing the sidebar is remembered, but not synced between the windows
         app_settings
             .bind("sidebar-show", &self.split_view(), "show-sidebar")
             .get_no_changes()
             .build();
 
+        // The binds below also apply changes made in other windows, so that all windows share the same settings.
+
         // autosave
-        app_settings
-            .bind("autosave", self, "autosave")
-            .get_no_changes()
-            .build();
+        app_settings.bind("autosave", self, "autosave").build();
 
         // autosave interval secs
         app_settings
             .bind("autosave-interval-secs", self, "autosave-interval-secs")
-            .get_no_changes()
             .build();
 
         // righthanded
         app_settings
             .bind("righthanded", self, "righthanded")
-            .get_no_changes()
+            .build();
+
+        // toolbar placement
+        app_settings
+            .bind("toolbar-placement", self, "toolbar-placement")
+            .build();
+        app_settings
+            .bind(
+                "toolbar-placement",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_toolbar_placement_row(),
+                "selected",
+            )
+            .build();
+
+        // tablet mode
+        app_settings
+            .bind("tablet-mode", self, "tablet-mode")
+            .build();
+        app_settings
+            .bind(
+                "tablet-mode",
+                &self.sidebar().settings_panel().general_tablet_mode_row(),
+                "selected",
+            )
+            .build();
+
+        // compact toolbar
+        app_settings
+            .bind("compact-toolbar", self, "compact-toolbar")
+            .build();
+        app_settings
+            .bind(
+                "compact-toolbar",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_compact_toolbar_row(),
+                "active",
+            )
             .build();
 
         // block pinch zoom
         app_settings
             .bind("block-pinch-zoom", self, "block-pinch-zoom")
-            .get_no_changes()
             .build();
 
         // touch drawing
         app_settings
             .bind("touch-drawing", self, "touch-drawing")
-            .get_no_changes()
             .build();
 
         // respect borders
         app_settings
             .bind("respect-borders", self, "respect-borders")
-            .get_no_changes()
+            .build();
+
+        // save backups
+        app_settings
+            .bind(
+                "save-backups",
+                &self.sidebar().settings_panel().general_save_backups_row(),
+                "value",
+            )
+            .build();
+
+        // save mirror format
+        app_settings
+            .bind(
+                "save-mirror-format",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_save_mirror_format_row(),
+                "selected",
+            )
+            .build();
+
+        // date and time format
+        app_settings
+            .bind(
+                "datetime-format",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_datetime_format_row(),
+                "text",
+            )
+            .build();
+
+        // daily notes
+        app_settings
+            .bind(
+                "daily-notes-dir",
+                &self.sidebar().settings_panel().daily_notes_dir_row(),
+                "text",
+            )
+            .build();
+        app_settings
+            .bind(
+                "daily-notes-template",
+                &self.sidebar().settings_panel().daily_notes_template_row(),
+                "text",
+            )
+            .build();
+        app_settings
+            .bind(
+                "daily-notes-name-format",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .daily_notes_name_format_row(),
+                "text",
+            )
+            .build();
+        app_settings
+            .bind(
+                "daily-notes-open-on-startup",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .daily_notes_open_on_startup_row(),
+                "active",
+            )
+            .build();
+
+        // quick notes
+        app_settings
+            .bind(
+                "quick-note-inbox",
+                &self.sidebar().settings_panel().quick_note_inbox_row(),
+                "text",
+            )
             .build();
 
         // show scrollbars
@@ -80,7 +235,19 @@ impl RnAppWindow {
                     .general_show_scrollbars_row(),
                 "active",
             )
-            .get_no_changes()
+            .build();
+
+        // show minimap
+        app_settings
+            .bind(
+                "show-minimap",
+                &self.sidebar().settings_panel().general_show_minimap_row(),
+                "active",
+            )
+            .build();
+        app_settings
+            .bind("show-minimap", &self.overlays().minimap(), "visible")
+            .get_only()
             .build();
 
         // inertial scrolling
@@ -93,7 +260,36 @@ impl RnAppWindow {
                     .general_inertial_scrolling_row(),
                 "active",
             )
-            .get_no_changes()
+            .build();
+
+        // scroll step
+        app_settings
+            .bind(
+                "scroll-step",
+                &self.sidebar().settings_panel().general_scroll_step_row(),
+                "value",
+            )
+            .build();
+
+        // zoom scroll step
+        app_settings
+            .bind(
+                "zoom-scroll-step",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_zoom_scroll_step_row(),
+                "value",
+            )
+            .build();
+
+        // trace dump
+        app_settings
+            .bind(
+                "trace-dump",
+                &self.sidebar().settings_panel().diagnostics_trace_dump_row(),
+                "active",
+            )
             .build();
 
         // regular cursor
@@ -106,7 +302,6 @@ impl RnAppWindow {
                     .general_regular_cursor_picker(),
                 "picked",
             )
-            .get_no_changes()
             .build();
 
         // drawing cursor
@@ -119,7 +314,6 @@ impl RnAppWindow {
                     .general_drawing_cursor_picker(),
                 "picked",
             )
-            .get_no_changes()
             .build();
 
         // show drawing cursor
@@ -132,7 +326,6 @@ impl RnAppWindow {
                     .general_show_drawing_cursor_row(),
                 "active",
             )
-            .get_no_changes()
             .build();
 
         // colorpicker palette
@@ -169,7 +362,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -179,7 +371,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -189,7 +380,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -199,7 +389,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -209,7 +398,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -219,7 +407,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -229,7 +416,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -239,7 +425,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -249,7 +434,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -259,7 +443,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -269,7 +452,6 @@ impl RnAppWindow {
             )
             .mapping(gdk_color_mapping)
             .set_mapping(gdk_color_set_mapping)
-            .get_no_changes()
             .build();
 
         // brush stroke widths
@@ -284,7 +466,6 @@ impl RnAppWindow {
                     .setter_1(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -297,7 +478,6 @@ impl RnAppWindow {
                     .setter_2(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -310,7 +490,6 @@ impl RnAppWindow {
                     .setter_3(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
 
         // shaper stroke widths
@@ -325,7 +504,6 @@ impl RnAppWindow {
                     .setter_1(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -338,7 +516,6 @@ impl RnAppWindow {
                     .setter_2(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -351,7 +528,6 @@ impl RnAppWindow {
                     .setter_3(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
 
         // eraser widths
@@ -366,7 +542,6 @@ impl RnAppWindow {
                     .setter_1(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -379,7 +554,6 @@ impl RnAppWindow {
                     .setter_2(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
         app_settings
             .bind(
@@ -392,7 +566,6 @@ impl RnAppWindow {
                     .setter_3(),
                 "stroke-width",
             )
-            .get_no_changes()
             .build();
 
         Ok(())
@@ -437,7 +610,12 @@ impl RnAppWindow {
     }
 
     /// Save settings that are not bound as binds.
+    ///
+    /// Quick note windows are skipped, their size and single page document should not become the defaults.
     pub(crate) fn save_to_settings(&self) -> anyhow::Result<()> {
+        if self.is_quick_note() {
+            return Ok(());
+        }
         let app = self.app();
         let app_settings = app
             .app_settings()
@@ -470,6 +648,115 @@ impl RnAppWindow {
         Ok(())
     }
 
+    /// The export hook profiles, their command templates by name.
+    pub(crate) fn export_hooks(&self) -> BTreeMap<String, String> {
+        self.app()
+            .app_settings()
+            .and_then(|app_settings| {
+                app_settings
+                    .value("export-hooks")
+                    .get::<BTreeMap<String, String>>()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Save the export hook profiles.
+    pub(crate) fn set_export_hooks(&self, export_hooks: &BTreeMap<String, String>) {
+        let Some(app_settings) = self.app().app_settings() else {
+            return;
+        };
+        if let Err(e) = app_settings.set_value("export-hooks", &export_hooks.to_variant()) {
+            error!("Saving export hooks to settings failed, Err: {e:?}");
+        }
+    }
+
+    /// Export the settings and the engine config of the active tab, including the pen presets, as Json.
+    pub(crate) fn export_settings_profile(&self) -> anyhow::Result<String> {
+        let app_settings = self
+            .app()
+            .app_settings()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+        let schema = app_settings
+            .settings_schema()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+
+        let settings = schema
+            .list_keys()
+            .into_iter()
+            .filter(|key| !SettingsProfile::EXCLUDED_KEYS.contains(&key.as_str()))
+            .map(|key| {
+                let value = app_settings.value(&key).print(false).to_string();
+                (key.to_string(), value)
+            })
+            .collect();
+        let engine_config = serde_json::from_str(
+            &self
+                .active_tab_wrapper()
+                .canvas()
+                .engine_ref()
+                .export_engine_config_as_json()?,
+        )?;
+
+        Ok(serde_json::to_string_pretty(&SettingsProfile {
+            version: SettingsProfile::VERSION,
+            settings,
+            engine_config,
+        })?)
+    }
+
+    /// Import settings and an engine config that were exported with `export_settings_profile()`.
+    ///
+    /// Unknown or invalid settings are skipped. The document of the active tab is not changed.
+    pub(crate) fn import_settings_profile(&self, serialized: &str) -> anyhow::Result<()> {
+        let app_settings = self
+            .app()
+            .app_settings()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+        let schema = app_settings
+            .settings_schema()
+            .ok_or_else(|| anyhow::anyhow!("Settings schema not found."))?;
+        let profile = serde_json::from_str::<SettingsProfile>(serialized)?;
+        if profile.version > SettingsProfile::VERSION {
+            return Err(anyhow::anyhow!(
+                "Settings profile version {} is not supported.",
+                profile.version
+            ));
+        }
+
+        for (key, value) in profile.settings {
+            if SettingsProfile::EXCLUDED_KEYS.contains(&key.as_str()) || !schema.has_key(&key) {
+                warn!("Skipping unknown setting `{key}` while importing settings profile.");
+                continue;
+            }
+            let value_type = schema.key(&key).value_type();
+            match glib::Variant::parse(Some(&value_type), &value) {
+                Ok(value) => {
+                    if let Err(e) = app_settings.set_value(&key, &value) {
+                        warn!("Failed to import setting `{key}`, Err: {e:?}");
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to parse value of setting `{key}`, Err: {e:?}");
+                }
+            }
+        }
+        // The color scheme is not bound, so it needs to be applied
+        self.app().activate_action(
+            "color-scheme",
+            Some(&app_settings.string("color-scheme").to_variant()),
+        );
+
+        let canvas = self.active_tab_wrapper().canvas();
+        let engine_config = EngineConfig::from_json_lenient(&profile.engine_config.to_string())?;
+        let widget_flags = canvas
+            .engine_mut()
+            .load_engine_config_sync_tab(engine_config, crate::env::pkg_data_dir().ok());
+        self.handle_widget_flags(widget_flags, &canvas);
+        canvas.save_engine_config(&app_settings)?;
+
+        Ok(())
+    }
+
     pub(crate) fn setup_periodic_save(&self) -> anyhow::Result<()> {
         let app = self.app();
         let app_settings = app
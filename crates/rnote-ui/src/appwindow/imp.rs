@@ -3,8 +3,9 @@ use crate::{config, dialogs, RnMainHeader, RnOverlays, RnSidebar};
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
 use gtk4::{
-    gdk, glib, glib::clone, Align, ArrowType, CompositeTemplate, CornerType, CssProvider, PackType,
-    PadActionType, PadController, PositionType,
+    gdk, glib, glib::clone, Align, ArrowType, CompositeTemplate, CornerType, CssProvider,
+    EventControllerLegacy, EventControllerMotion, PackType, PadActionType, PadController,
+    PositionType, PropagationPhase,
 };
 use once_cell::sync::Lazy;
 use std::cell::{Cell, RefCell};
@@ -17,23 +18,37 @@ pub(crate) struct RnAppWindow {
     pub(crate) drawing_pad_controller: RefCell<Option<PadController>>,
     pub(crate) autosave_source_id: RefCell<Option<glib::SourceId>>,
     pub(crate) periodic_configsave_source_id: RefCell<Option<glib::SourceId>>,
+    pub(crate) document_palette_source_id: RefCell<Option<glib::SourceId>>,
 
     pub(crate) save_in_progress: Cell<bool>,
+    /// The number of tabs with unsaved changes.
+    pub(crate) unsaved_tabs: Cell<u32>,
     pub(crate) save_in_progress_toast: RefCell<Option<adw::Toast>>,
     pub(crate) autosave: Cell<bool>,
     pub(crate) autosave_interval_secs: Cell<u32>,
     pub(crate) righthanded: Cell<bool>,
+    pub(crate) toolbar_placement: Cell<u32>,
+    pub(crate) compact_toolbar: Cell<bool>,
+    pub(crate) tablet_mode: Cell<u32>,
+    /// Whether the last pointer input came from a touchscreen, used by the automatic tablet mode.
+    pub(crate) touchscreen_primary: Cell<bool>,
     pub(crate) block_pinch_zoom: Cell<bool>,
     pub(crate) respect_borders: Cell<bool>,
     pub(crate) touch_drawing: Cell<bool>,
     pub(crate) focus_mode: Cell<bool>,
+    /// Whether the window was fullscreened and the sidebar was shown before entering focus mode.
+    pub(crate) focus_mode_restore: Cell<(bool, bool)>,
     pub(crate) close_in_progress: Cell<bool>,
+    /// Whether the window is a quick note window, which does not save its state to the settings.
+    pub(crate) quick_note: Cell<bool>,
 
     #[template_child]
     pub(crate) main_header: TemplateChild<RnMainHeader>,
     #[template_child]
     pub(crate) split_view: TemplateChild<adw::OverlaySplitView>,
     #[template_child]
+    pub(crate) toolbar_view: TemplateChild<adw::ToolbarView>,
+    #[template_child]
     pub(crate) sidebar: TemplateChild<RnSidebar>,
     #[template_child]
     pub(crate) tabbar: TemplateChild<adw::TabBar>,
@@ -47,20 +62,29 @@ impl Default for RnAppWindow {
             drawing_pad_controller: RefCell::new(None),
             autosave_source_id: RefCell::new(None),
             periodic_configsave_source_id: RefCell::new(None),
+            document_palette_source_id: RefCell::new(None),
 
             save_in_progress: Cell::new(false),
+            unsaved_tabs: Cell::new(0),
             save_in_progress_toast: RefCell::new(None),
             autosave: Cell::new(true),
             autosave_interval_secs: Cell::new(super::RnAppWindow::AUTOSAVE_INTERVAL_DEFAULT),
             righthanded: Cell::new(true),
+            toolbar_placement: Cell::new(super::RnAppWindow::TOOLBAR_PLACEMENT_AUTO),
+            compact_toolbar: Cell::new(false),
+            tablet_mode: Cell::new(super::RnAppWindow::TABLET_MODE_AUTO),
+            touchscreen_primary: Cell::new(false),
             block_pinch_zoom: Cell::new(false),
             respect_borders: Cell::new(false),
             touch_drawing: Cell::new(false),
             focus_mode: Cell::new(false),
+            focus_mode_restore: Cell::new((false, false)),
             close_in_progress: Cell::new(false),
+            quick_note: Cell::new(false),
 
             main_header: TemplateChild::<RnMainHeader>::default(),
             split_view: TemplateChild::<adw::OverlaySplitView>::default(),
+            toolbar_view: TemplateChild::<adw::ToolbarView>::default(),
             sidebar: TemplateChild::<RnSidebar>::default(),
             tabbar: TemplateChild::<adw::TabBar>::default(),
             overlays: TemplateChild::<RnOverlays>::default(),
@@ -107,6 +131,16 @@ impl ObjectImpl for RnAppWindow {
         self.setup_input();
         self.setup_split_view();
         self.setup_tabbar();
+
+        // Switching windows counts as using their active tab for the recent tabs
+        obj.connect_is_active_notify(|appwindow| {
+            if !appwindow.is_active() {
+                return;
+            }
+            if let Some(tab_page) = appwindow.overlays().tabview().selected_page() {
+                appwindow.app().record_recent_tab(&tab_page);
+            }
+        });
     }
 
     fn dispose(&self) {
@@ -122,6 +156,10 @@ impl ObjectImpl for RnAppWindow {
                 glib::ParamSpecBoolean::builder("save-in-progress")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecUInt::builder("unsaved-tabs")
+                    .default_value(0)
+                    .read_only()
+                    .build(),
                 glib::ParamSpecBoolean::builder("autosave")
                     .default_value(false)
                     .build(),
@@ -133,6 +171,19 @@ impl ObjectImpl for RnAppWindow {
                 glib::ParamSpecBoolean::builder("righthanded")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecUInt::builder("toolbar-placement")
+                    .minimum(super::RnAppWindow::TOOLBAR_PLACEMENT_AUTO)
+                    .maximum(super::RnAppWindow::TOOLBAR_PLACEMENT_RIGHT)
+                    .default_value(super::RnAppWindow::TOOLBAR_PLACEMENT_AUTO)
+                    .build(),
+                glib::ParamSpecBoolean::builder("compact-toolbar")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecUInt::builder("tablet-mode")
+                    .minimum(super::RnAppWindow::TABLET_MODE_AUTO)
+                    .maximum(super::RnAppWindow::TABLET_MODE_ON)
+                    .default_value(super::RnAppWindow::TABLET_MODE_AUTO)
+                    .build(),
                 glib::ParamSpecBoolean::builder("block-pinch-zoom")
                     .default_value(false)
                     .build(),
@@ -153,9 +204,13 @@ impl ObjectImpl for RnAppWindow {
     fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
         match pspec.name() {
             "save-in-progress" => self.save_in_progress.get().to_value(),
+            "unsaved-tabs" => self.unsaved_tabs.get().to_value(),
             "autosave" => self.autosave.get().to_value(),
             "autosave-interval-secs" => self.autosave_interval_secs.get().to_value(),
             "righthanded" => self.righthanded.get().to_value(),
+            "toolbar-placement" => self.toolbar_placement.get().to_value(),
+            "compact-toolbar" => self.compact_toolbar.get().to_value(),
+            "tablet-mode" => self.tablet_mode.get().to_value(),
             "block-pinch-zoom" => self.block_pinch_zoom.get().to_value(),
             "respect-borders" => self.respect_borders.get().to_value(),
             "touch-drawing" => self.touch_drawing.get().to_value(),
@@ -204,7 +259,37 @@ impl ObjectImpl for RnAppWindow {
 
                 self.righthanded.replace(righthanded);
 
-                self.handle_righthanded_property(righthanded);
+                self.update_toolbar_placement();
+            }
+            "toolbar-placement" => {
+                let toolbar_placement = value
+                    .get::<u32>()
+                    .expect("The value needs to be of type `u32`");
+
+                self.toolbar_placement.replace(toolbar_placement);
+
+                self.update_toolbar_placement();
+            }
+            "compact-toolbar" => {
+                let compact_toolbar: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                self.compact_toolbar.replace(compact_toolbar);
+
+                if compact_toolbar {
+                    self.overlays.add_css_class("compact_toolbar");
+                } else {
+                    self.overlays.remove_css_class("compact_toolbar");
+                }
+                self.overlays.penpicker().set_compact(compact_toolbar);
+            }
+            "tablet-mode" => {
+                let tablet_mode = value
+                    .get::<u32>()
+                    .expect("The value needs to be of type `u32`");
+
+                self.tablet_mode.replace(tablet_mode);
+
+                self.update_tablet_mode();
             }
             "block-pinch-zoom" => {
                 let block_pinch_zoom: bool =
@@ -223,11 +308,11 @@ impl ObjectImpl for RnAppWindow {
             }
             "focus-mode" => {
                 let focus_mode: bool = value.get().expect("The value needs to be of type `bool`");
-                self.focus_mode.replace(focus_mode);
+                if self.focus_mode.replace(focus_mode) == focus_mode {
+                    return;
+                }
 
-                self.overlays.penpicker().set_visible(!focus_mode);
-                self.overlays.colorpicker().set_visible(!focus_mode);
-                self.overlays.sidebar_box().set_visible(!focus_mode);
+                self.handle_focus_mode_property(focus_mode);
             }
             _ => unimplemented!(),
         }
@@ -370,6 +455,132 @@ impl RnAppWindow {
         obj.add_controller(drawing_pad_controller.clone());
         self.drawing_pad_controller
             .replace(Some(drawing_pad_controller));
+
+        // Track whether a touchscreen or a mouse was used last for the automatic tablet mode.
+        // Stylus input is ignored, because it is used together with both.
+        let pointer_source_controller = EventControllerLegacy::builder()
+            .name("pointer_source_controller")
+            .propagation_phase(PropagationPhase::Capture)
+            .build();
+        pointer_source_controller.connect_event(clone!(
+            #[weak(rename_to=appwindow)]
+            obj,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, event| {
+                if !matches!(
+                    event.event_type(),
+                    gdk::EventType::ButtonPress | gdk::EventType::TouchBegin
+                ) {
+                    return glib::Propagation::Proceed;
+                }
+                let touchscreen_primary = match event.device().map(|device| device.source()) {
+                    Some(gdk::InputSource::Touchscreen) => true,
+                    Some(gdk::InputSource::Mouse | gdk::InputSource::Touchpad) => false,
+                    _ => return glib::Propagation::Proceed,
+                };
+                let imp = appwindow.imp();
+                if imp.touchscreen_primary.get() != touchscreen_primary {
+                    imp.touchscreen_primary.set(touchscreen_primary);
+                    imp.update_tablet_mode();
+                }
+                glib::Propagation::Proceed
+            }
+        ));
+        obj.add_controller(pointer_source_controller);
+
+        // Show the floating button to bring the headerbar back while it is hidden
+        self.toolbar_view.connect_reveal_top_bars_notify(clone!(
+            #[weak(rename_to=appwindow)]
+            obj,
+            move |toolbar_view| {
+                appwindow
+                    .overlays()
+                    .reveal_headerbar_button()
+                    .set_visible(!toolbar_view.reveals_top_bars() && !appwindow.focus_mode());
+            }
+        ));
+
+        // Reveal the pen picker in focus mode when the pointer approaches the bottom edge
+        let focus_mode_motion_controller = EventControllerMotion::builder()
+            .name("focus_mode_motion_controller")
+            .propagation_phase(PropagationPhase::Capture)
+            .build();
+        focus_mode_motion_controller.connect_motion(clone!(
+            #[weak(rename_to=appwindow)]
+            obj,
+            move |_, _, y| {
+                if !appwindow.focus_mode() {
+                    return;
+                }
+                let overlays = appwindow.overlays();
+                let near_bottom =
+                    y > overlays.height() as f64 - super::RnAppWindow::FOCUS_MODE_REVEAL_DISTANCE;
+                overlays.penpicker_revealer().set_reveal_child(near_bottom);
+            }
+        ));
+        focus_mode_motion_controller.connect_leave(clone!(
+            #[weak(rename_to=appwindow)]
+            obj,
+            move |_| {
+                if appwindow.focus_mode() {
+                    appwindow
+                        .overlays()
+                        .penpicker_revealer()
+                        .set_reveal_child(false);
+                }
+            }
+        ));
+        self.overlays.add_controller(focus_mode_motion_controller);
+    }
+
+    /// Hide the headerbar, the sidebar and the toolbars for writing without distractions.
+    ///
+    /// The window is made fullscreen, and the previous state is restored when leaving focus mode.
+    fn handle_focus_mode_property(&self, focus_mode: bool) {
+        let obj = self.obj();
+
+        if focus_mode {
+            self.focus_mode_restore
+                .set((obj.is_fullscreen(), self.split_view.shows_sidebar()));
+            self.split_view.set_show_sidebar(false);
+            obj.fullscreen();
+        } else {
+            let (fullscreened, show_sidebar) = self.focus_mode_restore.get();
+            self.split_view.set_show_sidebar(show_sidebar);
+            if !fullscreened {
+                obj.unfullscreen();
+            }
+        }
+        self.toolbar_view.set_reveal_top_bars(!focus_mode);
+        self.tabbar.set_visible(!focus_mode);
+        self.overlays
+            .penpicker_revealer()
+            .set_reveal_child(!focus_mode);
+        self.overlays.colorpicker().set_visible(!focus_mode);
+        self.overlays.sidebar_box().set_visible(!focus_mode);
+    }
+
+    /// Apply the tablet mode with larger buttons and a headerbar that can be hidden.
+    fn update_tablet_mode(&self) {
+        let obj = self.obj();
+        let active = match self.tablet_mode.get() {
+            super::RnAppWindow::TABLET_MODE_ON => true,
+            super::RnAppWindow::TABLET_MODE_OFF => false,
+            _ => self.touchscreen_primary.get(),
+        };
+
+        if active {
+            obj.add_css_class("tablet_mode");
+        } else {
+            obj.remove_css_class("tablet_mode");
+            if !self.focus_mode.get() {
+                self.toolbar_view.set_reveal_top_bars(true);
+            }
+        }
+        obj.main_header()
+            .collapse_headerbar_button()
+            .set_visible(active);
     }
 
     fn setup_tabbar(&self) {
@@ -494,10 +705,23 @@ impl RnAppWindow {
         ));
     }
 
-    fn handle_righthanded_property(&self, righthanded: bool) {
+    /// Place the sidebar and the toolbar on the side configured by the toolbar placement.
+    ///
+    /// With automatic placement they are placed opposite of the writing hand,
+    /// so that the hand does not cover them. Popovers always open towards the canvas.
+    fn update_toolbar_placement(&self) {
+        let toolbar_start = match self.toolbar_placement.get() {
+            super::RnAppWindow::TOOLBAR_PLACEMENT_LEFT => true,
+            super::RnAppWindow::TOOLBAR_PLACEMENT_RIGHT => false,
+            _ => self.righthanded.get(),
+        };
+        self.handle_toolbar_side(toolbar_start);
+    }
+
+    fn handle_toolbar_side(&self, toolbar_start: bool) {
         let obj = self.obj();
 
-        if righthanded {
+        if toolbar_start {
             obj.split_view().set_sidebar_position(PackType::Start);
             obj.main_header()
                 .left_sidebar_reveal_toggle()
@@ -624,6 +848,46 @@ impl RnAppWindow {
                 .tools_page()
                 .verticalspace_menubutton()
                 .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .measure_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .stamp_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .fill_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .perspective_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .redaction_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .connector_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .selector_page()
+                .selection_transform_menubutton()
+                .set_direction(ArrowType::Right);
+            obj.overlays()
+                .penssidebar()
+                .selector_page()
+                .selection_opacity_menubutton()
+                .set_direction(ArrowType::Right);
         } else {
             obj.split_view().set_sidebar_position(PackType::End);
             obj.main_header()
@@ -751,6 +1015,46 @@ impl RnAppWindow {
                 .tools_page()
                 .verticalspace_menubutton()
                 .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .measure_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .stamp_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .fill_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .perspective_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .redaction_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .tools_page()
+                .connector_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .selector_page()
+                .selection_transform_menubutton()
+                .set_direction(ArrowType::Left);
+            obj.overlays()
+                .penssidebar()
+                .selector_page()
+                .selection_opacity_menubutton()
+                .set_direction(ArrowType::Left);
         }
     }
 }
@@ -2,6 +2,7 @@
 mod actions;
 mod appsettings;
 mod imp;
+mod quicknote;
 
 // Imports
 use crate::{
@@ -10,14 +11,17 @@ use crate::{
 };
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
-use gtk4::{gdk, gio, glib, Application, IconTheme};
+use gtk4::{gdk, gio, glib, glib::clone, Application, IconTheme, UriLauncher};
+use rnote_compose::penevent::PenProgress;
 use rnote_compose::Color;
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::fileformats::rnoteformat::encryption;
 use rnote_engine::pens::pensconfig::brushconfig::BrushStyle;
 use rnote_engine::pens::pensconfig::shaperconfig::ShaperStyle;
 use rnote_engine::pens::PenStyle;
 use rnote_engine::{engine::EngineTask, WidgetFlags};
 use std::path::Path;
+use std::time::Duration;
 use tracing::{error, warn};
 
 glib::wrapper! {
@@ -30,6 +34,18 @@ glib::wrapper! {
 impl RnAppWindow {
     const AUTOSAVE_INTERVAL_DEFAULT: u32 = 30;
     const PERIODIC_CONFIGSAVE_INTERVAL: u32 = 10;
+    /// The delay after the last change before the document palette is updated.
+    const DOCUMENT_PALETTE_UPDATE_DELAY: Duration = Duration::from_millis(1000);
+    /// The toolbar and sidebar are placed opposite of the writing hand.
+    const TOOLBAR_PLACEMENT_AUTO: u32 = 0;
+    const TOOLBAR_PLACEMENT_LEFT: u32 = 1;
+    const TOOLBAR_PLACEMENT_RIGHT: u32 = 2;
+    /// Tablet mode is active while a touchscreen is the primary pointer.
+    const TABLET_MODE_AUTO: u32 = 0;
+    const TABLET_MODE_OFF: u32 = 1;
+    const TABLET_MODE_ON: u32 = 2;
+    /// The distance to the bottom edge at which the pen picker is revealed in focus mode.
+    const FOCUS_MODE_REVEAL_DISTANCE: f64 = 96.0;
 
     pub(crate) fn new(app: &Application) -> Self {
         glib::Object::builder().property("application", app).build()
@@ -108,6 +124,10 @@ impl RnAppWindow {
         self.imp().main_header.get()
     }
 
+    pub(crate) fn toolbar_view(&self) -> adw::ToolbarView {
+        self.imp().toolbar_view.get()
+    }
+
     pub(crate) fn split_view(&self) -> adw::OverlaySplitView {
         self.imp().split_view.get()
     }
@@ -199,12 +219,49 @@ impl RnAppWindow {
         self.destroy();
     }
 
+    /// Update the document palette of the canvas after a short delay,
+    /// so that the strokes are not rescanned on every change.
+    ///
+    /// The update waits until the remaining strokes of a lazily loaded document are loaded.
+    fn update_document_palette_delayed(&self, canvas: &RnCanvas) {
+        if let Some(id) = self.imp().document_palette_source_id.take() {
+            id.remove();
+        }
+        let id = glib::timeout_add_local_once(
+            Self::DOCUMENT_PALETTE_UPDATE_DELAY,
+            clone!(
+                #[weak(rename_to=appwindow)]
+                self,
+                #[weak]
+                canvas,
+                move || {
+                    appwindow.imp().document_palette_source_id.take();
+                    if canvas.engine_ref().store.has_pending_strokes() {
+                        appwindow.update_document_palette_delayed(&canvas);
+                        return;
+                    }
+                    if canvas.engine_mut().update_document_palette()
+                        && canvas == appwindow.active_tab_wrapper().canvas()
+                    {
+                        let palette = canvas.engine_ref().document.palette.clone();
+                        appwindow
+                            .overlays()
+                            .colorpicker()
+                            .set_document_palette(&palette);
+                    }
+                }
+            ),
+        );
+        self.imp().document_palette_source_id.replace(Some(id));
+    }
+
     // Returns true if the flags indicate that any loop that handles the flags should be quit. (usually an async event loop)
     pub(crate) fn handle_widget_flags(&self, widget_flags: WidgetFlags, canvas: &RnCanvas) {
         //debug!("handling widget flags: '{widget_flags:?}'");
 
         if widget_flags.redraw {
             canvas.queue_draw();
+            canvas.broadcast_invalidate_frame();
         }
         if widget_flags.resize {
             canvas.queue_resize();
@@ -215,6 +272,19 @@ impl RnAppWindow {
         if widget_flags.store_modified {
             canvas.set_unsaved_changes(true);
             canvas.set_empty(false);
+            canvas.collab_sync();
+            // Changes while drawing are followed by another one when the stroke is finished
+            let stroke_finished =
+                canvas.engine_ref().penholder.current_pen_progress() == PenProgress::Idle;
+            if stroke_finished {
+                self.update_document_palette_delayed(canvas);
+            }
+            if canvas == &self.active_tab_wrapper().canvas() {
+                if stroke_finished {
+                    self.sidebar().refresh_outline_delayed(canvas);
+                }
+                self.overlays().minimap().regenerate_image();
+            }
         }
         if widget_flags.view_modified {
             let widget_size = canvas.widget_size();
@@ -222,6 +292,9 @@ impl RnAppWindow {
             let offset = canvas.engine_ref().camera.offset();
             // Keep the adjustments configuration in sync
             canvas.configure_adjustments(widget_size, offset_mins_maxs, offset);
+            self.main_header()
+                .canvasmenu()
+                .refresh_rotation_reset_label(canvas.engine_ref().camera.rotation());
             canvas.queue_resize();
         }
         if widget_flags.zoomed_temporarily {
@@ -260,6 +333,45 @@ impl RnAppWindow {
         if let Some(enable_text_preprocessing) = widget_flags.enable_text_preprocessing {
             canvas.set_text_preprocessing(enable_text_preprocessing);
         }
+        if widget_flags.open_radial_menu {
+            self.active_tab_wrapper().popup_radial_menu(self);
+        }
+        if widget_flags.open_context_menu {
+            self.active_tab_wrapper().popup_contextmenu_at_pointer();
+        }
+        if widget_flags.redraw || widget_flags.refresh_ui || widget_flags.store_modified {
+            // The selection or the copied style might have changed
+            if let Some(action) = self
+                .lookup_action("selection-paste-style")
+                .and_downcast::<gio::SimpleAction>()
+            {
+                action.set_enabled(canvas.engine_ref().can_paste_style_to_selection());
+            }
+        }
+        if let Some(uri) = widget_flags.open_uri {
+            let is_web_address =
+                url::Url::parse(&uri).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+            if is_web_address {
+                self.launch_uri(uri);
+            } else {
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to=appwindow)]
+                    self,
+                    async move {
+                        dialogs::dialog_open_uri(&appwindow, uri).await;
+                    }
+                ));
+            }
+        }
+    }
+
+    /// Open the URI with the default application for it.
+    pub(crate) fn launch_uri(&self, uri: String) {
+        UriLauncher::new(&uri).launch(Some(self), gio::Cancellable::NONE, move |res| {
+            if let Err(e) = res {
+                error!("Launching link URI '{uri}' failed, Err: {e:?}");
+            }
+        });
     }
 
     /// Get the active (selected) tab page.
@@ -361,20 +473,60 @@ impl RnAppWindow {
     }
 
     pub(crate) fn tabs_any_unsaved_changes(&self) -> bool {
-        self.overlays()
-            .tabview()
-            .pages()
-            .snapshot()
+        self.tabs_snapshot()
             .iter()
-            .map(|o| {
-                o.downcast_ref::<adw::TabPage>()
-                    .unwrap()
-                    .child()
-                    .downcast_ref::<RnCanvasWrapper>()
-                    .unwrap()
-                    .canvas()
-            })
-            .any(|c| c.unsaved_changes())
+            .any(|tab| Self::tab_canvas(tab).unsaved_changes())
+    }
+
+    fn tab_canvas(tab_page: &adw::TabPage) -> RnCanvas {
+        tab_page
+            .child()
+            .downcast::<RnCanvasWrapper>()
+            .unwrap()
+            .canvas()
+    }
+
+    /// Recount the tabs with unsaved changes.
+    ///
+    /// Needs to be called when the unsaved changes of a tab change, or when tabs are attached or detached.
+    pub(crate) fn refresh_unsaved_tabs(&self) {
+        let unsaved_tabs = self
+            .tabs_snapshot()
+            .iter()
+            .filter(|tab| Self::tab_canvas(tab).unsaved_changes())
+            .count() as u32;
+        if self.imp().unsaved_tabs.replace(unsaved_tabs) != unsaved_tabs {
+            self.notify("unsaved-tabs");
+        }
+    }
+
+    /// Save the documents of all tabs with unsaved changes.
+    ///
+    /// Tabs whose document was never saved are selected one after another and a save dialog is presented for them.
+    pub(crate) async fn save_all_tabs(&self) {
+        for tab_page in self.tabs_snapshot() {
+            let canvas = Self::tab_canvas(&tab_page);
+            if !canvas.unsaved_changes() {
+                continue;
+            }
+
+            if let Some(output_file) = canvas.output_file() {
+                self.overlays().progressbar_start_pulsing();
+
+                if let Err(e) = canvas.save_document_to_file(&output_file).await {
+                    error!("Saving document failed while saving all documents, Err: `{e:?}`");
+                    canvas.set_output_file(None);
+                    self.overlays()
+                        .dispatch_toast_error(&gettext("Saving document failed"));
+                    self.overlays().progressbar_abort();
+                } else {
+                    self.overlays().progressbar_finish();
+                }
+            } else {
+                self.overlays().tabview().set_selected_page(&tab_page);
+                dialogs::export::dialog_save_doc_as(self, &canvas).await;
+            }
+        }
     }
 
     pub(crate) fn tabs_any_saves_in_progress(&self) -> bool {
@@ -472,13 +624,21 @@ impl RnAppWindow {
         let title = canvas.doc_title_display();
         let subtitle = canvas.doc_folderpath_display();
 
+        // Mark the window title as modified, like the tab and the header title
+        let modified_marker = if canvas.unsaved_changes() { "• " } else { "" };
         self.set_title(Some(
-            &(title.clone() + " - " + config::APP_NAME_CAPITALIZED),
+            &(String::from(modified_marker) + &title + " - " + config::APP_NAME_CAPITALIZED),
         ));
 
+        let saving_spinner = self.main_header().main_title_saving_spinner();
+        saving_spinner.set_visible(canvas.save_in_progress());
+        saving_spinner.set_spinning(canvas.save_in_progress());
         self.main_header()
             .main_title_unsaved_indicator()
             .set_visible(canvas.unsaved_changes());
+        self.main_header()
+            .main_title_encrypted_indicator()
+            .set_visible(canvas.encrypted());
         if canvas.unsaved_changes() {
             self.main_header()
                 .main_title()
@@ -497,6 +657,7 @@ impl RnAppWindow {
     ///
     /// When the file is a rnote save file, `rnote_file_new_tab` determines if a new tab is opened,
     /// or if it loads and overwrites the content of the current active one.
+    #[tracing::instrument(skip_all, fields(path = format!("{:?}", input_file.path())))]
     pub(crate) async fn open_file_w_dialogs(
         &self,
         input_file: gio::File,
@@ -552,21 +713,59 @@ impl RnAppWindow {
                         rnote_file_new_tab
                     };
 
+                    let (bytes, _) = input_file.load_bytes_future().await?;
+                    let (bytes, passphrase) = if encryption::is_encrypted(&bytes) {
+                        match dialogs::dialog_unlock_doc(self, bytes.to_vec()).await? {
+                            Some((bytes, passphrase)) => (bytes, Some(passphrase)),
+                            None => return Ok(false),
+                        }
+                    } else {
+                        (bytes.to_vec(), None)
+                    };
+
                     let wrapper = if rnote_file_new_tab {
                         // a new tab for rnote files
                         self.new_canvas_wrapper()
                     } else {
                         self.active_tab_wrapper()
                     };
-                    let (bytes, _) = input_file.load_bytes_future().await?;
-                    let widget_flags = wrapper
+                    let (widget_flags, dropped) = wrapper
                         .canvas()
-                        .load_in_rnote_bytes(bytes.to_vec(), input_file.path())
+                        .load_in_rnote_bytes(bytes, input_file.path(), passphrase)
                         .await?;
                     if rnote_file_new_tab {
                         self.append_wrapper_new_tab(&wrapper);
                     }
                     self.handle_widget_flags(widget_flags, &wrapper.canvas());
+                    if wrapper.canvas().engine_ref().broken_linked_images_count() > 0 {
+                        // Linked image files are only read when the user agrees to it
+                        self.overlays().dispatch_toast_w_button(
+                            &gettext("The document links to image files"),
+                            &gettext("Load Images"),
+                            clone!(
+                                #[weak(rename_to=appwindow)]
+                                self,
+                                #[weak]
+                                wrapper,
+                                move |_| {
+                                    let canvas = wrapper.canvas();
+                                    let (n_failed, widget_flags) =
+                                        canvas.engine_mut().load_linked_images();
+                                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                                    if n_failed > 0 {
+                                        appwindow.overlays().dispatch_toast_text(
+                                            &gettext("Some linked images could not be loaded"),
+                                            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                                        );
+                                    }
+                                }
+                            ),
+                            None,
+                        );
+                    }
+                    if !dropped.is_empty() {
+                        dialogs::dialog_recovered_content(self, &dropped).await;
+                    }
                     true
                 }
             }
@@ -581,8 +780,23 @@ impl RnAppWindow {
             FileType::BitmapImageFile => {
                 let canvas = self.active_tab_wrapper().canvas();
                 let (bytes, _) = input_file.load_bytes_future().await?;
+                let linked_path = if canvas
+                    .engine_ref()
+                    .import_prefs
+                    .bitmapimage_import_prefs
+                    .link_images
+                {
+                    input_file.path()
+                } else {
+                    None
+                };
                 canvas
-                    .load_in_bitmapimage_bytes(bytes.to_vec(), target_pos, self.respect_borders())
+                    .load_in_bitmapimage_bytes(
+                        bytes.to_vec(),
+                        target_pos,
+                        self.respect_borders(),
+                        linked_path,
+                    )
                     .await?;
                 true
             }
@@ -597,6 +811,14 @@ impl RnAppWindow {
                 }
                 file_imported
             }
+            FileType::EnexFile => {
+                // a new tab for enex file import
+                let wrapper = self.new_canvas_wrapper();
+                let (bytes, _) = input_file.load_bytes_future().await?;
+                wrapper.canvas().load_in_enex_bytes(bytes.to_vec()).await?;
+                self.append_wrapper_new_tab(&wrapper);
+                true
+            }
             FileType::PdfFile => {
                 let canvas = self.active_tab_wrapper().canvas();
                 dialogs::import::dialog_import_pdf_w_prefs(self, &canvas, input_file, target_pos)
@@ -605,7 +827,11 @@ impl RnAppWindow {
             FileType::PlaintextFile => {
                 let canvas = self.active_tab_wrapper().canvas();
                 let (bytes, _) = input_file.load_bytes_future().await?;
-                canvas.load_in_text(String::from_utf8(bytes.to_vec())?, target_pos)?;
+                let markdown = input_file
+                    .path()
+                    .and_then(|path| path.extension().map(|ext| ext.to_ascii_lowercase()))
+                    .is_some_and(|ext| ext == "md" || ext == "markdown");
+                canvas.load_in_text(String::from_utf8(bytes.to_vec())?, markdown, target_pos)?;
                 true
             }
             FileType::Folder => {
@@ -625,6 +851,99 @@ impl RnAppWindow {
         Ok(file_imported)
     }
 
+    /// Open the daily note of today, creating it from the configured template when it does not exist yet.
+    pub(crate) async fn open_daily_note(&self) {
+        let Some(app_settings) = self.app().app_settings() else {
+            return;
+        };
+        let dir = app_settings.string("daily-notes-dir");
+        if dir.trim().is_empty() {
+            self.overlays().dispatch_toast_text(
+                &gettext("Select a folder for the daily notes in the settings first"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            return;
+        }
+        let template = app_settings.string("daily-notes-template");
+        let name_format = app_settings.string("daily-notes-name-format");
+
+        match self
+            .create_daily_note(Path::new(dir.trim()), template.trim(), &name_format)
+            .await
+        {
+            Ok(file) => self.open_file_w_dialogs(file, None, true).await,
+            Err(e) => {
+                error!("Creating the daily note failed, Err: {e:?}");
+                self.overlays()
+                    .dispatch_toast_error(&gettext("Creating the daily note failed"));
+            }
+        }
+    }
+
+    /// Creates the daily note of today in the given folder if it does not exist yet, and returns its file.
+    ///
+    /// The note is a copy of the template, or a new document when no template is set.
+    async fn create_daily_note(
+        &self,
+        dir: &Path,
+        template: &str,
+        name_format: &str,
+    ) -> anyhow::Result<gio::File> {
+        let name_format = if name_format.trim().is_empty() {
+            "%Y-%m-%d"
+        } else {
+            name_format.trim()
+        };
+        let name = glib::DateTime::now_local()?.format(name_format)?;
+        // The note must stay inside the folder, so separators in the formatted name are replaced
+        let name = name.trim().replace(['/', '\\'], "-");
+        if name.is_empty() || name.chars().all(|c| c == '.') {
+            return Err(anyhow::anyhow!(
+                "The daily note name `{name}` formatted with `{name_format}` is not a valid file name."
+            ));
+        }
+        let file_path = dir.join(format!("{name}.rnote"));
+        let file = gio::File::for_path(&file_path);
+        std::fs::create_dir_all(dir)?;
+        // Creating the file fails when it already exists, so an existing note is never overwritten
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+        {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Ok(file),
+            Err(e) => return Err(e.into()),
+        }
+
+        let result = async {
+            if template.is_empty() {
+                let active_canvas = self.active_tab_wrapper().canvas();
+                let canvas = if active_canvas.empty() && active_canvas.output_file().is_none() {
+                    active_canvas
+                } else {
+                    let wrapper = self.new_canvas_wrapper();
+                    self.append_wrapper_new_tab(&wrapper);
+                    wrapper.canvas()
+                };
+                canvas.save_document_to_file(&file).await?;
+            } else {
+                let (bytes, _) = gio::File::for_path(template).load_bytes_future().await?;
+                crate::utils::create_replace_file_future(bytes.to_vec(), &file).await?;
+            }
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            // Don't leave an empty note behind that would be opened the next time
+            if let Err(remove_e) = std::fs::remove_file(&file_path) {
+                error!("Removing the incomplete daily note failed, Err: {remove_e:?}");
+            }
+            return Err(e);
+        }
+        Ok(file)
+    }
+
     /// Refresh the UI from the engine state from the given tab page.
     pub(crate) fn refresh_ui_from_engine(&self, active_tab: &RnCanvasWrapper) {
         let canvas = active_tab.canvas();
@@ -634,9 +953,13 @@ impl RnAppWindow {
         let pen_sounds = canvas.engine_ref().pen_sounds();
         let doc_format = canvas.engine_ref().document.format;
         let total_zoom = canvas.engine_ref().camera.total_zoom();
+        let rotation = canvas.engine_ref().camera.rotation();
         let snap_positions = canvas.engine_ref().document.snap_positions;
         let can_undo = canvas.engine_ref().can_undo();
         let can_redo = canvas.engine_ref().can_redo();
+        let text_is_being_edited = canvas.engine_ref().text_is_being_edited();
+        let document_palette = canvas.engine_ref().document.palette.clone();
+        let bookmarks = canvas.engine_ref().document.bookmarks.clone();
 
         self.overlays()
             .penpicker()
@@ -649,6 +972,20 @@ impl RnAppWindow {
         self.main_header()
             .canvasmenu()
             .refresh_zoom_reset_label(total_zoom);
+        self.main_header()
+            .canvasmenu()
+            .refresh_rotation_reset_label(rotation);
+        self.main_header()
+            .canvasmenu()
+            .refresh_bookmarks(&bookmarks);
+        self.overlays().penpresetsbar().refresh_ui(active_tab);
+        self.sidebar().refresh_outline(&canvas);
+        self.overlays()
+            .colorpicker()
+            .set_document_palette(&document_palette);
+        self.overlays()
+            .text_format_revealer()
+            .set_reveal_child(pen_style == PenStyle::Typewriter && text_is_being_edited);
 
         // we change the state through the actions, because they themselves hold state.
         // (for example needed to display ticks in menus for boolean actions)
@@ -745,6 +1082,18 @@ impl RnAppWindow {
                             .colorpicker()
                             .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
                     }
+                    BrushStyle::Custom => {
+                        let stroke_color = canvas
+                            .engine_ref()
+                            .pens_config
+                            .brush_config
+                            .custom_options
+                            .stroke_color
+                            .unwrap_or(Color::TRANSPARENT);
+                        self.overlays()
+                            .colorpicker()
+                            .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
+                    }
                 }
             }
             PenStyle::Shaper => {
@@ -894,10 +1243,16 @@ impl RnAppWindow {
             prev_canvas.engine_ref().extract_engine_config(),
             crate::env::pkg_data_dir().ok(),
         );
-        // The visual-debug field is not saved in the config, but we want to sync its value between tabs.
+        // The visual-debug, debug-hud and color vision filter fields are not saved in the config, but we want to sync their values between tabs.
         widget_flags |= active_canvas
             .engine_mut()
             .set_visual_debug(prev_canvas.engine_mut().visual_debug());
+        widget_flags |= active_canvas
+            .engine_mut()
+            .set_debug_hud(prev_canvas.engine_mut().debug_hud());
+        widget_flags |= active_canvas
+            .engine_mut()
+            .set_color_vision_filter(prev_canvas.engine_mut().color_vision_filter());
 
         self.handle_widget_flags(widget_flags, &active_canvas);
     }
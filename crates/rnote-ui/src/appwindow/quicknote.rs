@@ -0,0 +1,151 @@
+// Imports
+use super::RnAppWindow;
+use crate::RnCanvasWrapper;
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk4::{gio, subclass::prelude::*, Button};
+use rnote_engine::document::Layout;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::Engine;
+use std::path::Path;
+use tracing::error;
+
+impl RnAppWindow {
+    /// The default width and height of quick note windows.
+    const QUICK_NOTE_SIZE: (i32, i32) = (480, 640);
+
+    pub(crate) fn is_quick_note(&self) -> bool {
+        self.imp().quick_note.get()
+    }
+
+    /// Turns the window into a small quick note window with a single page document.
+    ///
+    /// Must be called after `init()`. Keeping the window above others can't be requested in GTK4,
+    /// this is left to the window manager.
+    pub(crate) fn setup_quick_note(&self) {
+        let imp = self.imp();
+        imp.quick_note.set(true);
+
+        // The engine config of the quick note should not end up in the settings
+        if let Some(source_id) = imp.periodic_configsave_source_id.take() {
+            source_id.remove();
+        }
+        gio::Settings::unbind(&self.split_view(), "show-sidebar");
+        self.split_view().set_show_sidebar(false);
+
+        self.unmaximize();
+        self.set_default_size(Self::QUICK_NOTE_SIZE.0, Self::QUICK_NOTE_SIZE.1);
+        self.set_title(Some(&gettext("Quick Note")));
+
+        let canvas = self.active_tab_wrapper().canvas();
+        let widget_flags = canvas.engine_mut().set_doc_layout(Layout::FixedSize);
+        self.handle_widget_flags(widget_flags, &canvas);
+
+        let append_button = Button::builder()
+            .label(gettext("Append to Inbox"))
+            .action_name("win.quick-note-append")
+            .tooltip_text(gettext(
+                "Append the note to the inbox document and clear it",
+            ))
+            .build();
+        append_button.add_css_class("suggested-action");
+        self.main_header().headerbar().pack_end(&append_button);
+    }
+
+    /// Appends the document of the active tab to the inbox document that is set in the settings, and clears it.
+    pub(crate) async fn append_quick_note_to_inbox(&self) {
+        let inbox = self
+            .app()
+            .app_settings()
+            .map(|app_settings| app_settings.string("quick-note-inbox"))
+            .unwrap_or_default();
+        if inbox.trim().is_empty() {
+            self.overlays().dispatch_toast_text(
+                &gettext("Select an inbox document in the settings first"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            return;
+        }
+        let canvas = self.active_tab_wrapper().canvas();
+        canvas.load_pending_strokes();
+        if canvas.engine_ref().bounds_w_content_extended().is_none() {
+            self.overlays().dispatch_toast_text(
+                &gettext("The note is empty"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            return;
+        }
+        let snapshot = canvas.engine_ref().take_snapshot();
+
+        self.overlays().progressbar_start_pulsing();
+        match self
+            .append_snapshot_to_inbox(Path::new(inbox.trim()), snapshot)
+            .await
+        {
+            Ok(()) => {
+                let widget_flags = canvas.engine_mut().clear();
+                self.handle_widget_flags(widget_flags, &canvas);
+                canvas.set_unsaved_changes(false);
+                canvas.set_empty(true);
+                self.overlays().progressbar_finish();
+                self.overlays().dispatch_toast_text(
+                    &gettext("Appended to the inbox"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+            Err(e) => {
+                error!("Appending the quick note to the inbox failed, Err: {e:?}");
+                self.overlays()
+                    .dispatch_toast_error(&gettext("Appending to the inbox failed"));
+                self.overlays().progressbar_abort();
+            }
+        }
+    }
+
+    /// Appends the snapshot below the content of the inbox document, which is created when it does not exist yet.
+    ///
+    /// When the inbox is opened in a tab of any window, it is appended there and the tab is saved,
+    /// so that the content of the tab and the file don't diverge.
+    async fn append_snapshot_to_inbox(
+        &self,
+        inbox_path: &Path,
+        snapshot: EngineSnapshot,
+    ) -> anyhow::Result<()> {
+        for appwindow in self
+            .app()
+            .windows()
+            .into_iter()
+            .filter_map(|w| w.downcast::<RnAppWindow>().ok())
+        {
+            let Some(page) = appwindow.tabs_query_file_opened(inbox_path) else {
+                continue;
+            };
+            let inbox_canvas = page.child().downcast::<RnCanvasWrapper>().unwrap().canvas();
+            let widget_flags = inbox_canvas.engine_mut().append_snapshot(snapshot);
+            appwindow.handle_widget_flags(widget_flags, &inbox_canvas);
+            inbox_canvas.set_unsaved_changes(true);
+            if let Some(output_file) = inbox_canvas.output_file() {
+                inbox_canvas.save_document_to_file(&output_file).await?;
+            }
+            return Ok(());
+        }
+
+        let inbox_file = gio::File::for_path(inbox_path);
+        let mut engine = Engine::default();
+        if inbox_path.exists() {
+            let (bytes, _) = inbox_file.load_bytes_future().await?;
+            let inbox_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes.to_vec()).await?;
+            let _ = engine.load_snapshot(inbox_snapshot);
+            let _ = engine.append_snapshot(snapshot);
+        } else {
+            let _ = engine.load_snapshot(snapshot);
+        }
+        let file_name = inbox_file
+            .basename()
+            .map(|basename| basename.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let bytes = engine.save_as_rnote_bytes(file_name).await??;
+        crate::utils::create_replace_file_future(bytes, &inbox_file).await?;
+        Ok(())
+    }
+}
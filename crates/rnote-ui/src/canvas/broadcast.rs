@@ -0,0 +1,280 @@
+//! Broadcasting the canvas as read-only live stream, to be followed in a browser.
+//!
+//! A minimal Http server serves a page that periodically fetches the content of the viewport as Svg.
+//! Both are only served below a random session key, so that the broadcast can't be found by probing the port.
+
+// Imports
+use super::websocket;
+use super::RnCanvas;
+use gtk4::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// The port that is used by default for broadcasting.
+pub(crate) const BROADCAST_PORT_DEFAULT: u16 = 7625;
+
+/// The interval in milliseconds in which the page fetches a new frame.
+const BROADCAST_FRAME_INTERVAL_MS: u32 = 500;
+/// The minimum interval in which a new frame is rendered, no matter how many viewers request it.
+const BROADCAST_FRAME_MIN_INTERVAL: Duration =
+    Duration::from_millis(BROADCAST_FRAME_INTERVAL_MS as u64);
+/// The maximum number of headers of a request. The size of the request head is limited as well.
+const BROADCAST_REQUEST_HEADERS_MAX: usize = 64;
+
+#[derive(Debug)]
+pub(crate) struct BroadcastServer {
+    service: gio::SocketService,
+    port: u16,
+    session_key: String,
+    /// The last rendered frame, served until the canvas changes.
+    frame: Option<Vec<u8>>,
+    frame_outdated: bool,
+    frame_rendered: Option<Instant>,
+    frame_rendering: bool,
+}
+
+impl RnCanvas {
+    /// Whether the canvas is being broadcast.
+    pub(crate) fn broadcast_active(&self) -> bool {
+        self.imp().broadcast.borrow().is_some()
+    }
+
+    /// The port of the running broadcast.
+    pub(crate) fn broadcast_port(&self) -> Option<u16> {
+        self.imp().broadcast.borrow().as_ref().map(|b| b.port)
+    }
+
+    /// The path of the broadcast page, containing the session key.
+    pub(crate) fn broadcast_path(&self) -> Option<String> {
+        self.imp()
+            .broadcast
+            .borrow()
+            .as_ref()
+            .map(|b| format!("/{}/", b.session_key))
+    }
+
+    /// Starts serving the canvas on the given port.
+    ///
+    /// Only connections from this device are accepted, unless `allow_remote` is set.
+    /// Encrypted documents are not broadcast.
+    pub(crate) fn broadcast_start(&self, port: u16, allow_remote: bool) -> anyhow::Result<()> {
+        self.broadcast_stop();
+        if self.encrypted() {
+            return Err(anyhow::anyhow!("Encrypted documents can't be broadcast"));
+        }
+
+        let service = gio::SocketService::new();
+        if allow_remote {
+            service.add_inet_port(port, None::<&glib::Object>)?;
+        } else {
+            service.add_address(
+                &gio::InetSocketAddress::new(
+                    &gio::InetAddress::new_loopback(gio::SocketFamily::Ipv4),
+                    port,
+                ),
+                gio::SocketType::Stream,
+                gio::SocketProtocol::Tcp,
+                None::<&glib::Object>,
+            )?;
+        }
+        service.connect_incoming(clone!(
+            #[weak(rename_to=canvas)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, connection, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    canvas,
+                    #[strong]
+                    connection,
+                    async move {
+                        if let Err(e) = canvas.broadcast_handle_request(&connection).await {
+                            debug!("Handling broadcast request failed, Err: {e:?}");
+                        }
+                        if let Err(e) = connection.close_future(glib::Priority::DEFAULT).await {
+                            debug!("Closing broadcast connection failed, Err: {e:?}");
+                        }
+                    }
+                ));
+                false
+            }
+        ));
+        service.start();
+
+        self.imp().broadcast.replace(Some(BroadcastServer {
+            service,
+            port,
+            session_key: glib::uuid_string_random().to_string(),
+            frame: None,
+            frame_outdated: true,
+            frame_rendered: None,
+            frame_rendering: false,
+        }));
+        Ok(())
+    }
+
+    pub(crate) fn broadcast_stop(&self) {
+        if let Some(broadcast) = self.imp().broadcast.take() {
+            broadcast.service.stop();
+            broadcast.service.close();
+        }
+    }
+
+    /// Marks the broadcast frame as outdated, needs to be called when the canvas changed.
+    pub(crate) fn broadcast_invalidate_frame(&self) {
+        if let Some(broadcast) = self.imp().broadcast.borrow_mut().as_mut() {
+            broadcast.frame_outdated = true;
+        }
+    }
+
+    /// The current frame of the broadcast.
+    ///
+    /// The last frame is served as long as the canvas did not change, while another frame is being rendered
+    /// or when the last one was rendered too recently.
+    async fn broadcast_frame(&self) -> anyhow::Result<Vec<u8>> {
+        let frame_receiver = {
+            let mut broadcast = self.imp().broadcast.borrow_mut();
+            let Some(broadcast) = broadcast.as_mut() else {
+                return Err(anyhow::anyhow!("Broadcast was stopped"));
+            };
+            if let Some(frame) = &broadcast.frame {
+                let throttled = broadcast
+                    .frame_rendered
+                    .is_some_and(|rendered| rendered.elapsed() < BROADCAST_FRAME_MIN_INTERVAL);
+                if !broadcast.frame_outdated || broadcast.frame_rendering || throttled {
+                    return Ok(frame.clone());
+                }
+            }
+            broadcast.frame_outdated = false;
+            broadcast.frame_rendering = true;
+            broadcast.frame_rendered = Some(Instant::now());
+            self.engine_ref().export_viewport_as_svg_bytes()
+        };
+        let frame = frame_receiver
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|frame| frame);
+
+        if let Some(broadcast) = self.imp().broadcast.borrow_mut().as_mut() {
+            broadcast.frame_rendering = false;
+            match &frame {
+                Ok(frame) => broadcast.frame = Some(frame.clone()),
+                Err(_) => broadcast.frame_outdated = true,
+            }
+        }
+        frame
+    }
+
+    async fn broadcast_handle_request(
+        &self,
+        connection: &gio::SocketConnection,
+    ) -> anyhow::Result<()> {
+        // The headers are not needed, but must be consumed before responding
+        let head = websocket::WebSocketReader::new(connection, websocket::Role::Server)
+            .read_head()
+            .await?;
+        let mut lines = head.split("\r\n").filter(|line| !line.is_empty());
+        let request_line = lines.next().unwrap_or_default();
+        if lines.count() > BROADCAST_REQUEST_HEADERS_MAX {
+            return Err(anyhow::anyhow!("Request has too many headers"));
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts
+            .next()
+            .unwrap_or_default()
+            .split('?')
+            .next()
+            .unwrap_or_default();
+        let Some(session_key) = self
+            .imp()
+            .broadcast
+            .borrow()
+            .as_ref()
+            .map(|b| b.session_key.clone())
+        else {
+            return Ok(());
+        };
+        // Everything is served below the session key, other paths are not found
+        let path = path
+            .strip_prefix('/')
+            .and_then(|path| path.split_once('/'))
+            .and_then(|(key, path)| websocket::keys_equal(key, &session_key).then_some(path));
+
+        let (status, content_type, body) = match (method, path) {
+            // The document might have been encrypted after the broadcast was started
+            (_, Some(_)) if self.encrypted() => (
+                "403 Forbidden",
+                "text/plain; charset=utf-8",
+                b"Forbidden".to_vec(),
+            ),
+            ("GET", Some("")) => (
+                "200 OK",
+                "text/html; charset=utf-8",
+                broadcast_page().into_bytes(),
+            ),
+            ("GET", Some("frame.svg")) => {
+                ("200 OK", "image/svg+xml", self.broadcast_frame().await?)
+            }
+            ("GET", _) => (
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                b"Not Found".to_vec(),
+            ),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain; charset=utf-8",
+                b"Method Not Allowed".to_vec(),
+            ),
+        };
+
+        let mut response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        response.extend(body);
+        connection
+            .output_stream()
+            .write_all_future(response, glib::Priority::DEFAULT)
+            .await
+            .map_err(|(_, e)| e)?;
+        Ok(())
+    }
+}
+
+/// The page that displays the broadcast.
+///
+/// The next frame is loaded in the background and only swapped in when it is complete, to avoid flickering.
+fn broadcast_page() -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Rnote</title>
+<style>
+html, body {{ margin: 0; height: 100%; background: #3d3846; }}
+img {{ display: block; width: 100%; height: 100%; object-fit: contain; }}
+</style>
+</head>
+<body>
+<img id="frame" src="frame.svg" alt="">
+<script>
+const frame = document.getElementById("frame");
+function next() {{
+    const img = new Image();
+    img.onload = () => {{ frame.src = img.src; setTimeout(next, {BROADCAST_FRAME_INTERVAL_MS}); }};
+    img.onerror = () => setTimeout(next, {BROADCAST_FRAME_INTERVAL_MS} * 4);
+    img.src = "frame.svg?t=" + Date.now();
+}}
+setTimeout(next, {BROADCAST_FRAME_INTERVAL_MS});
+</script>
+</body>
+</html>
+"#
+    )
+}
@@ -0,0 +1,375 @@
+//! Experimental collaborative editing, syncing the strokes of the canvas with peers over WebSocket.
+//!
+//! One peer hosts the session and relays the messages between the peers that joined it.
+//! The messages are sent as Json text messages. Peers must know the session key to join.
+
+// Imports
+use super::websocket::{self, Role, WebSocketReader};
+use super::RnCanvas;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gtk4::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use rnote_engine::engine::collab::CollabMessage;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use tracing::{debug, error};
+
+/// The port that is used by default for hosting a session.
+pub(crate) const COLLAB_PORT_DEFAULT: u16 = 7624;
+
+#[derive(Debug, Default)]
+pub(crate) struct CollabConnection {
+    /// Is Some when hosting the session.
+    service: Option<gio::SocketService>,
+    /// The key that peers need to join the session.
+    session_key: String,
+    peers: RefCell<Vec<CollabPeer>>,
+    next_peer_id: Cell<usize>,
+}
+
+#[derive(Debug)]
+struct CollabPeer {
+    id: usize,
+    role: Role,
+    /// Sends encoded frames to the writer task of the connection.
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    /// The ids of the remote peers whose messages arrived over the connection.
+    remote_peers: HashSet<u32>,
+}
+
+impl RnCanvas {
+    /// Whether the canvas is part of a collaboration session.
+    pub(crate) fn collab_active(&self) -> bool {
+        self.imp().collab.borrow().is_some()
+    }
+
+    /// Hosts a collaboration session that peers can join on the given port with the session key.
+    ///
+    /// Only connections from this device are accepted, unless `allow_remote` is set.
+    pub(crate) fn collab_host(
+        &self,
+        port: u16,
+        name: String,
+        session_key: String,
+        allow_remote: bool,
+    ) -> anyhow::Result<()> {
+        self.collab_stop();
+        if session_key.is_empty() {
+            return Err(anyhow::anyhow!("The session key is empty"));
+        }
+
+        let service = gio::SocketService::new();
+        if allow_remote {
+            service.add_inet_port(port, None::<&glib::Object>)?;
+        } else {
+            service.add_address(
+                &gio::InetSocketAddress::new(
+                    &gio::InetAddress::new_loopback(gio::SocketFamily::Ipv4),
+                    port,
+                ),
+                gio::SocketType::Stream,
+                gio::SocketProtocol::Tcp,
+                None::<&glib::Object>,
+            )?;
+        }
+        service.connect_incoming(clone!(
+            #[weak(rename_to=canvas)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, connection, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    canvas,
+                    #[strong]
+                    connection,
+                    async move {
+                        canvas.collab_accept(connection).await;
+                    }
+                ));
+                false
+            }
+        ));
+        service.start();
+
+        let widget_flags = self.engine_mut().start_collab_session(name);
+        self.emit_handle_widget_flags(widget_flags);
+        self.imp().collab.replace(Some(CollabConnection {
+            service: Some(service),
+            session_key,
+            ..Default::default()
+        }));
+        Ok(())
+    }
+
+    /// Performs the handshake with a peer that connected to the hosted session and syncs it.
+    async fn collab_accept(&self, connection: gio::SocketConnection) {
+        let Some(session_key) = self
+            .imp()
+            .collab
+            .borrow()
+            .as_ref()
+            .map(|collab| collab.session_key.clone())
+        else {
+            return;
+        };
+        let mut reader = WebSocketReader::new(&connection, Role::Server);
+        if let Err(e) = websocket::handshake_server(&connection, &mut reader, &session_key).await {
+            debug!("Handshake with collaboration peer failed, Err: {e:?}");
+            let _ = connection.close_future(glib::Priority::DEFAULT).await;
+            return;
+        }
+        // The session might have ended during the handshake
+        if !self.collab_active() {
+            return;
+        }
+        let Some(peer_id) = self.collab_add_peer(connection, reader, Role::Server) else {
+            return;
+        };
+        // Sync the peer that joined with everything that happened so far
+        let history = self.engine_mut().collab_history();
+        for message in history.iter() {
+            self.collab_send(message, |id| id == peer_id);
+        }
+    }
+
+    /// Joins the collaboration session hosted at the given address.
+    pub(crate) async fn collab_join(
+        &self,
+        host: &str,
+        port: u16,
+        name: String,
+        session_key: String,
+    ) -> anyhow::Result<()> {
+        self.collab_stop();
+
+        let connection = gio::SocketClient::new()
+            .connect_to_host_future(host, port)
+            .await?;
+        let mut reader = WebSocketReader::new(&connection, Role::Client);
+        websocket::handshake_client(&connection, &mut reader, host, port, &session_key).await?;
+        let widget_flags = self.engine_mut().start_collab_session(name);
+        self.emit_handle_widget_flags(widget_flags);
+        self.imp().collab.replace(Some(CollabConnection {
+            session_key,
+            ..Default::default()
+        }));
+        self.collab_add_peer(connection, reader, Role::Client);
+
+        // Share the strokes that are already on the canvas
+        let history = self.engine_mut().collab_history();
+        for message in history.iter() {
+            self.collab_send(message, |_| true);
+        }
+        Ok(())
+    }
+
+    /// Leaves the collaboration session. When hosting it, the session ends for all peers.
+    pub(crate) fn collab_stop(&self) {
+        if !self.collab_active() {
+            return;
+        }
+        let leave = self.engine_ref().collab_leave();
+        if let Some(leave) = leave {
+            self.collab_send(&leave, |_| true);
+        }
+        let Some(collab) = self.imp().collab.take() else {
+            return;
+        };
+        // Closing the channels lets the writer tasks close their connection
+        for peer in collab.peers.borrow().iter() {
+            peer.tx.close_channel();
+        }
+        if let Some(service) = collab.service {
+            service.stop();
+            service.close();
+        }
+        let widget_flags = self.engine_mut().stop_collab_session();
+        self.emit_handle_widget_flags(widget_flags);
+    }
+
+    /// Sends the local changes to the peers. Expected to be called when the store was modified.
+    pub(crate) fn collab_sync(&self) {
+        if !self.collab_active() {
+            return;
+        }
+        let changes = self.engine_mut().collab_local_changes();
+        if let Some(changes) = changes {
+            self.collab_send(&changes, |_| true);
+        }
+    }
+
+    /// Sends the current cursor position to the peers.
+    pub(crate) fn collab_send_cursor(&self) {
+        if !self.collab_active() {
+            return;
+        }
+        let cursor = self.engine_ref().collab_cursor();
+        if let Some(cursor) = cursor {
+            self.collab_send(&cursor, |_| true);
+        }
+    }
+
+    /// Sends the message to the connected peers for which the filter returns true.
+    fn collab_send(&self, message: &CollabMessage, filter: impl Fn(usize) -> bool) {
+        let text = match serde_json::to_string(message) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("Serializing collaboration message failed, Err: {e:?}");
+                return;
+            }
+        };
+        if text.len() > websocket::MESSAGE_MAX_SIZE {
+            error!("Collaboration message exceeds the maximum size and is not sent.");
+            return;
+        }
+        self.collab_send_text(&text, filter);
+    }
+
+    fn collab_send_text(&self, text: &str, filter: impl Fn(usize) -> bool) {
+        let collab = self.imp().collab.borrow();
+        let Some(collab) = collab.as_ref() else {
+            return;
+        };
+        for peer in collab.peers.borrow().iter().filter(|peer| filter(peer.id)) {
+            if let Err(e) = peer
+                .tx
+                .unbounded_send(websocket::encode_text(text, peer.role))
+            {
+                debug!("Sending collaboration message to peer failed, Err: {e:?}");
+            }
+        }
+    }
+
+    /// Spawns the tasks that read from and write to the connection, after the handshake was performed.
+    ///
+    /// Returns the id of the peer.
+    fn collab_add_peer(
+        &self,
+        connection: gio::SocketConnection,
+        mut reader: WebSocketReader,
+        role: Role,
+    ) -> Option<usize> {
+        let (tx, mut rx) = mpsc::unbounded::<Vec<u8>>();
+        let peer_id = {
+            let collab = self.imp().collab.borrow();
+            let collab = collab.as_ref()?;
+            let peer_id = collab.next_peer_id.get();
+            collab.next_peer_id.set(peer_id + 1);
+            collab.peers.borrow_mut().push(CollabPeer {
+                id: peer_id,
+                role,
+                tx: tx.clone(),
+                remote_peers: HashSet::new(),
+            });
+            peer_id
+        };
+
+        let output_stream = connection.output_stream();
+        glib::spawn_future_local(clone!(
+            #[strong]
+            connection,
+            async move {
+                while let Some(frame) = rx.next().await {
+                    if let Err((_, e)) = output_stream
+                        .write_all_future(frame, glib::Priority::DEFAULT)
+                        .await
+                    {
+                        debug!("Writing to collaboration peer failed, Err: {e:?}");
+                        break;
+                    }
+                }
+                let _ = output_stream
+                    .write_all_future(websocket::encode_close(role), glib::Priority::DEFAULT)
+                    .await;
+                if let Err(e) = connection.close_future(glib::Priority::DEFAULT).await {
+                    debug!("Closing connection to collaboration peer failed, Err: {e:?}");
+                }
+            }
+        ));
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=canvas)]
+            self,
+            async move {
+                loop {
+                    match reader.read_message(&tx).await {
+                        Ok(Some(text)) => canvas.collab_handle_text(peer_id, &text),
+                        Ok(None) => break,
+                        Err(e) => {
+                            debug!("Reading from collaboration peer failed, Err: {e:?}");
+                            break;
+                        }
+                    }
+                }
+                canvas.collab_remove_peer(peer_id);
+            }
+        ));
+
+        Some(peer_id)
+    }
+
+    fn collab_handle_text(&self, peer_id: usize, text: &str) {
+        let message = match serde_json::from_str::<CollabMessage>(text) {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Deserializing collaboration message failed, Err: {e:?}");
+                return;
+            }
+        };
+        let hosting = {
+            let collab = self.imp().collab.borrow();
+            let Some(collab) = collab.as_ref() else {
+                return;
+            };
+            if let CollabMessage::Cursor { peer, .. } = &message {
+                if let Some(sender) = collab
+                    .peers
+                    .borrow_mut()
+                    .iter_mut()
+                    .find(|p| p.id == peer_id)
+                {
+                    sender.remote_peers.insert(*peer);
+                }
+            }
+            collab.service.is_some()
+        };
+        if hosting {
+            self.collab_send_text(text, |id| id != peer_id);
+        }
+        let widget_flags = self.engine_mut().collab_handle_message(message);
+        self.emit_handle_widget_flags(widget_flags);
+    }
+
+    fn collab_remove_peer(&self, peer_id: usize) {
+        let (removed, hosting) = {
+            let collab = self.imp().collab.borrow();
+            let Some(collab) = collab.as_ref() else {
+                return;
+            };
+            let mut peers = collab.peers.borrow_mut();
+            let removed = peers
+                .iter()
+                .position(|p| p.id == peer_id)
+                .map(|i| peers.remove(i));
+            (removed, collab.service.is_some())
+        };
+        let Some(removed) = removed else {
+            return;
+        };
+        removed.tx.close_channel();
+
+        if !hosting {
+            // The host ended the session
+            self.collab_stop();
+            return;
+        }
+        // Peers that disconnected without leaving still need to be removed
+        for peer in removed.remote_peers {
+            let leave = CollabMessage::Leave { peer };
+            self.collab_send(&leave, |_| true);
+            let widget_flags = self.engine_mut().collab_handle_message(leave);
+            self.emit_handle_widget_flags(widget_flags);
+        }
+    }
+}
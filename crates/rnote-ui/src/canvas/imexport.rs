@@ -1,46 +1,61 @@
 // Imports
 use super::RnCanvas;
-use anyhow::Context;
+use crate::RnAppWindow;
 use futures::channel::oneshot;
-use futures::AsyncWriteExt;
-use gtk4::{gio, prelude::*};
+use gettextrs::gettext;
+use gtk4::{gio, glib, prelude::*};
 use rnote_compose::ext::Vector2Ext;
-use rnote_engine::engine::export::{DocExportPrefs, DocPagesExportPrefs, SelectionExportPrefs};
-use rnote_engine::engine::{EngineSnapshot, StrokeContent};
+use rnote_engine::engine::export::{
+    DocExportFormat, DocExportPrefs, DocPagesExportPrefs, PageSelection, SelectionExportPrefs,
+};
+use rnote_engine::engine::{EngineSnapshot, Progress, StrokeContent};
+use rnote_engine::fileformats::rnoteformat::encryption;
+use rnote_engine::photocorrection::{self, PhotoCorners};
 use rnote_engine::strokes::resize::ImageSizeOption;
 use rnote_engine::strokes::Stroke;
 use rnote_engine::WidgetFlags;
 use std::ops::Range;
-use std::path::Path;
-use tracing::{debug, error};
+use std::path::{Path, PathBuf};
+use tracing::{debug, error, warn};
+use zeroize::Zeroizing;
 
 impl RnCanvas {
     /// Load the bytes of a `.rnote` file and imports it into the engine.
     ///
     /// `file_path` is optional but needs to be supplied when the origin file should be tracked.
+    /// The bytes need to be already decrypted, `passphrase` is the passphrase the file was encrypted with.
     ///
     /// The function returns `WidgetFlags` instead of emitting the `handle_signal_flags` signal, because a signal
     /// handler might not yet be connected when this function is called.
+    /// It also returns descriptions of the parts of a corrupt file that could not be read and were dropped.
     pub(crate) async fn load_in_rnote_bytes<P>(
         &self,
         bytes: Vec<u8>,
         file_path: Option<P>,
-    ) -> anyhow::Result<WidgetFlags>
+        passphrase: Option<Zeroizing<String>>,
+    ) -> anyhow::Result<(WidgetFlags, Vec<String>)>
     where
         P: AsRef<Path>,
     {
-        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        let (engine_snapshot, dropped) = EngineSnapshot::load_from_rnote_bytes_lazy(bytes).await?;
         let mut widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
         widget_flags |= self
             .engine_mut()
             .set_scale_factor(self.scale_factor() as f64);
 
         self.set_output_file(file_path.map(gio::File::for_path));
+        self.set_passphrase(passphrase);
         self.dismiss_output_file_modified_toast();
         self.set_unsaved_changes(false);
         self.set_empty(false);
 
-        Ok(widget_flags)
+        Ok((widget_flags, dropped))
+    }
+
+    /// Append the pages of the document in the given .rnote bytes below the current document.
+    pub(crate) async fn append_rnote_bytes(&self, bytes: Vec<u8>) -> anyhow::Result<WidgetFlags> {
+        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        Ok(self.engine_mut().append_snapshot(engine_snapshot))
     }
 
     /// Reload the engine from the file that is set as origin file.
@@ -53,9 +68,25 @@ impl RnCanvas {
             ));
         };
         let (bytes, _) = output_file.load_bytes_future().await?;
-        let widget_flags = self
-            .load_in_rnote_bytes(bytes.to_vec(), output_file.path())
+        let bytes = bytes.to_vec();
+        let passphrase = self.passphrase();
+        let bytes = if encryption::is_encrypted(&bytes) {
+            // The file is expected to be still encrypted with the passphrase it was opened with
+            let passphrase = passphrase.clone().ok_or_else(|| {
+                anyhow::anyhow!("Failed to reload file from disk, the file is encrypted.")
+            })?;
+            gio::spawn_blocking(move || encryption::decrypt(&bytes, &passphrase))
+                .await
+                .map_err(|_| anyhow::anyhow!("Decrypting the file panicked."))??
+        } else {
+            bytes
+        };
+        let (widget_flags, dropped) = self
+            .load_in_rnote_bytes(bytes, output_file.path(), passphrase)
             .await?;
+        for d in dropped {
+            warn!("Dropped unreadable content while reloading file from disk: {d}");
+        }
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
     }
@@ -73,8 +104,21 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Loads in the bytes of an Evernote .enex export file, replacing the current document.
+    pub(crate) async fn load_in_enex_bytes(&self, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let engine_snapshot = EngineSnapshot::load_from_enex_bytes(bytes).await?;
+        let widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
+        self.emit_handle_widget_flags(widget_flags);
+
+        self.set_output_file(None);
+        self.set_unsaved_changes(true);
+        self.set_empty(false);
+        Ok(())
+    }
+
     /// Loads in bytes from a vector image and imports it.
     ///
+    /// Depending on the import prefs the paths of the vector image are imported as individual shape strokes.
     /// `target_pos` is in coordinate space of the doc.
     pub(crate) async fn load_in_vectorimage_bytes(
         &self,
@@ -86,13 +130,14 @@ impl RnCanvas {
 
         // Splitting the import operation into two parts: a receiver that gets awaited with the content, and
         // the blocking import avoids borrowing the entire engine RefCell while awaiting the content, avoiding panics.
-        let vectorimage_receiver =
+        let strokes_receiver =
             self.engine_mut()
-                .generate_vectorimage_from_bytes(pos, bytes, respect_borders);
-        let vectorimage = vectorimage_receiver.await??;
-        let widget_flags = self
-            .engine_mut()
-            .import_generated_content(vec![(Stroke::VectorImage(vectorimage), None)], false);
+                .generate_svg_strokes_from_bytes(pos, bytes, respect_borders);
+        let strokes = strokes_receiver.await??;
+        let widget_flags = self.engine_mut().import_generated_content(
+            strokes.into_iter().map(|stroke| (stroke, None)).collect(),
+            false,
+        );
 
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
@@ -101,18 +146,21 @@ impl RnCanvas {
     /// Loads in bytes from a bitmap image and imports it.
     ///
     /// `target_pos` is in coordinate space of the doc.
+    /// When `linked_path` is supplied, the image is linked to that file instead of being embedded.
     pub(crate) async fn load_in_bitmapimage_bytes(
         &self,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
         respect_borders: bool,
+        linked_path: Option<PathBuf>,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
 
         let bitmapimage_receiver =
             self.engine_mut()
                 .generate_bitmapimage_from_bytes(pos, bytes, respect_borders);
-        let bitmapimage = bitmapimage_receiver.await??;
+        let mut bitmapimage = bitmapimage_receiver.await??;
+        bitmapimage.linked_path = linked_path;
         let widget_flags = self
             .engine_mut()
             .import_generated_content(vec![(Stroke::BitmapImage(bitmapimage), None)], false);
@@ -121,14 +169,46 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Corrects the perspective of the photo and imports it as bitmap image.
+    ///
+    /// `target_pos` is in coordinate space of the doc.
+    pub(crate) async fn load_in_photo_bytes(
+        &self,
+        bytes: Vec<u8>,
+        corners: PhotoCorners,
+        target_pos: Option<na::Vector2<f64>>,
+        respect_borders: bool,
+    ) -> anyhow::Result<()> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let enhance_contrast = self
+            .engine_ref()
+            .import_prefs
+            .bitmapimage_import_prefs
+            .enhance_photo_contrast;
+
+        rayon::spawn(move || {
+            let result = photocorrection::correct_photo(&bytes, corners, enhance_contrast);
+            if oneshot_sender.send(result).is_err() {
+                error!(
+                    "Sending result to receiver while correcting photo failed. Receiver already dropped."
+                );
+            }
+        });
+        let corrected_bytes = oneshot_receiver.await??;
+        self.load_in_bitmapimage_bytes(corrected_bytes, target_pos, respect_borders, None)
+            .await
+    }
+
     /// Loads in bytes from a pdf and imports it.
     ///
     /// `target_pos` is in coordinate space of the doc.
+    /// The import can be cancelled through `progress`.
     pub(crate) async fn load_in_pdf_bytes(
         &self,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
         page_range: Option<Range<u32>>,
+        progress: Progress,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
         let adjust_document = self
@@ -139,7 +219,7 @@ impl RnCanvas {
 
         let strokes_receiver = self
             .engine_mut()
-            .generate_pdf_pages_from_bytes(bytes, pos, page_range);
+            .generate_pdf_pages_from_bytes(bytes, pos, page_range, progress);
         let strokes = strokes_receiver.await??;
         let widget_flags = self
             .engine_mut()
@@ -149,17 +229,22 @@ impl RnCanvas {
         Ok(())
     }
 
-    /// Imports a text.
+    /// Imports a text. When `markdown` is true, basic markdown is converted to styled text.
     ///
     /// `target_pos` is in coordinate space of the doc.
     pub(crate) fn load_in_text(
         &self,
         text: String,
+        markdown: bool,
         target_pos: Option<na::Vector2<f64>>,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
 
-        let widget_flags = self.engine_mut().insert_text(text, Some(pos));
+        let widget_flags = if markdown {
+            self.engine_mut().insert_markdown_text(text, Some(pos))
+        } else {
+            self.engine_mut().insert_text(text, Some(pos))
+        };
 
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
@@ -174,21 +259,8 @@ impl RnCanvas {
         resize_option: ImageSizeOption,
         target_pos: Option<na::Vector2<f64>>,
     ) -> anyhow::Result<()> {
-        let (oneshot_sender, oneshot_receiver) =
-            oneshot::channel::<anyhow::Result<StrokeContent>>();
         let pos = self.determine_stroke_import_pos(target_pos);
-
-        rayon::spawn(move || {
-            let result = || -> Result<StrokeContent, anyhow::Error> {
-                Ok(serde_json::from_str(&json_string)?)
-            };
-            if oneshot_sender.send(result()).is_err() {
-                error!(
-                    "Sending result to receiver while inserting stroke content failed. Receiver already dropped."
-                );
-            }
-        });
-        let content = oneshot_receiver.await??;
+        let content = deserialize_stroke_content(json_string).await?;
         let widget_flags = self
             .engine_mut()
             .insert_stroke_content(content, pos, resize_option);
@@ -197,6 +269,27 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Deserializes the stroke content and inserts it into the engine at the position it was copied from,
+    /// without resizing it.
+    pub(crate) async fn insert_stroke_content_in_place(
+        &self,
+        json_string: String,
+    ) -> anyhow::Result<()> {
+        let content = deserialize_stroke_content(json_string).await?;
+        let pos = content
+            .bounds()
+            .map(|bounds| bounds.mins.coords)
+            .unwrap_or_else(|| self.determine_stroke_import_pos(None));
+        let widget_flags = self.engine_mut().insert_stroke_content(
+            content,
+            pos,
+            ImageSizeOption::RespectOriginalSize,
+        );
+
+        self.emit_handle_widget_flags(widget_flags);
+        Ok(())
+    }
+
     /// Saves the document to the given file.
     ///
     /// Returns Ok(true) if saved successfully, Ok(false) when a save is already in progress and no file operatiosn were
@@ -210,6 +303,7 @@ impl RnCanvas {
         }
         self.set_save_in_progress(true);
         debug!("Saving file is now in progress");
+        self.load_pending_strokes();
 
         let file_path = file
             .path()
@@ -217,9 +311,20 @@ impl RnCanvas {
         let basename = file
             .basename()
             .ok_or_else(|| anyhow::anyhow!("Could not retrieve basename for file: `{file:?}`."))?;
+        if self
+            .engine_ref()
+            .document
+            .image_compression
+            .compress_on_save
+        {
+            let widget_flags = self.engine_mut().compress_images();
+            self.emit_handle_widget_flags(widget_flags);
+        }
         let rnote_bytes_receiver = self
             .engine_ref()
             .save_as_rnote_bytes(basename.to_string_lossy().to_string());
+        // The saved state is captured now, so edits made while saving mark the document as changed again.
+        self.set_unsaved_changes(false);
         let mut skip_set_output_file = false;
         if let Some(output_file_path) = self.output_file().and_then(|f| f.path()) {
             if crate::utils::paths_abs_eq(output_file_path, &file_path).unwrap_or(false) {
@@ -228,35 +333,41 @@ impl RnCanvas {
         }
         self.dismiss_output_file_modified_toast();
 
+        let discard_backups = self.discard_backups();
+        let n_backups = if discard_backups {
+            0
+        } else {
+            self.root()
+                .and_downcast::<RnAppWindow>()
+                .and_then(|appwindow| appwindow.app().app_settings())
+                .map(|app_settings| app_settings.uint("save-backups"))
+                .unwrap_or(0)
+        };
+
+        let passphrase = self.passphrase();
+
         let file_write_operation = async move {
             let bytes = rnote_bytes_receiver.await??;
+            let bytes = encrypt_if_needed(bytes, passphrase).await?;
             self.set_output_file_expect_write(true);
-            let mut write_file = async_fs::OpenOptions::new()
-                .create(true)
-                .truncate(true)
-                .write(true)
-                .open(&file_path)
-                .await
-                .context(format!(
-                    "Failed to create/open/truncate file for path '{}'",
-                    file_path.display()
-                ))?;
             if !skip_set_output_file {
                 // this installs the file watcher.
                 self.set_output_file(Some(file.to_owned()));
             }
-            write_file.write_all(&bytes).await.context(format!(
-                "Failed to write bytes to file with path '{}'",
-                file_path.display()
-            ))?;
-            write_file.sync_all().await.context(format!(
-                "Failed to sync file after writing with path '{}'",
-                file_path.display()
-            ))?;
+            gio::spawn_blocking(move || {
+                crate::utils::atomic_replace_file(&bytes, &file_path, n_backups)?;
+                if discard_backups {
+                    crate::utils::remove_backup_files(&file_path)?;
+                }
+                anyhow::Ok(())
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("Writing the file panicked."))??;
             Ok(())
         };
 
         if let Err(e) = file_write_operation.await {
+            self.set_unsaved_changes(true);
             self.set_save_in_progress(false);
             // If the file operations failed in any way, we make sure to clear the expect_write flag
             // because we can't know for sure if the output-file watcher will be able to.
@@ -265,23 +376,97 @@ impl RnCanvas {
         }
 
         debug!("Saving file has finished successfully");
-        self.set_unsaved_changes(false);
         self.set_save_in_progress(false);
+        if discard_backups {
+            self.set_discard_backups(false);
+        }
+
+        if let Err(e) = self.export_save_mirror(file).await {
+            error!("Exporting readable copy of the saved document failed, Err: {e:?}");
+            if let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() {
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting readable copy failed"));
+            }
+        }
 
         Ok(true)
     }
 
+    /// Exports a readable copy next to the saved document, when a format for it is set in the settings.
+    ///
+    /// The copy has the same name as the document and is overwritten on every save.
+    /// It is not exported for encrypted documents, since it can't be encrypted. A copy that was exported
+    /// before the document was encrypted is removed instead.
+    async fn export_save_mirror(&self, file: &gio::File) -> anyhow::Result<()> {
+        let Some(app_settings) = self
+            .root()
+            .and_downcast::<RnAppWindow>()
+            .and_then(|appwindow| appwindow.app().app_settings())
+        else {
+            return Ok(());
+        };
+        let export_format = match app_settings.uint("save-mirror-format") {
+            1 => DocExportFormat::Pdf,
+            2 => DocExportFormat::Svg,
+            _ => return Ok(()),
+        };
+        let folder = file
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Could not get the parent of file: `{file:?}`."))?;
+        let file_stem = file
+            .basename()
+            .and_then(|basename| Some(basename.file_stem()?.to_string_lossy().to_string()))
+            .ok_or_else(|| anyhow::anyhow!("Could not get the file stem of file: `{file:?}`."))?;
+        let mirror_file = folder.child(format!("{file_stem}.{}", export_format.file_ext()));
+        if self.encrypted() {
+            debug!("Not exporting a readable copy of the saved document, because it is encrypted");
+            if let Some(mirror_path) = mirror_file.path() {
+                gio::spawn_blocking(move || match std::fs::remove_file(&mirror_path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+                        "Removing the readable copy `{mirror_path:?}` of the encrypted document failed, Err: {e:?}"
+                    )),
+                    _ => Ok(()),
+                })
+                .await
+                .map_err(|_| anyhow::anyhow!("Removing the readable copy panicked."))??;
+            }
+            return Ok(());
+        }
+        let export_prefs_override = DocExportPrefs {
+            export_format,
+            ..self.engine_ref().export_prefs.doc_export_prefs
+        };
+
+        self.load_pending_strokes();
+        let export_bytes = self.engine_ref().export_doc(
+            file_stem,
+            Some(export_prefs_override),
+            None,
+            Progress::default(),
+        );
+        crate::utils::create_replace_file_future(export_bytes.await??, &mirror_file).await?;
+        Ok(())
+    }
+
     pub(crate) async fn export_doc(
         &self,
         file: &gio::File,
         title: String,
         export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> anyhow::Result<()> {
-        let export_bytes = self.engine_ref().export_doc(title, export_prefs_override);
+        self.load_pending_strokes();
+        let export_bytes =
+            self.engine_ref()
+                .export_doc(title, export_prefs_override, page_selection, progress);
 
         crate::utils::create_replace_file_future(export_bytes.await??, file).await?;
 
         self.set_last_export_dir(file.parent());
+        self.run_export_hook("export-hook-doc", std::slice::from_ref(file))
+            .await;
 
         Ok(())
     }
@@ -289,11 +474,14 @@ impl RnCanvas {
     /// Exports document pages
     /// `file_stem_name`: the stem name of the created files. This is extended by an enumeration of the page number and
     /// file extension overwrites existing files with the same name!
+    /// When a page selection is given, only the selected pages are exported and keep their page number.
     pub(crate) async fn export_doc_pages(
         &self,
         dir: &gio::File,
         file_stem_name: String,
         export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<PageSelection>,
+        progress: Progress,
     ) -> anyhow::Result<()> {
         if dir.query_file_type(gio::FileQueryInfoFlags::NONE, gio::Cancellable::NONE)
             != gio::FileType::Directory
@@ -302,26 +490,37 @@ impl RnCanvas {
                 "Supplied target file `{dir:?}` is not a directory."
             ));
         }
+        self.load_pending_strokes();
         let export_prefs =
             export_prefs_override.unwrap_or(self.engine_ref().export_prefs.doc_pages_export_prefs);
         let file_ext = export_prefs.export_format.file_ext();
+        let n_pages = self
+            .engine_ref()
+            .pages_bounds_w_content(export_prefs.page_order)
+            .len();
+        let page_indices = match &page_selection {
+            Some(page_selection) => page_selection.indices(n_pages),
+            None => (0..n_pages).collect(),
+        };
 
-        let export_bytes_recv = self.engine_ref().export_doc_pages(export_prefs_override);
+        let export_bytes_recv =
+            self.engine_ref()
+                .export_doc_pages(export_prefs_override, page_selection, progress);
         let export_bytes = export_bytes_recv.await??;
 
-        for (i, page_bytes) in export_bytes.into_iter().enumerate() {
-            crate::utils::create_replace_file_future(
-                page_bytes,
-                &dir.child(
-                    &(rnote_engine::utils::doc_pages_files_names(file_stem_name.clone(), i + 1)
-                        + "."
-                        + &file_ext),
-                ),
-            )
-            .await?;
+        let mut files = Vec::with_capacity(export_bytes.len());
+        for (i, page_bytes) in page_indices.into_iter().zip(export_bytes) {
+            let file = dir.child(
+                &(rnote_engine::utils::doc_pages_files_names(file_stem_name.clone(), i + 1)
+                    + "."
+                    + &file_ext),
+            );
+            crate::utils::create_replace_file_future(page_bytes, &file).await?;
+            files.push(file);
         }
 
         self.set_last_export_dir(Some(dir.clone()));
+        self.run_export_hook("export-hook-doc-pages", &files).await;
 
         Ok(())
     }
@@ -335,6 +534,8 @@ impl RnCanvas {
 
         if let Some(export_bytes) = export_bytes.await?? {
             crate::utils::create_replace_file_future(export_bytes, file).await?;
+            self.run_export_hook("export-hook-selection", std::slice::from_ref(file))
+                .await;
         }
 
         self.set_last_export_dir(file.parent());
@@ -342,9 +543,43 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Run the export hook profile that is selected in the app settings under the given key for every exported file.
+    ///
+    /// A failing hook does not fail the export, it is only reported.
+    pub(crate) async fn run_export_hook(&self, settings_key: &str, files: &[gio::File]) {
+        let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        let Some(profile) = appwindow
+            .app()
+            .app_settings()
+            .map(|app_settings| app_settings.string(settings_key).to_string())
+            .filter(|profile| !profile.is_empty())
+        else {
+            return;
+        };
+        let Some(template) = appwindow.export_hooks().remove(&profile) else {
+            warn!("Export hook profile `{profile}` does not exist, not running it.");
+            return;
+        };
+        if template.trim().is_empty() {
+            return;
+        }
+        for file in files {
+            if let Err(e) = run_export_hook_command(&template, file).await {
+                error!("Running export hook `{profile}` for file `{file:?}` failed, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Running export hook failed"));
+                return;
+            }
+        }
+    }
+
     /// exports and writes the engine state as json into the file.
     /// Only for debugging!
     pub(crate) async fn export_engine_state(&self, file: &gio::File) -> anyhow::Result<()> {
+        self.load_pending_strokes();
         let exported_engine_state = self.engine_ref().export_state_as_json()?;
 
         crate::utils::create_replace_file_future(exported_engine_state.into_bytes(), file).await?;
@@ -366,6 +601,66 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Saves a copy of the document to the given file.
+    ///
+    /// In contrast to `save_document_to_file()` the output file and the unsaved changes state are left unchanged.
+    pub(crate) async fn save_document_copy_to_file(&self, file: &gio::File) -> anyhow::Result<()> {
+        let file_path = file
+            .path()
+            .ok_or_else(|| anyhow::anyhow!("Could not get a path for file: `{file:?}`."))?;
+        let basename = file
+            .basename()
+            .ok_or_else(|| anyhow::anyhow!("Could not retrieve basename for file: `{file:?}`."))?;
+        // Writing the copy over the open file would conflict with its file watcher and the unsaved changes state
+        if let Some(output_file_path) = self.output_file().and_then(|f| f.path()) {
+            if crate::utils::paths_abs_eq(output_file_path, &file_path).unwrap_or(false) {
+                return Err(anyhow::anyhow!(
+                    "Can't save a copy to the file of the document itself."
+                ));
+            }
+        }
+        self.load_pending_strokes();
+
+        let bytes = self
+            .engine_ref()
+            .save_as_rnote_bytes(basename.to_string_lossy().to_string())
+            .await??;
+        // The copy is protected with the same passphrase
+        let bytes = encrypt_if_needed(bytes, self.passphrase()).await?;
+        gio::spawn_blocking(move || crate::utils::atomic_replace_file(&bytes, &file_path, 0))
+            .await
+            .map_err(|_| anyhow::anyhow!("Writing the file panicked."))??;
+        Ok(())
+    }
+
+    /// Loads the current state of the document of another canvas as a new document that is not saved to a file yet.
+    ///
+    /// The function returns `WidgetFlags` instead of emitting the `handle_signal_flags` signal, because a signal
+    /// handler might not yet be connected when this function is called.
+    pub(crate) fn load_duplicate_of(&self, other: &RnCanvas) -> WidgetFlags {
+        other.load_pending_strokes();
+        let mut widget_flags = self
+            .engine_mut()
+            .load_snapshot(other.engine_ref().take_snapshot());
+        widget_flags |= self
+            .engine_mut()
+            .set_scale_factor(self.scale_factor() as f64);
+
+        self.set_output_file(None);
+        // The duplicate is protected with the same passphrase
+        self.set_passphrase(other.passphrase());
+        self.set_unsaved_changes(!other.empty());
+        self.set_empty(other.empty());
+        widget_flags
+    }
+
+    /// Load the strokes of a lazily loaded document that are not yet loaded,
+    /// needed before the whole document is saved or exported.
+    pub(crate) fn load_pending_strokes(&self) {
+        let widget_flags = self.engine_mut().load_pending_strokes();
+        self.emit_handle_widget_flags(widget_flags);
+    }
+
     fn determine_stroke_import_pos(
         &self,
         target_pos: Option<na::Vector2<f64>>,
@@ -384,3 +679,95 @@ impl RnCanvas {
         })
     }
 }
+
+async fn deserialize_stroke_content(json_string: String) -> anyhow::Result<StrokeContent> {
+    let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<StrokeContent>>();
+
+    rayon::spawn(move || {
+        let result =
+            || -> Result<StrokeContent, anyhow::Error> { Ok(serde_json::from_str(&json_string)?) };
+        if oneshot_sender.send(result()).is_err() {
+            error!(
+                "Sending result to receiver while inserting stroke content failed. Receiver already dropped."
+            );
+        }
+    });
+    oneshot_receiver.await?
+}
+
+/// Encrypts the bytes of a `.rnote` file on a worker thread when a passphrase is given.
+pub(crate) async fn encrypt_if_needed(
+    bytes: Vec<u8>,
+    passphrase: Option<Zeroizing<String>>,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(passphrase) = passphrase else {
+        return Ok(bytes);
+    };
+    gio::spawn_blocking(move || encryption::encrypt(&bytes, &passphrase))
+        .await
+        .map_err(|_| anyhow::anyhow!("Encrypting the file panicked."))?
+}
+
+/// Run the export hook command template for the exported file and wait until it has finished.
+///
+/// The template is split into arguments like a shell command line, then the placeholders `{file}`, `{dir}`, `{name}`,
+/// `{stem}` and `{ext}` in each argument are replaced by the path, parent directory, file name, file name without
+/// extension and extension of the file. The replacements are not subject to shell quoting, so they stay a single
+/// argument even with spaces, whether the placeholder is quoted in the template or not.
+/// When the template doesn't contain any of them, the path is appended as an argument.
+async fn run_export_hook_command(template: &str, file: &gio::File) -> anyhow::Result<()> {
+    let path = file
+        .path()
+        .ok_or_else(|| anyhow::anyhow!("Exported file `{file:?}` has no path."))?;
+    let argv = expand_export_hook_template(template, &path)?;
+    let argv = argv.iter().map(|arg| arg.as_os_str()).collect::<Vec<_>>();
+    gio::Subprocess::newv(&argv, gio::SubprocessFlags::NONE)?
+        .wait_check_future()
+        .await?;
+    Ok(())
+}
+
+/// Splits the export hook template into arguments and replaces the placeholders in them with the parts of the path.
+fn expand_export_hook_template(
+    template: &str,
+    path: &Path,
+) -> anyhow::Result<Vec<std::ffi::OsString>> {
+    let mut has_placeholder = false;
+    let mut argv = glib::shell_parse_argv(template)?
+        .into_iter()
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            let mut expanded = std::ffi::OsString::with_capacity(arg.len());
+            let mut rest = arg.as_ref();
+            while let Some(start) = rest.find('{') {
+                expanded.push(&rest[..start]);
+                rest = &rest[start..];
+                let replacement = rest.find('}').and_then(|end| match &rest[1..end] {
+                    "file" => Some((end, Some(path.as_os_str()))),
+                    "dir" => Some((end, path.parent().map(|dir| dir.as_os_str()))),
+                    "name" => Some((end, path.file_name())),
+                    "stem" => Some((end, path.file_stem())),
+                    "ext" => Some((end, path.extension())),
+                    _ => None,
+                });
+                match replacement {
+                    Some((end, replacement)) => {
+                        expanded.push(replacement.unwrap_or_default());
+                        rest = &rest[end + 1..];
+                        has_placeholder = true;
+                    }
+                    None => {
+                        expanded.push("{");
+                        rest = &rest[1..];
+                    }
+                }
+            }
+            expanded.push(rest);
+            expanded
+        })
+        .collect::<Vec<_>>();
+    if !has_placeholder {
+        argv.push(path.as_os_str().to_owned());
+    }
+    Ok(argv)
+}
@@ -5,6 +5,7 @@ use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenState, Shor
 use rnote_compose::penpath::Element;
 use rnote_engine::ext::EventPropagationExt;
 use rnote_engine::ext::GraphenePointExt;
+use rnote_engine::pens::deviceprofiles::{InputDevice, InputDeviceType};
 use rnote_engine::pens::penholder::BacklogPolicy;
 use rnote_engine::pens::PenMode;
 use rnote_engine::WidgetFlags;
@@ -13,6 +14,7 @@ use std::time::{Duration, Instant};
 use tracing::trace;
 
 // Returns whether the event should be inhibited from propagating, and the new pen state
+#[tracing::instrument(level = "debug", skip_all)]
 pub(crate) fn handle_pointer_controller_event(
     canvas: &RnCanvas,
     event: &gdk::Event,
@@ -24,6 +26,7 @@ pub(crate) fn handle_pointer_controller_event(
     let gdk_event_type = event.event_type();
     let gdk_modifiers = event.modifier_state();
     let _gdk_device = event.device().unwrap();
+    let input_device = retrieve_input_device(event);
     let backlog_policy = canvas.engine_ref().penholder.backlog_policy();
     let is_stylus = event_is_stylus(event);
 
@@ -82,9 +85,11 @@ pub(crate) fn handle_pointer_controller_event(
                 let shortcut_key = retrieve_button_shortcut_key(gdk_button, is_stylus);
 
                 if let Some(shortcut_key) = shortcut_key {
-                    let (ep, wf) = canvas
-                        .engine_mut()
-                        .handle_pressed_shortcut_key(shortcut_key, now);
+                    let (ep, wf) = canvas.engine_mut().handle_pressed_shortcut_key(
+                        shortcut_key,
+                        Some(&input_device),
+                        now,
+                    );
                     widget_flags |= wf;
                     propagation = ep.into_glib();
                 }
@@ -188,6 +193,7 @@ pub(crate) fn handle_pointer_controller_event(
                             modifier_keys: modifier_keys.clone(),
                         },
                         pen_mode,
+                        Some(&input_device),
                         event_time,
                     );
                     widget_flags |= wf;
@@ -202,6 +208,7 @@ pub(crate) fn handle_pointer_controller_event(
                             modifier_keys: modifier_keys.clone(),
                         },
                         pen_mode,
+                        Some(&input_device),
                         event_time,
                     );
                     widget_flags |= wf;
@@ -217,6 +224,7 @@ pub(crate) fn handle_pointer_controller_event(
                             modifier_keys: modifier_keys.clone(),
                         },
                         pen_mode,
+                        Some(&input_device),
                         event_time,
                     );
                     widget_flags |= wf;
@@ -230,6 +238,7 @@ pub(crate) fn handle_pointer_controller_event(
     (propagation, pen_state)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub(crate) fn handle_key_controller_key_pressed(
     canvas: &RnCanvas,
     gdk_key: gdk::Key,
@@ -246,7 +255,7 @@ pub(crate) fn handle_key_controller_key_pressed(
     let (propagation, widget_flags) = if let Some(shortcut_key) = shortcut_key {
         canvas
             .engine_mut()
-            .handle_pressed_shortcut_key(shortcut_key, now)
+            .handle_pressed_shortcut_key(shortcut_key, None, now)
     } else {
         canvas.engine_mut().handle_pen_event(
             PenEvent::KeyPressed {
@@ -254,6 +263,7 @@ pub(crate) fn handle_key_controller_key_pressed(
                 modifier_keys,
             },
             None,
+            None,
             now,
         )
     };
@@ -278,6 +288,7 @@ pub(crate) fn handle_imcontext_text_commit(canvas: &RnCanvas, text: &str) {
             text: text.to_string(),
         },
         None,
+        None,
         now,
     );
     canvas.emit_handle_widget_flags(widget_flags);
@@ -394,8 +405,19 @@ fn retrieve_pointer_elements(
             } else {
                 Element::PRESSURE_DEFAULT
             };
+            let tilt = if is_stylus
+                && available_axes.contains(gdk::AxisFlags::XTILT)
+                && available_axes.contains(gdk::AxisFlags::YTILT)
+            {
+                normalize_tilt(
+                    axes[crate::utils::axis_use_idx(gdk::AxisUse::Xtilt)],
+                    axes[crate::utils::axis_use_idx(gdk::AxisUse::Ytilt)],
+                )
+            } else {
+                na::Vector2::zeros()
+            };
 
-            entries.push((Element::new(pos, pressure), entry_time));
+            entries.push((Element::new(pos, pressure).with_tilt(tilt), entry_time));
         }
 
         elements.extend(entries.into_iter().rev());
@@ -410,12 +432,30 @@ fn retrieve_pointer_elements(
     } else {
         Element::PRESSURE_DEFAULT
     };
+    let tilt = if is_stylus {
+        event
+            .axis(gdk::AxisUse::Xtilt)
+            .zip(event.axis(gdk::AxisUse::Ytilt))
+            .map(|(x, y)| normalize_tilt(x, y))
+            .unwrap_or_else(na::Vector2::zeros)
+    } else {
+        na::Vector2::zeros()
+    };
 
-    elements.push((Element::new(pos, pressure), now));
+    elements.push((Element::new(pos, pressure).with_tilt(tilt), now));
 
     Some(elements)
 }
 
+/// Gdk reports the tilt axes in degrees, in the range [-90.0, 90.0].
+/// Normalizes them to the range [-1.0, 1.0] expected by the pen path elements.
+fn normalize_tilt(x_deg: f64, y_deg: f64) -> na::Vector2<f64> {
+    na::vector![
+        (x_deg / 90.0).clamp(-1.0, 1.0),
+        (y_deg / 90.0).clamp(-1.0, 1.0)
+    ]
+}
+
 pub(crate) fn retrieve_button_shortcut_key(
     gdk_button: u32,
     is_stylus: bool,
@@ -445,6 +485,33 @@ pub(crate) fn retrieve_modifier_keys(modifier: gdk::ModifierType) -> HashSet<Mod
     keys
 }
 
+/// Retrieve the input device the event originates from.
+fn retrieve_input_device(event: &gdk::Event) -> InputDevice {
+    let gdk_device = event.device();
+    let name = gdk_device
+        .as_ref()
+        .map(|d| d.name().to_string())
+        .unwrap_or_default();
+    let is_touch = matches!(
+        event.event_type(),
+        gdk::EventType::TouchBegin
+            | gdk::EventType::TouchUpdate
+            | gdk::EventType::TouchEnd
+            | gdk::EventType::TouchCancel
+    ) || gdk_device
+        .map(|d| d.source() == gdk::InputSource::Touchscreen)
+        .unwrap_or(false);
+
+    let device_type = if event_is_stylus(event) {
+        InputDeviceType::Pen
+    } else if is_touch {
+        InputDeviceType::Touchscreen
+    } else {
+        InputDeviceType::Mouse
+    };
+    InputDevice::new(device_type, name)
+}
+
 fn retrieve_pen_mode(event: &gdk::Event) -> Option<PenMode> {
     let device_tool = event.device_tool()?;
     match device_tool.tool_type() {
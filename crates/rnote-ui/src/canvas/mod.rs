@@ -1,7 +1,10 @@
 // Modules
+pub(crate) mod broadcast;
 mod canvaslayout;
+pub(crate) mod collab;
 pub(crate) mod imexport;
 mod input;
+mod websocket;
 mod widgetflagsboxed;
 
 // Re-exports
@@ -32,6 +35,7 @@ use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::path::Path;
 use std::time::Duration;
 use tracing::{debug, error, warn};
+use zeroize::Zeroizing;
 
 #[derive(Debug, Default)]
 struct Connections {
@@ -39,10 +43,13 @@ struct Connections {
     vadjustment: Option<glib::SignalHandlerId>,
     tab_page_output_file: Option<glib::Binding>,
     tab_page_unsaved_changes: Option<glib::Binding>,
+    tab_page_save_in_progress: Option<glib::Binding>,
+    tab_page_encrypted: Option<glib::Binding>,
     appwindow_output_file: Option<glib::SignalHandlerId>,
     appwindow_scalefactor: Option<glib::SignalHandlerId>,
     appwindow_save_in_progress: Option<glib::SignalHandlerId>,
     appwindow_unsaved_changes: Option<glib::SignalHandlerId>,
+    appwindow_encrypted: Option<glib::SignalHandlerId>,
     appwindow_touch_drawing: Option<glib::Binding>,
     appwindow_show_drawing_cursor: Option<glib::Binding>,
     appwindow_regular_cursor: Option<glib::Binding>,
@@ -79,13 +86,23 @@ mod imp {
         pub(crate) output_file_watcher_task: RefCell<Option<glib::JoinHandle<()>>>,
         pub(crate) output_file_modified_toast_singleton: glib::WeakRef<adw::Toast>,
         pub(crate) output_file_expect_write: Cell<bool>,
+        /// Set when the backup copies of the output file must be deleted on the next save,
+        /// for example because they contain content that was redacted.
+        pub(crate) discard_backups: Cell<bool>,
         pub(crate) save_in_progress: Cell<bool>,
         pub(crate) unsaved_changes: Cell<bool>,
+        /// The passphrase the document is encrypted with when saving. None when the document is not encrypted.
+        ///
+        /// Zeroed in memory when it is dropped.
+        pub(crate) passphrase: RefCell<Option<Zeroizing<String>>>,
         pub(crate) empty: Cell<bool>,
         pub(crate) touch_drawing: Cell<bool>,
         pub(crate) show_drawing_cursor: Cell<bool>,
 
         pub(crate) last_export_dir: RefCell<Option<gio::File>>,
+
+        pub(crate) collab: RefCell<Option<collab::CollabConnection>>,
+        pub(crate) broadcast: RefCell<Option<broadcast::BroadcastServer>>,
     }
 
     impl Default for RnCanvas {
@@ -173,13 +190,18 @@ mod imp {
                 // is automatically updated whenever the output file changes.
                 output_file_modified_toast_singleton: glib::WeakRef::new(),
                 output_file_expect_write: Cell::new(false),
+                discard_backups: Cell::new(false),
                 save_in_progress: Cell::new(false),
                 unsaved_changes: Cell::new(false),
+                passphrase: RefCell::new(None),
                 empty: Cell::new(true),
                 touch_drawing: Cell::new(false),
                 show_drawing_cursor: Cell::new(false),
 
                 last_export_dir: RefCell::new(None),
+
+                collab: RefCell::new(None),
+                broadcast: RefCell::new(None),
             }
         }
     }
@@ -248,6 +270,8 @@ mod imp {
         }
 
         fn dispose(&self) {
+            self.obj().collab_stop();
+            self.obj().broadcast_stop();
             self.obj().disconnect_connections();
             self.obj().abort_engine_task_handler();
 
@@ -267,6 +291,10 @@ mod imp {
                     glib::ParamSpecBoolean::builder("unsaved-changes")
                         .default_value(false)
                         .build(),
+                    glib::ParamSpecBoolean::builder("encrypted")
+                        .default_value(false)
+                        .read_only()
+                        .build(),
                     glib::ParamSpecBoolean::builder("empty")
                         .default_value(true)
                         .build(),
@@ -297,6 +325,7 @@ mod imp {
                 "output-file" => self.output_file.borrow().to_value(),
                 "save-in-progress" => self.save_in_progress.get().to_value(),
                 "unsaved-changes" => self.unsaved_changes.get().to_value(),
+                "encrypted" => self.passphrase.borrow().is_some().to_value(),
                 "empty" => self.empty.get().to_value(),
                 "hadjustment" => self.hadjustment.borrow().to_value(),
                 "vadjustment" => self.vadjustment.borrow().to_value(),
@@ -451,13 +480,14 @@ mod imp {
                 snapshot.push_clip(&graphene::Rect::from_p2d_aabb(clip_bounds));
 
                 // Draw the entire engine
-                self.engine
+                let res = self
+                    .engine
                     .borrow()
-                    .draw_to_gtk_snapshot(snapshot, obj.bounds())?;
+                    .draw_to_gtk_snapshot(snapshot, obj.bounds());
 
-                // pop the clip
+                // pop the clip, also when drawing failed
                 snapshot.pop();
-                Ok(())
+                res
             }() {
                 error!("Snapshot canvas failed , Err: {e:?}");
             }
@@ -631,6 +661,10 @@ pub(crate) static OUTPUT_FILE_NEW_SUBTITLE: once_cell::sync::Lazy<String> =
 impl RnCanvas {
     // Sets the canvas zoom scroll step in % for one unit of the event controller delta
     pub(crate) const ZOOM_SCROLL_STEP: f64 = 0.1;
+    // The default distance the canvas is scrolled by for one mouse wheel step
+    pub(crate) const SCROLL_STEP_DEFAULT: f64 = 60.0;
+    // The twist angle in radians that is needed before the touch rotate gesture starts rotating the view
+    pub(crate) const TOUCH_ROTATION_THRESHOLD: f64 = 0.2;
 
     pub(crate) fn new() -> Self {
         glib::Object::new()
@@ -676,6 +710,16 @@ impl RnCanvas {
         self.imp().output_file_expect_write.set(expect_write);
     }
 
+    #[allow(unused)]
+    pub(crate) fn discard_backups(&self) -> bool {
+        self.imp().discard_backups.get()
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_discard_backups(&self, discard_backups: bool) {
+        self.imp().discard_backups.set(discard_backups);
+    }
+
     #[allow(unused)]
     pub(crate) fn save_in_progress(&self) -> bool {
         self.property::<bool>("save-in-progress")
@@ -700,6 +744,27 @@ impl RnCanvas {
         }
     }
 
+    /// Whether the document is encrypted with a passphrase when it is saved.
+    pub(crate) fn encrypted(&self) -> bool {
+        self.property::<bool>("encrypted")
+    }
+
+    /// The passphrase the document is encrypted with when it is saved.
+    pub(crate) fn passphrase(&self) -> Option<Zeroizing<String>> {
+        self.imp().passphrase.borrow().clone()
+    }
+
+    /// Set the passphrase the document is encrypted with when it is saved. None removes the encryption.
+    ///
+    /// The document is only encrypted with it the next time it is saved.
+    pub(crate) fn set_passphrase(&self, passphrase: Option<Zeroizing<String>>) {
+        let was_encrypted = self.encrypted();
+        self.imp().passphrase.replace(passphrase);
+        if self.encrypted() != was_encrypted {
+            self.notify("encrypted");
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn empty(&self) -> bool {
         self.property::<bool>("empty")
@@ -882,6 +947,22 @@ impl RnCanvas {
         }
     }
 
+    /// Shows the cursor with the given name while panning the canvas,
+    /// or switches back to the regular or drawing cursor when `None`.
+    pub(crate) fn set_panning_cursor(&self, cursor_name: Option<&str>) {
+        if let Some(cursor_name) = cursor_name {
+            self.set_cursor_from_name(Some(cursor_name));
+        } else if self.imp().drawing_cursor_enabled.get() {
+            if self.imp().show_drawing_cursor.get() {
+                self.set_cursor(Some(&*self.imp().drawing_cursor.borrow()));
+            } else {
+                self.set_cursor(Some(&*self.imp().invisible_cursor.borrow()));
+            }
+        } else {
+            self.set_cursor(Some(&*self.imp().regular_cursor.borrow()));
+        }
+    }
+
     /// The document title for display. Can be used to get a string as the basename of the existing / a new save file.
     ///
     /// When there is no output-file, falls back to the "New document" string
@@ -986,6 +1067,13 @@ impl RnCanvas {
                     else {
                         return;
                     };
+                    if canvas.output_file_expect_write()
+                        && crate::utils::paths_abs_eq(file_path, to_path).unwrap_or(false)
+                    {
+                        // Own file writing has finished, saves replace the file through a rename
+                        canvas.set_output_file_expect_write(false);
+                        return;
+                    }
                     if !crate::utils::paths_abs_eq(file_path, from_path).unwrap_or(false) {
                         return;
                     }
@@ -999,6 +1087,11 @@ impl RnCanvas {
                     if !crate::utils::paths_abs_eq(file_path, event_path).unwrap_or(false) {
                         return;
                     }
+                    if canvas.output_file_expect_write() {
+                        // Own file writing has finished, saves replace the file through a rename
+                        canvas.set_output_file_expect_write(false);
+                        return;
+                    }
                     dispatch_toast_reload_modified_file(appwindow, canvas);
                 }
                 EventKind::Modify(ModifyKind::Name(_)) => {
@@ -1171,6 +1264,7 @@ impl RnCanvas {
                     } else if !appwindow.tabs_any_saves_in_progress() {
                         appwindow.set_save_in_progress(false);
                     }
+                    appwindow.refresh_titles(&appwindow.active_tab_wrapper());
                 }
             ),
         );
@@ -1178,6 +1272,19 @@ impl RnCanvas {
         // Update titles when there are changes
         let appwindow_unsaved_changes = self.connect_notify_local(
             Some("unsaved-changes"),
+            clone!(
+                #[weak]
+                appwindow,
+                move |_, _| {
+                    appwindow.refresh_unsaved_tabs();
+                    appwindow.refresh_titles(&appwindow.active_tab_wrapper());
+                }
+            ),
+        );
+
+        // Update the lock indicator
+        let appwindow_encrypted = self.connect_notify_local(
+            Some("encrypted"),
             clone!(
                 #[weak]
                 appwindow,
@@ -1255,7 +1362,7 @@ impl RnCanvas {
                         }
                     };
                 } else if value.is::<String>() {
-                    match canvas.load_in_text(value.get::<String>().unwrap(), Some(pos)) {
+                    match canvas.load_in_text(value.get::<String>().unwrap(), false, Some(pos)) {
                         Ok(_) => {
                             accept_drop = true;
                         }
@@ -1319,6 +1426,9 @@ impl RnCanvas {
         {
             self.disconnect(old);
         }
+        if let Some(old) = connections.appwindow_encrypted.replace(appwindow_encrypted) {
+            self.disconnect(old);
+        }
         if let Some(old) = connections
             .appwindow_touch_drawing
             .replace(appwindow_touch_drawing)
@@ -1376,6 +1486,9 @@ impl RnCanvas {
         if let Some(old) = connections.appwindow_unsaved_changes.take() {
             self.disconnect(old);
         }
+        if let Some(old) = connections.appwindow_encrypted.take() {
+            self.disconnect(old);
+        }
         if let Some(old) = connections.appwindow_touch_drawing.take() {
             old.unbind();
         }
@@ -1402,6 +1515,12 @@ impl RnCanvas {
         if let Some(old) = connections.tab_page_unsaved_changes.take() {
             old.unbind();
         }
+        if let Some(old) = connections.tab_page_save_in_progress.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.tab_page_encrypted.take() {
+            old.unbind();
+        }
     }
 
     /// When the widget is the child of a tab page, we want to connect their titles, icons, ..
@@ -1431,6 +1550,21 @@ impl RnCanvas {
             .sync_create()
             .build();
 
+        // display a spinner while saving
+        let tab_page_save_in_progress = self
+            .bind_property("save-in-progress", page, "loading")
+            .sync_create()
+            .build();
+
+        // display a lock for encrypted documents
+        let tab_page_encrypted = self
+            .bind_property("encrypted", page, "indicator-icon")
+            .transform_to(|_, from: bool| {
+                Some(from.then_some(gio::ThemedIcon::new("changes-prevent-symbolic")))
+            })
+            .sync_create()
+            .build();
+
         let mut connections = self.imp().connections.borrow_mut();
         if let Some(old) = connections
             .tab_page_output_file
@@ -1444,6 +1578,15 @@ impl RnCanvas {
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .tab_page_save_in_progress
+            .replace(tab_page_save_in_progress)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections.tab_page_encrypted.replace(tab_page_encrypted) {
+            old.unbind();
+        }
     }
 
     pub(crate) fn bounds(&self) -> Aabb {
@@ -0,0 +1,284 @@
+//! A minimal WebSocket (RFC 6455) transport on top of gio socket connections.
+//!
+//! Only what is needed for the collaboration sessions is implemented: the opening handshake with a session key,
+//! text messages, fragmentation, ping/pong and closing. Messages are bounded in size.
+
+// Imports
+use gtk4::{gio, glib, prelude::*};
+
+/// The maximum size of a single message. Larger messages end the connection.
+pub(crate) const MESSAGE_MAX_SIZE: usize = 64 * 1024 * 1024;
+/// The maximum size of the request and response heads of the opening handshake.
+const HANDSHAKE_MAX_SIZE: usize = 8 * 1024;
+/// The size in which the input stream is read.
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+/// Appended to the key of the client to compute the accept key of the server.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// The header that carries the key of the collaboration session.
+const SESSION_KEY_HEADER: &str = "rnote-session-key";
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Which side of the connection this is. Clients mask the frames they send, servers don't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    Client,
+    Server,
+}
+
+/// Reads from the input stream of a connection, buffering what was read ahead.
+#[derive(Debug)]
+pub(crate) struct WebSocketReader {
+    input_stream: gio::InputStream,
+    role: Role,
+    buf: Vec<u8>,
+}
+
+impl WebSocketReader {
+    pub(crate) fn new(connection: &gio::SocketConnection, role: Role) -> Self {
+        Self {
+            input_stream: connection.input_stream(),
+            role,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Reads more bytes into the buffer. Errors when the connection was closed.
+    async fn fill(&mut self) -> anyhow::Result<()> {
+        let (chunk, n) = self
+            .input_stream
+            .read_future(vec![0; READ_CHUNK_SIZE], glib::Priority::DEFAULT)
+            .await
+            .map_err(|(_, e)| e)?;
+        if n == 0 {
+            return Err(anyhow::anyhow!("Connection closed"));
+        }
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(())
+    }
+
+    async fn read_exact(&mut self, n: usize) -> anyhow::Result<Vec<u8>> {
+        while self.buf.len() < n {
+            self.fill().await?;
+        }
+        Ok(self.buf.drain(..n).collect())
+    }
+
+    /// Reads the head of a Http request or response, up to and including the empty line.
+    pub(crate) async fn read_head(&mut self) -> anyhow::Result<String> {
+        loop {
+            if let Some(end) = self.buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                let head = self.buf.drain(..end + 4).collect::<Vec<u8>>();
+                return Ok(String::from_utf8(head)?);
+            }
+            if self.buf.len() > HANDSHAKE_MAX_SIZE {
+                return Err(anyhow::anyhow!("Handshake exceeds the maximum size"));
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Reads the next text message.
+    ///
+    /// Pings are answered through `tx`. Returns `None` when the peer closed the connection.
+    pub(crate) async fn read_message(
+        &mut self,
+        tx: &futures::channel::mpsc::UnboundedSender<Vec<u8>>,
+    ) -> anyhow::Result<Option<String>> {
+        let mut message: Option<Vec<u8>> = None;
+        loop {
+            let header = self.read_exact(2).await?;
+            let fin = header[0] & 0x80 != 0;
+            let opcode = header[0] & 0x0F;
+            let masked = header[1] & 0x80 != 0;
+            if masked != (self.role == Role::Server) {
+                return Err(anyhow::anyhow!("Unexpected masking of frame"));
+            }
+            let len = match header[1] & 0x7F {
+                126 => u16::from_be_bytes(self.read_exact(2).await?.try_into().unwrap()) as u64,
+                127 => u64::from_be_bytes(self.read_exact(8).await?.try_into().unwrap()),
+                len => len as u64,
+            };
+            let is_control = opcode & 0x08 != 0;
+            if is_control && (len > 125 || !fin) {
+                return Err(anyhow::anyhow!("Invalid control frame"));
+            }
+            let message_len = message.as_ref().map(|m| m.len()).unwrap_or(0) as u64;
+            if message_len.saturating_add(len) > MESSAGE_MAX_SIZE as u64 {
+                return Err(anyhow::anyhow!("Message exceeds the maximum size"));
+            }
+            let mask = if masked {
+                Some(self.read_exact(4).await?)
+            } else {
+                None
+            };
+            let mut payload = self.read_exact(len as usize).await?;
+            if let Some(mask) = mask {
+                for (i, b) in payload.iter_mut().enumerate() {
+                    *b ^= mask[i % 4];
+                }
+            }
+
+            match opcode {
+                OPCODE_TEXT if message.is_none() => message = Some(payload),
+                OPCODE_CONTINUATION if message.is_some() => {
+                    message.as_mut().unwrap().extend_from_slice(&payload)
+                }
+                // The close frame is answered by the writer of the connection when it is closed
+                OPCODE_CLOSE => return Ok(None),
+                OPCODE_PING => {
+                    let _ = tx.unbounded_send(encode_frame(OPCODE_PONG, &payload, self.role));
+                    continue;
+                }
+                OPCODE_PONG => continue,
+                OPCODE_BINARY => return Err(anyhow::anyhow!("Binary messages are not supported")),
+                opcode => return Err(anyhow::anyhow!("Unexpected frame with opcode {opcode}")),
+            }
+            if fin {
+                return Ok(Some(String::from_utf8(message.take().unwrap())?));
+            }
+        }
+    }
+}
+
+/// Encodes a frame with the given opcode and payload.
+fn encode_frame(opcode: u8, payload: &[u8], role: Role) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode);
+    let mask_bit = if role == Role::Client { 0x80 } else { 0x00 };
+    match payload.len() {
+        len @ 0..=125 => frame.push(mask_bit | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(mask_bit | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    if role == Role::Client {
+        let mask = glib::random_int().to_be_bytes();
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    } else {
+        frame.extend_from_slice(payload);
+    }
+    frame
+}
+
+/// Encodes a text message as a single frame.
+pub(crate) fn encode_text(text: &str, role: Role) -> Vec<u8> {
+    encode_frame(OPCODE_TEXT, text.as_bytes(), role)
+}
+
+/// Encodes the frame that closes the connection.
+pub(crate) fn encode_close(role: Role) -> Vec<u8> {
+    encode_frame(OPCODE_CLOSE, &[], role)
+}
+
+fn accept_key(key: &str) -> String {
+    let mut checksum = glib::Checksum::new(glib::ChecksumType::Sha1).unwrap();
+    checksum.update(key.as_bytes());
+    checksum.update(ACCEPT_GUID.as_bytes());
+    glib::base64_encode(&checksum.digest()).to_string()
+}
+
+/// The value of a header, with a case-insensitive name.
+fn header_value<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.split("\r\n").skip(1).find_map(|line| {
+        let (n, v) = line.split_once(':')?;
+        n.trim().eq_ignore_ascii_case(name).then_some(v.trim())
+    })
+}
+
+/// Compares the keys in constant time, so that the session key can't be guessed from the response time.
+pub(crate) fn keys_equal(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Performs the opening handshake as client, sending the session key.
+pub(crate) async fn handshake_client(
+    connection: &gio::SocketConnection,
+    reader: &mut WebSocketReader,
+    host: &str,
+    port: u16,
+    session_key: &str,
+) -> anyhow::Result<()> {
+    let key_bytes = (0..4)
+        .flat_map(|_| glib::random_int().to_be_bytes())
+        .collect::<Vec<u8>>();
+    let key = glib::base64_encode(&key_bytes).to_string();
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n{SESSION_KEY_HEADER}: {session_key}\r\n\r\n"
+    );
+    connection
+        .output_stream()
+        .write_all_future(request.into_bytes(), glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, e)| e)?;
+
+    let head = reader.read_head().await?;
+    let status = head.split("\r\n").next().unwrap_or_default();
+    if status.split_whitespace().nth(1) == Some("403") {
+        return Err(anyhow::anyhow!("The session key was not accepted"));
+    }
+    if status.split_whitespace().nth(1) != Some("101") {
+        return Err(anyhow::anyhow!("Unexpected handshake response '{status}'"));
+    }
+    if header_value(&head, "sec-websocket-accept") != Some(accept_key(&key).as_str()) {
+        return Err(anyhow::anyhow!("Invalid accept key in handshake response"));
+    }
+    Ok(())
+}
+
+/// Performs the opening handshake as server, rejecting clients that don't send the session key.
+pub(crate) async fn handshake_server(
+    connection: &gio::SocketConnection,
+    reader: &mut WebSocketReader,
+    session_key: &str,
+) -> anyhow::Result<()> {
+    let output_stream = connection.output_stream();
+    let head = reader.read_head().await?;
+    let key = header_value(&head, "sec-websocket-key");
+    let valid = head.starts_with("GET ")
+        && header_value(&head, "upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+        && header_value(&head, "sec-websocket-version") == Some("13")
+        && key.is_some();
+    if !valid {
+        let _ = output_stream
+            .write_all_future(
+                b"HTTP/1.1 400 Bad Request\r\n\r\n".to_vec(),
+                glib::Priority::DEFAULT,
+            )
+            .await;
+        return Err(anyhow::anyhow!("Invalid handshake request"));
+    }
+    if !header_value(&head, SESSION_KEY_HEADER).is_some_and(|k| keys_equal(k, session_key)) {
+        let _ = output_stream
+            .write_all_future(
+                b"HTTP/1.1 403 Forbidden\r\n\r\n".to_vec(),
+                glib::Priority::DEFAULT,
+            )
+            .await;
+        return Err(anyhow::anyhow!("Client sent a wrong session key"));
+    }
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key.unwrap())
+    );
+    output_stream
+        .write_all_future(response.into_bytes(), glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, e)| e)?;
+    Ok(())
+}
@@ -4,6 +4,7 @@ use gtk4::{
     gio, glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate, MenuButton,
     PopoverMenu, Widget,
 };
+use rnote_engine::document::Bookmark;
 use rnote_engine::Camera;
 
 mod imp {
@@ -27,7 +28,13 @@ mod imp {
         #[template_child]
         pub(crate) zoom_fit_width_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) rotation_reset_button: TemplateChild<Button>,
+        #[template_child]
         pub(crate) fixedsize_quickactions_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) bookmarks_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) bookmarks_remove_submenu: TemplateChild<gio::Menu>,
     }
 
     #[glib::object_subclass]
@@ -98,6 +105,7 @@ impl RnCanvasMenu {
         self.imp()
             .zoom_reset_button
             .set_label(format!("{:.0}%", (100.0 * Camera::ZOOM_DEFAULT).round()).as_str());
+        self.refresh_rotation_reset_label(0.0);
     }
 
     pub(crate) fn refresh_zoom_reset_label(&self, zoom: f64) {
@@ -105,4 +113,37 @@ impl RnCanvasMenu {
             .zoom_reset_button
             .set_label(format!("{:.0}%", (100.0 * zoom).round()).as_str());
     }
+
+    /// Refresh the label of the rotation reset button with the view rotation in radians.
+    pub(crate) fn refresh_rotation_reset_label(&self, rotation: f64) {
+        // avoid displaying "-0°"
+        let degrees = rotation.to_degrees().round() + 0.0;
+        self.imp()
+            .rotation_reset_button
+            .set_label(format!("{degrees:.0}°").as_str());
+    }
+
+    /// Fill the bookmarks submenu with the bookmarks of the document.
+    pub(crate) fn refresh_bookmarks(&self, bookmarks: &[Bookmark]) {
+        let section = self.imp().bookmarks_section.get();
+        let remove_submenu = self.imp().bookmarks_remove_submenu.get();
+        section.remove_all();
+        remove_submenu.remove_all();
+
+        for (i, bookmark) in bookmarks.iter().enumerate() {
+            let goto_item = gio::MenuItem::new(Some(&bookmark.name), None);
+            goto_item.set_action_and_target_value(
+                Some("win.bookmark-goto"),
+                Some(&(i as u32).to_variant()),
+            );
+            section.append_item(&goto_item);
+
+            let remove_item = gio::MenuItem::new(Some(&bookmark.name), None);
+            remove_item.set_action_and_target_value(
+                Some("win.bookmark-remove"),
+                Some(&(i as u32).to_variant()),
+            );
+            remove_submenu.append_item(&remove_item);
+        }
+    }
 }
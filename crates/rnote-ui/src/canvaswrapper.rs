@@ -1,14 +1,17 @@
 // Imports
-use crate::{RnAppWindow, RnCanvas, RnContextMenu};
+use crate::{RnAppWindow, RnCanvas, RnContextMenu, RnRadialMenu};
 use gtk4::{
     gdk, glib, glib::clone, graphene, prelude::*, subclass::prelude::*, CompositeTemplate,
-    CornerType, EventControllerMotion, EventControllerScroll, EventControllerScrollFlags,
-    EventSequenceState, GestureDrag, GestureLongPress, GestureZoom, PropagationPhase,
-    ScrolledWindow, Widget,
+    CornerType, EventControllerKey, EventControllerMotion, EventControllerScroll,
+    EventControllerScrollFlags, EventSequenceState, GestureClick, GestureDrag, GestureLongPress,
+    GestureRotate, GestureZoom, PropagationPhase, ScrolledWindow, Widget,
 };
 use once_cell::sync::Lazy;
-use rnote_compose::penevent::ShortcutKey;
+use p2d::query::PointQuery;
+use rnote_compose::penevent::{PenProgress, ShortcutKey};
 use rnote_engine::ext::GraphenePointExt;
+use rnote_engine::pens::deviceprofiles::{InputDevice, InputDeviceType};
+use rnote_engine::pens::PenStyle;
 use rnote_engine::Camera;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
@@ -19,6 +22,8 @@ struct Connections {
     appwindow_block_pinch_zoom_bind: Option<glib::Binding>,
     appwindow_show_scrollbars_bind: Option<glib::Binding>,
     appwindow_inertial_scrolling_bind: Option<glib::Binding>,
+    appwindow_scroll_step_bind: Option<glib::Binding>,
+    appwindow_zoom_scroll_step_bind: Option<glib::Binding>,
     appwindow_righthanded_bind: Option<glib::Binding>,
 }
 
@@ -33,18 +38,30 @@ mod imp {
         pub(crate) show_scrollbars: Cell<bool>,
         pub(crate) block_pinch_zoom: Cell<bool>,
         pub(crate) inertial_scrolling: Cell<bool>,
+        /// The distance in surface coordinates that the canvas is scrolled by with one mouse wheel step.
+        pub(crate) scroll_step: Cell<f64>,
+        /// The fraction the zoom changes by with one mouse wheel step while holding <ctrl>.
+        pub(crate) zoom_scroll_step: Cell<f64>,
         pub(crate) pointer_pos: Cell<Option<na::Vector2<f64>>>,
         pub(crate) last_contextmenu_pos: Cell<Option<na::Vector2<f64>>>,
 
         pub(crate) pointer_motion_controller: EventControllerMotion,
         pub(crate) canvas_drag_gesture: GestureDrag,
         pub(crate) canvas_zoom_gesture: GestureZoom,
+        pub(crate) canvas_rotate_gesture: GestureRotate,
         pub(crate) canvas_zoom_scroll_controller: EventControllerScroll,
+        pub(crate) canvas_eraser_width_scroll_controller: EventControllerScroll,
+        pub(crate) canvas_wheel_scroll_controller: EventControllerScroll,
         pub(crate) canvas_mouse_drag_middle_gesture: GestureDrag,
+        pub(crate) canvas_space_key_controller: EventControllerKey,
+        pub(crate) canvas_space_drag_gesture: GestureDrag,
+        /// Whether the space bar is held down for panning.
+        pub(crate) space_panning: Cell<bool>,
         pub(crate) canvas_alt_drag_gesture: GestureDrag,
         pub(crate) canvas_alt_shift_drag_gesture: GestureDrag,
         pub(crate) touch_two_finger_long_press_gesture: GestureLongPress,
         pub(crate) touch_long_press_gesture: GestureLongPress,
+        pub(crate) contextmenu_click_gesture: GestureClick,
 
         #[template_child]
         pub(crate) scroller: TemplateChild<ScrolledWindow>,
@@ -52,6 +69,8 @@ mod imp {
         pub(crate) canvas: TemplateChild<RnCanvas>,
         #[template_child]
         pub(crate) contextmenu: TemplateChild<RnContextMenu>,
+        #[template_child]
+        pub(crate) radialmenu: TemplateChild<RnRadialMenu>,
     }
 
     impl Default for RnCanvasWrapper {
@@ -75,12 +94,32 @@ mod imp {
                 .propagation_phase(PropagationPhase::Capture)
                 .build();
 
+            let canvas_rotate_gesture = GestureRotate::builder()
+                .name("canvas_rotate_gesture")
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             let canvas_zoom_scroll_controller = EventControllerScroll::builder()
                 .name("canvas_zoom_scroll_controller")
                 .propagation_phase(PropagationPhase::Bubble)
                 .flags(EventControllerScrollFlags::VERTICAL)
                 .build();
 
+            // Needs to be in the capture phase, so that the scroll is handled before the scrolled window scrolls
+            let canvas_eraser_width_scroll_controller = EventControllerScroll::builder()
+                .name("canvas_eraser_width_scroll_controller")
+                .propagation_phase(PropagationPhase::Capture)
+                .flags(EventControllerScrollFlags::VERTICAL)
+                .build();
+
+            // Needs to be in the capture phase as well, so that mouse wheel steps are scrolled by the configured step
+            // instead of the scrolled window's own.
+            let canvas_wheel_scroll_controller = EventControllerScroll::builder()
+                .name("canvas_wheel_scroll_controller")
+                .propagation_phase(PropagationPhase::Capture)
+                .flags(EventControllerScrollFlags::BOTH_AXES)
+                .build();
+
             let canvas_mouse_drag_middle_gesture = GestureDrag::builder()
                 .name("canvas_mouse_drag_middle_gesture")
                 .button(gdk::BUTTON_MIDDLE)
@@ -88,6 +127,21 @@ mod imp {
                 .propagation_phase(PropagationPhase::Bubble)
                 .build();
 
+            // Holding the space bar switches to panning, independent of the current pen.
+            // Needs to be in the capture phase, so that the key is not forwarded to the pen.
+            let canvas_space_key_controller = EventControllerKey::builder()
+                .name("canvas_space_key_controller")
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
+            // space + drag for panning with pointer
+            let canvas_space_drag_gesture = GestureDrag::builder()
+                .name("canvas_space_drag_gesture")
+                .button(gdk::BUTTON_PRIMARY)
+                .exclusive(true)
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             // alt + drag for panning with pointer
             let canvas_alt_drag_gesture = GestureDrag::builder()
                 .name("canvas_alt_drag_gesture")
@@ -118,28 +172,47 @@ mod imp {
                 .touch_only(true)
                 .build();
 
+            // Secondary click on a misspelled word while editing text shows spelling suggestions,
+            // on a table cell, sticky note or the selection while the selector is active their actions.
+            // Needs to be in the capture phase, so that it can be claimed before the click is handled as pen shortcut.
+            let contextmenu_click_gesture = GestureClick::builder()
+                .name("contextmenu_click_gesture")
+                .button(gdk::BUTTON_SECONDARY)
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             Self {
                 connections: RefCell::new(Connections::default()),
                 canvas_touch_drawing_handler: RefCell::new(None),
                 show_scrollbars: Cell::new(false),
                 block_pinch_zoom: Cell::new(false),
                 inertial_scrolling: Cell::new(true),
+                scroll_step: Cell::new(RnCanvas::SCROLL_STEP_DEFAULT),
+                zoom_scroll_step: Cell::new(RnCanvas::ZOOM_SCROLL_STEP),
                 pointer_pos: Cell::new(None),
                 last_contextmenu_pos: Cell::new(None),
 
                 pointer_motion_controller,
                 canvas_drag_gesture,
                 canvas_zoom_gesture,
+                canvas_rotate_gesture,
                 canvas_zoom_scroll_controller,
+                canvas_eraser_width_scroll_controller,
+                canvas_wheel_scroll_controller,
                 canvas_mouse_drag_middle_gesture,
+                canvas_space_key_controller,
+                canvas_space_drag_gesture,
+                space_panning: Cell::new(false),
                 canvas_alt_drag_gesture,
                 canvas_alt_shift_drag_gesture,
                 touch_two_finger_long_press_gesture,
                 touch_long_press_gesture,
+                contextmenu_click_gesture,
 
                 scroller: TemplateChild::<ScrolledWindow>::default(),
                 canvas: TemplateChild::<RnCanvas>::default(),
                 contextmenu: TemplateChild::<RnContextMenu>::default(),
+                radialmenu: TemplateChild::<RnRadialMenu>::default(),
             }
         }
     }
@@ -171,10 +244,20 @@ mod imp {
                 .add_controller(self.canvas_drag_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_zoom_gesture.clone());
+            self.scroller
+                .add_controller(self.canvas_rotate_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_zoom_scroll_controller.clone());
+            self.scroller
+                .add_controller(self.canvas_eraser_width_scroll_controller.clone());
+            self.scroller
+                .add_controller(self.canvas_wheel_scroll_controller.clone());
             self.scroller
                 .add_controller(self.canvas_mouse_drag_middle_gesture.clone());
+            self.scroller
+                .add_controller(self.canvas_space_key_controller.clone());
+            self.scroller
+                .add_controller(self.canvas_space_drag_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_alt_drag_gesture.clone());
             self.scroller
@@ -183,10 +266,14 @@ mod imp {
                 .add_controller(self.touch_two_finger_long_press_gesture.clone());
             self.canvas
                 .add_controller(self.touch_long_press_gesture.clone());
+            self.canvas
+                .add_controller(self.contextmenu_click_gesture.clone());
 
             // group
             self.touch_two_finger_long_press_gesture
                 .group_with(&self.canvas_zoom_gesture);
+            self.canvas_rotate_gesture
+                .group_with(&self.canvas_zoom_gesture);
 
             self.setup_input();
 
@@ -236,6 +323,16 @@ mod imp {
                     glib::ParamSpecBoolean::builder("inertial-scrolling")
                         .default_value(true)
                         .build(),
+                    glib::ParamSpecDouble::builder("scroll-step")
+                        .minimum(1.0)
+                        .maximum(f64::MAX)
+                        .default_value(RnCanvas::SCROLL_STEP_DEFAULT)
+                        .build(),
+                    glib::ParamSpecDouble::builder("zoom-scroll-step")
+                        .minimum(0.01)
+                        .maximum(0.9)
+                        .default_value(RnCanvas::ZOOM_SCROLL_STEP)
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -246,6 +343,8 @@ mod imp {
                 "show-scrollbars" => self.show_scrollbars.get().to_value(),
                 "block-pinch-zoom" => self.block_pinch_zoom.get().to_value(),
                 "inertial-scrolling" => self.inertial_scrolling.get().to_value(),
+                "scroll-step" => self.scroll_step.get().to_value(),
+                "zoom-scroll-step" => self.zoom_scroll_step.get().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -276,6 +375,18 @@ mod imp {
                     self.inertial_scrolling.replace(inertial_scrolling);
                     self.canvas_kinetic_scrolling_update();
                 }
+                "scroll-step" => {
+                    let scroll_step = value
+                        .get::<f64>()
+                        .expect("The value needs to be of type `f64`");
+                    self.scroll_step.replace(scroll_step);
+                }
+                "zoom-scroll-step" => {
+                    let zoom_scroll_step = value
+                        .get::<f64>()
+                        .expect("The value needs to be of type `f64`");
+                    self.zoom_scroll_step.replace(zoom_scroll_step);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -285,13 +396,13 @@ mod imp {
 
     impl RnCanvasWrapper {
         fn canvas_zoom_gesture_update(&self) {
-            if !self.block_pinch_zoom.get() && !self.canvas.touch_drawing() {
-                self.canvas_zoom_gesture
-                    .set_propagation_phase(PropagationPhase::Capture);
+            let phase = if !self.block_pinch_zoom.get() && !self.canvas.touch_drawing() {
+                PropagationPhase::Capture
             } else {
-                self.canvas_zoom_gesture
-                    .set_propagation_phase(PropagationPhase::None);
-            }
+                PropagationPhase::None
+            };
+            self.canvas_zoom_gesture.set_propagation_phase(phase);
+            self.canvas_rotate_gesture.set_propagation_phase(phase);
         }
 
         fn canvas_kinetic_scrolling_update(&self) {
@@ -309,6 +420,22 @@ mod imp {
                     obj,
                     move |_, x, y| {
                         canvaswrapper.imp().pointer_pos.set(Some(na::vector![x, y]));
+
+                        let canvas = canvaswrapper.canvas();
+                        let Some(surface_pos) = canvaswrapper
+                            .compute_point(&canvas, &graphene::Point::new(x as f32, y as f32))
+                        else {
+                            return;
+                        };
+                        let doc_pos = canvas
+                            .engine_ref()
+                            .camera
+                            .transform()
+                            .inverse_transform_point(&surface_pos.to_na_vec().into())
+                            .coords;
+                        let widget_flags = canvas.engine_mut().set_cursor_pos(Some(doc_pos));
+                        canvas.emit_handle_widget_flags(widget_flags);
+                        canvas.collab_send_cursor();
                     }
                 ));
 
@@ -317,6 +444,11 @@ mod imp {
                     obj,
                     move |_| {
                         canvaswrapper.imp().pointer_pos.set(None);
+
+                        let canvas = canvaswrapper.canvas();
+                        let widget_flags = canvas.engine_mut().set_cursor_pos(None);
+                        canvas.emit_handle_widget_flags(widget_flags);
+                        canvas.collab_send_cursor();
                     }
                 ));
             }
@@ -348,7 +480,8 @@ mod imp {
                         }
                         let canvas = canvaswrapper.canvas();
                         let old_zoom = canvas.engine_ref().camera.total_zoom();
-                        let new_zoom = old_zoom * (1.0 - dy * RnCanvas::ZOOM_SCROLL_STEP);
+                        let new_zoom =
+                            old_zoom * (1.0 - dy * canvaswrapper.imp().zoom_scroll_step.get());
 
                         if (Camera::ZOOM_MIN..=Camera::ZOOM_MAX).contains(&new_zoom) {
                             let camera_offset = canvas.engine_ref().camera.offset();
@@ -364,6 +497,11 @@ mod imp {
                                     p.to_na_vec()
                                 })
                                 .unwrap_or_else(|| camera_size * 0.5);
+                            // The zoom is anchored in the coordinate space of the offset, which is not rotated
+                            let screen_offset = canvas
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_pos(screen_offset);
                             let new_camera_offset = (((camera_offset + screen_offset) / old_zoom)
                                 * new_zoom)
                                 - screen_offset;
@@ -380,6 +518,71 @@ mod imp {
                 ));
             }
 
+            // change the eraser width with <alt> + scroll, or by scrolling while erasing
+            {
+                self.canvas_eraser_width_scroll_controller
+                    .connect_scroll(clone!(
+                        #[weak(rename_to=canvaswrapper)]
+                        obj,
+                        #[upgrade_or]
+                        glib::Propagation::Proceed,
+                        move |controller, _, dy| {
+                            let canvas = canvaswrapper.canvas();
+                            let (eraser_active, erasing) = {
+                                let engine = canvas.engine_ref();
+                                (
+                                    engine.penholder.current_pen_style_w_override()
+                                        == PenStyle::Eraser,
+                                    engine.penholder.current_pen_progress()
+                                        == PenProgress::InProgress,
+                                )
+                            };
+                            if !eraser_active
+                                || (controller.current_event_state() != gdk::ModifierType::ALT_MASK
+                                    && !erasing)
+                            {
+                                return glib::Propagation::Proceed;
+                            }
+                            let widget_flags = canvas.engine_mut().eraser_adjust_width(-dy);
+                            canvas.emit_handle_widget_flags(widget_flags);
+                            glib::Propagation::Stop
+                        }
+                    ));
+            }
+
+            // scrolling with the mouse wheel, horizontally with <shift> + scroll.
+            // Smooth scrolling from touchpads is left to the scrolled window, which handles it natively.
+            {
+                self.canvas_wheel_scroll_controller.connect_scroll(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    #[upgrade_or]
+                    glib::Propagation::Proceed,
+                    move |controller, dx, dy| {
+                        let modifiers = controller.current_event_state();
+                        if controller.unit() != gdk::ScrollUnit::Wheel
+                            || modifiers.intersects(
+                                gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::ALT_MASK,
+                            )
+                        {
+                            return glib::Propagation::Proceed;
+                        }
+                        let (dx, dy) = if modifiers.contains(gdk::ModifierType::SHIFT_MASK) {
+                            (dx + dy, 0.0)
+                        } else {
+                            (dx, dy)
+                        };
+                        let scroll_step = canvaswrapper.imp().scroll_step.get();
+                        let canvas = canvaswrapper.canvas();
+                        let hadj = canvas.hadjustment().unwrap();
+                        let vadj = canvas.vadjustment().unwrap();
+                        hadj.set_value(hadj.value() + dx * scroll_step);
+                        vadj.set_value(vadj.value() + dy * scroll_step);
+                        glib::Propagation::Stop
+                    }
+                ));
+            }
+
             // Drag canvas gesture
             {
                 let touch_drag_start = Rc::new(Cell::new(na::vector![0.0, 0.0]));
@@ -406,7 +609,11 @@ mod imp {
                     obj,
                     move |_, x, y| {
                         let canvas = canvaswrapper.canvas();
-                        let new_offset = touch_drag_start.get() - na::vector![x, y];
+                        let delta = canvas
+                            .engine_ref()
+                            .camera
+                            .unrotate_surface_vec(na::vector![x, y]);
+                        let new_offset = touch_drag_start.get() - delta;
                         let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
                         canvas.emit_handle_widget_flags(widget_flags);
                     }
@@ -439,6 +646,7 @@ mod imp {
                         move |_, _, _| {
                             mouse_drag_start
                                 .set(canvaswrapper.canvas().engine_ref().camera.offset());
+                            canvaswrapper.canvas().set_panning_cursor(Some("grabbing"));
                         }
                     ));
                 self.canvas_mouse_drag_middle_gesture
@@ -449,7 +657,11 @@ mod imp {
                         obj,
                         move |_, x, y| {
                             let canvas = canvaswrapper.canvas();
-                            let new_offset = mouse_drag_start.get() - na::vector![x, y];
+                            let delta = canvas
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_vec(na::vector![x, y]);
+                            let new_offset = mouse_drag_start.get() - delta;
                             let widget_flags =
                                 canvas.engine_mut().camera_set_offset_expand(new_offset);
                             canvas.emit_handle_widget_flags(widget_flags);
@@ -460,6 +672,9 @@ mod imp {
                         #[weak(rename_to=canvaswrapper)]
                         obj,
                         move |_, _, _| {
+                            canvaswrapper.canvas().set_panning_cursor(
+                                canvaswrapper.imp().space_panning.get().then_some("grab"),
+                            );
                             let widget_flags = canvaswrapper
                                 .canvas()
                                 .engine_mut()
@@ -471,6 +686,99 @@ mod imp {
                     ));
             }
 
+            // Move Canvas while holding the space bar
+            {
+                self.canvas_space_key_controller.connect_key_pressed(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    #[upgrade_or]
+                    glib::Propagation::Proceed,
+                    move |_, key, _, modifiers| {
+                        if key != gdk::Key::space || !modifiers.is_empty() {
+                            return glib::Propagation::Proceed;
+                        }
+                        if canvaswrapper.imp().space_panning.get() {
+                            // Key repeat
+                            return glib::Propagation::Stop;
+                        }
+                        let canvas = canvaswrapper.canvas();
+                        {
+                            // The space bar is needed for typing text
+                            let engine = canvas.engine_ref();
+                            if engine.penholder.current_pen_style_w_override()
+                                == PenStyle::Typewriter
+                                || engine.penholder.current_pen_progress() != PenProgress::Idle
+                            {
+                                return glib::Propagation::Proceed;
+                            }
+                        }
+                        canvaswrapper.imp().space_panning.set(true);
+                        canvas.set_panning_cursor(Some("grab"));
+                        glib::Propagation::Stop
+                    }
+                ));
+                self.canvas_space_key_controller
+                    .connect_key_released(clone!(
+                        #[weak(rename_to=canvaswrapper)]
+                        obj,
+                        move |_, key, _, _| {
+                            if key != gdk::Key::space || !canvaswrapper.imp().space_panning.get() {
+                                return;
+                            }
+                            canvaswrapper.imp().space_panning.set(false);
+                            if !canvaswrapper.imp().canvas_space_drag_gesture.is_active() {
+                                canvaswrapper.canvas().set_panning_cursor(None);
+                            }
+                        }
+                    ));
+
+                let offset_start = Rc::new(Cell::new(na::Vector2::<f64>::zeros()));
+
+                self.canvas_space_drag_gesture.connect_drag_begin(clone!(
+                    #[strong]
+                    offset_start,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |gesture, _, _| {
+                        if canvaswrapper.imp().space_panning.get() {
+                            gesture.set_state(EventSequenceState::Claimed);
+                            offset_start.set(canvaswrapper.canvas().engine_ref().camera.offset());
+                            canvaswrapper.canvas().set_panning_cursor(Some("grabbing"));
+                        } else {
+                            gesture.set_state(EventSequenceState::Denied);
+                        }
+                    }
+                ));
+                self.canvas_space_drag_gesture.connect_drag_update(clone!(
+                    #[strong]
+                    offset_start,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, offset_x, offset_y| {
+                        let canvas = canvaswrapper.canvas();
+                        let delta = canvas
+                            .engine_ref()
+                            .camera
+                            .unrotate_surface_vec(na::vector![offset_x, offset_y]);
+                        let new_offset = offset_start.get() - delta;
+                        let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
+                        canvas.emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+                self.canvas_space_drag_gesture.connect_drag_end(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, _, _| {
+                        let canvas = canvaswrapper.canvas();
+                        canvas.set_panning_cursor(
+                            canvaswrapper.imp().space_panning.get().then_some("grab"),
+                        );
+                        let widget_flags = canvas.engine_mut().update_rendering_current_viewport();
+                        canvas.emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+            }
+
             // Canvas gesture zooming with dragging
             {
                 let prev_scale = Rc::new(Cell::new(1_f64));
@@ -500,11 +808,14 @@ mod imp {
                         new_zoom.set(current_zoom);
                         prev_scale.set(1.0);
 
-                        bbcenter_begin.set(
-                            gesture
-                                .bounding_box_center()
-                                .map(|coords| na::vector![coords.0, coords.1]),
-                        );
+                        // The bounding box centers are unrotated into the coordinate space of the offset
+                        bbcenter_begin.set(gesture.bounding_box_center().map(|coords| {
+                            canvaswrapper
+                                .canvas()
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_pos(na::vector![coords.0, coords.1])
+                        }));
                         offset_begin.set(canvaswrapper.canvas().engine_ref().camera.offset());
                     }
                 ));
@@ -534,9 +845,13 @@ mod imp {
 
                         let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom.get());
 
-                        if let Some(bbcenter_current) = gesture
-                            .bounding_box_center()
-                            .map(|coords| na::vector![coords.0, coords.1])
+                        if let Some(bbcenter_current) =
+                            gesture.bounding_box_center().map(|coords| {
+                                canvas
+                                    .engine_ref()
+                                    .camera
+                                    .unrotate_surface_pos(na::vector![coords.0, coords.1])
+                            })
                         {
                             let bbcenter_begin = if let Some(bbcenter_begin) = bbcenter_begin.get()
                             {
@@ -588,6 +903,47 @@ mod imp {
                 ));
             }
 
+            // Rotate the view with a two finger twist
+            {
+                let rotation_begin = Rc::new(Cell::new(0_f64));
+                let rotating = Rc::new(Cell::new(false));
+
+                self.canvas_rotate_gesture.connect_begin(clone!(
+                    #[strong]
+                    rotation_begin,
+                    #[strong]
+                    rotating,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, _| {
+                        rotation_begin.set(canvaswrapper.canvas().engine_ref().camera.rotation());
+                        rotating.set(false);
+                    }
+                ));
+
+                self.canvas_rotate_gesture.connect_angle_changed(clone!(
+                    #[strong]
+                    rotation_begin,
+                    #[strong]
+                    rotating,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, _, angle_delta| {
+                        // Only start rotating after a distinct twist, so that pinch zooming does not rotate the view by accident
+                        if !rotating.get() && angle_delta.abs() < RnCanvas::TOUCH_ROTATION_THRESHOLD
+                        {
+                            return;
+                        }
+                        rotating.set(true);
+                        let canvas = canvaswrapper.canvas();
+                        let widget_flags = canvas
+                            .engine_mut()
+                            .camera_set_rotation(rotation_begin.get() + angle_delta);
+                        canvas.emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+            }
+
             // Pan with alt + drag
             {
                 let offset_start = Rc::new(Cell::new(na::Vector2::<f64>::zeros()));
@@ -617,7 +973,11 @@ mod imp {
                     obj,
                     move |_, offset_x, offset_y| {
                         let canvas = canvaswrapper.canvas();
-                        let new_offset = offset_start.get() - na::vector![offset_x, offset_y];
+                        let delta = canvas
+                            .engine_ref()
+                            .camera
+                            .unrotate_surface_vec(na::vector![offset_x, offset_y]);
+                        let new_offset = offset_start.get() - delta;
                         let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
                         canvas.emit_handle_widget_flags(widget_flags);
                     }
@@ -723,12 +1083,16 @@ mod imp {
                     .connect_pressed(clone!(
                         #[weak(rename_to=canvaswrapper)]
                         obj,
-                        move |_gesture, _, _| {
+                        move |gesture, _, _| {
+                            let input_device = gesture.device().map(|device| {
+                                InputDevice::new(InputDeviceType::Touchscreen, device.name())
+                            });
                             let (_, widget_flags) = canvaswrapper
                                 .canvas()
                                 .engine_mut()
                                 .handle_pressed_shortcut_key(
                                     ShortcutKey::TouchTwoFingerLongPress,
+                                    input_device.as_ref(),
                                     Instant::now(),
                                 );
                             canvaswrapper
@@ -744,14 +1108,22 @@ mod imp {
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |_gesture, x, y| {
-                        let popover = canvaswrapper.contextmenu().popover();
-                        canvaswrapper
-                            .imp()
-                            .last_contextmenu_pos
-                            .set(Some(na::vector![x, y]));
-                        popover
-                            .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
-                        popover.popup();
+                        let suggestions = canvaswrapper.spellcheck_suggestions_at(x, y);
+                        canvaswrapper.popup_contextmenu(x, y, suggestions.as_deref());
+                    }
+                ));
+
+                self.contextmenu_click_gesture.connect_pressed(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |gesture, _, x, y| {
+                        if let Some(suggestions) = canvaswrapper.spellcheck_suggestions_at(x, y) {
+                            gesture.set_state(EventSequenceState::Claimed);
+                            canvaswrapper.popup_contextmenu(x, y, Some(&suggestions));
+                        } else if canvaswrapper.object_actions_available_at(x, y) {
+                            gesture.set_state(EventSequenceState::Claimed);
+                            canvaswrapper.popup_contextmenu(x, y, None);
+                        }
                     }
                 ));
             }
@@ -804,6 +1176,94 @@ impl RnCanvasWrapper {
         self.set_property("inertial-scrolling", inertial_scrolling);
     }
 
+    #[allow(unused)]
+    pub(crate) fn scroll_step(&self) -> f64 {
+        self.property::<f64>("scroll-step")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_scroll_step(&self, scroll_step: f64) {
+        self.set_property("scroll-step", scroll_step);
+    }
+
+    #[allow(unused)]
+    pub(crate) fn zoom_scroll_step(&self) -> f64 {
+        self.property::<f64>("zoom-scroll-step")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_zoom_scroll_step(&self, zoom_scroll_step: f64) {
+        self.set_property("zoom-scroll-step", zoom_scroll_step);
+    }
+
+    /// Spelling suggestions for a misspelled word at the given position in canvas coordinates.
+    ///
+    /// Returns `None` if there is no misspelled word at the position.
+    fn spellcheck_suggestions_at(&self, x: f64, y: f64) -> Option<Vec<String>> {
+        let canvas = self.canvas();
+        let pos = (canvas.engine_ref().camera.transform().inverse() * na::point![x, y]).coords;
+        let suggestions = canvas.engine_ref().spellcheck_suggestions_at(pos);
+        suggestions
+    }
+
+    /// Whether the selector is active and there is a table cell, sticky note, mind map, board, frame or the selection
+    /// at the given position in canvas coordinates.
+    fn object_actions_available_at(&self, x: f64, y: f64) -> bool {
+        let canvas = self.canvas();
+        let engine = canvas.engine_ref();
+        let pos = (engine.camera.transform().inverse() * na::point![x, y]).coords;
+        engine.penholder.current_pen_style_w_override() == PenStyle::Selector
+            && (engine.table_cell_exists_at(pos)
+                || engine.sticky_note_text_at(pos).is_some()
+                || engine.mind_map_outline_at(pos).is_some()
+                || engine.board_columns_at(pos).is_some()
+                || engine.frame_name_at(pos).is_some()
+                || self.selection_at(pos))
+    }
+
+    /// Whether the selector is active and the position in document coordinates is inside the selection bounds.
+    fn selection_at(&self, doc_pos: na::Vector2<f64>) -> bool {
+        let canvas = self.canvas();
+        let engine = canvas.engine_ref();
+        engine.penholder.current_pen_style_w_override() == PenStyle::Selector
+            && engine
+                .selection_bounds()
+                .is_some_and(|bounds| bounds.contains_local_point(&doc_pos.into()))
+    }
+
+    /// Pop up the context menu at the given position in canvas coordinates.
+    fn popup_contextmenu(&self, x: f64, y: f64, spellcheck_suggestions: Option<&[String]>) {
+        let contextmenu = self.contextmenu();
+        let popover = contextmenu.popover();
+        contextmenu.set_spellcheck_suggestions(spellcheck_suggestions);
+        let canvas = self.canvas();
+        let doc_pos = (canvas.engine_ref().camera.transform().inverse() * na::point![x, y]).coords;
+        contextmenu.set_table_actions_visible(canvas.engine_ref().table_cell_exists_at(doc_pos));
+        contextmenu.set_sticky_note_actions_visible(
+            canvas.engine_ref().sticky_note_text_at(doc_pos).is_some(),
+        );
+        contextmenu.set_mind_map_actions_visible(
+            canvas.engine_ref().mind_map_outline_at(doc_pos).is_some(),
+        );
+        contextmenu
+            .set_board_actions_visible(canvas.engine_ref().board_columns_at(doc_pos).is_some());
+        contextmenu.set_frame_actions_visible(canvas.engine_ref().frame_name_at(doc_pos).is_some());
+        let selection_at = self.selection_at(doc_pos);
+        contextmenu.set_selection_actions_visible(selection_at);
+        contextmenu.set_canvas_actions_visible(
+            spellcheck_suggestions.is_none()
+                && !selection_at
+                && !canvas.engine_ref().table_cell_exists_at(doc_pos)
+                && canvas.engine_ref().sticky_note_text_at(doc_pos).is_none()
+                && canvas.engine_ref().mind_map_outline_at(doc_pos).is_none()
+                && canvas.engine_ref().board_columns_at(doc_pos).is_none()
+                && canvas.engine_ref().frame_name_at(doc_pos).is_none(),
+        );
+        self.imp().last_contextmenu_pos.set(Some(na::vector![x, y]));
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
+        popover.popup();
+    }
+
     pub(crate) fn last_contextmenu_pos(&self) -> Option<na::Vector2<f64>> {
         self.imp().last_contextmenu_pos.get()
     }
@@ -820,6 +1280,31 @@ impl RnCanvasWrapper {
         self.imp().contextmenu.get()
     }
 
+    pub(crate) fn radialmenu(&self) -> RnRadialMenu {
+        self.imp().radialmenu.get()
+    }
+
+    /// Open the context menu at the pointer position, or in the center if the pointer is not above the canvas.
+    pub(crate) fn popup_contextmenu_at_pointer(&self) {
+        let pos = self
+            .imp()
+            .pointer_pos
+            .get()
+            .unwrap_or_else(|| na::vector![self.width() as f64, self.height() as f64] * 0.5);
+        let suggestions = self.spellcheck_suggestions_at(pos[0], pos[1]);
+        self.popup_contextmenu(pos[0], pos[1], suggestions.as_deref());
+    }
+
+    /// Open the radial menu at the pointer position, or in the center if the pointer is not above the canvas.
+    pub(crate) fn popup_radial_menu(&self, appwindow: &RnAppWindow) {
+        let pos = self
+            .imp()
+            .pointer_pos
+            .get()
+            .unwrap_or_else(|| na::vector![self.width() as f64, self.height() as f64] * 0.5);
+        self.radialmenu().popup_at(appwindow, pos);
+    }
+
     /// Initializes for the given appwindow. Usually `init()` is only called once,
     /// but because this widget can be moved across appwindows through tabs,
     /// this function also disconnects and replaces all existing old connections
@@ -849,6 +1334,24 @@ impl RnCanvasWrapper {
             .sync_create()
             .build();
 
+        let appwindow_scroll_step_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .general_scroll_step_row()
+            .bind_property("value", self, "scroll-step")
+            .sync_create()
+            .build();
+
+        // The setting is in percent
+        let appwindow_zoom_scroll_step_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .general_zoom_scroll_step_row()
+            .bind_property("value", self, "zoom-scroll-step")
+            .transform_to(|_, percent: f64| Some(percent / 100.0))
+            .sync_create()
+            .build();
+
         let appwindow_righthanded_bind = appwindow
             .bind_property("righthanded", &self.scroller(), "window-placement")
             .transform_to(|_, righthanded: bool| {
@@ -880,6 +1383,18 @@ impl RnCanvasWrapper {
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_scroll_step_bind
+            .replace(appwindow_scroll_step_bind)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections
+            .appwindow_zoom_scroll_step_bind
+            .replace(appwindow_zoom_scroll_step_bind)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_righthanded_bind
             .replace(appwindow_righthanded_bind)
@@ -905,6 +1420,12 @@ impl RnCanvasWrapper {
         if let Some(old) = connections.appwindow_inertial_scrolling_bind.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_scroll_step_bind.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.appwindow_zoom_scroll_step_bind.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_righthanded_bind.take() {
             old.unbind();
         }
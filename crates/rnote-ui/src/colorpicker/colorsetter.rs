@@ -126,6 +126,11 @@ mod imp {
             self.obj()
                 .style_context()
                 .add_provider(&css, gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION);
+            // The setters only show the color, describe it for screen readers
+            self.obj()
+                .update_property(&[gtk4::accessible::Property::Label(
+                    &crate::utils::color_to_hsv_label_string(color),
+                )]);
 
             self.obj().queue_draw();
         }
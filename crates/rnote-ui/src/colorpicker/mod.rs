@@ -16,6 +16,7 @@ use once_cell::sync::Lazy;
 use rnote_compose::{color, Color};
 use rnote_engine::ext::GdkRGBAExt;
 use std::cell::RefCell;
+use std::str::FromStr;
 use tracing::debug;
 
 mod imp {
@@ -57,6 +58,10 @@ mod imp {
         #[template_child]
         pub(crate) colordialog_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) document_palette_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) document_palette_setter_box: TemplateChild<gtk4::Box>,
+        #[template_child]
         pub(crate) active_color_label: TemplateChild<Label>,
     }
 
@@ -83,6 +88,8 @@ mod imp {
                 setter_8: TemplateChild::default(),
                 setter_9: TemplateChild::default(),
                 colordialog_button: TemplateChild::default(),
+                document_palette_box: TemplateChild::default(),
+                document_palette_setter_box: TemplateChild::default(),
                 active_color_label: TemplateChild::default(),
             }
         }
@@ -386,7 +393,7 @@ mod imp {
             });
         }
 
-        fn default_color(i: usize) -> gdk::RGBA {
+        pub(super) fn default_color(i: usize) -> gdk::RGBA {
             match i {
                 0 => gdk::RGBA::new(0.0, 0.0, 0.0, 1.0),
                 1 => gdk::RGBA::new(1.0, 1.0, 1.0, 1.0),
@@ -425,6 +432,44 @@ pub(crate) static STROKE_COLOR_DEFAULT: Lazy<Color> =
 pub(crate) static FILL_COLOR_DEFAULT: Lazy<Color> =
     Lazy::new(|| Color::from(color::GNOME_BLUES[1]));
 
+/// The built-in palettes for the color setters that don't hold black, white or transparent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetterPalette {
+    Default,
+    /// Color-blind safe.
+    OkabeIto,
+    /// Color-blind safe.
+    TolBright,
+}
+
+impl FromStr for SetterPalette {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "okabe-ito" => Ok(Self::OkabeIto),
+            "tol-bright" => Ok(Self::TolBright),
+            s => Err(anyhow::anyhow!(
+                "SetterPalette from_string failed, invalid name: {s}"
+            )),
+        }
+    }
+}
+
+impl SetterPalette {
+    fn colors(self) -> [gdk::RGBA; 6] {
+        let from_piet = |c: piet::Color| gdk::RGBA::from_compose_color(Color::from(c));
+        match self {
+            Self::Default => std::array::from_fn(|i| imp::RnColorPicker::default_color(i + 3)),
+            // Orange is left out, it is hard to tell apart from vermillion and yellow
+            Self::OkabeIto => [1, 4, 2, 3, 5, 6].map(|i| from_piet(color::OKABE_ITO[i])),
+            // Grey is left out
+            Self::TolBright => [4, 0, 2, 3, 1, 5].map(|i| from_piet(color::TOL_BRIGHT[i])),
+        }
+    }
+}
+
 impl RnColorPicker {
     pub(crate) fn new() -> Self {
         glib::Object::new()
@@ -522,6 +567,50 @@ impl RnColorPicker {
         );
     }
 
+    /// Replace the colors of the document palette. The palette is hidden when it is empty.
+    pub(crate) fn set_document_palette(&self, colors: &[Color]) {
+        let setter_box = self.imp().document_palette_setter_box.get();
+        while let Some(child) = setter_box.first_child() {
+            setter_box.remove(&child);
+        }
+        for color in colors {
+            let setter = RnColorSetter::new();
+            setter.set_color(gdk::RGBA::from_compose_color(*color));
+            setter.set_width_request(26);
+            setter.set_height_request(26);
+            setter.connect_clicked(clone!(
+                #[weak(rename_to=colorpicker)]
+                self,
+                move |setter| {
+                    // The palette colors are applied once and don't stay selected like the setters
+                    setter.set_active(false);
+                    colorpicker.deselect_setters();
+                    colorpicker.set_color_active_pad(setter.color());
+                }
+            ));
+            setter_box.append(&setter);
+        }
+        self.imp()
+            .document_palette_box
+            .set_visible(!colors.is_empty());
+    }
+
+    /// Replace the colors of the setters that don't hold black, white or transparent with the palette.
+    pub(crate) fn apply_setter_palette(&self, palette: SetterPalette) {
+        let imp = self.imp();
+        let setters = [
+            &imp.setter_4,
+            &imp.setter_5,
+            &imp.setter_6,
+            &imp.setter_7,
+            &imp.setter_8,
+            &imp.setter_9,
+        ];
+        for (setter, color) in setters.into_iter().zip(palette.colors()) {
+            setter.set_color(color);
+        }
+    }
+
     fn set_color_active_setter(&self, color: gdk::RGBA) {
         let imp = self.imp();
 
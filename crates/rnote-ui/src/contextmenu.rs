@@ -1,5 +1,6 @@
 // Imports
-use gtk4::{glib, prelude::*, subclass::prelude::*, CompositeTemplate, PopoverMenu, Widget};
+use gettextrs::gettext;
+use gtk4::{gio, glib, prelude::*, subclass::prelude::*, CompositeTemplate, PopoverMenu, Widget};
 
 mod imp {
     use super::*;
@@ -9,6 +10,22 @@ mod imp {
     pub(crate) struct RnContextMenu {
         #[template_child]
         pub(crate) popover: TemplateChild<PopoverMenu>,
+        #[template_child]
+        pub(crate) spellcheck_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) table_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) sticky_note_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) mind_map_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) board_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) frame_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) selection_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) canvas_section: TemplateChild<gio::Menu>,
     }
 
     #[glib::object_subclass]
@@ -66,4 +83,145 @@ impl RnContextMenu {
     pub(crate) fn popover(&self) -> PopoverMenu {
         self.imp().popover.get()
     }
+
+    /// Show the suggestions for a misspelled word at the top of the menu.
+    ///
+    /// `None` removes the suggestions.
+    pub(crate) fn set_spellcheck_suggestions(&self, suggestions: Option<&[String]>) {
+        const MAX_SUGGESTIONS: usize = 5;
+        let section = self.imp().spellcheck_section.get();
+        section.remove_all();
+
+        let Some(suggestions) = suggestions else {
+            return;
+        };
+        if suggestions.is_empty() {
+            // An item without an action is displayed insensitive
+            section.append(Some(&gettext("No Suggestions")), None);
+        }
+        for suggestion in suggestions.iter().take(MAX_SUGGESTIONS) {
+            let item = gio::MenuItem::new(Some(suggestion), None);
+            item.set_action_and_target_value(
+                Some("win.spellcheck-correct"),
+                Some(&suggestion.to_variant()),
+            );
+            section.append_item(&item);
+        }
+    }
+
+    /// Show the actions for editing a table cell, when the menu was opened above one.
+    pub(crate) fn set_table_actions_visible(&self, visible: bool) {
+        let section = self.imp().table_section.get();
+        section.remove_all();
+
+        if !visible {
+            return;
+        }
+        section.append(Some(&gettext("Edit Cell…")), Some("win.table-edit-cell"));
+        for (label, edit) in [
+            (gettext("Insert Row Above"), "insert-row-above"),
+            (gettext("Insert Row Below"), "insert-row-below"),
+            (gettext("Insert Column Left"), "insert-column-left"),
+            (gettext("Insert Column Right"), "insert-column-right"),
+            (gettext("Remove Row"), "remove-row"),
+            (gettext("Remove Column"), "remove-column"),
+        ] {
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(Some("win.table-edit"), Some(&edit.to_variant()));
+            section.append_item(&item);
+        }
+    }
+
+    /// Show the action for editing a sticky note, when the menu was opened above one.
+    pub(crate) fn set_sticky_note_actions_visible(&self, visible: bool) {
+        let section = self.imp().sticky_note_section.get();
+        section.remove_all();
+
+        if visible {
+            section.append(Some(&gettext("Edit Note…")), Some("win.sticky-note-edit"));
+        }
+    }
+
+    /// Show the actions for editing a mind map, when the menu was opened above one of its nodes.
+    pub(crate) fn set_mind_map_actions_visible(&self, visible: bool) {
+        let section = self.imp().mind_map_section.get();
+        section.remove_all();
+
+        if !visible {
+            return;
+        }
+        section.append(Some(&gettext("Edit Mind Map…")), Some("win.mind-map-edit"));
+        section.append(
+            Some(&gettext("Collapse or Expand Node")),
+            Some("win.mind-map-toggle-node"),
+        );
+    }
+
+    /// Show the action for editing a board, when the menu was opened above one.
+    pub(crate) fn set_board_actions_visible(&self, visible: bool) {
+        let section = self.imp().board_section.get();
+        section.remove_all();
+
+        if visible {
+            section.append(Some(&gettext("Edit Board…")), Some("win.board-edit"));
+        }
+    }
+
+    /// Show the action for renaming a frame, when the menu was opened above its border or name.
+    pub(crate) fn set_frame_actions_visible(&self, visible: bool) {
+        let section = self.imp().frame_section.get();
+        section.remove_all();
+
+        if visible {
+            section.append(Some(&gettext("Rename Frame…")), Some("win.frame-rename"));
+        }
+    }
+
+    /// Show the actions for the selection, when the menu was opened above it.
+    pub(crate) fn set_selection_actions_visible(&self, visible: bool) {
+        let section = self.imp().selection_section.get();
+        section.remove_all();
+
+        if !visible {
+            return;
+        }
+        for (label, action) in [
+            (gettext("Duplicate"), "win.selection-duplicate"),
+            (gettext("Delete"), "win.selection-trash"),
+            (gettext("Bring to Front"), "win.selection-bring-to-front"),
+            (gettext("Send to Back"), "win.selection-send-to-back"),
+            (gettext("Lock"), "win.selection-lock"),
+            (gettext("Add Frame…"), "win.selection-add-frame"),
+            (gettext("Export Selection…"), "win.export-selection"),
+            (gettext("Export as Figure…"), "win.export-selection-figure"),
+        ] {
+            section.append(Some(&label), Some(action));
+        }
+    }
+
+    /// Show the actions for inserting content at the menu position, when it was opened above empty canvas.
+    pub(crate) fn set_canvas_actions_visible(&self, visible: bool) {
+        let section = self.imp().canvas_section.get();
+        section.remove_all();
+
+        if !visible {
+            return;
+        }
+        for (label, action) in [
+            (gettext("Paste in Place"), "win.clipboard-paste-in-place"),
+            (gettext("Insert Image…"), "win.insert-image-contextmenu"),
+            (gettext("Insert Text"), "win.insert-text-contextmenu"),
+            (
+                gettext("Insert Date and Time"),
+                "win.insert-datetime-contextmenu",
+            ),
+            (gettext("Select All"), "win.selection-select-all"),
+            (
+                gettext("Page Background…"),
+                "win.page-background-contextmenu",
+            ),
+        ] {
+            section.append(Some(&label), Some(action));
+        }
+    }
 }
@@ -1,20 +1,28 @@
 //adw::ToolbarView is a replacement for adw::Dialog but not suitable for an async flow
 
 // Imports
+use crate::canvas::imexport::encrypt_if_needed;
 use crate::canvas::{self, RnCanvas};
 use crate::RnStrokeContentPreview;
 use crate::{config, RnAppWindow};
 use adw::prelude::*;
 use gettextrs::gettext;
-use gtk4::{gio, glib, glib::clone, Builder, Button, FileDialog, FileFilter, Label};
+use gtk4::{
+    gio, glib, glib::clone, Builder, Button, CheckButton, FileDialog, FileFilter, Label, ListBox,
+    StringList,
+};
 use num_traits::ToPrimitive;
 use rnote_compose::SplitOrder;
 use rnote_engine::document::Layout;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
-    SelectionExportFormat, SelectionExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, FigureExportFormat,
+    FigureSnippetMarkup, PageSelection, SelectionExportFormat, SelectionExportPrefs,
 };
+use rnote_engine::engine::progress::is_cancelled_err;
+use rnote_engine::engine::{EngineSnapshot, Progress};
+use rnote_engine::Engine;
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use tracing::{debug, error};
 
@@ -92,6 +100,67 @@ pub(crate) async fn dialog_save_doc_as(appwindow: &RnAppWindow, canvas: &RnCanva
     }
 }
 
+/// Saves a copy of the document to a new file, without changing the file of the open document.
+pub(crate) async fn dialog_save_doc_copy(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.rnote");
+    } else {
+        filter.add_mime_type("application/rnote");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("rnote");
+    }
+    filter.set_name(Some(&gettext(".rnote")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let initial_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(&(String::from(" - ") + &gettext("Copy") + ".rnote")),
+    );
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Save a Copy"))
+        .modal(true)
+        .accept_label(gettext("Save"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(&initial_name)
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            appwindow.overlays().progressbar_start_pulsing();
+
+            if let Err(e) = canvas.save_document_copy_to_file(&selected_file).await {
+                error!("Saving a copy of the document failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Saving a copy of the document failed"));
+                appwindow.overlays().progressbar_abort();
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Saved a copy of the document successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                appwindow.overlays().progressbar_finish();
+            }
+        }
+        Err(e) => {
+            debug!("Did not save a copy of the document (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
 pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &RnCanvas) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
@@ -105,14 +174,23 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         builder.object("export_doc_optimize_printing_row").unwrap();
     let export_format_row: adw::ComboRow = builder.object("export_doc_export_format_row").unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_page_order_row").unwrap();
+    let page_selection_row: adw::EntryRow =
+        builder.object("export_doc_page_selection_row").unwrap();
+    let header_title_row: adw::SwitchRow = builder.object("export_doc_header_title_row").unwrap();
+    let header_date_row: adw::SwitchRow = builder.object("export_doc_header_date_row").unwrap();
+    let footer_page_numbers_row: adw::SwitchRow = builder
+        .object("export_doc_footer_page_numbers_row")
+        .unwrap();
     let export_file_label: Label = builder.object("export_doc_export_file_label").unwrap();
     let export_file_button: Button = builder.object("export_doc_export_file_button").unwrap();
+    let hook_row: adw::ComboRow = builder.object("export_doc_hook_row").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_preview").unwrap();
     let export_doc_button_cancel: Button = builder.object("export_doc_button_cancel").unwrap();
     let export_doc_button_confirm: Button = builder.object("export_doc_button_confirm").unwrap();
 
     let initial_doc_export_prefs = canvas.engine_ref().export_prefs.doc_export_prefs;
     let doc_layout = canvas.engine_ref().document.layout;
+    setup_export_hook_row(appwindow, &hook_row, "export-hook-doc");
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -132,6 +210,18 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     export_file_label.set_label(&gettext("- no file selected -"));
     page_order_row
         .set_sensitive(doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite);
+    page_selection_row
+        .set_sensitive(initial_doc_export_prefs.export_format != DocExportFormat::Svg);
+    header_title_row.set_active(initial_doc_export_prefs.header_footer.header_title);
+    header_date_row.set_active(initial_doc_export_prefs.header_footer.header_date);
+    footer_page_numbers_row.set_active(initial_doc_export_prefs.header_footer.footer_page_numbers);
+    for row in [
+        &header_title_row,
+        &header_date_row,
+        &footer_page_numbers_row,
+    ] {
+        row.set_sensitive(initial_doc_export_prefs.export_format == DocExportFormat::Pdf);
+    }
     button_confirm.set_sensitive(false);
 
     // Update prefs
@@ -230,6 +320,14 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         button_confirm,
         #[weak]
+        page_selection_row,
+        #[weak]
+        header_title_row,
+        #[weak]
+        header_date_row,
+        #[weak]
+        footer_page_numbers_row,
+        #[weak]
         canvas,
         move |row| {
             let export_format = DocExportFormat::try_from(row.selected()).unwrap();
@@ -238,6 +336,15 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 .export_prefs
                 .doc_export_prefs
                 .export_format = export_format;
+            // Svg exports the document as a single image
+            page_selection_row.set_sensitive(export_format != DocExportFormat::Svg);
+            for row in [
+                &header_title_row,
+                &header_date_row,
+                &footer_page_numbers_row,
+            ] {
+                row.set_sensitive(export_format == DocExportFormat::Pdf);
+            }
 
             // force the user to pick another file
             export_file_label.set_label(&gettext("- no file selected -"));
@@ -250,11 +357,68 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         preview,
         #[weak]
+        page_selection_row,
+        #[weak]
         canvas,
         move |row| {
             let page_order = SplitOrder::try_from(row.selected()).unwrap();
             canvas.engine_mut().export_prefs.doc_export_prefs.page_order = page_order;
-            preview.set_contents(canvas.engine_ref().extract_pages_content(page_order));
+            let page_selection = parse_page_selection(&page_selection_row.text()).unwrap_or(None);
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_selected_pages_content(page_order, page_selection.as_ref()),
+            );
+        }
+    ));
+
+    page_selection_row.connect_changed(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            let page_order = canvas.engine_ref().export_prefs.doc_export_prefs.page_order;
+            refresh_page_selection(row, &preview, &canvas, page_order);
+        }
+    ));
+
+    header_title_row.connect_active_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .doc_export_prefs
+                .header_footer
+                .header_title = row.is_active();
+        }
+    ));
+
+    header_date_row.connect_active_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .doc_export_prefs
+                .header_footer
+                .header_date = row.is_active();
+        }
+    ));
+
+    footer_page_numbers_row.connect_active_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .doc_export_prefs
+                .header_footer
+                .footer_page_numbers = row.is_active();
         }
     ));
 
@@ -268,23 +432,32 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         }
     ));
 
-    export_doc_button_confirm.connect_clicked(clone!(#[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+    export_doc_button_confirm.connect_clicked(clone!(#[weak] page_selection_row, #[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+        let Ok(page_selection) = parse_page_selection(&page_selection_row.text()) else {
+            appwindow.overlays().dispatch_toast_error(&gettext("Invalid page selection"));
+            return;
+        };
         dialog.close();
 
         if let Some(file) = selected_file.take() {
             glib::spawn_future_local(clone!(#[weak] canvas, #[weak] appwindow , async move {
+                let progress = Progress::new();
                 appwindow.overlays().progressbar_start_pulsing();
+                appwindow.overlays().progressbar_track(&gettext("Exporting document…"), progress.clone());
 
                 let file_title = crate::utils::default_file_title_for_export(
                     Some(file.clone()),
                     Some(&canvas::OUTPUT_FILE_NEW_TITLE),
                     None,
                 );
-                if let Err(e) = canvas.export_doc(&file, file_title, None).await {
-                    error!("Exporting document failed, Err: `{e:?}`");
-
-                    appwindow.overlays().dispatch_toast_error(&gettext("Exporting document failed"));
+                if let Err(e) = canvas.export_doc(&file, file_title, None, page_selection, progress).await {
                     appwindow.overlays().progressbar_abort();
+                    if is_cancelled_err(&e) {
+                        appwindow.overlays().dispatch_toast_text(&gettext("Exporting document cancelled"), crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT);
+                    } else {
+                        error!("Exporting document failed, Err: `{e:?}`");
+                        appwindow.overlays().dispatch_toast_error(&gettext("Exporting document failed"));
+                    }
                 } else {
                     appwindow.overlays().dispatch_toast_w_button(
                         &gettext("Exported document successfully"),
@@ -319,6 +492,34 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     dialog.present(appwindow.root().as_ref());
 }
 
+/// Parse the text of a page selection row, where no text selects all pages.
+fn parse_page_selection(text: &str) -> anyhow::Result<Option<PageSelection>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    text.parse::<PageSelection>().map(Some)
+}
+
+/// Update the preview with the selected pages, or mark the row when the selection is invalid.
+fn refresh_page_selection(
+    row: &adw::EntryRow,
+    preview: &RnStrokeContentPreview,
+    canvas: &RnCanvas,
+    page_order: SplitOrder,
+) {
+    match parse_page_selection(&row.text()) {
+        Ok(page_selection) => {
+            row.remove_css_class("error");
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_selected_pages_content(page_order, page_selection.as_ref()),
+            );
+        }
+        Err(_) => row.add_css_class("error"),
+    }
+}
+
 fn create_filedialog_export_doc(
     appwindow: &RnAppWindow,
     canvas: &RnCanvas,
@@ -368,6 +569,17 @@ fn create_filedialog_export_doc(
             }
             filter.set_name(Some(&gettext("Xopp")));
         }
+        DocExportFormat::Html => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.html");
+            } else {
+                filter.add_mime_type("text/html");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("html");
+            }
+            filter.set_name(Some(&gettext("Html")));
+        }
     }
     let file_ext = doc_export_prefs.export_format.file_ext();
     let file_name = crate::utils::default_file_title_for_export(
@@ -420,6 +632,10 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     let page_files_naming_info_label: Label = builder
         .object("export_doc_pages_page_files_naming_info_label")
         .unwrap();
+    let page_selection_row: adw::EntryRow = builder
+        .object("export_doc_pages_page_selection_row")
+        .unwrap();
+    let hook_row: adw::ComboRow = builder.object("export_doc_pages_hook_row").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_pages_preview").unwrap();
     let export_doc_pages_button_cancel: Button =
         builder.object("export_doc_pages_button_cancel").unwrap();
@@ -428,6 +644,7 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
 
     let initial_doc_pages_export_prefs = canvas.engine_ref().export_prefs.doc_pages_export_prefs;
     let doc_layout = canvas.engine_ref().document.layout;
+    setup_export_hook_row(appwindow, &hook_row, "export-hook-doc-pages");
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -612,6 +829,8 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         #[weak]
         preview,
         #[weak]
+        page_selection_row,
+        #[weak]
         canvas,
         move |row| {
             let page_order = SplitOrder::try_from(row.selected()).unwrap();
@@ -620,7 +839,27 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
                 .export_prefs
                 .doc_pages_export_prefs
                 .page_order = page_order;
-            preview.set_contents(canvas.engine_ref().extract_pages_content(page_order));
+            let page_selection = parse_page_selection(&page_selection_row.text()).unwrap_or(None);
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_selected_pages_content(page_order, page_selection.as_ref()),
+            );
+        }
+    ));
+
+    page_selection_row.connect_changed(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            let page_order = canvas
+                .engine_ref()
+                .export_prefs
+                .doc_pages_export_prefs
+                .page_order;
+            refresh_page_selection(row, &preview, &canvas, page_order);
         }
     ));
 
@@ -682,19 +921,28 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
-    export_doc_pages_button_confirm.connect_clicked(clone!(#[weak] export_files_stemname_entryrow, #[weak] dialog, #[weak] canvas, #[weak] appwindow,  move |_| {
+    export_doc_pages_button_confirm.connect_clicked(clone!(#[weak] export_files_stemname_entryrow, #[weak] page_selection_row, #[weak] dialog, #[weak] canvas, #[weak] appwindow,  move |_| {
+        let Ok(page_selection) = parse_page_selection(&page_selection_row.text()) else {
+            appwindow.overlays().dispatch_toast_error(&gettext("Invalid page selection"));
+            return;
+        };
         dialog.close();
 
         if let Some(dir) = selected_file.take() {
             glib::spawn_future_local(clone!(#[weak] export_files_stemname_entryrow, #[weak] canvas, #[weak] appwindow,  async move {
+                let progress = Progress::new();
                 appwindow.overlays().progressbar_start_pulsing();
+                appwindow.overlays().progressbar_track(&gettext("Exporting document pages…"), progress.clone());
 
                 let file_stem_name = export_files_stemname_entryrow.text().to_string();
-                if let Err(e) = canvas.export_doc_pages(&dir, file_stem_name, None).await {
-                    error!("Exporting document pages failed, Err: {e:?}");
-
-                    appwindow.overlays().dispatch_toast_error(&gettext("Exporting document pages failed"));
+                if let Err(e) = canvas.export_doc_pages(&dir, file_stem_name, None, page_selection, progress).await {
                     appwindow.overlays().progressbar_abort();
+                    if is_cancelled_err(&e) {
+                        appwindow.overlays().dispatch_toast_text(&gettext("Exporting document pages cancelled"), crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT);
+                    } else {
+                        error!("Exporting document pages failed, Err: {e:?}");
+                        appwindow.overlays().dispatch_toast_error(&gettext("Exporting document pages failed"));
+                    }
                 } else {
                     appwindow.overlays().dispatch_toast_w_button(
                         &gettext("Exported document pages successfully"),
@@ -777,6 +1025,28 @@ fn create_filedialog_export_doc_pages(
             }
             filter.set_name(Some(&gettext("Jpeg")));
         }
+        DocPagesExportFormat::Html => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.html");
+            } else {
+                filter.add_mime_type("text/html");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("html");
+            }
+            filter.set_name(Some(&gettext("Html")));
+        }
+        DocPagesExportFormat::Eps => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.eps");
+            } else {
+                filter.add_mime_type("image/x-eps");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("eps");
+            }
+            filter.set_name(Some(&gettext("Eps")));
+        }
     }
 
     filedialog.set_default_filter(Some(&filter));
@@ -784,6 +1054,567 @@ fn create_filedialog_export_doc_pages(
     filedialog
 }
 
+/// A document listed in the batch export dialog.
+struct BatchExportEntry {
+    path: PathBuf,
+    row: adw::ActionRow,
+    check: CheckButton,
+}
+
+pub(crate) async fn dialog_batch_export(appwindow: &RnAppWindow) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_batch_export").unwrap();
+    let button_cancel: Button = builder.object("batch_export_button_cancel").unwrap();
+    let button_confirm: Button = builder.object("batch_export_button_confirm").unwrap();
+    let dir_label: Label = builder.object("batch_export_dir_label").unwrap();
+    let dir_button: Button = builder.object("batch_export_dir_button").unwrap();
+    let files_listbox: ListBox = builder.object("batch_export_files_listbox").unwrap();
+    let export_format_row: adw::ComboRow =
+        builder.object("batch_export_export_format_row").unwrap();
+    let with_background_row: adw::SwitchRow =
+        builder.object("batch_export_with_background_row").unwrap();
+    let with_pattern_row: adw::SwitchRow = builder.object("batch_export_with_pattern_row").unwrap();
+    let optimize_printing_row: adw::SwitchRow = builder
+        .object("batch_export_optimize_printing_row")
+        .unwrap();
+
+    let entries: Rc<RefCell<Vec<BatchExportEntry>>> = Rc::new(RefCell::new(Vec::new()));
+
+    with_background_row
+        .bind_property("active", &with_pattern_row, "sensitive")
+        .sync_create()
+        .build();
+
+    dir_button.connect_clicked(
+        clone!(#[strong] entries, #[weak] dir_label, #[weak] files_listbox, #[weak] button_confirm, #[weak] dialog, #[weak] appwindow, move |_| {
+            glib::spawn_future_local(clone!(#[strong] entries, #[weak] dir_label, #[weak] files_listbox, #[weak] button_confirm, #[weak] dialog, #[weak] appwindow, async move {
+                dialog.set_sensitive(false);
+
+                let filedialog = FileDialog::builder()
+                    .title(gettext("Batch Export"))
+                    .modal(true)
+                    .accept_label(gettext("Select"))
+                    .build();
+                filedialog.set_initial_folder(appwindow.sidebar().workspacebrowser().dir_list_file().as_ref());
+
+                files_listbox.remove_all();
+                entries.borrow_mut().clear();
+                match filedialog.select_folder_future(Some(&appwindow)).await {
+                    Ok(dir) => {
+                        if let Some(dir_path) = dir.path() {
+                            dir_label.set_label(&dir_path.to_string_lossy());
+                            let rnote_files = rnote_engine::utils::rnote_files_in_dir(&dir_path)
+                                .unwrap_or_else(|e| {
+                                    error!("Reading the Rnote files in folder `{dir_path:?}` failed, Err: {e:?}");
+                                    vec![]
+                                });
+                            for path in rnote_files {
+                                let file_name = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                let check = CheckButton::builder().active(true).build();
+                                let row = adw::ActionRow::builder()
+                                    .title(glib::markup_escape_text(&file_name))
+                                    .activatable_widget(&check)
+                                    .build();
+                                row.add_prefix(&check);
+                                files_listbox.append(&row);
+                                entries.borrow_mut().push(BatchExportEntry { path, row, check });
+                            }
+                        } else {
+                            dir_label.set_label(&gettext("- no directory selected -"));
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Did not select a directory for the batch export (Error or dialog dismissed by user), Err: {e:?}");
+                        dir_label.set_label(&gettext("- no directory selected -"));
+                    }
+                }
+                button_confirm.set_sensitive(!entries.borrow().is_empty());
+
+                dialog.set_sensitive(true);
+            }));
+        }),
+    );
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_confirm.connect_clicked(clone!(
+        #[strong]
+        entries,
+        #[weak]
+        button_cancel,
+        #[weak]
+        dir_button,
+        #[weak]
+        export_format_row,
+        #[weak]
+        with_background_row,
+        #[weak]
+        with_pattern_row,
+        #[weak]
+        optimize_printing_row,
+        #[weak]
+        appwindow,
+        move |button_confirm| {
+            let format = export_format_row.selected();
+            let with_background = with_background_row.is_active();
+            let with_pattern = with_pattern_row.is_active();
+            let optimize_printing = optimize_printing_row.is_active();
+
+            // The rows stay visible to show the progress and the errors of the individual documents.
+            for widget in [
+                button_confirm.upcast_ref::<gtk4::Widget>(),
+                dir_button.upcast_ref(),
+                export_format_row.upcast_ref(),
+                with_background_row.upcast_ref(),
+                with_pattern_row.upcast_ref(),
+                optimize_printing_row.upcast_ref(),
+            ] {
+                widget.set_sensitive(false);
+            }
+            for entry in entries.borrow().iter() {
+                entry.check.set_sensitive(false);
+            }
+
+            glib::spawn_future_local(clone!(
+                #[strong]
+                entries,
+                #[weak]
+                button_cancel,
+                #[weak]
+                appwindow,
+                async move {
+                    appwindow.overlays().progressbar_start_pulsing();
+                    let selected = entries
+                        .borrow()
+                        .iter()
+                        .filter(|entry| entry.check.is_active())
+                        .map(|entry| (entry.path.clone(), entry.row.clone()))
+                        .collect::<Vec<(PathBuf, adw::ActionRow)>>();
+                    let mut n_failed = 0;
+
+                    for (path, row) in selected.iter() {
+                        row.set_subtitle(&gettext("Exporting…"));
+                        match batch_export_file(
+                            path,
+                            format,
+                            with_background,
+                            with_pattern,
+                            optimize_printing,
+                        )
+                        .await
+                        {
+                            Ok(()) => row.set_subtitle(&gettext("Exported")),
+                            Err(e) => {
+                                error!(
+                                    "Batch export of file `{}` failed, Err: {e:?}",
+                                    path.display()
+                                );
+                                row.set_subtitle(&glib::markup_escape_text(&format!(
+                                    "{}: {e}",
+                                    gettext("Export failed")
+                                )));
+                                row.add_css_class("error");
+                                n_failed += 1;
+                            }
+                        }
+                    }
+
+                    if n_failed == 0 {
+                        appwindow.overlays().dispatch_toast_text(
+                            &gettext("Exported documents successfully"),
+                            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                        );
+                        appwindow.overlays().progressbar_finish();
+                    } else {
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Exporting some documents failed"));
+                        appwindow.overlays().progressbar_abort();
+                    }
+                    button_cancel.set_label(&gettext("Close"));
+                }
+            ));
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+/// Load the rnote file into a separate engine and export it next to the file.
+///
+/// `format` is the selected index of the batch export format row.
+async fn batch_export_file(
+    path: &Path,
+    format: u32,
+    with_background: bool,
+    with_pattern: bool,
+    optimize_printing: bool,
+) -> anyhow::Result<()> {
+    let (bytes, _) = gio::File::for_path(path).load_bytes_future().await?;
+    let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes.to_vec()).await?;
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(engine_snapshot);
+    let file_stem_name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| canvas::OUTPUT_FILE_NEW_TITLE.to_string());
+
+    match format {
+        0 | 1 => {
+            let export_format = if format == 0 {
+                DocExportFormat::Pdf
+            } else {
+                DocExportFormat::Svg
+            };
+            let doc_export_prefs = DocExportPrefs {
+                with_background,
+                with_pattern,
+                optimize_printing,
+                export_format,
+                ..Default::default()
+            };
+            let export_bytes = engine
+                .export_doc(
+                    file_stem_name,
+                    Some(doc_export_prefs),
+                    None,
+                    Progress::default(),
+                )
+                .await??;
+            crate::utils::create_replace_file_future(
+                export_bytes,
+                &gio::File::for_path(path.with_extension(export_format.file_ext())),
+            )
+            .await?;
+        }
+        _ => {
+            let doc_pages_export_prefs = DocPagesExportPrefs {
+                with_background,
+                with_pattern,
+                optimize_printing,
+                export_format: DocPagesExportFormat::Png,
+                ..Default::default()
+            };
+            let file_ext = doc_pages_export_prefs.export_format.file_ext();
+            let pages_bytes = engine
+                .export_doc_pages(Some(doc_pages_export_prefs), None, Progress::default())
+                .await??;
+            let dir = path.parent().unwrap_or(Path::new("."));
+            for (i, page_bytes) in pages_bytes.into_iter().enumerate() {
+                crate::utils::create_replace_file_future(
+                    page_bytes,
+                    &gio::File::for_path(dir.join(
+                        rnote_engine::utils::doc_pages_files_names(file_stem_name.clone(), i + 1)
+                            + "."
+                            + &file_ext,
+                    )),
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Save a selection of pages as a new document, optionally removing them from the current one.
+pub(crate) async fn dialog_extract_pages(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    if !canvas.engine_ref().pages_rearrangeable() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("Extracting pages is only available for documents with a fixed width"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_extract_pages").unwrap();
+    let selection_row: adw::EntryRow = builder.object("extract_pages_selection_row").unwrap();
+    let remove_row: adw::SwitchRow = builder.object("extract_pages_remove_row").unwrap();
+
+    if dialog.choose_future(appwindow).await.as_str() != "extract" {
+        return;
+    }
+    let n_pages = canvas.engine_ref().pages_count();
+    let indices = selection_row
+        .text()
+        .parse::<PageSelection>()
+        .map(|page_selection| page_selection.indices(n_pages))
+        .unwrap_or_default();
+    if indices.is_empty() {
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Invalid page selection"));
+        return;
+    }
+    let remove = remove_row.is_active();
+
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.rnote");
+    } else {
+        filter.add_mime_type("application/rnote");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("rnote");
+    }
+    filter.set_name(Some(&gettext(".rnote")));
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Save Extracted Pages"))
+        .modal(true)
+        .accept_label(gettext("Save"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(canvas.doc_title_display() + " - " + &gettext("Pages") + ".rnote")
+        .build();
+    if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        filedialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
+    }
+    let file = match filedialog.save_future(Some(appwindow)).await {
+        Ok(file) => file,
+        Err(e) => {
+            debug!("Did not extract pages (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+
+    appwindow.overlays().progressbar_start_pulsing();
+    let file_name = file
+        .basename()
+        .map(|basename| basename.to_string_lossy().to_string())
+        .unwrap_or_else(|| canvas::OUTPUT_FILE_NEW_TITLE.to_string() + ".rnote");
+    canvas.load_pending_strokes();
+    let bytes_recv = canvas
+        .engine_ref()
+        .save_pages_as_rnote_bytes(&indices, file_name);
+    // The extracted pages are protected with the same passphrase as the document
+    let passphrase = canvas.passphrase();
+    let result = async {
+        let bytes = encrypt_if_needed(bytes_recv.await??, passphrase).await?;
+        crate::utils::create_replace_file_future(bytes, &file).await
+    }
+    .await;
+    if let Err(e) = result {
+        error!("Extracting pages failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Extracting pages failed"));
+        appwindow.overlays().progressbar_abort();
+        return;
+    }
+    if remove {
+        let widget_flags = canvas.engine_mut().pages_delete(&indices);
+        appwindow.handle_widget_flags(widget_flags, canvas);
+    }
+    appwindow.overlays().dispatch_toast_text(
+        &gettext("Extracted pages successfully"),
+        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+    );
+    appwindow.overlays().progressbar_finish();
+}
+
+pub(crate) async fn dialog_export_frames(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    if canvas.engine_ref().extract_frames_content().is_empty() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("No frames in the document"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_export_frames").unwrap();
+    let export_format_row: adw::ComboRow =
+        builder.object("export_frames_export_format_row").unwrap();
+    let hook_row: adw::ComboRow = builder.object("export_frames_hook_row").unwrap();
+    setup_export_hook_row(appwindow, &hook_row, "export-hook-selection");
+
+    let mut selection_export_prefs = canvas.engine_ref().export_prefs.selection_export_prefs;
+    export_format_row.set_selected(
+        selection_export_prefs
+            .export_format
+            .to_u32()
+            .unwrap_or_default(),
+    );
+    if dialog.choose_future(appwindow).await.as_str() != "export" {
+        return;
+    }
+    selection_export_prefs.export_format =
+        SelectionExportFormat::try_from(export_format_row.selected()).unwrap_or_default();
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Frames"))
+        .modal(true)
+        .accept_label(gettext("Select"))
+        .build();
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+    let dir = match filedialog.select_folder_future(Some(appwindow)).await {
+        Ok(dir) => dir,
+        Err(e) => {
+            debug!("Did not export frames (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+
+    appwindow.overlays().progressbar_start_pulsing();
+    canvas.load_pending_strokes();
+    let frames_recv = canvas
+        .engine_ref()
+        .export_frames(Some(selection_export_prefs));
+    let file_ext = selection_export_prefs.export_format.file_ext();
+    let result = async {
+        let mut files = Vec::new();
+        for (stem_name, bytes) in frames_recv.await?? {
+            let file = dir.child(format!("{stem_name}.{file_ext}"));
+            crate::utils::create_replace_file_future(bytes, &file).await?;
+            files.push(file);
+        }
+        anyhow::Ok(files)
+    }
+    .await;
+    let files = match result {
+        Ok(files) => files,
+        Err(e) => {
+            error!("Exporting frames failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Exporting frames failed"));
+            appwindow.overlays().progressbar_abort();
+            return;
+        }
+    };
+    canvas.set_last_export_dir(Some(dir));
+    canvas
+        .run_export_hook("export-hook-selection", &files)
+        .await;
+    appwindow.overlays().dispatch_toast_text(
+        &gettext("Exported frames successfully"),
+        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+    );
+    appwindow.overlays().progressbar_finish();
+}
+
+pub(crate) async fn dialog_export_figure(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_export_figure").unwrap();
+    let export_format_row: adw::ComboRow =
+        builder.object("export_figure_export_format_row").unwrap();
+    let snippet_markup_row: adw::ComboRow =
+        builder.object("export_figure_snippet_markup_row").unwrap();
+    let with_background_row: adw::SwitchRow =
+        builder.object("export_figure_with_background_row").unwrap();
+    let with_pattern_row: adw::SwitchRow =
+        builder.object("export_figure_with_pattern_row").unwrap();
+    let optimize_printing_row: adw::SwitchRow = builder
+        .object("export_figure_optimize_printing_row")
+        .unwrap();
+
+    let mut figure_export_prefs = canvas.engine_ref().export_prefs.figure_export_prefs;
+    export_format_row.set_selected(figure_export_prefs.export_format.to_u32().unwrap());
+    snippet_markup_row.set_selected(figure_export_prefs.snippet_markup.to_u32().unwrap());
+    with_background_row.set_active(figure_export_prefs.with_background);
+    with_pattern_row.set_active(figure_export_prefs.with_pattern);
+    optimize_printing_row.set_active(figure_export_prefs.optimize_printing);
+    with_background_row
+        .bind_property("active", &with_pattern_row, "sensitive")
+        .sync_create()
+        .build();
+
+    if dialog.choose_future(appwindow).await.as_str() != "export" {
+        return;
+    }
+    figure_export_prefs.export_format =
+        FigureExportFormat::try_from(export_format_row.selected()).unwrap_or_default();
+    figure_export_prefs.snippet_markup =
+        FigureSnippetMarkup::try_from(snippet_markup_row.selected()).unwrap_or_default();
+    figure_export_prefs.with_background = with_background_row.is_active();
+    figure_export_prefs.with_pattern = with_pattern_row.is_active();
+    figure_export_prefs.optimize_printing = optimize_printing_row.is_active();
+    canvas.engine_mut().export_prefs.figure_export_prefs = figure_export_prefs;
+
+    let file_ext = figure_export_prefs.export_format.file_ext();
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Selection as Figure"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .initial_name(crate::utils::default_file_title_for_export(
+            canvas.output_file(),
+            Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+            Some(&(String::from(" - Figure") + "." + &file_ext)),
+        ))
+        .build();
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+    let file = match filedialog.save_future(Some(appwindow)).await {
+        Ok(file) => file,
+        Err(e) => {
+            debug!("Did not export figure (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let (Some(file_name), Some(file_stem), Some(dir)) = (
+        file.basename()
+            .map(|basename| basename.to_string_lossy().to_string()),
+        file.basename()
+            .and_then(|basename| Some(basename.file_stem()?.to_string_lossy().to_string())),
+        file.parent(),
+    ) else {
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Exporting figure failed"));
+        return;
+    };
+    let snippet_file = dir.child(format!(
+        "{file_stem}.{}",
+        figure_export_prefs.snippet_markup.file_ext()
+    ));
+
+    appwindow.overlays().progressbar_start_pulsing();
+    let figure_recv = canvas
+        .engine_ref()
+        .export_selection_as_figure(file_name, Some(figure_export_prefs));
+    let result = async {
+        let Some((figure_bytes, snippet)) = figure_recv.await?? else {
+            return Err(anyhow::anyhow!("Nothing is selected."));
+        };
+        crate::utils::create_replace_file_future(figure_bytes, &file).await?;
+        crate::utils::create_replace_file_future(snippet.into_bytes(), &snippet_file).await?;
+        anyhow::Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        error!("Exporting selection as figure failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Exporting figure failed"));
+        appwindow.overlays().progressbar_abort();
+        return;
+    }
+    canvas.set_last_export_dir(Some(dir));
+    appwindow.overlays().dispatch_toast_text(
+        &gettext("Exported figure and snippet successfully"),
+        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+    );
+    appwindow.overlays().progressbar_finish();
+}
+
 pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, canvas: &RnCanvas) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
@@ -813,6 +1644,7 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
     let jpeg_quality_row: adw::SpinRow =
         builder.object("export_selection_jpeg_quality_row").unwrap();
     let margin_row: adw::SpinRow = builder.object("export_selection_margin_row").unwrap();
+    let hook_row: adw::ComboRow = builder.object("export_selection_hook_row").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_selection_preview").unwrap();
     let export_selection_button_cancel: Button =
         builder.object("export_selection_button_cancel").unwrap();
@@ -820,6 +1652,7 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         builder.object("export_selection_button_confirm").unwrap();
 
     let initial_selection_export_prefs = canvas.engine_ref().export_prefs.selection_export_prefs;
+    setup_export_hook_row(appwindow, &hook_row, "export-hook-selection");
 
     // initial widget state with the preferences
     let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
@@ -1094,6 +1927,42 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
 /// 3. the directory shown in the sidebar of the window
 ///
 /// The first available will be returned.
+/// Fills the row with the export hook profiles and stores the selected profile in the app settings under the given key.
+fn setup_export_hook_row(appwindow: &RnAppWindow, row: &adw::ComboRow, settings_key: &'static str) {
+    let Some(app_settings) = appwindow.app().app_settings() else {
+        row.set_visible(false);
+        return;
+    };
+    let profiles = appwindow
+        .export_hooks()
+        .into_keys()
+        .collect::<Vec<String>>();
+    let model = StringList::new(&[]);
+    model.append(&gettext("None"));
+    for profile in profiles.iter() {
+        model.append(profile);
+    }
+    row.set_model(Some(&model));
+    let selected_profile = app_settings.string(settings_key);
+    row.set_selected(
+        profiles
+            .iter()
+            .position(|profile| profile == selected_profile.as_str())
+            .map_or(0, |i| i as u32 + 1),
+    );
+    row.set_sensitive(!profiles.is_empty());
+
+    row.connect_selected_notify(move |row| {
+        let profile = match row.selected() {
+            0 => String::new(),
+            i => profiles.get(i as usize - 1).cloned().unwrap_or_default(),
+        };
+        if let Err(e) = app_settings.set_string(settings_key, &profile) {
+            error!("Saving the selected export hook profile to settings failed, Err: {e:?}");
+        }
+    });
+}
+
 fn get_initial_folder_for_export(appwindow: &RnAppWindow, canvas: &RnCanvas) -> Option<gio::File> {
     canvas
         .last_export_dir()
@@ -1160,6 +2029,28 @@ fn create_filedialog_export_selection(
             }
             filter.set_name(Some(&gettext("Jpeg")));
         }
+        SelectionExportFormat::Html => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.html");
+            } else {
+                filter.add_mime_type("text/html");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("html");
+            }
+            filter.set_name(Some(&gettext("Html")));
+        }
+        SelectionExportFormat::Eps => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.eps");
+            } else {
+                filter.add_mime_type("image/x-eps");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("eps");
+            }
+            filter.set_name(Some(&gettext("Eps")));
+        }
     }
     let file_ext = selection_export_prefs.export_format.file_ext();
     let file_name = crate::utils::default_file_title_for_export(
@@ -1298,3 +2189,62 @@ pub(crate) async fn filechooser_export_engine_config(appwindow: &RnAppWindow, ca
         }
     }
 }
+
+/// Export the settings and pen presets as a settings profile.
+pub(crate) async fn filechooser_export_settings(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("json");
+    }
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Settings"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(gettext("Rnote Settings") + ".json")
+        .build();
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            let res = match appwindow.export_settings_profile() {
+                Ok(serialized) => {
+                    crate::utils::create_replace_file_future(
+                        serialized.into_bytes(),
+                        &selected_file,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = res {
+                error!("Exporting settings failed, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting settings failed"));
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Exported settings successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+        }
+        Err(e) => {
+            debug!("Did not export settings (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
@@ -7,11 +7,16 @@ use adw::prelude::*;
 use futures::StreamExt;
 use gettextrs::gettext;
 use gtk4::{
-    gio, glib, glib::clone, Builder, Button, CallbackAction, FileDialog, FileFilter, Label,
-    Shortcut, ShortcutController, ShortcutTrigger, ToggleButton,
+    gdk, gio, glib, glib::clone, Builder, Button, CallbackAction, FileDialog, FileFilter,
+    GestureDrag, Label, Shortcut, ShortcutController, ShortcutTrigger, ToggleButton,
 };
 use num_traits::ToPrimitive;
 use rnote_engine::engine::import::{PdfImportPageSpacing, PdfImportPagesType};
+use rnote_engine::engine::progress::is_cancelled_err;
+use rnote_engine::engine::Progress;
+use rnote_engine::photocorrection::PhotoCorners;
+use std::cell::Cell;
+use std::rc::Rc;
 use tracing::{debug, error};
 
 /// Opens a new rnote save file in a new tab
@@ -55,6 +60,64 @@ pub(crate) async fn filedialog_open_doc(appwindow: &RnAppWindow) {
     }
 }
 
+/// Appends the pages of another rnote save file to the document of the canvas.
+pub(crate) async fn filedialog_import_pages_from_doc(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.rnote");
+    } else {
+        filter.add_mime_type("application/rnote");
+    }
+    filter.add_suffix("rnote");
+    filter.set_name(Some(&gettext(".rnote")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Import Pages From File"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        filedialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
+    }
+
+    let selected_file = match filedialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not import pages (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+
+    appwindow.overlays().progressbar_start_pulsing();
+    let result = async {
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        canvas.append_rnote_bytes(bytes.to_vec()).await
+    }
+    .await;
+    match result {
+        Ok(widget_flags) => {
+            appwindow.handle_widget_flags(widget_flags, canvas);
+            appwindow.overlays().progressbar_finish();
+        }
+        Err(e) => {
+            error!("Importing pages from file failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Importing pages from file failed"));
+            appwindow.overlays().progressbar_abort();
+        }
+    }
+}
+
 pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     let filter = FileFilter::new();
     // note : mimetypes are not supported with the native file picker on windows
@@ -62,6 +125,7 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
     if cfg!(target_os = "windows") {
         filter.add_pattern("*.xopp");
+        filter.add_pattern("*.enex");
         filter.add_pattern("*.pdf");
         filter.add_pattern("*.svg");
         filter.add_pattern("*.png");
@@ -69,6 +133,7 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
         filter.add_pattern("*.txt");
     } else {
         filter.add_mime_type("application/x-xopp");
+        filter.add_mime_type("application/enex+xml");
         filter.add_mime_type("application/pdf");
         filter.add_mime_type("image/svg+xml");
         filter.add_mime_type("image/png");
@@ -76,13 +141,14 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
         filter.add_mime_type("text/plain");
     }
     filter.add_suffix("xopp");
+    filter.add_suffix("enex");
     filter.add_suffix("pdf");
     filter.add_suffix("svg");
     filter.add_suffix("png");
     filter.add_suffix("jpg");
     filter.add_suffix("jpeg");
     filter.add_suffix("txt");
-    filter.set_name(Some(&gettext("Jpg, Pdf, Png, Svg, Xopp, Txt")));
+    filter.set_name(Some(&gettext("Jpg, Pdf, Png, Svg, Xopp, Enex, Txt")));
 
     let filter_list = gio::ListStore::new::<FileFilter>();
     filter_list.append(&filter);
@@ -111,6 +177,167 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     }
 }
 
+/// Import a settings profile that replaces the current settings and pen presets.
+pub(crate) async fn filedialog_import_settings(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Import Settings"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not import settings (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let res = match selected_file.load_bytes_future().await {
+        Ok((bytes, _)) => String::from_utf8(bytes.to_vec())
+            .map_err(anyhow::Error::from)
+            .and_then(|serialized| appwindow.import_settings_profile(&serialized)),
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = res {
+        error!("Importing settings failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Importing settings failed"));
+    } else {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("Imported settings successfully"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+    }
+}
+
+/// Inserts an image file at the target position.
+pub(crate) async fn filedialog_insert_image(
+    appwindow: &RnAppWindow,
+    target_pos: Option<na::Vector2<f64>>,
+) {
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.svg");
+        filter.add_pattern("*.png");
+        filter.add_pattern("*.jpeg");
+    } else {
+        filter.add_mime_type("image/svg+xml");
+        filter.add_mime_type("image/png");
+        filter.add_mime_type("image/jpeg");
+    }
+    filter.add_suffix("svg");
+    filter.add_suffix("png");
+    filter.add_suffix("jpg");
+    filter.add_suffix("jpeg");
+    filter.set_name(Some(&gettext("Jpg, Png, Svg")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Insert Image"))
+        .modal(true)
+        .accept_label(gettext("Insert"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        dialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
+    }
+
+    match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            appwindow
+                .open_file_w_dialogs(selected_file, target_pos, true)
+                .await;
+        }
+        Err(e) => {
+            debug!("Did not insert image (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
+/// Links the selected linked images to a new image file.
+pub(crate) async fn filedialog_relink_image(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let Some(linked_path) = canvas.engine_ref().selection_linked_image_path() else {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("No linked image selected"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    };
+
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.png");
+        filter.add_pattern("*.jpeg");
+    } else {
+        filter.add_mime_type("image/png");
+        filter.add_mime_type("image/jpeg");
+    }
+    filter.add_suffix("png");
+    filter.add_suffix("jpg");
+    filter.add_suffix("jpeg");
+    filter.set_name(Some(&gettext("Jpg, Png")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Relink Image"))
+        .modal(true)
+        .accept_label(gettext("Relink"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    if let Some(parent_dir) = linked_path.parent().filter(|dir| dir.is_dir()) {
+        dialog.set_initial_folder(Some(&gio::File::for_path(parent_dir)));
+    }
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not relink image (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let Some(new_path) = selected_file.path() else {
+        error!("Relinking image failed, could not get a path for the selected file");
+        return;
+    };
+
+    let widget_flags = canvas.engine_mut().relink_selected_images(&new_path);
+    match widget_flags {
+        Ok(widget_flags) => appwindow.handle_widget_flags(widget_flags, canvas),
+        Err(e) => {
+            error!("Relinking image failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Relinking image failed"));
+        }
+    }
+}
+
 /// Imports the file as Pdf with an import dialog.
 ///
 /// Returns true when the file was imported, else false.
@@ -346,12 +573,12 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         }
     ));
 
-    import_pdf_button_confirm.connect_clicked(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] dialog, #[weak] canvas , move |_| {
+    import_pdf_button_confirm.connect_clicked(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] dialog, #[weak] canvas, #[weak] appwindow, move |_| {
         dialog.close();
 
         let inner_tx_confirm = tx_confirm.clone();
 
-        glib::spawn_future_local(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] canvas , async move {
+        glib::spawn_future_local(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] canvas, #[weak] appwindow, async move {
             let page_range =
                 (pdf_page_start_row.value() as u32 - 1)..pdf_page_end_row.value() as u32;
 
@@ -364,8 +591,13 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                     return;
                 }
             };
-            if let Err(e) = canvas.load_in_pdf_bytes(bytes.to_vec(), target_pos, Some(page_range)).await {
-                if let Err(e) = inner_tx_confirm.unbounded_send(Err(e)) {
+            // The progressbar was already started when opening the file
+            let progress = Progress::new();
+            appwindow.overlays().progressbar_track(&gettext("Importing Pdf…"), progress.clone());
+            if let Err(e) = canvas.load_in_pdf_bytes(bytes.to_vec(), target_pos, Some(page_range), progress).await {
+                // A cancelled import is reported as not imported
+                let res = if is_cancelled_err(&e) { Ok(false) } else { Err(e) };
+                if let Err(e) = inner_tx_confirm.unbounded_send(res) {
                     error!("Failed to load PDF, but failed to send signal through channel. Err: {e:?}");
                 }
                 return;
@@ -511,3 +743,326 @@ pub(crate) async fn dialog_import_xopp_w_prefs(
         )),
     }
 }
+
+/// Opens a photo and imports it with the perspective correction dialog.
+pub(crate) async fn filedialog_import_photo(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.png");
+        filter.add_pattern("*.jpeg");
+    } else {
+        filter.add_mime_type("image/png");
+        filter.add_mime_type("image/jpeg");
+    }
+    filter.add_suffix("png");
+    filter.add_suffix("jpg");
+    filter.add_suffix("jpeg");
+    filter.set_name(Some(&gettext("Jpg, Png")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(gettext("Import Photo"))
+        .modal(true)
+        .accept_label(gettext("Open"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        dialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
+    }
+
+    let selected_file = match dialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not import photo (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let canvas = appwindow.active_tab_wrapper().canvas();
+    if let Err(e) = dialog_import_photo(appwindow, &canvas, selected_file).await {
+        error!("Importing photo failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Importing photo failed"));
+    }
+}
+
+/// Imports the photo after correcting its perspective in a dialog.
+///
+/// Returns true when the photo was imported, else false.
+pub(crate) async fn dialog_import_photo(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    input_file: gio::File,
+) -> anyhow::Result<bool> {
+    let (bytes, _) = input_file.load_bytes_future().await?;
+    let texture = gdk::Texture::from_bytes(&bytes)?;
+
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/import.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_import_photo").unwrap();
+    let picture: gtk4::Picture = builder.object("import_photo_picture").unwrap();
+    let corners_area: gtk4::DrawingArea = builder.object("import_photo_corners_area").unwrap();
+    let enhance_contrast_row: adw::SwitchRow =
+        builder.object("import_photo_enhance_contrast_row").unwrap();
+    let reset_corners_button: Button = builder.object("import_photo_reset_corners_button").unwrap();
+    let import_photo_button_cancel: Button = builder.object("import_photo_button_cancel").unwrap();
+    let import_photo_button_confirm: Button =
+        builder.object("import_photo_button_confirm").unwrap();
+
+    let corners = Rc::new(Cell::new(PhotoCorners::default()));
+    let texture_size = na::vector![f64::from(texture.width()), f64::from(texture.height())];
+    picture.set_paintable(Some(&texture));
+
+    // Set initial widget state for preference
+    enhance_contrast_row.set_active(
+        canvas
+            .engine_ref()
+            .import_prefs
+            .bitmapimage_import_prefs
+            .enhance_photo_contrast,
+    );
+
+    // Update preferences
+    enhance_contrast_row.connect_active_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .import_prefs
+                .bitmapimage_import_prefs
+                .enhance_photo_contrast = row.is_active();
+        }
+    ));
+
+    corners_area.set_draw_func(clone!(
+        #[strong]
+        corners,
+        move |_, cx, width, height| {
+            let widget_corners = photo_corners_to_widget_coords(
+                corners.get(),
+                na::vector![f64::from(width), f64::from(height)],
+                texture_size,
+            );
+            if let Err(e) = draw_photo_corners(cx, &widget_corners) {
+                error!("Drawing photo corners failed, Err: {e:?}");
+            }
+        }
+    ));
+
+    let drag_gesture = GestureDrag::new();
+    // The index of the dragged corner and its position at the start of the drag
+    let dragged_corner = Rc::new(Cell::new(None::<(usize, na::Vector2<f64>)>));
+    drag_gesture.connect_drag_begin(clone!(
+        #[strong]
+        corners,
+        #[strong]
+        dragged_corner,
+        #[weak]
+        corners_area,
+        move |_, x, y| {
+            let widget_corners = photo_corners_to_widget_coords(
+                corners.get(),
+                na::vector![
+                    f64::from(corners_area.width()),
+                    f64::from(corners_area.height())
+                ],
+                texture_size,
+            );
+            let pos = na::vector![x, y];
+            dragged_corner.set(
+                widget_corners
+                    .iter()
+                    .enumerate()
+                    .map(|(i, corner)| (i, (corner - pos).norm()))
+                    .filter(|(_, distance)| *distance < PHOTO_CORNER_GRAB_RADIUS)
+                    .min_by(|(_, d0), (_, d1)| d0.total_cmp(d1))
+                    .map(|(i, _)| (i, widget_corners[i])),
+            );
+        }
+    ));
+    drag_gesture.connect_drag_update(clone!(
+        #[strong]
+        corners,
+        #[strong]
+        dragged_corner,
+        #[weak]
+        corners_area,
+        move |_, offset_x, offset_y| {
+            let Some((i, start)) = dragged_corner.get() else {
+                return;
+            };
+            let area_size = na::vector![
+                f64::from(corners_area.width()),
+                f64::from(corners_area.height())
+            ];
+            let (image_offset, image_scale) = photo_placement(area_size, texture_size);
+            let mut new_corners = corners.get();
+            new_corners.0[i] = ((start + na::vector![offset_x, offset_y] - image_offset)
+                / image_scale)
+                .component_div(&texture_size)
+                .map(|c| c.clamp(0.0, 1.0));
+            corners.set(new_corners);
+            corners_area.queue_draw();
+        }
+    ));
+    drag_gesture.connect_drag_end(clone!(
+        #[strong]
+        dragged_corner,
+        move |_, _, _| {
+            dragged_corner.set(None);
+        }
+    ));
+    corners_area.add_controller(drag_gesture);
+
+    reset_corners_button.connect_clicked(clone!(
+        #[strong]
+        corners,
+        #[weak]
+        corners_area,
+        move |_| {
+            corners.set(PhotoCorners::default());
+            corners_area.queue_draw();
+        }
+    ));
+
+    // Listen to responses
+
+    let (tx, mut rx) = futures::channel::mpsc::unbounded::<anyhow::Result<bool>>();
+    let tx_cancel = tx.clone();
+    let tx_confirm = tx.clone();
+
+    import_photo_button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+
+            if let Err(e) = tx_cancel.unbounded_send(Ok(false)) {
+                error!(
+                    "Photo import dialog closed, but failed to send signal through channel. Err: {e:?}"
+                );
+            }
+        }
+    ));
+
+    import_photo_button_confirm.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        canvas,
+        #[weak]
+        appwindow,
+        #[strong]
+        corners,
+        move |_| {
+            dialog.close();
+
+            let inner_tx_confirm = tx_confirm.clone();
+            let bytes = bytes.to_vec();
+            let corners = corners.get();
+
+            glib::spawn_future_local(clone!(
+                #[weak]
+                canvas,
+                #[weak]
+                appwindow,
+                async move {
+                    let result = canvas
+                        .load_in_photo_bytes(bytes, corners, None, appwindow.respect_borders())
+                        .await
+                        .map(|_| true);
+                    if let Err(e) = inner_tx_confirm.unbounded_send(result) {
+                        error!(
+                            "Photo imported, but failed to send signal through channel. Err: {e:?}"
+                        );
+                    }
+                }
+            ));
+        }
+    ));
+
+    // Overwrite builtin close shortcut
+    let controller = ShortcutController::new();
+    controller.add_shortcut(Shortcut::new(
+        Some(ShortcutTrigger::parse_string("Escape").unwrap()),
+        Some(CallbackAction::new(clone!(
+            #[weak]
+            import_photo_button_cancel,
+            #[upgrade_or]
+            glib::Propagation::Stop,
+            move |_, _| {
+                import_photo_button_cancel.emit_clicked();
+
+                glib::Propagation::Stop
+            }
+        ))),
+    ));
+    dialog.add_controller(controller);
+
+    // Present than wait for a response from the dialog
+    dialog.present(appwindow.root().as_ref());
+
+    match rx.next().await {
+        Some(res) => res,
+        None => Err(anyhow::anyhow!(
+            "Channel closed before receiving a response from dialog."
+        )),
+    }
+}
+
+/// The radius around the photo corners in which they can be grabbed.
+const PHOTO_CORNER_GRAB_RADIUS: f64 = 24.0;
+
+/// The offset and scale of the photo when it is contained and centered in the area.
+fn photo_placement(
+    area_size: na::Vector2<f64>,
+    texture_size: na::Vector2<f64>,
+) -> (na::Vector2<f64>, f64) {
+    let scale = (area_size[0] / texture_size[0]).min(area_size[1] / texture_size[1]);
+    ((area_size - texture_size * scale) * 0.5, scale)
+}
+
+fn photo_corners_to_widget_coords(
+    corners: PhotoCorners,
+    area_size: na::Vector2<f64>,
+    texture_size: na::Vector2<f64>,
+) -> [na::Vector2<f64>; 4] {
+    let (offset, scale) = photo_placement(area_size, texture_size);
+    corners
+        .0
+        .map(|corner| offset + corner.component_mul(&texture_size) * scale)
+}
+
+fn draw_photo_corners(
+    cx: &cairo::Context,
+    widget_corners: &[na::Vector2<f64>; 4],
+) -> anyhow::Result<()> {
+    let (r, g, b, _) = rnote_compose::color::GNOME_BLUES[3].as_rgba();
+
+    cx.set_source_rgba(r, g, b, 0.2);
+    cx.move_to(widget_corners[0][0], widget_corners[0][1]);
+    for corner in widget_corners[1..].iter() {
+        cx.line_to(corner[0], corner[1]);
+    }
+    cx.close_path();
+    cx.fill_preserve()?;
+    cx.set_source_rgba(r, g, b, 1.0);
+    cx.set_line_width(2.0);
+    cx.stroke()?;
+
+    for corner in widget_corners {
+        cx.new_sub_path();
+        cx.arc(corner[0], corner[1], 8.0, 0.0, std::f64::consts::TAU);
+    }
+    cx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+    cx.fill_preserve()?;
+    cx.set_source_rgba(r, g, b, 1.0);
+    cx.stroke()?;
+    Ok(())
+}
@@ -4,18 +4,33 @@ pub(crate) mod import;
 
 // Imports
 use crate::appwindow::RnAppWindow;
+use crate::canvas::broadcast::BROADCAST_PORT_DEFAULT;
+use crate::canvas::collab::COLLAB_PORT_DEFAULT;
 use crate::canvas::RnCanvas;
 use crate::canvaswrapper::RnCanvasWrapper;
 use crate::config;
+use crate::share;
 use crate::workspacebrowser::workspacesbar::RnWorkspaceRow;
 use crate::{globals, RnIconPicker};
 use adw::prelude::*;
 use gettextrs::{gettext, pgettext};
 use gtk4::{
-    gio, glib, glib::clone, Builder, Button, CheckButton, ColorDialogButton, FileDialog, Label,
-    MenuButton, ShortcutsWindow, StringList,
+    gdk, gio, glib, glib::clone, Align, Builder, Button, CheckButton, ColorDialogButton,
+    DragSource, DropTarget, FileDialog, FlowBox, Label, MenuButton, Orientation, Picture,
+    ShortcutsWindow, StringList, WidgetPaintable,
 };
+use num_traits::ToPrimitive;
+use rnote_engine::document::background::PatternStyle;
+use rnote_engine::document::links::LinkTarget;
+use rnote_engine::document::Background;
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::fileformats::rnoteformat::encryption;
+use rnote_engine::render;
+use rnote_engine::strokes::mindmapstroke::{MindMapLayout, MindMapNode};
+use rnote_engine::strokes::StickyNoteStroke;
+use std::collections::HashMap;
 use tracing::{debug, error, warn};
+use zeroize::Zeroizing;
 
 // About Dialog
 pub(crate) fn dialog_about(appwindow: &RnAppWindow) {
@@ -79,6 +94,25 @@ pub(crate) async fn dialog_clear_doc(appwindow: &RnAppWindow, canvas: &RnCanvas)
     }
 }
 
+pub(crate) async fn dialog_apply_redactions(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_apply_redactions").unwrap();
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "apply" => {
+            let widget_flags = canvas.engine_mut().apply_redactions();
+            appwindow.handle_widget_flags(widget_flags, canvas);
+            // Backup copies still contain the redacted content
+            canvas.set_discard_backups(true);
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
 #[allow(unused)]
 pub(crate) async fn dialog_new_doc(appwindow: &RnAppWindow, canvas: &RnCanvas) {
     let builder = Builder::from_resource(
@@ -419,6 +453,993 @@ pub(crate) async fn dialog_close_window(appwindow: &RnAppWindow) {
     }
 }
 
+/// Inform that parts of an opened document could not be read and were dropped.
+pub(crate) async fn dialog_recovered_content(appwindow: &RnAppWindow, dropped: &[String]) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_recovered_content").unwrap();
+    let label: Label = builder.object("recovered_content_label").unwrap();
+
+    label.set_label(&dropped.join("\n"));
+    dialog.choose_future(appwindow).await;
+}
+
+/// Asks for the password of an encrypted document and decrypts it, asking again when the password is wrong.
+///
+/// Returns the decrypted bytes and the password, or None if the dialog was cancelled.
+pub(crate) async fn dialog_unlock_doc(
+    appwindow: &RnAppWindow,
+    bytes: Vec<u8>,
+) -> anyhow::Result<Option<(Vec<u8>, Zeroizing<String>)>> {
+    let mut wrong_passphrase = false;
+    loop {
+        let builder = Builder::from_resource(
+            (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+        );
+        let dialog: adw::AlertDialog = builder.object("dialog_unlock_doc").unwrap();
+        let entryrow: adw::PasswordEntryRow =
+            builder.object("unlock_doc_password_entryrow").unwrap();
+        if wrong_passphrase {
+            dialog.set_body(&gettext("The password is wrong. Please try again."));
+            entryrow.add_css_class("error");
+        }
+
+        if dialog.choose_future(appwindow).await.as_str() != "unlock" {
+            return Ok(None);
+        }
+        let passphrase = Zeroizing::new(entryrow.text().to_string());
+        let decrypt_bytes = bytes.clone();
+        let decrypt_passphrase = passphrase.clone();
+        match gio::spawn_blocking(move || encryption::decrypt(&decrypt_bytes, &decrypt_passphrase))
+            .await
+            .map_err(|_| anyhow::anyhow!("Decrypting the document panicked."))?
+        {
+            Ok(decrypted) => return Ok(Some((decrypted, passphrase))),
+            Err(encryption::DecryptError::WrongPassphrase) => {
+                wrong_passphrase = true;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Sets, changes or removes the password the document is encrypted with.
+pub(crate) async fn dialog_doc_password(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_doc_password").unwrap();
+    let entryrow: adw::PasswordEntryRow = builder.object("doc_password_entryrow").unwrap();
+    let confirm_entryrow: adw::PasswordEntryRow =
+        builder.object("doc_password_confirm_entryrow").unwrap();
+
+    dialog.set_response_enabled("remove", canvas.encrypted());
+    dialog.set_response_enabled("apply", false);
+    let update_apply = clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        entryrow,
+        #[weak]
+        confirm_entryrow,
+        move || {
+            let matching = entryrow.text() == confirm_entryrow.text();
+            if matching {
+                confirm_entryrow.remove_css_class("error");
+            } else {
+                confirm_entryrow.add_css_class("error");
+            }
+            dialog.set_response_enabled("apply", matching && !entryrow.text().is_empty());
+        }
+    );
+    entryrow.connect_changed(clone!(
+        #[strong]
+        update_apply,
+        move |_| update_apply()
+    ));
+    confirm_entryrow.connect_changed(move |_| update_apply());
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "apply" => {
+            canvas.set_passphrase(Some(Zeroizing::new(entryrow.text().to_string())));
+            // Saving is needed to write the document encrypted
+            canvas.set_unsaved_changes(true);
+            // Backup copies are not encrypted, or encrypted with the previous password
+            canvas.set_discard_backups(true);
+        }
+        "remove" => {
+            canvas.set_passphrase(None);
+            canvas.set_unsaved_changes(true);
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
+pub(crate) async fn dialog_selection_add_link(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_selection_add_link").unwrap();
+    let entryrow: adw::EntryRow = builder.object("selection_add_link_entryrow").unwrap();
+
+    if dialog.choose_future(appwindow).await.as_str() != "add" {
+        return;
+    }
+    let text = entryrow.text().trim().to_string();
+    if text.is_empty() {
+        return;
+    }
+    // Page numbers start at one, web addresses without a scheme are assumed to be https
+    let target = match text.parse::<usize>() {
+        Ok(page) => {
+            let Some(target) = page
+                .checked_sub(1)
+                .and_then(|page| canvas.engine_ref().page_link_target(page))
+            else {
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("The page does not exist"));
+                return;
+            };
+            target
+        }
+        Err(_) if text.contains("://") || text.starts_with("mailto:") => LinkTarget::Uri(text),
+        Err(_) => LinkTarget::Uri(format!("https://{text}")),
+    };
+
+    let widget_flags = canvas.engine_mut().add_link_to_selection(target);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Confirm opening a link that is not a web address, because it might open a local file or another application.
+pub(crate) async fn dialog_open_uri(appwindow: &RnAppWindow, uri: String) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_open_uri").unwrap();
+    dialog.set_body(
+        &gettext("The link leads to “{}”, which is not a web address. It might open a file or another application.")
+            .replace("{}", &uri),
+    );
+
+    if dialog.choose_future(appwindow).await.as_str() == "open" {
+        appwindow.launch_uri(uri);
+    }
+}
+
+pub(crate) async fn dialog_share(appwindow: &RnAppWindow, canvas: &RnCanvas, target_id: &str) {
+    let Some(target) = share::share_target(target_id) else {
+        error!("Share target with id `{target_id}` does not exist");
+        return;
+    };
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_share").unwrap();
+    let destination_entryrow: adw::EntryRow = builder.object("share_destination_entryrow").unwrap();
+    let content_row: adw::ComboRow = builder.object("share_content_row").unwrap();
+    let format_row: adw::ComboRow = builder.object("share_format_row").unwrap();
+
+    let app_settings = appwindow.app().app_settings();
+    let mut destinations = app_settings
+        .as_ref()
+        .and_then(|s| {
+            s.value("share-destinations")
+                .get::<HashMap<String, String>>()
+        })
+        .unwrap_or_default();
+
+    dialog.set_heading(Some(target.name().trim_end_matches('…')));
+    destination_entryrow.set_title(&target.destination_title());
+    destination_entryrow.set_text(
+        &destinations
+            .get(target.id())
+            .cloned()
+            .unwrap_or_else(|| target.default_destination()),
+    );
+    if !canvas.engine_ref().nothing_selected() {
+        content_row.set_selected(1);
+    }
+
+    if dialog.choose_future(appwindow).await.as_str() != "share" {
+        return;
+    }
+    let destination = destination_entryrow.text().to_string();
+    destinations.insert(target.id().to_string(), destination.clone());
+    if let Some(app_settings) = app_settings {
+        if let Err(e) = app_settings.set_value("share-destinations", &destinations.to_variant()) {
+            error!("Saving share destinations to settings failed, Err: {e:?}");
+        }
+    }
+
+    appwindow.overlays().progressbar_start_pulsing();
+    let result = async {
+        let Some(file) = share::gen_share_file(
+            canvas,
+            content_row.selected() == 1,
+            format_row.selected() == 1,
+        )
+        .await?
+        else {
+            return Ok::<_, anyhow::Error>(None);
+        };
+        target.share(destination, file).await.map(Some)
+    }
+    .await;
+    appwindow.overlays().progressbar_finish();
+
+    match result {
+        Ok(None) => appwindow.overlays().dispatch_toast_text(
+            &gettext("Nothing selected to share"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        ),
+        Ok(Some(Some(url))) => {
+            appwindow.clipboard().set_text(&url);
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Shared, the link was copied to the clipboard"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+        Ok(Some(None)) => appwindow.overlays().dispatch_toast_text(
+            &gettext("Shared"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        ),
+        Err(e) => {
+            error!("Sharing failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Sharing failed"));
+        }
+    }
+}
+
+pub(crate) async fn dialog_bookmark_add(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_bookmark_add").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("bookmark_add_name_entryrow").unwrap();
+
+    let n_bookmarks = canvas.engine_ref().document.bookmarks.len();
+    name_entryrow.set_text(&gettext("Bookmark {}").replace("{}", &(n_bookmarks + 1).to_string()));
+
+    if dialog.choose_future(appwindow).await.as_str() != "add" {
+        return;
+    }
+    let name = name_entryrow.text().trim().to_string();
+    if name.is_empty() {
+        return;
+    }
+    let widget_flags = canvas.engine_mut().add_bookmark(name);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Override the background of the page at the given position in document coordinates.
+pub(crate) async fn dialog_page_background(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_page_background").unwrap();
+    let color_button: ColorDialogButton = builder.object("page_background_color_button").unwrap();
+    let pattern_row: adw::ComboRow = builder.object("page_background_pattern_row").unwrap();
+    let pattern_color_button: ColorDialogButton = builder
+        .object("page_background_pattern_color_button")
+        .unwrap();
+
+    let Some(background) = canvas.engine_ref().page_background_at(pos) else {
+        return;
+    };
+    let overridden = canvas.engine_ref().page_background_overridden_at(pos);
+    dialog.set_response_enabled("reset", overridden);
+    color_button.set_rgba(&gdk::RGBA::from_compose_color(background.color));
+    pattern_row.set_selected(background.pattern as u32);
+    pattern_color_button.set_rgba(&gdk::RGBA::from_compose_color(background.pattern_color));
+
+    let page_background = match dialog.choose_future(appwindow).await.as_str() {
+        "apply" => {
+            let Ok(pattern) = PatternStyle::try_from(pattern_row.selected()) else {
+                return;
+            };
+            Some(Background {
+                color: color_button.rgba().into_compose_color(),
+                pattern,
+                pattern_color: pattern_color_button.rgba().into_compose_color(),
+                ..background
+            })
+        }
+        "reset" => None,
+        _ => return,
+    };
+    let widget_flags = canvas
+        .engine_mut()
+        .set_page_background_at(pos, page_background);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Width of the page thumbnails in the pages overview.
+const PAGES_THUMBNAIL_WIDTH: f64 = 160.0;
+
+/// An overview of all pages, where pages can be reordered by drag and drop, duplicated, deleted
+/// and jumped to.
+pub(crate) fn dialog_pages(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    if !canvas.engine_ref().pages_rearrangeable() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("The page overview is only available for documents with a fixed width"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_pages").unwrap();
+    let flowbox: FlowBox = builder.object("pages_flowbox").unwrap();
+
+    flowbox.connect_child_activated(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        dialog,
+        move |_, child| {
+            let Some(page_bounds) = usize::try_from(child.index())
+                .ok()
+                .and_then(|i| canvas.engine_ref().pages_bounds_vertical().get(i).copied())
+            else {
+                return;
+            };
+            let widget_flags = canvas
+                .engine_mut()
+                .follow_link(LinkTarget::Position(page_bounds.mins.coords));
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+            dialog.close();
+        }
+    ));
+
+    pages_flowbox_refresh(appwindow, canvas, &flowbox);
+    dialog.present(appwindow.root().as_ref());
+}
+
+fn pages_flowbox_refresh(appwindow: &RnAppWindow, canvas: &RnCanvas, flowbox: &FlowBox) {
+    let receiver = canvas
+        .engine_ref()
+        .gen_page_thumbnails(PAGES_THUMBNAIL_WIDTH);
+    glib::spawn_future_local(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        flowbox,
+        async move {
+            let thumbnails = match receiver.await {
+                Ok(Ok(thumbnails)) => thumbnails,
+                Ok(Err(e)) => {
+                    error!("Generating page thumbnails failed, Err: {e:?}");
+                    return;
+                }
+                Err(e) => {
+                    error!("Awaiting page thumbnails failed, Err: {e:?}");
+                    return;
+                }
+            };
+            flowbox.remove_all();
+            for (index, image) in thumbnails.into_iter().enumerate() {
+                flowbox.append(&page_thumbnail_item(
+                    &appwindow, &canvas, &flowbox, index, image,
+                ));
+            }
+        }
+    ));
+}
+
+fn page_thumbnail_item(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    flowbox: &FlowBox,
+    index: usize,
+    image: render::Image,
+) -> gtk4::Box {
+    let picture = Picture::builder()
+        .can_shrink(false)
+        .halign(Align::Center)
+        .css_classes(["card"])
+        .build();
+    match image.to_memtexture() {
+        Ok(texture) => picture.set_paintable(Some(&texture)),
+        Err(e) => error!("Creating texture of page thumbnail failed, Err: {e:?}"),
+    }
+    let label = Label::new(Some(
+        &gettext("Page {}").replace("{}", &(index + 1).to_string()),
+    ));
+    let duplicate_button = Button::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text(gettext("Duplicate Page"))
+        .css_classes(["flat"])
+        .build();
+    let delete_button = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text(gettext("Delete Page"))
+        .css_classes(["flat"])
+        .build();
+    let buttons_box = gtk4::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .halign(Align::Center)
+        .spacing(6)
+        .build();
+    buttons_box.append(&duplicate_button);
+    buttons_box.append(&delete_button);
+    let item = gtk4::Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .build();
+    item.append(&picture);
+    item.append(&label);
+    item.append(&buttons_box);
+
+    duplicate_button.connect_clicked(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        flowbox,
+        move |_| {
+            let widget_flags = canvas.engine_mut().page_duplicate(index);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+            pages_flowbox_refresh(&appwindow, &canvas, &flowbox);
+        }
+    ));
+    delete_button.connect_clicked(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        flowbox,
+        move |_| {
+            let widget_flags = canvas.engine_mut().page_delete(index);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+            pages_flowbox_refresh(&appwindow, &canvas, &flowbox);
+        }
+    ));
+
+    // Reorder pages by dragging them onto another page
+    let drag_source = DragSource::builder()
+        .actions(gdk::DragAction::MOVE)
+        .content(&gdk::ContentProvider::for_value(&(index as u32).to_value()))
+        .build();
+    drag_source.connect_drag_begin(clone!(
+        #[weak]
+        picture,
+        move |drag_source, _| {
+            drag_source.set_icon(Some(&WidgetPaintable::new(Some(&picture))), 0, 0);
+        }
+    ));
+    item.add_controller(drag_source);
+    let drop_target = DropTarget::new(u32::static_type(), gdk::DragAction::MOVE);
+    drop_target.connect_drop(clone!(
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        flowbox,
+        #[upgrade_or]
+        false,
+        move |_, value, _, _| {
+            let Ok(from) = value.get::<u32>() else {
+                return false;
+            };
+            let widget_flags = canvas.engine_mut().page_move(from as usize, index);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+            pages_flowbox_refresh(&appwindow, &canvas, &flowbox);
+            true
+        }
+    ));
+    item.add_controller(drop_target);
+
+    item
+}
+
+pub(crate) async fn dialog_broadcast(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_broadcast").unwrap();
+    let port_row: adw::SpinRow = builder.object("broadcast_port_row").unwrap();
+    let allow_remote_row: adw::SwitchRow = builder.object("broadcast_allow_remote_row").unwrap();
+
+    if canvas.encrypted() {
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Encrypted documents can't be broadcast"));
+        return;
+    }
+    port_row.set_value(f64::from(
+        canvas.broadcast_port().unwrap_or(BROADCAST_PORT_DEFAULT),
+    ));
+
+    if dialog.choose_future(appwindow).await.as_str() != "broadcast" {
+        return;
+    }
+    let port = port_row.value() as u16;
+    let allow_remote = allow_remote_row.is_active();
+    match canvas.broadcast_start(port, allow_remote) {
+        Ok(()) => {
+            let host = if allow_remote {
+                glib::host_name().to_string()
+            } else {
+                String::from("localhost")
+            };
+            let url = format!(
+                "http://{host}:{port}{}",
+                canvas.broadcast_path().unwrap_or_default()
+            );
+            appwindow.clipboard().set_text(&url);
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Broadcasting canvas at {}, the address was copied").replace("{}", &url),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+        Err(e) => {
+            error!("Starting broadcast failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Starting broadcast failed"));
+        }
+    }
+}
+
+pub(crate) async fn dialog_collab_host(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_collab_host").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("collab_host_name_entryrow").unwrap();
+    let port_row: adw::SpinRow = builder.object("collab_host_port_row").unwrap();
+    let key_entryrow: adw::EntryRow = builder.object("collab_host_key_entryrow").unwrap();
+    let allow_remote_row: adw::SwitchRow = builder.object("collab_host_allow_remote_row").unwrap();
+
+    name_entryrow.set_text(&collab_default_name());
+    port_row.set_value(f64::from(COLLAB_PORT_DEFAULT));
+    key_entryrow.set_text(&glib::uuid_string_random());
+
+    if dialog.choose_future(appwindow).await.as_str() != "host" {
+        return;
+    }
+    let port = port_row.value() as u16;
+    let session_key = key_entryrow.text().trim().to_string();
+    if session_key.is_empty() {
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("The session key is empty"));
+        return;
+    }
+    match canvas.collab_host(
+        port,
+        collab_name(&name_entryrow),
+        session_key,
+        allow_remote_row.is_active(),
+    ) {
+        Ok(()) => appwindow.overlays().dispatch_toast_text(
+            &gettext("Hosting collaboration session on port {}").replace("{}", &port.to_string()),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        ),
+        Err(e) => {
+            error!("Hosting collaboration session failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Hosting collaboration session failed"));
+        }
+    }
+}
+
+pub(crate) async fn dialog_collab_join(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_collab_join").unwrap();
+    let address_entryrow: adw::EntryRow = builder.object("collab_join_address_entryrow").unwrap();
+    let key_entryrow: adw::PasswordEntryRow = builder.object("collab_join_key_entryrow").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("collab_join_name_entryrow").unwrap();
+
+    name_entryrow.set_text(&collab_default_name());
+
+    if dialog.choose_future(appwindow).await.as_str() != "join" {
+        return;
+    }
+    let address = address_entryrow.text().trim().to_string();
+    if address.is_empty() {
+        return;
+    }
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => (host.to_string(), port),
+            Err(_) => {
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("The port is not valid"));
+                return;
+            }
+        },
+        None => (address, COLLAB_PORT_DEFAULT),
+    };
+    if let Err(e) = canvas
+        .collab_join(
+            &host,
+            port,
+            collab_name(&name_entryrow),
+            key_entryrow.text().trim().to_string(),
+        )
+        .await
+    {
+        error!("Joining collaboration session failed, Err: {e:?}");
+        appwindow
+            .overlays()
+            .dispatch_toast_error(&gettext("Joining collaboration session failed"));
+    }
+}
+
+/// The name of the user, shown next to the cursor on the canvas of the peers.
+fn collab_default_name() -> String {
+    let real_name = glib::real_name().to_string_lossy().to_string();
+    if real_name.is_empty() || real_name == "Unknown" {
+        glib::user_name().to_string_lossy().to_string()
+    } else {
+        real_name
+    }
+}
+
+fn collab_name(name_entryrow: &adw::EntryRow) -> String {
+    let name = name_entryrow.text().trim().to_string();
+    if name.is_empty() {
+        collab_default_name()
+    } else {
+        name
+    }
+}
+
+pub(crate) async fn dialog_insert_table(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_insert_table").unwrap();
+    let rows_row: adw::SpinRow = builder.object("insert_table_rows_row").unwrap();
+    let columns_row: adw::SpinRow = builder.object("insert_table_columns_row").unwrap();
+
+    if dialog.choose_future(appwindow).await.as_str() != "insert" {
+        return;
+    }
+    let widget_flags = canvas.engine_mut().insert_table(
+        rows_row.value() as usize,
+        columns_row.value() as usize,
+        None,
+    );
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+pub(crate) async fn dialog_flatten_selection(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_flatten_selection").unwrap();
+    let dpi_row: adw::SpinRow = builder.object("flatten_selection_dpi_row").unwrap();
+
+    if dialog.choose_future(appwindow).await.as_str() != "flatten" {
+        return;
+    }
+    let res = canvas.engine_mut().flatten_selection(dpi_row.value());
+    match res {
+        Ok(Some(widget_flags)) => appwindow.handle_widget_flags(widget_flags, canvas),
+        Ok(None) => {}
+        Err(e) => {
+            error!("Flattening selection failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Flattening selection failed"));
+        }
+    }
+}
+
+pub(crate) async fn dialog_insert_todo_list(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_insert_todo_list").unwrap();
+    let textview: gtk4::TextView = builder.object("insert_todo_list_textview").unwrap();
+
+    if dialog.choose_future(appwindow).await.as_str() != "insert" {
+        return;
+    }
+    let buffer = textview.buffer();
+    let labels = buffer
+        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<String>>();
+    if labels.is_empty() {
+        return;
+    }
+    let widget_flags = canvas.engine_mut().insert_checkboxes(labels, None);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Edit the text of the table cell at the given position in document coordinates.
+pub(crate) async fn dialog_edit_table_cell(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_edit_table_cell").unwrap();
+    let entryrow: adw::EntryRow = builder.object("edit_table_cell_entryrow").unwrap();
+
+    let Some(text) = canvas.engine_ref().table_cell_text_at(pos) else {
+        return;
+    };
+    entryrow.set_text(&text);
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let widget_flags = canvas
+        .engine_mut()
+        .table_set_cell_text_at(pos, entryrow.text().to_string());
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+pub(crate) async fn dialog_insert_sticky_note(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_sticky_note").unwrap();
+    let textview: gtk4::TextView = builder.object("sticky_note_textview").unwrap();
+    let color_button: ColorDialogButton = builder.object("sticky_note_color_button").unwrap();
+
+    dialog.set_heading(Some(&gettext("Insert Sticky Note")));
+    color_button.set_rgba(&gdk::RGBA::from_compose_color(
+        StickyNoteStroke::COLOR_DEFAULT,
+    ));
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let buffer = textview.buffer();
+    let text = buffer
+        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+        .to_string();
+    let widget_flags = canvas.engine_mut().insert_sticky_note(
+        text,
+        color_button.rgba().into_compose_color(),
+        None,
+    );
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Edit the text of the sticky note at the given position in document coordinates.
+pub(crate) async fn dialog_edit_sticky_note(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_sticky_note").unwrap();
+    let textview: gtk4::TextView = builder.object("sticky_note_textview").unwrap();
+    let color_group: adw::PreferencesGroup = builder.object("sticky_note_color_group").unwrap();
+
+    let Some(text) = canvas.engine_ref().sticky_note_text_at(pos) else {
+        return;
+    };
+    dialog.set_heading(Some(&gettext("Edit Sticky Note")));
+    color_group.set_visible(false);
+    textview.buffer().set_text(&text);
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let buffer = textview.buffer();
+    let text = buffer
+        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+        .to_string();
+    let widget_flags = canvas.engine_mut().sticky_note_set_text_at(pos, text);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+pub(crate) async fn dialog_insert_board(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_board").unwrap();
+    let textview: gtk4::TextView = builder.object("board_textview").unwrap();
+
+    dialog.set_heading(Some(&gettext("Insert Board")));
+    textview
+        .buffer()
+        .set_text(&[gettext("To Do"), gettext("In Progress"), gettext("Done")].join("\n"));
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let columns = board_dialog_columns(&textview);
+    if columns.is_empty() {
+        return;
+    }
+    let widget_flags = canvas.engine_mut().insert_board(columns, None);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Edit the column titles of the board at the given position in document coordinates.
+pub(crate) async fn dialog_edit_board(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_board").unwrap();
+    let textview: gtk4::TextView = builder.object("board_textview").unwrap();
+
+    let Some(columns) = canvas.engine_ref().board_columns_at(pos) else {
+        return;
+    };
+    dialog.set_heading(Some(&gettext("Edit Board")));
+    textview.buffer().set_text(&columns.join("\n"));
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let columns = board_dialog_columns(&textview);
+    if columns.is_empty() {
+        return;
+    }
+    let widget_flags = canvas.engine_mut().board_set_columns_at(pos, columns);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// The column titles entered in the board dialog, one per line.
+fn board_dialog_columns(textview: &gtk4::TextView) -> Vec<String> {
+    let buffer = textview.buffer();
+    buffer
+        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+pub(crate) async fn dialog_insert_mind_map(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_mind_map").unwrap();
+    let textview: gtk4::TextView = builder.object("mind_map_textview").unwrap();
+    let layout_row: adw::ComboRow = builder.object("mind_map_layout_row").unwrap();
+
+    dialog.set_heading(Some(&gettext("Insert Mind Map")));
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let Some((root, layout)) = mind_map_dialog_result(&textview, &layout_row) else {
+        return;
+    };
+    let widget_flags = canvas.engine_mut().insert_mind_map(root, layout, None);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// Edit the nodes and layout of the mind map at the given position in document coordinates.
+pub(crate) async fn dialog_edit_mind_map(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_mind_map").unwrap();
+    let textview: gtk4::TextView = builder.object("mind_map_textview").unwrap();
+    let layout_row: adw::ComboRow = builder.object("mind_map_layout_row").unwrap();
+
+    let Some((outline, layout)) = canvas.engine_ref().mind_map_outline_at(pos) else {
+        return;
+    };
+    dialog.set_heading(Some(&gettext("Edit Mind Map")));
+    textview.buffer().set_text(&outline);
+    layout_row.set_selected(layout.to_u32().unwrap());
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let Some((root, layout)) = mind_map_dialog_result(&textview, &layout_row) else {
+        return;
+    };
+    let widget_flags = canvas
+        .engine_mut()
+        .mind_map_set_outline_at(pos, root, layout);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+/// The root node parsed from the outline and the layout entered in the mind map dialog.
+///
+/// Returns `None` if the outline is empty.
+fn mind_map_dialog_result(
+    textview: &gtk4::TextView,
+    layout_row: &adw::ComboRow,
+) -> Option<(MindMapNode, MindMapLayout)> {
+    let buffer = textview.buffer();
+    let root = MindMapNode::from_outline(
+        buffer
+            .text(&buffer.start_iter(), &buffer.end_iter(), false)
+            .as_str(),
+    )?;
+    let layout = MindMapLayout::try_from(layout_row.selected()).unwrap_or_default();
+    Some((root, layout))
+}
+
+pub(crate) async fn dialog_add_frame(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_frame").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("frame_name_entryrow").unwrap();
+
+    dialog.set_heading(Some(&gettext("Add Frame")));
+    name_entryrow.set_text(&gettext("Frame"));
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let name = name_entryrow.text().trim().to_string();
+    let Some(widget_flags) = canvas.engine_mut().add_frame_around_selection(name) else {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("Adding frame failed, nothing selected"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    };
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
+pub(crate) async fn dialog_rename_frame(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    pos: na::Vector2<f64>,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_frame").unwrap();
+    let name_entryrow: adw::EntryRow = builder.object("frame_name_entryrow").unwrap();
+
+    let Some(name) = canvas.engine_ref().frame_name_at(pos) else {
+        return;
+    };
+    dialog.set_heading(Some(&gettext("Rename Frame")));
+    name_entryrow.set_text(&name);
+
+    if dialog.choose_future(appwindow).await.as_str() != "apply" {
+        return;
+    }
+    let name = name_entryrow.text().trim().to_string();
+    let widget_flags = canvas.engine_mut().frame_set_name_at(pos, name);
+    appwindow.handle_widget_flags(widget_flags, canvas);
+}
+
 pub(crate) async fn dialog_edit_selected_workspace(appwindow: &RnAppWindow) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
@@ -1,7 +1,22 @@
 // Imports
 use crate::config;
+use gtk4::glib;
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The long name of the command line flag that enables portable mode.
+pub(crate) const PORTABLE_FLAG: &str = "portable";
+/// The environment variable that enables portable mode.
+///
+/// Set to `1` to use the default portable directory next to the executable,
+/// or to a path to use that directory instead.
+const PORTABLE_ENV_VAR: &str = "RNOTE_PORTABLE";
+/// The name of the directory next to the executable that is used in portable mode.
+const PORTABLE_DIR_NAME: &str = "rnote-portable";
+
+/// The directory that holds the settings and user data in portable mode. None when not in portable mode.
+static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
 
 pub(crate) fn lib_dir() -> anyhow::Result<PathBuf> {
     if cfg!(target_os = "windows") {
@@ -43,6 +58,61 @@ pub(crate) fn pkg_data_dir() -> anyhow::Result<PathBuf> {
     Ok(data_dir()?.join(config::APP_NAME))
 }
 
+/// Enables portable mode if it was requested through the command line flag or the environment variable,
+/// and creates the portable directory.
+///
+/// Must be called before the app is created, because the settings are set up on creation.
+pub(crate) fn setup_portable_mode() -> anyhow::Result<()> {
+    let flag = format!("--{PORTABLE_FLAG}");
+    let from_flag = std::env::args_os().skip(1).any(|arg| arg == *flag);
+    let from_env_var = std::env::var_os(PORTABLE_ENV_VAR).filter(|v| !v.is_empty() && v != "0");
+
+    let dir = match (from_flag, from_env_var) {
+        (_, Some(value)) if value != "1" => Some(PathBuf::from(value)),
+        (true, _) | (_, Some(_)) => Some(exec_parent_dir()?.join(PORTABLE_DIR_NAME)),
+        (false, None) => None,
+    };
+    if let Some(dir) = &dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    PORTABLE_DIR
+        .set(dir)
+        .map_err(|_| anyhow::anyhow!("Portable mode was already set up"))
+}
+
+/// The directory that holds the settings and user data, if the app runs in portable mode.
+pub(crate) fn portable_dir() -> Option<&'static Path> {
+    PORTABLE_DIR.get().and_then(|dir| dir.as_deref())
+}
+
+/// The settings file in the portable directory, if the app runs in portable mode.
+///
+/// The settings are then stored there instead of the default settings backend.
+pub(crate) fn portable_settings_file() -> Option<PathBuf> {
+    portable_dir().map(|dir| dir.join("settings.ini"))
+}
+
+/// The directory for user data like the stamp and brush libraries.
+pub(crate) fn user_data_dir() -> PathBuf {
+    match portable_dir() {
+        Some(dir) => dir.join("data"),
+        None => glib::user_data_dir().join(config::APP_NAME),
+    }
+}
+
+/// The directory for user provided templates, like the daily notes template.
+pub(crate) fn user_templates_dir() -> PathBuf {
+    user_data_dir().join("templates")
+}
+
+/// The directory for cached data like trace dumps.
+pub(crate) fn user_cache_dir() -> PathBuf {
+    match portable_dir() {
+        Some(dir) => dir.join("cache"),
+        None => glib::user_cache_dir().join(config::APP_NAME),
+    }
+}
+
 pub(crate) fn locale_dir() -> anyhow::Result<PathBuf> {
     if cfg!(target_os = "windows") {
         let exec_dir = exec_parent_dir()?;
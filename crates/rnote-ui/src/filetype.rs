@@ -10,6 +10,7 @@ pub(crate) enum FileType {
     VectorImageFile,
     BitmapImageFile,
     XoppFile,
+    EnexFile,
     PdfFile,
     PlaintextFile,
     Unsupported,
@@ -38,6 +39,9 @@ impl FileType {
                             "application/x-xopp" => {
                                 return Self::XoppFile;
                             }
+                            "application/enex+xml" => {
+                                return Self::EnexFile;
+                            }
                             "application/pdf" => {
                                 return Self::PdfFile;
                             }
@@ -76,6 +80,9 @@ impl FileType {
                     "xopp" => {
                         return Self::XoppFile;
                     }
+                    "enex" => {
+                        return Self::EnexFile;
+                    }
                     "pdf" => {
                         return Self::PdfFile;
                     }
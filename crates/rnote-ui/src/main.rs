@@ -23,14 +23,19 @@ pub(crate) mod globals;
 pub(crate) mod groupediconpicker;
 pub(crate) mod iconpicker;
 pub(crate) mod mainheader;
+pub(crate) mod minimap;
 pub(crate) mod overlays;
 pub(crate) mod penpicker;
+pub(crate) mod penpresetsbar;
 pub(crate) mod penssidebar;
+pub(crate) mod radialmenu;
 pub(crate) mod settingspanel;
+pub(crate) mod share;
 pub(crate) mod sidebar;
 pub(crate) mod strokecontentpaintable;
 pub(crate) mod strokecontentpreview;
 pub(crate) mod strokewidthpicker;
+pub(crate) mod tracedump;
 pub(crate) mod unitentry;
 pub(crate) mod utils;
 pub(crate) mod workspacebrowser;
@@ -48,9 +53,12 @@ pub(crate) use filetype::FileType;
 pub(crate) use groupediconpicker::RnGroupedIconPicker;
 pub(crate) use iconpicker::RnIconPicker;
 pub(crate) use mainheader::RnMainHeader;
+pub(crate) use minimap::RnMinimap;
 pub(crate) use overlays::RnOverlays;
 pub(crate) use penpicker::RnPenPicker;
+pub(crate) use penpresetsbar::RnPenPresetsBar;
 pub(crate) use penssidebar::RnPensSideBar;
+pub(crate) use radialmenu::RnRadialMenu;
 pub(crate) use settingspanel::RnSettingsPanel;
 pub(crate) use sidebar::RnSidebar;
 pub(crate) use strokecontentpaintable::StrokeContentPaintable;
@@ -67,6 +75,7 @@ extern crate parry2d_f64 as p2d;
 use anyhow::Context;
 use gtk4::{gio, glib, prelude::*};
 use tracing::debug;
+use tracing_subscriber::prelude::*;
 
 fn main() -> glib::ExitCode {
     if let Err(e) = setup_tracing() {
@@ -75,6 +84,9 @@ fn main() -> glib::ExitCode {
     if let Err(e) = env::setup_env() {
         eprintln!("failed to setup env, Err: {e:?}");
     }
+    if let Err(e) = env::setup_portable_mode() {
+        eprintln!("failed to setup portable mode, Err: {e:?}");
+    }
     if let Err(e) = setup_i18n() {
         eprintln!("failed to setup i18n, Err: {e:?}");
     }
@@ -89,10 +101,14 @@ fn main() -> glib::ExitCode {
 fn setup_tracing() -> anyhow::Result<()> {
     let timer = tracing_subscriber::fmt::time::Uptime::default();
 
-    tracing_subscriber::fmt()
-        .compact()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .with_timer(timer)
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .compact()
+                .with_timer(timer)
+                .with_filter(tracing_subscriber::EnvFilter::from_default_env()),
+        )
+        .with(tracedump::layer())
         .try_init()
         .map_err(|e| anyhow::anyhow!(e))?;
     debug!(".. tracing subscriber initialized.");
@@ -1,8 +1,8 @@
 // Imports
 use crate::{appmenu::RnAppMenu, appwindow::RnAppWindow, canvasmenu::RnCanvasMenu};
 use gtk4::{
-    glib, prelude::*, subclass::prelude::*, Box, CompositeTemplate, EventControllerLegacy, Label,
-    ToggleButton, Widget,
+    glib, prelude::*, subclass::prelude::*, Box, Button, CompositeTemplate, EventControllerLegacy,
+    Image, Label, Spinner, ToggleButton, Widget,
 };
 
 mod imp {
@@ -16,8 +16,12 @@ mod imp {
         #[template_child]
         pub(crate) main_title: TemplateChild<adw::WindowTitle>,
         #[template_child]
+        pub(crate) main_title_saving_spinner: TemplateChild<Spinner>,
+        #[template_child]
         pub(crate) main_title_unsaved_indicator: TemplateChild<Label>,
         #[template_child]
+        pub(crate) main_title_encrypted_indicator: TemplateChild<Image>,
+        #[template_child]
         pub(crate) left_sidebar_reveal_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) right_sidebar_reveal_toggle: TemplateChild<ToggleButton>,
@@ -29,6 +33,8 @@ mod imp {
         pub(crate) quickactions_box: TemplateChild<Box>,
         #[template_child]
         pub(crate) right_buttons_box: TemplateChild<Box>,
+        #[template_child]
+        pub(crate) collapse_headerbar_button: TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
@@ -85,10 +91,18 @@ impl RnMainHeader {
         self.imp().main_title.get()
     }
 
+    pub(crate) fn main_title_saving_spinner(&self) -> Spinner {
+        self.imp().main_title_saving_spinner.get()
+    }
+
     pub(crate) fn main_title_unsaved_indicator(&self) -> Label {
         self.imp().main_title_unsaved_indicator.get()
     }
 
+    pub(crate) fn main_title_encrypted_indicator(&self) -> Image {
+        self.imp().main_title_encrypted_indicator.get()
+    }
+
     pub(crate) fn left_sidebar_reveal_toggle(&self) -> ToggleButton {
         self.imp().left_sidebar_reveal_toggle.get()
     }
@@ -97,6 +111,10 @@ impl RnMainHeader {
         self.imp().right_sidebar_reveal_toggle.get()
     }
 
+    pub(crate) fn collapse_headerbar_button(&self) -> Button {
+        self.imp().collapse_headerbar_button.get()
+    }
+
     pub(crate) fn canvasmenu(&self) -> RnCanvasMenu {
         self.imp().canvasmenu.get()
     }
@@ -0,0 +1,325 @@
+// Imports
+use crate::{RnAppWindow, RnCanvas, RnCanvasWrapper};
+use gtk4::{
+    gdk, glib, glib::clone, graphene, gsk, prelude::*, subclass::prelude::*, GestureDrag,
+    Orientation, SizeRequestMode, Widget,
+};
+use p2d::bounding_volume::Aabb;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug)]
+    pub(crate) struct RnMinimap {
+        pub(crate) canvas: glib::WeakRef<RnCanvas>,
+        pub(crate) texture: RefCell<Option<gdk::Texture>>,
+        /// The document bounds the texture was generated for.
+        pub(crate) texture_bounds: Cell<Option<Aabb>>,
+        /// The document bounds and viewport at the last draw, used to redraw only when they changed.
+        pub(crate) drawn_bounds: Cell<Option<(Aabb, Aabb)>>,
+        pub(crate) regenerate_timeout_id: RefCell<Option<glib::SourceId>>,
+        pub(crate) drag_gesture: GestureDrag,
+    }
+
+    impl Default for RnMinimap {
+        fn default() -> Self {
+            Self {
+                canvas: glib::WeakRef::new(),
+                texture: RefCell::new(None),
+                texture_bounds: Cell::new(None),
+                drawn_bounds: Cell::new(None),
+                regenerate_timeout_id: RefCell::new(None),
+                drag_gesture: GestureDrag::builder()
+                    .name("minimap_drag_gesture")
+                    .button(gdk::BUTTON_PRIMARY)
+                    .build(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnMinimap {
+        const NAME: &'static str = "RnMinimap";
+        type Type = super::RnMinimap;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("minimap");
+        }
+    }
+
+    impl ObjectImpl for RnMinimap {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_overflow(gtk4::Overflow::Hidden);
+            obj.set_cursor_from_name(Some("pointer"));
+            obj.add_controller(self.drag_gesture.clone());
+
+            let drag_start = Rc::new(Cell::new(na::Vector2::<f64>::zeros()));
+            self.drag_gesture.connect_drag_begin(clone!(
+                #[strong]
+                drag_start,
+                #[weak(rename_to=minimap)]
+                obj,
+                move |gesture, x, y| {
+                    gesture.set_state(gtk4::EventSequenceState::Claimed);
+                    drag_start.set(na::vector![x, y]);
+                    minimap.navigate_to(na::vector![x, y]);
+                }
+            ));
+            self.drag_gesture.connect_drag_update(clone!(
+                #[strong]
+                drag_start,
+                #[weak(rename_to=minimap)]
+                obj,
+                move |_, x, y| {
+                    minimap.navigate_to(drag_start.get() + na::vector![x, y]);
+                }
+            ));
+            self.drag_gesture.connect_drag_end(clone!(
+                #[weak(rename_to=minimap)]
+                obj,
+                move |_, _, _| {
+                    let Some(canvas) = minimap.imp().canvas.upgrade() else {
+                        return;
+                    };
+                    let widget_flags = canvas.engine_mut().update_rendering_current_viewport();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            ));
+
+            // The viewport is changed from many places, so it is polled every frame while the minimap is shown.
+            obj.add_tick_callback(|minimap, _| {
+                minimap.update_from_active_canvas();
+                glib::ControlFlow::Continue
+            });
+        }
+
+        fn dispose(&self) {
+            if let Some(id) = self.regenerate_timeout_id.take() {
+                id.remove();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnMinimap {
+        fn request_mode(&self) -> SizeRequestMode {
+            SizeRequestMode::ConstantSize
+        }
+
+        fn measure(&self, orientation: Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            match orientation {
+                Orientation::Horizontal => (super::WIDTH, super::WIDTH, -1, -1),
+                Orientation::Vertical => (super::HEIGHT, super::HEIGHT, -1, -1),
+                _ => unimplemented!(),
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk4::Snapshot) {
+            let obj = self.obj();
+            let Some((doc_bounds, viewport)) = self.drawn_bounds.get() else {
+                return;
+            };
+            let to_widget = |bounds: Aabb| -> graphene::Rect {
+                let (scale, offset) = obj.doc_to_widget_transform(doc_bounds);
+                let mins = bounds.mins.coords * scale + offset;
+                let extents = bounds.extents() * scale;
+                graphene::Rect::new(
+                    mins[0] as f32,
+                    mins[1] as f32,
+                    extents[0] as f32,
+                    extents[1] as f32,
+                )
+            };
+
+            // accessing colors through the style context is deprecated,
+            // but this needs new color API to fetch theme colors.
+            #[allow(deprecated)]
+            let accent_color = obj
+                .style_context()
+                .lookup_color("accent_color")
+                .unwrap_or(gdk::RGBA::new(0.208, 0.518, 0.894, 1.0));
+
+            if let (Some(texture), Some(texture_bounds)) =
+                (&*self.texture.borrow(), self.texture_bounds.get())
+            {
+                snapshot.append_texture(texture, &to_widget(texture_bounds));
+            }
+
+            let viewport_rect = to_widget(viewport);
+            let mut fill_color = accent_color;
+            fill_color.set_alpha(0.15);
+            snapshot.append_color(&fill_color, &viewport_rect);
+            snapshot.append_border(
+                &gsk::RoundedRect::from_rect(viewport_rect, 0.0),
+                &[1.5; 4],
+                &[accent_color; 4],
+            );
+        }
+    }
+}
+
+/// The width of the minimap.
+const WIDTH: i32 = 120;
+/// The height of the minimap.
+const HEIGHT: i32 = 180;
+/// The time to wait after the last change of the document before the overview image is regenerated.
+const REGENERATE_TIMEOUT: Duration = Duration::from_millis(750);
+
+glib::wrapper! {
+    /// A small overview of the whole document with the current viewport, which can be dragged to navigate.
+    pub(crate) struct RnMinimap(ObjectSubclass<imp::RnMinimap>)
+        @extends Widget;
+}
+
+impl Default for RnMinimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnMinimap {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Regenerate the overview image of the document after a short delay,
+    /// so that it is not regenerated on every change while drawing.
+    pub(crate) fn regenerate_image(&self) {
+        if let Some(id) = self.imp().regenerate_timeout_id.take() {
+            id.remove();
+        }
+        if !self.is_visible() {
+            // Regenerated when shown again
+            self.imp().texture_bounds.set(None);
+            return;
+        }
+        let id = glib::timeout_add_local_once(
+            REGENERATE_TIMEOUT,
+            clone!(
+                #[weak(rename_to=minimap)]
+                self,
+                move || {
+                    minimap.imp().regenerate_timeout_id.take();
+                    minimap.regenerate_image_now();
+                }
+            ),
+        );
+        self.imp().regenerate_timeout_id.replace(Some(id));
+    }
+
+    fn regenerate_image_now(&self) {
+        let Some(canvas) = self.imp().canvas.upgrade() else {
+            return;
+        };
+        let scale_factor = self.scale_factor() as f64;
+        let doc_bounds = canvas.engine_ref().document.bounds();
+        let (scale, _) = self.doc_to_widget_transform(doc_bounds);
+        let receiver = canvas
+            .engine_ref()
+            .gen_overview_image(doc_bounds.extents()[0] * scale * scale_factor);
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=minimap)]
+            self,
+            #[weak]
+            canvas,
+            async move {
+                let image = match receiver.await {
+                    Ok(Ok(image)) => image,
+                    Ok(Err(e)) => {
+                        error!("Generating the minimap image failed, Err: {e:?}");
+                        return;
+                    }
+                    Err(e) => {
+                        error!("Awaiting the minimap image failed, Err: {e:?}");
+                        return;
+                    }
+                };
+                // The active tab might have changed in the meantime
+                if minimap.imp().canvas.upgrade().as_ref() != Some(&canvas) {
+                    return;
+                }
+                match image.to_memtexture() {
+                    Ok(texture) => {
+                        minimap.imp().texture.replace(Some(texture.upcast()));
+                        minimap.imp().texture_bounds.set(Some(doc_bounds));
+                        minimap.queue_draw();
+                    }
+                    Err(e) => {
+                        error!("Creating the minimap texture failed, Err: {e:?}");
+                    }
+                }
+            }
+        ));
+    }
+
+    /// Track the active canvas and redraw when its document bounds or viewport have changed.
+    fn update_from_active_canvas(&self) {
+        let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        // There is no selected tab while the window is closing
+        let Some(wrapper) = appwindow
+            .overlays()
+            .tabview()
+            .selected_page()
+            .and_then(|page| page.child().downcast::<RnCanvasWrapper>().ok())
+        else {
+            return;
+        };
+        let canvas = wrapper.canvas();
+        let imp = self.imp();
+
+        if imp.canvas.upgrade().as_ref() != Some(&canvas) {
+            imp.canvas.set(Some(&canvas));
+            imp.texture.replace(None);
+            imp.texture_bounds.set(None);
+        }
+        if imp.texture_bounds.get().is_none() && imp.regenerate_timeout_id.borrow().is_none() {
+            imp.texture_bounds
+                .set(Some(canvas.engine_ref().document.bounds()));
+            self.regenerate_image_now();
+        }
+
+        let bounds = {
+            let engine = canvas.engine_ref();
+            (engine.document.bounds(), engine.camera.viewport())
+        };
+        if imp.drawn_bounds.get() != Some(bounds) {
+            imp.drawn_bounds.set(Some(bounds));
+            self.queue_draw();
+        }
+    }
+
+    /// The scale and offset to map document coordinates into the widget, fitting the document bounds.
+    fn doc_to_widget_transform(&self, doc_bounds: Aabb) -> (f64, na::Vector2<f64>) {
+        let size = na::vector![self.width() as f64, self.height() as f64];
+        let doc_extents = doc_bounds.extents().map(|e| e.max(1.0));
+        let scale = (size[0] / doc_extents[0]).min(size[1] / doc_extents[1]);
+        let offset = (size - doc_extents * scale) * 0.5 - doc_bounds.mins.coords * scale;
+        (scale, offset)
+    }
+
+    /// Move the viewport of the canvas to be centered at the given position in widget coordinates.
+    fn navigate_to(&self, pos: na::Vector2<f64>) {
+        let Some(canvas) = self.imp().canvas.upgrade() else {
+            return;
+        };
+        let doc_bounds = canvas.engine_ref().document.bounds();
+        let (scale, offset) = self.doc_to_widget_transform(doc_bounds);
+        let doc_pos = (pos - offset) / scale;
+        let new_offset = {
+            let engine = canvas.engine_ref();
+            doc_pos * engine.camera.total_zoom() - engine.camera.size() * 0.5
+        };
+        let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
+        canvas.emit_handle_widget_flags(widget_flags);
+    }
+}
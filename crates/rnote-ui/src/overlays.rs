@@ -1,12 +1,14 @@
 // Imports
 use crate::canvaswrapper::RnCanvasWrapper;
 use crate::RnPensSideBar;
-use crate::{dialogs, RnAppWindow, RnColorPicker, RnPenPicker};
+use crate::{dialogs, RnAppWindow, RnColorPicker, RnMinimap, RnPenPicker, RnPenPresetsBar};
 use core::time::Duration;
+use gettextrs::gettext;
 use gtk4::{
-    gio, glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, Overlay,
-    ProgressBar, ScrolledWindow, Widget,
+    gio, glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, Overlay,
+    ProgressBar, Revealer, ScrolledWindow, Widget,
 };
+use rnote_engine::engine::Progress;
 use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::PenStyle;
 use std::cell::{Cell, RefCell};
@@ -20,6 +22,8 @@ mod imp {
     pub(crate) struct RnOverlays {
         pub(crate) progresspulses_active: Cell<usize>,
         pub(crate) progresspulse_id: RefCell<Option<glib::SourceId>>,
+        pub(crate) tracked_progress: RefCell<Option<Progress>>,
+        pub(crate) tracked_progress_toast: glib::WeakRef<adw::Toast>,
         pub(super) prev_active_tab_page: glib::WeakRef<adw::TabPage>,
 
         #[template_child]
@@ -29,10 +33,18 @@ mod imp {
         #[template_child]
         pub(crate) progressbar: TemplateChild<ProgressBar>,
         #[template_child]
+        pub(crate) penpicker_revealer: TemplateChild<Revealer>,
+        #[template_child]
         pub(crate) penpicker: TemplateChild<RnPenPicker>,
         #[template_child]
+        pub(crate) penpresetsbar: TemplateChild<RnPenPresetsBar>,
+        #[template_child]
         pub(crate) colorpicker: TemplateChild<RnColorPicker>,
         #[template_child]
+        pub(crate) reveal_headerbar_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) minimap: TemplateChild<RnMinimap>,
+        #[template_child]
         pub(crate) tabview: TemplateChild<adw::TabView>,
         #[template_child]
         pub(crate) sidebar_box: TemplateChild<gtk4::Box>,
@@ -40,6 +52,8 @@ mod imp {
         pub(crate) sidebar_scroller: TemplateChild<ScrolledWindow>,
         #[template_child]
         pub(crate) penssidebar: TemplateChild<RnPensSideBar>,
+        #[template_child]
+        pub(crate) text_format_revealer: TemplateChild<Revealer>,
     }
 
     #[glib::object_subclass]
@@ -79,6 +93,8 @@ mod imp {
                 .set_measure_overlay(&*self.colorpicker, true);
             self.toolbar_overlay
                 .set_measure_overlay(&*self.penpicker, true);
+            self.toolbar_overlay
+                .set_measure_overlay(&*self.penpresetsbar, true);
             self.toolbar_overlay
                 .set_measure_overlay(&*self.sidebar_box, true);
         }
@@ -108,10 +124,28 @@ impl RnOverlays {
         self.imp().penpicker.get()
     }
 
+    /// Hides the pen picker in focus mode until the pointer approaches the bottom edge.
+    pub(crate) fn penpicker_revealer(&self) -> Revealer {
+        self.imp().penpicker_revealer.get()
+    }
+
+    pub(crate) fn penpresetsbar(&self) -> RnPenPresetsBar {
+        self.imp().penpresetsbar.get()
+    }
+
     pub(crate) fn colorpicker(&self) -> RnColorPicker {
         self.imp().colorpicker.get()
     }
 
+    /// The floating button to show the headerbar again after it was hidden.
+    pub(crate) fn reveal_headerbar_button(&self) -> Button {
+        self.imp().reveal_headerbar_button.get()
+    }
+
+    pub(crate) fn minimap(&self) -> RnMinimap {
+        self.imp().minimap.get()
+    }
+
     pub(crate) fn toast_overlay(&self) -> adw::ToastOverlay {
         self.imp().toast_overlay.get()
     }
@@ -136,6 +170,11 @@ impl RnOverlays {
         self.imp().penssidebar.get()
     }
 
+    /// The floating text format toolbar, revealed while editing text.
+    pub(crate) fn text_format_revealer(&self) -> Revealer {
+        self.imp().text_format_revealer.get()
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
         imp.colorpicker.get().init(appwindow);
@@ -226,6 +265,11 @@ impl RnOverlays {
     fn setup_tabview(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
+        // Ctrl+Tab is used to switch to the most recently used tab instead
+        imp.tabview.remove_shortcuts(
+            adw::TabViewShortcuts::CONTROL_TAB | adw::TabViewShortcuts::CONTROL_SHIFT_TAB,
+        );
+
         imp.tabview.connect_selected_page_notify(clone!(
             #[weak(rename_to=overlays)]
             self,
@@ -248,6 +292,7 @@ impl RnOverlays {
                     .imp()
                     .prev_active_tab_page
                     .set(Some(&active_tab_page));
+                appwindow.app().record_recent_tab(&active_tab_page);
 
                 let widget_flags = active_canvaswrapper.canvas().engine_mut().set_active(true);
                 appwindow.handle_widget_flags(widget_flags, &active_canvaswrapper.canvas());
@@ -264,12 +309,15 @@ impl RnOverlays {
                 canvaswrapper.connect_to_tab_page(page);
                 let widget_flags = canvaswrapper.canvas().engine_mut().set_active(true);
                 appwindow.handle_widget_flags(widget_flags, &canvaswrapper.canvas());
+                appwindow.refresh_unsaved_tabs();
             }
         ));
 
         imp.tabview.connect_page_detached(clone!(
             #[weak(rename_to=overlays)]
             self,
+            #[weak]
+            appwindow,
             move |_, page, _| {
                 let canvaswrapper = page.child().downcast::<RnCanvasWrapper>().unwrap();
 
@@ -285,6 +333,7 @@ impl RnOverlays {
 
                 let _ = canvaswrapper.canvas().engine_mut().set_active(false);
                 canvaswrapper.disconnect_connections();
+                appwindow.refresh_unsaved_tabs();
             }
         ));
 
@@ -371,7 +420,16 @@ impl RnOverlays {
                         #[upgrade_or]
                         glib::ControlFlow::Break,
                         move || {
-                            appwindow.progressbar().pulse();
+                            let fraction = appwindow
+                                .imp()
+                                .tracked_progress
+                                .borrow()
+                                .as_ref()
+                                .and_then(|progress| progress.fraction());
+                            match fraction {
+                                Some(fraction) => appwindow.progressbar().set_fraction(fraction),
+                                None => appwindow.progressbar().pulse(),
+                            }
 
                             glib::ControlFlow::Continue
                         }
@@ -382,9 +440,42 @@ impl RnOverlays {
         }
     }
 
+    /// Report the progress of a long running operation in the progressbar
+    /// and dispatch a toast with a button to cancel the operation.
+    ///
+    /// The progressbar needs to be started before, the progress is tracked until it is finished or aborted.
+    pub(crate) fn progressbar_track(&self, text: &str, progress: Progress) {
+        self.progressbar_untrack();
+        let toast = self.dispatch_toast_w_button(
+            text,
+            &gettext("Cancel"),
+            clone!(
+                #[strong]
+                progress,
+                move |_toast| {
+                    progress.cancel();
+                }
+            ),
+            None,
+        );
+        self.imp().tracked_progress.replace(Some(progress));
+        self.imp()
+            .tracked_progress_toast
+            .set(toast.upgrade().as_ref());
+    }
+
+    fn progressbar_untrack(&self) {
+        self.imp().tracked_progress.take();
+        if let Some(toast) = self.imp().tracked_progress_toast.upgrade() {
+            toast.dismiss();
+        }
+        self.imp().tracked_progress_toast.set(None);
+    }
+
     pub(crate) fn progressbar_finish(&self) {
         const FINISH_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
 
+        self.progressbar_untrack();
         self.progressbar().set_fraction(1.);
         self.imp()
             .progresspulses_active
@@ -409,6 +500,7 @@ impl RnOverlays {
 
     #[allow(unused)]
     pub(crate) fn progressbar_abort(&self) {
+        self.progressbar_untrack();
         self.imp()
             .progresspulses_active
             .set(self.imp().progresspulses_active.get().saturating_sub(1));
@@ -12,6 +12,8 @@ mod imp {
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/penpicker.ui")]
     pub(crate) struct RnPenPicker {
+        #[template_child]
+        pub(crate) pens_box: TemplateChild<gtk4::Box>,
         #[template_child]
         pub(crate) brush_toggle: TemplateChild<ToggleButton>,
         #[template_child]
@@ -105,6 +107,18 @@ impl RnPenPicker {
         self.imp().redo_button.get()
     }
 
+    /// Shrink the pen buttons for small screens.
+    pub(crate) fn set_compact(&self, compact: bool) {
+        let pens_box = self.imp().pens_box.get();
+        if compact {
+            pens_box.set_width_request(-1);
+            pens_box.set_spacing(2);
+        } else {
+            pens_box.set_width_request(350);
+            pens_box.set_spacing(6);
+        }
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
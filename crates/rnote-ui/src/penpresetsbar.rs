@@ -0,0 +1,126 @@
+// Imports
+use crate::RnCanvasWrapper;
+use gettextrs::gettext;
+use gtk4::{
+    glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate, GestureClick, TemplateChild,
+    Widget,
+};
+use rnote_engine::pens::pensconfig::presets::{PenPreset, PenPresets};
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/penpresetsbar.ui")]
+    pub(crate) struct RnPenPresetsBar {
+        #[template_child]
+        pub(crate) presets_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) save_preset_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnPenPresetsBar {
+        const NAME: &'static str = "RnPenPresetsBar";
+        type Type = super::RnPenPresetsBar;
+        type ParentType = gtk4::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnPenPresetsBar {
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnPenPresetsBar {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnPenPresetsBar(ObjectSubclass<imp::RnPenPresetsBar>)
+    @extends Widget;
+}
+
+impl Default for RnPenPresetsBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnPenPresetsBar {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
+        let imp = self.imp();
+        let presets = active_tab.canvas().engine_ref().pens_config.presets.clone();
+
+        while let Some(child) = imp.presets_box.first_child() {
+            imp.presets_box.remove(&child);
+        }
+        for (i, preset) in presets.iter().enumerate() {
+            imp.presets_box.append(&preset_button(i, preset));
+        }
+        imp.presets_box.set_visible(!presets.is_empty());
+        imp.save_preset_button
+            .set_sensitive(presets.len() < PenPresets::MAX_PRESETS);
+    }
+}
+
+fn preset_button(i: usize, preset: &PenPreset) -> Button {
+    let (stroke_color, _) = preset.options.stroke_color_width();
+    let color_hex = stroke_color
+        .map(|c| format!("#{:06x}", u32::from(c) >> 8))
+        .unwrap_or_else(|| String::from("#000000"));
+
+    let content = gtk4::Box::builder().spacing(3).build();
+    content.append(&gtk4::Image::from_icon_name(
+        &preset.options.pen_style().icon_name(),
+    ));
+    let label = gtk4::Label::new(None);
+    label.set_markup(&format!(
+        "<span foreground=\"{color_hex}\">●</span> {}",
+        i + 1
+    ));
+    content.append(&label);
+
+    let button = Button::builder()
+        .child(&content)
+        .tooltip_text(format!(
+            "{}\n{}",
+            preset.name,
+            gettext("Right click to remove")
+        ))
+        .action_name("win.pen-preset-apply")
+        .action_target(&(i as i32).to_variant())
+        .css_classes(["flat"])
+        .build();
+
+    let remove_gesture = GestureClick::builder()
+        .button(gtk4::gdk::BUTTON_SECONDARY)
+        .build();
+    remove_gesture.connect_pressed(move |gesture, _, _, _| {
+        if let Some(widget) = gesture.widget() {
+            if let Err(e) =
+                widget.activate_action("win.pen-preset-remove", Some(&(i as i32).to_variant()))
+            {
+                error!("Activating action `pen-preset-remove` failed, Err: {e:?}");
+            }
+        }
+    });
+    button.add_controller(remove_gesture);
+
+    button
+}
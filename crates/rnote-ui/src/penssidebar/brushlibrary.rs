@@ -0,0 +1,106 @@
+// Imports
+use crate::config;
+use anyhow::Context;
+use gtk4::gio;
+use rnote_compose::style::custom::BrushDefinition;
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+
+/// The file extension of brush definition files.
+pub(crate) const BRUSH_FILE_EXTENSION: &str = "rnbrush";
+
+/// The file names of the brush definitions that are bundled as resources.
+const BUILTIN_BRUSHES: &[&str] = &["round", "calligraphy", "chalk", "spray"];
+
+/// The directory of the user brush library.
+///
+/// Every brush definition file in it is available as custom brush.
+pub(crate) fn user_brushes_dir() -> PathBuf {
+    crate::env::user_data_dir().join("brushes")
+}
+
+/// Parses a brush definition file, which is a Json serialized [BrushDefinition].
+pub(crate) fn parse_brush_definition(data: &[u8]) -> anyhow::Result<BrushDefinition> {
+    Ok(serde_json::from_slice::<BrushDefinition>(data)?.sanitized())
+}
+
+/// Loads the bundled brushes, followed by the brushes of the user library sorted by name.
+pub(crate) fn load_brushes() -> Vec<BrushDefinition> {
+    let mut brushes = BUILTIN_BRUSHES
+        .iter()
+        .filter_map(|name| {
+            let path =
+                String::from(config::APP_IDPATH) + "brushes/" + name + "." + BRUSH_FILE_EXTENSION;
+            let definition = gio::resources_lookup_data(&path, gio::ResourceLookupFlags::NONE)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| parse_brush_definition(&bytes));
+            match definition {
+                Ok(definition) => Some(definition),
+                Err(e) => {
+                    error!("Loading bundled brush `{name}` failed, Err: {e:?}");
+                    None
+                }
+            }
+        })
+        .collect::<Vec<BrushDefinition>>();
+
+    let user_brushes_dir = user_brushes_dir();
+    if !user_brushes_dir.is_dir() {
+        return brushes;
+    }
+    let mut user_brushes = match brush_files_in(&user_brushes_dir) {
+        Ok(files) => files
+            .into_iter()
+            .filter_map(|file| {
+                match std::fs::read(&file)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|data| parse_brush_definition(&data))
+                {
+                    Ok(definition) => Some(definition),
+                    Err(e) => {
+                        warn!("Reading brush file `{}` failed, Err: {e:?}", file.display());
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<BrushDefinition>>(),
+        Err(e) => {
+            error!("Reading the user brush library failed, Err: {e:?}");
+            vec![]
+        }
+    };
+    user_brushes.sort_by(|a, b| a.name.cmp(&b.name));
+    brushes.append(&mut user_brushes);
+    brushes
+}
+
+/// Copies the given brush definition files into the user brush library.
+///
+/// Every file is validated first, so invalid files don't end up in the library.
+pub(crate) fn import_files(files: &[PathBuf]) -> anyhow::Result<()> {
+    let user_brushes_dir = user_brushes_dir();
+    std::fs::create_dir_all(&user_brushes_dir).context("Creating the brush library failed")?;
+
+    for file in files {
+        let Some(file_name) = file.file_name() else {
+            continue;
+        };
+        parse_brush_definition(&std::fs::read(file)?)
+            .with_context(|| format!("Invalid brush definition file `{}`", file.display()))?;
+        std::fs::copy(file, user_brushes_dir.join(file_name))
+            .with_context(|| format!("Copying brush `{}` failed", file.display()))?;
+    }
+    Ok(())
+}
+
+fn brush_files_in(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(BRUSH_FILE_EXTENSION))
+        })
+        .collect())
+}
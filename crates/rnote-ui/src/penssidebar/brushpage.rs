@@ -1,16 +1,24 @@
 // Imports
+use super::brushlibrary;
 use crate::{RnAppWindow, RnCanvasWrapper, RnStrokeWidthPicker};
 use adw::prelude::*;
+use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, subclass::prelude::*, Button, CompositeTemplate, ListBox, MenuButton,
-    Popover,
+    gio, glib, glib::clone, subclass::prelude::*, Button, CompositeTemplate, FileDialog,
+    FileFilter, ListBox, MenuButton, Popover, StringList,
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::PenPathBuilderType;
+use rnote_compose::style::custom::BrushDefinition;
+use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::style::textured::{TexturedDotsDistribution, TexturedOptions};
 use rnote_compose::style::PressureCurve;
-use rnote_engine::pens::pensconfig::brushconfig::{BrushStyle, SolidOptions};
+use rnote_engine::pens::pensconfig::brushconfig::{
+    BrushStyle, SolidOptions, SymmetryConfig, SymmetryMode,
+};
 use rnote_engine::pens::pensconfig::BrushConfig;
+use std::cell::RefCell;
+use tracing::{debug, error};
 
 mod imp {
     use super::*;
@@ -33,6 +41,8 @@ mod imp {
         #[template_child]
         pub(crate) brushstyle_textured_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) brushstyle_custom_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub(crate) brushconfig_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) brushconfig_popover: TemplateChild<Popover>,
@@ -47,13 +57,35 @@ mod imp {
         #[template_child]
         pub(crate) brush_buildertype_modeled: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) brush_simulate_pressure_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) symmetry_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) symmetry_axis_angle_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) symmetry_n_fold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) symmetry_radial_mirror_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) symmetry_center_button: TemplateChild<Button>,
+        #[template_child]
         pub(crate) solidstyle_pressure_curves_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) solidstyle_tilt_sensitivity_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) texturedstyle_density_row: TemplateChild<adw::SpinRow>,
         #[template_child]
         pub(crate) texturedstyle_distribution_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) customstyle_brush_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) customstyle_brush_list: TemplateChild<StringList>,
+        #[template_child]
+        pub(crate) customstyle_import_button: TemplateChild<Button>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
+
+        pub(crate) brushes: RefCell<Vec<BrushDefinition>>,
     }
 
     #[glib::object_subclass]
@@ -129,6 +161,10 @@ impl RnBrushPage {
                 .imp()
                 .brushstyle_listbox
                 .select_row(Some(&*self.imp().brushstyle_textured_row)),
+            BrushStyle::Custom => self
+                .imp()
+                .brushstyle_listbox
+                .select_row(Some(&*self.imp().brushstyle_custom_row)),
         }
     }
 
@@ -188,6 +224,50 @@ impl RnBrushPage {
             .set_selected(position);
     }
 
+    pub(crate) fn symmetry_mode(&self) -> SymmetryMode {
+        SymmetryMode::try_from(self.imp().symmetry_mode_row.get().selected()).unwrap()
+    }
+
+    pub(crate) fn set_symmetry_mode(&self, mode: SymmetryMode) {
+        let position = mode.to_u32().unwrap();
+
+        self.imp().symmetry_mode_row.get().set_selected(position);
+    }
+
+    /// Reloads the brush library and updates the brush choices.
+    fn reload_brushes(&self) {
+        let imp = self.imp();
+        let brushes = brushlibrary::load_brushes();
+        let names = brushes
+            .iter()
+            .map(|brush| brush.name.as_str())
+            .collect::<Vec<&str>>();
+
+        // Replacing the list items changes the selection, so the brushes are stored first
+        *imp.brushes.borrow_mut() = brushes.clone();
+        imp.customstyle_brush_list
+            .splice(0, imp.customstyle_brush_list.n_items(), &names);
+    }
+
+    fn set_customstyle_brush(&self, definition: &BrushDefinition) {
+        let imp = self.imp();
+        let position = imp
+            .brushes
+            .borrow()
+            .iter()
+            .position(|brush| brush == definition)
+            .or_else(|| {
+                imp.brushes
+                    .borrow()
+                    .iter()
+                    .position(|brush| brush.name == definition.name)
+            });
+
+        if let Some(position) = position {
+            imp.customstyle_brush_row.set_selected(position as u32);
+        }
+    }
+
     pub(crate) fn stroke_width_picker(&self) -> RnStrokeWidthPicker {
         self.imp().stroke_width_picker.get()
     }
@@ -256,6 +336,14 @@ impl RnBrushPage {
                                 .textured_options
                                 .stroke_width = stroke_width;
                         }
+                        BrushStyle::Custom => {
+                            canvas
+                                .engine_mut()
+                                .pens_config
+                                .brush_config
+                                .custom_options
+                                .stroke_width = stroke_width;
+                        }
                     }
                 }
             ),
@@ -333,6 +421,24 @@ impl RnBrushPage {
                                 .brushstyle_menubutton
                                 .set_icon_name("pen-brush-style-textured-symbolic");
                         }
+                        BrushStyle::Custom => {
+                            let stroke_width = appwindow
+                                .active_tab_wrapper()
+                                .canvas()
+                                .engine_mut()
+                                .pens_config
+                                .brush_config
+                                .custom_options
+                                .stroke_width;
+                            brushpage
+                                .imp()
+                                .stroke_width_picker
+                                .set_stroke_width(stroke_width);
+                            brushpage
+                                .imp()
+                                .brushstyle_menubutton
+                                .set_icon_name("pen-brush-style-custom-symbolic");
+                        }
                     }
                 }
             }
@@ -357,6 +463,121 @@ impl RnBrushPage {
             }
         ));
 
+        // Simulate pressure
+        imp.brush_simulate_pressure_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .simulate_pressure = row.is_active();
+                }
+            ));
+
+        // Symmetry
+        // Mode
+        imp.symmetry_mode_row.get().connect_selected_notify(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let mode = brushpage.symmetry_mode();
+                {
+                    let mut engine = canvas.engine_mut();
+                    let viewport_center = engine.camera.viewport().center().coords;
+                    let symmetry = &mut engine.pens_config.brush_config.symmetry;
+                    // Place the center where the user currently looks at when enabling symmetry
+                    if symmetry.mode == SymmetryMode::Off && mode != SymmetryMode::Off {
+                        symmetry.center = viewport_center;
+                    }
+                    symmetry.mode = mode;
+                }
+                canvas.queue_draw();
+            }
+        ));
+
+        // Axis angle
+        imp.symmetry_axis_angle_row
+            .get()
+            .set_value(SymmetryConfig::default().axis_angle);
+
+        imp.symmetry_axis_angle_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .brush_config
+                    .symmetry
+                    .axis_angle = row.value();
+                canvas.queue_draw();
+            }
+        ));
+
+        // N-fold
+        imp.symmetry_n_fold_row.get().set_range(
+            SymmetryConfig::N_FOLD_MIN as f64,
+            SymmetryConfig::N_FOLD_MAX as f64,
+        );
+        // set value after the range!
+        imp.symmetry_n_fold_row
+            .get()
+            .set_value(SymmetryConfig::default().n_fold as f64);
+
+        imp.symmetry_n_fold_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                canvas.engine_mut().pens_config.brush_config.symmetry.n_fold =
+                    row.value().round() as u32;
+                canvas.queue_draw();
+            }
+        ));
+
+        // Radial mirror
+        imp.symmetry_radial_mirror_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .symmetry
+                        .radial_mirror = row.is_active();
+                }
+            ));
+
+        // Center
+        imp.symmetry_center_button.get().connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                {
+                    let mut engine = canvas.engine_mut();
+                    let viewport_center = engine.camera.viewport().center().coords;
+                    engine.pens_config.brush_config.symmetry.center = viewport_center;
+                }
+                canvas.queue_draw();
+            }
+        ));
+
         // Solid style
         // Pressure curve
         imp.solidstyle_pressure_curves_row
@@ -378,6 +599,33 @@ impl RnBrushPage {
                 }
             ));
 
+        // Tilt sensitivity
+        imp.solidstyle_tilt_sensitivity_row.get().set_range(
+            SmoothOptions::TILT_SENSITIVITY_MIN,
+            SmoothOptions::TILT_SENSITIVITY_MAX,
+        );
+        // set value after the range!
+        imp.solidstyle_tilt_sensitivity_row
+            .get()
+            .set_value(SolidOptions::default().tilt_sensitivity);
+
+        imp.solidstyle_tilt_sensitivity_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .solid_options
+                        .tilt_sensitivity = row.value();
+                }
+            ));
+
         // Textured style
         // Density
         imp.texturedstyle_density_row
@@ -422,6 +670,102 @@ impl RnBrushPage {
                         .distribution = brushpage.texturedstyle_dots_distribution();
                 }
             ));
+
+        // Custom style
+        // Brush
+        self.reload_brushes();
+
+        imp.customstyle_brush_row
+            .get()
+            .connect_selected_notify(clone!(
+                #[weak(rename_to=brushpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(definition) = brushpage
+                        .imp()
+                        .brushes
+                        .borrow()
+                        .get(row.selected() as usize)
+                        .cloned()
+                    else {
+                        return;
+                    };
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .custom_options
+                        .definition = definition;
+                }
+            ));
+
+        // Import
+        imp.customstyle_import_button.connect_clicked(clone!(
+            #[weak(rename_to=brushpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    brushpage,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        brushpage.import_brushes(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+    }
+
+    async fn import_brushes(&self, appwindow: &RnAppWindow) {
+        let filter = FileFilter::new();
+        filter.add_suffix(brushlibrary::BRUSH_FILE_EXTENSION);
+        filter.set_name(Some(&gettext("Brush Definitions")));
+
+        let filter_list = gio::ListStore::new::<FileFilter>();
+        filter_list.append(&filter);
+
+        let filedialog = FileDialog::builder()
+            .title(gettext("Import Brushes"))
+            .modal(true)
+            .accept_label(gettext("Import"))
+            .filters(&filter_list)
+            .default_filter(&filter)
+            .build();
+
+        let files = match filedialog.open_multiple_future(Some(appwindow)).await {
+            Ok(files) => files
+                .iter::<gio::File>()
+                .filter_map(|file| file.ok()?.path())
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                debug!("Did not import brushes (Error or dialog dismissed by user), Err: {e:?}");
+                return;
+            }
+        };
+
+        match brushlibrary::import_files(&files) {
+            Ok(()) => {
+                self.reload_brushes();
+                self.refresh_ui(&appwindow.active_tab_wrapper());
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Imported brushes"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+            Err(e) => {
+                error!("Importing brushes failed, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Importing brushes failed"));
+            }
+        }
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -433,10 +777,22 @@ impl RnBrushPage {
             .brush_config
             .clone();
 
+        imp.brush_simulate_pressure_row
+            .set_active(brush_config.simulate_pressure);
+        self.set_symmetry_mode(brush_config.symmetry.mode);
+        imp.symmetry_axis_angle_row
+            .set_value(brush_config.symmetry.axis_angle);
+        imp.symmetry_n_fold_row
+            .set_value(brush_config.symmetry.n_fold as f64);
+        imp.symmetry_radial_mirror_row
+            .set_active(brush_config.symmetry.radial_mirror);
         self.set_solidstyle_pressure_curve(brush_config.solid_options.pressure_curve);
+        imp.solidstyle_tilt_sensitivity_row
+            .set_value(brush_config.solid_options.tilt_sensitivity);
         imp.texturedstyle_density_row
             .set_value(brush_config.textured_options.density);
         self.set_texturedstyle_distribution_variant(brush_config.textured_options.distribution);
+        self.set_customstyle_brush(&brush_config.custom_options.definition);
 
         self.set_brush_style(brush_config.style);
         self.set_buildertype(brush_config.builder_type);
@@ -454,6 +810,10 @@ impl RnBrushPage {
                 imp.stroke_width_picker
                     .set_stroke_width(brush_config.textured_options.stroke_width);
             }
+            BrushStyle::Custom => {
+                imp.stroke_width_picker
+                    .set_stroke_width(brush_config.custom_options.stroke_width);
+            }
         }
     }
 }
@@ -1,8 +1,10 @@
 // Modules
+mod brushlibrary;
 mod brushpage;
 mod eraserpage;
 mod selectorpage;
 mod shaperpage;
+mod stamplibrary;
 mod toolspage;
 mod typewriterpage;
 
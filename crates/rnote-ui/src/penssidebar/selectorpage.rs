@@ -1,6 +1,10 @@
 // Imports
-use crate::{RnAppWindow, RnCanvasWrapper};
-use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, ToggleButton};
+use crate::{RnAppWindow, RnCanvasWrapper, RnUnitEntry};
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, MenuButton,
+    Popover, ToggleButton,
+};
+use p2d::bounding_volume::Aabb;
 use rnote_engine::pens::pensconfig::selectorconfig::SelectorStyle;
 
 mod imp {
@@ -19,6 +23,26 @@ mod imp {
         pub(crate) selectorstyle_intersectingpath_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) resize_lock_aspectratio_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) edit_nodes_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) selection_transform_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) selection_opacity_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) selection_transform_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) selection_transform_x_unitentry: TemplateChild<RnUnitEntry>,
+        #[template_child]
+        pub(crate) selection_transform_y_unitentry: TemplateChild<RnUnitEntry>,
+        #[template_child]
+        pub(crate) selection_transform_width_unitentry: TemplateChild<RnUnitEntry>,
+        #[template_child]
+        pub(crate) selection_transform_height_unitentry: TemplateChild<RnUnitEntry>,
+        #[template_child]
+        pub(crate) selection_transform_rotation_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) selection_transform_apply_button: TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
@@ -68,6 +92,14 @@ impl RnSelectorPage {
         glib::Object::new()
     }
 
+    pub(crate) fn selection_transform_menubutton(&self) -> MenuButton {
+        self.imp().selection_transform_menubutton.get()
+    }
+
+    pub(crate) fn selection_opacity_menubutton(&self) -> MenuButton {
+        self.imp().selection_opacity_menubutton.get()
+    }
+
     #[allow(unused)]
     pub(crate) fn selector_style(&self) -> Option<SelectorStyle> {
         if self.imp().selectorstyle_polygon_toggle.is_active() {
@@ -96,6 +128,43 @@ impl RnSelectorPage {
         }
     }
 
+    /// Fills the transform entries with the current selection bounds, in the unit of the rulers.
+    fn refresh_transform_entries(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let canvas = appwindow.active_tab_wrapper().canvas();
+        let (bounds, dpi, unit) = {
+            let engine = canvas.engine_ref();
+            (
+                engine.selection_bounds(),
+                engine.document.format.dpi(),
+                engine.rulers_unit(),
+            )
+        };
+        let Some(bounds) = bounds else {
+            imp.selection_transform_apply_button.set_sensitive(false);
+            return;
+        };
+        imp.selection_transform_apply_button.set_sensitive(true);
+
+        for (unitentry, value) in [
+            (&imp.selection_transform_x_unitentry, bounds.mins[0]),
+            (&imp.selection_transform_y_unitentry, bounds.mins[1]),
+            (
+                &imp.selection_transform_width_unitentry,
+                bounds.extents()[0],
+            ),
+            (
+                &imp.selection_transform_height_unitentry,
+                bounds.extents()[1],
+            ),
+        ] {
+            unitentry.set_dpi(dpi);
+            unitentry.set_unit(unit);
+            unitentry.set_value_in_px(value);
+        }
+        imp.selection_transform_rotation_row.set_value(0.0);
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
@@ -178,6 +247,56 @@ impl RnSelectorPage {
                         .resize_lock_aspectratio = resize_lock_aspectratio_togglebutton.is_active();
                 }
             ));
+
+        imp.edit_nodes_togglebutton.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |edit_nodes_togglebutton| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                canvas.engine_mut().pens_config.selector_config.edit_nodes =
+                    edit_nodes_togglebutton.is_active();
+                canvas.queue_draw();
+            }
+        ));
+
+        imp.selection_transform_menubutton
+            .connect_active_notify(clone!(
+                #[weak(rename_to=selectorpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |menubutton| {
+                    if menubutton.is_active() {
+                        selectorpage.refresh_transform_entries(&appwindow);
+                    }
+                }
+            ));
+
+        imp.selection_transform_apply_button.connect_clicked(clone!(
+            #[weak(rename_to=selectorpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let imp = selectorpage.imp();
+                let mins = na::point![
+                    imp.selection_transform_x_unitentry.value_in_px(),
+                    imp.selection_transform_y_unitentry.value_in_px()
+                ];
+                let extents = na::vector![
+                    imp.selection_transform_width_unitentry.value_in_px(),
+                    imp.selection_transform_height_unitentry.value_in_px()
+                ];
+                let angle = imp.selection_transform_rotation_row.value().to_radians();
+
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas
+                    .engine_mut()
+                    .transform_selection(Aabb::new(mins, mins + extents), angle);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                imp.selection_transform_popover.popdown();
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -194,5 +313,7 @@ impl RnSelectorPage {
 
         imp.resize_lock_aspectratio_togglebutton
             .set_active(selector_config.resize_lock_aspectratio);
+        imp.edit_nodes_togglebutton
+            .set_active(selector_config.edit_nodes);
     }
 }
@@ -6,15 +6,19 @@ use crate::{
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, Button, CompositeTemplate, ListBox, MenuButton, Popover, StringList,
+    gdk, glib, glib::clone, Button, ColorDialogButton, CompositeTemplate, ListBox, MenuButton,
+    Popover, StringList,
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::ShapeBuilderType;
 use rnote_compose::constraints::ConstraintRatio;
 use rnote_compose::style::rough::roughoptions::FillStyle;
 use rnote_compose::style::smooth::SmoothOptions;
+use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::pensconfig::shaperconfig::ShaperStyle;
 use rnote_engine::pens::pensconfig::ShaperConfig;
+use rnote_engine::strokes::gradient::GradientKind;
+use rnote_engine::strokes::hatch::HatchStyle;
 
 mod imp {
     use super::*;
@@ -37,6 +41,20 @@ mod imp {
         #[template_child]
         pub(crate) roughstyle_hachure_angle_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub(crate) gradient_kind_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) gradient_start_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) gradient_end_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) gradient_angle_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) hatch_style_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) hatch_spacing_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) hatch_angle_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
         #[template_child]
         pub(crate) shapebuildertype_menubutton: TemplateChild<MenuButton>,
@@ -174,6 +192,26 @@ impl RnShaperPage {
             .set_selected(position);
     }
 
+    pub(crate) fn gradient_kind(&self) -> GradientKind {
+        GradientKind::try_from(self.imp().gradient_kind_row.get().selected()).unwrap()
+    }
+
+    pub(crate) fn set_gradient_kind(&self, kind: GradientKind) {
+        let position = kind.to_u32().unwrap();
+
+        self.imp().gradient_kind_row.get().set_selected(position);
+    }
+
+    pub(crate) fn hatch_style(&self) -> HatchStyle {
+        HatchStyle::try_from(self.imp().hatch_style_row.get().selected()).unwrap()
+    }
+
+    pub(crate) fn set_hatch_style(&self, style: HatchStyle) {
+        let position = style.to_u32().unwrap();
+
+        self.imp().hatch_style_row.get().set_selected(position);
+    }
+
     pub(crate) fn stroke_width_picker(&self) -> RnStrokeWidthPicker {
         self.imp().stroke_width_picker.get()
     }
@@ -359,6 +397,125 @@ impl RnShaperPage {
                 }
             ));
 
+        // Gradient fill
+        imp.gradient_kind_row.get().connect_selected_notify(clone!(
+            #[weak(rename_to=shaperpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .fill_gradient
+                    .kind = shaperpage.gradient_kind();
+            }
+        ));
+
+        imp.gradient_start_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .fill_gradient
+                    .start_color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.gradient_end_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .fill_gradient
+                    .end_color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.gradient_angle_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .fill_gradient
+                    .angle = row
+                    .value()
+                    .round()
+                    .to_radians()
+                    .clamp(-std::f64::consts::PI, std::f64::consts::PI);
+            }
+        ));
+
+        // Hatch fill
+        imp.hatch_style_row.get().connect_selected_notify(clone!(
+            #[weak(rename_to=shaperpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .hatch
+                    .style = shaperpage.hatch_style();
+            }
+        ));
+
+        imp.hatch_spacing_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .hatch
+                    .spacing = row.value();
+            }
+        ));
+
+        imp.hatch_angle_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .hatch
+                    .angle = row
+                    .value()
+                    .round()
+                    .to_radians()
+                    .clamp(-std::f64::consts::PI, std::f64::consts::PI);
+            }
+        ));
+
         // shape builder type
         imp.shapebuildertype_picker.set_groups(
             shape_builder_type_icons_get_groups(),
@@ -532,6 +689,25 @@ impl RnShaperPage {
         imp.roughstyle_hachure_angle_row
             .set_value(shaper_config.rough_options.hachure_angle.to_degrees());
 
+        // Gradient fill
+        self.set_gradient_kind(shaper_config.fill_gradient.kind);
+        imp.gradient_start_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                shaper_config.fill_gradient.start_color,
+            ));
+        imp.gradient_end_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                shaper_config.fill_gradient.end_color,
+            ));
+        imp.gradient_angle_row
+            .set_value(shaper_config.fill_gradient.angle.to_degrees());
+
+        // Hatch fill
+        self.set_hatch_style(shaper_config.hatch.style);
+        imp.hatch_spacing_row.set_value(shaper_config.hatch.spacing);
+        imp.hatch_angle_row
+            .set_value(shaper_config.hatch.angle.to_degrees());
+
         // constraints
         imp.constraint_enabled_row
             .set_active(shaper_config.constraints.enabled);
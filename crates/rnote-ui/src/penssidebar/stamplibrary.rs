@@ -0,0 +1,112 @@
+// Imports
+use crate::config;
+use anyhow::Context;
+use gtk4::gio;
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+
+/// The names of the stamps that are bundled as resources.
+const BUILTIN_STAMPS: &[&str] = &[
+    "arrow-right",
+    "arrow-double",
+    "checkmark",
+    "cross",
+    "star",
+    "heart",
+    "smiley",
+    "sad",
+    "resistor",
+    "capacitor",
+    "diode",
+    "battery",
+    "ground",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) struct Stamp {
+    pub(crate) name: String,
+    pub(crate) svg_data: String,
+}
+
+/// The directory of the user stamp library.
+///
+/// Every Svg file in it is available as stamp.
+pub(crate) fn user_stamps_dir() -> PathBuf {
+    crate::env::user_data_dir().join("stamps")
+}
+
+/// Loads the bundled stamps, followed by the stamps of the user library sorted by name.
+pub(crate) fn load_stamps() -> Vec<Stamp> {
+    let mut stamps = BUILTIN_STAMPS
+        .iter()
+        .filter_map(|name| {
+            let path = String::from(config::APP_IDPATH) + "stamps/" + name + ".svg";
+            match gio::resources_lookup_data(&path, gio::ResourceLookupFlags::NONE) {
+                Ok(bytes) => Some(Stamp {
+                    name: name.to_string(),
+                    svg_data: String::from_utf8_lossy(&bytes).into_owned(),
+                }),
+                Err(e) => {
+                    error!("Loading bundled stamp `{name}` failed, Err: {e:?}");
+                    None
+                }
+            }
+        })
+        .collect::<Vec<Stamp>>();
+
+    let user_stamps_dir = user_stamps_dir();
+    if !user_stamps_dir.is_dir() {
+        return stamps;
+    }
+    let mut user_stamps = match svg_files_in(&user_stamps_dir) {
+        Ok(files) => files
+            .into_iter()
+            .filter_map(|file| match std::fs::read_to_string(&file) {
+                Ok(svg_data) => Some(Stamp {
+                    name: file.file_stem()?.to_string_lossy().to_string(),
+                    svg_data,
+                }),
+                Err(e) => {
+                    warn!("Reading stamp file `{}` failed, Err: {e:?}", file.display());
+                    None
+                }
+            })
+            .collect::<Vec<Stamp>>(),
+        Err(e) => {
+            error!("Reading the user stamp library failed, Err: {e:?}");
+            vec![]
+        }
+    };
+    user_stamps.sort_by(|a, b| a.name.cmp(&b.name));
+    stamps.append(&mut user_stamps);
+    stamps
+}
+
+/// Copies all Svg files in the given folder into the user stamp library.
+///
+/// Files with the same name as an existing stamp replace it.
+pub(crate) fn import_folder(folder: &Path) -> anyhow::Result<()> {
+    let user_stamps_dir = user_stamps_dir();
+    std::fs::create_dir_all(&user_stamps_dir).context("Creating the stamp library failed")?;
+
+    for file in svg_files_in(folder)? {
+        let Some(file_name) = file.file_name() else {
+            continue;
+        };
+        std::fs::copy(&file, user_stamps_dir.join(file_name))
+            .with_context(|| format!("Copying stamp `{}` failed", file.display()))?;
+    }
+    Ok(())
+}
+
+fn svg_files_in(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+        })
+        .collect())
+}
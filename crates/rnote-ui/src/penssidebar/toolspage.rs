@@ -1,10 +1,19 @@
 // Imports
+use super::stamplibrary::{self, Stamp};
 use crate::{RnAppWindow, RnCanvasWrapper};
+use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, MenuButton,
-    Popover, ToggleButton,
+    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, Button, ColorDialogButton,
+    CompositeTemplate, FileDialog, FlowBox, MenuButton, Popover, ToggleButton,
 };
+use num_traits::ToPrimitive;
+use rnote_engine::document::format::MeasureUnit;
+use rnote_engine::document::perspective::PerspectiveMode;
+use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::pensconfig::toolsconfig::ToolStyle;
+use rnote_engine::strokes::connectorstroke::ConnectorRouting;
+use std::cell::RefCell;
+use tracing::{debug, error};
 
 mod imp {
     use super::*;
@@ -19,6 +28,8 @@ mod imp {
         #[template_child]
         pub(crate) toolstyle_zoom_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) toolstyle_measure_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) verticalspace_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) verticalspace_popover: TemplateChild<Popover>,
@@ -29,6 +40,54 @@ mod imp {
         #[template_child]
         pub(crate) verticalspace_limit_movement_horizontal_bordersrow:
             TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) measure_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) measure_unit_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) toolstyle_stamp_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) stamp_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) stamp_flowbox: TemplateChild<FlowBox>,
+        #[template_child]
+        pub(crate) stamp_size_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) stamp_import_folder_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) toolstyle_fill_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) fill_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) fill_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) toolstyle_perspective_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) perspective_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) perspective_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) perspective_constrain_lines_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) perspective_reset_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) toolstyle_redaction_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) redaction_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) toolstyle_connector_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) connector_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) connector_routing_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) connector_arrow_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) connector_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) connector_color_button: TemplateChild<ColorDialogButton>,
+
+        pub(crate) stamps: RefCell<Vec<Stamp>>,
     }
 
     #[glib::object_subclass]
@@ -88,6 +147,18 @@ impl RnToolsPage {
             Some(ToolStyle::OffsetCamera)
         } else if imp.toolstyle_zoom_toggle.is_active() {
             Some(ToolStyle::Zoom)
+        } else if imp.toolstyle_measure_toggle.is_active() {
+            Some(ToolStyle::Measure)
+        } else if imp.toolstyle_stamp_toggle.is_active() {
+            Some(ToolStyle::Stamp)
+        } else if imp.toolstyle_fill_toggle.is_active() {
+            Some(ToolStyle::Fill)
+        } else if imp.toolstyle_perspective_toggle.is_active() {
+            Some(ToolStyle::Perspective)
+        } else if imp.toolstyle_redaction_toggle.is_active() {
+            Some(ToolStyle::Redaction)
+        } else if imp.toolstyle_connector_toggle.is_active() {
+            Some(ToolStyle::Connector)
         } else {
             None
         }
@@ -98,6 +169,30 @@ impl RnToolsPage {
         self.imp().verticalspace_menubutton.get()
     }
 
+    pub(crate) fn measure_menubutton(&self) -> MenuButton {
+        self.imp().measure_menubutton.get()
+    }
+
+    pub(crate) fn stamp_menubutton(&self) -> MenuButton {
+        self.imp().stamp_menubutton.get()
+    }
+
+    pub(crate) fn fill_menubutton(&self) -> MenuButton {
+        self.imp().fill_menubutton.get()
+    }
+
+    pub(crate) fn perspective_menubutton(&self) -> MenuButton {
+        self.imp().perspective_menubutton.get()
+    }
+
+    pub(crate) fn redaction_menubutton(&self) -> MenuButton {
+        self.imp().redaction_menubutton.get()
+    }
+
+    pub(crate) fn connector_menubutton(&self) -> MenuButton {
+        self.imp().connector_menubutton.get()
+    }
+
     #[allow(unused)]
     pub(crate) fn set_tool_style(&self, style: ToolStyle) {
         let imp = self.imp();
@@ -106,6 +201,51 @@ impl RnToolsPage {
             ToolStyle::VerticalSpace => imp.toolstyle_verticalspace_toggle.set_active(true),
             ToolStyle::OffsetCamera => imp.toolstyle_offsetcamera_toggle.set_active(true),
             ToolStyle::Zoom => imp.toolstyle_zoom_toggle.set_active(true),
+            ToolStyle::Measure => imp.toolstyle_measure_toggle.set_active(true),
+            ToolStyle::Stamp => imp.toolstyle_stamp_toggle.set_active(true),
+            ToolStyle::Fill => imp.toolstyle_fill_toggle.set_active(true),
+            ToolStyle::Perspective => imp.toolstyle_perspective_toggle.set_active(true),
+            ToolStyle::Redaction => imp.toolstyle_redaction_toggle.set_active(true),
+            ToolStyle::Connector => imp.toolstyle_connector_toggle.set_active(true),
+        }
+    }
+
+    /// Reloads the stamp library and rebuilds the stamp picker.
+    fn reload_stamps(&self) {
+        let imp = self.imp();
+        let stamps = stamplibrary::load_stamps();
+
+        imp.stamp_flowbox.remove_all();
+        for stamp in stamps.iter() {
+            let image =
+                match gdk::Texture::from_bytes(&glib::Bytes::from(stamp.svg_data.as_bytes())) {
+                    Ok(texture) => gtk4::Image::from_paintable(Some(&texture)),
+                    Err(e) => {
+                        error!(
+                            "Creating preview for stamp `{}` failed, Err: {e:?}",
+                            stamp.name
+                        );
+                        gtk4::Image::from_icon_name("image-missing-symbolic")
+                    }
+                };
+            image.set_pixel_size(32);
+            image.set_tooltip_text(Some(&stamp.name));
+            imp.stamp_flowbox.append(&image);
+        }
+        *imp.stamps.borrow_mut() = stamps;
+    }
+
+    fn select_stamp(&self, stamp_name: &str) {
+        let imp = self.imp();
+        let index = imp
+            .stamps
+            .borrow()
+            .iter()
+            .position(|stamp| stamp.name == stamp_name);
+
+        match index.and_then(|i| imp.stamp_flowbox.child_at_index(i as i32)) {
+            Some(child) => imp.stamp_flowbox.select_child(&child),
+            None => imp.stamp_flowbox.unselect_all(),
         }
     }
 
@@ -162,6 +302,102 @@ impl RnToolsPage {
             }
         ));
 
+        imp.toolstyle_measure_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Measure;
+                }
+            }
+        ));
+
+        imp.toolstyle_stamp_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Stamp;
+                }
+            }
+        ));
+
+        imp.toolstyle_fill_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Fill;
+                }
+            }
+        ));
+
+        imp.toolstyle_perspective_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Perspective;
+                }
+            }
+        ));
+
+        imp.toolstyle_redaction_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Redaction;
+                }
+            }
+        ));
+
+        imp.toolstyle_connector_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if toggle.is_active() {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .style = ToolStyle::Connector;
+                }
+            }
+        ));
+
         imp.verticalspace_menubutton.connect_active_notify(clone!(
             #[weak(rename_to=toolspage)]
             self,
@@ -172,6 +408,66 @@ impl RnToolsPage {
             }
         ));
 
+        imp.measure_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Measure);
+                }
+            }
+        ));
+
+        imp.stamp_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Stamp);
+                }
+            }
+        ));
+
+        imp.fill_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Fill);
+                }
+            }
+        ));
+
+        imp.perspective_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Perspective);
+                }
+            }
+        ));
+
+        imp.redaction_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Redaction);
+                }
+            }
+        ));
+
+        imp.connector_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Connector);
+                }
+            }
+        ));
+
         imp.verticalspace_popover_close_button
             .connect_clicked(clone!(
                 #[weak]
@@ -213,6 +509,237 @@ impl RnToolsPage {
                         .limit_movement_horizontal_borders = row.is_active();
                 }
             ));
+
+        imp.measure_unit_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(unit) = MeasureUnit::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .measure_tool_config
+                        .unit = unit;
+                    canvas.queue_draw();
+                }
+            ));
+
+        imp.fill_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .fill_tool_config
+                    .fill_color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.connector_routing_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(routing) = ConnectorRouting::try_from(row.selected()) else {
+                        return;
+                    };
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .connector_tool_config
+                        .routing = routing;
+                }
+            ));
+
+        imp.connector_arrow_row.get().connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .connector_tool_config
+                    .arrow = row.is_active();
+            }
+        ));
+
+        imp.connector_width_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .connector_tool_config
+                    .width = row.value();
+            }
+        ));
+
+        imp.connector_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .connector_tool_config
+                    .color = button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.perspective_mode_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(mode) = PerspectiveMode::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas.engine_mut().set_perspective_mode(mode);
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.perspective_constrain_lines_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .document
+                        .perspective
+                        .constrain_lines = row.is_active();
+                }
+            ));
+
+        imp.perspective_reset_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().reset_vanishing_points();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        self.reload_stamps();
+
+        imp.stamp_flowbox.connect_child_activated(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, child| {
+                let Some(stamp) = toolspage
+                    .imp()
+                    .stamps
+                    .borrow()
+                    .get(child.index() as usize)
+                    .cloned()
+                else {
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let mut engine = canvas.engine_mut();
+                engine.pens_config.tools_config.stamp_tool_config.stamp_name = stamp.name;
+                engine.pens_config.tools_config.stamp_tool_config.svg_data = stamp.svg_data;
+            }
+        ));
+
+        imp.stamp_size_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .stamp_tool_config
+                    .size = row.value();
+            }
+        ));
+
+        imp.stamp_import_folder_button.connect_clicked(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    toolspage,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let filedialog = FileDialog::builder()
+                            .title(gettext("Import Stamps From Folder"))
+                            .modal(true)
+                            .accept_label(gettext("Import"))
+                            .build();
+
+                        let folder = match filedialog.select_folder_future(Some(&appwindow)).await
+                        {
+                            Ok(selected_file) => selected_file.path(),
+                            Err(e) => {
+                                debug!("Did not select folder to import stamps from (Error or dialog dismissed by user), Err: {e:?}");
+                                None
+                            }
+                        };
+                        let Some(folder) = folder else {
+                            return;
+                        };
+
+                        match stamplibrary::import_folder(&folder) {
+                            Ok(()) => {
+                                toolspage.reload_stamps();
+                                toolspage.refresh_ui(&appwindow.active_tab_wrapper());
+                                appwindow.overlays().dispatch_toast_text(
+                                    &gettext("Imported stamps"),
+                                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                                );
+                            }
+                            Err(e) => {
+                                error!("Importing stamps from folder `{}` failed, Err: {e:?}", folder.display());
+                                appwindow
+                                    .overlays()
+                                    .dispatch_toast_error(&gettext("Importing stamps failed"));
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -222,6 +749,12 @@ impl RnToolsPage {
             .pens_config
             .tools_config
             .clone();
+        let perspective = active_tab
+            .canvas()
+            .engine_ref()
+            .document
+            .perspective
+            .clone();
 
         self.set_tool_style(tools_config.style);
 
@@ -238,5 +771,28 @@ impl RnToolsPage {
                     .verticalspace_tool_config
                     .limit_movement_vertical_borders,
             );
+        imp.measure_unit_row
+            .set_selected(tools_config.measure_tool_config.unit.to_u32().unwrap());
+        imp.stamp_size_row
+            .set_value(tools_config.stamp_tool_config.size);
+        self.select_stamp(&tools_config.stamp_tool_config.stamp_name);
+        imp.fill_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                tools_config.fill_tool_config.fill_color,
+            ));
+        imp.connector_routing_row
+            .set_selected(tools_config.connector_tool_config.routing.to_u32().unwrap());
+        imp.connector_arrow_row
+            .set_active(tools_config.connector_tool_config.arrow);
+        imp.connector_width_row
+            .set_value(tools_config.connector_tool_config.width);
+        imp.connector_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                tools_config.connector_tool_config.color,
+            ));
+        imp.perspective_mode_row
+            .set_selected(perspective.mode.to_u32().unwrap());
+        imp.perspective_constrain_lines_row
+            .set_active(perspective.constrain_lines);
     }
 }
@@ -35,6 +35,8 @@ mod imp {
         #[template_child]
         pub(crate) text_strikethrough_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) text_heading_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) text_align_start_togglebutton: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) text_align_center_togglebutton: TemplateChild<ToggleButton>,
@@ -134,7 +136,7 @@ impl RnTypewriterPage {
 
                         typewriterpage.imp().prev_picked_font_family.borrow_mut().replace(new_font_family);
                         canvas.engine_mut().pens_config.typewriter_config.text_style.font_family.clone_from(&font_family_name);
-                        let widget_flags = canvas.engine_mut().text_selection_change_style(|style| {style.font_family = font_family_name});
+                        let widget_flags = canvas.engine_mut().text_change_font_family(font_family_name);
                         appwindow.handle_widget_flags(widget_flags, &canvas);
                     }
                     Err(e) => debug!("Did not choose new font family (Error or dialog dismissed by user), Err: {e:?}"),
@@ -246,6 +248,28 @@ impl RnTypewriterPage {
             }
         ));
 
+        // Heading
+        imp.text_heading_togglebutton.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |togglebutton| {
+                let heading = togglebutton.is_active();
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                {
+                    let mut engine = canvas.engine_mut();
+                    let text_style = &mut engine.pens_config.typewriter_config.text_style;
+                    if text_style.heading == heading {
+                        return;
+                    }
+                    text_style.heading = heading;
+                }
+                let widget_flags = canvas
+                    .engine_mut()
+                    .text_selection_change_style(|style| style.heading = heading);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
         // Alignment
         imp.text_align_start_togglebutton
             .connect_active_notify(clone!(
@@ -350,5 +374,7 @@ impl RnTypewriterPage {
             .set_value(typewriter_config.text_style.font_size);
 
         self.set_alignment(typewriter_config.text_style.alignment);
+        imp.text_heading_togglebutton
+            .set_active(typewriter_config.text_style.heading);
     }
 }
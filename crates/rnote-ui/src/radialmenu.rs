@@ -0,0 +1,199 @@
+// Imports
+use crate::RnAppWindow;
+use gtk4::{
+    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, Fixed,
+    Popover, Widget,
+};
+use rnote_compose::Color;
+use rnote_engine::ext::GdkRGBAExt;
+use std::f64::consts::PI;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/radialmenu.ui")]
+    pub(crate) struct RnRadialMenu {
+        #[template_child]
+        pub(crate) popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) fixed: TemplateChild<Fixed>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnRadialMenu {
+        const NAME: &'static str = "RnRadialMenu";
+        type Type = super::RnRadialMenu;
+        type ParentType = gtk4::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnRadialMenu {
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnRadialMenu {
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            self.popover.get().present();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub(crate) struct RnRadialMenu(ObjectSubclass<imp::RnRadialMenu>)
+    @extends Widget;
+}
+
+impl Default for RnRadialMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnRadialMenu {
+    /// The size of the buttons arranged on the circle.
+    const BUTTON_SIZE: f64 = 36.0;
+    /// The minimum radius of the circle the buttons are arranged on.
+    const RADIUS_MIN: f64 = 56.0;
+
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn popover(&self) -> Popover {
+        self.imp().popover.get()
+    }
+
+    /// Rebuild the items of the menu and open it centered at the given position.
+    pub(crate) fn popup_at(&self, appwindow: &RnAppWindow, pos: na::Vector2<f64>) {
+        let imp = self.imp();
+        let buttons = self.build_buttons(appwindow);
+
+        while let Some(child) = imp.fixed.first_child() {
+            imp.fixed.remove(&child);
+        }
+
+        // Spread the buttons evenly on a circle, starting at the top.
+        let n = buttons.len().max(1) as f64;
+        let radius = (n * Self::BUTTON_SIZE * 1.25 / (2.0 * PI)).max(Self::RADIUS_MIN);
+        let size = 2.0 * radius + Self::BUTTON_SIZE;
+        for (i, button) in buttons.iter().enumerate() {
+            let angle = 2.0 * PI * i as f64 / n - PI * 0.5;
+            let x = radius + radius * angle.cos();
+            let y = radius + radius * angle.sin();
+            button.set_size_request(Self::BUTTON_SIZE as i32, Self::BUTTON_SIZE as i32);
+            imp.fixed.put(button, x, y);
+        }
+        imp.fixed.set_size_request(size as i32, size as i32);
+
+        let popover = imp.popover.get();
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(
+            pos[0] as i32,
+            pos[1] as i32,
+            1,
+            1,
+        )));
+        popover.set_offset(0, -(size * 0.5) as i32);
+        popover.popup();
+    }
+
+    fn build_buttons(&self, appwindow: &RnAppWindow) -> Vec<Button> {
+        let canvas = appwindow.active_tab_wrapper().canvas();
+        let undo_button = self.action_button("edit-undo-symbolic", "win.undo", None);
+        undo_button.set_sensitive(canvas.engine_ref().can_undo());
+        let redo_button = self.action_button("edit-redo-symbolic", "win.redo", None);
+        redo_button.set_sensitive(canvas.engine_ref().can_redo());
+        let mut buttons = vec![undo_button, redo_button];
+
+        let presets = canvas.engine_ref().pens_config.presets.clone();
+        for (i, preset) in presets.iter().enumerate() {
+            let button = self.action_button(
+                &preset.options.pen_style().icon_name(),
+                "win.pen-preset-apply",
+                Some((i as i32).to_variant()),
+            );
+            button.set_tooltip_text(Some(&preset.name));
+            buttons.push(button);
+        }
+
+        let colorpicker = appwindow.overlays().colorpicker();
+        for setter in [
+            colorpicker.setter_1(),
+            colorpicker.setter_2(),
+            colorpicker.setter_3(),
+            colorpicker.setter_4(),
+            colorpicker.setter_5(),
+            colorpicker.setter_6(),
+            colorpicker.setter_7(),
+            colorpicker.setter_8(),
+            colorpicker.setter_9(),
+        ] {
+            buttons.push(self.color_button(appwindow, setter.color().into_compose_color()));
+        }
+
+        buttons
+    }
+
+    fn action_button(
+        &self,
+        icon_name: &str,
+        action_name: &str,
+        target: Option<glib::Variant>,
+    ) -> Button {
+        let button = Button::builder()
+            .icon_name(icon_name)
+            .action_name(action_name)
+            .css_classes(["circular"])
+            .build();
+        if let Some(target) = target {
+            button.set_action_target_value(Some(&target));
+        }
+        button.connect_clicked(clone!(
+            #[weak(rename_to=radialmenu)]
+            self,
+            move |_| {
+                radialmenu.popover().popdown();
+            }
+        ));
+        button
+    }
+
+    fn color_button(&self, appwindow: &RnAppWindow, color: Color) -> Button {
+        let color_hex = format!("#{:06x}", u32::from(color) >> 8);
+        let label = gtk4::Label::new(None);
+        label.set_markup(&format!(
+            "<span foreground=\"{color_hex}\" size=\"x-large\">●</span>"
+        ));
+        let button = Button::builder()
+            .child(&label)
+            .css_classes(["circular"])
+            .build();
+        button.connect_clicked(clone!(
+            #[weak(rename_to=radialmenu)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                appwindow
+                    .overlays()
+                    .colorpicker()
+                    .set_stroke_color(gdk::RGBA::from_compose_color(color));
+                radialmenu.popover().popdown();
+            }
+        ));
+        button
+    }
+}
@@ -7,20 +7,26 @@ pub(crate) use penshortcutrow::RnPenShortcutRow;
 use rnote_compose::ext::Vector2Ext;
 
 // Imports
-use crate::{RnAppWindow, RnCanvasWrapper, RnIconPicker, RnUnitEntry};
+use crate::{RnAppWindow, RnCanvas, RnCanvasWrapper, RnIconPicker, RnUnitEntry};
 use adw::prelude::*;
 use gettextrs::{gettext, pgettext};
 use gtk4::{
-    gdk, glib, glib::clone, subclass::prelude::*, Adjustment, Button, ColorDialogButton,
-    CompositeTemplate, MenuButton, ScrolledWindow, StringList, ToggleButton, Widget,
+    gdk, gio, glib, glib::clone, subclass::prelude::*, Adjustment, Button, ColorDialogButton,
+    CompositeTemplate, FileDialog, FileFilter, MenuButton, ScrolledWindow, StringList,
+    ToggleButton, Widget,
 };
 use num_traits::ToPrimitive;
 use rnote_compose::penevent::ShortcutKey;
+use rnote_compose::style::PressureCurve;
 use rnote_engine::document::background::PatternStyle;
 use rnote_engine::document::format::{self, Format, PredefinedFormat};
 use rnote_engine::document::Layout;
+use rnote_engine::engine::ContrastMode;
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::deviceprofiles::{InputDeviceAction, InputDeviceProfile, InputDeviceType};
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use tracing::{debug, error};
 
 mod imp {
     use super::*;
@@ -29,6 +35,14 @@ mod imp {
     #[template(resource = "/com/github/flxzt/rnote/ui/settingspanel.ui")]
     pub(crate) struct RnSettingsPanel {
         pub(crate) temporary_format: RefCell<Format>,
+        /// The languages available for spellchecking, in the order of the spellcheck language row (after "Disabled").
+        pub(crate) spellcheck_languages: RefCell<Vec<String>>,
+        /// The names of the connected input devices, in the order of the connected device row.
+        pub(crate) connected_device_names: RefCell<Vec<String>>,
+        /// The rows of the device profiles, rebuilt whenever the profiles change.
+        pub(crate) named_profile_rows: RefCell<Vec<adw::ExpanderRow>>,
+        /// The rows of the export hook profiles, rebuilt whenever profiles are added or removed.
+        pub(crate) export_hook_rows: RefCell<Vec<adw::EntryRow>>,
         pub(crate) app_restart_toast_singleton: RefCell<Option<adw::Toast>>,
 
         #[template_child]
@@ -38,12 +52,42 @@ mod imp {
         #[template_child]
         pub(crate) general_autosave_interval_secs_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub(crate) general_save_backups_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_save_mirror_format_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub(crate) general_show_scrollbars_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_show_minimap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_toolbar_placement_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_tablet_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_compact_toolbar_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_show_rulers_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_rulers_unit_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) general_pen_hover_preview_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_contrast_mode_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub(crate) general_optimize_epd_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_link_images_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_convert_svg_paths_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_datetime_format_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
         pub(crate) general_inertial_scrolling_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_scroll_step_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_zoom_scroll_step_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) general_regular_cursor_picker: TemplateChild<RnIconPicker>,
         #[template_child]
         pub(crate) general_regular_cursor_picker_menubutton: TemplateChild<MenuButton>,
@@ -82,6 +126,10 @@ mod imp {
         #[template_child]
         pub(crate) doc_document_layout_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) doc_spellcheck_language_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) doc_spellcheck_language_list: TemplateChild<StringList>,
+        #[template_child]
         pub(crate) doc_format_border_color_button: TemplateChild<ColorDialogButton>,
         #[template_child]
         pub(crate) doc_background_color_button: TemplateChild<ColorDialogButton>,
@@ -96,6 +144,50 @@ mod imp {
         #[template_child]
         pub(crate) background_pattern_invert_color_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) doc_image_compression_on_save_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) doc_image_compression_quality_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) doc_image_compression_max_dimension_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) doc_image_compression_compress_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) export_hooks_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) export_hooks_add_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) daily_notes_dir_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) daily_notes_dir_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) daily_notes_template_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) daily_notes_template_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) daily_notes_name_format_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) daily_notes_open_on_startup_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) quick_note_inbox_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) quick_note_inbox_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) devices_pen_action_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) devices_pen_pressure_curve_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) devices_mouse_action_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) devices_touchscreen_action_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) devices_named_profiles_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) devices_connected_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) devices_connected_list: TemplateChild<StringList>,
+        #[template_child]
+        pub(crate) devices_add_profile_button: TemplateChild<Button>,
+        #[template_child]
         pub(crate) penshortcut_stylus_button_primary_row: TemplateChild<RnPenShortcutRow>,
         #[template_child]
         pub(crate) penshortcut_stylus_button_secondary_row: TemplateChild<RnPenShortcutRow>,
@@ -113,6 +205,8 @@ mod imp {
         pub(crate) penshortcut_drawing_pad_button_2: TemplateChild<RnPenShortcutRow>,
         #[template_child]
         pub(crate) penshortcut_drawing_pad_button_3: TemplateChild<RnPenShortcutRow>,
+        #[template_child]
+        pub(crate) diagnostics_trace_dump_row: TemplateChild<adw::SwitchRow>,
     }
 
     #[glib::object_subclass]
@@ -374,14 +468,74 @@ impl RnSettingsPanel {
         self.imp().general_drawing_cursor_picker.clone()
     }
 
+    pub(crate) fn general_save_backups_row(&self) -> adw::SpinRow {
+        self.imp().general_save_backups_row.clone()
+    }
+
+    pub(crate) fn general_save_mirror_format_row(&self) -> adw::ComboRow {
+        self.imp().general_save_mirror_format_row.clone()
+    }
+
+    pub(crate) fn general_datetime_format_row(&self) -> adw::EntryRow {
+        self.imp().general_datetime_format_row.clone()
+    }
+
+    pub(crate) fn daily_notes_dir_row(&self) -> adw::EntryRow {
+        self.imp().daily_notes_dir_row.clone()
+    }
+
+    pub(crate) fn daily_notes_template_row(&self) -> adw::EntryRow {
+        self.imp().daily_notes_template_row.clone()
+    }
+
+    pub(crate) fn daily_notes_name_format_row(&self) -> adw::EntryRow {
+        self.imp().daily_notes_name_format_row.clone()
+    }
+
+    pub(crate) fn daily_notes_open_on_startup_row(&self) -> adw::SwitchRow {
+        self.imp().daily_notes_open_on_startup_row.clone()
+    }
+
+    pub(crate) fn quick_note_inbox_row(&self) -> adw::EntryRow {
+        self.imp().quick_note_inbox_row.clone()
+    }
+
     pub(crate) fn general_show_scrollbars_row(&self) -> adw::SwitchRow {
         self.imp().general_show_scrollbars_row.clone()
     }
 
+    pub(crate) fn general_show_minimap_row(&self) -> adw::SwitchRow {
+        self.imp().general_show_minimap_row.clone()
+    }
+
+    pub(crate) fn general_toolbar_placement_row(&self) -> adw::ComboRow {
+        self.imp().general_toolbar_placement_row.clone()
+    }
+
+    pub(crate) fn general_tablet_mode_row(&self) -> adw::ComboRow {
+        self.imp().general_tablet_mode_row.clone()
+    }
+
+    pub(crate) fn general_compact_toolbar_row(&self) -> adw::SwitchRow {
+        self.imp().general_compact_toolbar_row.clone()
+    }
+
     pub(crate) fn general_inertial_scrolling_row(&self) -> adw::SwitchRow {
         self.imp().general_inertial_scrolling_row.clone()
     }
 
+    pub(crate) fn general_scroll_step_row(&self) -> adw::SpinRow {
+        self.imp().general_scroll_step_row.clone()
+    }
+
+    pub(crate) fn general_zoom_scroll_step_row(&self) -> adw::SpinRow {
+        self.imp().general_zoom_scroll_step_row.clone()
+    }
+
+    pub(crate) fn diagnostics_trace_dump_row(&self) -> adw::SwitchRow {
+        self.imp().diagnostics_trace_dump_row.clone()
+    }
+
     pub(crate) fn document_layout(&self) -> Layout {
         Layout::try_from(self.imp().doc_document_layout_row.get().selected()).unwrap()
     }
@@ -392,10 +546,35 @@ impl RnSettingsPanel {
             .set_selected(layout.to_u32().unwrap());
     }
 
+    pub(crate) fn spellcheck_language(&self) -> Option<String> {
+        let selected = self.imp().doc_spellcheck_language_row.get().selected() as usize;
+        // The first entry disables spellchecking
+        selected
+            .checked_sub(1)
+            .and_then(|i| self.imp().spellcheck_languages.borrow().get(i).cloned())
+    }
+
+    pub(crate) fn set_spellcheck_language(&self, language: Option<&str>) {
+        let position = language
+            .and_then(|language| {
+                self.imp()
+                    .spellcheck_languages
+                    .borrow()
+                    .iter()
+                    .position(|l| l == language)
+            })
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        self.imp()
+            .doc_spellcheck_language_row
+            .set_selected(position as u32);
+    }
+
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
         self.refresh_general_ui(active_tab);
         self.refresh_format_ui(active_tab);
         self.refresh_doc_ui(active_tab);
+        self.refresh_devices_ui(active_tab);
         self.refresh_shortcuts_ui(active_tab);
     }
 
@@ -405,11 +584,35 @@ impl RnSettingsPanel {
 
         let format_border_color = canvas.engine_ref().document.format.border_color;
         let optimize_epd = canvas.engine_ref().optimize_epd();
+        let show_rulers = canvas.engine_ref().show_rulers();
+        let rulers_unit = canvas.engine_ref().rulers_unit();
+        let pen_hover_preview = canvas.engine_ref().pen_hover_preview();
+        let contrast_mode = canvas.engine_ref().contrast_mode();
+        let link_images = canvas
+            .engine_ref()
+            .import_prefs
+            .bitmapimage_import_prefs
+            .link_images;
+        let convert_svg_paths = canvas
+            .engine_ref()
+            .import_prefs
+            .vectorimage_import_prefs
+            .convert_paths;
 
         imp.doc_format_border_color_button
             .set_rgba(&gdk::RGBA::from_compose_color(format_border_color));
 
         imp.general_optimize_epd_row.set_active(optimize_epd);
+        imp.general_show_rulers_row.set_active(show_rulers);
+        imp.general_rulers_unit_row
+            .set_selected(rulers_unit.to_u32().unwrap());
+        imp.general_pen_hover_preview_row
+            .set_active(pen_hover_preview);
+        imp.general_contrast_mode_row
+            .set_selected(contrast_mode.to_u32().unwrap());
+        imp.general_link_images_row.set_active(link_images);
+        imp.general_convert_svg_paths_row
+            .set_active(convert_svg_paths);
     }
 
     fn refresh_format_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -433,6 +636,8 @@ impl RnSettingsPanel {
         let background = canvas.engine_ref().document.background;
         let format = canvas.engine_ref().document.format;
         let document_layout = canvas.engine_ref().document.layout;
+        let spellcheck_language = canvas.engine_ref().spellcheck_language();
+        let image_compression = canvas.engine_ref().document.image_compression;
 
         imp.doc_background_color_button
             .set_rgba(&gdk::RGBA::from_compose_color(background.color));
@@ -448,6 +653,273 @@ impl RnSettingsPanel {
         imp.doc_background_pattern_height_unitentry
             .set_value_in_px(background.pattern_size[1]);
         self.set_document_layout(&document_layout);
+        self.set_spellcheck_language(spellcheck_language.as_deref());
+        imp.doc_image_compression_on_save_row
+            .set_active(image_compression.compress_on_save);
+        imp.doc_image_compression_quality_row
+            .set_value(f64::from(image_compression.jpeg_quality));
+        imp.doc_image_compression_max_dimension_row
+            .set_value(f64::from(image_compression.max_dimension));
+    }
+
+    fn refresh_devices_ui(&self, active_tab: &RnCanvasWrapper) {
+        let imp = self.imp();
+        let canvas = active_tab.canvas();
+        let device_profiles = canvas.engine_ref().penholder.device_profiles();
+        let pen_profile = device_profiles.type_profile(InputDeviceType::Pen);
+
+        imp.devices_pen_action_row
+            .set_selected(pen_profile.action.to_u32().unwrap());
+        imp.devices_pen_pressure_curve_row
+            .set_selected(pen_profile.pressure_curve.to_u32().unwrap());
+        imp.devices_mouse_action_row.set_selected(
+            device_profiles
+                .type_profile(InputDeviceType::Mouse)
+                .action
+                .to_u32()
+                .unwrap(),
+        );
+        imp.devices_touchscreen_action_row.set_selected(
+            device_profiles
+                .type_profile(InputDeviceType::Touchscreen)
+                .action
+                .to_u32()
+                .unwrap(),
+        );
+
+        self.refresh_connected_devices();
+        self.refresh_named_profiles_ui(&canvas);
+    }
+
+    /// Lists the currently connected pointing devices in the connected device row.
+    fn refresh_connected_devices(&self) {
+        let imp = self.imp();
+        let Some(seat) = gdk::Display::default().and_then(|display| display.default_seat()) else {
+            return;
+        };
+
+        let mut names = seat
+            .devices(gdk::SeatCapabilities::ALL)
+            .into_iter()
+            .filter(|device| {
+                !matches!(
+                    device.source(),
+                    gdk::InputSource::Keyboard | gdk::InputSource::TabletPad
+                )
+            })
+            .map(|device| device.name().to_string())
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+
+        imp.devices_connected_list
+            .splice(0, imp.devices_connected_list.n_items(), &[]);
+        for name in names.iter() {
+            imp.devices_connected_list.append(name);
+        }
+        imp.devices_add_profile_button
+            .set_sensitive(!names.is_empty());
+        *imp.connected_device_names.borrow_mut() = names;
+    }
+
+    /// Rebuilds the rows for the profiles of specific devices.
+    fn refresh_named_profiles_ui(&self, canvas: &RnCanvas) {
+        let imp = self.imp();
+        for row in imp.named_profile_rows.borrow_mut().drain(..) {
+            imp.devices_named_profiles_group.remove(&row);
+        }
+
+        let device_profiles = canvas.engine_ref().penholder.device_profiles();
+        let rows = device_profiles
+            .named_profile_names()
+            .into_iter()
+            .filter_map(|name| {
+                let profile = device_profiles.named_profile(&name)?;
+                Some(self.named_profile_row(canvas, name, profile))
+            })
+            .collect::<Vec<adw::ExpanderRow>>();
+
+        for row in rows.iter() {
+            imp.devices_named_profiles_group.add(row);
+        }
+        *imp.named_profile_rows.borrow_mut() = rows;
+    }
+
+    fn named_profile_row(
+        &self,
+        canvas: &RnCanvas,
+        name: String,
+        profile: InputDeviceProfile,
+    ) -> adw::ExpanderRow {
+        let expander_row = adw::ExpanderRow::builder().title(&name).build();
+
+        let action_model = StringList::new(&[]);
+        for item in [gettext("Draw"), gettext("Pan")] {
+            action_model.append(&item);
+        }
+        let action_row = adw::ComboRow::builder()
+            .title(gettext("Action"))
+            .model(&action_model)
+            .selected(profile.action.to_u32().unwrap())
+            .build();
+        let pressure_curve_model = StringList::new(&[]);
+        for item in [
+            gettext("Constant"),
+            gettext("Linear"),
+            gettext("Square root"),
+            gettext("Cubic root"),
+            gettext("Quadratic Parabola"),
+            gettext("Cubic Parabola"),
+        ] {
+            pressure_curve_model.append(&item);
+        }
+        let pressure_curve_row = adw::ComboRow::builder()
+            .title(gettext("Pressure Curve"))
+            .model(&pressure_curve_model)
+            .selected(profile.pressure_curve.to_u32().unwrap())
+            .build();
+        let remove_button = Button::builder()
+            .valign(gtk4::Align::Center)
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Remove Profile"))
+            .css_classes(["flat"])
+            .build();
+
+        expander_row.add_suffix(&remove_button);
+        expander_row.add_row(&action_row);
+        expander_row.add_row(&pressure_curve_row);
+
+        action_row.connect_selected_item_notify(clone!(
+            #[weak]
+            canvas,
+            #[strong]
+            name,
+            move |row| {
+                let Ok(action) = InputDeviceAction::try_from(row.selected()) else {
+                    return;
+                };
+                let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                let Some(mut profile) = device_profiles.named_profile(&name) else {
+                    return;
+                };
+
+                if profile.action != action {
+                    profile.action = action;
+                    device_profiles.set_named_profile(name.clone(), profile);
+                    canvas
+                        .engine_mut()
+                        .penholder
+                        .set_device_profiles(device_profiles);
+                }
+            }
+        ));
+
+        pressure_curve_row.connect_selected_item_notify(clone!(
+            #[weak]
+            canvas,
+            #[strong]
+            name,
+            move |row| {
+                let Ok(pressure_curve) = PressureCurve::try_from(row.selected()) else {
+                    return;
+                };
+                let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                let Some(mut profile) = device_profiles.named_profile(&name) else {
+                    return;
+                };
+
+                if profile.pressure_curve != pressure_curve {
+                    profile.pressure_curve = pressure_curve;
+                    device_profiles.set_named_profile(name.clone(), profile);
+                    canvas
+                        .engine_mut()
+                        .penholder
+                        .set_device_profiles(device_profiles);
+                }
+            }
+        ));
+
+        // The button mapping of the profile, falling back to the registered shortcuts for unmapped buttons
+        let button_shortcut_keys = [
+            (
+                ShortcutKey::StylusPrimaryButton,
+                gettext("Stylus Primary Button Action"),
+            ),
+            (
+                ShortcutKey::StylusSecondaryButton,
+                gettext("Stylus Secondary Button Action"),
+            ),
+            (
+                ShortcutKey::MouseSecondaryButton,
+                gettext("Mouse Secondary Button Action"),
+            ),
+        ];
+        for (shortcut_key, title) in button_shortcut_keys {
+            let shortcut_row = RnPenShortcutRow::new();
+            shortcut_row.set_title(&title);
+            if let Some(action) = profile
+                .button_mapping
+                .get(&shortcut_key)
+                .copied()
+                .or_else(|| {
+                    canvas
+                        .engine_ref()
+                        .penholder
+                        .get_shortcut_action(shortcut_key)
+                })
+            {
+                shortcut_row.set_action(action);
+            }
+            expander_row.add_row(&shortcut_row);
+
+            shortcut_row.connect_local(
+                "action-changed",
+                false,
+                clone!(
+                    #[weak]
+                    canvas,
+                    #[weak]
+                    shortcut_row,
+                    #[strong]
+                    name,
+                    #[upgrade_or]
+                    None,
+                    move |_values| {
+                        let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                        let Some(mut profile) = device_profiles.named_profile(&name) else {
+                            return None;
+                        };
+                        profile
+                            .button_mapping
+                            .insert(shortcut_key, shortcut_row.action());
+                        device_profiles.set_named_profile(name.clone(), profile);
+                        canvas
+                            .engine_mut()
+                            .penholder
+                            .set_device_profiles(device_profiles);
+                        None
+                    }
+                ),
+            );
+        }
+
+        remove_button.connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            canvas,
+            move |_| {
+                let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                device_profiles.remove_named_profile(&name);
+                canvas
+                    .engine_mut()
+                    .penholder
+                    .set_device_profiles(device_profiles);
+                settingspanel.refresh_named_profiles_ui(&canvas);
+            }
+        ));
+
+        expander_row
     }
 
     fn refresh_shortcuts_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -495,7 +967,12 @@ impl RnSettingsPanel {
         self.setup_general(appwindow);
         self.setup_format(appwindow);
         self.setup_doc(appwindow);
+        self.setup_daily_notes(appwindow);
+        self.setup_quick_notes(appwindow);
+        self.setup_export_hooks(appwindow);
+        self.setup_devices(appwindow);
         self.setup_shortcuts(appwindow);
+        self.setup_diagnostics(appwindow);
     }
 
     fn setup_general(&self, appwindow: &RnAppWindow) {
@@ -548,6 +1025,62 @@ impl RnSettingsPanel {
                 }
             ));
 
+        imp.general_show_rulers_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().set_show_rulers(row.is_active());
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.general_show_rulers_row
+            .bind_property("active", &*imp.general_rulers_unit_row, "sensitive")
+            .sync_create()
+            .build();
+
+        imp.general_rulers_unit_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(rulers_unit) = format::MeasureUnit::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas.engine_mut().set_rulers_unit(rulers_unit);
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.general_pen_hover_preview_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas.engine_mut().set_pen_hover_preview(row.is_active());
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.general_contrast_mode_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(contrast_mode) = ContrastMode::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas.engine_mut().set_contrast_mode(contrast_mode);
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
         imp.general_optimize_epd_row
             .bind_property(
                 "active",
@@ -569,6 +1102,35 @@ impl RnSettingsPanel {
             }
         ));
 
+        imp.general_link_images_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                appwindow
+                    .active_tab_wrapper()
+                    .canvas()
+                    .engine_mut()
+                    .import_prefs
+                    .bitmapimage_import_prefs
+                    .link_images = row.is_active();
+            }
+        ));
+
+        imp.general_convert_svg_paths_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .active_tab_wrapper()
+                        .canvas()
+                        .engine_mut()
+                        .import_prefs
+                        .vectorimage_import_prefs
+                        .convert_paths = row.is_active();
+                }
+            ));
+
         // Regular cursor picker
         imp.general_regular_cursor_picker.set_list(
             StringList::new(CURSORS_LIST),
@@ -658,6 +1220,33 @@ impl RnSettingsPanel {
     fn setup_doc(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
+        // The installed dictionaries don't change while the app is running, so the list is only populated once
+        let spellcheck_languages = rnote_engine::Spellcheck::default().available_languages();
+        for language in spellcheck_languages.iter() {
+            imp.doc_spellcheck_language_list.append(language);
+        }
+        *imp.spellcheck_languages.borrow_mut() = spellcheck_languages;
+
+        imp.doc_spellcheck_language_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let spellcheck_language = settings_panel.spellcheck_language();
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+
+                    if canvas.engine_ref().spellcheck_language() != spellcheck_language {
+                        let widget_flags = canvas
+                            .engine_mut()
+                            .set_spellcheck_language(spellcheck_language);
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                }
+            ));
+
         imp.doc_format_border_color_button
             .connect_rgba_notify(clone!(
                 #[weak(rename_to=settingspanel)]
@@ -933,6 +1522,407 @@ impl RnSettingsPanel {
                     appwindow.handle_widget_flags(widget_flags, &canvas);
                 }
             ));
+
+        imp.doc_image_compression_on_save_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let compress_on_save = row.is_active();
+                    if canvas
+                        .engine_ref()
+                        .document
+                        .image_compression
+                        .compress_on_save
+                        != compress_on_save
+                    {
+                        canvas
+                            .engine_mut()
+                            .document
+                            .image_compression
+                            .compress_on_save = compress_on_save;
+                        canvas.set_unsaved_changes(true);
+                    }
+                }
+            ));
+
+        imp.doc_image_compression_quality_row
+            .connect_value_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let jpeg_quality = row.value().round() as u8;
+                    if canvas.engine_ref().document.image_compression.jpeg_quality != jpeg_quality {
+                        canvas.engine_mut().document.image_compression.jpeg_quality = jpeg_quality;
+                        canvas.set_unsaved_changes(true);
+                    }
+                }
+            ));
+
+        imp.doc_image_compression_max_dimension_row
+            .connect_value_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let max_dimension = row.value().round() as u32;
+                    if canvas.engine_ref().document.image_compression.max_dimension != max_dimension
+                    {
+                        canvas.engine_mut().document.image_compression.max_dimension =
+                            max_dimension;
+                        canvas.set_unsaved_changes(true);
+                    }
+                }
+            ));
+
+        imp.doc_image_compression_compress_button
+            .get()
+            .connect_clicked(clone!(
+                #[weak]
+                appwindow,
+                move |_| {
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let widget_flags = canvas.engine_mut().compress_images();
+                    if !widget_flags.store_modified {
+                        appwindow.overlays().dispatch_toast_text(
+                            &gettext("No images to compress"),
+                            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                        );
+                    }
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+    }
+
+    fn setup_daily_notes(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.daily_notes_dir_button.get().connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    settingspanel,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let filedialog = FileDialog::builder()
+                            .title(gettext("Daily Notes Folder"))
+                            .modal(true)
+                            .accept_label(gettext("Select"))
+                            .build();
+                        match filedialog.select_folder_future(Some(&appwindow)).await {
+                            Ok(dir) => {
+                                if let Some(dir_path) = dir.path() {
+                                    settingspanel
+                                        .daily_notes_dir_row()
+                                        .set_text(&dir_path.to_string_lossy());
+                                }
+                            }
+                            Err(e) => {
+                                debug!("Did not select a daily notes folder (Error or dialog dismissed by user), Err: {e:?}");
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
+
+        imp.daily_notes_template_button
+            .get()
+            .connect_clicked(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        settingspanel,
+                        #[weak]
+                        appwindow,
+                        async move {
+                            let templates_dir = crate::env::user_templates_dir();
+                            if let Err(e) = std::fs::create_dir_all(&templates_dir) {
+                                error!("Creating the templates directory failed, Err: {e:?}");
+                            }
+                            if let Some(file_path) = filedialog_select_rnote_file(
+                                &appwindow,
+                                &gettext("Daily Notes Template"),
+                                Some(&templates_dir),
+                            )
+                            .await
+                            {
+                                settingspanel
+                                    .daily_notes_template_row()
+                                    .set_text(&file_path.to_string_lossy());
+                            }
+                        }
+                    ));
+                }
+            ));
+    }
+
+    fn setup_quick_notes(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.quick_note_inbox_button.get().connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    settingspanel,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        if let Some(file_path) = filedialog_select_rnote_file(
+                            &appwindow,
+                            &gettext("Quick Notes Inbox"),
+                            None,
+                        )
+                        .await
+                        {
+                            settingspanel
+                                .quick_note_inbox_row()
+                                .set_text(&file_path.to_string_lossy());
+                        }
+                    }
+                ));
+            }
+        ));
+    }
+
+    fn setup_export_hooks(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.export_hooks_add_row.get().connect_apply(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let name = row.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                let mut export_hooks = appwindow.export_hooks();
+                if export_hooks.contains_key(&name) {
+                    return;
+                }
+                export_hooks.insert(name, String::new());
+                appwindow.set_export_hooks(&export_hooks);
+                row.set_text("");
+            }
+        ));
+
+        // The profiles are shared between all windows
+        if let Some(app_settings) = appwindow.app().app_settings() {
+            app_settings.connect_changed(
+                Some("export-hooks"),
+                clone!(
+                    #[weak(rename_to=settingspanel)]
+                    self,
+                    #[weak]
+                    appwindow,
+                    move |_, _| {
+                        settingspanel.refresh_export_hooks_ui(&appwindow);
+                    }
+                ),
+            );
+        }
+        self.refresh_export_hooks_ui(appwindow);
+    }
+
+    /// Rebuilds the rows for the export hook profiles when profiles were added or removed, else updates their commands.
+    fn refresh_export_hooks_ui(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let export_hooks = appwindow.export_hooks();
+
+        let names_unchanged = imp
+            .export_hook_rows
+            .borrow()
+            .iter()
+            .map(|row| row.title().to_string())
+            .eq(export_hooks.keys().cloned());
+        if names_unchanged {
+            for (row, template) in imp
+                .export_hook_rows
+                .borrow()
+                .iter()
+                .zip(export_hooks.values())
+            {
+                if row.text().as_str() != template.as_str() {
+                    row.set_text(template);
+                }
+            }
+            return;
+        }
+
+        for row in imp.export_hook_rows.borrow_mut().drain(..) {
+            imp.export_hooks_group.remove(&row);
+        }
+        let rows = export_hooks
+            .into_iter()
+            .map(|(name, template)| self.export_hook_row(appwindow, name, template))
+            .collect::<Vec<adw::EntryRow>>();
+        for row in rows.iter() {
+            imp.export_hooks_group.add(row);
+        }
+        *imp.export_hook_rows.borrow_mut() = rows;
+    }
+
+    fn export_hook_row(
+        &self,
+        appwindow: &RnAppWindow,
+        name: String,
+        template: String,
+    ) -> adw::EntryRow {
+        let row = adw::EntryRow::builder()
+            .title(&name)
+            .text(&template)
+            .show_apply_button(true)
+            .build();
+        let remove_button = Button::builder()
+            .valign(gtk4::Align::Center)
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Remove Profile"))
+            .css_classes(["flat"])
+            .build();
+        row.add_suffix(&remove_button);
+
+        row.connect_apply(clone!(
+            #[weak]
+            appwindow,
+            #[strong]
+            name,
+            move |row| {
+                let mut export_hooks = appwindow.export_hooks();
+                export_hooks.insert(name.clone(), row.text().to_string());
+                appwindow.set_export_hooks(&export_hooks);
+            }
+        ));
+        remove_button.connect_clicked(clone!(
+            #[weak]
+            appwindow,
+            move |_| {
+                let mut export_hooks = appwindow.export_hooks();
+                export_hooks.remove(&name);
+                appwindow.set_export_hooks(&export_hooks);
+            }
+        ));
+
+        row
+    }
+
+    fn setup_devices(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        let device_action_rows = [
+            (InputDeviceType::Pen, imp.devices_pen_action_row.get()),
+            (InputDeviceType::Mouse, imp.devices_mouse_action_row.get()),
+            (
+                InputDeviceType::Touchscreen,
+                imp.devices_touchscreen_action_row.get(),
+            ),
+        ];
+
+        for (device_type, row) in device_action_rows {
+            row.connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(action) = InputDeviceAction::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                    let mut profile = device_profiles.type_profile(device_type);
+
+                    if profile.action != action {
+                        profile.action = action;
+                        device_profiles.set_type_profile(device_type, profile);
+                        canvas
+                            .engine_mut()
+                            .penholder
+                            .set_device_profiles(device_profiles);
+                    }
+                }
+            ));
+        }
+
+        imp.devices_pen_pressure_curve_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Ok(pressure_curve) = PressureCurve::try_from(row.selected()) else {
+                        return;
+                    };
+                    let canvas = appwindow.active_tab_wrapper().canvas();
+                    let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                    let mut profile = device_profiles.type_profile(InputDeviceType::Pen);
+
+                    if profile.pressure_curve != pressure_curve {
+                        profile.pressure_curve = pressure_curve;
+                        device_profiles.set_type_profile(InputDeviceType::Pen, profile);
+                        canvas
+                            .engine_mut()
+                            .penholder
+                            .set_device_profiles(device_profiles);
+                    }
+                }
+            ));
+
+        // A new device profile starts out from the settings of the device type
+        imp.devices_add_profile_button.get().connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let imp = settingspanel.imp();
+                let Some(name) = imp
+                    .connected_device_names
+                    .borrow()
+                    .get(imp.devices_connected_row.selected() as usize)
+                    .cloned()
+                else {
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let mut device_profiles = canvas.engine_ref().penholder.device_profiles();
+                if device_profiles.named_profile(&name).is_some() {
+                    return;
+                }
+                let device_type = gdk::Display::default()
+                    .and_then(|display| display.default_seat())
+                    .and_then(|seat| {
+                        seat.devices(gdk::SeatCapabilities::ALL)
+                            .into_iter()
+                            .find(|device| device.name() == name)
+                    })
+                    .map(|device| match device.source() {
+                        gdk::InputSource::Pen | gdk::InputSource::TabletPad => InputDeviceType::Pen,
+                        gdk::InputSource::Touchscreen => InputDeviceType::Touchscreen,
+                        _ => InputDeviceType::Mouse,
+                    })
+                    .unwrap_or(InputDeviceType::Mouse);
+
+                device_profiles.set_named_profile(name, device_profiles.type_profile(device_type));
+                canvas
+                    .engine_mut()
+                    .penholder
+                    .set_device_profiles(device_profiles);
+                settingspanel.refresh_named_profiles_ui(&canvas);
+            }
+        ));
     }
 
     fn setup_shortcuts(&self, appwindow: &RnAppWindow) {
@@ -1159,6 +2149,41 @@ impl RnSettingsPanel {
         );
     }
 
+    fn setup_diagnostics(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.diagnostics_trace_dump_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                if row.is_active() {
+                    match crate::tracedump::start() {
+                        Ok(path) => {
+                            appwindow.overlays().dispatch_toast_text(
+                                &gettext("Recording performance trace to “{}”")
+                                    .replace("{}", &path.display().to_string()),
+                                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                            );
+                        }
+                        Err(e) => {
+                            error!("Starting the performance trace dump failed, Err: {e:?}");
+                            appwindow.overlays().dispatch_toast_error(&gettext(
+                                "Recording performance trace failed",
+                            ));
+                            row.set_active(false);
+                        }
+                    }
+                } else if let Some(path) = crate::tracedump::stop() {
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Performance trace saved to “{}”")
+                            .replace("{}", &path.display().to_string()),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                }
+            }
+        ));
+    }
+
     fn revert_format(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
         let canvas = appwindow.active_tab_wrapper().canvas();
@@ -1236,3 +2261,39 @@ fn cursors_list_to_display_name(icon_name: &str) -> String {
         _ => unimplemented!(),
     }
 }
+
+/// Lets the user select a .rnote file and returns its path, None if the dialog was dismissed.
+async fn filedialog_select_rnote_file(
+    appwindow: &RnAppWindow,
+    title: &str,
+    initial_folder: Option<&Path>,
+) -> Option<PathBuf> {
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.rnote");
+    } else {
+        filter.add_mime_type("application/rnote");
+    }
+    filter.add_suffix("rnote");
+    filter.set_name(Some(&gettext(".rnote")));
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(title)
+        .modal(true)
+        .accept_label(gettext("Select"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+    if let Some(initial_folder) = initial_folder {
+        filedialog.set_initial_folder(Some(&gio::File::for_path(initial_folder)));
+    }
+    match filedialog.open_future(Some(appwindow)).await {
+        Ok(file) => file.path(),
+        Err(e) => {
+            debug!("Did not select a file (Error or dialog dismissed by user), Err: {e:?}");
+            None
+        }
+    }
+}
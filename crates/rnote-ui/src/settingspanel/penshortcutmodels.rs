@@ -9,6 +9,15 @@ use rnote_engine::pens::PenStyle;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
+/// The item of the list model for opening the radial menu instead of changing the pen style.
+const RADIAL_MENU_ITEM: &str = "radial-menu";
+/// The position of the radial menu item, after all pen styles.
+pub(crate) const RADIAL_MENU_ITEM_POS: u32 = 6;
+/// The item of the list model for opening the context menu instead of changing the pen style.
+const CONTEXT_MENU_ITEM: &str = "context-menu";
+/// The position of the context menu item, after the radial menu item.
+pub(crate) const CONTEXT_MENU_ITEM_POS: u32 = 7;
+
 #[derive(Debug, Clone)]
 pub(crate) struct ChangePenStyleListModel(StringList);
 
@@ -21,6 +30,8 @@ impl Default for ChangePenStyleListModel {
             &PenStyle::Eraser.to_string(),
             &PenStyle::Selector.to_string(),
             &PenStyle::Tools.to_string(),
+            RADIAL_MENU_ITEM,
+            CONTEXT_MENU_ITEM,
         ]))
     }
 }
@@ -63,28 +74,19 @@ impl Default for ChangePenStyleListFactory {
         });
         factory.connect_bind(move |_factory, list_item| {
             let list_item = list_item.downcast_ref::<ListItem>().unwrap();
-            let pen_style = PenStyle::from_str(
+            let (label, icon_name) = item_label_icon_name(
                 &list_item
                     .item()
                     .unwrap()
                     .downcast::<StringObject>()
                     .unwrap()
                     .string(),
-            )
-            .unwrap();
+            );
             let item_box = list_item.child().unwrap().downcast::<gtk4::Box>().unwrap();
 
             let mut child = item_box.first_child();
             while let Some(ref next_child) = child {
                 if next_child.type_() == Label::static_type() {
-                    let label = match pen_style {
-                        PenStyle::Brush => gettext("Brush"),
-                        PenStyle::Shaper => gettext("Shaper"),
-                        PenStyle::Typewriter => gettext("Typewriter"),
-                        PenStyle::Eraser => gettext("Eraser"),
-                        PenStyle::Selector => gettext("Selector"),
-                        PenStyle::Tools => gettext("Tools"),
-                    };
                     next_child
                         .downcast_ref::<Label>()
                         .unwrap()
@@ -93,7 +95,7 @@ impl Default for ChangePenStyleListFactory {
                     next_child
                         .downcast_ref::<Image>()
                         .unwrap()
-                        .set_icon_name(Some(pen_style.icon_name().as_str()));
+                        .set_icon_name(Some(icon_name.as_str()));
                 }
 
                 child = next_child.next_sibling();
@@ -131,20 +133,19 @@ impl Default for ChangePenStyleIconFactory {
         });
         factory.connect_bind(move |_factory, list_item| {
             let list_item = list_item.downcast_ref::<ListItem>().unwrap();
-            let pen_style = PenStyle::from_str(
+            let (_, icon_name) = item_label_icon_name(
                 &list_item
                     .item()
                     .unwrap()
                     .downcast::<StringObject>()
                     .unwrap()
                     .string(),
-            )
-            .unwrap();
+            );
             let image = list_item.child().unwrap().downcast::<Image>().unwrap();
             image
                 .downcast_ref::<Image>()
                 .unwrap()
-                .set_icon_name(Some(pen_style.icon_name().as_str()));
+                .set_icon_name(Some(icon_name.as_str()));
         });
         Self(factory)
     }
@@ -163,3 +164,23 @@ impl DerefMut for ChangePenStyleIconFactory {
         &mut self.0
     }
 }
+
+/// The label and icon name for an item of the list model.
+fn item_label_icon_name(item: &str) -> (String, String) {
+    if item == RADIAL_MENU_ITEM {
+        return (gettext("Radial Menu"), String::from("radial-menu-symbolic"));
+    }
+    if item == CONTEXT_MENU_ITEM {
+        return (gettext("Context Menu"), String::from("misc-menu-symbolic"));
+    }
+    let pen_style = PenStyle::from_str(item).unwrap();
+    let label = match pen_style {
+        PenStyle::Brush => gettext("Brush"),
+        PenStyle::Shaper => gettext("Shaper"),
+        PenStyle::Typewriter => gettext("Typewriter"),
+        PenStyle::Eraser => gettext("Eraser"),
+        PenStyle::Selector => gettext("Selector"),
+        PenStyle::Tools => gettext("Tools"),
+    };
+    (label, pen_style.icon_name())
+}
@@ -1,6 +1,7 @@
 // Imports
 use super::penshortcutmodels::{
     ChangePenStyleIconFactory, ChangePenStyleListFactory, ChangePenStyleListModel,
+    CONTEXT_MENU_ITEM_POS, RADIAL_MENU_ITEM_POS,
 };
 use adw::{prelude::*, subclass::prelude::*};
 use gtk4::{glib, glib::clone, glib::subclass::*, CompositeTemplate, DropDown};
@@ -67,13 +68,18 @@ mod imp {
             obj.set_factory(Some(&*icon_factory));
 
             obj.connect_selected_item_notify(move |row| {
-                let new_pen_style = row.pen_style();
-
-                match &mut *row.imp().action.borrow_mut() {
-                    ShortcutAction::ChangePenStyle { style, .. } => {
-                        *style = new_pen_style;
+                let new_action = match row.pen_style() {
+                    Some(style) => ShortcutAction::ChangePenStyle {
+                        style,
+                        mode: row.shortcut_mode(),
+                    },
+                    None if row.selected() == CONTEXT_MENU_ITEM_POS => {
+                        ShortcutAction::OpenContextMenu
                     }
-                }
+                    None => ShortcutAction::OpenRadialMenu,
+                };
+
+                *row.imp().action.borrow_mut() = new_action;
                 row.emit_by_name::<()>("action-changed", &[]);
             });
 
@@ -85,6 +91,7 @@ mod imp {
                         ShortcutAction::ChangePenStyle { mode, .. } => {
                             *mode = penshortcutrow.shortcut_mode();
                         }
+                        ShortcutAction::OpenRadialMenu | ShortcutAction::OpenContextMenu => {}
                     }
                     penshortcutrow.emit_by_name::<()>("action-changed", &[]);
                 }
@@ -134,7 +141,6 @@ glib::wrapper! {
 
 impl RnPenShortcutRow {
     #[allow(clippy::new_without_default)]
-    #[allow(unused)]
     pub(crate) fn new() -> Self {
         glib::Object::new()
     }
@@ -150,8 +156,9 @@ impl RnPenShortcutRow {
         self.emit_by_name::<()>("action-changed", &[]);
     }
 
-    pub(crate) fn pen_style(&self) -> PenStyle {
-        PenStyle::try_from(self.selected()).unwrap()
+    /// The selected pen style. Is `None` when opening the radial or context menu is selected.
+    pub(crate) fn pen_style(&self) -> Option<PenStyle> {
+        PenStyle::try_from(self.selected()).ok()
     }
 
     pub(crate) fn set_pen_style(&self, style: PenStyle) {
@@ -173,6 +180,15 @@ impl RnPenShortcutRow {
             ShortcutAction::ChangePenStyle { style, mode } => {
                 self.set_pen_style(style);
                 self.set_shortcut_mode(mode);
+                self.imp().mode_dropdown.set_sensitive(true);
+            }
+            ShortcutAction::OpenRadialMenu => {
+                self.set_selected(RADIAL_MENU_ITEM_POS);
+                self.imp().mode_dropdown.set_sensitive(false);
+            }
+            ShortcutAction::OpenContextMenu => {
+                self.set_selected(CONTEXT_MENU_ITEM_POS);
+                self.imp().mode_dropdown.set_sensitive(false);
             }
         }
     }
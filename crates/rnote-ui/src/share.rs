@@ -0,0 +1,280 @@
+//! Sharing the current view or the selection with remote services.
+//!
+//! Every service is a [ShareTarget]. New services are added by implementing the trait and registering the
+//! implementation in [share_targets()], the share menu and dialog pick them up from there.
+
+// Imports
+use crate::RnCanvas;
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use gettextrs::gettext;
+use gtk4::{gio, glib, prelude::*};
+use rnote_engine::engine::export::{SelectionExportFormat, SelectionExportPrefs};
+use tracing::debug;
+
+/// A file that is shared.
+#[derive(Debug, Clone)]
+pub(crate) struct ShareFile {
+    pub(crate) name: String,
+    pub(crate) mime_type: &'static str,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// A service that files can be shared with.
+pub(crate) trait ShareTarget {
+    /// The unique id of the target, used as parameter of the `share` action and to store its destination.
+    fn id(&self) -> &'static str;
+    /// The name of the target, displayed in the share menu.
+    fn name(&self) -> String;
+    /// The title of the destination entry in the share dialog.
+    fn destination_title(&self) -> String;
+    /// The destination that is used until the user entered one.
+    fn default_destination(&self) -> String;
+    /// Shares the file with the destination.
+    ///
+    /// Returns the url where the shared file can be accessed, if it is known.
+    fn share(
+        &self,
+        destination: String,
+        file: ShareFile,
+    ) -> LocalBoxFuture<'static, anyhow::Result<Option<String>>>;
+}
+
+/// All available share targets.
+pub(crate) fn share_targets() -> Vec<Box<dyn ShareTarget>> {
+    vec![
+        Box::new(LocationShareTarget),
+        Box::new(PasteServiceShareTarget),
+    ]
+}
+
+pub(crate) fn share_target(id: &str) -> Option<Box<dyn ShareTarget>> {
+    share_targets().into_iter().find(|target| target.id() == id)
+}
+
+/// Generates the file that is shared from the selection or the current view of the canvas.
+///
+/// Returns `Ok(None)` when the selection should be shared, but nothing is selected.
+pub(crate) async fn gen_share_file(
+    canvas: &RnCanvas,
+    selection: bool,
+    svg: bool,
+) -> anyhow::Result<Option<ShareFile>> {
+    let (export_format, image_format, extension, mime_type) = if svg {
+        (SelectionExportFormat::Svg, None, "svg", "image/svg+xml")
+    } else {
+        (
+            SelectionExportFormat::Png,
+            Some(image::ImageFormat::Png),
+            "png",
+            "image/png",
+        )
+    };
+    let bytes = if selection {
+        let prefs_override = SelectionExportPrefs {
+            export_format,
+            ..canvas.engine_ref().export_prefs.selection_export_prefs
+        };
+        let selection_bytes = canvas.engine_ref().export_selection(Some(prefs_override));
+        let Some(bytes) = selection_bytes.await?? else {
+            return Ok(None);
+        };
+        bytes
+    } else if let Some(image_format) = image_format {
+        let viewport_bytes = canvas
+            .engine_ref()
+            .export_viewport_as_bitmap_bytes(image_format);
+        viewport_bytes.await??
+    } else {
+        let viewport_bytes = canvas.engine_ref().export_viewport_as_svg_bytes();
+        viewport_bytes.await??
+    };
+    let name = if selection {
+        gettext("{} - Selection").replace("{}", &canvas.doc_title_display())
+    } else {
+        gettext("{} - View").replace("{}", &canvas.doc_title_display())
+    };
+    Ok(Some(ShareFile {
+        name: format!("{name}.{extension}"),
+        mime_type,
+        bytes,
+    }))
+}
+
+/// Writes the file into a folder of any location that is supported by gio, for example a WebDAV share, a Sftp
+/// server or a Samba share.
+#[derive(Debug, Clone, Copy)]
+struct LocationShareTarget;
+
+impl ShareTarget for LocationShareTarget {
+    fn id(&self) -> &'static str {
+        "location"
+    }
+
+    fn name(&self) -> String {
+        gettext("Network Location…")
+    }
+
+    fn destination_title(&self) -> String {
+        gettext("Folder Address (davs://, sftp://, smb://, ..)")
+    }
+
+    fn default_destination(&self) -> String {
+        String::new()
+    }
+
+    fn share(
+        &self,
+        destination: String,
+        file: ShareFile,
+    ) -> LocalBoxFuture<'static, anyhow::Result<Option<String>>> {
+        async move {
+            let destination = destination.trim().trim_end_matches('/').to_string();
+            if destination.is_empty() {
+                return Err(anyhow::anyhow!("No destination folder entered."));
+            }
+            // Gio accesses WebDAV shares through the dav schemes
+            let uri = if let Some(rest) = destination.strip_prefix("https://") {
+                format!("davs://{rest}")
+            } else if let Some(rest) = destination.strip_prefix("http://") {
+                format!("dav://{rest}")
+            } else {
+                destination.clone()
+            };
+            let folder = gio::File::for_uri(&uri);
+            let target_file = folder.child(&file.name);
+            crate::utils::create_replace_file_future(file.bytes, &target_file).await?;
+
+            if destination.starts_with("https://") || destination.starts_with("http://") {
+                Ok(Some(format!(
+                    "{destination}/{}",
+                    glib::Uri::escape_string(&file.name, None, false)
+                )))
+            } else {
+                Ok(Some(target_file.uri().to_string()))
+            }
+        }
+        .boxed_local()
+    }
+}
+
+/// Uploads the file to a paste service that accepts multipart form uploads in a `file` field and responds with
+/// the url of the upload, like 0x0.st.
+#[derive(Debug, Clone, Copy)]
+struct PasteServiceShareTarget;
+
+impl ShareTarget for PasteServiceShareTarget {
+    fn id(&self) -> &'static str {
+        "paste-service"
+    }
+
+    fn name(&self) -> String {
+        gettext("Paste Service…")
+    }
+
+    fn destination_title(&self) -> String {
+        gettext("Upload Url")
+    }
+
+    fn default_destination(&self) -> String {
+        String::from("https://0x0.st")
+    }
+
+    fn share(
+        &self,
+        destination: String,
+        file: ShareFile,
+    ) -> LocalBoxFuture<'static, anyhow::Result<Option<String>>> {
+        async move {
+            let response = http_post_multipart(destination.trim(), &file).await?;
+            let url = response.trim();
+            if url.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(url.to_string()))
+        }
+        .boxed_local()
+    }
+}
+
+/// Posts the file as multipart form upload and returns the response body.
+///
+/// A minimal Http/1.0 client, so that responses are never chunked.
+async fn http_post_multipart(url: &str, file: &ShareFile) -> anyhow::Result<String> {
+    let url = url::Url::parse(url)?;
+    let tls = match url.scheme() {
+        "https" => true,
+        "http" => false,
+        scheme => return Err(anyhow::anyhow!("Unsupported url scheme `{scheme}`.")),
+    };
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("Url has no host."))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| anyhow::anyhow!("Url has no port."))?;
+
+    let boundary = format!("rnote-{:016x}", rand::random::<u64>());
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        file.name.replace('"', ""),
+        file.mime_type
+    )
+    .into_bytes();
+    body.extend_from_slice(&file.bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let mut request = format!(
+        "POST {path} HTTP/1.0\r\nHost: {host}\r\nUser-Agent: Rnote/{}\r\nAccept: */*\r\nContent-Type: multipart/form-data; boundary={boundary}\r\nContent-Length: {}\r\n\r\n",
+        crate::config::APP_VERSION,
+        body.len()
+    )
+    .into_bytes();
+    request.extend(body);
+
+    let client = gio::SocketClient::new();
+    client.set_tls(tls);
+    let connection = client.connect_to_host_future(host, port).await?;
+    connection
+        .output_stream()
+        .write_all_future(request, glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, e)| e)?;
+
+    let input_stream = connection.input_stream();
+    let mut response = Vec::new();
+    loop {
+        let bytes = input_stream
+            .read_bytes_future(8192, glib::Priority::DEFAULT)
+            .await?;
+        if bytes.is_empty() {
+            break;
+        }
+        response.extend_from_slice(&bytes);
+    }
+    if let Err(e) = connection.close_future(glib::Priority::DEFAULT).await {
+        debug!("Closing connection to paste service failed, Err: {e:?}");
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow::anyhow!("Received malformed Http response."))?;
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| anyhow::anyhow!("Received Http response without status code."))?;
+    if !(200..300).contains(&status) {
+        return Err(anyhow::anyhow!(
+            "Upload failed with `{status_line}`, response: `{}`",
+            body.trim()
+        ));
+    }
+    Ok(body.to_string())
+}
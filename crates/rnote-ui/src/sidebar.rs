@@ -1,8 +1,16 @@
 // Imports
-use crate::{RnAppMenu, RnAppWindow, RnSettingsPanel, RnWorkspaceBrowser};
+use crate::{RnAppMenu, RnAppWindow, RnCanvas, RnSettingsPanel, RnWorkspaceBrowser};
+use adw::prelude::*;
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, Widget,
+    glib, glib::clone, subclass::prelude::*, Button, CompositeTemplate, Image, ListBox, Widget,
 };
+use rnote_engine::document::links::LinkTarget;
+use rnote_engine::document::{OutlineEntry, OutlineEntryKind};
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// The delay after the last change before the outline is refreshed.
+const OUTLINE_REFRESH_DELAY: Duration = Duration::from_millis(750);
 
 mod imp {
     use super::*;
@@ -10,6 +18,9 @@ mod imp {
     #[derive(Debug, CompositeTemplate, Default)]
     #[template(resource = "/com/github/flxzt/rnote/ui/sidebar.ui")]
     pub(crate) struct RnSidebar {
+        pub(crate) outline: RefCell<Vec<OutlineEntry>>,
+        pub(crate) outline_refresh_source_id: RefCell<Option<glib::SourceId>>,
+
         #[template_child]
         pub(crate) headerbar: TemplateChild<adw::HeaderBar>,
         #[template_child]
@@ -24,6 +35,8 @@ mod imp {
         pub(crate) workspacebrowser: TemplateChild<RnWorkspaceBrowser>,
         #[template_child]
         pub(crate) settings_panel: TemplateChild<RnSettingsPanel>,
+        #[template_child]
+        pub(crate) outline_listbox: TemplateChild<ListBox>,
     }
 
     #[glib::object_subclass]
@@ -109,6 +122,42 @@ impl RnSidebar {
         imp.workspacebrowser.get().init(appwindow);
         imp.settings_panel.get().init(appwindow);
 
+        imp.outline_listbox.connect_row_activated(clone!(
+            #[weak(rename_to=sidebar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_, row| {
+                let Some(pos) = usize::try_from(row.index())
+                    .ok()
+                    .and_then(|i| sidebar.imp().outline.borrow().get(i).map(|entry| entry.pos))
+                else {
+                    return;
+                };
+                let canvas = appwindow.active_tab_wrapper().canvas();
+                let widget_flags = canvas.engine_mut().follow_link(LinkTarget::Position(pos));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // The outline is only refreshed while it is shown
+        imp.sidebar_stack.connect_visible_child_name_notify(clone!(
+            #[weak(rename_to=sidebar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                sidebar.refresh_outline(&appwindow.active_tab_wrapper().canvas());
+            }
+        ));
+        self.connect_map(clone!(
+            #[weak]
+            appwindow,
+            move |sidebar| {
+                sidebar.refresh_outline(&appwindow.active_tab_wrapper().canvas());
+            }
+        ));
+
         imp.left_close_button.connect_clicked(clone!(
             #[weak]
             appwindow,
@@ -124,4 +173,67 @@ impl RnSidebar {
             }
         ));
     }
+
+    /// Whether the outline page is currently shown.
+    fn outline_shown(&self) -> bool {
+        self.is_mapped()
+            && self.imp().sidebar_stack.visible_child_name().as_deref() == Some("outline_page")
+    }
+
+    /// Refresh the outline page after a short delay, so that it is not regenerated on every change.
+    pub(crate) fn refresh_outline_delayed(&self, canvas: &RnCanvas) {
+        if let Some(id) = self.imp().outline_refresh_source_id.take() {
+            id.remove();
+        }
+        if !self.outline_shown() {
+            return;
+        }
+        let id = glib::timeout_add_local_once(
+            OUTLINE_REFRESH_DELAY,
+            clone!(
+                #[weak(rename_to=sidebar)]
+                self,
+                #[weak]
+                canvas,
+                move || {
+                    sidebar.imp().outline_refresh_source_id.take();
+                    sidebar.refresh_outline(&canvas);
+                }
+            ),
+        );
+        self.imp().outline_refresh_source_id.replace(Some(id));
+    }
+
+    /// Refresh the outline page with the outline of the document of the canvas.
+    ///
+    /// Skipped while the outline page is not shown, it is refreshed when it gets shown.
+    pub(crate) fn refresh_outline(&self, canvas: &RnCanvas) {
+        if let Some(id) = self.imp().outline_refresh_source_id.take() {
+            id.remove();
+        }
+        if !self.outline_shown() {
+            return;
+        }
+        // Headings might be in strokes of a lazily loaded document that are not yet loaded
+        canvas.load_pending_strokes();
+        let outline = canvas.engine_ref().outline();
+        if *self.imp().outline.borrow() == outline {
+            return;
+        }
+        let listbox = self.imp().outline_listbox.get();
+        listbox.remove_all();
+        for entry in outline.iter() {
+            let icon_name = match entry.kind {
+                OutlineEntryKind::Heading => "workspacelistentryicon-text-symbolic",
+                OutlineEntryKind::Bookmark => "workspacelistentryicon-bookmark-symbolic",
+            };
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&entry.title))
+                .activatable(true)
+                .build();
+            row.add_prefix(&Image::from_icon_name(icon_name));
+            listbox.append(&row);
+        }
+        *self.imp().outline.borrow_mut() = outline;
+    }
 }
@@ -1,5 +1,6 @@
 // Imports
 use super::RnStrokeWidthPreview;
+use gettextrs::gettext;
 use gtk4::{glib, prelude::*, subclass::prelude::*, Button, Overflow, ToggleButton, Widget};
 use once_cell::sync::Lazy;
 use std::cell::Cell;
@@ -81,6 +82,11 @@ mod imp {
         fn update_appearance(&self, stroke_width: f64) {
             self.obj()
                 .set_tooltip_text(Some(&format!("{stroke_width:.1}")));
+            self.obj()
+                .update_property(&[gtk4::accessible::Property::Label(&format!(
+                    "{} {stroke_width:.1}",
+                    gettext("Stroke Width")
+                ))]);
         }
     }
 }
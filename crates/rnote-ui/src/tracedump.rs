@@ -0,0 +1,113 @@
+// Imports
+use crate::config;
+use gtk4::glib;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::Layer;
+
+/// Whether the trace dump is currently recorded.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// The file the trace dump is written to, together with its path.
+static FILE: Mutex<Option<(PathBuf, Arc<Mutex<File>>)>> = Mutex::new(None);
+
+/// The tracing layer that writes the timings of spans and all events of debug level or higher into the trace dump
+/// file, while the dump is recorded.
+///
+/// The trace dump is independent of the log level set through the environment, so that users can record one
+/// without restarting the app.
+pub(crate) fn layer<S>() -> impl Layer<S>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer()
+        .with_writer(TraceDumpWriter)
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_timer(tracing_subscriber::fmt::time::Uptime::default())
+        .with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+            ENABLED.load(Ordering::Relaxed) && *metadata.level() <= Level::DEBUG
+        }))
+}
+
+/// The directory where the trace dumps are stored.
+pub(crate) fn dir() -> PathBuf {
+    crate::env::user_cache_dir().join("traces")
+}
+
+/// Start recording a trace dump into a new file.
+///
+/// Returns the path of the file.
+pub(crate) fn start() -> anyhow::Result<PathBuf> {
+    let mut file = FILE.lock().unwrap();
+    if let Some((path, _)) = file.as_ref() {
+        return Ok(path.clone());
+    }
+    let dir = dir();
+    std::fs::create_dir_all(&dir)?;
+    let timestamp = glib::DateTime::now_local()?.format("%Y-%m-%d_%H-%M-%S")?;
+    let path = dir.join(format!("trace_{timestamp}.log"));
+    let mut dump_file = File::create(&path)?;
+    writeln!(
+        dump_file,
+        "{} {} trace dump, {}",
+        config::APP_NAME_CAPITALIZED,
+        config::APP_VERSION,
+        std::env::consts::OS
+    )?;
+    *file = Some((path.clone(), Arc::new(Mutex::new(dump_file))));
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(path)
+}
+
+/// Stop recording the trace dump.
+///
+/// Returns the path of the finished file, if a dump was recorded.
+pub(crate) fn stop() -> Option<PathBuf> {
+    ENABLED.store(false, Ordering::Relaxed);
+    let (path, dump_file) = FILE.lock().unwrap().take()?;
+    if let Err(e) = dump_file.lock().unwrap().flush() {
+        tracing::error!("Flushing trace dump file failed, Err: {e:?}");
+    }
+    Some(path)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TraceDumpWriter;
+
+impl<'a> MakeWriter<'a> for TraceDumpWriter {
+    type Writer = TraceDumpFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        TraceDumpFileWriter(
+            FILE.lock()
+                .unwrap()
+                .as_ref()
+                .map(|(_, dump_file)| Arc::clone(dump_file)),
+        )
+    }
+}
+
+#[derive(Debug)]
+struct TraceDumpFileWriter(Option<Arc<Mutex<File>>>);
+
+impl Write for TraceDumpFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &self.0 {
+            Some(dump_file) => dump_file.lock().unwrap().write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.0 {
+            Some(dump_file) => dump_file.lock().unwrap().flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Imports
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, DropDown,
-    EventControllerScroll, PropagationPhase, SpinButton, Widget,
+    glib, glib::clone, prelude::*, subclass::prelude::*, AccessibleRole, CompositeTemplate,
+    DropDown, EventControllerScroll, PropagationPhase, SpinButton, Widget,
 };
 use num_traits::ToPrimitive;
 use once_cell::sync::Lazy;
@@ -18,6 +18,8 @@ mod imp {
         pub(crate) value: Cell<f64>,
         pub(crate) unit: Cell<MeasureUnit>,
         pub(crate) dpi: Cell<f64>,
+        /// Whether negative values can be entered, e.g. for positions.
+        pub(crate) signed: Cell<bool>,
 
         #[template_child]
         pub(crate) value_spinner: TemplateChild<SpinButton>,
@@ -31,6 +33,7 @@ mod imp {
                 value: Cell::new(1.0),
                 unit: Cell::new(MeasureUnit::Px),
                 dpi: Cell::new(96.0),
+                signed: Cell::new(false),
                 value_spinner: TemplateChild::<SpinButton>::default(),
                 unit_dropdown: TemplateChild::<DropDown>::default(),
             }
@@ -45,6 +48,8 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            // The value and the unit are announced together with the label of the entry
+            klass.set_accessible_role(AccessibleRole::Group);
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -123,6 +128,9 @@ mod imp {
                         .maximum(f64::MAX)
                         .default_value(96.0)
                         .build(),
+                    glib::ParamSpecBoolean::builder("signed")
+                        .default_value(false)
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -133,6 +141,7 @@ mod imp {
                 "value" => self.value.get().to_value(),
                 "unit" => self.unit.get().to_u32().unwrap().to_value(),
                 "dpi" => self.dpi.get().to_value(),
+                "signed" => self.signed.get().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -178,12 +187,28 @@ mod imp {
                         self.dpi.replace(dpi);
                     }
                 }
+                "signed" => {
+                    let signed = value
+                        .get::<bool>()
+                        .expect("The value must be of type 'bool'");
+                    self.signed.replace(signed);
+                    self.configure_spinner(self.unit.get(), self.dpi.get());
+                }
                 _ => unimplemented!(),
             }
         }
     }
 
-    impl WidgetImpl for RnUnitEntry {}
+    impl WidgetImpl for RnUnitEntry {
+        // Focus the value, for example when the row containing the entry is activated with the keyboard
+        fn grab_focus(&self) -> bool {
+            self.value_spinner.grab_focus()
+        }
+
+        fn mnemonic_activate(&self, _group_cycling: bool) -> bool {
+            self.value_spinner.grab_focus()
+        }
+    }
 
     impl RnUnitEntry {
         const MIN_VAL_IN_PX: f64 = 1.0;
@@ -202,13 +227,13 @@ mod imp {
         const DIGITS_CM: u32 = 2;
 
         fn configure_spinner(&self, unit: MeasureUnit, dpi: f64) {
-            let min_val = MeasureUnit::convert_measurement(
-                Self::MIN_VAL_IN_PX,
-                MeasureUnit::Px,
-                dpi,
-                unit,
-                dpi,
-            );
+            let min_val_in_px = if self.signed.get() {
+                -Self::MAX_VAL_IN_PX
+            } else {
+                Self::MIN_VAL_IN_PX
+            };
+            let min_val =
+                MeasureUnit::convert_measurement(min_val_in_px, MeasureUnit::Px, dpi, unit, dpi);
             let max_val = MeasureUnit::convert_measurement(
                 Self::MAX_VAL_IN_PX,
                 MeasureUnit::Px,
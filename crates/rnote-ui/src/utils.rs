@@ -46,6 +46,127 @@ pub(crate) async fn create_replace_file_future(
     Ok(())
 }
 
+/// Replace the file at the given path atomically, so that it is never left partially written on power loss
+/// or when a sync client picks it up while saving.
+///
+/// The bytes are written into a temporary file next to it, synced and then renamed over the file.
+/// When `n_backups` is not zero, the replaced file is kept as `<file name>.bak`
+/// and older backups are rotated up to `<file name>.<n_backups - 1>.bak`.
+///
+/// This is blocking and should be run on a worker thread.
+pub(crate) fn atomic_replace_file(
+    bytes: &[u8],
+    file_path: &Path,
+    n_backups: u32,
+) -> anyhow::Result<()> {
+    // Replace the target of a symlink instead of the link itself
+    let file_path = std::fs::canonicalize(file_path).unwrap_or_else(|_| file_path.to_path_buf());
+    let (Some(dir), Some(file_name)) = (file_path.parent(), file_path.file_name()) else {
+        return Err(anyhow::anyhow!(
+            "Can't replace file with path '{}', it has no parent directory or file name.",
+            file_path.display()
+        ));
+    };
+    let file_name = file_name.to_string_lossy();
+    let tmp_path = dir.join(format!(".{file_name}.tmp"));
+    let backup_path = |i: u32| {
+        if i == 0 {
+            dir.join(format!("{file_name}.bak"))
+        } else {
+            dir.join(format!("{file_name}.{i}.bak"))
+        }
+    };
+
+    let write_tmp_file = || -> anyhow::Result<()> {
+        let mut tmp_file = std::fs::File::create(&tmp_path).context(format!(
+            "Failed to create temporary file with path '{}'",
+            tmp_path.display()
+        ))?;
+        std::io::Write::write_all(&mut tmp_file, bytes).context(format!(
+            "Failed to write bytes to temporary file with path '{}'",
+            tmp_path.display()
+        ))?;
+        if let Ok(metadata) = std::fs::metadata(&file_path) {
+            tmp_file.set_permissions(metadata.permissions())?;
+        }
+        tmp_file.sync_all().context(format!(
+            "Failed to sync temporary file with path '{}'",
+            tmp_path.display()
+        ))?;
+        Ok(())
+    };
+    if let Err(e) = write_tmp_file() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    if n_backups > 0 && file_path.exists() {
+        let rotate_backups = || -> std::io::Result<()> {
+            for i in (0..n_backups - 1).rev() {
+                if backup_path(i).exists() {
+                    std::fs::rename(backup_path(i), backup_path(i + 1))?;
+                }
+            }
+            std::fs::copy(&file_path, backup_path(0))?;
+            Ok(())
+        };
+        // A failing backup should not prevent saving
+        if let Err(e) = rotate_backups() {
+            tracing::error!(
+                "Rotating backups of file with path '{}' failed, Err: {e:?}",
+                file_path.display()
+            );
+        }
+    }
+
+    std::fs::rename(&tmp_path, &file_path).context(format!(
+        "Failed to rename temporary file to path '{}'",
+        file_path.display()
+    ))?;
+    // Make the rename itself durable
+    #[cfg(unix)]
+    std::fs::File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .context(format!(
+            "Failed to sync directory with path '{}'",
+            dir.display()
+        ))?;
+    Ok(())
+}
+
+/// Remove the backup copies of the file at the given path that were created by [atomic_replace_file].
+///
+/// This is blocking and should be run on a worker thread.
+pub(crate) fn remove_backup_files(file_path: &Path) -> anyhow::Result<()> {
+    let file_path = std::fs::canonicalize(file_path).unwrap_or_else(|_| file_path.to_path_buf());
+    let (Some(dir), Some(file_name)) = (file_path.parent(), file_path.file_name()) else {
+        return Ok(());
+    };
+    let file_name = file_name.to_string_lossy();
+    let is_backup = |name: &str| {
+        let Some(rest) = name
+            .strip_prefix(file_name.as_ref())
+            .and_then(|rest| rest.strip_suffix(".bak"))
+        else {
+            return false;
+        };
+        rest.is_empty()
+            || rest
+                .strip_prefix('.')
+                .is_some_and(|i| !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()))
+    };
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if is_backup(&entry.file_name().to_string_lossy()) {
+            std::fs::remove_file(entry.path()).context(format!(
+                "Failed to remove backup file with path '{}'",
+                entry.path().display()
+            ))?;
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn str_from_u8_nul_utf8(utf8_src: &[u8]) -> Result<&str, std::str::Utf8Error> {
     let nul_range_end = utf8_src
         .iter()